@@ -1,13 +1,13 @@
 //! Authentication use cases for login and registration.
 
 use std::io::{self, Read, Write};
-use std::sync::mpsc::channel;
+use std::sync::mpsc::{Receiver, channel};
 
 use crate::domain::{User, UserId};
 use crate::infrastructure::storage::Storage;
 use crate::tcp::messages::{
     ErrorMsg, LoginRequest, LoginResponse, LogoutResponse, Message, RegisterRequest,
-    RegisterResponse,
+    RegisterResponse, ResumeRequest, ResumeResponse,
 };
 use crate::tcp::protocol::write_message;
 
@@ -34,7 +34,7 @@ impl AuthUseCase {
         ));
 
         // Verify credentials
-        let user = match self.verify_credentials(&req.username, &req.password_hash) {
+        let user = match self.authenticate(&req.username, &req.password_hash) {
             Some(u) => u,
             None => {
                 self.logger.error(&format!(
@@ -46,8 +46,10 @@ impl AuthUseCase {
             }
         };
 
-        // Check if already logged in
-        if self.storage.is_user_logged_in(&user.id) {
+        // Check if already logged in; a session left suspended by a dropped
+        // connection is replaced by the new login
+        if self.storage.is_user_logged_in(&user.id) && !self.storage.is_session_suspended(&user.id)
+        {
             self.logger.error(&format!(
                 "User {} already logged in from another session",
                 user.username
@@ -61,10 +63,29 @@ impl AuthUseCase {
     }
 
     /// Verify user credentials
-    fn verify_credentials(&self, username: &str, password_hash: &str) -> Option<User> {
-        self.storage
+    ///
+    /// Users still stored with a legacy hash are migrated to Argon2 on
+    /// their first successful login.
+    pub fn authenticate(&self, username: &str, password_hash: &str) -> Option<User> {
+        let mut user = self
+            .storage
             .get_user_by_username(username)
-            .filter(|user| user.verify_password(password_hash))
+            .filter(|user| user.verify_password(password_hash))?;
+
+        if user.needs_rehash() {
+            user.set_password(password_hash);
+            match self.storage.update_user(user.clone()) {
+                Ok(()) => self
+                    .logger
+                    .info(&format!("Re-hashed legacy password for {}", username)),
+                Err(e) => self.logger.warn(&format!(
+                    "Failed to re-hash legacy password for {}: {}",
+                    username, e
+                )),
+            }
+        }
+
+        Some(user)
     }
 
     /// Complete login: connect user, send response, return channel
@@ -86,8 +107,18 @@ impl AuthUseCase {
             return Ok(None);
         }
 
+        let session_token = match self.storage.create_session(&user_id) {
+            Ok(token) => Some(token),
+            Err(e) => {
+                self.logger
+                    .warn(&format!("Failed to create session for {}: {}", username, e));
+                None
+            }
+        };
+
         // Send success response
-        self.send_login_success(stream, &user_id, &username)?;
+        self.send_login_success(stream, &user_id, &username, session_token)?;
+        self.storage.metrics().record_login();
 
         self.logger.info(&format!("User {} logged in", username));
         Ok(Some((user_id, rx)))
@@ -99,11 +130,13 @@ impl AuthUseCase {
         stream: &mut S,
         user_id: &str,
         username: &str,
+        session_token: Option<String>,
     ) -> io::Result<()> {
         let response = Message::LoginResponse(LoginResponse {
             success: true,
             user_id: Some(user_id.to_string()),
             username: Some(username.to_string()),
+            session_token,
             error: None,
         });
         write_message(stream, &response).map_err(io::Error::other)
@@ -115,11 +148,56 @@ impl AuthUseCase {
             success: false,
             user_id: None,
             username: None,
+            session_token: None,
             error: Some("Invalid credentials".to_string()),
         });
         write_message(stream, &response).map_err(io::Error::other)
     }
 
+    /// Handle a request to resume a session whose connection dropped
+    ///
+    /// On success the caller takes over the session's message queue, which
+    /// still holds every message sent to the user while they were away.
+    pub fn handle_resume(
+        &self,
+        req: &ResumeRequest,
+    ) -> (Message, Option<(UserId, Receiver<Message>)>) {
+        let (user_id, receiver) = match self.storage.resume_session(&req.session_token) {
+            Ok(session) => session,
+            Err(e) => {
+                self.logger.warn(&format!("Session resume rejected: {}", e));
+                let response = Message::ResumeResponse(ResumeResponse {
+                    success: false,
+                    user_id: None,
+                    username: None,
+                    call_id: None,
+                    error: Some(e),
+                });
+                return (response, None);
+            }
+        };
+
+        let username = self.storage.get_user(&user_id).map(|user| user.username);
+        let call_id = self
+            .storage
+            .get_user_active_call(&user_id)
+            .map(|call| call.call_id);
+        self.logger.info(&format!(
+            "User {} resumed their session (call: {})",
+            user_id,
+            call_id.as_deref().unwrap_or("none")
+        ));
+
+        let response = Message::ResumeResponse(ResumeResponse {
+            success: true,
+            user_id: Some(user_id.clone()),
+            username,
+            call_id,
+            error: None,
+        });
+        (response, Some((user_id, receiver)))
+    }
+
     /// Handle logout request
     pub fn handle_register(&self, req: &RegisterRequest) -> io::Result<Message> {
         self.logger.info(&format!(
@@ -2,11 +2,12 @@
 
 use std::io;
 
-use crate::domain::{CallState, UserId, UserState};
+use crate::domain::Call;
+use crate::domain::{CallEvent, CallState, DeclineReason, UserId, UserState};
 use crate::infrastructure::storage::Storage;
 use crate::tcp::messages::{
     CallAcceptedMsg, CallDeclinedMsg, CallNotificationMsg, CallRequest, CallResponseMsg, ErrorMsg,
-    HangupMsg, Message,
+    HangupMsg, Message, ParticipantJoinedMsg, ParticipantLeftMsg,
 };
 
 /// Call management use case handler
@@ -40,6 +41,21 @@ impl CallUseCase {
             })));
         }
 
+        // A callee already in another call declines as busy without ringing
+        if let Some(call) = self.storage.get_user_active_call(&req.to_user_id)
+            && !call.has_participant(caller_id)
+        {
+            self.logger.info(&format!(
+                "Callee {} is busy in call {}",
+                req.to_user_id, call.call_id
+            ));
+            return Ok(Some(self.declined_message(
+                "",
+                &req.to_user_id,
+                DeclineReason::Busy,
+            )));
+        }
+
         // Check if callee is available
         let callee_state = self.storage.get_user_state(&req.to_user_id);
         if callee_state != Some(UserState::Available) {
@@ -53,6 +69,13 @@ impl CallUseCase {
             })));
         }
 
+        // A caller already in an active call invites the callee into it
+        if let Some(call) = self.storage.get_user_active_call(caller_id)
+            && call.state == CallState::Active
+        {
+            return self.invite_to_call(caller_id, &call, &req.to_user_id);
+        }
+
         // Create call
         let call = match self
             .storage
@@ -68,6 +91,13 @@ impl CallUseCase {
             }
         };
         let call_id = call.call_id.clone();
+        self.record_event(
+            &call_id,
+            CallEvent::Requested {
+                caller_id: caller_id.clone(),
+                callee_id: req.to_user_id.clone(),
+            },
+        );
 
         // Send notification to callee
         let caller = self.storage.get_user(caller_id);
@@ -105,7 +135,15 @@ impl CallUseCase {
             None => return Ok(None),
         };
 
-        if resp.accepted {
+        if call.pending_invites.contains_key(callee_id) {
+            if resp.accepted {
+                if let Some(error) = self.join_call(callee_id, &resp.call_id)? {
+                    return Ok(Some(error));
+                }
+            } else {
+                self.decline_invite(callee_id, &resp.call_id)?;
+            }
+        } else if resp.accepted {
             self.accept_call(callee_id, &resp.call_id, &call.caller_id)?;
         } else {
             self.decline_call(callee_id, &resp.call_id, &call.caller_id)?;
@@ -122,6 +160,12 @@ impl CallUseCase {
     fn accept_call(&self, callee_id: &UserId, call_id: &str, caller_id: &UserId) -> io::Result<()> {
         // Update call state to Active
         let _ = self.storage.update_call_state(call_id, CallState::Active);
+        self.record_event(
+            call_id,
+            CallEvent::Accepted {
+                user_id: callee_id.clone(),
+            },
+        );
 
         // Get callee info
         let callee_user = self.storage.get_user(callee_id);
@@ -138,6 +182,11 @@ impl CallUseCase {
         });
         let _ = self.storage.forward_to_user(caller_id, accepted_msg);
 
+        self.storage
+            .broadcast_state_update(caller_id, UserState::Busy);
+        self.storage
+            .broadcast_state_update(callee_id, UserState::Busy);
+
         self.logger.info(&format!("Call accepted: {}", call_id));
         self.logger.info(&format!(
             "Users {} and {} set to Busy",
@@ -147,6 +196,124 @@ impl CallUseCase {
         Ok(())
     }
 
+    /// Invite another user into an active call
+    fn invite_to_call(
+        &self,
+        inviter_id: &UserId,
+        call: &Call,
+        invitee_id: &UserId,
+    ) -> io::Result<Option<Message>> {
+        if let Err(e) = self
+            .storage
+            .invite_to_call(&call.call_id, inviter_id, invitee_id)
+        {
+            self.logger.error(&format!("Failed to invite user: {}", e));
+            return Ok(Some(Message::Error(ErrorMsg {
+                code: 409,
+                message: e,
+            })));
+        }
+        self.record_event(
+            &call.call_id,
+            CallEvent::Requested {
+                caller_id: inviter_id.clone(),
+                callee_id: invitee_id.clone(),
+            },
+        );
+
+        if let Some(inviter) = self.storage.get_user(inviter_id) {
+            let notification = Message::CallNotification(CallNotificationMsg {
+                call_id: call.call_id.clone(),
+                from_user_id: inviter_id.clone(),
+                from_username: inviter.username,
+            });
+            let _ = self.storage.forward_to_user(invitee_id, notification);
+        }
+
+        self.logger.info(&format!(
+            "User {} invited {} to call {}",
+            inviter_id, invitee_id, call.call_id
+        ));
+
+        Ok(None)
+    }
+
+    /// Add an invited user to the call and notify the existing participants
+    ///
+    /// Each existing participant then sends an SDP offer to the newcomer.
+    /// If the call cannot take the user (e.g. it is full) the invitation is
+    /// dropped and the error is returned for the joining user.
+    fn join_call(&self, user_id: &UserId, call_id: &str) -> io::Result<Option<Message>> {
+        let call = match self.storage.join_call(call_id, user_id) {
+            Ok(call) => call,
+            Err(e) => {
+                self.logger.error(&format!("Failed to join call: {}", e));
+                self.storage.decline_invite(call_id, user_id);
+                return Ok(Some(Message::Error(ErrorMsg {
+                    code: 409,
+                    message: e,
+                })));
+            }
+        };
+        self.record_event(
+            call_id,
+            CallEvent::Accepted {
+                user_id: user_id.clone(),
+            },
+        );
+
+        let username = self
+            .storage
+            .get_user(user_id)
+            .map(|u| u.username)
+            .unwrap_or_default();
+
+        for peer_id in call.other_participants(user_id) {
+            let joined_msg = Message::ParticipantJoined(ParticipantJoinedMsg {
+                call_id: call_id.to_string(),
+                user_id: user_id.clone(),
+                username: username.clone(),
+            });
+            let _ = self.storage.forward_to_user(&peer_id, joined_msg);
+        }
+
+        self.storage
+            .broadcast_state_update(user_id, UserState::Busy);
+
+        self.logger.info(&format!(
+            "User {} joined call {} ({} participants)",
+            user_id,
+            call_id,
+            call.participants.len()
+        ));
+
+        Ok(None)
+    }
+
+    /// Drop an invitation and notify the inviter
+    fn decline_invite(&self, user_id: &UserId, call_id: &str) -> io::Result<()> {
+        let Some(inviter_id) = self.storage.decline_invite(call_id, user_id) else {
+            return Ok(());
+        };
+        self.record_event(
+            call_id,
+            CallEvent::Declined {
+                user_id: user_id.clone(),
+                reason: DeclineReason::Declined,
+            },
+        );
+
+        let declined_msg = self.declined_message(call_id, user_id, DeclineReason::Declined);
+        let _ = self.storage.forward_to_user(&inviter_id, declined_msg);
+
+        self.logger.info(&format!(
+            "User {} declined invitation to call {}",
+            user_id, call_id
+        ));
+
+        Ok(())
+    }
+
     /// Decline call and notify caller
     fn decline_call(
         &self,
@@ -156,20 +323,22 @@ impl CallUseCase {
     ) -> io::Result<()> {
         // Remove call
         self.storage.remove_call(call_id);
-
-        // Get callee info
-        let callee_user = self.storage.get_user(callee_id);
-        let callee_username = callee_user
-            .as_ref()
-            .map(|u| u.username.clone())
-            .unwrap_or_default();
+        self.record_event(
+            call_id,
+            CallEvent::Declined {
+                user_id: callee_id.clone(),
+                reason: DeclineReason::Declined,
+            },
+        );
+        self.record_event(
+            call_id,
+            CallEvent::Ended {
+                user_id: callee_id.clone(),
+            },
+        );
 
         // Notify caller
-        let declined_msg = Message::CallDeclined(CallDeclinedMsg {
-            call_id: call_id.to_string(),
-            peer_user_id: callee_id.clone(),
-            peer_username: callee_username,
-        });
+        let declined_msg = self.declined_message(call_id, callee_id, DeclineReason::Declined);
         let _ = self.storage.forward_to_user(caller_id, declined_msg);
 
         self.logger.info(&format!("Call declined: {}", call_id));
@@ -177,7 +346,28 @@ impl CallUseCase {
         Ok(())
     }
 
-    /// Handle hangup from either party
+    /// Build the `CallDeclined` message telling the caller why `callee_id` is not joining
+    fn declined_message(
+        &self,
+        call_id: &str,
+        callee_id: &UserId,
+        reason: DeclineReason,
+    ) -> Message {
+        let callee_username = self
+            .storage
+            .get_user(callee_id)
+            .map(|u| u.username)
+            .unwrap_or_default();
+
+        Message::CallDeclined(CallDeclinedMsg {
+            call_id: call_id.to_string(),
+            peer_user_id: callee_id.clone(),
+            peer_username: callee_username,
+            reason: reason.to_string(),
+        })
+    }
+
+    /// Handle hangup from any participant
     pub fn handle_hangup(
         &self,
         user_id: &UserId,
@@ -188,59 +378,345 @@ impl CallUseCase {
             user_id, hangup.call_id
         ));
 
-        if let Some(call) = self.storage.remove_call(&hangup.call_id) {
-            let peer_id = if &call.caller_id == user_id {
-                call.callee_id.clone()
-            } else {
-                call.caller_id.clone()
-            };
+        self.leave_call(user_id, &hangup.call_id);
+
+        Ok(None)
+    }
+
+    /// Cleanup when user disconnects (leave active calls)
+    pub fn cleanup_user_disconnect(&self, user_id: &UserId) {
+        self.logger
+            .info(&format!("User {} disconnecting - cleanup calls", user_id));
+
+        if let Some(call) = self.storage.get_user_active_call(user_id) {
+            self.leave_call(user_id, &call.call_id);
+        }
+
+        // Disconnect user (handles state broadcast)
+        let _ = self.storage.disconnect_user(user_id);
+    }
+
+    /// Remove a participant and notify the rest of the call
+    ///
+    /// While two or more participants remain they receive `ParticipantLeft`;
+    /// otherwise the call ends and the last participant receives `Hangup`.
+    fn leave_call(&self, user_id: &UserId, call_id: &str) {
+        let Some(call) = self.storage.leave_call(call_id, user_id) else {
+            return;
+        };
+
+        self.storage
+            .broadcast_state_update(user_id, UserState::Available);
+
+        if call.participants.len() >= 2 {
+            let username = self
+                .storage
+                .get_user(user_id)
+                .map(|u| u.username)
+                .unwrap_or_default();
+
+            for peer_id in &call.participants {
+                let left_msg = Message::ParticipantLeft(ParticipantLeftMsg {
+                    call_id: call_id.to_string(),
+                    user_id: user_id.clone(),
+                    username: username.clone(),
+                });
+                let _ = self.storage.forward_to_user(peer_id, left_msg);
+            }
+
+            self.logger.info(&format!(
+                "User {} left call {} ({} participants remain)",
+                user_id,
+                call_id,
+                call.participants.len()
+            ));
+            return;
+        }
 
-            // Forward hangup to peer
+        self.record_event(
+            call_id,
+            CallEvent::Ended {
+                user_id: user_id.clone(),
+            },
+        );
+        for peer_id in &call.participants {
             let _ = self.storage.forward_to_user(
-                &peer_id,
+                peer_id,
                 Message::Hangup(HangupMsg {
-                    call_id: hangup.call_id.clone(),
+                    call_id: call_id.to_string(),
                 }),
             );
-
             self.storage
-                .broadcast_state_update(user_id, UserState::Available);
-            self.storage
-                .broadcast_state_update(&peer_id, UserState::Available);
-
-            self.logger.info(&format!(
-                "Call ended: {} - both users set to Available",
-                hangup.call_id
-            ));
+                .broadcast_state_update(peer_id, UserState::Available);
         }
 
-        Ok(None)
+        self.logger.info(&format!(
+            "Call ended: {} - all users set to Available",
+            call_id
+        ));
     }
 
-    /// Cleanup when user disconnects (end active calls)
-    pub fn cleanup_user_disconnect(&self, user_id: &UserId) {
+    /// Record a call lifecycle event and write it to the log as JSON
+    fn record_event(&self, call_id: &str, event: CallEvent) {
+        let record = self.storage.call_log().record(call_id, event);
         self.logger
-            .info(&format!("User {} disconnecting - cleanup calls", user_id));
+            .info(&format!("[CALL_EVENT] {}", record.to_json()));
+    }
+}
 
-        // Get active call for this user
-        if let Some(call) = self.storage.get_user_active_call(user_id) {
-            // Notify the other party
-            let peer_id = if &call.caller_id == user_id {
-                call.callee_id.clone()
-            } else {
-                call.caller_id.clone()
-            };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::User;
+    use logging::LogLevel;
+    use std::sync::mpsc::{self, Receiver};
 
-            let hangup_msg = Message::Hangup(HangupMsg {
-                call_id: call.call_id.clone(),
-            });
-            let _ = self.storage.forward_to_user(&peer_id, hangup_msg);
+    fn create_test_logger(name: &str) -> logging::Logger {
+        let log_path = std::env::temp_dir().join(format!("test_call_usecase_{}.log", name));
+        logging::Logger::new(log_path, LogLevel::Debug).unwrap()
+    }
+
+    fn connect(storage: &Storage, id: &str, username: &str) -> Receiver<Message> {
+        storage
+            .create_user(User::new(id.to_string(), username.to_string(), "pass"))
+            .unwrap();
+        let (tx, rx) = mpsc::channel();
+        storage.connect_user(id.to_string(), tx).unwrap();
+        rx
+    }
+
+    fn drain(rx: &Receiver<Message>) -> Vec<Message> {
+        rx.try_iter().collect()
+    }
+
+    fn find_call_id(messages: &[Message]) -> String {
+        messages
+            .iter()
+            .find_map(|m| match m {
+                Message::CallNotification(n) => Some(n.call_id.clone()),
+                _ => None,
+            })
+            .expect("call notification")
+    }
+
+    fn respond(usecase: &CallUseCase, user_id: &str, call_id: &str) {
+        usecase
+            .handle_call_response(
+                &user_id.to_string(),
+                &CallResponseMsg {
+                    call_id: call_id.to_string(),
+                    accepted: true,
+                },
+            )
+            .unwrap();
+    }
 
-            // Remove the call
-            self.storage.remove_call(&call.call_id);
+    fn request(usecase: &CallUseCase, from: &str, to: &str) {
+        let response = usecase
+            .handle_call_request(
+                &from.to_string(),
+                &CallRequest {
+                    to_user_id: to.to_string(),
+                },
+            )
+            .unwrap();
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn test_third_participant_join_notifies_existing_members() {
+        let storage = Storage::new();
+        let usecase = CallUseCase::new(storage.clone(), create_test_logger("join"));
+        let alice_rx = connect(&storage, "u1", "alice");
+        let bob_rx = connect(&storage, "u2", "bob");
+        let carol_rx = connect(&storage, "u3", "carol");
+
+        request(&usecase, "u1", "u2");
+        let call_id = find_call_id(&drain(&bob_rx));
+        respond(&usecase, "u2", &call_id);
+
+        // Alice invites Carol into the running call
+        request(&usecase, "u1", "u3");
+        assert_eq!(find_call_id(&drain(&carol_rx)), call_id);
+        drain(&alice_rx);
+        drain(&bob_rx);
+        respond(&usecase, "u3", &call_id);
+
+        for rx in [&alice_rx, &bob_rx] {
+            let joined: Vec<_> = drain(rx)
+                .into_iter()
+                .filter_map(|m| match m {
+                    Message::ParticipantJoined(j) => Some(j),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(joined.len(), 1);
+            assert_eq!(joined[0].call_id, call_id);
+            assert_eq!(joined[0].user_id, "u3");
+            assert_eq!(joined[0].username, "carol");
         }
+        assert_eq!(storage.get_call(&call_id).unwrap().participants.len(), 3);
+    }
 
-        // Disconnect user (handles state broadcast)
-        let _ = self.storage.disconnect_user(user_id);
+    #[test]
+    fn test_join_full_call_returns_capacity_error() {
+        let storage = Storage::new().with_max_call_participants(2);
+        let usecase = CallUseCase::new(storage.clone(), create_test_logger("full"));
+        let _alice_rx = connect(&storage, "u1", "alice");
+        let bob_rx = connect(&storage, "u2", "bob");
+        let _carol_rx = connect(&storage, "u3", "carol");
+
+        request(&usecase, "u1", "u2");
+        let call_id = find_call_id(&drain(&bob_rx));
+        respond(&usecase, "u2", &call_id);
+
+        // Inviting into a call already at capacity is rejected
+        let response = usecase
+            .handle_call_request(
+                &"u1".to_string(),
+                &CallRequest {
+                    to_user_id: "u3".to_string(),
+                },
+            )
+            .unwrap();
+        assert!(matches!(
+            response,
+            Some(Message::Error(e)) if e.code == 409 && e.message == "Call is full (max 2 participants)"
+        ));
+        assert_eq!(storage.get_call(&call_id).unwrap().participants.len(), 2);
+    }
+
+    #[test]
+    fn test_call_to_busy_user_is_declined_without_ringing() {
+        let storage = Storage::new();
+        let usecase = CallUseCase::new(storage.clone(), create_test_logger("busy"));
+        let _alice_rx = connect(&storage, "u1", "alice");
+        let bob_rx = connect(&storage, "u2", "bob");
+        let carol_rx = connect(&storage, "u3", "carol");
+
+        request(&usecase, "u1", "u2");
+        let call_id = find_call_id(&drain(&bob_rx));
+        respond(&usecase, "u2", &call_id);
+        assert!(drain(&carol_rx).iter().any(
+            |m| matches!(m, Message::UserStateUpdate(u) if u.user_id == "u2" && u.state == "Busy")
+        ));
+
+        let response = usecase
+            .handle_call_request(
+                &"u3".to_string(),
+                &CallRequest {
+                    to_user_id: "u2".to_string(),
+                },
+            )
+            .unwrap();
+
+        match response {
+            Some(Message::CallDeclined(declined)) => {
+                assert_eq!(declined.peer_user_id, "u2");
+                assert_eq!(declined.reason, DeclineReason::Busy.to_string());
+            }
+            other => panic!("expected busy decline, got {:?}", other),
+        }
+        assert!(
+            !drain(&bob_rx)
+                .iter()
+                .any(|m| matches!(m, Message::CallNotification(_)))
+        );
+    }
+
+    #[test]
+    fn test_normal_call_records_lifecycle_events() {
+        use crate::application::usecases::SignalingUseCase;
+        use crate::tcp::messages::SdpAnswerMsg;
+
+        let storage = Storage::new();
+        let logger = create_test_logger("events");
+        let usecase = CallUseCase::new(storage.clone(), logger.clone());
+        let signaling = SignalingUseCase::new(storage.clone(), logger);
+        let _alice_rx = connect(&storage, "u1", "alice");
+        let bob_rx = connect(&storage, "u2", "bob");
+
+        request(&usecase, "u1", "u2");
+        let call_id = find_call_id(&drain(&bob_rx));
+        respond(&usecase, "u2", &call_id);
+
+        // Bob answers Alice's offer, twice to check only the first counts
+        let answer = SdpAnswerMsg {
+            call_id: call_id.clone(),
+            from_user_id: "u2".to_string(),
+            to_user_id: "u1".to_string(),
+            sdp: "v=0".to_string(),
+        };
+        signaling.handle_sdp_answer(&answer).unwrap();
+        signaling.handle_sdp_answer(&answer).unwrap();
+
+        usecase
+            .handle_hangup(
+                &"u1".to_string(),
+                &HangupMsg {
+                    call_id: call_id.clone(),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            storage.call_log().events_for(&call_id),
+            vec![
+                CallEvent::Requested {
+                    caller_id: "u1".to_string(),
+                    callee_id: "u2".to_string(),
+                },
+                CallEvent::Accepted {
+                    user_id: "u2".to_string(),
+                },
+                CallEvent::Connected,
+                CallEvent::Ended {
+                    user_id: "u1".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_leave_keeps_call_until_one_participant_remains() {
+        let storage = Storage::new();
+        let usecase = CallUseCase::new(storage.clone(), create_test_logger("leave"));
+        let alice_rx = connect(&storage, "u1", "alice");
+        let bob_rx = connect(&storage, "u2", "bob");
+        let carol_rx = connect(&storage, "u3", "carol");
+
+        request(&usecase, "u1", "u2");
+        let call_id = find_call_id(&drain(&bob_rx));
+        respond(&usecase, "u2", &call_id);
+        request(&usecase, "u1", "u3");
+        drain(&carol_rx);
+        respond(&usecase, "u3", &call_id);
+        drain(&alice_rx);
+        drain(&bob_rx);
+
+        usecase.cleanup_user_disconnect(&"u1".to_string());
+
+        assert!(
+            drain(&bob_rx)
+                .iter()
+                .any(|m| matches!(m, Message::ParticipantLeft(l) if l.user_id == "u1"))
+        );
+        assert!(storage.get_call(&call_id).is_some());
+
+        usecase
+            .handle_hangup(
+                &"u2".to_string(),
+                &HangupMsg {
+                    call_id: call_id.clone(),
+                },
+            )
+            .unwrap();
+
+        assert!(
+            drain(&carol_rx)
+                .iter()
+                .any(|m| matches!(m, Message::Hangup(_)))
+        );
+        assert!(storage.get_call(&call_id).is_none());
     }
 }
@@ -2,6 +2,7 @@
 
 use std::io;
 
+use crate::domain::CallEvent;
 use crate::infrastructure::storage::Storage;
 use crate::tcp::messages::{IceCandidateMsg, Message, SdpAnswerMsg, SdpOfferMsg};
 
@@ -23,9 +24,13 @@ impl SignalingUseCase {
             offer.from_user_id, offer.to_user_id, offer.call_id, offer.sdp
         ));
 
-        let _ = self
+        if let Err(e) = self
             .storage
-            .forward_to_user(&offer.to_user_id, Message::SdpOffer(offer.clone()));
+            .forward_signaling(&offer.to_user_id, Message::SdpOffer(offer.clone()))
+        {
+            self.logger.warn(&format!("SDP offer dropped: {}", e));
+            return Ok(None);
+        }
 
         self.logger.info("SDP offer forwarded successfully");
         Ok(None)
@@ -38,14 +43,35 @@ impl SignalingUseCase {
             answer.from_user_id, answer.to_user_id, answer.call_id, answer.sdp
         ));
 
-        let _ = self
+        if let Err(e) = self
             .storage
-            .forward_to_user(&answer.to_user_id, Message::SdpAnswer(answer.clone()));
+            .forward_signaling(&answer.to_user_id, Message::SdpAnswer(answer.clone()))
+        {
+            self.logger.warn(&format!("SDP answer dropped: {}", e));
+            return Ok(None);
+        }
 
         self.logger.info("SDP answer forwarded successfully");
+        self.record_connected(&answer.call_id);
         Ok(None)
     }
 
+    /// Record the call as connected when its first SDP answer is relayed
+    ///
+    /// Media flows peer to peer, so the completed offer/answer exchange is the
+    /// closest point the server sees to the call connecting.
+    fn record_connected(&self, call_id: &str) {
+        let call_log = self.storage.call_log();
+        if self.storage.get_call(call_id).is_none()
+            || call_log.contains(call_id, &CallEvent::Connected)
+        {
+            return;
+        }
+        let record = call_log.record(call_id, CallEvent::Connected);
+        self.logger
+            .info(&format!("[CALL_EVENT] {}", record.to_json()));
+    }
+
     /// Handle ICE candidate and forward to peer
     pub fn handle_ice_candidate(&self, candidate: &IceCandidateMsg) -> io::Result<Option<Message>> {
         self.logger.info(&format!(
@@ -53,10 +79,13 @@ impl SignalingUseCase {
             candidate.from_user_id, candidate.to_user_id, candidate.call_id, candidate.candidate
         ));
 
-        let _ = self.storage.forward_to_user(
+        if let Err(e) = self.storage.forward_signaling(
             &candidate.to_user_id,
             Message::IceCandidate(candidate.clone()),
-        );
+        ) {
+            self.logger.warn(&format!("ICE candidate dropped: {}", e));
+            return Ok(None);
+        }
 
         self.logger.info("ICE candidate forwarded successfully");
         Ok(None)
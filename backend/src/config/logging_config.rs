@@ -1,4 +1,5 @@
 use json_parser::impl_json;
+use std::collections::HashMap;
 
 /// Logging configuration
 #[derive(Debug, Clone)]
@@ -7,6 +8,28 @@ pub struct LoggingConfig {
     pub log_level: String,
     pub enable_console: bool,
     pub enable_file: bool,
+    /// Rotate the log file once it reaches this size; `0` disables size rotation
+    pub max_file_size_bytes: u64,
+    /// Rotate the log file at local midnight
+    pub rotate_daily: bool,
+    /// Rotated files kept as `<log_file_path>.1` .. `.N`
+    pub max_log_files: usize,
+    /// Per-component level overrides, e.g. `{"TCP": "debug"}`
+    pub component_levels: HashMap<String, String>,
+}
+
+impl LoggingConfig {
+    /// Rotation policy described by this configuration
+    pub fn rotation(&self) -> logging::Rotation {
+        let mut rotation = logging::Rotation::new().max_archives(self.max_log_files);
+        if self.max_file_size_bytes > 0 {
+            rotation = rotation.max_size(self.max_file_size_bytes);
+        }
+        if self.rotate_daily {
+            rotation = rotation.daily();
+        }
+        rotation
+    }
 }
 
 impl Default for LoggingConfig {
@@ -16,6 +39,10 @@ impl Default for LoggingConfig {
             log_level: "info".to_string(),
             enable_console: true,
             enable_file: true,
+            max_file_size_bytes: 0,
+            rotate_daily: false,
+            max_log_files: 5,
+            component_levels: HashMap::new(),
         }
     }
 }
@@ -26,5 +53,9 @@ impl_json! {
         log_level: String,
         enable_console: bool,
         enable_file: bool,
+        max_file_size_bytes: u64,
+        rotate_daily: bool,
+        max_log_files: usize,
+        component_levels: HashMap<String, String>,
     }
 }
@@ -18,9 +18,15 @@ impl_json! {
 }
 
 impl RoomRtcConfig {
-    /// Load configuration from a JSON file
+    /// Load configuration from a JSON file, or TOML when the path ends in `.toml`
+    ///
+    /// `${VAR}` and `${VAR:-default}` tokens are replaced from the environment.
     pub fn load_from_file(path: &str) -> Result<Self, Box<dyn Error>> {
-        let content = config_loader::load_config_file(path)?;
+        if path.ends_with(".toml") {
+            return config_loader::load_toml(path).map_err(|e| e.into());
+        }
+
+        let content = config_loader::load_with_env_expansion(path)?;
         from_str(&content).map_err(|e| e.into())
     }
 }
@@ -5,10 +5,34 @@ use json_parser::impl_json;
 pub struct ServerConfig {
     pub bind_address: String,
     pub port: u32,
+    /// Signaling transport: "tcp" (length-prefixed frames) or "ws" (WebSocket)
+    pub transport: String,
     pub max_connections: usize,
     pub enable_tls: bool,
     pub pkcs12_path: Option<String>,
     pub pkcs12_password: Option<String>,
+    /// SQLite database file; when unset users are kept in `users.txt`
+    pub database_path: Option<String>,
+    /// Sustained inbound messages per second per connection (0 disables limiting)
+    pub max_messages_per_sec: u32,
+    /// Messages a connection may send in a burst above the sustained rate
+    pub message_burst: u32,
+    /// Consecutive rate-limited messages before the client is disconnected (0 never)
+    pub max_rate_violations: u32,
+    /// Seconds a dropped client's session and calls are kept for it to resume (0 ends them at once)
+    pub session_resume_secs: u32,
+    /// Seconds without any message (heartbeat or other) before a client is disconnected (0 disables)
+    pub heartbeat_timeout_secs: u32,
+    /// Seconds signaling messages for an offline user are kept for delivery on reconnect
+    pub offline_message_ttl_secs: u32,
+    /// Signaling messages kept per offline user; further ones are dropped
+    pub max_offline_messages: usize,
+    /// Participants allowed in one call (at least 2)
+    pub max_call_participants: usize,
+    /// Serve the HTTP metrics endpoint (`/metrics`, `/health`)
+    pub metrics_enabled: bool,
+    pub metrics_bind_address: String,
+    pub metrics_port: u32,
 }
 
 impl Default for ServerConfig {
@@ -16,10 +40,23 @@ impl Default for ServerConfig {
         ServerConfig {
             bind_address: "127.0.0.1".to_string(),
             port: 8080,
+            transport: "tcp".to_string(),
             max_connections: 100,
             enable_tls: false,
             pkcs12_path: None,
             pkcs12_password: None,
+            database_path: None,
+            max_messages_per_sec: 20,
+            message_burst: 50,
+            max_rate_violations: 100,
+            session_resume_secs: 30,
+            heartbeat_timeout_secs: 60,
+            offline_message_ttl_secs: 30,
+            max_offline_messages: 100,
+            max_call_participants: 4,
+            metrics_enabled: false,
+            metrics_bind_address: "127.0.0.1".to_string(),
+            metrics_port: 9090,
         }
     }
 }
@@ -28,9 +65,22 @@ impl_json! {
     ServerConfig {
         bind_address: String,
         port: u32,
+        transport: String,
         max_connections: usize,
         enable_tls: bool,
         pkcs12_path: Option<String>,
         pkcs12_password: Option<String>,
+        database_path: Option<String>,
+        max_messages_per_sec: u32,
+        message_burst: u32,
+        max_rate_violations: u32,
+        session_resume_secs: u32,
+        heartbeat_timeout_secs: u32,
+        offline_message_ttl_secs: u32,
+        max_offline_messages: usize,
+        max_call_participants: usize,
+        metrics_enabled: bool,
+        metrics_bind_address: String,
+        metrics_port: u32,
     }
 }
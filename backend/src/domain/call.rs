@@ -1,14 +1,25 @@
 //! Call domain model - Core business entity for video call management
 
 use crate::domain::{CallState, UserId};
+use std::collections::HashMap;
 
 /// Call entity representing an active video call session
+///
+/// A call starts between `caller_id` and `callee_id`; once active, further
+/// users can be invited and the call becomes a mesh where every participant
+/// connects to every other one.
 #[derive(Debug, Clone)]
 pub struct Call {
     pub call_id: String,
     pub caller_id: UserId,
     pub callee_id: UserId,
     pub state: CallState,
+    /// Members of the call, in join order
+    pub participants: Vec<UserId>,
+    /// Outstanding invitations (invitee -> inviter)
+    pub pending_invites: HashMap<UserId, UserId>,
+    /// Unix timestamp (seconds) when the call was created
+    pub started_at: u64,
 }
 
 impl Call {
@@ -18,12 +29,44 @@ impl Call {
 
         Call {
             call_id,
+            participants: vec![caller_id.clone(), callee_id.clone()],
             caller_id,
             callee_id,
             state: CallState::Ringing,
+            pending_invites: HashMap::new(),
+            started_at: current_timestamp(),
         }
     }
 
+    /// Returns true if the user is a member of the call
+    pub fn has_participant(&self, user_id: &UserId) -> bool {
+        self.participants.contains(user_id)
+    }
+
+    /// Adds a participant, ignoring users already in the call
+    pub fn add_participant(&mut self, user_id: UserId) {
+        self.pending_invites.remove(&user_id);
+        if !self.has_participant(&user_id) {
+            self.participants.push(user_id);
+        }
+    }
+
+    /// Removes a participant, returning true if they were in the call
+    pub fn remove_participant(&mut self, user_id: &UserId) -> bool {
+        let before = self.participants.len();
+        self.participants.retain(|id| id != user_id);
+        self.participants.len() != before
+    }
+
+    /// Returns every participant except the given user
+    pub fn other_participants(&self, user_id: &UserId) -> Vec<UserId> {
+        self.participants
+            .iter()
+            .filter(|id| *id != user_id)
+            .cloned()
+            .collect()
+    }
+
     /// Generates a unique call ID using timestamp and random number
     fn generate_call_id() -> String {
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -39,6 +82,15 @@ impl Call {
     }
 }
 
+/// Get current Unix timestamp
+fn current_timestamp() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time before UNIX epoch")
+        .as_secs()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,6 +106,28 @@ mod tests {
         assert_eq!(call.callee_id, callee);
         assert_eq!(call.state, CallState::Ringing);
         assert!(call.call_id.starts_with("call_"));
+        assert_eq!(call.participants, vec![caller, callee]);
+    }
+
+    #[test]
+    fn test_participant_management() {
+        let mut call = Call::new("alice".to_string(), "bob".to_string());
+        call.pending_invites
+            .insert("carol".to_string(), "alice".to_string());
+
+        call.add_participant("carol".to_string());
+        call.add_participant("carol".to_string());
+
+        assert_eq!(call.participants.len(), 3);
+        assert!(call.pending_invites.is_empty());
+        assert_eq!(
+            call.other_participants(&"bob".to_string()),
+            vec!["alice".to_string(), "carol".to_string()]
+        );
+
+        assert!(call.remove_participant(&"alice".to_string()));
+        assert!(!call.remove_participant(&"alice".to_string()));
+        assert!(!call.has_participant(&"alice".to_string()));
     }
 
     #[test]
@@ -0,0 +1,37 @@
+//! Call lifecycle event domain model
+
+use super::{DeclineReason, UserId};
+
+/// A transition in a call's lifecycle, recorded for auditing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallEvent {
+    /// A user was asked to join the call (new call or invitation)
+    Requested {
+        caller_id: UserId,
+        callee_id: UserId,
+    },
+    /// A user accepted and is now a participant
+    Accepted { user_id: UserId },
+    /// A user did not join the call
+    Declined {
+        user_id: UserId,
+        reason: DeclineReason,
+    },
+    /// The first SDP answer was relayed; media can flow between the peers
+    Connected,
+    /// The call was removed; `user_id` is who hung up, declined or disconnected
+    Ended { user_id: UserId },
+}
+
+impl CallEvent {
+    /// Stable lowercase name used in logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            CallEvent::Requested { .. } => "requested",
+            CallEvent::Accepted { .. } => "accepted",
+            CallEvent::Declined { .. } => "declined",
+            CallEvent::Connected => "connected",
+            CallEvent::Ended { .. } => "ended",
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Call decline reason domain model
+
+/// Why a call request did not lead to a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclineReason {
+    /// The callee rejected the call
+    Declined,
+    /// The callee is already in another call
+    Busy,
+}
+
+impl std::fmt::Display for DeclineReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeclineReason::Declined => write!(f, "Declined"),
+            DeclineReason::Busy => write!(f, "Busy"),
+        }
+    }
+}
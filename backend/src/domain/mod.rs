@@ -4,11 +4,15 @@
 pub type UserId = String;
 
 mod call;
+mod call_event;
 mod call_state;
+mod decline_reason;
 mod user;
 mod user_state;
 
 pub use call::Call;
+pub use call_event::CallEvent;
 pub use call_state::CallState;
+pub use decline_reason::DeclineReason;
 pub use user::User;
 pub use user_state::UserState;
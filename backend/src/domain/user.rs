@@ -1,35 +1,77 @@
 //! User model for authentication and user management
 
 use crate::domain::UserId;
+use argon2::Argon2;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+
+/// Prefix of Argon2 PHC strings (`$argon2id$v=19$m=...$salt$hash`)
+const ARGON2_PREFIX: &str = "$argon2";
 
 /// User information stored in memory
 #[derive(Debug, Clone)]
 pub struct User {
     pub id: UserId,
     pub username: String,
+    /// Argon2 PHC string (algorithm, parameters, salt and hash)
     pub password_hash: String,
     pub created_at: u64,
 }
 
 impl User {
-    /// Create a new user with hashed password
+    /// Create a new user with a salted Argon2 hash of the password
     pub fn new(id: UserId, username: String, password: &str) -> Self {
         Self {
             id,
             username,
-            password_hash: simple_hash(password),
+            password_hash: hash_password(password),
             created_at: current_timestamp(),
         }
     }
 
     /// Verify provided password against stored hash
+    ///
+    /// Entries written before Argon2 hashing (unsalted legacy hashes) are
+    /// still accepted; see `needs_rehash`.
     pub fn verify_password(&self, password: &str) -> bool {
-        self.password_hash == simple_hash(password)
+        if self.needs_rehash() {
+            return constant_time_eq(
+                self.password_hash.as_bytes(),
+                legacy_hash(password).as_bytes(),
+            );
+        }
+
+        PasswordHash::new(&self.password_hash)
+            .map(|parsed| {
+                Argon2::default()
+                    .verify_password(password.as_bytes(), &parsed)
+                    .is_ok()
+            })
+            .unwrap_or(false)
     }
+
+    /// Returns true if the stored hash predates Argon2 and should be replaced
+    pub fn needs_rehash(&self) -> bool {
+        !self.password_hash.starts_with(ARGON2_PREFIX)
+    }
+
+    /// Replace the stored hash with a fresh salted hash of `password`
+    pub fn set_password(&mut self, password: &str) {
+        self.password_hash = hash_password(password);
+    }
+}
+
+/// Hash a password with Argon2id and a random salt
+fn hash_password(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .expect("Argon2 hashing with default parameters cannot fail")
 }
 
-/// Simple hash function using std library only
-fn simple_hash(input: &str) -> String {
+/// Legacy unsalted hash used before Argon2 (kept to migrate old entries)
+fn legacy_hash(input: &str) -> String {
     let bytes = input.as_bytes();
     let mut hash: u64 = 0;
     for (i, &byte) in bytes.iter().enumerate() {
@@ -38,6 +80,14 @@ fn simple_hash(input: &str) -> String {
     format!("{:016x}", hash)
 }
 
+/// Compare two byte strings without short-circuiting on the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 /// Get current Unix timestamp
 fn current_timestamp() -> u64 {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -76,4 +126,33 @@ mod tests {
         assert!(!user.verify_password(""));
         assert!(!user.verify_password("Correct")); // Case sensitive
     }
+
+    #[test]
+    fn test_same_password_produces_different_hashes() {
+        let user1 = User::new("user1".to_string(), "alice".to_string(), "shared");
+        let user2 = User::new("user2".to_string(), "bob".to_string(), "shared");
+
+        assert!(user1.password_hash.starts_with(ARGON2_PREFIX));
+        assert_ne!(user1.password_hash, user2.password_hash);
+        assert!(user1.verify_password("shared"));
+        assert!(user2.verify_password("shared"));
+    }
+
+    #[test]
+    fn test_legacy_hash_verifies_and_needs_rehash() {
+        let mut user = User {
+            id: "user1".to_string(),
+            username: "alice".to_string(),
+            password_hash: legacy_hash("old_secret"),
+            created_at: 0,
+        };
+
+        assert!(user.needs_rehash());
+        assert!(user.verify_password("old_secret"));
+        assert!(!user.verify_password("wrong"));
+
+        user.set_password("old_secret");
+        assert!(!user.needs_rehash());
+        assert!(user.verify_password("old_secret"));
+    }
 }
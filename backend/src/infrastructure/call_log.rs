@@ -0,0 +1,168 @@
+//! Audit log of call lifecycle events
+
+use crate::domain::CallEvent;
+use json_parser::JsonValue;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Events kept in memory; older ones are dropped first
+const MAX_CALL_EVENTS: usize = 1000;
+
+/// A call event with the call it belongs to and when it happened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallEventRecord {
+    pub call_id: String,
+    pub event: CallEvent,
+    /// Unix timestamp (milliseconds)
+    pub timestamp_ms: u64,
+}
+
+impl CallEventRecord {
+    pub fn to_json(&self) -> JsonValue {
+        let mut map = HashMap::new();
+        let mut insert = |key: &str, value: &str| {
+            map.insert(key.to_string(), JsonValue::String(value.to_string()));
+        };
+
+        insert("call_id", &self.call_id);
+        insert("event", self.event.name());
+        match &self.event {
+            CallEvent::Requested {
+                caller_id,
+                callee_id,
+            } => {
+                insert("caller_id", caller_id);
+                insert("callee_id", callee_id);
+            }
+            CallEvent::Accepted { user_id } | CallEvent::Ended { user_id } => {
+                insert("user_id", user_id);
+            }
+            CallEvent::Declined { user_id, reason } => {
+                insert("user_id", user_id);
+                insert("reason", &reason.to_string());
+            }
+            CallEvent::Connected => {}
+        }
+        map.insert(
+            "timestamp_ms".to_string(),
+            JsonValue::Number(self.timestamp_ms as f64),
+        );
+        JsonValue::Object(map)
+    }
+}
+
+/// Recent call events shared by every clone of `Storage`
+#[derive(Debug, Default)]
+pub struct CallLog {
+    records: Mutex<VecDeque<CallEventRecord>>,
+}
+
+impl CallLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an event for `call_id`, returning the timestamped record
+    pub fn record(&self, call_id: &str, event: CallEvent) -> CallEventRecord {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let record = CallEventRecord {
+            call_id: call_id.to_string(),
+            event,
+            timestamp_ms,
+        };
+
+        if let Ok(mut records) = self.records.lock() {
+            if records.len() >= MAX_CALL_EVENTS {
+                records.pop_front();
+            }
+            records.push_back(record.clone());
+        }
+        record
+    }
+
+    /// Events recorded for a call, oldest first
+    pub fn events_for(&self, call_id: &str) -> Vec<CallEvent> {
+        self.records
+            .lock()
+            .map(|records| {
+                records
+                    .iter()
+                    .filter(|record| record.call_id == call_id)
+                    .map(|record| record.event.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Check if `event` was already recorded for a call
+    pub fn contains(&self, call_id: &str, event: &CallEvent) -> bool {
+        self.records
+            .lock()
+            .map(|records| {
+                records
+                    .iter()
+                    .any(|record| record.call_id == call_id && &record.event == event)
+            })
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DeclineReason;
+
+    #[test]
+    fn test_events_are_kept_per_call_in_order() {
+        let log = CallLog::new();
+        log.record("call_1", CallEvent::Connected);
+        log.record(
+            "call_2",
+            CallEvent::Declined {
+                user_id: "u2".to_string(),
+                reason: DeclineReason::Busy,
+            },
+        );
+        log.record(
+            "call_1",
+            CallEvent::Ended {
+                user_id: "u1".to_string(),
+            },
+        );
+
+        assert_eq!(
+            log.events_for("call_1"),
+            vec![
+                CallEvent::Connected,
+                CallEvent::Ended {
+                    user_id: "u1".to_string()
+                }
+            ]
+        );
+        assert!(log.contains("call_1", &CallEvent::Connected));
+        assert!(!log.contains("call_2", &CallEvent::Connected));
+    }
+
+    #[test]
+    fn test_record_to_json() {
+        let log = CallLog::new();
+        let record = log.record(
+            "call_1",
+            CallEvent::Declined {
+                user_id: "u2".to_string(),
+                reason: DeclineReason::Busy,
+            },
+        );
+
+        let json = record.to_json().to_string();
+        assert!(json.contains(r#""call_id":"call_1""#));
+        assert!(json.contains(r#""event":"declined""#));
+        assert!(json.contains(r#""user_id":"u2""#));
+        assert!(json.contains(r#""reason":"Busy""#));
+        assert!(json.contains(r#""timestamp_ms":"#));
+    }
+}
@@ -0,0 +1,134 @@
+//! Runtime counters exposed by the metrics endpoint
+
+use json_parser::JsonValue;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Monotonic counters shared by every clone of `Storage`
+///
+/// Gauges (registered/connected users, active calls) are read from storage
+/// when a snapshot is taken; only totals are tracked here.
+#[derive(Debug)]
+pub struct Metrics {
+    started_at: Instant,
+    messages_processed: AtomicU64,
+    logins: AtomicU64,
+    calls_started: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics {
+            started_at: Instant::now(),
+            messages_processed: AtomicU64::new(0),
+            logins: AtomicU64::new(0),
+            calls_started: AtomicU64::new(0),
+        }
+    }
+
+    /// Count one inbound protocol message
+    pub fn record_message(&self) {
+        self.messages_processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count one successful login
+    pub fn record_login(&self) {
+        self.logins.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count one call created
+    pub fn record_call_started(&self) {
+        self.calls_started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn messages_processed(&self) -> u64 {
+        self.messages_processed.load(Ordering::Relaxed)
+    }
+
+    pub fn logins(&self) -> u64 {
+        self.logins.load(Ordering::Relaxed)
+    }
+
+    pub fn calls_started(&self) -> u64 {
+        self.calls_started.load(Ordering::Relaxed)
+    }
+
+    pub fn uptime_secs(&self) -> u64 {
+        self.started_at.elapsed().as_secs()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Point-in-time view of server metrics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub registered_users: usize,
+    pub connected_users: usize,
+    pub active_calls: usize,
+    pub messages_processed: u64,
+    pub logins: u64,
+    pub calls_started: u64,
+    pub uptime_secs: u64,
+}
+
+impl MetricsSnapshot {
+    pub fn to_json(&self) -> JsonValue {
+        let fields = [
+            ("registered_users", self.registered_users as f64),
+            ("connected_users", self.connected_users as f64),
+            ("active_calls", self.active_calls as f64),
+            ("messages_processed", self.messages_processed as f64),
+            ("logins", self.logins as f64),
+            ("calls_started", self.calls_started as f64),
+            ("uptime_secs", self.uptime_secs as f64),
+        ];
+
+        let map: HashMap<String, JsonValue> = fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), JsonValue::Number(value)))
+            .collect();
+        JsonValue::Object(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_increment() {
+        let metrics = Metrics::new();
+        metrics.record_message();
+        metrics.record_message();
+        metrics.record_login();
+        metrics.record_call_started();
+
+        assert_eq!(metrics.messages_processed(), 2);
+        assert_eq!(metrics.logins(), 1);
+        assert_eq!(metrics.calls_started(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_to_json() {
+        let snapshot = MetricsSnapshot {
+            registered_users: 3,
+            connected_users: 2,
+            active_calls: 1,
+            messages_processed: 42,
+            logins: 2,
+            calls_started: 1,
+            uptime_secs: 10,
+        };
+
+        let json = snapshot.to_json().to_string();
+        assert!(json.contains(r#""registered_users":3"#));
+        assert!(json.contains(r#""active_calls":1"#));
+        assert!(json.contains(r#""messages_processed":42"#));
+    }
+}
@@ -2,5 +2,8 @@
 //!
 //! Persistence and storage management.
 
+pub mod call_log;
+pub mod metrics;
 pub mod persistence;
+pub mod sqlite;
 pub mod storage;
@@ -1,8 +1,9 @@
 //! User persistence module
 //!
-//! Handles loading and saving users to a plain text file
+//! Defines the `PersistenceBackend` interface used by `Storage` and the
+//! default backend that keeps users in a plain text file
 
-use crate::domain::{User, UserId};
+use crate::domain::{Call, User, UserId};
 use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
@@ -10,6 +11,86 @@ use std::path::Path;
 
 const USERS_FILE: &str = "users.txt";
 
+/// A finished call, as stored in the call history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallRecord {
+    pub call_id: String,
+    pub caller_id: UserId,
+    pub callee_id: UserId,
+    /// Final call state ("Ringing" if it was never answered)
+    pub state: String,
+    /// Unix timestamp (seconds) when the call was created
+    pub started_at: u64,
+    /// Unix timestamp (seconds) when the call was removed
+    pub ended_at: u64,
+}
+
+impl CallRecord {
+    /// Build the history entry for a call that ends now
+    pub fn ended_now(call: &Call) -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let ended_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(call.started_at);
+
+        Self {
+            call_id: call.call_id.clone(),
+            caller_id: call.caller_id.clone(),
+            callee_id: call.callee_id.clone(),
+            state: call.state.to_string(),
+            started_at: call.started_at,
+            ended_at,
+        }
+    }
+}
+
+/// Durable storage for registered users and call history
+pub trait PersistenceBackend: Send + Sync {
+    /// Load every registered user
+    fn load_users(&self) -> Result<HashMap<UserId, User>, String>;
+
+    /// Store a newly registered user
+    fn save_user(&self, user: &User) -> Result<(), String>;
+
+    /// Overwrite an existing user (e.g. after a password re-hash)
+    fn update_user(&self, user: &User) -> Result<(), String>;
+
+    /// Append a finished call to the history
+    fn record_call(&self, record: &CallRecord) -> Result<(), String>;
+
+    /// Load the call history, oldest first
+    fn load_call_history(&self) -> Result<Vec<CallRecord>, String>;
+}
+
+/// Plain text backend (`users.txt`); call history is not kept
+pub struct TextFileBackend;
+
+impl PersistenceBackend for TextFileBackend {
+    fn load_users(&self) -> Result<HashMap<UserId, User>, String> {
+        load_users_from_file()
+    }
+
+    fn save_user(&self, user: &User) -> Result<(), String> {
+        save_user_to_file(user)
+    }
+
+    fn update_user(&self, user: &User) -> Result<(), String> {
+        let mut users = load_users_from_file()?;
+        users.insert(user.id.clone(), user.clone());
+        write_users_to_file(users.values())
+    }
+
+    fn record_call(&self, _record: &CallRecord) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load_call_history(&self) -> Result<Vec<CallRecord>, String> {
+        Ok(Vec::new())
+    }
+}
+
 /// Load users from file
 pub fn load_users_from_file() -> Result<HashMap<UserId, User>, String> {
     let path = Path::new(USERS_FILE);
@@ -64,11 +145,16 @@ pub(crate) fn parse_user_from_line(line: &str) -> Option<User> {
 
 /// Save a user to file
 pub fn save_user_to_file(user: &User) -> Result<(), String> {
-    let path = Path::new(USERS_FILE);
-
     // Read existing users
     let existing_users = load_users_from_file()?;
 
+    write_users_to_file(existing_users.values().chain(std::iter::once(user)))
+}
+
+/// Rewrite the users file with the given users
+fn write_users_to_file<'a>(users: impl Iterator<Item = &'a User>) -> Result<(), String> {
+    let path = Path::new(USERS_FILE);
+
     // Open file in write mode (this will overwrite)
     let mut file = OpenOptions::new()
         .create(true)
@@ -82,8 +168,7 @@ pub fn save_user_to_file(user: &User) -> Result<(), String> {
     let _ = writeln!(file, "# Format: id|username|password_hash|created_at");
     let _ = writeln!(file, "#");
 
-    // Write existing users
-    for (_, u) in existing_users.iter() {
+    for u in users {
         let line = format!(
             "{}|{}|{}|{}",
             u.id, u.username, u.password_hash, u.created_at
@@ -91,13 +176,6 @@ pub fn save_user_to_file(user: &User) -> Result<(), String> {
         writeln!(file, "{}", line).map_err(|e| format!("Failed to write user: {}", e))?;
     }
 
-    // Write new user
-    let line = format!(
-        "{}|{}|{}|{}",
-        user.id, user.username, user.password_hash, user.created_at
-    );
-    writeln!(file, "{}", line).map_err(|e| format!("Failed to write user: {}", e))?;
-
     Ok(())
 }
 
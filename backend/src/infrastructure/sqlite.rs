@@ -0,0 +1,201 @@
+//! SQLite persistence backend
+//!
+//! Stores registered users and the call history in a single SQLite file.
+
+use crate::domain::{User, UserId};
+use crate::infrastructure::persistence::{CallRecord, PersistenceBackend};
+use rusqlite::{Connection, params};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS users (
+        id            TEXT PRIMARY KEY,
+        username      TEXT NOT NULL UNIQUE,
+        password_hash TEXT NOT NULL,
+        created_at    INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS call_history (
+        call_id    TEXT PRIMARY KEY,
+        caller_id  TEXT NOT NULL,
+        callee_id  TEXT NOT NULL,
+        state      TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        ended_at   INTEGER NOT NULL
+    );
+";
+
+/// SQLite-backed user and call history store
+pub struct SqliteBackend {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteBackend {
+    /// Open (or create) the database at `path` and ensure the schema exists
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let conn = Connection::open(path.as_ref())
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to create schema: {}", e))?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+impl PersistenceBackend for SqliteBackend {
+    fn load_users(&self) -> Result<HashMap<UserId, User>, String> {
+        let conn = self.conn.lock().map_err(|_| "Failed to lock database")?;
+        let mut stmt = conn
+            .prepare("SELECT id, username, password_hash, created_at FROM users")
+            .map_err(|e| format!("Failed to query users: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(User {
+                    id: row.get(0)?,
+                    username: row.get(1)?,
+                    password_hash: row.get(2)?,
+                    created_at: row.get::<_, i64>(3)? as u64,
+                })
+            })
+            .map_err(|e| format!("Failed to query users: {}", e))?;
+
+        let mut users = HashMap::new();
+        for user in rows {
+            let user = user.map_err(|e| format!("Failed to read user: {}", e))?;
+            users.insert(user.id.clone(), user);
+        }
+        Ok(users)
+    }
+
+    fn save_user(&self, user: &User) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|_| "Failed to lock database")?;
+        conn.execute(
+            "INSERT INTO users (id, username, password_hash, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                user.id,
+                user.username,
+                user.password_hash,
+                user.created_at as i64
+            ],
+        )
+        .map_err(|e| format!("Failed to save user: {}", e))?;
+        Ok(())
+    }
+
+    fn update_user(&self, user: &User) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|_| "Failed to lock database")?;
+        let updated = conn
+            .execute(
+                "UPDATE users SET username = ?2, password_hash = ?3 WHERE id = ?1",
+                params![user.id, user.username, user.password_hash],
+            )
+            .map_err(|e| format!("Failed to update user: {}", e))?;
+
+        if updated == 0 {
+            return Err(format!("User {} not found", user.id));
+        }
+        Ok(())
+    }
+
+    fn record_call(&self, record: &CallRecord) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|_| "Failed to lock database")?;
+        conn.execute(
+            "INSERT OR REPLACE INTO call_history
+                (call_id, caller_id, callee_id, state, started_at, ended_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                record.call_id,
+                record.caller_id,
+                record.callee_id,
+                record.state,
+                record.started_at as i64,
+                record.ended_at as i64
+            ],
+        )
+        .map_err(|e| format!("Failed to record call: {}", e))?;
+        Ok(())
+    }
+
+    fn load_call_history(&self) -> Result<Vec<CallRecord>, String> {
+        let conn = self.conn.lock().map_err(|_| "Failed to lock database")?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT call_id, caller_id, callee_id, state, started_at, ended_at
+                 FROM call_history ORDER BY started_at, rowid",
+            )
+            .map_err(|e| format!("Failed to query call history: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(CallRecord {
+                    call_id: row.get(0)?,
+                    caller_id: row.get(1)?,
+                    callee_id: row.get(2)?,
+                    state: row.get(3)?,
+                    started_at: row.get::<_, i64>(4)? as u64,
+                    ended_at: row.get::<_, i64>(5)? as u64,
+                })
+            })
+            .map_err(|e| format!("Failed to query call history: {}", e))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read call record: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}_{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_save_and_load_users() {
+        let path = temp_db("sqlite_users");
+        let backend = SqliteBackend::open(&path).unwrap();
+        let user = User::new("user1".to_string(), "alice".to_string(), "secret");
+
+        backend.save_user(&user).unwrap();
+        assert!(backend.save_user(&user).is_err());
+
+        let users = backend.load_users().unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users["user1"].password_hash, user.password_hash);
+
+        let mut updated = user.clone();
+        updated.set_password("new_secret");
+        backend.update_user(&updated).unwrap();
+        let users = backend.load_users().unwrap();
+        assert!(users["user1"].verify_password("new_secret"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_call_history_round_trip() {
+        let path = temp_db("sqlite_calls");
+        let backend = SqliteBackend::open(&path).unwrap();
+        let record = CallRecord {
+            call_id: "call_1".to_string(),
+            caller_id: "alice".to_string(),
+            callee_id: "bob".to_string(),
+            state: "Active".to_string(),
+            started_at: 100,
+            ended_at: 160,
+        };
+
+        backend.record_call(&record).unwrap();
+
+        assert_eq!(backend.load_call_history().unwrap(), vec![record]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
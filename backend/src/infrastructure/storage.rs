@@ -1,44 +1,111 @@
 //! In-memory storage for users, connections, and calls
 
 use crate::domain::{Call, CallState, User, UserId, UserState};
-use crate::infrastructure::persistence;
+use crate::infrastructure::call_log::CallLog;
+use crate::infrastructure::metrics::{Metrics, MetricsSnapshot};
+use crate::infrastructure::persistence::{CallRecord, PersistenceBackend, TextFileBackend};
+use crate::infrastructure::sqlite::SqliteBackend;
 use crate::tcp::messages::{Message, UserStateUpdateMsg};
-use std::collections::HashMap;
-use std::sync::mpsc::Sender;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long undeliverable signaling messages wait for their target by default
+const DEFAULT_OFFLINE_MESSAGE_TTL: Duration = Duration::from_secs(30);
+/// Undeliverable signaling messages kept per target by default
+const DEFAULT_MAX_OFFLINE_MESSAGES: usize = 100;
+/// Participants allowed in one call by default
+const DEFAULT_MAX_CALL_PARTICIPANTS: usize = 4;
+
+/// Messages waiting for a user, with the time each was queued
+type OfflineQueue = VecDeque<(Instant, Message)>;
+
+/// Signaling session of a logged-in user
+///
+/// Outlives the connection that created it: when the connection drops the
+/// session is suspended and keeps the user's message queue, so a new
+/// connection presenting the token can take over.
+struct Session {
+    user_id: UserId,
+    /// When the connection dropped and the queue filled since, while suspended
+    suspended: Option<(Instant, Receiver<Message>)>,
+}
 
 /// Thread-safe in-memory storage for the application
 ///
 /// Manages two types of data:
-/// 1. Persistent data (users) - loaded from and saved to disk
+/// 1. Persistent data (users, call history) - written through a `PersistenceBackend`
 /// 2. Volatile data (connections, calls) - only exists at runtime
 #[derive(Clone)]
 pub struct Storage {
     // Persistent data
     users: Arc<Mutex<HashMap<UserId, User>>>,
     username_to_id: Arc<Mutex<HashMap<String, UserId>>>,
+    backend: Option<Arc<dyn PersistenceBackend>>,
 
     // Runtime data
     connections: Arc<Mutex<HashMap<UserId, Sender<Message>>>>,
+    sessions: Arc<Mutex<HashMap<String, Session>>>,
+    /// Signaling messages waiting for users who could not receive them
+    offline_messages: Arc<Mutex<HashMap<UserId, OfflineQueue>>>,
+    offline_ttl: Duration,
+    max_offline_messages: usize,
     active_calls: Arc<Mutex<HashMap<String, Call>>>,
+    max_call_participants: usize,
+    call_log: Arc<CallLog>,
+    metrics: Arc<Metrics>,
 }
 
 impl Storage {
+    /// Create in-memory storage without persistence
     pub fn new() -> Self {
         Self {
             users: Arc::new(Mutex::new(HashMap::new())),
             username_to_id: Arc::new(Mutex::new(HashMap::new())),
+            backend: None,
             connections: Arc::new(Mutex::new(HashMap::new())),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            offline_messages: Arc::new(Mutex::new(HashMap::new())),
+            offline_ttl: DEFAULT_OFFLINE_MESSAGE_TTL,
+            max_offline_messages: DEFAULT_MAX_OFFLINE_MESSAGES,
             active_calls: Arc::new(Mutex::new(HashMap::new())),
+            max_call_participants: DEFAULT_MAX_CALL_PARTICIPANTS,
+            call_log: Arc::new(CallLog::new()),
+            metrics: Arc::new(Metrics::new()),
         }
     }
 
+    /// Set how long and how many undeliverable signaling messages are kept per user
+    pub fn with_offline_queue(mut self, ttl: Duration, max_messages: usize) -> Self {
+        self.offline_ttl = ttl;
+        self.max_offline_messages = max_messages;
+        self
+    }
+
+    /// Set how many participants a call may hold (never fewer than two)
+    pub fn with_max_call_participants(mut self, max_participants: usize) -> Self {
+        self.max_call_participants = max_participants.max(2);
+        self
+    }
+
     /// Create storage and load users from file
     pub fn with_persistence() -> Self {
-        let storage = Self::new();
+        Self::with_backend(Arc::new(TextFileBackend))
+    }
+
+    /// Create storage backed by the SQLite database at `path`
+    pub fn with_sqlite(path: impl AsRef<Path>) -> Result<Self, String> {
+        let backend = SqliteBackend::open(path)?;
+        Ok(Self::with_backend(Arc::new(backend)))
+    }
+
+    /// Create storage and load users from the given backend
+    pub fn with_backend(backend: Arc<dyn PersistenceBackend>) -> Self {
+        let mut storage = Self::new();
 
-        // Load users from file
-        match persistence::load_users_from_file() {
+        match backend.load_users() {
             Ok(users) => {
                 if let (Ok(mut storage_users), Ok(mut username_map)) =
                     (storage.users.lock(), storage.username_to_id.lock())
@@ -47,14 +114,15 @@ impl Storage {
                         username_map.insert(user.username.clone(), id.clone());
                         storage_users.insert(id, user);
                     }
-                    println!("Loaded {} users from file", storage_users.len());
+                    println!("Loaded {} users", storage_users.len());
                 }
             }
             Err(e) => {
-                eprintln!(" Failed to load users from file: {}", e);
+                eprintln!(" Failed to load users: {}", e);
             }
         }
 
+        storage.backend = Some(backend);
         storage
     }
 
@@ -76,9 +144,10 @@ impl Storage {
         let user_id = user.id.clone();
         users.insert(user.id.clone(), user);
 
-        // Persist user to file
-        if let Some(user) = users.get(&user_id)
-            && let Err(e) = persistence::save_user_to_file(user)
+        // Persist user
+        if let Some(backend) = &self.backend
+            && let Some(user) = users.get(&user_id)
+            && let Err(e) = backend.save_user(user)
         {
             eprintln!("Failed to persist user: {}", e);
         }
@@ -86,6 +155,20 @@ impl Storage {
         Ok(())
     }
 
+    /// Replace a registered user's stored data
+    pub fn update_user(&self, user: User) -> Result<(), String> {
+        let mut users = self.users.lock().map_err(|_| "Failed to lock users")?;
+        if !users.contains_key(&user.id) {
+            return Err("User not found".to_string());
+        }
+
+        if let Some(backend) = &self.backend {
+            backend.update_user(&user)?;
+        }
+        users.insert(user.id.clone(), user);
+        Ok(())
+    }
+
     /// Get user by ID
     pub fn get_user(&self, user_id: &UserId) -> Option<User> {
         self.users.lock().ok()?.get(user_id).cloned()
@@ -116,6 +199,7 @@ impl Storage {
             .lock()
             .map_err(|_| "Failed to lock connections")?
             .insert(user_id.clone(), sender);
+        self.flush_offline_messages(&user_id);
 
         // Broadcast state change
         if let Some(user) = self.get_user(&user_id) {
@@ -125,13 +209,14 @@ impl Storage {
         Ok(())
     }
 
-    /// Disconnect a user (removes connection, cleans up calls, broadcasts state)
+    /// Disconnect a user (removes connection and session, cleans up calls, broadcasts state)
     pub fn disconnect_user(&self, user_id: &UserId) -> Result<(), String> {
         // Remove connection
         self.connections
             .lock()
             .map_err(|_| "Failed to lock connections")?
             .remove(user_id);
+        self.end_session(user_id);
 
         // Cleanup any active calls involving this user
         self.cleanup_user_calls(user_id);
@@ -144,10 +229,29 @@ impl Storage {
         Ok(())
     }
 
-    /// Remove all calls involving a specific user
+    /// Remove a user from every call, dropping calls left with fewer than two members
     fn cleanup_user_calls(&self, user_id: &UserId) {
-        if let Ok(mut calls) = self.active_calls.lock() {
-            calls.retain(|_, call| call.caller_id != *user_id && call.callee_id != *user_id);
+        let ended: Vec<Call> = match self.active_calls.lock() {
+            Ok(mut calls) => {
+                for call in calls.values_mut() {
+                    call.remove_participant(user_id);
+                    call.pending_invites.remove(user_id);
+                }
+                let ended_ids: Vec<String> = calls
+                    .values()
+                    .filter(|call| call.participants.len() < 2)
+                    .map(|call| call.call_id.clone())
+                    .collect();
+                ended_ids
+                    .iter()
+                    .filter_map(|call_id| calls.remove(call_id))
+                    .collect()
+            }
+            Err(_) => return,
+        };
+
+        for call in &ended {
+            self.record_call_end(call);
         }
     }
 
@@ -167,6 +271,52 @@ impl Storage {
             .map_err(|e| format!("Failed to send message: {}", e))
     }
 
+    /// Forward a signaling message, queueing it if the target cannot receive it now
+    ///
+    /// Messages for a registered user who is offline or whose connection
+    /// dropped are kept for the offline TTL and delivered when they log in
+    /// or resume. Fails if the user is unknown or their queue is full.
+    pub fn forward_signaling(&self, target_id: &UserId, message: Message) -> Result<(), String> {
+        if !self.is_session_suspended(target_id)
+            && self.forward_to_user(target_id, message.clone()).is_ok()
+        {
+            return Ok(());
+        }
+        if self.get_user(target_id).is_none() {
+            return Err(format!("User {} not found", target_id));
+        }
+
+        let mut queues = self
+            .offline_messages
+            .lock()
+            .map_err(|_| "Failed to lock offline messages")?;
+        queues.retain(|_, queue| {
+            queue.retain(|(queued_at, _)| queued_at.elapsed() < self.offline_ttl);
+            !queue.is_empty()
+        });
+
+        let queue = queues.entry(target_id.clone()).or_default();
+        if queue.len() >= self.max_offline_messages {
+            return Err(format!("Message queue for {} is full", target_id));
+        }
+        queue.push_back((Instant::now(), message));
+        Ok(())
+    }
+
+    /// Deliver the queued signaling messages of a user that have not expired
+    fn flush_offline_messages(&self, user_id: &UserId) {
+        let queued = match self.offline_messages.lock() {
+            Ok(mut queues) => queues.remove(user_id),
+            Err(_) => return,
+        };
+
+        for (queued_at, message) in queued.into_iter().flatten() {
+            if queued_at.elapsed() < self.offline_ttl {
+                let _ = self.forward_to_user(user_id, message);
+            }
+        }
+    }
+
     /// Broadcast user state update to all connected users
     fn broadcast_user_state_update(&self, user_id: &UserId, username: &str, state: UserState) {
         let update = Message::UserStateUpdate(UserStateUpdateMsg {
@@ -193,6 +343,117 @@ impl Storage {
             .unwrap_or(false)
     }
 
+    // ===== Session Management =====
+
+    /// Start a session for a user who just logged in, replacing any previous one
+    ///
+    /// Returns the token a new connection presents to resume the session.
+    pub fn create_session(&self, user_id: &UserId) -> Result<String, String> {
+        let token = format!("{:032x}", rand::random::<u128>());
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| "Failed to lock sessions")?;
+
+        sessions.retain(|_, session| &session.user_id != user_id);
+        sessions.insert(
+            token.clone(),
+            Session {
+                user_id: user_id.clone(),
+                suspended: None,
+            },
+        );
+        Ok(token)
+    }
+
+    /// Keep a user's session after their connection dropped
+    ///
+    /// The user stays connected and in their calls; messages sent to them
+    /// queue on `receiver` until the session is resumed or expires.
+    pub fn suspend_session(
+        &self,
+        user_id: &UserId,
+        receiver: Receiver<Message>,
+    ) -> Result<(), String> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| "Failed to lock sessions")?;
+        let session = sessions
+            .values_mut()
+            .find(|session| &session.user_id == user_id)
+            .ok_or("No session for user")?;
+
+        session.suspended = Some((Instant::now(), receiver));
+        Ok(())
+    }
+
+    /// Check if the user's connection dropped and their session awaits resumption
+    pub fn is_session_suspended(&self, user_id: &UserId) -> bool {
+        self.sessions
+            .lock()
+            .ok()
+            .map(|sessions| {
+                sessions
+                    .values()
+                    .any(|session| &session.user_id == user_id && session.suspended.is_some())
+            })
+            .unwrap_or(false)
+    }
+
+    /// Rebind a suspended session to a new connection
+    ///
+    /// Returns the session's user and the queue of messages sent to them
+    /// while they were away, followed by their buffered signaling messages.
+    pub fn resume_session(&self, token: &str) -> Result<(UserId, Receiver<Message>), String> {
+        let (user_id, receiver) = {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .map_err(|_| "Failed to lock sessions")?;
+            let session = sessions
+                .get_mut(token)
+                .ok_or("Unknown or expired session")?;
+            let (_, receiver) = session
+                .suspended
+                .take()
+                .ok_or("Session is still connected")?;
+            (session.user_id.clone(), receiver)
+        };
+
+        self.flush_offline_messages(&user_id);
+        Ok((user_id, receiver))
+    }
+
+    /// Drop sessions suspended for longer than `grace`, returning their users
+    ///
+    /// The caller is responsible for disconnecting the returned users.
+    pub fn expire_sessions(&self, grace: Duration) -> Vec<UserId> {
+        let Ok(mut sessions) = self.sessions.lock() else {
+            return Vec::new();
+        };
+
+        let expired: Vec<String> = sessions
+            .iter()
+            .filter(|(_, session)| {
+                matches!(&session.suspended, Some((since, _)) if since.elapsed() >= grace)
+            })
+            .map(|(token, _)| token.clone())
+            .collect();
+        expired
+            .iter()
+            .filter_map(|token| sessions.remove(token))
+            .map(|session| session.user_id)
+            .collect()
+    }
+
+    /// Forget a user's session so its token can no longer be resumed
+    fn end_session(&self, user_id: &UserId) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.retain(|_, session| &session.user_id != user_id);
+        }
+    }
+
     // ===== State Derivation =====
 
     /// Get user state (derived from connections and active calls)
@@ -216,10 +477,9 @@ impl Storage {
             .lock()
             .ok()
             .map(|calls| {
-                calls.values().any(|call| {
-                    (call.caller_id == *user_id || call.callee_id == *user_id)
-                        && call.state == CallState::Active
-                })
+                calls
+                    .values()
+                    .any(|call| call.has_participant(user_id) && call.state == CallState::Active)
             })
             .unwrap_or(false)
     }
@@ -241,6 +501,7 @@ impl Storage {
             .map_err(|_| "Failed to lock calls")?;
 
         calls.insert(call.call_id.clone(), call.clone());
+        self.metrics.record_call_started();
         Ok(call)
     }
 
@@ -264,9 +525,32 @@ impl Storage {
         }
     }
 
-    /// Remove a call
+    /// Remove a call, recording it in the call history
     pub fn remove_call(&self, call_id: &str) -> Option<Call> {
-        self.active_calls.lock().ok()?.remove(call_id)
+        let call = self.active_calls.lock().ok()?.remove(call_id)?;
+        self.record_call_end(&call);
+        Some(call)
+    }
+
+    /// Get the persisted call history, oldest first
+    pub fn get_call_history(&self) -> Vec<CallRecord> {
+        let Some(backend) = &self.backend else {
+            return Vec::new();
+        };
+        backend.load_call_history().unwrap_or_else(|e| {
+            eprintln!("Failed to load call history: {}", e);
+            Vec::new()
+        })
+    }
+
+    /// Persist a call that just ended
+    fn record_call_end(&self, call: &Call) {
+        let Some(backend) = &self.backend else {
+            return;
+        };
+        if let Err(e) = backend.record_call(&CallRecord::ended_now(call)) {
+            eprintln!("Failed to record call: {}", e);
+        }
     }
 
     /// Get active call for a user
@@ -275,16 +559,125 @@ impl Storage {
             .lock()
             .ok()?
             .values()
-            .find(|call| call.caller_id == *user_id || call.callee_id == *user_id)
+            .find(|call| call.has_participant(user_id))
             .cloned()
     }
 
+    /// Record an invitation for a user to join an existing call
+    pub fn invite_to_call(
+        &self,
+        call_id: &str,
+        inviter_id: &UserId,
+        invitee_id: &UserId,
+    ) -> Result<Call, String> {
+        let mut calls = self
+            .active_calls
+            .lock()
+            .map_err(|_| "Failed to lock calls")?;
+        let call = calls.get_mut(call_id).ok_or("Call not found")?;
+
+        if call.has_participant(invitee_id) {
+            return Err("User already in call".to_string());
+        }
+        self.check_call_capacity(call)?;
+
+        call.pending_invites
+            .insert(invitee_id.clone(), inviter_id.clone());
+        Ok(call.clone())
+    }
+
+    /// Add an invited user to a call, returning the updated call
+    pub fn join_call(&self, call_id: &str, user_id: &UserId) -> Result<Call, String> {
+        let mut calls = self
+            .active_calls
+            .lock()
+            .map_err(|_| "Failed to lock calls")?;
+        let call = calls.get_mut(call_id).ok_or("Call not found")?;
+
+        if !call.pending_invites.contains_key(user_id) {
+            return Err("User was not invited to this call".to_string());
+        }
+        self.check_call_capacity(call)?;
+
+        call.add_participant(user_id.clone());
+        Ok(call.clone())
+    }
+
+    /// Reject adding a participant to a call that is already at capacity
+    fn check_call_capacity(&self, call: &Call) -> Result<(), String> {
+        if call.participants.len() >= self.max_call_participants {
+            return Err(format!(
+                "Call is full (max {} participants)",
+                self.max_call_participants
+            ));
+        }
+        Ok(())
+    }
+
+    /// Drop a pending invitation, returning the inviter
+    pub fn decline_invite(&self, call_id: &str, user_id: &UserId) -> Option<UserId> {
+        self.active_calls
+            .lock()
+            .ok()?
+            .get_mut(call_id)?
+            .pending_invites
+            .remove(user_id)
+    }
+
+    /// Remove a participant from a call
+    ///
+    /// Returns the call with the remaining participants. When fewer than two
+    /// remain the call is over and is removed from storage.
+    pub fn leave_call(&self, call_id: &str, user_id: &UserId) -> Option<Call> {
+        let mut calls = self.active_calls.lock().ok()?;
+        let call = calls.get_mut(call_id)?;
+
+        if !call.remove_participant(user_id) {
+            return None;
+        }
+
+        if call.participants.len() < 2 {
+            let call = calls.remove(call_id)?;
+            drop(calls);
+            self.record_call_end(&call);
+            return Some(call);
+        }
+        Some(call.clone())
+    }
+
     /// Broadcast user state update to all connected users except the user themselves
     pub fn broadcast_state_update(&self, user_id: &UserId, state: UserState) {
         if let Some(user) = self.get_user(user_id) {
             self.broadcast_user_state_update(user_id, &user.username, state);
         }
     }
+
+    // ===== Call Events =====
+
+    /// Call lifecycle events shared by all clones of this storage
+    pub fn call_log(&self) -> &CallLog {
+        &self.call_log
+    }
+
+    // ===== Metrics =====
+
+    /// Counters shared by all clones of this storage
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Combine runtime counters with current user and call counts
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            registered_users: self.users.lock().map(|u| u.len()).unwrap_or(0),
+            connected_users: self.connections.lock().map(|c| c.len()).unwrap_or(0),
+            active_calls: self.active_calls.lock().map(|c| c.len()).unwrap_or(0),
+            messages_processed: self.metrics.messages_processed(),
+            logins: self.metrics.logins(),
+            calls_started: self.metrics.calls_started(),
+            uptime_secs: self.metrics.uptime_secs(),
+        }
+    }
 }
 
 impl Default for Storage {
@@ -564,4 +957,193 @@ mod tests {
         // Call should be removed
         assert!(storage.get_call(&call_id).is_none());
     }
+
+    #[test]
+    fn test_suspended_session_keeps_messages_until_resumed() {
+        let storage = Storage::new();
+        let (tx, rx) = mpsc::channel();
+        storage.connect_user("user1".to_string(), tx).unwrap();
+        let token = storage.create_session(&"user1".to_string()).unwrap();
+
+        // Resuming a live session is rejected
+        assert!(storage.resume_session(&token).is_err());
+
+        storage.suspend_session(&"user1".to_string(), rx).unwrap();
+        assert!(storage.is_session_suspended(&"user1".to_string()));
+        assert!(storage.is_user_connected(&"user1".to_string()));
+        storage
+            .forward_to_user(&"user1".to_string(), Message::UserListRequest)
+            .unwrap();
+
+        let (user_id, rx) = storage.resume_session(&token).unwrap();
+        assert_eq!(user_id, "user1");
+        assert!(matches!(rx.try_recv(), Ok(Message::UserListRequest)));
+        assert!(!storage.is_session_suspended(&"user1".to_string()));
+        assert!(storage.resume_session("unknown").is_err());
+    }
+
+    #[test]
+    fn test_expire_and_end_sessions() {
+        let storage = Storage::new();
+        let (_tx, rx) = mpsc::channel();
+        let token = storage.create_session(&"user1".to_string()).unwrap();
+        let other = storage.create_session(&"user2".to_string()).unwrap();
+
+        storage.suspend_session(&"user1".to_string(), rx).unwrap();
+        assert!(storage.expire_sessions(Duration::from_secs(60)).is_empty());
+        assert_eq!(
+            storage.expire_sessions(Duration::ZERO),
+            vec!["user1".to_string()]
+        );
+        assert!(storage.resume_session(&token).is_err());
+
+        // Logging out ends the session
+        storage.disconnect_user(&"user2".to_string()).unwrap();
+        let (_tx, rx) = mpsc::channel();
+        assert!(storage.suspend_session(&"user2".to_string(), rx).is_err());
+        assert!(storage.resume_session(&other).is_err());
+    }
+
+    fn hangup(call_id: &str) -> Message {
+        Message::Hangup(crate::tcp::messages::HangupMsg {
+            call_id: call_id.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_signaling_for_offline_user_delivered_on_login() {
+        let storage = Storage::new();
+        storage
+            .create_user(User::new("user1".to_string(), "alice".to_string(), "pass"))
+            .unwrap();
+
+        storage
+            .forward_signaling(&"user1".to_string(), hangup("call_1"))
+            .unwrap();
+        assert!(
+            storage
+                .forward_signaling(&"nobody".to_string(), hangup("call_1"))
+                .is_err()
+        );
+
+        let (tx, rx) = mpsc::channel();
+        storage.connect_user("user1".to_string(), tx).unwrap();
+        assert!(matches!(rx.try_recv(), Ok(Message::Hangup(h)) if h.call_id == "call_1"));
+
+        // Once online, messages are delivered directly
+        storage
+            .forward_signaling(&"user1".to_string(), hangup("call_2"))
+            .unwrap();
+        assert!(matches!(rx.try_recv(), Ok(Message::Hangup(h)) if h.call_id == "call_2"));
+    }
+
+    #[test]
+    fn test_expired_signaling_is_dropped() {
+        let storage = Storage::new()
+            .with_offline_queue(Duration::from_millis(20), DEFAULT_MAX_OFFLINE_MESSAGES);
+        storage
+            .create_user(User::new("user1".to_string(), "alice".to_string(), "pass"))
+            .unwrap();
+
+        storage
+            .forward_signaling(&"user1".to_string(), hangup("call_1"))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(40));
+
+        let (tx, rx) = mpsc::channel();
+        storage.connect_user("user1".to_string(), tx).unwrap();
+        assert!(!rx.try_iter().any(|m| matches!(m, Message::Hangup(_))));
+    }
+
+    #[test]
+    fn test_offline_queue_is_bounded() {
+        let storage = Storage::new().with_offline_queue(DEFAULT_OFFLINE_MESSAGE_TTL, 2);
+        storage
+            .create_user(User::new("user1".to_string(), "alice".to_string(), "pass"))
+            .unwrap();
+
+        let user = "user1".to_string();
+        assert!(storage.forward_signaling(&user, hangup("call_1")).is_ok());
+        assert!(storage.forward_signaling(&user, hangup("call_2")).is_ok());
+        assert!(storage.forward_signaling(&user, hangup("call_3")).is_err());
+    }
+
+    #[test]
+    fn test_signaling_for_suspended_user_waits_for_resume() {
+        let storage = Storage::new();
+        storage
+            .create_user(User::new("user1".to_string(), "alice".to_string(), "pass"))
+            .unwrap();
+        let (tx, rx) = mpsc::channel();
+        storage.connect_user("user1".to_string(), tx).unwrap();
+        let token = storage.create_session(&"user1".to_string()).unwrap();
+        storage.suspend_session(&"user1".to_string(), rx).unwrap();
+
+        storage
+            .forward_signaling(&"user1".to_string(), hangup("call_1"))
+            .unwrap();
+
+        let (_, rx) = storage.resume_session(&token).unwrap();
+        assert!(matches!(rx.try_recv(), Ok(Message::Hangup(h)) if h.call_id == "call_1"));
+    }
+
+    #[test]
+    fn test_join_and_leave_mesh_call() {
+        let storage = Storage::new();
+
+        let call = storage
+            .create_call("user1".to_string(), "user2".to_string())
+            .unwrap();
+        let call_id = call.call_id.clone();
+        storage
+            .update_call_state(&call_id, CallState::Active)
+            .unwrap();
+
+        // Joining without an invitation is rejected
+        assert!(storage.join_call(&call_id, &"user3".to_string()).is_err());
+
+        storage
+            .invite_to_call(&call_id, &"user1".to_string(), &"user3".to_string())
+            .unwrap();
+        let joined = storage.join_call(&call_id, &"user3".to_string()).unwrap();
+        assert_eq!(joined.participants.len(), 3);
+
+        // Call survives while two participants remain
+        let remaining = storage.leave_call(&call_id, &"user1".to_string()).unwrap();
+        assert_eq!(remaining.participants.len(), 2);
+        assert!(storage.get_call(&call_id).is_some());
+
+        let remaining = storage.leave_call(&call_id, &"user2".to_string()).unwrap();
+        assert_eq!(remaining.participants, vec!["user3".to_string()]);
+        assert!(storage.get_call(&call_id).is_none());
+    }
+
+    #[test]
+    fn test_join_beyond_capacity_is_rejected() {
+        let storage = Storage::new().with_max_call_participants(3);
+
+        let call = storage
+            .create_call("user1".to_string(), "user2".to_string())
+            .unwrap();
+        let call_id = call.call_id.clone();
+        for invitee in ["user3", "user4"] {
+            storage
+                .invite_to_call(&call_id, &"user1".to_string(), &invitee.to_string())
+                .unwrap();
+        }
+        storage.join_call(&call_id, &"user3".to_string()).unwrap();
+
+        let err = storage
+            .join_call(&call_id, &"user4".to_string())
+            .unwrap_err();
+        assert_eq!(err, "Call is full (max 3 participants)");
+        assert_eq!(storage.get_call(&call_id).unwrap().participants.len(), 3);
+
+        // A full call takes no further invitations either
+        assert!(
+            storage
+                .invite_to_call(&call_id, &"user1".to_string(), &"user5".to_string())
+                .is_err()
+        );
+    }
 }
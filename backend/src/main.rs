@@ -5,6 +5,8 @@ pub mod infrastructure;
 pub mod tcp;
 
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
 
 use config::RoomRtcConfig;
 use infrastructure::storage::Storage;
@@ -21,7 +23,13 @@ fn main() {
     logger.info("RoomRTC Server starting...");
 
     // Initialize storage with persistence
-    let storage = Arc::new(Storage::with_persistence());
+    let storage = initialize_storage(&config, &logger)
+        .with_offline_queue(
+            Duration::from_secs(u64::from(config.server.offline_message_ttl_secs)),
+            config.server.max_offline_messages,
+        )
+        .with_max_call_participants(config.server.max_call_participants);
+    let storage = Arc::new(storage);
 
     // Run TCP server
     run_tcp_server(&config, storage, logger);
@@ -34,15 +42,20 @@ fn initialize_logger(config: &RoomRtcConfig) -> logging::Logger {
         .log_level
         .parse()
         .unwrap_or(logging::LogLevel::Info);
-    let log_path = config.logging.log_file_path.clone().into();
-    let enable_console = config.logging.enable_console;
-
-    match logging::Logger::with_component(log_path, log_level, "Main".to_string(), enable_console) {
+    let logger = logging::Logger::builder(config.logging.log_file_path.clone().into())
+        .level(log_level)
+        .component("Main")
+        .console_output(config.logging.enable_console)
+        .rotation(config.logging.rotation())
+        .build();
+
+    match logger {
         Ok(logger) => {
             println!(
                 "Logging initialized: {} (level: {})",
                 config.logging.log_file_path, config.logging.log_level
             );
+            apply_component_levels(config, &logger);
             logger
         }
         Err(e) => {
@@ -53,6 +66,38 @@ fn initialize_logger(config: &RoomRtcConfig) -> logging::Logger {
     }
 }
 
+/// Registers the configured per-component level overrides
+fn apply_component_levels(config: &RoomRtcConfig, logger: &logging::Logger) {
+    for (component, level) in &config.logging.component_levels {
+        match level.parse() {
+            Ok(level) => logger.set_component_level(component, level),
+            Err(()) => logger.warn(&format!(
+                "Ignoring invalid log level '{}' for component '{}'",
+                level, component
+            )),
+        }
+    }
+}
+
+/// Opens the configured persistence backend (SQLite or the users file)
+fn initialize_storage(config: &RoomRtcConfig, logger: &logging::Logger) -> Storage {
+    let Some(path) = &config.server.database_path else {
+        return Storage::with_persistence();
+    };
+
+    match Storage::with_sqlite(path) {
+        Ok(storage) => {
+            logger.info(&format!("Using SQLite database: {}", path));
+            storage
+        }
+        Err(e) => {
+            logger.error(&format!("Failed to open database {}: {}", path, e));
+            eprintln!("Cannot continue without the configured database.");
+            std::process::exit(1);
+        }
+    }
+}
+
 /// Loads configuration from file or returns default values
 fn load_config() -> RoomRtcConfig {
     // Determine the configuration file path in this order:
@@ -97,7 +142,23 @@ fn run_tcp_server(config: &RoomRtcConfig, storage: Arc<Storage>, main_logger: lo
         std::process::exit(1);
     });
 
-    let tcp_server = tcp::TcpServer::new(storage.as_ref().clone(), tcp_logger.clone());
+    let transport = config.server.transport.parse().unwrap_or_else(|e: String| {
+        tcp_logger.error(&format!("{} - falling back to TCP", e));
+        tcp::Transport::Tcp
+    });
+
+    let shutdown = register_shutdown_signals(&tcp_logger);
+
+    if config.server.metrics_enabled {
+        start_metrics_server(config, storage.as_ref().clone(), &main_logger, &shutdown);
+    }
+
+    let tcp_server = tcp::TcpServer::new(storage.as_ref().clone(), tcp_logger.clone())
+        .with_rate_limit(&config.server)
+        .with_session_resume(&config.server)
+        .with_heartbeat_timeout(&config.server)
+        .with_transport(transport)
+        .with_shutdown(shutdown);
     tcp_logger.info(&format!("TCP Server starting on {}", bind_addr));
 
     // Enable TLS if configured
@@ -144,4 +205,51 @@ fn run_tcp_server(config: &RoomRtcConfig, storage: Arc<Storage>, main_logger: lo
         tcp_logger.error(&format!("TCP server error: {}", e));
         std::process::exit(1);
     }
+
+    println!("TCP Server stopped");
+    tcp_logger.info("TCP Server stopped");
+}
+
+/// Serves the metrics endpoint on a background thread until shutdown
+fn start_metrics_server(
+    config: &RoomRtcConfig,
+    storage: Storage,
+    main_logger: &logging::Logger,
+    shutdown: &Arc<AtomicBool>,
+) {
+    let bind_addr = format!(
+        "{}:{}",
+        config.server.metrics_bind_address, config.server.metrics_port
+    );
+    let metrics_logger = main_logger
+        .for_component("Metrics")
+        .unwrap_or_else(|_| main_logger.clone());
+    let server = tcp::MetricsServer::new(storage, metrics_logger.clone())
+        .with_shutdown(Arc::clone(shutdown));
+
+    std::thread::spawn(move || {
+        if let Err(e) = server.start(&bind_addr) {
+            metrics_logger.error(&format!("Metrics endpoint error: {}", e));
+        }
+    });
+}
+
+/// Sets the returned flag on Ctrl-C or SIGTERM; a second signal exits immediately
+fn register_shutdown_signals(logger: &logging::Logger) -> Arc<AtomicBool> {
+    use signal_hook::consts::TERM_SIGNALS;
+
+    let shutdown = Arc::new(AtomicBool::new(false));
+    for &signal in TERM_SIGNALS {
+        let registered =
+            signal_hook::flag::register_conditional_shutdown(signal, 1, Arc::clone(&shutdown))
+                .and_then(|_| signal_hook::flag::register(signal, Arc::clone(&shutdown)));
+
+        if let Err(e) = registered {
+            logger.warn(&format!(
+                "Failed to register handler for signal {}: {}",
+                signal, e
+            ));
+        }
+    }
+    shutdown
 }
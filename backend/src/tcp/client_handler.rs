@@ -3,24 +3,33 @@
 use std::io::{self, ErrorKind};
 use std::net::TcpStream;
 use std::sync::Arc;
-use std::sync::mpsc::Receiver;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
 
 use crate::application::handlers::message_handler::MessageHandler;
 use crate::application::usecases::AuthUseCase;
 use crate::infrastructure::storage::Storage;
-use crate::tcp::messages::{LoginRequest, Message};
+use crate::tcp::messages::{ErrorMsg, LoginRequest, Message, ResumeRequest, ServerShutdownMsg};
+use crate::tcp::rate_limiter::{RateDecision, RateLimiter};
 use crate::tcp::stream_type::StreamType;
 use crate::tcp::tls::TlsStream;
+use crate::tcp::websocket::{self, Transport};
 
 /// Client connection handler managing authentication and message routing
 pub struct ClientHandler {
     stream: StreamType,
     auth_usecase: AuthUseCase,
     message_handler: MessageHandler,
+    storage: Storage,
     logger: logging::Logger,
     authenticated_user_id: Option<String>,
     msg_receiver: Option<Receiver<Message>>,
+    rate_limiter: RateLimiter,
+    shutdown: Arc<AtomicBool>,
+    /// Disconnect the client after this long without any message from it
+    heartbeat_timeout: Option<Duration>,
+    last_activity: Instant,
 }
 
 impl ClientHandler {
@@ -29,6 +38,9 @@ impl ClientHandler {
         storage: Storage,
         logger: logging::Logger,
         tls_acceptor: Option<Arc<native_tls::TlsAcceptor>>,
+        rate_limiter: RateLimiter,
+        transport: Transport,
+        shutdown: Arc<AtomicBool>,
     ) -> io::Result<Self> {
         let peer_addr = stream.peer_addr()?;
 
@@ -49,6 +61,21 @@ impl ClientHandler {
             StreamType::Plain(stream)
         };
 
+        // Perform the HTTP upgrade if signaling runs over WebSocket
+        if transport == Transport::WebSocket {
+            stream = Self::upgrade_to_websocket(stream).map_err(|e| {
+                logger.error(&format!(
+                    "WebSocket handshake failed with {}: {}",
+                    peer_addr, e
+                ));
+                e
+            })?;
+            logger.info(&format!(
+                "WebSocket connection established with {}",
+                peer_addr
+            ));
+        }
+
         // Set read timeout for non-blocking receiver check
         if let Err(e) = stream.set_read_timeout(Duration::from_millis(100)) {
             logger.warn(&format!("Failed to set read timeout: {}", e));
@@ -58,44 +85,121 @@ impl ClientHandler {
             .for_component("Auth Usecase")
             .unwrap_or_else(|_| logger.clone());
         let message_handler = MessageHandler::new(storage.clone(), logger.clone());
-        let auth_usecase = AuthUseCase::new(storage, auth_logger);
+        let auth_usecase = AuthUseCase::new(storage.clone(), auth_logger);
 
         Ok(ClientHandler {
             stream,
             auth_usecase,
             message_handler,
+            storage,
             logger,
             authenticated_user_id: None,
             msg_receiver: None,
+            rate_limiter,
+            shutdown,
+            heartbeat_timeout: None,
+            last_activity: Instant::now(),
         })
     }
 
+    /// Treat the client as dead when it sends nothing (not even a heartbeat) for `timeout`
+    ///
+    /// A zero timeout disables the check.
+    pub fn with_heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = Some(timeout).filter(|timeout| !timeout.is_zero());
+        self
+    }
+
+    /// Run the WebSocket handshake on a freshly accepted stream
+    fn upgrade_to_websocket(stream: StreamType) -> io::Result<StreamType> {
+        match stream {
+            StreamType::Plain(stream) => websocket::accept(stream).map(StreamType::WebSocket),
+            StreamType::Tls(stream) => websocket::accept(stream).map(StreamType::SecureWebSocket),
+            upgraded => Ok(upgraded),
+        }
+    }
+
     pub fn handle(&mut self) -> io::Result<()> {
         let peer_addr = self.stream.peer_addr()?;
         self.logger
             .info(&format!("New connection from {}", peer_addr));
 
         loop {
+            if self.shutdown.load(Ordering::SeqCst) {
+                self.close_for_shutdown(&peer_addr);
+                return Ok(());
+            }
+
             self.send_pending_messages()?;
 
             let message = match self.read_message_with_timeout() {
                 Ok(Some(msg)) => msg,
-                Ok(None) => continue, // Timeout, check for pending messages
+                Ok(None) => {
+                    // Timeout: keep the transport alive, then check for pending messages
+                    if let Err(e) = self.stream.keepalive() {
+                        self.logger
+                            .warn(&format!("Keepalive failed for {}: {}", peer_addr, e));
+                        self.suspend_session();
+                        return Err(e);
+                    }
+                    if self.heartbeat_expired() {
+                        self.logger.warn(&format!(
+                            "No heartbeat from {} for {:?}, disconnecting",
+                            peer_addr,
+                            self.last_activity.elapsed()
+                        ));
+                        self.cleanup_disconnect();
+                        return Err(io::Error::new(ErrorKind::TimedOut, "Heartbeat timeout"));
+                    }
+                    continue;
+                }
                 Err(e) => {
                     self.logger
                         .error(&format!("Failed to read message from {}: {}", peer_addr, e));
-                    self.cleanup_disconnect();
+                    self.suspend_session();
                     return Err(e);
                 }
             };
+            self.last_activity = Instant::now();
+
+            match self.rate_limiter.check() {
+                RateDecision::Allowed => {}
+                RateDecision::Rejected => {
+                    self.logger
+                        .warn(&format!("Rate limit exceeded by {}", peer_addr));
+                    if let Err(e) = self.send_rate_limit_error() {
+                        self.suspend_session();
+                        return Err(e);
+                    }
+                    continue;
+                }
+                RateDecision::Disconnect => {
+                    self.logger.error(&format!(
+                        "Disconnecting {} after sustained rate limit violations",
+                        peer_addr
+                    ));
+                    self.cleanup_disconnect();
+                    return Err(io::Error::other("Rate limit exceeded"));
+                }
+            }
+
+            self.storage.metrics().record_message();
 
             if let Err(e) = self.handle_and_respond(message, &peer_addr) {
-                self.cleanup_disconnect();
+                self.suspend_session();
                 return Err(e);
             }
+            // Time spent answering (e.g. hashing a login) is not client silence
+            self.last_activity = Instant::now();
         }
     }
 
+    /// Check if the client has been silent for longer than the heartbeat timeout
+    fn heartbeat_expired(&self) -> bool {
+        self.heartbeat_timeout
+            .is_some_and(|timeout| self.last_activity.elapsed() > timeout)
+    }
+
     /// Send pending broadcast messages to client (non-blocking)
     fn send_pending_messages(&mut self) -> io::Result<()> {
         if let Some(ref rx) = self.msg_receiver {
@@ -108,8 +212,8 @@ impl ClientHandler {
                         io::Error::new(ErrorKind::BrokenPipe, e)
                     })?;
                 }
-                Err(std::sync::mpsc::TryRecvError::Empty) => {}
-                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => {
                     self.logger.warn("Message channel disconnected");
                 }
             }
@@ -117,6 +221,43 @@ impl ClientHandler {
         Ok(())
     }
 
+    /// Flush queued messages, tell the client the server is stopping and release its state
+    fn close_for_shutdown(&mut self, peer_addr: &std::net::SocketAddr) {
+        self.logger
+            .info(&format!("Closing connection to {} for shutdown", peer_addr));
+
+        if let Some(ref rx) = self.msg_receiver {
+            while let Ok(pending_msg) = rx.try_recv() {
+                if self.stream.write_message(&pending_msg).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let notice = Message::ServerShutdown(ServerShutdownMsg {
+            reason: "Server is shutting down".to_string(),
+        });
+        if let Err(e) = self.stream.write_message(&notice) {
+            self.logger.warn(&format!(
+                "Failed to send shutdown notice to {}: {}",
+                peer_addr, e
+            ));
+        }
+
+        self.cleanup_disconnect();
+    }
+
+    /// Tell the client its message was dropped by the rate limiter
+    fn send_rate_limit_error(&mut self) -> io::Result<()> {
+        let error = Message::Error(ErrorMsg {
+            code: 429,
+            message: "Rate limit exceeded".to_string(),
+        });
+        self.stream
+            .write_message(&error)
+            .map_err(|e| io::Error::new(ErrorKind::BrokenPipe, e))
+    }
+
     /// Read message with timeout handling
     fn read_message_with_timeout(&mut self) -> io::Result<Option<Message>> {
         match self.stream.read_message() {
@@ -155,6 +296,8 @@ impl ClientHandler {
         match message {
             // Login handled here (needs TcpStream for writer thread setup)
             Message::LoginRequest(req) => self.handle_login(req),
+            // Resume rebinds this connection to an existing session's queue
+            Message::ResumeRequest(req) => self.handle_resume(req),
             // All other messages delegated to application layer
             _ => self
                 .message_handler
@@ -168,6 +311,8 @@ impl ClientHandler {
         let result = match &mut self.stream {
             StreamType::Plain(stream) => self.auth_usecase.handle_login(&req, stream),
             StreamType::Tls(stream) => self.auth_usecase.handle_login(&req, stream),
+            StreamType::WebSocket(stream) => self.auth_usecase.handle_login(&req, stream),
+            StreamType::SecureWebSocket(stream) => self.auth_usecase.handle_login(&req, stream),
         };
 
         match result {
@@ -191,6 +336,45 @@ impl ClientHandler {
         }
     }
 
+    /// Handle a request to take over a session whose connection dropped
+    fn handle_resume(&mut self, req: ResumeRequest) -> io::Result<Option<Message>> {
+        if self.authenticated_user_id.is_some() {
+            return Ok(Some(Message::Error(ErrorMsg {
+                code: 409,
+                message: "Connection already has a session".to_string(),
+            })));
+        }
+
+        let (response, session) = self.auth_usecase.handle_resume(&req);
+        if let Some((user_id, receiver)) = session {
+            self.logger.info(&format!("Session resumed: {}", user_id));
+            self.authenticated_user_id = Some(user_id);
+            self.msg_receiver = Some(receiver);
+        }
+        Ok(Some(response))
+    }
+
+    /// Keep the user's session resumable after the connection dropped
+    ///
+    /// Falls back to a full disconnect when there is no session to keep,
+    /// e.g. after the user logged out.
+    fn suspend_session(&mut self) {
+        let (Some(user_id), Some(receiver)) = (
+            self.authenticated_user_id.as_ref(),
+            self.msg_receiver.take(),
+        ) else {
+            self.cleanup_disconnect();
+            return;
+        };
+
+        match self.storage.suspend_session(user_id, receiver) {
+            Ok(()) => self
+                .logger
+                .info(&format!("Session of {} suspended until resumed", user_id)),
+            Err(_) => self.cleanup_disconnect(),
+        }
+    }
+
     fn cleanup_disconnect(&self) {
         if let Some(user_id) = &self.authenticated_user_id {
             self.message_handler.cleanup_user_disconnect(user_id);
@@ -66,9 +66,12 @@ impl CallAcceptedMsg {
 
 #[derive(Debug, Clone)]
 pub struct CallDeclinedMsg {
+    /// Empty when the callee was busy and no call was created
     pub call_id: String,
     pub peer_user_id: String,
     pub peer_username: String,
+    /// `DeclineReason` as text ("Declined" or "Busy")
+    pub reason: String,
 }
 
 impl CallDeclinedMsg {
@@ -77,6 +80,41 @@ impl CallDeclinedMsg {
         insert_string(&mut map, "call_id", self.call_id.clone());
         insert_string(&mut map, "peer_user_id", self.peer_user_id.clone());
         insert_string(&mut map, "peer_username", self.peer_username.clone());
+        insert_string(&mut map, "reason", self.reason.clone());
+        JsonValue::Object(map)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParticipantJoinedMsg {
+    pub call_id: String,
+    pub user_id: String,
+    pub username: String,
+}
+
+impl ParticipantJoinedMsg {
+    pub fn to_json(&self) -> JsonValue {
+        let mut map = HashMap::new();
+        insert_string(&mut map, "call_id", self.call_id.clone());
+        insert_string(&mut map, "user_id", self.user_id.clone());
+        insert_string(&mut map, "username", self.username.clone());
+        JsonValue::Object(map)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParticipantLeftMsg {
+    pub call_id: String,
+    pub user_id: String,
+    pub username: String,
+}
+
+impl ParticipantLeftMsg {
+    pub fn to_json(&self) -> JsonValue {
+        let mut map = HashMap::new();
+        insert_string(&mut map, "call_id", self.call_id.clone());
+        insert_string(&mut map, "user_id", self.user_id.clone());
+        insert_string(&mut map, "username", self.username.clone());
         JsonValue::Object(map)
     }
 }
@@ -25,3 +25,17 @@ impl ErrorMsg {
         JsonValue::Object(map)
     }
 }
+
+/// Sent to every connected client right before the server stops
+#[derive(Debug, Clone)]
+pub struct ServerShutdownMsg {
+    pub reason: String,
+}
+
+impl ServerShutdownMsg {
+    pub fn to_json(&self) -> JsonValue {
+        let mut map = HashMap::new();
+        insert_string(&mut map, "reason", self.reason.clone());
+        JsonValue::Object(map)
+    }
+}
@@ -25,6 +25,8 @@ pub struct LoginResponse {
     pub success: bool,
     pub user_id: Option<String>,
     pub username: Option<String>,
+    /// Token that lets a new connection resume this session
+    pub session_token: Option<String>,
     pub error: Option<String>,
 }
 
@@ -34,6 +36,7 @@ impl LoginResponse {
         insert_bool(&mut map, "success", self.success);
         insert_optional_string(&mut map, "user_id", &self.user_id);
         insert_optional_string(&mut map, "username", &self.username);
+        insert_optional_string(&mut map, "session_token", &self.session_token);
         insert_optional_string(&mut map, "error", &self.error);
         JsonValue::Object(map)
     }
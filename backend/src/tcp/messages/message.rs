@@ -4,7 +4,8 @@ use std::collections::HashMap;
 use super::{
     CallAcceptedMsg, CallDeclinedMsg, CallNotificationMsg, CallRequest, CallResponseMsg, ErrorMsg,
     HangupMsg, HeartbeatMsg, IceCandidateMsg, LoginRequest, LoginResponse, LogoutRequest,
-    LogoutResponse, MessageType, RegisterRequest, RegisterResponse, SdpAnswerMsg, SdpOfferMsg,
+    LogoutResponse, MessageType, ParticipantJoinedMsg, ParticipantLeftMsg, RegisterRequest,
+    RegisterResponse, ResumeRequest, ResumeResponse, SdpAnswerMsg, SdpOfferMsg, ServerShutdownMsg,
     UserListResponse, UserStateUpdateMsg,
 };
 
@@ -14,6 +15,7 @@ pub enum Message {
     LoginRequest(LoginRequest),
     RegisterRequest(RegisterRequest),
     LogoutRequest(LogoutRequest),
+    ResumeRequest(ResumeRequest),
     UserListRequest,
     CallRequest(CallRequest),
     CallResponse(CallResponseMsg),
@@ -27,12 +29,16 @@ pub enum Message {
     LoginResponse(LoginResponse),
     RegisterResponse(RegisterResponse),
     LogoutResponse(LogoutResponse),
+    ResumeResponse(ResumeResponse),
     UserListResponse(UserListResponse),
     UserStateUpdate(UserStateUpdateMsg),
     CallNotification(CallNotificationMsg),
     CallAccepted(CallAcceptedMsg),
     CallDeclined(CallDeclinedMsg),
+    ParticipantJoined(ParticipantJoinedMsg),
+    ParticipantLeft(ParticipantLeftMsg),
     Error(ErrorMsg),
+    ServerShutdown(ServerShutdownMsg),
 }
 
 impl Message {
@@ -44,6 +50,8 @@ impl Message {
             Message::RegisterResponse(_) => MessageType::RegisterResponse,
             Message::LogoutRequest(_) => MessageType::LogoutRequest,
             Message::LogoutResponse(_) => MessageType::LogoutResponse,
+            Message::ResumeRequest(_) => MessageType::ResumeRequest,
+            Message::ResumeResponse(_) => MessageType::ResumeResponse,
             Message::UserListRequest => MessageType::UserListRequest,
             Message::UserListResponse(_) => MessageType::UserListResponse,
             Message::UserStateUpdate(_) => MessageType::UserStateUpdate,
@@ -52,12 +60,15 @@ impl Message {
             Message::CallResponse(_) => MessageType::CallResponse,
             Message::CallAccepted(_) => MessageType::CallAccepted,
             Message::CallDeclined(_) => MessageType::CallDeclined,
+            Message::ParticipantJoined(_) => MessageType::ParticipantJoined,
+            Message::ParticipantLeft(_) => MessageType::ParticipantLeft,
             Message::SdpOffer(_) => MessageType::SdpOffer,
             Message::SdpAnswer(_) => MessageType::SdpAnswer,
             Message::IceCandidate(_) => MessageType::IceCandidate,
             Message::Hangup(_) => MessageType::Hangup,
             Message::Heartbeat(_) => MessageType::Heartbeat,
             Message::Error(_) => MessageType::Error,
+            Message::ServerShutdown(_) => MessageType::ServerShutdown,
         }
     }
 
@@ -66,16 +77,20 @@ impl Message {
             Message::LoginResponse(r) => r.to_json(),
             Message::RegisterResponse(r) => r.to_json(),
             Message::LogoutResponse(r) => r.to_json(),
+            Message::ResumeResponse(r) => r.to_json(),
             Message::UserListResponse(r) => r.to_json(),
             Message::UserStateUpdate(u) => u.to_json(),
             Message::CallNotification(n) => n.to_json(),
             Message::CallAccepted(a) => a.to_json(),
             Message::CallDeclined(d) => d.to_json(),
+            Message::ParticipantJoined(j) => j.to_json(),
+            Message::ParticipantLeft(l) => l.to_json(),
             Message::SdpOffer(o) => o.to_json(),
             Message::SdpAnswer(a) => a.to_json(),
             Message::IceCandidate(c) => c.to_json(),
             Message::Hangup(h) => h.to_json(),
             Message::Error(e) => e.to_json(),
+            Message::ServerShutdown(s) => s.to_json(),
             _ => JsonValue::Object(HashMap::new()),
         }
     }
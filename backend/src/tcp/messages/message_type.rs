@@ -21,6 +21,11 @@ pub enum MessageType {
     Error = 0x12,
     LogoutRequest = 0x13,
     LogoutResponse = 0x14,
+    ParticipantJoined = 0x15,
+    ParticipantLeft = 0x16,
+    ServerShutdown = 0x17,
+    ResumeRequest = 0x18,
+    ResumeResponse = 0x19,
 }
 
 impl MessageType {
@@ -46,6 +51,11 @@ impl MessageType {
             0x12 => Some(MessageType::Error),
             0x13 => Some(MessageType::LogoutRequest),
             0x14 => Some(MessageType::LogoutResponse),
+            0x15 => Some(MessageType::ParticipantJoined),
+            0x16 => Some(MessageType::ParticipantLeft),
+            0x17 => Some(MessageType::ServerShutdown),
+            0x18 => Some(MessageType::ResumeRequest),
+            0x19 => Some(MessageType::ResumeResponse),
             _ => None,
         }
     }
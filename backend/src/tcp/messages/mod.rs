@@ -6,17 +6,20 @@ mod logout;
 mod message;
 mod message_type;
 mod register;
+mod resume;
 mod signaling;
 mod user;
 
 pub use call::{
     CallAcceptedMsg, CallDeclinedMsg, CallNotificationMsg, CallRequest, CallResponseMsg,
+    ParticipantJoinedMsg, ParticipantLeftMsg,
 };
-pub use common::{ErrorMsg, HeartbeatMsg};
+pub use common::{ErrorMsg, HeartbeatMsg, ServerShutdownMsg};
 pub use login::{LoginRequest, LoginResponse};
 pub use logout::{LogoutRequest, LogoutResponse};
 pub use message::Message;
 pub use message_type::MessageType;
 pub use register::{RegisterRequest, RegisterResponse};
+pub use resume::{ResumeRequest, ResumeResponse};
 pub use signaling::{HangupMsg, IceCandidateMsg, SdpAnswerMsg, SdpOfferMsg};
 pub use user::{UserInfoMsg, UserListResponse, UserStateUpdateMsg};
@@ -0,0 +1,39 @@
+use super::json_helpers::{get_string_field, insert_bool, insert_optional_string};
+use json_parser::JsonValue;
+use std::collections::HashMap;
+
+/// Sent on a new connection to take over a session whose connection dropped
+#[derive(Debug, Clone)]
+pub struct ResumeRequest {
+    pub session_token: String,
+}
+
+impl ResumeRequest {
+    pub fn from_json(json: &JsonValue) -> Result<Self, String> {
+        let obj = json.as_object().ok_or("Expected object")?;
+        let session_token = get_string_field(obj, "session_token")?;
+        Ok(ResumeRequest { session_token })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResumeResponse {
+    pub success: bool,
+    pub user_id: Option<String>,
+    pub username: Option<String>,
+    /// Call the user is still part of, if any
+    pub call_id: Option<String>,
+    pub error: Option<String>,
+}
+
+impl ResumeResponse {
+    pub fn to_json(&self) -> JsonValue {
+        let mut map = HashMap::new();
+        insert_bool(&mut map, "success", self.success);
+        insert_optional_string(&mut map, "user_id", &self.user_id);
+        insert_optional_string(&mut map, "username", &self.username);
+        insert_optional_string(&mut map, "call_id", &self.call_id);
+        insert_optional_string(&mut map, "error", &self.error);
+        JsonValue::Object(map)
+    }
+}
@@ -0,0 +1,112 @@
+//! Minimal HTTP endpoint serving server metrics as JSON.
+//!
+//! Routes:
+//! - `GET /metrics` - `MetricsSnapshot` as JSON
+//! - `GET /health`  - `{"status":"ok"}`
+//!
+//! Each request is answered on the accept thread and the connection closed;
+//! the endpoint is meant for monitoring probes, not general traffic.
+
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::infrastructure::storage::Storage;
+
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// HTTP server exposing `Storage` metrics
+pub struct MetricsServer {
+    storage: Storage,
+    logger: logging::Logger,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl MetricsServer {
+    pub fn new(storage: Storage, logger: logging::Logger) -> Self {
+        MetricsServer {
+            storage,
+            logger,
+            shutdown: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Use an external flag to request shutdown; `start` returns once it is set
+    pub fn with_shutdown(mut self, shutdown: Arc<AtomicBool>) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Serve requests until the shutdown flag is set
+    pub fn start(&self, bind_addr: &str) -> io::Result<()> {
+        let listener = TcpListener::bind(bind_addr)?;
+        listener.set_nonblocking(true)?;
+        self.logger
+            .info(&format!("Metrics endpoint listening on {}", bind_addr));
+
+        while !self.shutdown.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, peer_addr)) => {
+                    if let Err(e) = self.handle_request(stream) {
+                        self.logger
+                            .warn(&format!("Metrics request from {} failed: {}", peer_addr, e));
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(e) => {
+                    self.logger
+                        .error(&format!("Failed to accept metrics connection: {}", e));
+                }
+            }
+        }
+
+        self.logger.info("Metrics endpoint stopped");
+        Ok(())
+    }
+
+    fn handle_request(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        // Consume headers so the client sees a clean close
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 && line != "\r\n" {
+            line.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (status, body) = match (parts.next(), parts.next()) {
+            (Some("GET"), Some("/metrics")) => (
+                "200 OK",
+                self.storage.metrics_snapshot().to_json().to_string(),
+            ),
+            (Some("GET"), Some("/health")) => ("200 OK", r#"{"status":"ok"}"#.to_string()),
+            (Some("GET"), Some(_)) => ("404 Not Found", r#"{"error":"not found"}"#.to_string()),
+            _ => (
+                "405 Method Not Allowed",
+                r#"{"error":"method not allowed"}"#.to_string(),
+            ),
+        };
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let mut stream = reader.into_inner();
+        stream.write_all(response.as_bytes())?;
+        stream.flush()
+    }
+}
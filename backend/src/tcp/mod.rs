@@ -2,9 +2,14 @@
 
 mod client_handler;
 pub mod messages;
+mod metrics_endpoint;
 pub mod protocol;
+pub mod rate_limiter;
 mod server;
 mod stream_type;
 pub mod tls;
+pub mod websocket;
 
+pub use metrics_endpoint::MetricsServer;
 pub use server::TcpServer;
+pub use websocket::Transport;
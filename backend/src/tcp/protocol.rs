@@ -115,7 +115,7 @@ fn parse_json_payload(payload: &[u8]) -> Result<json_parser::JsonValue> {
 fn parse_message(msg_type: MessageType, payload: &[u8]) -> Result<Message> {
     use crate::tcp::messages::{
         CallRequest, CallResponseMsg, HangupMsg, HeartbeatMsg, IceCandidateMsg, LoginRequest,
-        LogoutRequest, Message, RegisterRequest, SdpAnswerMsg, SdpOfferMsg,
+        LogoutRequest, Message, RegisterRequest, ResumeRequest, SdpAnswerMsg, SdpOfferMsg,
     };
 
     let json = parse_json_payload(payload)?;
@@ -133,6 +133,9 @@ fn parse_message(msg_type: MessageType, payload: &[u8]) -> Result<Message> {
                 LogoutRequest::from_json(json).map_err(ProtocolError::JsonParse)?;
                 Ok(Message::LogoutRequest(LogoutRequest))
             }
+            MessageType::ResumeRequest => ResumeRequest::from_json(json)
+                .map(Message::ResumeRequest)
+                .map_err(ProtocolError::JsonParse),
             MessageType::UserListRequest => Ok(Message::UserListRequest),
             MessageType::CallRequest => CallRequest::from_json(json)
                 .map(Message::CallRequest)
@@ -160,12 +163,16 @@ fn parse_message(msg_type: MessageType, payload: &[u8]) -> Result<Message> {
             MessageType::LoginResponse
             | MessageType::RegisterResponse
             | MessageType::LogoutResponse
+            | MessageType::ResumeResponse
             | MessageType::UserListResponse
             | MessageType::UserStateUpdate
             | MessageType::CallNotification
             | MessageType::CallAccepted
             | MessageType::CallDeclined
-            | MessageType::Error => Err(ProtocolError::InvalidMessageType(msg_type as u8)),
+            | MessageType::ParticipantJoined
+            | MessageType::ParticipantLeft
+            | MessageType::Error
+            | MessageType::ServerShutdown => Err(ProtocolError::InvalidMessageType(msg_type as u8)),
         }
     };
 
@@ -221,6 +228,7 @@ mod tests {
             success: true,
             user_id: Some("user123".to_string()),
             username: Some("alice".to_string()),
+            session_token: None,
             error: None,
         });
 
@@ -312,6 +320,7 @@ mod tests {
             success: false,
             user_id: None,
             username: None,
+            session_token: None,
             error: Some("Invalid credentials".to_string()),
         });
 
@@ -0,0 +1,134 @@
+//! Per-connection token bucket rate limiting for inbound messages.
+
+use std::time::Instant;
+
+/// Outcome of checking one inbound message against the limiter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateDecision {
+    /// Message may be processed
+    Allowed,
+    /// Message is over the limit and should be rejected
+    Rejected,
+    /// Client kept exceeding the limit and should be disconnected
+    Disconnect,
+}
+
+/// Token bucket limiting messages per second with a burst allowance
+///
+/// Each message costs one token; tokens refill continuously at
+/// `messages_per_sec` up to `burst`. Consecutive rejected messages count as
+/// violations, and reaching `max_violations` asks for a disconnect.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    messages_per_sec: f64,
+    burst: f64,
+    max_violations: u32,
+    tokens: f64,
+    violations: u32,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a limiter with a full bucket
+    ///
+    /// # Arguments
+    /// * `messages_per_sec` - Sustained message rate (0 disables limiting)
+    /// * `burst` - Bucket capacity (at least 1)
+    /// * `max_violations` - Consecutive rejections before disconnect (0 never disconnects)
+    pub fn new(messages_per_sec: u32, burst: u32, max_violations: u32) -> Self {
+        let burst = burst.max(1) as f64;
+        Self {
+            messages_per_sec: messages_per_sec as f64,
+            burst,
+            max_violations,
+            tokens: burst,
+            violations: 0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Checks a message received now
+    pub fn check(&mut self) -> RateDecision {
+        self.check_at(Instant::now())
+    }
+
+    /// Checks a message received at `now`
+    pub fn check_at(&mut self, now: Instant) -> RateDecision {
+        if self.messages_per_sec <= 0.0 {
+            return RateDecision::Allowed;
+        }
+
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.messages_per_sec).min(self.burst);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            self.violations = 0;
+            return RateDecision::Allowed;
+        }
+
+        self.violations += 1;
+        if self.max_violations > 0 && self.violations >= self.max_violations {
+            RateDecision::Disconnect
+        } else {
+            RateDecision::Rejected
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_messages_over_limit_are_rejected() {
+        let mut limiter = RateLimiter::new(10, 5, 0);
+        let start = Instant::now();
+
+        // A burst of 20 messages within 10ms: only the bucket capacity passes
+        let decisions: Vec<RateDecision> = (0..20)
+            .map(|i| limiter.check_at(start + Duration::from_micros(i * 500)))
+            .collect();
+
+        assert!(decisions[..5].iter().all(|d| *d == RateDecision::Allowed));
+        assert!(decisions[5..].iter().all(|d| *d == RateDecision::Rejected));
+    }
+
+    #[test]
+    fn test_tokens_refill_over_time() {
+        let mut limiter = RateLimiter::new(10, 2, 0);
+        let start = Instant::now();
+
+        assert_eq!(limiter.check_at(start), RateDecision::Allowed);
+        assert_eq!(limiter.check_at(start), RateDecision::Allowed);
+        assert_eq!(limiter.check_at(start), RateDecision::Rejected);
+
+        // 100ms at 10 msg/s refills one token
+        let later = start + Duration::from_millis(100);
+        assert_eq!(limiter.check_at(later), RateDecision::Allowed);
+        assert_eq!(limiter.check_at(later), RateDecision::Rejected);
+    }
+
+    #[test]
+    fn test_sustained_abuse_disconnects() {
+        let mut limiter = RateLimiter::new(1, 1, 3);
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at(now), RateDecision::Allowed);
+        assert_eq!(limiter.check_at(now), RateDecision::Rejected);
+        assert_eq!(limiter.check_at(now), RateDecision::Rejected);
+        assert_eq!(limiter.check_at(now), RateDecision::Disconnect);
+    }
+
+    #[test]
+    fn test_zero_rate_disables_limiting() {
+        let mut limiter = RateLimiter::new(0, 1, 1);
+        let now = Instant::now();
+
+        assert!((0..100).all(|_| limiter.check_at(now) == RateDecision::Allowed));
+    }
+}
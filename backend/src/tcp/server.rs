@@ -1,31 +1,111 @@
 //! TCP server for WebRTC signaling over binary protocol.
 
-use std::io;
+use std::io::{self, ErrorKind};
 use std::net::TcpListener;
 use std::sync::Arc;
-use std::thread;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
+use crate::application::handlers::message_handler::MessageHandler;
+use crate::config::ServerConfig;
 use crate::infrastructure::storage::Storage;
+use crate::tcp::rate_limiter::RateLimiter;
 use crate::tcp::tls::load_tls_acceptor;
+use crate::tcp::websocket::Transport;
 
 use super::client_handler::ClientHandler;
 
+/// How often the accept loop checks the shutdown flag
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Maximum time to wait for client handlers to finish after shutdown
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// TCP Server for persistent connections with TLS support
 pub struct TcpServer {
     storage: Storage,
     logger: logging::Logger,
     tls_acceptor: Option<Arc<native_tls::TlsAcceptor>>,
+    /// Template cloned for every new connection
+    rate_limiter: RateLimiter,
+    transport: Transport,
+    /// How long a dropped client's session stays resumable
+    session_resume: Duration,
+    /// Silence after which a client is considered dead (zero disables)
+    heartbeat_timeout: Duration,
+    /// Disconnects users whose suspended session expired
+    session_cleanup: MessageHandler,
+    /// Set to stop accepting connections and disconnect all clients
+    shutdown: Arc<AtomicBool>,
 }
 
 impl TcpServer {
     pub fn new(storage: Storage, logger: logging::Logger) -> Self {
+        let defaults = ServerConfig::default();
+        let session_cleanup = MessageHandler::new(storage.clone(), logger.clone());
         TcpServer {
             storage,
             logger,
             tls_acceptor: None,
+            rate_limiter: RateLimiter::new(
+                defaults.max_messages_per_sec,
+                defaults.message_burst,
+                defaults.max_rate_violations,
+            ),
+            transport: Transport::Tcp,
+            session_resume: Duration::from_secs(u64::from(defaults.session_resume_secs)),
+            heartbeat_timeout: Duration::from_secs(u64::from(defaults.heartbeat_timeout_secs)),
+            session_cleanup,
+            shutdown: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Use an external flag to request shutdown; `start` returns once it is set
+    pub fn with_shutdown(mut self, shutdown: Arc<AtomicBool>) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Select the signaling framing (raw TCP or WebSocket)
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Set the per-connection inbound message limits
+    pub fn with_rate_limit(mut self, config: &ServerConfig) -> Self {
+        self.logger.info(&format!(
+            "Rate limit: {} msg/s, burst {}, disconnect after {} violations",
+            config.max_messages_per_sec, config.message_burst, config.max_rate_violations
+        ));
+        self.rate_limiter = RateLimiter::new(
+            config.max_messages_per_sec,
+            config.message_burst,
+            config.max_rate_violations,
+        );
+        self
+    }
+
+    /// Set how long a dropped client's session and calls are kept for it to resume
+    pub fn with_session_resume(mut self, config: &ServerConfig) -> Self {
+        self.logger.info(&format!(
+            "Dropped sessions resumable for {} s",
+            config.session_resume_secs
+        ));
+        self.session_resume = Duration::from_secs(u64::from(config.session_resume_secs));
+        self
+    }
+
+    /// Set how long a client may stay silent before it is disconnected
+    pub fn with_heartbeat_timeout(mut self, config: &ServerConfig) -> Self {
+        self.logger.info(&format!(
+            "Heartbeat timeout: {} s",
+            config.heartbeat_timeout_secs
+        ));
+        self.heartbeat_timeout = Duration::from_secs(u64::from(config.heartbeat_timeout_secs));
+        self
+    }
+
     /// Enable TLS with the given PKCS#12 file and password
     pub fn with_tls(mut self, pkcs12_path: &str, password: &str) -> Result<Self, String> {
         match load_tls_acceptor(pkcs12_path, password) {
@@ -39,41 +119,38 @@ impl TcpServer {
         }
     }
 
+    /// Accept connections until the shutdown flag is set, then drain clients
     pub fn start(&self, bind_addr: &str) -> io::Result<()> {
         let listener = TcpListener::bind(bind_addr)?;
+        listener.set_nonblocking(true)?;
 
-        let protocol = if self.tls_acceptor.is_some() {
-            "TLS"
-        } else {
-            "Plain TCP"
+        let protocol = match (self.transport, self.tls_acceptor.is_some()) {
+            (Transport::Tcp, true) => "TLS",
+            (Transport::Tcp, false) => "Plain TCP",
+            (Transport::WebSocket, true) => "Secure WebSocket",
+            (Transport::WebSocket, false) => "WebSocket",
         };
         self.logger.info(&format!(
             "TCP Server listening on {} ({} protocol)",
             bind_addr, protocol
         ));
 
-        for stream in listener.incoming() {
-            match stream {
-                Ok(stream) => {
-                    let storage = self.storage.clone();
-                    let logger = self
-                        .logger
-                        .for_component("ClientHandler")
-                        .unwrap_or_else(|_| self.logger.clone());
-                    let tls_acceptor = self.tls_acceptor.clone();
-
-                    thread::spawn(move || {
-                        match ClientHandler::new(stream, storage, logger.clone(), tls_acceptor) {
-                            Ok(mut handler) => {
-                                if let Err(e) = handler.handle() {
-                                    logger.error(&format!("Client handler error: {}", e));
-                                }
-                            }
-                            Err(e) => {
-                                logger.error(&format!("Failed to create client handler: {}", e));
-                            }
-                        }
-                    });
+        let mut clients: Vec<JoinHandle<()>> = Vec::new();
+
+        while !self.shutdown.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if let Err(e) = stream.set_nonblocking(false) {
+                        self.logger
+                            .error(&format!("Failed to configure connection: {}", e));
+                        continue;
+                    }
+                    clients.retain(|handle| !handle.is_finished());
+                    clients.push(self.spawn_client(stream));
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    self.expire_sessions();
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
                 }
                 Err(e) => {
                     self.logger
@@ -82,6 +159,84 @@ impl TcpServer {
             }
         }
 
+        // Release the port before waiting on clients
+        drop(listener);
+        self.logger
+            .info("Shutdown requested - no longer accepting connections");
+        self.drain_clients(clients);
+
         Ok(())
     }
+
+    /// Fully disconnect users who did not resume their session in time
+    fn expire_sessions(&self) {
+        for user_id in self.storage.expire_sessions(self.session_resume) {
+            self.logger
+                .info(&format!("Session of {} expired without resume", user_id));
+            self.session_cleanup.cleanup_user_disconnect(&user_id);
+        }
+    }
+
+    fn spawn_client(&self, stream: std::net::TcpStream) -> JoinHandle<()> {
+        let storage = self.storage.clone();
+        let logger = self
+            .logger
+            .for_component("ClientHandler")
+            .unwrap_or_else(|_| self.logger.clone());
+        let tls_acceptor = self.tls_acceptor.clone();
+        let rate_limiter = self.rate_limiter.clone();
+        let transport = self.transport;
+        let shutdown = self.shutdown.clone();
+        let heartbeat_timeout = self.heartbeat_timeout;
+
+        thread::spawn(move || {
+            match ClientHandler::new(
+                stream,
+                storage,
+                logger.clone(),
+                tls_acceptor,
+                rate_limiter,
+                transport,
+                shutdown,
+            ) {
+                Ok(handler) => {
+                    let mut handler = handler.with_heartbeat_timeout(heartbeat_timeout);
+                    if let Err(e) = handler.handle() {
+                        logger.error(&format!("Client handler error: {}", e));
+                    }
+                }
+                Err(e) => {
+                    logger.error(&format!("Failed to create client handler: {}", e));
+                }
+            }
+        })
+    }
+
+    /// Wait for client handlers to notify their peers and exit
+    fn drain_clients(&self, clients: Vec<JoinHandle<()>>) {
+        let deadline = Instant::now() + DRAIN_TIMEOUT;
+        let mut pending: Vec<JoinHandle<()>> = clients
+            .into_iter()
+            .filter(|handle| !handle.is_finished())
+            .collect();
+        self.logger.info(&format!(
+            "Waiting for {} client connection(s) to close",
+            pending.len()
+        ));
+
+        while !pending.is_empty() && Instant::now() < deadline {
+            thread::sleep(ACCEPT_POLL_INTERVAL);
+            pending.retain(|handle| !handle.is_finished());
+        }
+
+        if pending.is_empty() {
+            self.logger.info("All client connections closed");
+        } else {
+            self.logger.warn(&format!(
+                "{} client connection(s) still open after {:?}, shutting down anyway",
+                pending.len(),
+                DRAIN_TIMEOUT
+            ));
+        }
+    }
 }
@@ -1,4 +1,4 @@
-//! Stream type abstraction for Plain TCP and TLS connections.
+//! Stream type abstraction for Plain TCP, TLS and WebSocket connections.
 
 use std::io;
 use std::net::TcpStream;
@@ -7,11 +7,14 @@ use std::time::Duration;
 use super::protocol::{ProtocolError, read_message, write_message};
 use crate::tcp::messages::Message;
 use crate::tcp::tls::TlsStream;
+use crate::tcp::websocket::WebSocketStream;
 
-/// Stream type wrapper for Plain TCP or TLS connections
+/// Stream type wrapper for Plain TCP or TLS connections, optionally carrying WebSocket framing
 pub(crate) enum StreamType {
     Plain(TcpStream),
     Tls(TlsStream),
+    WebSocket(WebSocketStream<TcpStream>),
+    SecureWebSocket(WebSocketStream<TlsStream>),
 }
 
 impl StreamType {
@@ -20,6 +23,10 @@ impl StreamType {
         match self {
             StreamType::Plain(stream) => stream.set_read_timeout(Some(duration)),
             StreamType::Tls(stream) => stream.get_mut().set_read_timeout(Some(duration)),
+            StreamType::WebSocket(stream) => stream.get_mut().set_read_timeout(Some(duration)),
+            StreamType::SecureWebSocket(stream) => {
+                stream.get_mut().get_mut().set_read_timeout(Some(duration))
+            }
         }
     }
 
@@ -28,6 +35,8 @@ impl StreamType {
         match self {
             StreamType::Plain(stream) => stream.peer_addr(),
             StreamType::Tls(stream) => stream.get_ref().peer_addr(),
+            StreamType::WebSocket(stream) => stream.get_ref().peer_addr(),
+            StreamType::SecureWebSocket(stream) => stream.get_ref().get_ref().peer_addr(),
         }
     }
 
@@ -36,6 +45,8 @@ impl StreamType {
         match self {
             StreamType::Plain(stream) => read_message(stream),
             StreamType::Tls(stream) => read_message(stream),
+            StreamType::WebSocket(stream) => read_message(stream),
+            StreamType::SecureWebSocket(stream) => read_message(stream),
         }
     }
 
@@ -44,6 +55,17 @@ impl StreamType {
         match self {
             StreamType::Plain(stream) => write_message(stream, msg),
             StreamType::Tls(stream) => write_message(stream, msg),
+            StreamType::WebSocket(stream) => write_message(stream, msg),
+            StreamType::SecureWebSocket(stream) => write_message(stream, msg),
+        }
+    }
+
+    /// Send transport-level keepalives (WebSocket pings); no-op for raw TCP
+    pub(crate) fn keepalive(&mut self) -> io::Result<()> {
+        match self {
+            StreamType::Plain(_) | StreamType::Tls(_) => Ok(()),
+            StreamType::WebSocket(stream) => stream.keepalive(),
+            StreamType::SecureWebSocket(stream) => stream.keepalive(),
         }
     }
 }
@@ -0,0 +1,605 @@
+//! WebSocket transport (RFC 6455) for the signaling protocol.
+//!
+//! Each WebSocket message carries one signaling frame without the length
+//! prefix: `[1 byte type][N bytes JSON]`. `WebSocketStream` translates
+//! between that and the TCP framing (`[4 bytes length][1 byte type][JSON]`),
+//! so `protocol::read_message`/`write_message` and the use cases above them
+//! work unchanged over either transport.
+
+use crate::tcp::tls::TlsStream;
+use sha1::{Digest, Sha1};
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+/// GUID appended to the client key when computing `Sec-WebSocket-Accept`
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Maximum size of the HTTP upgrade request
+const MAX_HANDSHAKE_SIZE: usize = 8 * 1024;
+
+/// Time a client has to send its upgrade request
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum size of a (possibly fragmented) WebSocket message
+const MAX_FRAME_PAYLOAD: u64 = 1024 * 1024;
+
+/// Idle time after which the server pings the client
+pub const PING_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Silence after which the client is considered gone
+pub const PONG_TIMEOUT: Duration = Duration::from_secs(60);
+
+const RETRY_DELAY_MS: u64 = 10;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// Signaling transport selected in the server configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// Length-prefixed frames over TCP (optionally TLS)
+    Tcp,
+    /// WebSocket messages after an HTTP upgrade (optionally TLS)
+    WebSocket,
+}
+
+impl std::str::FromStr for Transport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "tcp" => Ok(Transport::Tcp),
+            "ws" | "websocket" => Ok(Transport::WebSocket),
+            other => Err(format!("Unknown transport: {}", other)),
+        }
+    }
+}
+
+/// Compute the `Sec-WebSocket-Accept` value for a client key
+pub fn accept_key(client_key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(client_key.trim().as_bytes());
+    hasher.update(WEBSOCKET_GUID.as_bytes());
+    base64_encode(&hasher.finalize())
+}
+
+/// Standard base64 with padding
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+
+        out.push(ALPHABET[(n >> 18) as usize & 0x3F] as char);
+        out.push(ALPHABET[(n >> 12) as usize & 0x3F] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[(n >> 6) as usize & 0x3F] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[n as usize & 0x3F] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+/// Read exactly `buf.len()` bytes, retrying on timeouts
+///
+/// Used once a frame has started arriving, so a slow peer can't leave the
+/// stream in the middle of a frame.
+fn read_exact_retrying<S: Read>(stream: &mut S, buf: &mut [u8]) -> io::Result<()> {
+    let mut total_read = 0;
+    while total_read < buf.len() {
+        match stream.read(&mut buf[total_read..]) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "Connection closed mid-frame",
+                ));
+            }
+            Ok(n) => total_read += n,
+            Err(e)
+                if e.kind() == ErrorKind::WouldBlock
+                    || e.kind() == ErrorKind::TimedOut
+                    || e.kind() == ErrorKind::Interrupted =>
+            {
+                std::thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Streams whose reads can be bounded by a timeout
+pub trait ReadTimeout {
+    /// Set (or clear, with `None`) the timeout of blocking reads
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl ReadTimeout for TcpStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+impl ReadTimeout for TlsStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.get_ref().set_read_timeout(timeout)
+    }
+}
+
+/// Perform the server side of the HTTP upgrade handshake
+///
+/// The client has [`HANDSHAKE_TIMEOUT`] to send its upgrade request.
+///
+/// # Returns
+/// The stream wrapped for WebSocket framing, or an error after replying
+/// `400 Bad Request` to a request that is not a valid upgrade.
+pub fn accept<S: Read + Write + ReadTimeout>(stream: S) -> io::Result<WebSocketStream<S>> {
+    accept_with_timeout(stream, HANDSHAKE_TIMEOUT)
+}
+
+/// [`accept`] with a custom deadline for the upgrade request
+///
+/// The read timeout is cleared once the upgrade completes.
+pub fn accept_with_timeout<S: Read + Write + ReadTimeout>(
+    mut stream: S,
+    timeout: Duration,
+) -> io::Result<WebSocketStream<S>> {
+    stream.set_read_timeout(Some(timeout))?;
+    let request = read_http_request(&mut stream)?;
+
+    let key = match parse_upgrade_request(&request) {
+        Ok(key) => key,
+        Err(e) => {
+            let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n");
+            return Err(io::Error::new(ErrorKind::InvalidData, e));
+        }
+    };
+
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(&key)
+    );
+    stream.write_all(response.as_bytes())?;
+    stream.flush()?;
+    stream.set_read_timeout(None)?;
+
+    Ok(WebSocketStream::new(stream))
+}
+
+/// Read the HTTP request head (up to the blank line)
+///
+/// A read timeout ends the handshake instead of being retried, so a client
+/// that connects and stays silent can't hold the connection open.
+fn read_http_request<S: Read>(stream: &mut S) -> io::Result<String> {
+    let mut request = Vec::new();
+    let mut byte = [0u8; 1];
+
+    while !request.ends_with(b"\r\n\r\n") {
+        if request.len() >= MAX_HANDSHAKE_SIZE {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Handshake request too large",
+            ));
+        }
+        stream.read_exact(&mut byte).map_err(|e| {
+            if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) {
+                io::Error::new(
+                    ErrorKind::TimedOut,
+                    "Timed out waiting for the upgrade request",
+                )
+            } else {
+                e
+            }
+        })?;
+        request.push(byte[0]);
+    }
+
+    String::from_utf8(request)
+        .map_err(|_| io::Error::new(ErrorKind::InvalidData, "Handshake is not UTF-8"))
+}
+
+/// Validate an upgrade request and return its `Sec-WebSocket-Key`
+fn parse_upgrade_request(request: &str) -> Result<String, String> {
+    let mut lines = request.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    if !request_line.starts_with("GET ") {
+        return Err("Upgrade request must be a GET".to_string());
+    }
+
+    let mut upgrade = false;
+    let mut key = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "upgrade" => upgrade = value.eq_ignore_ascii_case("websocket"),
+            "sec-websocket-key" => key = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    if !upgrade {
+        return Err("Missing 'Upgrade: websocket' header".to_string());
+    }
+    key.ok_or_else(|| "Missing Sec-WebSocket-Key header".to_string())
+}
+
+/// Server-side WebSocket connection presenting the TCP signaling framing
+///
+/// Reads yield `[4 bytes length][payload]` for every data message received;
+/// writes accept the same layout and send each complete frame as one binary
+/// WebSocket message. Ping, pong and close frames are handled internally.
+pub struct WebSocketStream<S> {
+    inner: S,
+    /// Decoded bytes (in TCP framing) not yet returned by `read`
+    read_buf: VecDeque<u8>,
+    /// Bytes written by the caller that don't form a complete frame yet
+    write_buf: Vec<u8>,
+    /// Payload of a fragmented message being reassembled
+    fragments: Vec<u8>,
+    last_received: Instant,
+    last_ping: Instant,
+    closed: bool,
+}
+
+impl<S: Read + Write> WebSocketStream<S> {
+    fn new(inner: S) -> Self {
+        let now = Instant::now();
+        Self {
+            inner,
+            read_buf: VecDeque::new(),
+            write_buf: Vec::new(),
+            fragments: Vec::new(),
+            last_received: now,
+            last_ping: now,
+            closed: false,
+        }
+    }
+
+    /// Get reference to the underlying stream
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get mutable reference to the underlying stream
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Ping an idle client and fail once it has been silent too long
+    ///
+    /// Call periodically (e.g. on read timeouts).
+    pub fn keepalive(&mut self) -> io::Result<()> {
+        let now = Instant::now();
+        if now.duration_since(self.last_received) >= PONG_TIMEOUT {
+            return Err(io::Error::new(
+                ErrorKind::TimedOut,
+                "WebSocket client stopped answering pings",
+            ));
+        }
+
+        if now.duration_since(self.last_received) >= PING_INTERVAL
+            && now.duration_since(self.last_ping) >= PING_INTERVAL
+        {
+            self.last_ping = now;
+            self.send_frame(OPCODE_PING, &[])?;
+        }
+        Ok(())
+    }
+
+    /// Send one unmasked, unfragmented frame
+    fn send_frame(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let mut frame = Vec::with_capacity(payload.len() + 10);
+        frame.push(0x80 | opcode);
+
+        let len = payload.len();
+        if len < 126 {
+            frame.push(len as u8);
+        } else if len <= u16::MAX as usize {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        } else {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+        frame.extend_from_slice(payload);
+
+        self.inner.write_all(&frame)?;
+        self.inner.flush()
+    }
+
+    /// Read one frame and process it
+    ///
+    /// Returns `WouldBlock`/`TimedOut` if no frame has started arriving.
+    fn read_frame(&mut self) -> io::Result<()> {
+        let mut header = [0u8; 2];
+        // Propagate timeouts only while nothing of the frame has been read
+        let n = self.inner.read(&mut header[..1])?;
+        if n == 0 {
+            self.closed = true;
+            return Ok(());
+        }
+        read_exact_retrying(&mut self.inner, &mut header[1..])?;
+
+        let fin = header[0] & 0x80 != 0;
+        let opcode = header[0] & 0x0F;
+        let masked = header[1] & 0x80 != 0;
+        let mut len = (header[1] & 0x7F) as u64;
+
+        if len == 126 {
+            let mut ext = [0u8; 2];
+            read_exact_retrying(&mut self.inner, &mut ext)?;
+            len = u16::from_be_bytes(ext) as u64;
+        } else if len == 127 {
+            let mut ext = [0u8; 8];
+            read_exact_retrying(&mut self.inner, &mut ext)?;
+            len = u64::from_be_bytes(ext);
+        }
+
+        if !masked {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Client frames must be masked",
+            ));
+        }
+        if len + self.fragments.len() as u64 > MAX_FRAME_PAYLOAD {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("WebSocket message too large: {} bytes", len),
+            ));
+        }
+
+        let mut mask = [0u8; 4];
+        read_exact_retrying(&mut self.inner, &mut mask)?;
+        let mut payload = vec![0u8; len as usize];
+        read_exact_retrying(&mut self.inner, &mut payload)?;
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+
+        self.last_received = Instant::now();
+
+        match opcode {
+            OPCODE_TEXT | OPCODE_BINARY | OPCODE_CONTINUATION => {
+                self.fragments.extend_from_slice(&payload);
+                if fin {
+                    let message = std::mem::take(&mut self.fragments);
+                    self.read_buf.extend((message.len() as u32).to_be_bytes());
+                    self.read_buf.extend(message);
+                }
+            }
+            OPCODE_PING => self.send_frame(OPCODE_PONG, &payload)?,
+            OPCODE_PONG => {}
+            OPCODE_CLOSE => {
+                // Echo the close frame and report end of stream
+                let _ = self.send_frame(OPCODE_CLOSE, &payload);
+                self.closed = true;
+            }
+            other => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Unsupported WebSocket opcode: 0x{:X}", other),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: Read + Write> Read for WebSocketStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.read_buf.is_empty() {
+            if self.closed {
+                return Ok(0);
+            }
+            self.read_frame()?;
+        }
+
+        let n = buf.len().min(self.read_buf.len());
+        for (slot, byte) in buf.iter_mut().zip(self.read_buf.drain(..n)) {
+            *slot = byte;
+        }
+        Ok(n)
+    }
+}
+
+impl<S: Read + Write> Write for WebSocketStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_buf.extend_from_slice(buf);
+
+        // Send every complete [length][payload] frame as one binary message
+        while self.write_buf.len() >= 4 {
+            let len = u32::from_be_bytes([
+                self.write_buf[0],
+                self.write_buf[1],
+                self.write_buf[2],
+                self.write_buf[3],
+            ]) as usize;
+            if self.write_buf.len() < 4 + len {
+                break;
+            }
+            let frame: Vec<u8> = self.write_buf.drain(..4 + len).skip(4).collect();
+            self.send_frame(OPCODE_BINARY, &frame)?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// In-memory duplex stream: reads from `input`, records writes in `output`
+    struct MockStream {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for MockStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for MockStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn masked_frame(opcode: u8, fin: bool, payload: &[u8]) -> Vec<u8> {
+        let mask = [0x12, 0x34, 0x56, 0x78];
+        let mut frame = vec![
+            (if fin { 0x80 } else { 0 }) | opcode,
+            0x80 | payload.len() as u8,
+        ];
+        frame.extend_from_slice(&mask);
+        frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        frame
+    }
+
+    fn stream_with_input(input: Vec<u8>) -> WebSocketStream<MockStream> {
+        WebSocketStream::new(MockStream {
+            input: Cursor::new(input),
+            output: Vec::new(),
+        })
+    }
+
+    /// Server and client ends of a local TCP connection
+    fn tcp_pair() -> (TcpStream, TcpStream) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        (server, client)
+    }
+
+    #[test]
+    fn test_silent_client_times_out_handshake() {
+        let (server, _client) = tcp_pair();
+
+        let start = Instant::now();
+        let err = accept_with_timeout(server, Duration::from_millis(50))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_upgrade_clears_read_timeout() {
+        let (server, mut client) = tcp_pair();
+        client
+            .write_all(
+                b"GET / HTTP/1.1\r\nUpgrade: websocket\r\n\
+                  Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            )
+            .unwrap();
+
+        let stream = accept_with_timeout(server, Duration::from_secs(5)).unwrap();
+        assert_eq!(stream.get_ref().read_timeout().unwrap(), None);
+    }
+
+    #[test]
+    fn test_accept_key_matches_rfc_example() {
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn test_base64_padding() {
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+    }
+
+    #[test]
+    fn test_parse_upgrade_request_requires_key() {
+        let request = "GET / HTTP/1.1\r\nHost: x\r\nUpgrade: websocket\r\n\r\n";
+        assert!(parse_upgrade_request(request).is_err());
+
+        let request = "GET / HTTP/1.1\r\nUpgrade: WebSocket\r\nSec-WebSocket-Key: abc\r\n\r\n";
+        assert_eq!(parse_upgrade_request(request).unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_fragmented_message_is_reassembled_with_length_prefix() {
+        let mut input = masked_frame(OPCODE_BINARY, false, &[0x11, b'{']);
+        input.extend(masked_frame(OPCODE_CONTINUATION, true, b"}"));
+        let mut ws = stream_with_input(input);
+
+        let mut decoded = [0u8; 7];
+        ws.read_exact(&mut decoded).unwrap();
+        assert_eq!(decoded, [0, 0, 0, 3, 0x11, b'{', b'}']);
+    }
+
+    #[test]
+    fn test_ping_is_answered_with_pong() {
+        let mut input = masked_frame(OPCODE_PING, true, b"hi");
+        input.extend(masked_frame(OPCODE_CLOSE, true, &[]));
+        let mut ws = stream_with_input(input);
+
+        let mut buf = [0u8; 1];
+        assert_eq!(ws.read(&mut buf).unwrap(), 0);
+        assert_eq!(
+            &ws.get_ref().output[..4],
+            &[0x80 | OPCODE_PONG, 2, b'h', b'i']
+        );
+    }
+
+    #[test]
+    fn test_write_sends_one_binary_frame_per_message() {
+        let mut ws = stream_with_input(Vec::new());
+
+        // Length-prefixed frame written in two pieces
+        ws.write_all(&[0, 0, 0, 3]).unwrap();
+        assert!(ws.get_ref().output.is_empty());
+        ws.write_all(&[0x02, b'{', b'}']).unwrap();
+
+        assert_eq!(
+            ws.get_ref().output,
+            vec![0x80 | OPCODE_BINARY, 3, 0x02, b'{', b'}']
+        );
+    }
+
+    #[test]
+    fn test_unmasked_client_frame_is_rejected() {
+        let mut ws = stream_with_input(vec![0x80 | OPCODE_BINARY, 1, 0x01]);
+        let mut buf = [0u8; 1];
+        assert!(ws.read(&mut buf).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Fixtures shared by the integration tests
+//!
+//! Each test binary compiles its own copy of this module and uses only part
+//! of it, hence the `dead_code` allowance.
+
+#![allow(dead_code)]
+
+use roomrtc_server::domain::{CallState, User};
+use roomrtc_server::infrastructure::storage::Storage;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+pub const LOGIN_REQUEST: u8 = 0x01;
+pub const LOGIN_RESPONSE: u8 = 0x02;
+
+/// Logger writing to `file_name` in the temp dir
+pub fn test_logger(file_name: &str) -> logging::Logger {
+    logging::Logger::new(
+        std::env::temp_dir().join(file_name),
+        logging::LogLevel::Debug,
+    )
+    .unwrap()
+}
+
+/// Run `start` on a background thread with a free local address
+///
+/// # Arguments
+/// * `start` - Starts the server on the given address (blocking)
+///
+/// # Returns
+/// The address the server is started on
+pub fn start_server<T>(start: impl FnOnce(&str) -> T + Send + 'static) -> String {
+    let addr = {
+        let probe = TcpListener::bind("127.0.0.1:0").unwrap();
+        probe.local_addr().unwrap().to_string()
+    };
+
+    let bind_addr = addr.clone();
+    thread::spawn(move || {
+        let _ = start(bind_addr.as_str());
+    });
+
+    addr
+}
+
+/// Connect to the server, retrying while it starts
+pub fn connect(addr: &str) -> TcpStream {
+    for _ in 0..50 {
+        if let Ok(stream) = TcpStream::connect(addr) {
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            return stream;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("Server did not start on {}", addr);
+}
+
+/// Write a signaling frame: length, message type and JSON payload
+pub fn send(stream: &mut TcpStream, msg_type: u8, json: &str) {
+    let len = (json.len() + 1) as u32;
+    stream.write_all(&len.to_be_bytes()).unwrap();
+    stream.write_all(&[msg_type]).unwrap();
+    stream.write_all(json.as_bytes()).unwrap();
+}
+
+/// Read one signaling frame, returning (message type, JSON payload)
+pub fn read_frame(stream: &mut TcpStream) -> io::Result<(u8, String)> {
+    let mut header = [0u8; 5];
+    stream.read_exact(&mut header)?;
+    let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let mut payload = vec![0u8; len - 1];
+    stream.read_exact(&mut payload)?;
+    Ok((header[4], String::from_utf8(payload).unwrap()))
+}
+
+/// Read frames until one of `msg_type` arrives, skipping presence updates
+pub fn read_until(stream: &mut TcpStream, msg_type: u8) -> String {
+    loop {
+        let (received, json) = read_frame(stream).unwrap();
+        if received == msg_type {
+            return json;
+        }
+    }
+}
+
+/// Log in as `username` with the password of [`storage_with_call`]
+///
+/// # Returns
+/// The connection and the LOGIN_RESPONSE payload
+pub fn login(addr: &str, username: &str) -> (TcpStream, String) {
+    let mut stream = connect(addr);
+    send(
+        &mut stream,
+        LOGIN_REQUEST,
+        &format!(
+            "{{\"username\":\"{}\",\"password_hash\":\"secret\"}}",
+            username
+        ),
+    );
+    let json = read_until(&mut stream, LOGIN_RESPONSE);
+    (stream, json)
+}
+
+/// Storage with two registered users in an active call, returning the call id
+pub fn storage_with_call() -> (Storage, String) {
+    let storage = Storage::new();
+    for (id, name) in [("u_alice", "alice"), ("u_bob", "bob")] {
+        storage
+            .create_user(User::new(id.to_string(), name.to_string(), "secret"))
+            .unwrap();
+    }
+    let call = storage
+        .create_call("u_alice".to_string(), "u_bob".to_string())
+        .unwrap();
+    storage
+        .update_call_state(&call.call_id, CallState::Active)
+        .unwrap();
+    (storage, call.call_id)
+}
@@ -5,7 +5,9 @@
 //! - User login with valid credentials
 //! - Login with invalid credentials
 //! - Duplicate username handling
+//! - Migration of legacy password hashes
 
+use roomrtc_server::application::usecases::AuthUseCase;
 use roomrtc_server::domain::{User, UserState};
 use roomrtc_server::infrastructure::storage::Storage;
 use std::sync::mpsc;
@@ -210,3 +212,40 @@ fn test_password_hashing_consistency() {
     assert_eq!(retrieved.password_hash, original_hash);
     assert!(retrieved.verify_password(password));
 }
+
+/// Unsalted hash stored by versions before Argon2 hashing
+fn legacy_hash(input: &str) -> String {
+    let mut hash: u64 = 0;
+    for (i, &byte) in input.as_bytes().iter().enumerate() {
+        hash = hash.rotate_left(5) ^ (byte as u64) ^ (i as u64);
+    }
+    format!("{:016x}", hash)
+}
+
+#[test]
+fn test_legacy_hash_rehashed_on_login() {
+    let storage = Storage::new();
+    let legacy = User {
+        id: "user1".to_string(),
+        username: "henry".to_string(),
+        password_hash: legacy_hash("old_password"),
+        created_at: 0,
+    };
+    storage.create_user(legacy).unwrap();
+
+    let logger = logging::Logger::new(
+        std::env::temp_dir().join("integration_auth.log"),
+        logging::LogLevel::Debug,
+    )
+    .unwrap();
+    let auth = AuthUseCase::new(storage.clone(), logger);
+
+    assert!(auth.authenticate("henry", "wrong").is_none());
+    assert!(auth.authenticate("henry", "old_password").is_some());
+
+    // Stored hash has been replaced by a salted Argon2 hash
+    let migrated = storage.get_user_by_username("henry").unwrap();
+    assert!(!migrated.needs_rehash());
+    assert!(migrated.verify_password("old_password"));
+    assert!(auth.authenticate("henry", "old_password").is_some());
+}
@@ -6,9 +6,12 @@
 //! - Call state transitions
 //! - Call termination
 //! - Multi-user call scenarios
+//! - Busy callees declining new calls
 
-use roomrtc_server::domain::{CallState, User, UserState};
+use roomrtc_server::application::handlers::message_handler::MessageHandler;
+use roomrtc_server::domain::{CallState, DeclineReason, User, UserState};
 use roomrtc_server::infrastructure::storage::Storage;
+use roomrtc_server::tcp::messages::{CallRequest, CallResponseMsg, Message};
 use std::sync::mpsc;
 
 #[test]
@@ -370,3 +373,78 @@ fn test_complete_call_lifecycle() {
         Some(UserState::Disconnected)
     );
 }
+
+#[test]
+fn test_call_to_user_in_call_is_declined_as_busy() {
+    let storage = Storage::new();
+    let logger = logging::Logger::new(
+        std::env::temp_dir().join("integration_calls.log"),
+        logging::LogLevel::Debug,
+    )
+    .unwrap();
+    let handler = MessageHandler::new(storage.clone(), logger);
+
+    let mut receivers = Vec::new();
+    for (id, name) in [("user1", "alice"), ("user2", "bob"), ("user3", "carol")] {
+        storage
+            .create_user(User::new(id.to_string(), name.to_string(), "pass"))
+            .unwrap();
+        let (tx, rx) = mpsc::channel();
+        storage.connect_user(id.to_string(), tx).unwrap();
+        receivers.push(rx);
+    }
+    let bob_rx = &receivers[1];
+
+    // Alice calls Bob, who accepts
+    let call_request = |to: &str| {
+        Message::CallRequest(CallRequest {
+            to_user_id: to.to_string(),
+        })
+    };
+    handler
+        .process_message(call_request("user2"), Some(&"user1".to_string()))
+        .unwrap();
+    let call_id = bob_rx
+        .try_iter()
+        .find_map(|m| match m {
+            Message::CallNotification(n) => Some(n.call_id),
+            _ => None,
+        })
+        .expect("Bob is notified of Alice's call");
+    handler
+        .process_message(
+            Message::CallResponse(CallResponseMsg {
+                call_id: call_id.clone(),
+                accepted: true,
+            }),
+            Some(&"user2".to_string()),
+        )
+        .unwrap();
+    assert_eq!(
+        storage.get_user_state(&"user2".to_string()),
+        Some(UserState::Busy)
+    );
+
+    // Carol calls Bob and is told he is busy
+    let response = handler
+        .process_message(call_request("user2"), Some(&"user3".to_string()))
+        .unwrap();
+    match response {
+        Some(Message::CallDeclined(declined)) => {
+            assert_eq!(declined.peer_user_id, "user2");
+            assert_eq!(declined.peer_username, "bob");
+            assert_eq!(declined.reason, DeclineReason::Busy.to_string());
+        }
+        other => panic!("Expected busy decline, got {:?}", other),
+    }
+
+    // Bob's call is untouched and he never rang
+    assert!(
+        !bob_rx
+            .try_iter()
+            .any(|m| matches!(m, Message::CallNotification(_)))
+    );
+    let call = storage.get_call(&call_id).unwrap();
+    assert_eq!(call.participants.len(), 2);
+    assert!(!call.has_participant(&"user3".to_string()));
+}
@@ -0,0 +1,76 @@
+//! Integration tests for heartbeat-based dead connection detection
+//!
+//! Tests a real server socket end to end:
+//! - A client that stops sending anything is disconnected after the timeout
+//! - Its active call is ended and the peer receives HANGUP
+//! - A client that keeps sending heartbeats stays online
+
+mod common;
+
+use common::{login, read_frame, send, storage_with_call};
+use roomrtc_server::config::ServerConfig;
+use roomrtc_server::domain::UserState;
+use roomrtc_server::infrastructure::storage::Storage;
+use roomrtc_server::tcp::TcpServer;
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+
+const HANGUP: u8 = 0x10;
+const HEARTBEAT: u8 = 0x11;
+
+/// Start a server sharing `storage` on a free local port, returning its address
+fn start_server(storage: Storage, heartbeat_timeout_secs: u32) -> String {
+    let config = ServerConfig {
+        heartbeat_timeout_secs,
+        // A silent client must be ended, not kept resumable
+        session_resume_secs: 0,
+        ..ServerConfig::default()
+    };
+    let server = TcpServer::new(storage, common::test_logger("integration_heartbeat.log"))
+        .with_session_resume(&config)
+        .with_heartbeat_timeout(&config);
+    common::start_server(move |addr| server.start(addr))
+}
+
+#[test]
+fn test_silent_client_is_disconnected_and_call_ended() {
+    let (storage, call_id) = storage_with_call();
+    let addr = start_server(storage.clone(), 1);
+
+    let (_alice, _) = login(&addr, "alice");
+    let (mut bob, _) = login(&addr, "bob");
+    bob.set_read_timeout(Some(Duration::from_millis(200)))
+        .unwrap();
+
+    // Alice goes silent; Bob keeps heartbeating until the server gives up on Alice
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut got_hangup = false;
+    while Instant::now() < deadline && !got_hangup {
+        send(&mut bob, HEARTBEAT, "{}");
+        loop {
+            match read_frame(&mut bob) {
+                Ok((HANGUP, json)) => {
+                    assert!(json.contains(&call_id), "{}", json);
+                    got_hangup = true;
+                    break;
+                }
+                Ok(_) => continue,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    break;
+                }
+                Err(e) => panic!("Bob lost the connection: {}", e),
+            }
+        }
+    }
+
+    assert!(got_hangup, "Bob was not told the call ended");
+    assert!(storage.get_call(&call_id).is_none());
+    assert_eq!(
+        storage.get_user_state(&"u_alice".to_string()),
+        Some(UserState::Disconnected)
+    );
+    assert_ne!(
+        storage.get_user_state(&"u_bob".to_string()),
+        Some(UserState::Disconnected)
+    );
+}
@@ -0,0 +1,76 @@
+//! Integration tests for the HTTP metrics endpoint
+//!
+//! Tests a real server socket end to end:
+//! - `/metrics` reflects registered users and active calls
+//! - `/health` and unknown routes
+
+mod common;
+
+use roomrtc_server::domain::User;
+use roomrtc_server::infrastructure::storage::Storage;
+use roomrtc_server::tcp::MetricsServer;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+/// Start a metrics server on a free local port and return its address
+fn start_server(storage: Storage) -> String {
+    let server = MetricsServer::new(storage, common::test_logger("integration_metrics.log"));
+    common::start_server(move |addr| server.start(addr))
+}
+
+/// Send a GET request and return the full HTTP response
+fn get(addr: &str, path: &str) -> String {
+    for _ in 0..50 {
+        if let Ok(mut stream) = TcpStream::connect(addr) {
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+            stream.write_all(request.as_bytes()).unwrap();
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            return response;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("Metrics server did not start on {}", addr);
+}
+
+#[test]
+fn test_metrics_reflect_user_and_active_call() {
+    let storage = Storage::new();
+    storage
+        .create_user(User::new(
+            "user1".to_string(),
+            "alice".to_string(),
+            "secret",
+        ))
+        .unwrap();
+    storage
+        .create_call("user1".to_string(), "user2".to_string())
+        .unwrap();
+    let addr = start_server(storage);
+
+    let response = get(&addr, "/metrics");
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.contains("Content-Type: application/json"));
+    assert!(response.contains(r#""registered_users":1"#), "{}", response);
+    assert!(response.contains(r#""active_calls":1"#), "{}", response);
+    assert!(response.contains(r#""calls_started":1"#), "{}", response);
+    assert!(response.contains(r#""connected_users":0"#), "{}", response);
+}
+
+#[test]
+fn test_health_and_unknown_route() {
+    let addr = start_server(Storage::new());
+
+    let health = get(&addr, "/health");
+    assert!(health.starts_with("HTTP/1.1 200"));
+    assert!(health.ends_with(r#"{"status":"ok"}"#));
+
+    let missing = get(&addr, "/nope");
+    assert!(missing.starts_with("HTTP/1.1 404"));
+}
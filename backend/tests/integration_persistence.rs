@@ -0,0 +1,89 @@
+//! Integration tests for SQLite persistence
+//!
+//! Tests that data survives a storage restart:
+//! - Registration followed by login after reopening the database
+//! - Call history recording
+
+use roomrtc_server::application::usecases::AuthUseCase;
+use roomrtc_server::domain::User;
+use roomrtc_server::infrastructure::storage::Storage;
+use roomrtc_server::tcp::messages::{LoginRequest, Message, RegisterRequest};
+use std::io::Cursor;
+use std::path::PathBuf;
+
+fn temp_db(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}_{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn test_logger() -> logging::Logger {
+    let path = std::env::temp_dir().join("integration_persistence.log");
+    logging::Logger::new(path, logging::LogLevel::Debug).unwrap()
+}
+
+#[test]
+fn test_register_restart_and_login() {
+    let path = temp_db("persistence_login");
+
+    // Register through the auth use case
+    {
+        let storage = Storage::with_sqlite(&path).expect("Database should open");
+        let auth = AuthUseCase::new(storage, test_logger());
+        let response = auth
+            .handle_register(&RegisterRequest {
+                username: "alice".to_string(),
+                password_hash: "secret".to_string(),
+            })
+            .unwrap();
+        assert!(matches!(response, Message::RegisterResponse(r) if r.success));
+    }
+
+    // Restart storage against the same file
+    let storage = Storage::with_sqlite(&path).expect("Database should reopen");
+    assert!(storage.get_user_by_username("alice").is_some());
+
+    let auth = AuthUseCase::new(storage, test_logger());
+    let mut stream = Cursor::new(Vec::new());
+    let login = auth
+        .handle_login(
+            &LoginRequest {
+                username: "alice".to_string(),
+                password_hash: "secret".to_string(),
+            },
+            &mut stream,
+        )
+        .unwrap();
+    assert!(login.is_some(), "Login should succeed after restart");
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_call_history_survives_restart() {
+    let path = temp_db("persistence_calls");
+
+    {
+        let storage = Storage::with_sqlite(&path).unwrap();
+        storage
+            .create_user(User::new("user1".to_string(), "alice".to_string(), "pass"))
+            .unwrap();
+        storage
+            .create_user(User::new("user2".to_string(), "bob".to_string(), "pass"))
+            .unwrap();
+
+        let call = storage
+            .create_call("user1".to_string(), "user2".to_string())
+            .unwrap();
+        storage.remove_call(&call.call_id).unwrap();
+    }
+
+    let storage = Storage::with_sqlite(&path).unwrap();
+    let history = storage.get_call_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].caller_id, "user1");
+    assert_eq!(history[0].callee_id, "user2");
+    assert!(history[0].ended_at >= history[0].started_at);
+
+    let _ = std::fs::remove_file(&path);
+}
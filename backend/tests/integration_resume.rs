@@ -0,0 +1,173 @@
+//! Integration tests for resuming a dropped signaling connection
+//!
+//! Tests a real server socket end to end:
+//! - LOGIN_RESPONSE carries a session token
+//! - A dropped connection keeps its user in the call
+//! - RESUME_REQUEST with the token restores the session and replays missed messages
+//! - A session that is not resumed in time ends the call
+//! - Signaling sent to an offline user is delivered when they log in
+
+mod common;
+
+use common::{connect, read_until, send, storage_with_call};
+use roomrtc_server::config::ServerConfig;
+use roomrtc_server::infrastructure::storage::Storage;
+use roomrtc_server::tcp::TcpServer;
+use std::net::TcpStream;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SDP_OFFER: u8 = 0x0D;
+const SDP_ANSWER: u8 = 0x0E;
+const HANGUP: u8 = 0x10;
+const RESUME_REQUEST: u8 = 0x18;
+const RESUME_RESPONSE: u8 = 0x19;
+
+/// Start a server sharing `storage` on a free local port, returning its address
+fn start_server(storage: Storage, session_resume_secs: u32) -> String {
+    let config = ServerConfig {
+        session_resume_secs,
+        ..ServerConfig::default()
+    };
+    let server = TcpServer::new(storage, common::test_logger("integration_resume.log"))
+        .with_session_resume(&config);
+    common::start_server(move |addr| server.start(addr))
+}
+
+/// Extract a string field from a flat JSON object
+fn string_field(json: &str, field: &str) -> Option<String> {
+    let key = format!("\"{}\":\"", field);
+    let start = json.find(&key)? + key.len();
+    let end = json[start..].find('"')?;
+    Some(json[start..start + end].to_string())
+}
+
+/// Log in as `username`, returning the connection and its session token
+fn login(addr: &str, username: &str) -> (TcpStream, String) {
+    let (stream, json) = common::login(addr, username);
+    let token = string_field(&json, "session_token").expect("login returns a session token");
+    (stream, token)
+}
+
+fn wait_for(condition: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    false
+}
+
+#[test]
+fn test_resume_preserves_call_and_replays_messages() {
+    let (storage, call_id) = storage_with_call();
+    let addr = start_server(storage.clone(), 30);
+
+    let (alice, token) = login(&addr, "alice");
+    let (mut bob, _) = login(&addr, "bob");
+
+    // Alice's connection drops mid-call
+    drop(alice);
+    assert!(wait_for(
+        || storage.is_session_suspended(&"u_alice".to_string())
+    ));
+
+    // Bob keeps signaling while Alice is away
+    send(
+        &mut bob,
+        SDP_OFFER,
+        &format!(
+            "{{\"call_id\":\"{}\",\"from_user_id\":\"u_bob\",\"to_user_id\":\"u_alice\",\"sdp\":\"v=0 missed\"}}",
+            call_id
+        ),
+    );
+    thread::sleep(Duration::from_millis(200));
+
+    let mut alice = connect(&addr);
+    send(
+        &mut alice,
+        RESUME_REQUEST,
+        &format!("{{\"session_token\":\"{}\"}}", token),
+    );
+
+    let response = read_until(&mut alice, RESUME_RESPONSE);
+    assert!(response.contains("\"success\":true"), "{}", response);
+    assert_eq!(
+        string_field(&response, "user_id").as_deref(),
+        Some("u_alice")
+    );
+    assert_eq!(
+        string_field(&response, "call_id").as_deref(),
+        Some(call_id.as_str())
+    );
+
+    let offer = read_until(&mut alice, SDP_OFFER);
+    assert!(offer.contains("v=0 missed"), "{}", offer);
+
+    let call = storage.get_call(&call_id).expect("call survives the drop");
+    assert!(call.has_participant(&"u_alice".to_string()));
+    assert!(call.has_participant(&"u_bob".to_string()));
+    assert!(!storage.is_session_suspended(&"u_alice".to_string()));
+}
+
+#[test]
+fn test_unknown_token_is_rejected() {
+    let addr = start_server(Storage::new(), 30);
+    let mut client = connect(&addr);
+
+    send(
+        &mut client,
+        RESUME_REQUEST,
+        "{\"session_token\":\"not-a-session\"}",
+    );
+
+    let response = read_until(&mut client, RESUME_RESPONSE);
+    assert!(response.contains("\"success\":false"), "{}", response);
+    assert!(string_field(&response, "error").is_some());
+}
+
+#[test]
+fn test_expired_session_ends_call() {
+    let (storage, call_id) = storage_with_call();
+    let addr = start_server(storage.clone(), 0);
+
+    let (alice, token) = login(&addr, "alice");
+    let (mut bob, _) = login(&addr, "bob");
+
+    drop(alice);
+    assert!(wait_for(|| storage.get_call(&call_id).is_none()));
+    read_until(&mut bob, HANGUP);
+
+    let mut alice = connect(&addr);
+    send(
+        &mut alice,
+        RESUME_REQUEST,
+        &format!("{{\"session_token\":\"{}\"}}", token),
+    );
+    let response = read_until(&mut alice, RESUME_RESPONSE);
+    assert!(response.contains("\"success\":false"), "{}", response);
+}
+
+#[test]
+fn test_signaling_for_offline_user_delivered_on_login() {
+    let (storage, call_id) = storage_with_call();
+    let addr = start_server(storage, 30);
+
+    // Alice is not connected yet when Bob answers
+    let (mut bob, _) = login(&addr, "bob");
+    send(
+        &mut bob,
+        SDP_ANSWER,
+        &format!(
+            "{{\"call_id\":\"{}\",\"from_user_id\":\"u_bob\",\"to_user_id\":\"u_alice\",\"sdp\":\"v=0 queued\"}}",
+            call_id
+        ),
+    );
+    thread::sleep(Duration::from_millis(200));
+
+    let (mut alice, _) = login(&addr, "alice");
+    let answer = read_until(&mut alice, SDP_ANSWER);
+    assert!(answer.contains("v=0 queued"), "{}", answer);
+}
@@ -0,0 +1,76 @@
+//! Integration tests for graceful server shutdown
+//!
+//! Tests a real server socket end to end:
+//! - `start` returns once the shutdown flag is set
+//! - The listening port is released
+//! - Connected clients receive a SERVER_SHUTDOWN message
+
+mod common;
+
+use common::connect;
+use roomrtc_server::infrastructure::storage::Storage;
+use roomrtc_server::tcp::TcpServer;
+use std::io::Read;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+const SERVER_SHUTDOWN: u8 = 0x17;
+
+/// Start a server on a free local port, returning its address, the shutdown
+/// flag and a channel that fires when `start` returns
+fn start_server() -> (String, Arc<AtomicBool>, mpsc::Receiver<bool>) {
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let server = TcpServer::new(
+        Storage::new(),
+        common::test_logger("integration_shutdown.log"),
+    )
+    .with_shutdown(shutdown.clone());
+
+    let (done_tx, done_rx) = mpsc::channel();
+    let addr = common::start_server(move |addr| {
+        let result = server.start(addr);
+        let _ = done_tx.send(result.is_ok());
+    });
+
+    (addr, shutdown, done_rx)
+}
+
+#[test]
+fn test_shutdown_returns_and_releases_listener() {
+    let (addr, shutdown, done) = start_server();
+    let _client = connect(&addr);
+
+    shutdown.store(true, Ordering::SeqCst);
+
+    let result = done
+        .recv_timeout(Duration::from_secs(10))
+        .expect("start did not return after shutdown");
+    assert!(result);
+    assert!(TcpListener::bind(&addr).is_ok(), "listener still bound");
+}
+
+#[test]
+fn test_connected_client_is_notified() {
+    let (addr, shutdown, done) = start_server();
+    let mut client = connect(&addr);
+    // Give the server time to spawn the handler
+    thread::sleep(Duration::from_millis(200));
+
+    shutdown.store(true, Ordering::SeqCst);
+
+    let mut header = [0u8; 5];
+    client.read_exact(&mut header).unwrap();
+    let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    assert_eq!(header[4], SERVER_SHUTDOWN);
+
+    let mut payload = vec![0u8; len - 1];
+    client.read_exact(&mut payload).unwrap();
+    let json = String::from_utf8(payload).unwrap();
+    assert!(json.contains("reason"), "{}", json);
+
+    assert!(done.recv_timeout(Duration::from_secs(10)).unwrap());
+}
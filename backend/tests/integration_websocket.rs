@@ -0,0 +1,124 @@
+//! Integration tests for the WebSocket signaling transport
+//!
+//! Tests a real server socket end to end:
+//! - HTTP upgrade handshake
+//! - Login request/response carried in WebSocket frames
+//! - Ping/pong keepalive
+
+mod common;
+
+use common::{LOGIN_REQUEST, LOGIN_RESPONSE, connect};
+use roomrtc_server::domain::User;
+use roomrtc_server::infrastructure::storage::Storage;
+use roomrtc_server::tcp::websocket::accept_key;
+use roomrtc_server::tcp::{TcpServer, Transport};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+const CLIENT_KEY: &str = "dGhlIHNhbXBsZSBub25jZQ==";
+
+/// Start a WebSocket server on a free local port and return its address
+fn start_server(storage: Storage) -> String {
+    let server = TcpServer::new(storage, common::test_logger("integration_websocket.log"))
+        .with_transport(Transport::WebSocket);
+    common::start_server(move |addr| server.start(addr))
+}
+
+/// Send the upgrade request and return the response head
+fn handshake(stream: &mut TcpStream) -> String {
+    let request = format!(
+        "GET / HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        CLIENT_KEY
+    );
+    stream.write_all(request.as_bytes()).unwrap();
+
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        stream.read_exact(&mut byte).unwrap();
+        response.push(byte[0]);
+    }
+    String::from_utf8(response).unwrap()
+}
+
+/// Write a masked client frame (payloads under 126 bytes)
+fn write_frame(stream: &mut TcpStream, opcode: u8, payload: &[u8]) {
+    let mask = [0xA1, 0xB2, 0xC3, 0xD4];
+    let mut frame = vec![0x80 | opcode, 0x80 | payload.len() as u8];
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    stream.write_all(&frame).unwrap();
+}
+
+/// Read one unmasked server frame, returning (opcode, payload)
+fn read_frame(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).unwrap();
+    let mut len = (header[1] & 0x7F) as usize;
+    if len == 126 {
+        let mut ext = [0u8; 2];
+        stream.read_exact(&mut ext).unwrap();
+        len = u16::from_be_bytes(ext) as usize;
+    }
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).unwrap();
+    (header[0] & 0x0F, payload)
+}
+
+#[test]
+fn test_websocket_upgrade_and_login() {
+    let storage = Storage::new();
+    storage
+        .create_user(User::new(
+            "user1".to_string(),
+            "alice".to_string(),
+            "secret",
+        ))
+        .unwrap();
+    let addr = start_server(storage);
+    let mut stream = connect(&addr);
+
+    let response = handshake(&mut stream);
+    assert!(response.starts_with("HTTP/1.1 101"));
+    assert!(response.contains(&format!("Sec-WebSocket-Accept: {}", accept_key(CLIENT_KEY))));
+
+    // Login request: [type][JSON] in one binary frame
+    let mut login = vec![LOGIN_REQUEST];
+    login.extend_from_slice(br#"{"username":"alice","password_hash":"secret"}"#);
+    write_frame(&mut stream, 0x2, &login);
+
+    let (opcode, payload) = read_frame(&mut stream);
+    assert_eq!(opcode, 0x2);
+    assert_eq!(payload[0], LOGIN_RESPONSE);
+    let json = String::from_utf8(payload[1..].to_vec()).unwrap();
+    assert!(json.contains(r#""success":true"#), "{}", json);
+    assert!(json.contains("user1"));
+}
+
+#[test]
+fn test_websocket_ping_pong() {
+    let addr = start_server(Storage::new());
+    let mut stream = connect(&addr);
+    assert!(handshake(&mut stream).starts_with("HTTP/1.1 101"));
+
+    write_frame(&mut stream, 0x9, b"keepalive");
+
+    let (opcode, payload) = read_frame(&mut stream);
+    assert_eq!(opcode, 0xA);
+    assert_eq!(payload, b"keepalive");
+}
+
+#[test]
+fn test_plain_http_request_is_rejected() {
+    let addr = start_server(Storage::new());
+    let mut stream = connect(&addr);
+
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response);
+    assert!(response.starts_with("HTTP/1.1 400"));
+}
@@ -134,6 +134,40 @@ impl App {
         self.show_success("Logged out successfully".to_string());
     }
 
+    /// Handles the server closing the connection, returning to the login page
+    pub(in crate::app) fn handle_server_shutdown(&mut self, reason: String) {
+        // Leave the room locally; the server already ended our calls
+        if self.user_context.current_room_id.is_some() {
+            self.handle_exit_room();
+        }
+
+        self.user_context.clear();
+
+        if let Some(mut tcp_client) = self.tcp_client.take() {
+            self.logger.info("[AUTH] Disconnecting TCP client");
+            tcp_client.disconnect();
+        }
+
+        self.current_page = Page::Login;
+        self.show_error(format!("Disconnected: {}", reason));
+    }
+
+    /// Handles the outcome of resuming the session after the connection dropped
+    ///
+    /// On success calls and state are intact and missed messages follow;
+    /// otherwise the session is gone and the user has to log in again.
+    pub(in crate::app) fn handle_session_resumed(&mut self, success: bool, error: Option<String>) {
+        if success {
+            self.logger.info("[AUTH] Signaling session resumed");
+            return;
+        }
+
+        let err_msg = error.unwrap_or_else(|| "Session could not be resumed".to_string());
+        self.logger
+            .error(&format!("[AUTH] Session resume failed: {}", err_msg));
+        self.handle_server_shutdown(err_msg);
+    }
+
     /// Handles registration response from server
     pub(in crate::app) fn handle_register_response(
         &mut self,
@@ -238,6 +272,7 @@ impl App {
 
         // Navigate to lobby
         self.current_page = Page::Lobby;
+        self.warm_up_call_setup();
         self.show_success(format!("Welcome, {}!", username));
         self.logger
             .info(&format!("[AUTH] User '{}' navigated to Lobby", username));
@@ -76,15 +76,15 @@ impl App {
             call_id, user_name, peer_username
         ));
 
-        // Store peer info in context
-        self.user_context.peer_user_id = Some(peer_user_id.clone());
-
         // Mark peer as busy in lobby
         self.lobby.update_user_state(&peer_user_id, "", "Busy");
 
         // Create room data using call_id as room_id
         let room_id = call_id.clone();
         self.create_room(room_id.clone(), user_name, peer_username.clone());
+        if let Some(room) = self.current_room.as_mut() {
+            let _ = room.add_remote_participant(peer_user_id.clone(), peer_username.clone());
+        }
 
         // Set current room
         self.user_context.current_room_id = Some(room_id.clone());
@@ -100,19 +100,79 @@ impl App {
         self.show_success(format!("Connected to {}!", peer_username));
 
         // Generate offer (auto_start_connection will handle StartConnection after offer is ready)
-        let _ = self.logic_cmd_tx.send(LogicCommand::GenerateOffer);
+        let _ = self.logic_cmd_tx.send(LogicCommand::GenerateOffer {
+            peer_id: peer_user_id,
+        });
+    }
+
+    /// Handles another user joining our call (mesh rooms)
+    ///
+    /// Existing participants offer to the newcomer, who answers each of them.
+    pub(in crate::app) fn handle_participant_joined(
+        &mut self,
+        call_id: String,
+        user_id: String,
+        username: String,
+    ) {
+        if self.user_context.current_room_id.as_deref() != Some(call_id.as_str()) {
+            self.logger.warn(&format!(
+                "[CALL] Ignoring participant '{}' joining call '{}' - not in that room",
+                username, call_id
+            ));
+            return;
+        }
+
+        let Some(room) = self.current_room.as_mut() else {
+            return;
+        };
+        if let Err(e) = room.add_remote_participant(user_id.clone(), username.clone()) {
+            self.logger.error(&format!(
+                "[CALL] Cannot add participant '{}': {}",
+                username, e
+            ));
+            self.show_error(e);
+            return;
+        }
+
+        self.lobby.update_user_state(&user_id, &username, "Busy");
+        if self.user_context.outgoing_call_to.as_deref() == Some(user_id.as_str()) {
+            self.user_context.outgoing_call_to = None;
+        }
+
+        self.show_success(format!("{} joined the call", username));
+
+        let _ = self
+            .logic_cmd_tx
+            .send(LogicCommand::GenerateOffer { peer_id: user_id });
+    }
+
+    /// Handles a user leaving a call that continues with the rest of the room
+    pub(in crate::app) fn handle_participant_left(&mut self, user_id: String, username: String) {
+        self.lobby.update_user_state(&user_id, &username, "Available");
+        if self.remove_peer(&user_id) {
+            self.show_warning(format!("{} left the call", username));
+        }
     }
 
     /// Handles call declined by peer
-    pub(in crate::app) fn handle_call_declined(&mut self, peer_username: String) {
+    pub(in crate::app) fn handle_call_declined(&mut self, peer_username: String, reason: String) {
         self.user_context.outgoing_call_to = None;
-        self.show_warning(format!("{} declined the call", peer_username));
+        if reason == "Busy" {
+            self.show_warning(format!("{} is busy in another call", peer_username));
+        } else {
+            self.show_warning(format!("{} declined the call", peer_username));
+        }
     }
 
     /// Handles call hangup
     pub(in crate::app) fn handle_hangup(&mut self, call_id: String) {
-        // Mark peer as available again
-        if let Some(ref peer_id) = self.user_context.peer_user_id {
+        // Mark peers as available again
+        let peer_ids: Vec<String> = self
+            .current_room
+            .as_ref()
+            .map(|room| room.peers.keys().cloned().collect())
+            .unwrap_or_default();
+        for peer_id in &peer_ids {
             self.lobby.update_user_state(peer_id, "", "Available");
         }
 
@@ -143,5 +203,6 @@ impl App {
 
         // Stop WebRTC connection
         let _ = self.logic_cmd_tx.send(LogicCommand::StopConnection);
+        self.warm_up_call_setup();
     }
 }
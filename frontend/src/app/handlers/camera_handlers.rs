@@ -1,6 +1,7 @@
 //! Camera and Audio Operation Handlers
 //!
-//! Handles camera toggling, settings updates, and audio mute/unmute.
+//! Handles camera and screen share toggling, settings updates, and audio
+//! mute/unmute.
 
 use crate::app::state::App;
 use crate::events::LogicCommand;
@@ -84,6 +85,40 @@ impl App {
         }
     }
 
+    /// Starts or stops sharing the primary display for the current user
+    ///
+    /// The room's `screen_sharing` flag is only updated once the logic thread
+    /// confirms the change, so a failed start leaves the toggle off.
+    pub(in crate::app) fn handle_toggle_screen_share(&mut self) {
+        let Some(room) = self.current_room.as_ref() else {
+            self.logger
+                .warn("[SCREEN] Toggle screen share called but no current room");
+            return;
+        };
+        let screen_sharing = room.screen_sharing;
+
+        let fps = self
+            .user_context
+            .get_name()
+            .and_then(|name| room.get_participant(name))
+            .map_or(30.0, |p| p.camera_fps);
+
+        if screen_sharing {
+            self.logger.info("[SCREEN] Stopping screen share");
+            self.logic_cmd_tx
+                .send(LogicCommand::StopScreenShare)
+                .expect("Logic thread disconnected: failed to send StopScreenShare command");
+        } else {
+            self.logger.info(&format!(
+                "[SCREEN] Starting screen share - display: 0, fps: {}",
+                fps
+            ));
+            self.logic_cmd_tx
+                .send(LogicCommand::StartScreenShare { display_id: 0, fps })
+                .expect("Logic thread disconnected: failed to send StartScreenShare command");
+        }
+    }
+
     /// Updates camera settings (device ID and FPS) for the current user
     pub(in crate::app) fn handle_update_camera_settings(&mut self, device_id: i32, fps: f64) {
         let user_name = match self.user_context.get_name() {
@@ -101,8 +136,21 @@ impl App {
         ));
 
         if let Some(participant) = self.get_current_participant_mut() {
+            let switch_device =
+                participant.camera_on && participant.selected_camera_device != device_id;
             participant.selected_camera_device = device_id;
             participant.camera_fps = fps;
+
+            // A running camera moves to the new device without stopping the video
+            if switch_device {
+                self.logger.info(&format!(
+                    "[CAMERA] Switching camera to device {}",
+                    device_id
+                ));
+                self.logic_cmd_tx
+                    .send(LogicCommand::SwitchCamera { device_id })
+                    .expect("Logic thread disconnected: failed to send SwitchCamera command");
+            }
         } else {
             self.logger.warn(&format!(
                 "[CAMERA] Update camera settings failed - participant '{}' not found",
@@ -3,9 +3,15 @@
 //! Handles all lobby-related commands: calling users, accepting/declining calls, etc.
 
 use crate::app::state::App;
+use crate::events::LogicCommand;
 use crate::pages::Page;
 
 impl App {
+    /// Lets the logic thread gather ICE candidates while the user is in the lobby
+    pub(in crate::app) fn warm_up_call_setup(&self) {
+        let _ = self.logic_cmd_tx.send(LogicCommand::PregatherCandidates);
+    }
+
     /// Initiates a call to another user
     pub(in crate::app) fn handle_call_user(&mut self, to_user_id: String) {
         let Some(ref client) = self.tcp_client else {
@@ -74,9 +80,6 @@ impl App {
 
         match client.respond_to_call(&call_id, true) {
             Ok(()) => {
-                // Store peer info in context
-                self.user_context.peer_user_id = Some(caller_id);
-
                 self.logger.info(&format!(
                     "[LOBBY] Creating room for accepted call - call_id: {}, participants: [{}, {}]",
                     call_id, caller_name, user_name
@@ -85,6 +88,9 @@ impl App {
                 // Create room immediately (use call_id as room_id)
                 let room_id = call_id.clone();
                 self.create_room(room_id.clone(), caller_name.clone(), user_name.to_string());
+                if let Some(room) = self.current_room.as_mut() {
+                    let _ = room.add_remote_participant(caller_id, caller_name.clone());
+                }
 
                 // Set current room and transition to Room page
                 self.user_context.current_room_id = Some(room_id);
@@ -93,7 +99,7 @@ impl App {
                 self.user_context.outgoing_call_to = None;
 
                 self.logger.info(&format!(
-                    "[LOBBY] Room created successfully, waiting for SDP offer from '{}'",
+                    "[LOBBY] Room created successfully, waiting for SDP offers (from '{}' and any other participants)",
                     caller_name
                 ));
 
@@ -55,6 +55,14 @@ impl App {
             }
         }
 
+        // The peer goes back to Available unless the rest of a mesh room stays in the call
+        let peer_ids: Vec<String> = self
+            .current_room
+            .as_ref()
+            .filter(|room| room.peers.len() == 1)
+            .map(|room| room.peers.keys().cloned().collect())
+            .unwrap_or_default();
+
         // Remove participant from room
         if let Some(room) = self.current_room.as_mut() {
             room.remove_participant(&user_name);
@@ -76,11 +84,10 @@ impl App {
         ));
 
         // Clear peer context
-        if let Some(ref peer_id) = self.user_context.peer_user_id {
+        for peer_id in &peer_ids {
             self.lobby.update_user_state(peer_id, "", "Available");
         }
 
-        self.user_context.peer_user_id = None;
         self.user_context.outgoing_call_to = None;
 
         if let Some(ref user_id) = self.user_context.user_id {
@@ -95,6 +102,7 @@ impl App {
 
         // Return to lobby
         self.current_page = crate::pages::Page::Lobby;
+        self.warm_up_call_setup();
         self.logger
             .info(&format!("[ROOM] User '{}' returned to lobby", user_name));
     }
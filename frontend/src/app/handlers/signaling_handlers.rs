@@ -7,8 +7,13 @@ use crate::events::LogicCommand;
 use crate::pages::Page;
 
 impl App {
-    /// Handles incoming SDP offer (callee side)
-    pub(in crate::app) fn handle_sdp_offer(&mut self, call_id: String, offer: String) {
+    /// Handles incoming SDP offer (callee side, or a newcomer joining a mesh room)
+    pub(in crate::app) fn handle_sdp_offer(
+        &mut self,
+        call_id: String,
+        from_user_id: String,
+        offer: String,
+    ) {
         let my_name = self.user_context.get_name().unwrap_or("UNKNOWN");
 
         // Verify we're in the correct room already
@@ -20,41 +25,45 @@ impl App {
         }
 
         self.logger.info(&format!(
-            "[SIGNALING] Generating SDP answer for user '{}' - call_id: {}",
-            my_name, call_id
+            "[SIGNALING] Generating SDP answer for user '{}' - call_id: {}, peer: {}",
+            my_name, call_id, from_user_id
         ));
 
         // Generate answer from received offer
         // StartConnection will be called automatically after answer is sent
-        let _ = self
-            .logic_cmd_tx
-            .send(LogicCommand::GenerateAnswer { offer_sdp: offer });
+        let _ = self.logic_cmd_tx.send(LogicCommand::GenerateAnswer {
+            peer_id: from_user_id,
+            offer_sdp: offer,
+        });
     }
 
     /// Handles incoming SDP answer (caller side)
-    pub(in crate::app) fn handle_sdp_answer(&mut self, answer: String) {
+    pub(in crate::app) fn handle_sdp_answer(&mut self, from_user_id: String, answer: String) {
         let my_name = self.user_context.get_name().unwrap_or("UNKNOWN");
 
         self.logger.info(&format!(
-            "[SIGNALING] Processing SDP answer for user '{}'",
-            my_name
+            "[SIGNALING] Processing SDP answer for user '{}' from peer '{}'",
+            my_name, from_user_id
         ));
 
         // Process the answer to complete connection
-        let _ = self
-            .logic_cmd_tx
-            .send(LogicCommand::ProcessAnswer { answer_sdp: answer });
+        let _ = self.logic_cmd_tx.send(LogicCommand::ProcessAnswer {
+            peer_id: from_user_id,
+            answer_sdp: answer,
+        });
     }
 
     /// Handles incoming ICE candidate
     pub(in crate::app) fn handle_ice_candidate(
         &mut self,
+        from_user_id: String,
         candidate: String,
         sdp_mid: String,
         sdp_mline_index: u32,
     ) {
         // Send to logic thread to add ICE candidate to webrtc_connection
         let _ = self.logic_cmd_tx.send(LogicCommand::AddIceCandidate {
+            peer_id: from_user_id,
             candidate,
             sdp_mid,
             sdp_mline_index: sdp_mline_index as u16,
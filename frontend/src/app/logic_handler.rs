@@ -10,24 +10,24 @@ impl App {
     /// Updates application state based on background operations
     pub(super) fn handle_logic_event(&mut self, ctx: &egui::Context, event: LogicEvent) {
         match event {
-            LogicEvent::OfferGenerated(sdp) => {
-                self.handle_offer_generated(sdp);
+            LogicEvent::OfferGenerated { peer_id, sdp } => {
+                self.handle_offer_generated(peer_id, sdp);
             }
 
-            LogicEvent::AnswerGenerated(sdp) => {
-                self.handle_answer_generated(sdp);
+            LogicEvent::AnswerGenerated { peer_id, sdp } => {
+                self.handle_answer_generated(peer_id, sdp);
             }
 
-            LogicEvent::ConnectionReady => {
-                self.handle_connection_ready();
+            LogicEvent::ConnectionReady { peer_id } => {
+                self.handle_connection_ready(peer_id);
             }
 
             LogicEvent::LocalFrame(color_image) => {
                 self.handle_local_frame(ctx, color_image);
             }
 
-            LogicEvent::RemoteFrame(color_image) => {
-                self.handle_remote_frame(ctx, color_image);
+            LogicEvent::RemoteFrame { peer_id, image } => {
+                self.handle_remote_frame(ctx, &peer_id, image);
             }
 
             LogicEvent::CameraStarted => {
@@ -38,12 +38,20 @@ impl App {
                 self.handle_camera_stopped();
             }
 
-            LogicEvent::RemoteCameraOn => {
-                self.handle_remote_camera_on();
+            LogicEvent::ScreenShareStarted => {
+                self.handle_screen_share_started();
             }
 
-            LogicEvent::RemoteCameraOff => {
-                self.handle_remote_camera_off();
+            LogicEvent::ScreenShareStopped => {
+                self.handle_screen_share_stopped();
+            }
+
+            LogicEvent::RemoteCameraOn(peer_id) => {
+                self.handle_remote_camera_on(&peer_id);
+            }
+
+            LogicEvent::RemoteCameraOff(peer_id) => {
+                self.handle_remote_camera_off(&peer_id);
             }
 
             LogicEvent::AudioStarted => {
@@ -58,38 +66,54 @@ impl App {
                 self.handle_audio_unmuted();
             }
 
-            LogicEvent::RemoteAudioOn => {
-                self.handle_remote_audio_on();
+            LogicEvent::RemoteAudioOn(peer_id) => {
+                self.handle_remote_audio_on(&peer_id);
+            }
+
+            LogicEvent::RemoteAudioOff(peer_id) => {
+                self.handle_remote_audio_off(&peer_id);
             }
 
-            LogicEvent::RemoteAudioOff => {
-                self.handle_remote_audio_off();
+            LogicEvent::RemoteAudioMuted(peer_id) => {
+                self.handle_remote_audio_muted(&peer_id);
             }
 
-            LogicEvent::RemoteAudioMuted => {
-                self.handle_remote_audio_muted();
+            LogicEvent::RemoteAudioUnmuted(peer_id) => {
+                self.handle_remote_audio_unmuted(&peer_id);
             }
 
-            LogicEvent::RemoteAudioUnmuted => {
-                self.handle_remote_audio_unmuted();
+            LogicEvent::AudioLevel(level) => {
+                self.handle_audio_level(level);
             }
 
-            LogicEvent::RemoteParticipantName(name) => {
-                self.handle_remote_participant_name(name);
+            LogicEvent::RemoteParticipantName { peer_id, name } => {
+                self.handle_remote_participant_name(peer_id, name);
             }
 
-            LogicEvent::ParticipantDisconnected => {
-                self.handle_participant_disconnected();
+            LogicEvent::ParticipantDisconnected(peer_id) => {
+                self.handle_participant_disconnected(&peer_id);
             }
 
-            LogicEvent::OwnerDisconnected => {
-                self.handle_owner_disconnected();
+            LogicEvent::OwnerDisconnected(peer_id) => {
+                self.handle_owner_disconnected(&peer_id);
             }
 
             LogicEvent::StatsUpdated(stats) => {
                 self.handle_stats_updated(stats);
             }
 
+            LogicEvent::IceStateChanged(state) => {
+                self.handle_ice_state_changed(state);
+            }
+
+            LogicEvent::ChatMessageReceived {
+                from,
+                text,
+                timestamp,
+            } => {
+                self.handle_chat_message_received(&from, text, timestamp);
+            }
+
             LogicEvent::Error(msg) => {
                 self.logger.error(&format!("Logic error: {}", msg));
                 self.show_error(format!("Error: {}", msg));
@@ -249,9 +273,30 @@ impl App {
         }
     }
 
-    /// Sends SDP offer/answer via TCP with error handling
+    /// Adds a chat message from a peer to the room chat
+    fn handle_chat_message_received(&mut self, peer_id: &str, text: String, timestamp: u64) {
+        if let Some(room) = self.current_room.as_mut() {
+            let from = room.peer_name(peer_id).unwrap_or(peer_id).to_string();
+            room.chat.push(crate::models::ChatEntry {
+                from,
+                text,
+                timestamp,
+            });
+        }
+    }
+
+    fn handle_ice_state_changed(&mut self, state: webrtc::ConnectionState) {
+        self.logger
+            .info(&format!("[WEBRTC] ICE connection state: {}", state));
+        if let Some(room) = self.current_room.as_mut() {
+            room.ice_state = state;
+        }
+    }
+
+    /// Sends SDP offer/answer to a peer via TCP with error handling
     fn send_sdp_via_tcp(
         &mut self,
+        peer_id: &str,
         sdp: &str,
         sdp_type: &str,
         send_fn: impl FnOnce(&TcpClient, &str, &str, &str, &str) -> Result<(), String>,
@@ -269,19 +314,6 @@ impl App {
             }
         };
 
-        // Get peer_id from context
-        let peer_id = match &self.user_context.peer_user_id {
-            Some(id) => id.as_str(),
-            None => {
-                self.logger.error(&format!(
-                    "[WEBRTC] Cannot send SDP {} - no peer user ID in context",
-                    sdp_type
-                ));
-                self.show_error("Connection error: peer not found".to_string());
-                return false;
-            }
-        };
-
         let Some(ref client) = self.tcp_client else {
             self.logger.error(&format!(
                 "[WEBRTC] Cannot send SDP {} - not connected to server",
@@ -316,18 +348,19 @@ impl App {
         }
     }
 
-    fn handle_offer_generated(&mut self, sdp: String) {
+    fn handle_offer_generated(&mut self, peer_id: String, sdp: String) {
         self.logger.info(&format!(
-            "[WEBRTC] SDP Offer generated - length: {} bytes",
+            "[WEBRTC] SDP Offer generated for peer '{}' - length: {} bytes",
+            peer_id,
             sdp.len()
         ));
 
-        if self.send_sdp_via_tcp(&sdp, "offer", TcpClient::send_sdp_offer) {
+        if self.send_sdp_via_tcp(&peer_id, &sdp, "offer", TcpClient::send_sdp_offer) {
             self.show_success("Offer sent to peer!".to_string());
         }
     }
 
-    fn handle_answer_generated(&mut self, sdp: String) {
+    fn handle_answer_generated(&mut self, peer_id: String, sdp: String) {
         let my_name = self
             .user_context
             .get_name()
@@ -339,7 +372,7 @@ impl App {
             sdp.len()
         ));
 
-        if !self.send_sdp_via_tcp(&sdp, "answer", TcpClient::send_sdp_answer) {
+        if !self.send_sdp_via_tcp(&peer_id, &sdp, "answer", TcpClient::send_sdp_answer) {
             return;
         }
 
@@ -354,10 +387,10 @@ impl App {
             "[WEBRTC] Auto-starting connection for user '{}'",
             my_name
         ));
-        self.auto_start_connection();
+        self.auto_start_connection(peer_id);
     }
 
-    fn auto_start_connection(&self) {
+    fn auto_start_connection(&self, peer_id: String) {
         self.logger.info("[WEBRTC] Auto-start connection initiated");
 
         // Validate prerequisites
@@ -396,6 +429,7 @@ impl App {
 
         // Send start connection command
         if let Err(e) = self.logic_cmd_tx.send(LogicCommand::StartConnection {
+            peer_id,
             participant: participant.clone(),
         }) {
             self.logger.error(&format!(
@@ -405,10 +439,12 @@ impl App {
         }
     }
 
-    fn handle_connection_ready(&mut self) {
+    fn handle_connection_ready(&mut self, peer_id: String) {
         let my_name = self.user_context.get_name().unwrap_or("UNKNOWN");
-        self.logger
-            .info(&format!("[WEBRTC] Connection ready for user '{}'", my_name));
+        self.logger.info(&format!(
+            "[WEBRTC] Connection to peer '{}' ready for user '{}'",
+            peer_id, my_name
+        ));
 
         // If we're already on the Room page (automatic flow), start the connection automatically
         if self.current_page == crate::pages::Page::Room {
@@ -429,6 +465,7 @@ impl App {
                 if let Err(e) =
                     self.logic_cmd_tx
                         .send(crate::events::LogicCommand::StartConnection {
+                            peer_id,
                             participant: participant.clone(),
                         })
                 {
@@ -484,27 +521,42 @@ impl App {
         }
     }
 
-    fn handle_remote_frame(&mut self, ctx: &egui::Context, color_image: egui::ColorImage) {
+    fn handle_remote_frame(
+        &mut self,
+        ctx: &egui::Context,
+        peer_id: &str,
+        color_image: egui::ColorImage,
+    ) {
         // Only update if user is in a room
         if self.user_context.current_room_id.is_none() {
             return;
         }
 
+        // Frames arriving before the participant's name are not shown yet
+        let Some(name) = self
+            .current_room
+            .as_ref()
+            .and_then(|room| room.peer_name(peer_id))
+        else {
+            return;
+        };
+
         let Some(state) = &mut self.current_room_state else {
             return;
         };
 
-        // Update or create remote texture
-        match &mut state.other_texture {
+        // Update or create this participant's remote texture
+        match state.remote_textures.get_mut(name) {
             Some(tex) => {
                 tex.set(color_image, egui::TextureOptions::default());
             }
             None => {
-                state.other_texture = Some(ctx.load_texture(
-                    "remote_frame",
+                let texture = ctx.load_texture(
+                    format!("remote_frame_{}", peer_id),
                     color_image,
                     egui::TextureOptions::default(),
-                ));
+                );
+                state.remote_textures.insert(name.to_string(), texture);
             }
         }
     }
@@ -531,23 +583,63 @@ impl App {
         self.update_local_camera_state(false);
     }
 
-    fn handle_remote_camera_on(&mut self) {
-        self.logger.info("[VIDEO] Remote camera turned ON");
-        self.update_remote_camera_state(true);
+    fn handle_screen_share_started(&mut self) {
+        let user = self.user_context.get_name().unwrap_or("unknown");
+        self.logger
+            .info(&format!("[VIDEO] Screen share started for user '{}'", user));
+        self.show_success("Screen sharing started".to_string());
+
+        if let Some(room) = self.get_current_room_mut() {
+            room.screen_sharing = true;
+        }
     }
 
-    fn handle_remote_camera_off(&mut self) {
-        self.logger.info("[VIDEO] Remote camera turned OFF");
-        self.update_remote_camera_state(false);
+    fn handle_screen_share_stopped(&mut self) {
+        let user = self.user_context.get_name().unwrap_or("unknown");
+        self.logger
+            .info(&format!("[VIDEO] Screen share stopped for user '{}'", user));
+        self.show_warning("Screen sharing stopped".to_string());
 
-        // Clear remote texture
-        if let Some(state) = &mut self.current_room_state {
+        let camera_on = self
+            .get_current_participant_mut()
+            .is_some_and(|p| p.camera_on);
+        if !camera_on && let Some(state) = &mut self.current_room_state {
             state.my_texture = None;
         }
 
+        if let Some(room) = self.get_current_room_mut() {
+            room.screen_sharing = false;
+        }
+    }
+
+    fn handle_remote_camera_on(&mut self, peer_id: &str) {
+        self.logger
+            .info(&format!("[VIDEO] Remote camera of peer '{}' turned ON", peer_id));
+        self.update_remote_camera_state(peer_id, true);
+    }
+
+    fn handle_remote_camera_off(&mut self, peer_id: &str) {
+        self.logger
+            .info(&format!("[VIDEO] Remote camera of peer '{}' turned OFF", peer_id));
+        self.update_remote_camera_state(peer_id, false);
+
+        // Clear remote texture
+        let name = self
+            .current_room
+            .as_ref()
+            .and_then(|room| room.peer_name(peer_id))
+            .map(str::to_string);
+        if let Some(state) = &mut self.current_room_state
+            && let Some(name) = name
+        {
+            state.remote_textures.remove(&name);
+        }
+
         // Flush jitter buffer and decoder to prevent delayed frames from being displayed
         self.logic_cmd_tx
-            .send(crate::events::LogicCommand::ClearVideoBuffers)
+            .send(crate::events::LogicCommand::ClearVideoBuffers {
+                peer_id: peer_id.to_string(),
+            })
             .ok();
     }
 
@@ -569,48 +661,67 @@ impl App {
         self.update_local_audio_state(true, false);
     }
 
-    fn handle_remote_audio_on(&mut self) {
-        self.logger.info("[AUDIO] Remote audio turned ON");
-        self.update_remote_audio_state(true, false);
+    fn handle_audio_level(&mut self, level: f32) {
+        if let Some(room) = self.current_room.as_mut() {
+            room.local_audio_level = level;
+        }
     }
 
-    fn handle_remote_audio_off(&mut self) {
-        self.logger.info("[AUDIO] Remote audio turned OFF");
-        self.update_remote_audio_state(false, false);
+    fn handle_remote_audio_on(&mut self, peer_id: &str) {
+        self.logger
+            .info(&format!("[AUDIO] Remote audio of peer '{}' turned ON", peer_id));
+        self.update_remote_audio_state(peer_id, true, false);
     }
 
-    fn handle_remote_audio_muted(&mut self) {
-        self.logger.info("[AUDIO] Remote audio MUTED");
-        self.update_remote_audio_state(true, true);
+    fn handle_remote_audio_off(&mut self, peer_id: &str) {
+        self.logger
+            .info(&format!("[AUDIO] Remote audio of peer '{}' turned OFF", peer_id));
+        self.update_remote_audio_state(peer_id, false, false);
     }
 
-    fn handle_remote_audio_unmuted(&mut self) {
-        self.logger.info("[AUDIO] Remote audio UNMUTED");
-        self.update_remote_audio_state(true, false);
+    fn handle_remote_audio_muted(&mut self, peer_id: &str) {
+        self.logger
+            .info(&format!("[AUDIO] Remote audio of peer '{}' MUTED", peer_id));
+        self.update_remote_audio_state(peer_id, true, true);
     }
 
-    /// Handles guest participant disconnecting (owner remains in room)
-    fn handle_participant_disconnected(&mut self) {
+    fn handle_remote_audio_unmuted(&mut self, peer_id: &str) {
+        self.logger
+            .info(&format!("[AUDIO] Remote audio of peer '{}' UNMUTED", peer_id));
+        self.update_remote_audio_state(peer_id, true, false);
+    }
+
+    /// Handles a guest disconnecting (the rest of the room stays up)
+    fn handle_participant_disconnected(&mut self, peer_id: &str) {
         let room_id = self
             .user_context
             .current_room_id
             .as_deref()
             .unwrap_or("unknown");
         self.logger.info(&format!(
-            "[ROOM] Participant disconnected from room '{}'",
-            room_id
+            "[ROOM] Participant '{}' disconnected from room '{}'",
+            peer_id, room_id
         ));
-        self.show_warning("Participant left the room".to_string());
-        self.remove_other_participant();
-
-        // Clear remote texture (will show "Waiting for participant...")
-        if let Some(state) = &mut self.current_room_state {
-            state.other_texture = None;
+        // Removing the participant clears its tile (will show "Waiting for participant...")
+        if self.remove_peer(peer_id) {
+            self.show_warning("Participant left the room".to_string());
         }
     }
 
-    /// Handles owner disconnecting (guest gets kicked out)
-    fn handle_owner_disconnected(&mut self) {
+    /// Handles owner disconnecting
+    ///
+    /// In a two-person room the guest gets kicked out; in a mesh room the
+    /// call continues and the owner is removed like any other participant.
+    fn handle_owner_disconnected(&mut self, peer_id: &str) {
+        let remaining_peers = self
+            .current_room
+            .as_ref()
+            .map_or(0, |room| room.peers.len());
+        if remaining_peers > 1 {
+            self.handle_participant_disconnected(peer_id);
+            return;
+        }
+
         let room_id = self
             .user_context
             .current_room_id
@@ -621,10 +732,7 @@ impl App {
             room_id
         ));
         self.show_error("Room owner left. Ending call.".to_string());
-        self.remove_other_participant();
-        if let Some(state) = &mut self.current_room_state {
-            state.other_texture = None;
-        }
+        self.remove_peer(peer_id);
         self.current_room_state = None;
 
         self.logger
@@ -634,40 +742,36 @@ impl App {
             .ok();
         self.user_context.current_room_id = None;
         self.current_page = crate::pages::Page::Lobby;
+        self.warm_up_call_setup();
     }
 
-    /// Handles receiving the remote participant's name via RTP control message
-    fn handle_remote_participant_name(&mut self, name: String) {
+    /// Handles receiving a remote participant's name via RTP control message
+    fn handle_remote_participant_name(&mut self, peer_id: String, name: String) {
         if let Some(room_id) = &self.user_context.current_room_id
             && let Some(room) = self.current_room.as_mut()
         {
             let exists = room.participants.iter().any(|p| p.name == name);
-            if !exists {
-                self.logger.info(&format!(
-                    "[ROOM] Participant '{}' joined room '{}'",
-                    name, room_id
-                ));
-                let _ = room.add_participant(name.clone());
-                self.show_success(format!("Participant '{}' joined", name));
-            } else {
-                self.logger.debug(&format!(
-                    "[ROOM] Participant '{}' already in room '{}'",
-                    name, room_id
-                ));
+            match room.add_remote_participant(peer_id, name.clone()) {
+                Ok(()) if !exists => {
+                    self.logger.info(&format!(
+                        "[ROOM] Participant '{}' joined room '{}'",
+                        name, room_id
+                    ));
+                    self.show_success(format!("Participant '{}' joined", name));
+                }
+                Ok(()) => {
+                    self.logger.debug(&format!(
+                        "[ROOM] Participant '{}' already in room '{}'",
+                        name, room_id
+                    ));
+                }
+                Err(e) => {
+                    self.logger.warn(&format!(
+                        "[ROOM] Cannot add participant '{}' to room '{}': {}",
+                        name, room_id, e
+                    ));
+                }
             }
         }
     }
-
-    /// Removes the other participant from room data (on disconnect)
-    fn remove_other_participant(&mut self) {
-        let username = match self.user_context.get_name() {
-            Some(name) => name.to_string(),
-            None => return,
-        };
-
-        if let Some(room) = self.get_current_room_mut() {
-            // Remove the other participant (not the current user)
-            room.participants.retain(|p| p.name == username);
-        }
-    }
 }
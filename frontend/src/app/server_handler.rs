@@ -59,10 +59,15 @@ impl App {
                 self.handle_call_accepted(call_id, peer_user_id, peer_username);
             }
 
-            ServerMessage::CallDeclined { peer_username } => {
-                self.logger
-                    .info(&format!("[CALL] Call declined by user '{}'", peer_username));
-                self.handle_call_declined(peer_username);
+            ServerMessage::CallDeclined {
+                peer_username,
+                reason,
+            } => {
+                self.logger.info(&format!(
+                    "[CALL] Call declined by user '{}' ({})",
+                    peer_username, reason
+                ));
+                self.handle_call_declined(peer_username, reason);
             }
 
             ServerMessage::Hangup { call_id } => {
@@ -71,6 +76,30 @@ impl App {
                 self.handle_hangup(call_id);
             }
 
+            ServerMessage::ParticipantJoined {
+                call_id,
+                user_id,
+                username,
+            } => {
+                self.logger.info(&format!(
+                    "[CALL] Participant joined - call_id: {}, user: {} (id: {})",
+                    call_id, username, user_id
+                ));
+                self.handle_participant_joined(call_id, user_id, username);
+            }
+
+            ServerMessage::ParticipantLeft {
+                call_id,
+                user_id,
+                username,
+            } => {
+                self.logger.info(&format!(
+                    "[CALL] Participant left - call_id: {}, user: {} (id: {})",
+                    call_id, username, user_id
+                ));
+                self.handle_participant_left(user_id, username);
+            }
+
             // WebRTC signaling (dispatched to signaling_handlers)
             ServerMessage::SdpOffer {
                 call_id,
@@ -81,7 +110,7 @@ impl App {
                     "[SIGNALING] SDP Offer received - call_id: {}, from_user_id: {}, sdp_length: {}",
                     call_id, from_user_id, sdp.len()
                 ));
-                self.handle_sdp_offer(call_id, sdp);
+                self.handle_sdp_offer(call_id, from_user_id, sdp);
             }
 
             ServerMessage::SdpAnswer {
@@ -93,19 +122,20 @@ impl App {
                     "[SIGNALING] SDP Answer received - call_id: {}, from_user_id: {}, sdp_length: {}",
                     call_id, from_user_id, sdp.len()
                 ));
-                self.handle_sdp_answer(sdp);
+                self.handle_sdp_answer(from_user_id, sdp);
             }
 
             ServerMessage::IceCandidate {
+                from_user_id,
                 candidate,
                 sdp_mid,
                 sdp_mline_index,
             } => {
                 self.logger.info(&format!(
-                    "[SIGNALING] ICE Candidate received - mid: {}, mline_index: {}, candidate_length: {}",
-                    sdp_mid, sdp_mline_index, candidate.len()
+                    "[SIGNALING] ICE Candidate received - from_user_id: {}, mid: {}, mline_index: {}, candidate_length: {}",
+                    from_user_id, sdp_mid, sdp_mline_index, candidate.len()
                 ));
-                self.handle_ice_candidate(candidate, sdp_mid, sdp_mline_index);
+                self.handle_ice_candidate(from_user_id, candidate, sdp_mid, sdp_mline_index);
             }
 
             // Error handling
@@ -114,6 +144,24 @@ impl App {
                 self.show_error(format!("Server error: {}", message));
             }
 
+            ServerMessage::ServerShutdown { reason } => {
+                self.logger
+                    .warn(&format!("[SERVER] Shutdown notice: {}", reason));
+                self.handle_server_shutdown(reason);
+            }
+
+            ServerMessage::SessionResumed {
+                success,
+                call_id,
+                error,
+            } => {
+                self.logger.info(&format!(
+                    "[SERVER] Session resume response: success={}, call_id: {:?}",
+                    success, call_id
+                ));
+                self.handle_session_resumed(success, error);
+            }
+
             // Authentication responses
             ServerMessage::LoginResponse {
                 success,
@@ -259,12 +259,20 @@ impl App {
             let room_data = self.current_room.as_ref();
 
             // Get textures from current room state
-            let (my_texture, other_texture) = match &self.current_room_state {
-                Some(state) => (state.my_texture.as_ref(), state.other_texture.as_ref()),
+            let (my_texture, remote_textures) = match &self.current_room_state {
+                Some(state) => (state.my_texture.as_ref(), Some(&state.remote_textures)),
                 None => (None, None),
             };
 
-            ui_command = Room::show(ui, user_name, room_id, room_data, my_texture, other_texture);
+            ui_command = Room::show(
+                ui,
+                user_name,
+                room_id,
+                room_data,
+                my_texture,
+                remote_textures,
+                &self.lobby.users,
+            );
         });
 
         // Render file send dialog if open
@@ -654,15 +662,10 @@ impl App {
         }
     }
 
-    /// Updates camera state for remote participant (the other person in the room)
-    pub(super) fn update_remote_camera_state(&mut self, camera_on: bool) {
-        let username = match self.user_context.get_name() {
-            Some(name) => name.to_string(),
-            None => return,
-        };
-
+    /// Updates camera state for the remote participant behind a peer connection
+    pub(super) fn update_remote_camera_state(&mut self, peer_id: &str, camera_on: bool) {
         if let Some(room) = self.get_current_room_mut()
-            && let Some(participant) = room.participants.iter_mut().find(|p| p.name != username)
+            && let Some(participant) = room.get_peer_participant_mut(peer_id)
         {
             participant.camera_on = camera_on;
         }
@@ -676,18 +679,40 @@ impl App {
         }
     }
 
-    /// Updates audio state for remote participant (the other person in the room)
-    pub(super) fn update_remote_audio_state(&mut self, audio_on: bool, audio_muted: bool) {
-        let username = match self.user_context.get_name() {
-            Some(name) => name.to_string(),
-            None => return,
-        };
-
+    /// Updates audio state for the remote participant behind a peer connection
+    pub(super) fn update_remote_audio_state(
+        &mut self,
+        peer_id: &str,
+        audio_on: bool,
+        audio_muted: bool,
+    ) {
         if let Some(room) = self.get_current_room_mut()
-            && let Some(participant) = room.participants.iter_mut().find(|p| p.name != username)
+            && let Some(participant) = room.get_peer_participant_mut(peer_id)
         {
             participant.audio_on = audio_on;
             participant.audio_muted = audio_muted;
         }
     }
+
+    /// Removes a remote participant and closes its peer connection
+    ///
+    /// Returns true if the peer was part of the current room.
+    pub(super) fn remove_peer(&mut self, peer_id: &str) -> bool {
+        let _ = self.logic_cmd_tx.send(crate::events::LogicCommand::ClosePeer {
+            peer_id: peer_id.to_string(),
+        });
+
+        let Some(name) = self
+            .current_room
+            .as_mut()
+            .and_then(|room| room.remove_peer(peer_id))
+        else {
+            return false;
+        };
+
+        if let Some(state) = &mut self.current_room_state {
+            state.remote_textures.remove(&name);
+        }
+        true
+    }
 }
@@ -4,7 +4,7 @@
 //! Implementation details are split into domain-specific modules:
 //! - room_handlers: Room creation, joining, exit
 //! - webrtc_handlers: SDP offer/answer, connection setup
-//! - camera_handlers: Camera toggling and settings
+//! - camera_handlers: Camera and screen share toggling, settings
 
 use super::state::App;
 use crate::events::{LogicCommand, UiCommand};
@@ -35,6 +35,7 @@ impl App {
 
             // Camera operations
             UiCommand::ToggleCamera => self.handle_toggle_camera(),
+            UiCommand::ToggleScreenShare => self.handle_toggle_screen_share(),
             UiCommand::ToggleMute => self.handle_toggle_mute(),
             UiCommand::UpdateCameraSettings(device_id, fps) => {
                 self.handle_update_camera_settings(device_id, fps)
@@ -56,6 +57,9 @@ impl App {
             UiCommand::CancelFileTransfer { transfer_id } => {
                 self.handle_cancel_file_transfer(transfer_id)
             }
+
+            // Chat
+            UiCommand::SendChatMessage(text) => self.handle_send_chat_message(text),
         }
     }
 
@@ -165,4 +169,34 @@ impl App {
         self.logger
             .debug("[FILE] Transfer state cleared after cancel");
     }
+
+    /// Sends a chat message and shows it in the local chat
+    fn handle_send_chat_message(&mut self, text: String) {
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return;
+        }
+        if text.len() > webrtc::MAX_CHAT_MESSAGE_BYTES {
+            self.show_error(format!(
+                "Message too long (max {} bytes)",
+                webrtc::MAX_CHAT_MESSAGE_BYTES
+            ));
+            return;
+        }
+
+        let from = self.user_context.get_name().unwrap_or_default().to_string();
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+        if let Some(room) = self.current_room.as_mut() {
+            room.chat.push(crate::models::ChatEntry {
+                from,
+                text: text.clone(),
+                timestamp,
+            });
+        }
+
+        let _ = self.logic_cmd_tx.send(LogicCommand::SendChat { text });
+    }
 }
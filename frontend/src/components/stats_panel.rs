@@ -1,6 +1,7 @@
 //! RTCP Statistics Display Panel
 //! Displays real-time call quality metrics:
 //! - Bitrate (Mbps)
+//! - Estimated bandwidth (Mbps)
 //! - Packet Loss (%)
 //! - Jitter (ms)
 //! - RTT (ms)
@@ -13,6 +14,7 @@ use egui::{Color32, RichText, Ui};
 #[derive(Clone, PartialEq, Default, Debug)]
 pub struct CallStats {
     pub bitrate_mbps: f64,
+    pub estimated_bandwidth_mbps: f64,
     pub packet_loss_percent: f64,
     pub jitter_ms: f64,
     pub rtt_ms: f64,
@@ -114,6 +116,15 @@ pub fn render_stats_panel(ui: &mut Ui, stats: &CallStats, visible: bool) {
 
                 ui.add_space(5.0);
 
+                render_stat_row(
+                    ui,
+                    "Est. Bandwidth",
+                    &format!("{:.2} Mbps", stats.estimated_bandwidth_mbps),
+                    bitrate_quality(stats.estimated_bandwidth_mbps),
+                );
+
+                ui.add_space(5.0);
+
                 render_stat_row(
                     ui,
                     "Packet Loss",
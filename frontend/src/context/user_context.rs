@@ -8,7 +8,6 @@ pub struct UserContext {
     pub name: Option<String>,
     pub user_id: Option<String>,
     pub current_room_id: Option<String>,
-    pub peer_user_id: Option<String>,
     pub outgoing_call_to: Option<String>,
 }
 
@@ -19,7 +18,6 @@ impl UserContext {
             name: None,
             user_id: None,
             current_room_id: None,
-            peer_user_id: None,
             outgoing_call_to: None,
         }
     }
@@ -49,7 +47,6 @@ impl UserContext {
         self.name = None;
         self.user_id = None;
         self.current_room_id = None;
-        self.peer_user_id = None;
     }
 }
 
@@ -2,24 +2,33 @@ use crate::models::Participant;
 use std::path::PathBuf;
 
 /// Logic commands sent from UI thread to Logic thread
-/// WebRTC connections are managed internally by the Logic thread
+/// WebRTC connections are managed internally by the Logic thread,
+/// one per remote peer (identified by the peer's user ID)
 #[derive(Debug)]
 pub enum LogicCommand {
     /// Generate a WebRTC offer (creates new connection in logic thread)
-    GenerateOffer,
+    GenerateOffer {
+        peer_id: String,
+    },
 
     /// Generate a WebRTC answer from remote offer (creates new connection in logic thread)
     GenerateAnswer {
+        peer_id: String,
         offer_sdp: String,
     },
 
+    /// Gather ICE candidates ahead of a call so its offer/answer is built instantly
+    PregatherCandidates,
+
     /// Process remote answer to complete connection setup
     ProcessAnswer {
+        peer_id: String,
         answer_sdp: String,
     },
 
     /// Add remote ICE candidate to WebRTC connection
     AddIceCandidate {
+        peer_id: String,
         candidate: String,
         sdp_mid: String,
         sdp_mline_index: u16,
@@ -28,19 +37,36 @@ pub enum LogicCommand {
     // --- Room ---
     /// Start WebRTC connection with media threads (uses connection from logic thread)
     StartConnection {
+        peer_id: String,
         participant: Participant,
     },
+    /// Close the connection to a single peer (the rest of the room stays up)
+    ClosePeer {
+        peer_id: String,
+    },
     StartCamera {
         device_id: i32,
         fps: f64,
     },
     StopCamera,
+    /// Move the running camera to another device without restarting the video
+    SwitchCamera {
+        device_id: i32,
+    },
+    /// Share a display instead of (or while) the camera is off
+    StartScreenShare {
+        display_id: i32,
+        fps: f64,
+    },
+    StopScreenShare,
     StartAudio {
         sample_rate: u32,
         channels: u32,
     },
     ToggleMute,
-    ClearVideoBuffers,
+    ClearVideoBuffers {
+        peer_id: String,
+    },
     SendDisconnect {
         is_owner: bool,
     },
@@ -65,4 +91,10 @@ pub enum LogicCommand {
     CancelFileTransfer {
         transfer_id: u64,
     },
+
+    // --- Chat ---
+    /// Send a chat message to every peer
+    SendChat {
+        text: String,
+    },
 }
@@ -1,36 +1,61 @@
 use crate::components::CallStats;
 use egui::ColorImage;
 use std::path::PathBuf;
+use webrtc::ConnectionState;
 
 /// Events generated by the Logic Thread (Logic -> Controller)
 /// These are "results" of completed work.
 /// Note: WebRTC connections are managed internally by Logic Thread,
-/// not sent back to UI Thread.
+/// not sent back to UI Thread. Events about a remote participant carry
+/// the `peer_id` (user ID) of the connection they came from.
 #[derive(Debug)]
 pub enum LogicEvent {
     // --- RoomSetup ---
-    OfferGenerated(String),  // SDP only, connection stays in Logic Thread
-    AnswerGenerated(String), // SDP only, connection stays in Logic Thread
-    ConnectionReady,         // Signals connection is ready, actual connection in Logic Thread
+    /// SDP only, connection stays in Logic Thread
+    OfferGenerated {
+        peer_id: String,
+        sdp: String,
+    },
+    /// SDP only, connection stays in Logic Thread
+    AnswerGenerated {
+        peer_id: String,
+        sdp: String,
+    },
+    /// Signals connection is ready, actual connection in Logic Thread
+    ConnectionReady {
+        peer_id: String,
+    },
 
     // --- Room ---
     LocalFrame(ColorImage),
-    RemoteFrame(ColorImage),
+    RemoteFrame {
+        peer_id: String,
+        image: ColorImage,
+    },
     CameraStarted,
     CameraStopped,
-    RemoteCameraOn,
-    RemoteCameraOff,
+    ScreenShareStarted,
+    ScreenShareStopped,
+    RemoteCameraOn(String),
+    RemoteCameraOff(String),
     AudioStarted,
     AudioMuted,
     AudioUnmuted,
-    RemoteAudioOn,
-    RemoteAudioOff,
-    RemoteAudioMuted,
-    RemoteAudioUnmuted,
-    RemoteParticipantName(String),
-    ParticipantDisconnected,
-    OwnerDisconnected,
+    RemoteAudioOn(String),
+    RemoteAudioOff(String),
+    RemoteAudioMuted(String),
+    RemoteAudioUnmuted(String),
+    /// Local microphone level (0.0 to 1.0), sent periodically while in a call
+    AudioLevel(f32),
+    RemoteParticipantName {
+        peer_id: String,
+        name: String,
+    },
+    ParticipantDisconnected(String),
+    OwnerDisconnected(String),
     StatsUpdated(CallStats), // Real-time statistics from WebRTC connection
+    /// ICE connection state of the media path changed
+    IceStateChanged(ConnectionState),
     Error(String),
 
     // --- File Transfer ---
@@ -61,4 +86,13 @@ pub enum LogicEvent {
         transfer_id: u64,
         reason: String,
     },
+
+    // --- Chat ---
+    /// Chat message from the peer `from` (user ID), sent at `timestamp`
+    /// (milliseconds since the Unix epoch)
+    ChatMessageReceived {
+        from: String,
+        text: String,
+        timestamp: u64,
+    },
 }
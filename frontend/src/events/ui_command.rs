@@ -26,6 +26,7 @@ pub enum UiCommand {
 
     // --- Room ---
     ToggleCamera,
+    ToggleScreenShare,
     ToggleMute,
     UpdateCameraSettings(i32, f64), // device_id, fps
     ExitRoom,
@@ -47,4 +48,8 @@ pub enum UiCommand {
     CancelFileTransfer {
         transfer_id: u64,
     },
+
+    // --- Chat ---
+    /// Send a chat message to the room
+    SendChatMessage(String),
 }
@@ -1,13 +1,25 @@
 use crate::models::protocol::{ServerMessage, UserInfo};
 use logging::Logger;
-use std::io::{Read, Write};
+use std::io::{ErrorKind, Read, Write};
 use std::net::TcpStream;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Minimum wait between attempts to resume a dropped session
+const RESUME_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often an idle logged-in client tells the server it is still alive
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
 
 /// TCP client for persistent connection to server
 pub struct TcpClient {
+    addr: String,
     stream: Arc<Mutex<Option<native_tls::TlsStream<TcpStream>>>>,
     pending_messages: Arc<Mutex<Vec<ServerMessage>>>,
+    /// Token from the login response, presented to resume after the connection drops
+    session_token: Arc<Mutex<Option<String>>>,
+    last_resume_attempt: Arc<Mutex<Option<Instant>>>,
+    last_heartbeat: Arc<Mutex<Instant>>,
     logger: Logger,
 }
 
@@ -24,8 +36,12 @@ impl TcpClient {
         // Keep stream in blocking mode for reliable message reading
 
         Ok(TcpClient {
+            addr: addr.to_string(),
             stream: Arc::new(Mutex::new(Some(tls_stream))),
             pending_messages: Arc::new(Mutex::new(Vec::new())),
+            session_token: Arc::new(Mutex::new(None)),
+            last_resume_attempt: Arc::new(Mutex::new(None)),
+            last_heartbeat: Arc::new(Mutex::new(Instant::now())),
             logger: logger.clone(),
         })
     }
@@ -151,29 +167,62 @@ impl TcpClient {
 
     /// Poll for incoming messages (non-blocking)
     /// Reads from the stream and returns any complete messages
+    ///
+    /// If the connection dropped after login, reconnects and asks the server
+    /// to resume the session; the outcome arrives as `SessionResumed`.
+    /// While logged in, also sends a heartbeat every `HEARTBEAT_INTERVAL`.
     pub fn poll_messages(&self) -> Vec<ServerMessage> {
-        let mut stream_lock = match self.stream.lock() {
+        if self.read_available_messages() {
+            self.send_heartbeat_if_due();
+        } else {
+            self.try_resume();
+        }
+
+        let mut pending = match self.pending_messages.lock() {
             Ok(lock) => lock,
             Err(_) => return Vec::new(),
         };
 
+        std::mem::take(&mut *pending)
+    }
+
+    /// Read every complete message currently available into `pending_messages`
+    ///
+    /// Returns false if there is no connection or it was lost while reading.
+    fn read_available_messages(&self) -> bool {
+        let mut stream_lock = match self.stream.lock() {
+            Ok(lock) => lock,
+            Err(_) => return true,
+        };
+
         let stream = match stream_lock.as_mut() {
             Some(s) => s,
-            None => return Vec::new(),
+            None => return false,
         };
 
         // Set stream to non-blocking temporarily to check if data is available
         if stream.get_ref().set_nonblocking(true).is_err() {
-            return Vec::new();
+            return true;
         }
 
+        let mut connected = true;
+
         // Try to read messages from the stream
         loop {
             // Peek to see if data is available
             let mut peek_buf = [0u8; 1];
             match stream.get_ref().peek(&mut peek_buf) {
-                Ok(0) | Err(_) => {
-                    // No data available or error, stop reading
+                Ok(0) => {
+                    self.logger.warn("[TCP] Connection closed by server");
+                    connected = false;
+                    break;
+                }
+                Err(e) => {
+                    // No data available yet, or the connection failed
+                    if e.kind() != ErrorKind::WouldBlock {
+                        self.logger.warn(&format!("[TCP] Connection lost: {}", e));
+                        connected = false;
+                    }
                     break;
                 }
                 Ok(_) => {
@@ -189,6 +238,7 @@ impl TcpClient {
             if let Err(e) = stream.read_exact(&mut len_buf) {
                 self.logger
                     .error(&format!("[TCP] Failed to read message length: {}", e));
+                connected = false;
                 break;
             }
 
@@ -204,6 +254,7 @@ impl TcpClient {
             if let Err(e) = stream.read_exact(&mut type_buf) {
                 self.logger
                     .error(&format!("[TCP] Failed to read message type: {}", e));
+                connected = false;
                 break;
             }
             let msg_type = type_buf[0];
@@ -213,15 +264,18 @@ impl TcpClient {
             if let Err(e) = stream.read_exact(&mut payload) {
                 self.logger
                     .error(&format!("[TCP] Failed to read message payload: {}", e));
+                connected = false;
                 break;
             }
 
             // Parse JSON
-            if let Ok(json) = String::from_utf8(payload)
-                && let Some(server_msg) = parse_server_message(msg_type, &json)
-                && let Ok(mut pending) = self.pending_messages.lock()
-            {
-                pending.push(server_msg);
+            if let Ok(json) = String::from_utf8(payload) {
+                self.track_session(msg_type, &json);
+                if let Some(server_msg) = parse_server_message(msg_type, &json)
+                    && let Ok(mut pending) = self.pending_messages.lock()
+                {
+                    pending.push(server_msg);
+                }
             }
 
             // Set back to non-blocking to check for more messages
@@ -230,15 +284,97 @@ impl TcpClient {
             }
         }
 
-        // Ensure stream is left in blocking mode for writes
-        let _ = stream.get_ref().set_nonblocking(false);
+        if connected {
+            // Ensure stream is left in blocking mode for writes
+            let _ = stream.get_ref().set_nonblocking(false);
+        } else {
+            *stream_lock = None;
+        }
+        connected
+    }
 
-        let mut pending = match self.pending_messages.lock() {
-            Ok(lock) => lock,
-            Err(_) => return Vec::new(),
+    /// Keep the session token from a login response; a shutdown notice ends the session
+    fn track_session(&self, msg_type: u8, json: &str) {
+        let token = match msg_type {
+            0x02 => extract_string(json, "session_token"),
+            0x17 => None,
+            _ => return,
         };
+        if let Ok(mut session_token) = self.session_token.lock() {
+            *session_token = token;
+        }
+    }
 
-        std::mem::take(&mut *pending)
+    /// Send a heartbeat so the server does not drop an idle session as dead
+    fn send_heartbeat_if_due(&self) {
+        let logged_in = self
+            .session_token
+            .lock()
+            .map(|token| token.is_some())
+            .unwrap_or(false);
+        if !logged_in {
+            return;
+        }
+
+        if let Ok(mut last_heartbeat) = self.last_heartbeat.lock() {
+            if last_heartbeat.elapsed() < HEARTBEAT_INTERVAL {
+                return;
+            }
+            *last_heartbeat = Instant::now();
+        }
+
+        if let Err(e) = self.send_message(0x11, "{}") {
+            self.logger
+                .warn(&format!("[TCP] Failed to send heartbeat: {}", e));
+        }
+    }
+
+    /// Reconnect and send a resume request for the current session
+    ///
+    /// Does nothing before login or after `disconnect`. Attempts are spaced
+    /// by `RESUME_RETRY_INTERVAL` so an unreachable server does not stall polling.
+    fn try_resume(&self) {
+        let token = match self.session_token.lock() {
+            Ok(token) => match token.clone() {
+                Some(token) => token,
+                None => return,
+            },
+            Err(_) => return,
+        };
+
+        if let Ok(mut last_attempt) = self.last_resume_attempt.lock() {
+            if last_attempt.is_some_and(|at| at.elapsed() < RESUME_RETRY_INTERVAL) {
+                return;
+            }
+            *last_attempt = Some(Instant::now());
+        }
+
+        self.logger.info(&format!(
+            "[TCP] Reconnecting to {} to resume session",
+            self.addr
+        ));
+        let tls_stream = match super::tls_client::connect_tls(&self.addr) {
+            Ok(stream) => stream,
+            Err(e) => {
+                self.logger.warn(&format!(
+                    "[TCP] Failed to reconnect to {}: {}",
+                    self.addr, e
+                ));
+                return;
+            }
+        };
+
+        if let Ok(mut stream) = self.stream.lock() {
+            *stream = Some(tls_stream);
+        }
+        let message = format!(r#"{{"session_token":"{}"}}"#, token);
+        if let Err(e) = self.send_message(0x18, &message) {
+            self.logger
+                .warn(&format!("[TCP] Failed to send resume request: {}", e));
+            if let Ok(mut stream) = self.stream.lock() {
+                *stream = None;
+            }
+        }
     }
 
     /// Send a message with the protocol format: [length][type][payload]
@@ -276,6 +412,9 @@ impl TcpClient {
 
     /// Disconnect from server
     pub fn disconnect(&mut self) {
+        if let Ok(mut session_token) = self.session_token.lock() {
+            *session_token = None;
+        }
         if let Ok(mut stream) = self.stream.lock() {
             *stream = None;
         }
@@ -304,6 +443,10 @@ fn parse_server_message(msg_type: u8, json: &str) -> Option<ServerMessage> {
         0x0F => parse_ice_candidate(json),
         0x10 => parse_hangup(json),
         0x12 => parse_error(json),
+        0x15 => parse_participant_joined(json),
+        0x16 => parse_participant_left(json),
+        0x17 => parse_server_shutdown(json),
+        0x19 => parse_resume_response(json),
         _ => None,
     }
 }
@@ -403,6 +546,7 @@ fn parse_call_accepted(json: &str) -> Option<ServerMessage> {
 fn parse_call_declined(json: &str) -> Option<ServerMessage> {
     Some(ServerMessage::CallDeclined {
         peer_username: extract_string(json, "peer_username")?,
+        reason: extract_string(json, "reason").unwrap_or_else(|| "Declined".to_string()),
     })
 }
 
@@ -430,6 +574,7 @@ fn parse_ice_candidate(json: &str) -> Option<ServerMessage> {
     let sdp_mline_index = extract_number(json, "sdp_mline_index").unwrap_or(0);
 
     Some(ServerMessage::IceCandidate {
+        from_user_id: extract_string(json, "from_user_id")?,
         candidate: extract_string(json, "candidate")?,
         sdp_mid: extract_string(json, "sdp_mid")?,
         sdp_mline_index,
@@ -442,12 +587,47 @@ fn parse_hangup(json: &str) -> Option<ServerMessage> {
     })
 }
 
+fn parse_participant_joined(json: &str) -> Option<ServerMessage> {
+    Some(ServerMessage::ParticipantJoined {
+        call_id: extract_string(json, "call_id")?,
+        user_id: extract_string(json, "user_id")?,
+        username: extract_string(json, "username")?,
+    })
+}
+
+fn parse_participant_left(json: &str) -> Option<ServerMessage> {
+    Some(ServerMessage::ParticipantLeft {
+        call_id: extract_string(json, "call_id")?,
+        user_id: extract_string(json, "user_id")?,
+        username: extract_string(json, "username")?,
+    })
+}
+
 fn parse_error(json: &str) -> Option<ServerMessage> {
     let message = extract_string(json, "message").unwrap_or_else(|| "Unknown error".to_string());
 
     Some(ServerMessage::Error { message })
 }
 
+fn parse_server_shutdown(json: &str) -> Option<ServerMessage> {
+    let reason =
+        extract_string(json, "reason").unwrap_or_else(|| "Server is shutting down".to_string());
+
+    Some(ServerMessage::ServerShutdown { reason })
+}
+
+fn parse_resume_response(json: &str) -> Option<ServerMessage> {
+    let success = json.contains("\"success\":true");
+    let call_id = extract_string(json, "call_id");
+    let error = extract_string(json, "error");
+
+    Some(ServerMessage::SessionResumed {
+        success,
+        call_id,
+        error,
+    })
+}
+
 /// Extract string value from JSON
 fn extract_string(json: &str, key: &str) -> Option<String> {
     let pattern = format!("\"{}\":\"", key);
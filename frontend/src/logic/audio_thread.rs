@@ -2,21 +2,37 @@
 //!
 //! This module handles continuous audio capture in a dedicated thread.
 //! Audio frames are captured at a fixed interval (20ms Opus frames) and sent to the encoder.
+//! The microphone level is reported to the UI for the speaking indicator.
 
+use crate::events::LogicEvent;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use webrtc::WebRtcConnection;
 
 // Opus frame duration: 20ms is optimal for WebRTC
 const AUDIO_FRAME_DURATION: Duration = Duration::from_millis(20);
+// Frames per level report (100ms), the peak level of the window is reported
+const LEVEL_REPORT_FRAMES: u32 = 5;
 
 /// Runs the audio capture loop in a dedicated thread.
 ///
 /// The thread continuously checks if the microphone is running and captures audio frames
 /// when available. Frames are sent directly through WebRTC (no UI display needed).
 /// Maintains accurate 20ms timing for Opus encoding.
-pub fn run_audio_thread(webrtc_arc: Arc<Mutex<WebRtcConnection>>) {
-    loop {
+/// Sends `AudioLevel` every `LEVEL_REPORT_FRAMES` frames, and 0.0 once the microphone stops.
+/// Exits once `running` is cleared.
+pub fn run_audio_thread(
+    webrtc_arc: Arc<Mutex<WebRtcConnection>>,
+    evt_tx: Sender<LogicEvent>,
+    running: Arc<AtomicBool>,
+) {
+    let mut peak_level = 0.0f32;
+    let mut frames_since_report = 0;
+    let mut reported_level = 0.0f32;
+
+    while running.load(Ordering::Relaxed) {
         let frame_start = Instant::now();
 
         // Capture and send audio frame
@@ -29,19 +45,34 @@ pub fn run_audio_thread(webrtc_arc: Arc<Mutex<WebRtcConnection>>) {
             if !conn.is_microphone_running() {
                 None
             } else {
-                Some(conn.capture_audio_and_send())
+                Some(conn.capture_audio_and_send().map(|_| conn.audio_level()))
             }
         };
 
         let Some(result) = capture_result else {
-            // Microphone is off - sleep and retry
+            // Microphone is off - clear the indicator, sleep and retry
+            if reported_level > 0.0 {
+                let _ = evt_tx.send(LogicEvent::AudioLevel(0.0));
+                reported_level = 0.0;
+            }
+            peak_level = 0.0;
+            frames_since_report = 0;
             std::thread::sleep(Duration::from_millis(100));
             continue;
         };
 
         match result {
-            Ok(_) => {
+            Ok(level) => {
                 // Successfully captured and sent audio
+                peak_level = peak_level.max(level);
+                frames_since_report += 1;
+                if frames_since_report >= LEVEL_REPORT_FRAMES {
+                    let _ = evt_tx.send(LogicEvent::AudioLevel(peak_level));
+                    reported_level = peak_level;
+                    peak_level = 0.0;
+                    frames_since_report = 0;
+                }
+
                 let elapsed = frame_start.elapsed();
                 if elapsed < AUDIO_FRAME_DURATION {
                     std::thread::sleep(AUDIO_FRAME_DURATION - elapsed);
@@ -1,10 +1,12 @@
 //! Camera Capture Thread
 //!
-//! This module handles continuous camera frame capture in a dedicated thread.
+//! This module handles continuous camera (or shared screen) frame capture in a
+//! dedicated thread.
 //! Frames are captured at a fixed interval and sent to both the encoder and UI.
 
 use crate::events::LogicEvent;
 use crate::logic::utils::rgb_to_color_image;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -19,8 +21,13 @@ const FRAME_DURATION: Duration = Duration::from_millis((1000.0 / TARGET_FPS) as
 /// The thread continuously checks if the camera is running and captures frames
 /// when available. Frames are sent through the event channel for UI display.
 /// Maintains accurate 30 FPS by compensating for capture time.
-pub fn run_camera_thread(webrtc_arc: Arc<Mutex<WebRtcConnection>>, evt_tx: Sender<LogicEvent>) {
-    loop {
+/// Exits once `running` is cleared.
+pub fn run_camera_thread(
+    webrtc_arc: Arc<Mutex<WebRtcConnection>>,
+    evt_tx: Sender<LogicEvent>,
+    running: Arc<AtomicBool>,
+) {
+    while running.load(Ordering::Relaxed) {
         let frame_start = Instant::now();
 
         // Capture, send, and get RGB for preview in one operation
@@ -30,7 +37,7 @@ pub fn run_camera_thread(webrtc_arc: Arc<Mutex<WebRtcConnection>>, evt_tx: Sende
                 continue;
             };
 
-            if !conn.is_camera_running() {
+            if !conn.is_camera_running() && !conn.is_screen_sharing() {
                 None
             } else {
                 Some(conn.capture_and_send())
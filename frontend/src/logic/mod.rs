@@ -11,10 +11,17 @@ use crate::events::{LogicCommand, LogicEvent};
 use audio_thread::run_audio_thread;
 use camera_thread::run_camera_thread;
 use receive_thread::run_receive_thread;
-use state::LogicState;
+use state::{LogicState, PeerConnection};
+use std::sync::atomic::AtomicBool;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
-use webrtc_handler::{handle_generate_answer, handle_generate_offer, handle_process_answer};
+use webrtc_handler::{
+    handle_generate_answer, handle_generate_offer, handle_pregather, handle_process_answer,
+};
+
+/// Microphone settings used when a peer joins while audio is already on
+const AUDIO_SAMPLE_RATE: u32 = 48000;
+const AUDIO_CHANNELS: u32 = 2;
 
 /// Main function of the logic thread.
 /// Receives `LogicCommand`s and sends `LogicEvent`s back to the UI thread.
@@ -28,36 +35,66 @@ pub fn run_logic_thread(
     // Main loop: blocking wait for commands
     for command in cmd_rx {
         match command {
-            LogicCommand::GenerateOffer => {
-                handle_generate_offer(&mut state, &evt_tx);
+            LogicCommand::GenerateOffer { peer_id } => {
+                handle_generate_offer(&mut state, peer_id, &evt_tx);
+            }
+
+            LogicCommand::GenerateAnswer { peer_id, offer_sdp } => {
+                handle_generate_answer(&mut state, peer_id, offer_sdp, &evt_tx);
             }
 
-            LogicCommand::GenerateAnswer { offer_sdp } => {
-                handle_generate_answer(&mut state, offer_sdp, &evt_tx);
+            LogicCommand::PregatherCandidates => {
+                handle_pregather(&mut state);
             }
 
-            LogicCommand::ProcessAnswer { answer_sdp } => {
-                handle_process_answer(&mut state, answer_sdp, &evt_tx);
+            LogicCommand::ProcessAnswer {
+                peer_id,
+                answer_sdp,
+            } => {
+                handle_process_answer(&mut state, peer_id, answer_sdp, &evt_tx);
             }
 
             LogicCommand::AddIceCandidate {
+                peer_id,
                 candidate,
                 sdp_mid,
                 sdp_mline_index,
             } => {
-                handle_add_ice_candidate(&mut state, candidate, sdp_mid, sdp_mline_index, &evt_tx);
+                handle_add_ice_candidate(
+                    &mut state,
+                    &peer_id,
+                    candidate,
+                    sdp_mid,
+                    sdp_mline_index,
+                    &evt_tx,
+                );
             }
 
-            LogicCommand::StartConnection { participant } => {
-                if let Some(conn) = state.pending_connection.take() {
-                    handle_start_connection(conn, participant, &mut state, &evt_tx, logger.clone());
+            LogicCommand::StartConnection {
+                peer_id,
+                participant,
+            } => {
+                if let Some(conn) = state.pending_connections.remove(&peer_id) {
+                    handle_start_connection(
+                        peer_id,
+                        conn,
+                        participant,
+                        &mut state,
+                        &evt_tx,
+                        logger.clone(),
+                    );
                 } else {
-                    let _ = evt_tx.send(LogicEvent::Error(
-                        "No pending connection to start".to_string(),
-                    ));
+                    let _ = evt_tx.send(LogicEvent::Error(format!(
+                        "No pending connection to start for peer '{}'",
+                        peer_id
+                    )));
                 }
             }
 
+            LogicCommand::ClosePeer { peer_id } => {
+                state.close_peer(&peer_id);
+            }
+
             LogicCommand::StartCamera { device_id, fps } => {
                 handle_start_camera(device_id, fps, &state, &evt_tx);
             }
@@ -66,6 +103,18 @@ pub fn run_logic_thread(
                 handle_stop_camera(&state, &evt_tx);
             }
 
+            LogicCommand::SwitchCamera { device_id } => {
+                handle_switch_camera(device_id, &state, &evt_tx);
+            }
+
+            LogicCommand::StartScreenShare { display_id, fps } => {
+                handle_start_screen_share(display_id, fps, &state, &evt_tx);
+            }
+
+            LogicCommand::StopScreenShare => {
+                handle_stop_screen_share(&state, &evt_tx);
+            }
+
             LogicCommand::StartAudio {
                 sample_rate,
                 channels,
@@ -77,12 +126,12 @@ pub fn run_logic_thread(
                 handle_toggle_mute(&state, &evt_tx);
             }
 
-            LogicCommand::ClearVideoBuffers => {
-                handle_clear_video_buffers(&state);
+            LogicCommand::ClearVideoBuffers { peer_id } => {
+                handle_clear_video_buffers(&state, &peer_id);
             }
 
             LogicCommand::SendDisconnect { is_owner } => {
-                handle_send_disconnect_message(&state.webrtc, &evt_tx, is_owner);
+                handle_send_disconnect_message(&state, &evt_tx, is_owner);
             }
 
             LogicCommand::StopConnection => {
@@ -123,16 +172,20 @@ pub fn run_logic_thread(
                 }
 
                 // Check if we have an active WebRTC connection
-                if state.webrtc.is_none() {
+                if state.peers.is_empty() {
                     let _ = evt_tx.send(LogicEvent::Error(
                         "Cannot send file: No active call".to_string(),
                     ));
                     continue;
                 }
 
-                // Send file through WebRTC connection
-                let webrtc = state.webrtc.as_ref().unwrap();
-                if let Ok(conn) = webrtc.lock() {
+                // Send file through every peer connection
+                for webrtc in state.connections() {
+                    let Ok(conn) = webrtc.lock() else {
+                        let _ =
+                            evt_tx.send(LogicEvent::Error("WebRTC connection locked".to_string()));
+                        continue;
+                    };
                     match conn.send_file(&path) {
                         Ok(transfer_id) => {
                             if let Some(ref logger) = state.logger {
@@ -151,8 +204,6 @@ pub fn run_logic_thread(
                                 .send(LogicEvent::Error(format!("Failed to send file: {}", e)));
                         }
                     }
-                } else {
-                    let _ = evt_tx.send(LogicEvent::Error("WebRTC connection locked".to_string()));
                 }
             }
 
@@ -167,32 +218,25 @@ pub fn run_logic_thread(
                     ));
                 }
 
-                if let Some(ref webrtc) = state.webrtc {
-                    if let Ok(conn) = webrtc.lock() {
-                        match conn.accept_file_transfer(transfer_id, &save_path) {
-                            Ok(_) => {
-                                if let Some(ref logger) = state.logger {
-                                    logger.info(&format!(
-                                        "[FILE] ✓ File transfer accepted: id={}",
-                                        transfer_id
-                                    ));
-                                }
-                                let _ =
-                                    evt_tx.send(LogicEvent::FileTransferAccepted { transfer_id });
-                            }
-                            Err(e) => {
-                                if let Some(ref logger) = state.logger {
-                                    logger.error(&format!("[FILE] Failed to accept file: {}", e));
-                                }
-                                let _ = evt_tx.send(LogicEvent::Error(format!(
-                                    "Failed to accept file: {}",
-                                    e
-                                )));
-                            }
+                match try_each_connection(&state, |conn| {
+                    conn.accept_file_transfer(transfer_id, &save_path)
+                }) {
+                    Ok(_) => {
+                        if let Some(ref logger) = state.logger {
+                            logger.info(&format!(
+                                "[FILE] ✓ File transfer accepted: id={}",
+                                transfer_id
+                            ));
                         }
+                        let _ = evt_tx.send(LogicEvent::FileTransferAccepted { transfer_id });
+                    }
+                    Err(e) => {
+                        if let Some(ref logger) = state.logger {
+                            logger.error(&format!("[FILE] Failed to accept file: {}", e));
+                        }
+                        let _ =
+                            evt_tx.send(LogicEvent::Error(format!("Failed to accept file: {}", e)));
                     }
-                } else {
-                    let _ = evt_tx.send(LogicEvent::Error("No active connection".to_string()));
                 }
             }
 
@@ -207,34 +251,28 @@ pub fn run_logic_thread(
                     ));
                 }
 
-                if let Some(ref webrtc) = state.webrtc {
-                    if let Ok(conn) = webrtc.lock() {
-                        match conn.reject_file_transfer(transfer_id, &reason) {
-                            Ok(_) => {
-                                if let Some(ref logger) = state.logger {
-                                    logger.info(&format!(
-                                        "[FILE] ✓ File transfer rejected: id={}",
-                                        transfer_id
-                                    ));
-                                }
-                                let _ = evt_tx.send(LogicEvent::FileTransferRejected {
-                                    transfer_id,
-                                    reason: reason.clone(),
-                                });
-                            }
-                            Err(e) => {
-                                if let Some(ref logger) = state.logger {
-                                    logger.error(&format!("[FILE] Failed to reject file: {}", e));
-                                }
-                                let _ = evt_tx.send(LogicEvent::Error(format!(
-                                    "Failed to reject file: {}",
-                                    e
-                                )));
-                            }
+                match try_each_connection(&state, |conn| {
+                    conn.reject_file_transfer(transfer_id, &reason)
+                }) {
+                    Ok(_) => {
+                        if let Some(ref logger) = state.logger {
+                            logger.info(&format!(
+                                "[FILE] ✓ File transfer rejected: id={}",
+                                transfer_id
+                            ));
                         }
+                        let _ = evt_tx.send(LogicEvent::FileTransferRejected {
+                            transfer_id,
+                            reason: reason.clone(),
+                        });
+                    }
+                    Err(e) => {
+                        if let Some(ref logger) = state.logger {
+                            logger.error(&format!("[FILE] Failed to reject file: {}", e));
+                        }
+                        let _ =
+                            evt_tx.send(LogicEvent::Error(format!("Failed to reject file: {}", e)));
                     }
-                } else {
-                    let _ = evt_tx.send(LogicEvent::Error("No active connection".to_string()));
                 }
             }
 
@@ -246,36 +284,35 @@ pub fn run_logic_thread(
                     ));
                 }
 
-                if let Some(ref webrtc) = state.webrtc {
-                    if let Ok(conn) = webrtc.lock() {
-                        match conn.cancel_file_transfer(transfer_id, "User cancelled") {
-                            Ok(_) => {
-                                if let Some(ref logger) = state.logger {
-                                    logger.info(&format!(
-                                        "[FILE] ✓ File transfer cancelled: id={}",
-                                        transfer_id
-                                    ));
-                                }
-                                let _ = evt_tx.send(LogicEvent::FileTransferFailed {
-                                    transfer_id,
-                                    reason: "User cancelled".to_string(),
-                                });
-                            }
-                            Err(e) => {
-                                if let Some(ref logger) = state.logger {
-                                    logger.error(&format!("[FILE] Failed to cancel file: {}", e));
-                                }
-                                let _ = evt_tx.send(LogicEvent::Error(format!(
-                                    "Failed to cancel file: {}",
-                                    e
-                                )));
-                            }
+                match try_each_connection(&state, |conn| {
+                    conn.cancel_file_transfer(transfer_id, "User cancelled")
+                }) {
+                    Ok(_) => {
+                        if let Some(ref logger) = state.logger {
+                            logger.info(&format!(
+                                "[FILE] ✓ File transfer cancelled: id={}",
+                                transfer_id
+                            ));
                         }
+                        let _ = evt_tx.send(LogicEvent::FileTransferFailed {
+                            transfer_id,
+                            reason: "User cancelled".to_string(),
+                        });
+                    }
+                    Err(e) => {
+                        if let Some(ref logger) = state.logger {
+                            logger.error(&format!("[FILE] Failed to cancel file: {}", e));
+                        }
+                        let _ =
+                            evt_tx.send(LogicEvent::Error(format!("Failed to cancel file: {}", e)));
                     }
-                } else {
-                    let _ = evt_tx.send(LogicEvent::Error("No active connection".to_string()));
                 }
             }
+
+            // --- Chat Commands ---
+            LogicCommand::SendChat { text } => {
+                handle_send_chat(&state, &evt_tx, &text);
+            }
         }
     }
 
@@ -283,8 +320,9 @@ pub fn run_logic_thread(
     state.cleanup();
 }
 
-/// Establishes the WebRTC connection and spawns media threads for camera and frame reception.
+/// Establishes the WebRTC connection to a peer and spawns its media threads.
 fn handle_start_connection(
+    peer_id: String,
     mut conn: webrtc::WebRtcConnection,
     participant: crate::models::Participant,
     state: &mut LogicState,
@@ -292,14 +330,14 @@ fn handle_start_connection(
     logger: logging::Logger,
 ) {
     logger.info(&format!(
-        "[LOGIC] Starting connection for participant '{}'",
-        participant.name
+        "[LOGIC] Starting connection to peer '{}' for participant '{}'",
+        peer_id, participant.name
     ));
 
     if let Err(e) = conn.establish_connection() {
         logger.error(&format!(
-            "[LOGIC] Failed to establish connection for '{}': {}",
-            participant.name, e
+            "[LOGIC] Failed to establish connection to peer '{}': {}",
+            peer_id, e
         ));
         let _ = evt_tx.send(LogicEvent::Error(format!(
             "Failed to establish connection: {}",
@@ -309,8 +347,8 @@ fn handle_start_connection(
     }
 
     logger.info(&format!(
-        "[LOGIC] Connection established successfully for '{}', sending participant name",
-        participant.name
+        "[LOGIC] Connection to peer '{}' established, sending participant name",
+        peer_id
     ));
 
     if let Err(e) = conn.send_participant_name(&participant.name) {
@@ -325,23 +363,28 @@ fn handle_start_connection(
     }
 
     logger.info(&format!(
-        "[LOGIC] Spawning media threads for '{}'",
-        participant.name
+        "[LOGIC] Spawning media threads for peer '{}'",
+        peer_id
     ));
-    spawn_media_threads(conn, &participant.name, state, evt_tx, logger.clone());
+    let webrtc = spawn_media_threads(peer_id, conn, state, evt_tx, logger.clone());
 
     if participant.camera_on {
         logger.info(&format!(
             "[LOGIC] Auto-starting camera for '{}' (camera_on=true)",
             participant.name
         ));
-        start_participant_camera_async(&participant, &state.webrtc, evt_tx);
+        start_participant_camera_async(&participant, webrtc.clone(), evt_tx);
     } else {
         logger.info(&format!(
             "[LOGIC] Camera not started for '{}' (camera_on=false)",
             participant.name
         ));
     }
+
+    // A peer joining an ongoing call gets the microphone we already publish
+    if participant.audio_on {
+        start_participant_audio_async(&participant, webrtc, evt_tx);
+    }
 }
 
 /// Starts the camera for a participant asynchronously.
@@ -351,14 +394,9 @@ fn handle_start_connection(
 /// toggled manually from the UI.
 fn start_participant_camera_async(
     participant: &crate::models::Participant,
-    webrtc_opt: &Option<Arc<Mutex<webrtc::WebRtcConnection>>>,
+    webrtc: Arc<Mutex<webrtc::WebRtcConnection>>,
     evt_tx: &Sender<LogicEvent>,
 ) {
-    let Some(webrtc_arc) = webrtc_opt.as_ref() else {
-        return;
-    };
-
-    let webrtc = webrtc_arc.clone();
     let device_id = participant.selected_camera_device;
     let fps = participant.camera_fps;
     let tx = evt_tx.clone();
@@ -390,45 +428,95 @@ fn start_participant_camera_async(
     });
 }
 
-/// Spawns camera capture and frame receiver threads
+/// Starts the microphone on a new peer connection, keeping the participant's mute state.
+fn start_participant_audio_async(
+    participant: &crate::models::Participant,
+    webrtc: Arc<Mutex<webrtc::WebRtcConnection>>,
+    evt_tx: &Sender<LogicEvent>,
+) {
+    let muted = participant.audio_muted;
+    let tx = evt_tx.clone();
+
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        let mut conn = match webrtc.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let mut result = conn
+            .start_audio_auto(AUDIO_SAMPLE_RATE, AUDIO_CHANNELS)
+            .map(|_| ());
+        if muted && result.is_ok() {
+            result = conn.toggle_mute().map(|_| ());
+        }
+
+        if let Err(e) = result {
+            let _ = tx.send(LogicEvent::Error(format!("Audio error: {}", e)));
+        }
+    });
+}
+
+/// Spawns camera capture, audio capture and frame receiver threads for one peer
+///
+/// Every peer connection captures and encodes on its own, so devices that
+/// cannot be opened twice (most cameras) only stream to the first peer that
+/// opens them.
 fn spawn_media_threads(
+    peer_id: String,
     conn: webrtc::WebRtcConnection,
-    _participant_name: &str,
     state: &mut LogicState,
     evt_tx: &Sender<LogicEvent>,
     logger: logging::Logger,
-) {
+) -> Arc<Mutex<webrtc::WebRtcConnection>> {
     let webrtc_arc = Arc::new(Mutex::new(conn));
-    state.webrtc = Some(webrtc_arc.clone());
+    let running = Arc::new(AtomicBool::new(true));
 
     // Start camera capture thread
     let camera_handle = std::thread::spawn({
         let webrtc = webrtc_arc.clone();
         let tx = evt_tx.clone();
-        move || run_camera_thread(webrtc, tx)
+        let running = running.clone();
+        move || run_camera_thread(webrtc, tx, running)
     });
-    state.camera_thread_handle = Some(camera_handle);
 
     // Start audio capture thread
     let audio_handle = std::thread::spawn({
         let webrtc = webrtc_arc.clone();
-        move || run_audio_thread(webrtc)
+        let tx = evt_tx.clone();
+        let running = running.clone();
+        move || run_audio_thread(webrtc, tx, running)
     });
-    state.audio_thread_handle = Some(audio_handle);
 
     // Start remote frame receiver thread
     let receive_handle = std::thread::spawn({
+        let peer_id = peer_id.clone();
         let webrtc = webrtc_arc.clone();
         let tx = evt_tx.clone();
+        let running = running.clone();
         let logger_clone = logger
             .for_component("ReceiveThread")
             .unwrap_or(logger.clone());
-        move || run_receive_thread(webrtc, tx, logger_clone)
+        move || run_receive_thread(peer_id, webrtc, tx, running, logger_clone)
     });
-    state.receive_thread_handle = Some(receive_handle);
+
+    // Replace (and stop) a stale connection to the same peer
+    state.close_peer(&peer_id);
+    state.peers.insert(
+        peer_id,
+        PeerConnection {
+            webrtc: webrtc_arc.clone(),
+            running,
+            camera_thread_handle: Some(camera_handle),
+            audio_thread_handle: Some(audio_handle),
+            receive_thread_handle: Some(receive_handle),
+        },
+    );
+
+    webrtc_arc
 }
 
-/// Executes camera operations with the WebRTC connection in a separate thread.
+/// Executes camera operations on every peer connection in a separate thread.
 fn handle_start_camera(device_id: i32, fps: f64, state: &LogicState, evt_tx: &Sender<LogicEvent>) {
     execute_with_webrtc(state, evt_tx.clone(), move |webrtc| {
         match webrtc.start_camera(device_id, fps) {
@@ -446,7 +534,40 @@ fn handle_stop_camera(state: &LogicState, evt_tx: &Sender<LogicEvent>) {
     });
 }
 
-/// Starts audio capture with the WebRTC connection
+/// Switches the running camera of every peer connection to another device.
+fn handle_switch_camera(device_id: i32, state: &LogicState, evt_tx: &Sender<LogicEvent>) {
+    execute_with_webrtc(state, evt_tx.clone(), move |webrtc| {
+        match webrtc.switch_camera(device_id) {
+            Ok(_) => None,
+            Err(e) => Some(LogicEvent::Error(format!("Camera switch error: {}", e))),
+        }
+    });
+}
+
+/// Starts sharing a display through the video pipeline.
+fn handle_start_screen_share(
+    display_id: i32,
+    fps: f64,
+    state: &LogicState,
+    evt_tx: &Sender<LogicEvent>,
+) {
+    execute_with_webrtc(state, evt_tx.clone(), move |webrtc| {
+        match webrtc.start_screen_share(display_id, fps) {
+            Ok(_) => Some(LogicEvent::ScreenShareStarted),
+            Err(e) => Some(LogicEvent::Error(format!("Screen share error: {}", e))),
+        }
+    });
+}
+
+/// Stops screen sharing and sends a notification event.
+fn handle_stop_screen_share(state: &LogicState, evt_tx: &Sender<LogicEvent>) {
+    execute_with_webrtc(state, evt_tx.clone(), |webrtc| {
+        webrtc.stop_screen_share();
+        Some(LogicEvent::ScreenShareStopped)
+    });
+}
+
+/// Starts audio capture on every peer connection
 fn handle_start_audio(
     sample_rate: u32,
     channels: u32,
@@ -475,65 +596,114 @@ fn handle_toggle_mute(state: &LogicState, evt_tx: &Sender<LogicEvent>) {
     });
 }
 
-/// Clears video buffers to flush delayed frames from jitter buffer
-fn handle_clear_video_buffers(state: &LogicState) {
-    if let Some(webrtc_arc) = &state.webrtc
-        && let Ok(webrtc) = webrtc_arc.lock()
+/// Clears a peer's video buffers to flush delayed frames from jitter buffer
+fn handle_clear_video_buffers(state: &LogicState, peer_id: &str) {
+    if let Some(peer) = state.peers.get(peer_id)
+        && let Ok(webrtc) = peer.webrtc.lock()
     {
         webrtc.clear_video_buffers();
     }
 }
 
-/// Executes an operation with the WebRTC connection in a separate thread.
+/// Executes an operation on every peer connection in a separate thread.
 ///
 /// This prevents blocking the main logic thread while camera operations are in progress.
+/// Only one event is reported: the first success, or the first error if every peer failed.
 fn execute_with_webrtc<F>(state: &LogicState, evt_tx: Sender<LogicEvent>, operation: F)
 where
-    F: FnOnce(&mut webrtc::WebRtcConnection) -> Option<LogicEvent> + Send + 'static,
+    F: Fn(&mut webrtc::WebRtcConnection) -> Option<LogicEvent> + Send + 'static,
 {
-    if let Some(webrtc_arc) = state.webrtc.clone() {
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_millis(50));
-            if let Ok(mut webrtc) = webrtc_arc.lock()
-                && let Some(event) = operation(&mut webrtc)
-            {
-                let _ = evt_tx.send(event);
-            }
-        });
-    } else {
+    let connections = state.connections();
+    if connections.is_empty() {
         let _ = evt_tx.send(LogicEvent::Error(
             "Cannot execute operation: no WebRTC connection".to_string(),
         ));
+        return;
     }
+
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let mut success = None;
+        let mut error = None;
+        for webrtc_arc in connections {
+            let Ok(mut webrtc) = webrtc_arc.lock() else {
+                continue;
+            };
+            match operation(&mut webrtc) {
+                Some(event @ LogicEvent::Error(_)) => {
+                    error.get_or_insert(event);
+                }
+                Some(event) => {
+                    success.get_or_insert(event);
+                }
+                None => {}
+            }
+        }
+        if let Some(event) = success.or(error) {
+            let _ = evt_tx.send(event);
+        }
+    });
 }
 
-/// Sends a disconnect message through the WebRTC connection.
-fn handle_send_disconnect_message(
-    webrtc_state: &Option<Arc<Mutex<webrtc::WebRtcConnection>>>,
-    evt_tx: &Sender<LogicEvent>,
-    is_owner: bool,
-) {
-    if let Some(webrtc_arc) = webrtc_state
-        && let Ok(conn) = webrtc_arc.lock()
-        && let Err(e) = conn.send_disconnect_message(is_owner)
-    {
-        let _ = evt_tx.send(LogicEvent::Error(format!(
-            "Failed to send disconnect message: {}",
-            e
-        )));
+/// Runs a fallible operation on each peer connection until one succeeds.
+///
+/// Used for file transfers, whose IDs belong to a single (unknown) peer.
+fn try_each_connection<F>(state: &LogicState, operation: F) -> Result<(), String>
+where
+    F: Fn(&webrtc::WebRtcConnection) -> Result<(), Box<dyn std::error::Error>>,
+{
+    let mut last_error = "No active connection".to_string();
+    for webrtc_arc in state.connections() {
+        let Ok(conn) = webrtc_arc.lock() else {
+            continue;
+        };
+        match operation(&conn) {
+            Ok(()) => return Ok(()),
+            Err(e) => last_error = e.to_string(),
+        }
     }
+    Err(last_error)
 }
 
-/// Adds a remote ICE candidate to the pending or active WebRTC connection.
+/// Sends a chat message through every peer connection.
+fn handle_send_chat(state: &LogicState, evt_tx: &Sender<LogicEvent>, text: &str) {
+    for webrtc_arc in state.connections() {
+        if let Ok(conn) = webrtc_arc.lock()
+            && let Err(e) = conn.send_chat(text)
+        {
+            let _ = evt_tx.send(LogicEvent::Error(format!(
+                "Failed to send chat message: {}",
+                e
+            )));
+        }
+    }
+}
+
+/// Sends a disconnect message through every peer connection.
+fn handle_send_disconnect_message(state: &LogicState, evt_tx: &Sender<LogicEvent>, is_owner: bool) {
+    for webrtc_arc in state.connections() {
+        if let Ok(conn) = webrtc_arc.lock()
+            && let Err(e) = conn.send_disconnect_message(is_owner)
+        {
+            let _ = evt_tx.send(LogicEvent::Error(format!(
+                "Failed to send disconnect message: {}",
+                e
+            )));
+        }
+    }
+}
+
+/// Adds a remote ICE candidate to the pending or active connection of a peer.
 fn handle_add_ice_candidate(
     state: &mut LogicState,
+    peer_id: &str,
     candidate: String,
     sdp_mid: String,
     sdp_mline_index: u16,
     evt_tx: &Sender<LogicEvent>,
 ) {
     // Try pending connection first (during setup)
-    if let Some(ref mut conn) = state.pending_connection {
+    if let Some(conn) = state.pending_connections.get_mut(peer_id) {
         if let Err(e) = conn.add_ice_candidate(&candidate, &sdp_mid, sdp_mline_index) {
             let _ = evt_tx.send(LogicEvent::Error(format!(
                 "Failed to add ICE candidate to pending connection: {}",
@@ -544,8 +714,8 @@ fn handle_add_ice_candidate(
     }
 
     // Try active connection
-    if let Some(webrtc_arc) = &state.webrtc {
-        if let Ok(mut conn) = webrtc_arc.lock()
+    if let Some(peer) = state.peers.get(peer_id) {
+        if let Ok(mut conn) = peer.webrtc.lock()
             && let Err(e) = conn.add_ice_candidate(&candidate, &sdp_mid, sdp_mline_index)
         {
             let _ = evt_tx.send(LogicEvent::Error(format!(
@@ -554,8 +724,9 @@ fn handle_add_ice_candidate(
             )));
         }
     } else {
-        let _ = evt_tx.send(LogicEvent::Error(
-            "Cannot add ICE candidate: no WebRTC connection available".to_string(),
-        ));
+        let _ = evt_tx.send(LogicEvent::Error(format!(
+            "Cannot add ICE candidate: no WebRTC connection to peer '{}'",
+            peer_id
+        )));
     }
 }
@@ -7,6 +7,7 @@ use crate::components::CallStats;
 use crate::events::LogicEvent;
 use crate::logic::utils::rgb_to_color_image;
 use logging::Logger;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -16,61 +17,34 @@ const FRAME_RECEIVE_INTERVAL_MS: u64 = 1;
 const ERROR_RETRY_INTERVAL_MS: u64 = 100;
 const STATS_UPDATE_INTERVAL_MS: u64 = 1000;
 
-/// Tracks bitrate over a sliding time window
-struct BitrateTracker {
-    last_bytes_sent: u64,
-    last_update: Instant,
-}
-
-impl BitrateTracker {
-    fn new() -> Self {
-        Self {
-            last_bytes_sent: 0,
-            last_update: Instant::now(),
-        }
-    }
-
-    /// Calculate bitrate in Mbps based on bytes sent since last call
-    fn calculate_bitrate(&mut self, current_bytes_sent: u64) -> f64 {
-        let elapsed = self.last_update.elapsed().as_secs_f64();
-
-        if elapsed >= 1.0 {
-            let bytes_delta = current_bytes_sent.saturating_sub(self.last_bytes_sent);
-            let mbps = (bytes_delta as f64 * 8.0) / elapsed / 1_000_000.0;
-
-            self.last_bytes_sent = current_bytes_sent;
-            self.last_update = Instant::now();
-
-            mbps
-        } else {
-            // Not enough time elapsed
-            0.0
-        }
-    }
-}
-
 /// Runs the frame reception loop in a dedicated thread.
 ///
 /// The thread continuously receives frames from the remote peer through the WebRTC
 /// connection and sends them to the UI for display. Also polls for control messages
 /// to synchronize camera state between peers.
+///
+/// Events about the remote participant are tagged with `peer_id`. Exits once
+/// `running` is cleared.
 pub fn run_receive_thread(
+    peer_id: String,
     webrtc_arc: Arc<Mutex<WebRtcConnection>>,
     evt_tx: Sender<LogicEvent>,
+    running: Arc<AtomicBool>,
     logger: Logger,
 ) {
     let mut last_stats_update = Instant::now();
-    let mut bitrate_tracker = BitrateTracker::new();
 
-    loop {
-        poll_control_messages(&webrtc_arc, &evt_tx, &logger);
-        poll_video_frames(&webrtc_arc, &evt_tx, &logger);
+    while running.load(Ordering::Relaxed) {
+        poll_control_messages(&peer_id, &webrtc_arc, &evt_tx, &logger);
+        poll_video_frames(&peer_id, &webrtc_arc, &evt_tx, &logger);
         poll_audio_frames(&webrtc_arc, &logger);
         poll_sctp(&webrtc_arc, &evt_tx, &logger);
+        poll_chat_messages(&peer_id, &webrtc_arc, &evt_tx, &logger);
+        poll_ice_state(&webrtc_arc, &evt_tx, &logger);
 
         // Poll stats periodically
         if last_stats_update.elapsed() >= Duration::from_millis(STATS_UPDATE_INTERVAL_MS) {
-            poll_statistics(&webrtc_arc, &evt_tx, &mut bitrate_tracker, &logger);
+            poll_statistics(&webrtc_arc, &evt_tx, &logger);
             last_stats_update = Instant::now();
         }
     }
@@ -78,6 +52,7 @@ pub fn run_receive_thread(
 
 /// Polls for control messages from the remote peer
 fn poll_control_messages(
+    peer_id: &str,
     webrtc_arc: &Arc<Mutex<WebRtcConnection>>,
     evt_tx: &Sender<LogicEvent>,
     logger: &Logger,
@@ -94,17 +69,20 @@ fn poll_control_messages(
     };
 
     if let Ok(Some(control_msg)) = control_result {
+        let peer_id = peer_id.to_string();
         let event = match control_msg {
-            webrtc::ControlMessage::CameraOn => LogicEvent::RemoteCameraOn,
-            webrtc::ControlMessage::CameraOff => LogicEvent::RemoteCameraOff,
-            webrtc::ControlMessage::AudioOn => LogicEvent::RemoteAudioOn,
-            webrtc::ControlMessage::AudioOff => LogicEvent::RemoteAudioOff,
-            webrtc::ControlMessage::AudioMuted => LogicEvent::RemoteAudioMuted,
-            webrtc::ControlMessage::AudioUnmuted => LogicEvent::RemoteAudioUnmuted,
-            webrtc::ControlMessage::ParticipantDisconnected => LogicEvent::ParticipantDisconnected,
-            webrtc::ControlMessage::OwnerDisconnected => LogicEvent::OwnerDisconnected,
+            webrtc::ControlMessage::CameraOn => LogicEvent::RemoteCameraOn(peer_id),
+            webrtc::ControlMessage::CameraOff => LogicEvent::RemoteCameraOff(peer_id),
+            webrtc::ControlMessage::AudioOn => LogicEvent::RemoteAudioOn(peer_id),
+            webrtc::ControlMessage::AudioOff => LogicEvent::RemoteAudioOff(peer_id),
+            webrtc::ControlMessage::AudioMuted => LogicEvent::RemoteAudioMuted(peer_id),
+            webrtc::ControlMessage::AudioUnmuted => LogicEvent::RemoteAudioUnmuted(peer_id),
+            webrtc::ControlMessage::ParticipantDisconnected => {
+                LogicEvent::ParticipantDisconnected(peer_id)
+            }
+            webrtc::ControlMessage::OwnerDisconnected => LogicEvent::OwnerDisconnected(peer_id),
             webrtc::ControlMessage::ParticipantName(name) => {
-                LogicEvent::RemoteParticipantName(name)
+                LogicEvent::RemoteParticipantName { peer_id, name }
             }
         };
         let _ = evt_tx.send(event);
@@ -113,6 +91,7 @@ fn poll_control_messages(
 
 /// Polls for video frames from the remote peer
 fn poll_video_frames(
+    peer_id: &str,
     webrtc_arc: &Arc<Mutex<WebRtcConnection>>,
     evt_tx: &Sender<LogicEvent>,
     logger: &Logger,
@@ -131,7 +110,10 @@ fn poll_video_frames(
     match result {
         Ok(Some((width, height, rgb_pixels))) => {
             let color_image = rgb_to_color_image(width, height, rgb_pixels);
-            let _ = evt_tx.send(LogicEvent::RemoteFrame(color_image));
+            let _ = evt_tx.send(LogicEvent::RemoteFrame {
+                peer_id: peer_id.to_string(),
+                image: color_image,
+            });
         }
         Ok(None) => {
             std::thread::sleep(std::time::Duration::from_millis(FRAME_RECEIVE_INTERVAL_MS));
@@ -146,46 +128,46 @@ fn poll_video_frames(
 fn poll_statistics(
     webrtc_arc: &Arc<Mutex<WebRtcConnection>>,
     evt_tx: &Sender<LogicEvent>,
-    bitrate_tracker: &mut BitrateTracker,
     logger: &Logger,
 ) {
-    let conn = match webrtc_arc.lock() {
-        Ok(guard) => guard,
+    let connection_stats = match webrtc_arc.lock() {
+        Ok(mut guard) => guard.get_stats(),
         Err(poisoned) => {
             logger.error("WebRTC mutex poisoned in poll_statistics, recovering");
-            poisoned.into_inner()
+            poisoned.into_inner().get_stats()
         }
     };
 
-    let packet_stats = conn.get_packet_stats();
-    let jitter_stats = conn.get_jitter_stats();
-
-    // Calculate bitrate from RTCP stats
-    let bitrate_mbps = if let Some(rtcp_stats) = conn.get_rtcp_stats() {
-        bitrate_tracker.calculate_bitrate(rtcp_stats.bytes_sent)
-    } else {
-        0.0
+    let stats = CallStats {
+        bitrate_mbps: connection_stats.send_bitrate_bps / 1_000_000.0,
+        estimated_bandwidth_mbps: connection_stats.estimated_bandwidth_bps / 1_000_000.0,
+        packet_loss_percent: connection_stats.loss_rate * 100.0,
+        jitter_ms: connection_stats.jitter_ms,
+        rtt_ms: connection_stats.rtt_ms.unwrap_or(0.0),
+        packets_sent: connection_stats.packets_sent,
+        packets_received: connection_stats.packets_received as u32,
     };
 
-    let packets_sent = conn.get_rtcp_stats().map(|s| s.packets_sent).unwrap_or(0);
-
-    // Calculate RTT from RTCP (convert seconds to milliseconds)
-    let rtt_ms = conn
-        .get_rtcp_stats()
-        .and_then(|s| s.calculate_rtt())
-        .map(|rtt_secs| rtt_secs * 1000.0)
-        .unwrap_or(0.0);
+    let _ = evt_tx.send(LogicEvent::StatsUpdated(stats));
+}
 
-    let stats = CallStats {
-        bitrate_mbps,
-        packet_loss_percent: packet_stats.loss_rate * 100.0,
-        jitter_ms: jitter_stats.jitter_ms,
-        rtt_ms,
-        packets_sent,
-        packets_received: packet_stats.packets_received as u32,
+/// Forwards ICE connection state changes (consent freshness) to the UI
+fn poll_ice_state(
+    webrtc_arc: &Arc<Mutex<WebRtcConnection>>,
+    evt_tx: &Sender<LogicEvent>,
+    logger: &Logger,
+) {
+    let mut conn = match webrtc_arc.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            logger.error("WebRTC mutex poisoned in poll_ice_state, recovering");
+            poisoned.into_inner()
+        }
     };
 
-    let _ = evt_tx.send(LogicEvent::StatsUpdated(stats));
+    while let Some(state) = conn.poll_ice_state() {
+        let _ = evt_tx.send(LogicEvent::IceStateChanged(state));
+    }
 }
 
 /// Polls for outgoing SCTP packets and processes incoming SCTP data
@@ -246,6 +228,17 @@ fn poll_sctp(
                     reason,
                 });
             }
+            FileTransferEvent::Resumed { id, offset, total } => {
+                logger.info(&format!(
+                    "File transfer {} resumed at {} of {} bytes",
+                    id, offset, total
+                ));
+                let _ = evt_tx.send(LogicEvent::FileTransferProgress {
+                    transfer_id: id,
+                    bytes_transferred: offset,
+                    total_bytes: total,
+                });
+            }
             FileTransferEvent::Progress { id, bytes, total } => {
                 let _ = evt_tx.send(LogicEvent::FileTransferProgress {
                     transfer_id: id,
@@ -271,6 +264,30 @@ fn poll_sctp(
     }
 }
 
+/// Forwards chat messages received from the remote peer to the UI
+fn poll_chat_messages(
+    peer_id: &str,
+    webrtc_arc: &Arc<Mutex<WebRtcConnection>>,
+    evt_tx: &Sender<LogicEvent>,
+    logger: &Logger,
+) {
+    let conn = match webrtc_arc.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            logger.error("WebRTC mutex poisoned in poll_chat_messages, recovering");
+            poisoned.into_inner()
+        }
+    };
+
+    while let Some(webrtc::ChatMessage::Text { text, timestamp }) = conn.poll_chat_message() {
+        let _ = evt_tx.send(LogicEvent::ChatMessageReceived {
+            from: peer_id.to_string(),
+            text,
+            timestamp,
+        });
+    }
+}
+
 /// Polls for audio frames from the remote peer and plays them
 fn poll_audio_frames(webrtc_arc: &Arc<Mutex<WebRtcConnection>>, logger: &Logger) {
     let result = {
@@ -3,18 +3,40 @@
 //! Maintains WebRTC connection state independent of the UI thread.
 
 use logging::Logger;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use webrtc::WebRtcConnection;
 
-/// State maintained by the logic thread.
-/// This holds the WebRTC connection and is independent of egui.
-pub struct LogicState {
-    pub webrtc: Option<Arc<Mutex<WebRtcConnection>>>,
+/// An established connection to one remote peer and its media threads
+pub struct PeerConnection {
+    pub webrtc: Arc<Mutex<WebRtcConnection>>,
+    /// Cleared to stop this peer's media threads
+    pub running: Arc<AtomicBool>,
     pub camera_thread_handle: Option<std::thread::JoinHandle<()>>,
     pub audio_thread_handle: Option<std::thread::JoinHandle<()>>,
     pub receive_thread_handle: Option<std::thread::JoinHandle<()>>,
-    /// Temporary storage for connection being set up (before StartConnection command)
-    pub pending_connection: Option<WebRtcConnection>,
+}
+
+impl PeerConnection {
+    /// Stops the media threads and closes the connection
+    pub fn close(self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Ok(mut webrtc) = self.webrtc.lock() {
+            webrtc.close();
+        }
+    }
+}
+
+/// State maintained by the logic thread.
+/// This holds one WebRTC connection per remote peer (keyed by peer user ID)
+/// and is independent of egui.
+pub struct LogicState {
+    pub peers: HashMap<String, PeerConnection>,
+    /// Connections being set up (before StartConnection command), keyed by peer user ID
+    pub pending_connections: HashMap<String, WebRtcConnection>,
+    /// Connection with pregathered candidates, used by the next offer/answer
+    pub warm_connection: Option<WebRtcConnection>,
     /// Logger for file transfer operations
     pub logger: Option<Logger>,
 }
@@ -31,31 +53,45 @@ impl LogicState {
         .ok();
 
         Self {
-            webrtc: None,
-            camera_thread_handle: None,
-            audio_thread_handle: None,
-            receive_thread_handle: None,
-            pending_connection: None,
+            peers: HashMap::new(),
+            pending_connections: HashMap::new(),
+            warm_connection: None,
             logger,
         }
     }
 
+    /// Returns the established connections to every peer
+    pub fn connections(&self) -> Vec<Arc<Mutex<WebRtcConnection>>> {
+        self.peers.values().map(|peer| peer.webrtc.clone()).collect()
+    }
+
+    /// Closes the connection to a single peer
+    pub fn close_peer(&mut self, peer_id: &str) {
+        self.pending_connections.remove(peer_id);
+        if let Some(peer) = self.peers.remove(peer_id) {
+            if let Some(l) = self.logger.as_ref() {
+                l.info(&format!(
+                    "[LOGIC_CLEANUP] Closing connection to peer '{}'",
+                    peer_id
+                ))
+            }
+            peer.close();
+        }
+    }
+
     /// Stop all threads and clean up resources
     pub fn cleanup(&mut self) {
         // WebRtcConnection's Drop impl will send disconnect message automatically
-        if let Some(webrtc_arc) = self.webrtc.take()
-            && let Ok(mut webrtc) = webrtc_arc.lock()
-        {
-            if let Some(l) = self.logger
-                .as_ref() { l.info("[LOGIC_CLEANUP] Closing WebRTC connection...") }
-            webrtc.close();
-            if let Some(l) = self.logger
-                .as_ref() { l.info("[LOGIC_CLEANUP] WebRTC connection closed") }
+        if let Some(l) = self.logger.as_ref() {
+            l.info(&format!(
+                "[LOGIC_CLEANUP] Closing {} WebRTC connection(s)...",
+                self.peers.len()
+            ))
         }
-
-        self.camera_thread_handle = None;
-        self.audio_thread_handle = None;
-        self.receive_thread_handle = None;
+        for (_, peer) in self.peers.drain() {
+            peer.close();
+        }
+        self.pending_connections.clear();
 
         if let Some(l) = self.logger
             .as_ref() { l.info("[LOGIC_CLEANUP] Cleanup complete") }
@@ -11,8 +11,35 @@ fn send_error(evt_tx: &Sender<LogicEvent>, message: String) {
     let _ = evt_tx.send(LogicEvent::Error(message));
 }
 
-/// Generate WebRTC offer
-pub fn handle_generate_offer(state: &mut LogicState, evt_tx: &Sender<LogicEvent>) {
+/// Prepare a connection with pregathered ICE candidates for the next call
+///
+/// Best effort: on failure the next call creates its connection as usual.
+pub fn handle_pregather(state: &mut LogicState) {
+    let logger = match logging::Logger::with_component(
+        "room_setup.log".into(),
+        LogLevel::Info,
+        "WebRTC-Warmup".to_string(),
+        false,
+    ) {
+        Ok(l) => l,
+        Err(_) => return,
+    };
+
+    let conn = match state.warm_connection.take() {
+        Some(conn) => Ok(conn),
+        None => WebRtcConnection::new(None, logger.clone()),
+    };
+    match conn {
+        Ok(mut conn) => match conn.pregather() {
+            Ok(()) => state.warm_connection = Some(conn),
+            Err(e) => logger.warn(&format!("[WEBRTC] Pregathering failed: {}", e)),
+        },
+        Err(e) => logger.warn(&format!("[WEBRTC] Could not create warm connection: {}", e)),
+    }
+}
+
+/// Generate WebRTC offer for a peer
+pub fn handle_generate_offer(state: &mut LogicState, peer_id: String, evt_tx: &Sender<LogicEvent>) {
     let logger = match logging::Logger::with_component(
         "room_setup.log".into(),
         LogLevel::Info,
@@ -23,19 +50,27 @@ pub fn handle_generate_offer(state: &mut LogicState, evt_tx: &Sender<LogicEvent>
         Err(e) => return send_error(evt_tx, format!("Error creating logger: {}", e)),
     };
 
-    match WebRtcConnection::create_offer_from_new(logger) {
+    let result = match state.warm_connection.take() {
+        Some(conn) => WebRtcConnection::create_offer_from(conn),
+        None => WebRtcConnection::create_offer_from_new(logger),
+    };
+    match result {
         Ok((conn, offer)) => {
             // Store connection in LogicState temporarily
-            state.pending_connection = Some(conn);
-            let _ = evt_tx.send(LogicEvent::OfferGenerated(offer));
+            state.pending_connections.insert(peer_id.clone(), conn);
+            let _ = evt_tx.send(LogicEvent::OfferGenerated {
+                peer_id,
+                sdp: offer,
+            });
         }
         Err(e) => send_error(evt_tx, format!("Error creating offer: {}", e)),
     }
 }
 
-/// Generate WebRTC answer
+/// Generate WebRTC answer for a peer
 pub fn handle_generate_answer(
     state: &mut LogicState,
+    peer_id: String,
     offer_sdp: String,
     evt_tx: &Sender<LogicEvent>,
 ) {
@@ -49,16 +84,26 @@ pub fn handle_generate_answer(
         Err(e) => return send_error(evt_tx, format!("Error creating logger: {}", e)),
     };
 
-    logger.info("[WEBRTC] Creating answer from offer SDP");
-    match WebRtcConnection::create_answer_from_new(&offer_sdp, logger.clone()) {
+    logger.info(&format!(
+        "[WEBRTC] Creating answer from offer SDP of peer '{}'",
+        peer_id
+    ));
+    let result = match state.warm_connection.take() {
+        Some(conn) => WebRtcConnection::create_answer_from(conn, &offer_sdp),
+        None => WebRtcConnection::create_answer_from_new(&offer_sdp, logger.clone()),
+    };
+    match result {
         Ok((conn, answer)) => {
             logger.info(&format!(
                 "[WEBRTC] Answer created successfully - sdp_len: {} bytes",
                 answer.len()
             ));
             // Store connection in LogicState temporarily
-            state.pending_connection = Some(conn);
-            let _ = evt_tx.send(LogicEvent::AnswerGenerated(answer));
+            state.pending_connections.insert(peer_id.clone(), conn);
+            let _ = evt_tx.send(LogicEvent::AnswerGenerated {
+                peer_id,
+                sdp: answer,
+            });
         }
         Err(e) => {
             logger.error(&format!("[WEBRTC] Failed to create answer: {}", e));
@@ -70,22 +115,23 @@ pub fn handle_generate_answer(
 /// Process answer from remote peer
 pub fn handle_process_answer(
     state: &mut LogicState,
+    peer_id: String,
     answer_sdp: String,
     evt_tx: &Sender<LogicEvent>,
 ) {
     // Get pending connection from state
-    let Some(mut conn) = state.pending_connection.take() else {
+    let Some(mut conn) = state.pending_connections.remove(&peer_id) else {
         return send_error(
             evt_tx,
-            "No pending connection to process answer".to_string(),
+            format!("No pending connection to process answer from '{}'", peer_id),
         );
     };
 
     match conn.set_remote_answer(&answer_sdp) {
         Ok(_) => {
             // Put connection back in pending state until StartConnection
-            state.pending_connection = Some(conn);
-            let _ = evt_tx.send(LogicEvent::ConnectionReady);
+            state.pending_connections.insert(peer_id.clone(), conn);
+            let _ = evt_tx.send(LogicEvent::ConnectionReady { peer_id });
         }
         Err(e) => {
             send_error(evt_tx, format!("Error setting remote answer: {}", e));
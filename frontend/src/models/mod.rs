@@ -8,4 +8,4 @@ pub mod protocol;
 mod room;
 
 pub use participant::{Participant, ParticipantRole};
-pub use room::RoomData;
+pub use room::{ChatEntry, RoomData};
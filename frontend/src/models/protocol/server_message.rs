@@ -44,6 +44,8 @@ pub enum ServerMessage {
     },
     CallDeclined {
         peer_username: String,
+        /// "Declined", or "Busy" if the peer was already in a call
+        reason: String,
     },
     SdpOffer {
         call_id: String,
@@ -56,6 +58,7 @@ pub enum ServerMessage {
         sdp: String,
     },
     IceCandidate {
+        from_user_id: String,
         candidate: String,
         sdp_mid: String,
         sdp_mline_index: u32,
@@ -63,9 +66,30 @@ pub enum ServerMessage {
     Hangup {
         call_id: String,
     },
+    /// Another user joined the call we are in (mesh rooms)
+    ParticipantJoined {
+        call_id: String,
+        user_id: String,
+        username: String,
+    },
+    /// A user left the call, which continues with the remaining participants
+    ParticipantLeft {
+        call_id: String,
+        user_id: String,
+        username: String,
+    },
     Error {
         message: String,
     },
+    ServerShutdown {
+        reason: String,
+    },
+    /// Reply to the resume request sent after the connection dropped
+    SessionResumed {
+        success: bool,
+        call_id: Option<String>,
+        error: Option<String>,
+    },
 }
 
 impl std::fmt::Display for ServerMessage {
@@ -83,7 +107,11 @@ impl std::fmt::Display for ServerMessage {
             ServerMessage::SdpAnswer { .. } => write!(f, "SdpAnswer"),
             ServerMessage::IceCandidate { .. } => write!(f, "IceCandidate"),
             ServerMessage::Hangup { .. } => write!(f, "Hangup"),
+            ServerMessage::ParticipantJoined { .. } => write!(f, "ParticipantJoined"),
+            ServerMessage::ParticipantLeft { .. } => write!(f, "ParticipantLeft"),
             ServerMessage::Error { .. } => write!(f, "Error"),
+            ServerMessage::ServerShutdown { .. } => write!(f, "ServerShutdown"),
+            ServerMessage::SessionResumed { .. } => write!(f, "SessionResumed"),
         }
     }
 }
@@ -1,23 +1,45 @@
 //! Room Data Model
 //!
 //! Defines the room data structure for video call sessions.
-//! Rooms support a maximum of 2 participants: one Owner and one Guest.
+//! Rooms are a mesh: one Owner plus Guests, each remote participant reached
+//! through its own peer connection.
 
 use super::participant::{Participant, ParticipantRole};
+use std::collections::HashMap;
+use webrtc::ConnectionState;
 
 // Room configuration constants
-const MAX_PARTICIPANTS: usize = 2;
+const MAX_PARTICIPANTS: usize = 6;
 const ROOM_ID_LENGTH: usize = 8;
 
-/// Room data structure (max 2 participants: owner + guest)
+/// A chat message shown in the room
+#[derive(Clone, Debug)]
+pub struct ChatEntry {
+    /// Name of the participant who wrote the message
+    pub from: String,
+    pub text: String,
+    /// Milliseconds since the Unix epoch when the message was sent
+    pub timestamp: u64,
+}
+
+/// Room data structure (owner + up to `MAX_PARTICIPANTS - 1` guests)
 #[derive(Clone, Debug)]
 pub struct RoomData {
     pub id: String,
     pub participants: Vec<Participant>,
     pub stats: Option<crate::components::CallStats>,
+    pub screen_sharing: bool,
+    /// Remote peer user ID -> participant name (runtime only)
+    pub peers: HashMap<String, String>,
+    /// ICE connection state of the media path (runtime only)
+    pub ice_state: ConnectionState,
+    /// Level (0.0 to 1.0) of the local microphone (runtime only)
+    pub local_audio_level: f32,
+    /// Chat messages exchanged during the call, oldest first (runtime only)
+    pub chat: Vec<ChatEntry>,
 }
 
-// Manual implementation to handle the stats field which is not serialized
+// Manual implementation to handle the runtime fields which are not serialized
 impl json_parser::Serialize for RoomData {
     fn serialize(&self) -> json_parser::JsonValue {
         let mut map = std::collections::HashMap::new();
@@ -50,6 +72,11 @@ impl json_parser::Deserialize for RoomData {
             id: json_parser::Deserialize::deserialize(id)?,
             participants: json_parser::Deserialize::deserialize(participants)?,
             stats: None, // Runtime field, always starts as None when deserialized
+            screen_sharing: false,
+            peers: HashMap::new(),
+            ice_state: ConnectionState::New,
+            local_audio_level: 0.0,
+            chat: Vec::new(),
         })
     }
 }
@@ -61,6 +88,11 @@ impl RoomData {
             id: Self::generate_room_id(),
             participants: Vec::with_capacity(MAX_PARTICIPANTS),
             stats: None,
+            screen_sharing: false,
+            peers: HashMap::new(),
+            ice_state: ConnectionState::New,
+            local_audio_level: 0.0,
+            chat: Vec::new(),
         }
     }
 
@@ -70,6 +102,11 @@ impl RoomData {
             id,
             participants: Vec::with_capacity(MAX_PARTICIPANTS),
             stats: None,
+            screen_sharing: false,
+            peers: HashMap::new(),
+            ice_state: ConnectionState::New,
+            local_audio_level: 0.0,
+            chat: Vec::new(),
         }
     }
 
@@ -86,7 +123,10 @@ impl RoomData {
     /// Adds a participant to the room
     pub fn add_participant(&mut self, name: String) -> Result<ParticipantRole, String> {
         if self.is_full() {
-            return Err("Room is full (maximum 2 participants)".to_string());
+            return Err(format!(
+                "Room is full (maximum {} participants)",
+                MAX_PARTICIPANTS
+            ));
         }
 
         let role = if self.is_empty() {
@@ -99,9 +139,47 @@ impl RoomData {
         Ok(role)
     }
 
+    /// Adds (or maps) the participant reached through a peer connection
+    ///
+    /// The participant is created as a Guest if not already in the room.
+    pub fn add_remote_participant(&mut self, peer_id: String, name: String) -> Result<(), String> {
+        if self.get_participant(&name).is_none() {
+            self.add_participant(name.clone())?;
+        }
+        self.peers.insert(peer_id, name);
+        Ok(())
+    }
+
+    /// Removes the participant reached through a peer connection
+    pub fn remove_peer(&mut self, peer_id: &str) -> Option<String> {
+        let name = self.peers.remove(peer_id)?;
+        self.remove_participant(&name);
+        Some(name)
+    }
+
+    /// Gets the participant name for a peer user ID
+    pub fn peer_name(&self, peer_id: &str) -> Option<&str> {
+        self.peers.get(peer_id).map(String::as_str)
+    }
+
+    /// Gets a mutable reference to the participant reached through a peer connection
+    pub fn get_peer_participant_mut(&mut self, peer_id: &str) -> Option<&mut Participant> {
+        let name = self.peers.get(peer_id)?.clone();
+        self.get_participant_mut(&name)
+    }
+
+    /// Returns every participant except the local user
+    pub fn remote_participants(&self, user_name: &str) -> Vec<&Participant> {
+        self.participants
+            .iter()
+            .filter(|p| p.name != user_name)
+            .collect()
+    }
+
     /// Removes a participant from the room by name
     pub fn remove_participant(&mut self, name: &str) {
         self.participants.retain(|p| p.name != name);
+        self.peers.retain(|_, peer_name| peer_name != name);
     }
 
     /// Gets a reference to a participant by name
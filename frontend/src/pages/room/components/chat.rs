@@ -0,0 +1,91 @@
+//! Room Chat Component
+//!
+//! Displays the in-call text chat and an input to send new messages.
+
+use crate::events::UiCommand;
+use crate::models::ChatEntry;
+use egui::{Color32, FontId, RichText};
+
+/// Renders the chat window with the message history and input box
+///
+/// # Returns
+/// A `SendChatMessage` command when the user sends a message
+pub fn render_chat_panel(
+    ui: &mut egui::Ui,
+    user_name: &str,
+    messages: &[ChatEntry],
+) -> Option<UiCommand> {
+    let mut command = None;
+
+    egui::Window::new("💬 Chat")
+        .default_width(300.0)
+        .default_height(320.0)
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(20.0, -20.0))
+        .collapsible(true)
+        .resizable(true)
+        .show(ui.ctx(), |ui| {
+            render_messages(ui, user_name, messages);
+            ui.separator();
+            command = render_input(ui);
+        });
+
+    command
+}
+
+/// Renders the message history, scrolled to the latest message
+fn render_messages(ui: &mut egui::Ui, user_name: &str, messages: &[ChatEntry]) {
+    egui::ScrollArea::vertical()
+        .max_height(240.0)
+        .auto_shrink([false, false])
+        .stick_to_bottom(true)
+        .show(ui, |ui| {
+            if messages.is_empty() {
+                ui.label(RichText::new("No messages yet").color(Color32::GRAY));
+            }
+            for message in messages {
+                let color = if message.from == user_name {
+                    Color32::from_rgb(100, 180, 255)
+                } else {
+                    Color32::from_rgb(120, 220, 120)
+                };
+                ui.label(
+                    RichText::new(&message.from)
+                        .font(FontId::proportional(13.0))
+                        .color(color)
+                        .strong(),
+                );
+                ui.label(RichText::new(&message.text).color(Color32::WHITE));
+                ui.add_space(4.0);
+            }
+        });
+}
+
+/// Renders the input box and send button
+fn render_input(ui: &mut egui::Ui) -> Option<UiCommand> {
+    let input_id = egui::Id::new("chat_input");
+    let mut input: String = ui.data_mut(|data| data.get_temp(input_id).unwrap_or_default());
+    let mut send = false;
+
+    ui.horizontal(|ui| {
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut input)
+                .hint_text("Type a message...")
+                .desired_width(ui.available_width() - 60.0),
+        );
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            send = true;
+            response.request_focus();
+        }
+        if ui.button("Send").clicked() {
+            send = true;
+        }
+    });
+
+    let command = if send && !input.trim().is_empty() {
+        Some(UiCommand::SendChatMessage(std::mem::take(&mut input)))
+    } else {
+        None
+    };
+    ui.data_mut(|data| data.insert_temp(input_id, input));
+    command
+}
@@ -1,27 +1,32 @@
 //! Room Control Buttons
 //!
-//! Camera toggle, send file, and exit room buttons.
+//! Camera toggle, screen share, send file, and exit room buttons.
 
 use crate::components::{Button, ButtonVariant};
 use crate::events::UiCommand;
 use crate::models::Participant;
 use egui::Vec2;
 
-/// Renders control buttons (toggle camera, toggle microphone, send file, exit room)
+/// Renders control buttons (toggle camera, share screen, toggle microphone, send file, exit room)
 pub fn render_controls(
     ui: &mut egui::Ui,
     my_participant: Option<&Participant>,
+    screen_sharing: bool,
 ) -> Option<UiCommand> {
     let mut command = None;
 
     ui.horizontal(|ui| {
-        let available_width = ui.available_width() - 740.0; // Adjusted for four buttons
+        let available_width = ui.available_width() - 930.0; // Adjusted for five buttons
         ui.add_space(available_width / 2.0);
 
         if let Some(cmd) = render_camera_toggle(ui, my_participant) {
             command = Some(cmd);
         }
 
+        if let Some(cmd) = render_screen_share_toggle(ui, my_participant, screen_sharing) {
+            command = Some(cmd);
+        }
+
         if let Some(cmd) = render_microphone_toggle(ui, my_participant) {
             command = Some(cmd);
         }
@@ -71,6 +76,35 @@ fn get_camera_button_config(camera_on: bool) -> (&'static str, ButtonVariant) {
     }
 }
 
+/// Renders the screen share toggle button
+fn render_screen_share_toggle(
+    ui: &mut egui::Ui,
+    my_participant: Option<&Participant>,
+    screen_sharing: bool,
+) -> Option<UiCommand> {
+    let _participant = my_participant?;
+
+    let (button_text, button_variant) = if screen_sharing {
+        ("🖥 Stop Sharing", ButtonVariant::Secondary)
+    } else {
+        ("🖥 Share Screen", ButtonVariant::Primary)
+    };
+
+    let clicked = Button::new(button_text)
+        .variant(button_variant)
+        .min_size(Vec2::new(170.0, 50.0))
+        .show(ui)
+        .clicked();
+
+    ui.add_space(20.0);
+
+    if clicked {
+        Some(UiCommand::ToggleScreenShare)
+    } else {
+        None
+    }
+}
+
 /// Renders the microphone toggle button
 fn render_microphone_toggle(
     ui: &mut egui::Ui,
@@ -1,9 +1,10 @@
 //! Room Header Component
 //!
-//! Displays room ID, user role, and settings toggle button.
+//! Displays room ID, user role, settings toggle button and connection status.
 
 use crate::models::{Participant, ParticipantRole};
 use egui::{Color32, FontId, RichText};
+use webrtc::ConnectionState;
 
 /// Renders the room header with room ID, role, and settings button
 pub fn render_header(
@@ -64,3 +65,21 @@ fn render_role_label(ui: &mut egui::Ui, my_participant: Option<&Participant>) {
         );
     }
 }
+
+/// Renders a notice while the media path is interrupted
+pub fn render_connection_status(ui: &mut egui::Ui, ice_state: ConnectionState) {
+    let (text, color) = match ice_state {
+        ConnectionState::Disconnected => ("Reconnecting…", Color32::from_rgb(255, 152, 0)),
+        ConnectionState::Failed => ("Connection lost", Color32::from_rgb(244, 67, 54)),
+        _ => return,
+    };
+
+    ui.horizontal(|ui| {
+        ui.add_space(20.0);
+        ui.label(
+            RichText::new(text)
+                .font(FontId::proportional(18.0))
+                .color(color),
+        );
+    });
+}
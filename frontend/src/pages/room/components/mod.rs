@@ -3,13 +3,15 @@
 //! This module contains reusable UI components for the Room page.
 //! Each component is in its own file for better organization.
 
+mod chat;
 mod controls;
 mod header;
 mod sidebar;
 mod video_grid;
 mod video_placeholder;
 
+pub use chat::render_chat_panel;
 pub use controls::render_controls;
-pub use header::render_header;
+pub use header::{render_connection_status, render_header};
 pub use sidebar::{SIDEBAR_CONSTANT, render_settings_sidebar};
-pub use video_grid::render_video_grid;
+pub use video_grid::{LocalTile, render_video_grid};
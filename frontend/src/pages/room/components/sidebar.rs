@@ -1,11 +1,13 @@
 //! Settings Sidebar
 //!
 //! This module contains the settings sidebar component for camera configuration.
-//! Users can adjust camera device ID and FPS settings using a dropdown selector.
+//! Users can adjust camera device ID and FPS settings using a dropdown selector,
+//! and invite available lobby users into the call.
 
 use crate::components::{Button, ButtonVariant, EmptyState};
 use crate::events::UiCommand;
 use crate::models::Participant;
+use crate::models::protocol::UserInfo;
 use egui::{Color32, ComboBox, FontId, RichText};
 
 const SIDEBAR_WIDTH: f32 = 320.0;
@@ -15,7 +17,9 @@ pub const SIDEBAR_CONSTANT: f32 = SIDEBAR_WIDTH;
 /// Renders the camera settings sidebar
 pub fn render_settings_sidebar(
     ui: &mut egui::Ui,
+    user_name: &str,
     my_participant: Option<&Participant>,
+    users: &[UserInfo],
 ) -> Option<UiCommand> {
     let mut command = None;
 
@@ -40,6 +44,14 @@ pub fn render_settings_sidebar(
 
                 command = handle_settings_changes(ui, &settings, current_device, current_fps);
                 save_temp_settings(ui, settings);
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(10.0);
+
+                if let Some(invite_cmd) = render_invite_section(ui, user_name, users) {
+                    command = Some(invite_cmd);
+                }
             });
         });
 
@@ -80,6 +92,49 @@ fn save_temp_settings(ui: &egui::Ui, settings: (i32, f64)) {
     });
 }
 
+/// Renders the list of available lobby users that can be invited to the call
+fn render_invite_section(
+    ui: &mut egui::Ui,
+    user_name: &str,
+    users: &[UserInfo],
+) -> Option<UiCommand> {
+    let mut command = None;
+
+    ui.label(
+        RichText::new("Invite to Call")
+            .font(FontId::proportional(16.0))
+            .color(Color32::LIGHT_GRAY),
+    );
+    ui.add_space(5.0);
+
+    let available: Vec<&UserInfo> = users
+        .iter()
+        .filter(|u| u.username != user_name && u.state == "Available")
+        .collect();
+
+    if available.is_empty() {
+        ui.label(
+            RichText::new("No available users")
+                .size(12.0)
+                .color(Color32::GRAY),
+        );
+        return None;
+    }
+
+    for user in available {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(&user.username).color(Color32::WHITE));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("Invite").clicked() {
+                    command = Some(UiCommand::CallUser(user.user_id.clone()));
+                }
+            });
+        });
+    }
+
+    command
+}
+
 /// Handles settings changes and returns command if apply is clicked
 fn handle_settings_changes(
     ui: &mut egui::Ui,
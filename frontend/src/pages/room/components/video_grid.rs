@@ -1,69 +1,112 @@
 //! Video Grid Component
 //!
-//! Manages the 2-column layout for local and remote video streams.
+//! Lays out the local and remote video streams in a grid that grows with
+//! the number of participants.
 
 use super::video_placeholder::render_placeholder;
 use crate::models::Participant;
 use egui::{Color32, FontId, RichText, TextureHandle};
+use std::collections::HashMap;
 
-/// Renders the 2-column video grid layout
+/// Tiles per row for rooms with up to four participants
+const SMALL_GRID_COLUMNS: usize = 2;
+
+/// Tiles per row for larger rooms
+const LARGE_GRID_COLUMNS: usize = 3;
+
+/// Microphone level above which the local user is shown as speaking
+const SPEAKING_LEVEL: f32 = 0.35;
+
+/// Colour of the active speaker highlight
+const SPEAKING_COLOR: Color32 = Color32::from_rgb(80, 220, 120);
+
+/// What the local user's tile shows
+pub struct LocalTile<'a> {
+    pub participant: Option<&'a Participant>,
+    pub texture: Option<&'a TextureHandle>,
+    pub screen_sharing: bool,
+    /// Level (0.0 to 1.0) of the local microphone
+    pub audio_level: f32,
+}
+
+/// A single slot in the video grid
+enum Tile<'a> {
+    Mine,
+    Remote(&'a Participant),
+    /// Placeholder shown while nobody else is in the room
+    Waiting,
+}
+
+/// Renders the video grid: my tile first, then one tile per remote participant
 pub fn render_video_grid(
     ui: &mut egui::Ui,
     user_name: &str,
-    my_participant: Option<&Participant>,
-    other_participant: Option<&Participant>,
-    my_texture: Option<&TextureHandle>,
-    other_texture: Option<&TextureHandle>,
+    local: &LocalTile,
+    remote_participants: &[&Participant],
+    remote_textures: Option<&HashMap<String, TextureHandle>>,
 ) {
+    // Mine plus the remote tiles (or the "waiting" tile while alone)
+    let tile_count = 1 + remote_participants.len().max(1);
+    let columns = if tile_count <= 4 {
+        SMALL_GRID_COLUMNS
+    } else {
+        LARGE_GRID_COLUMNS
+    };
+
     let available_width = ui.available_width();
-    let video_width = (available_width - 60.0) / 2.0;
+    let video_width = (available_width - 20.0 * (columns as f32 + 1.0)) / columns as f32;
     let video_height = video_width * 0.75;
 
-    ui.horizontal(|ui| {
-        ui.add_space(20.0);
-
-        // My video
-        render_my_video(
-            ui,
-            user_name,
-            my_participant,
-            my_texture,
-            video_width,
-            video_height,
-        );
+    let mut tiles = vec![Tile::Mine];
+    if remote_participants.is_empty() {
+        tiles.push(Tile::Waiting);
+    } else {
+        tiles.extend(remote_participants.iter().copied().map(Tile::Remote));
+    }
 
-        ui.add_space(20.0);
+    for row in tiles.chunks(columns) {
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
 
-        // Other participant's video
-        render_other_video(
-            ui,
-            other_participant,
-            other_texture,
-            video_width,
-            video_height,
-        );
+            for tile in row {
+                match tile {
+                    Tile::Mine => render_my_video(ui, user_name, local, video_width, video_height),
+                    Tile::Remote(participant) => {
+                        let texture = remote_textures.and_then(|t| t.get(&participant.name));
+                        render_other_video(
+                            ui,
+                            Some(participant),
+                            texture,
+                            video_width,
+                            video_height,
+                        );
+                    }
+                    Tile::Waiting => {
+                        render_other_video(ui, None, None, video_width, video_height);
+                    }
+                }
 
+                ui.add_space(20.0);
+            }
+        });
         ui.add_space(20.0);
-    });
+    }
 }
 
-/// Renders the local user's video frame
-fn render_my_video(
-    ui: &mut egui::Ui,
-    user_name: &str,
-    my_participant: Option<&Participant>,
-    my_texture: Option<&TextureHandle>,
-    width: f32,
-    height: f32,
-) {
+/// Renders the local user's video frame, highlighted while speaking
+fn render_my_video(ui: &mut egui::Ui, user_name: &str, local: &LocalTile, width: f32, height: f32) {
     ui.vertical(|ui| {
         ui.set_width(width);
 
-        let camera_enabled = my_participant.is_some_and(|p| p.camera_on);
+        let camera_enabled = local.screen_sharing || local.participant.is_some_and(|p| p.camera_on);
+        let mic_live = local
+            .participant
+            .is_some_and(|p| p.audio_on && !p.audio_muted);
+        let speaking = mic_live && local.audio_level >= SPEAKING_LEVEL;
         let label = format!("{} (You)", user_name);
 
         if camera_enabled {
-            if let Some(texture) = my_texture {
+            if let Some(texture) = local.texture {
                 // Render video texture
                 ui.image((texture.id(), egui::vec2(width, height)));
             } else {
@@ -73,11 +116,25 @@ fn render_my_video(
             render_placeholder(ui, width, height, "Camera Off");
         }
 
+        if speaking {
+            let tile = egui::Rect::from_min_size(ui.min_rect().min, egui::vec2(width, height));
+            ui.painter().rect_stroke(
+                tile,
+                4.0,
+                egui::Stroke::new(3.0, SPEAKING_COLOR),
+                egui::StrokeKind::Outside,
+            );
+        }
+
         ui.add_space(10.0);
         ui.label(
             RichText::new(label)
                 .font(FontId::proportional(20.0))
-                .color(Color32::WHITE),
+                .color(if speaking {
+                    SPEAKING_COLOR
+                } else {
+                    Color32::WHITE
+                }),
         );
     });
 }
@@ -6,17 +6,26 @@ mod state;
 pub use state::RoomState;
 
 use crate::events::UiCommand;
+use crate::models::protocol::UserInfo;
 use crate::models::{Participant, RoomData};
 use egui::TextureHandle;
+use std::collections::HashMap;
 
 /// Parameters for rendering the room layout
 struct RoomRenderParams<'a> {
     user_name: &'a str,
     room_id: &'a str,
     my_participant: Option<&'a Participant>,
-    other_participant: Option<&'a Participant>,
+    remote_participants: Vec<&'a Participant>,
     my_texture: Option<&'a TextureHandle>,
-    other_texture: Option<&'a TextureHandle>,
+    /// Remote video textures keyed by participant name
+    remote_textures: Option<&'a HashMap<String, TextureHandle>>,
+    /// Lobby users, used to list who can be invited
+    users: &'a [UserInfo],
+    screen_sharing: bool,
+    ice_state: webrtc::ConnectionState,
+    /// Local microphone level (0.0 to 1.0), for the speaking indicator
+    audio_level: f32,
 }
 
 pub struct Room;
@@ -28,14 +37,15 @@ impl Room {
         room_id: &str,
         room_data: Option<&RoomData>,
         my_texture: Option<&TextureHandle>,
-        other_texture: Option<&TextureHandle>,
+        remote_textures: Option<&HashMap<String, TextureHandle>>,
+        users: &[UserInfo],
     ) -> Option<UiCommand> {
         let Some(room) = room_data else {
             return Self::render_error(ui);
         };
 
         let my_participant = room.get_participant(user_name);
-        let other_participant = Self::get_other_participant(room, user_name);
+        let remote_participants = room.remote_participants(user_name);
         let sidebar_open = Self::load_sidebar_state(ui);
         let stats_visible = Self::load_stats_visibility(ui);
         let (device_id, fps) = Self::get_camera_settings(ui, my_participant);
@@ -44,9 +54,13 @@ impl Room {
             user_name,
             room_id,
             my_participant,
-            other_participant,
+            remote_participants,
             my_texture,
-            other_texture,
+            remote_textures,
+            users,
+            screen_sharing: room.screen_sharing,
+            ice_state: room.ice_state,
+            audio_level: room.local_audio_level,
         };
 
         let (command, updated_sidebar_open) =
@@ -65,11 +79,6 @@ impl Room {
         command
     }
 
-    /// Gets the other participant in the room
-    fn get_other_participant<'a>(room: &'a RoomData, user_name: &str) -> Option<&'a Participant> {
-        room.participants.iter().find(|p| p.name != user_name)
-    }
-
     /// Loads sidebar state from UI storage
     fn load_sidebar_state(ui: &egui::Ui) -> bool {
         ui.data(|data| {
@@ -95,10 +104,21 @@ impl Room {
         stats_visible: bool,
     ) -> (Option<UiCommand>, bool) {
         let mut command = None;
+        let user_name = params.user_name;
 
-        Self::render_sidebar(ui, params.my_participant, sidebar_open, &mut command);
+        Self::render_sidebar(
+            ui,
+            params.user_name,
+            params.my_participant,
+            params.users,
+            sidebar_open,
+            &mut command,
+        );
         Self::render_central_panel(ui, params, &mut sidebar_open, &mut command);
         Self::render_stats_panel(ui, room_data, stats_visible);
+        if let Some(chat_cmd) = components::render_chat_panel(ui, user_name, &room_data.chat) {
+            command = Some(chat_cmd);
+        }
 
         (command, sidebar_open)
     }
@@ -106,7 +126,9 @@ impl Room {
     /// Renders the settings sidebar
     fn render_sidebar(
         ui: &mut egui::Ui,
+        user_name: &str,
         my_participant: Option<&Participant>,
+        users: &[UserInfo],
         sidebar_open: bool,
         command: &mut Option<UiCommand>,
     ) {
@@ -114,7 +136,8 @@ impl Room {
             .resizable(false)
             .exact_width(components::SIDEBAR_CONSTANT)
             .show_animated_inside(ui, sidebar_open, |ui| {
-                if let Some(settings_cmd) = components::render_settings_sidebar(ui, my_participant)
+                if let Some(settings_cmd) =
+                    components::render_settings_sidebar(ui, user_name, my_participant, users)
                 {
                     *command = Some(settings_cmd);
                 }
@@ -132,19 +155,27 @@ impl Room {
             ui.vertical(|ui| {
                 ui.add_space(20.0);
                 components::render_header(ui, params.room_id, params.my_participant, sidebar_open);
+                components::render_connection_status(ui, params.ice_state);
 
                 ui.add_space(30.0);
+                let local = components::LocalTile {
+                    participant: params.my_participant,
+                    texture: params.my_texture,
+                    screen_sharing: params.screen_sharing,
+                    audio_level: params.audio_level,
+                };
                 components::render_video_grid(
                     ui,
                     params.user_name,
-                    params.my_participant,
-                    params.other_participant,
-                    params.my_texture,
-                    params.other_texture,
+                    &local,
+                    &params.remote_participants,
+                    params.remote_textures,
                 );
                 ui.add_space(20.0);
 
-                if let Some(control_cmd) = components::render_controls(ui, params.my_participant) {
+                if let Some(control_cmd) =
+                    components::render_controls(ui, params.my_participant, params.screen_sharing)
+                {
                     *command = Some(control_cmd);
                 }
             });
@@ -158,6 +189,7 @@ impl Room {
         // Use real statistics from room data, or defaults if not available
         let stats = room_data.stats.clone().unwrap_or(CallStats {
             bitrate_mbps: 0.0,
+            estimated_bandwidth_mbps: 0.0,
             packet_loss_percent: 0.0,
             jitter_ms: 0.0,
             rtt_ms: 0.0,
@@ -4,12 +4,14 @@
 //! WebRTC connections and frame processing are handled by the logic thread.
 
 use egui::TextureHandle;
+use std::collections::HashMap;
 
 /// State for each room (video textures only)
 /// WebRTC connection is managed in logic thread, not here
 pub struct RoomState {
     pub my_texture: Option<TextureHandle>,
-    pub other_texture: Option<TextureHandle>,
+    /// Latest frame of each remote participant, keyed by participant name
+    pub remote_textures: HashMap<String, TextureHandle>,
 }
 
 impl RoomState {
@@ -17,7 +19,7 @@ impl RoomState {
     pub fn new() -> Self {
         Self {
             my_texture: None,
-            other_texture: None,
+            remote_textures: HashMap::new(),
         }
     }
 }
@@ -0,0 +1,146 @@
+//! Sustitución de variables de entorno en el contenido de configuración.
+
+use crate::error::{ConfigError, Result};
+use std::env;
+
+/// Reemplaza los tokens `${VAR}` y `${VAR:-default}` con valores del entorno.
+///
+/// - `${VAR}`: valor de `VAR`; si no está definida retorna `ConfigError::MissingEnvVar`
+/// - `${VAR:-default}`: valor de `VAR`, o `default` si no está definida o está vacía
+/// - `$${`: produce un `${` literal sin expandir
+///
+/// # Ejemplos
+///
+/// ```
+/// use config_loader::expand_env_vars;
+///
+/// let content = expand_env_vars(r#"{"password": "${ROOMRTC_DOC_UNSET:-changeme}"}"#)?;
+/// assert_eq!(content, r#"{"password": "changeme"}"#);
+/// # Ok::<(), config_loader::ConfigError>(())
+/// ```
+pub fn expand_env_vars(content: &str) -> Result<String> {
+    expand_with(content, |name| env::var(name).ok())
+}
+
+/// Igual que `expand_env_vars` pero obteniendo los valores de `lookup`
+fn expand_with(content: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+
+        if let Some(escaped) = after.strip_prefix("$${") {
+            out.push_str("${");
+            rest = escaped;
+            continue;
+        }
+
+        let Some(token) = after.strip_prefix("${") else {
+            out.push('$');
+            rest = &after[1..];
+            continue;
+        };
+
+        let end = token.find('}').ok_or_else(|| {
+            ConfigError::ParseError(format!("'${{' sin cerrar en '{}'", first_line(after)))
+        })?;
+        let (name, default) = match token[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&token[..end], None),
+        };
+
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(ConfigError::ParseError(format!(
+                "nombre de variable inválido '{}'",
+                name
+            )));
+        }
+
+        let value = match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => default.to_string(),
+            (Some(value), _) => value,
+            (None, Some(default)) => default.to_string(),
+            (None, None) => return Err(ConfigError::MissingEnvVar(name.to_string())),
+        };
+        out.push_str(&value);
+        rest = &token[end + 1..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "TLS_PASSWORD" => Some("s3cret".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_present_var() {
+        let result = expand_with(r#"{"pkcs12_password":"${TLS_PASSWORD}"}"#, lookup).unwrap();
+        assert_eq!(result, r#"{"pkcs12_password":"s3cret"}"#);
+
+        // El default se ignora si la variable existe
+        let result = expand_with("${TLS_PASSWORD:-fallback}", lookup).unwrap();
+        assert_eq!(result, "s3cret");
+    }
+
+    #[test]
+    fn test_missing_var_with_default() {
+        let result = expand_with("port = ${PORT:-8080}", lookup).unwrap();
+        assert_eq!(result, "port = 8080");
+
+        let result = expand_with("user = \"${EMPTY:-guest}\"", lookup).unwrap();
+        assert_eq!(result, "user = \"guest\"");
+
+        let result = expand_with("${PORT:-}", lookup).unwrap();
+        assert_eq!(result, "");
+    }
+
+    #[test]
+    fn test_unset_var_without_default() {
+        let result = expand_with("credential = \"${TURN_CREDENTIAL}\"", lookup);
+        assert!(matches!(
+            result,
+            Err(ConfigError::MissingEnvVar(ref name)) if name == "TURN_CREDENTIAL"
+        ));
+    }
+
+    #[test]
+    fn test_literals_and_malformed_tokens() {
+        assert_eq!(expand_with("cost = $5", lookup).unwrap(), "cost = $5");
+        assert_eq!(
+            expand_with("$${TLS_PASSWORD}", lookup).unwrap(),
+            "${TLS_PASSWORD}"
+        );
+        assert!(matches!(
+            expand_with("${TLS_PASSWORD", lookup),
+            Err(ConfigError::ParseError(_))
+        ));
+        assert!(matches!(
+            expand_with("${BAD NAME}", lookup),
+            Err(ConfigError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_process_environment() {
+        // SAFETY: nombre único, ningún otro test lee ni modifica esta variable
+        unsafe { env::set_var("CONFIG_LOADER_TEST_TOKEN", "from-env") };
+        let result = expand_env_vars("token=${CONFIG_LOADER_TEST_TOKEN}").unwrap();
+        assert_eq!(result, "token=from-env");
+    }
+}
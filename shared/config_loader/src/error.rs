@@ -11,6 +11,12 @@ pub enum ConfigError {
 
     /// Error al leer el archivo
     ReadError(String),
+
+    /// El contenido no tiene un formato válido o no coincide con el struct
+    ParseError(String),
+
+    /// Variable de entorno referenciada con `${VAR}` sin definir ni valor por defecto
+    MissingEnvVar(String),
 }
 
 impl fmt::Display for ConfigError {
@@ -22,6 +28,12 @@ impl fmt::Display for ConfigError {
             ConfigError::ReadError(msg) => {
                 write!(f, "Error al leer archivo de configuración: {}", msg)
             }
+            ConfigError::ParseError(msg) => {
+                write!(f, "Error al parsear archivo de configuración: {}", msg)
+            }
+            ConfigError::MissingEnvVar(name) => {
+                write!(f, "Variable de entorno no definida: {}", name)
+            }
         }
     }
 }
@@ -22,9 +22,15 @@
 //! }
 //! ```
 
+mod env_expansion;
 pub mod error;
+mod toml;
+mod watcher;
 
+pub use env_expansion::expand_env_vars;
 pub use error::{ConfigError, Result};
+pub use toml::parse_toml;
+pub use watcher::{ConfigWatcher, DEFAULT_DEBOUNCE, DEFAULT_POLL_INTERVAL, watch, watch_with};
 
 use std::env;
 use std::fs;
@@ -54,6 +60,25 @@ pub fn load_config_file<P: AsRef<Path>>(path: P) -> Result<String> {
     fs::read_to_string(path).map_err(|e| ConfigError::ReadError(e.to_string()))
 }
 
+/// Carga un archivo de configuración expandiendo variables de entorno.
+///
+/// Igual que `load_config_file`, pero reemplaza `${VAR}` y `${VAR:-default}`
+/// (ver `expand_env_vars`). Permite mantener secretos fuera de los archivos.
+///
+/// # Ejemplos
+///
+/// ```no_run
+/// use config_loader::load_with_env_expansion;
+///
+/// // config.json: {"pkcs12_password": "${TLS_PASSWORD}"}
+/// let content = load_with_env_expansion("./config/config.json")?;
+/// # Ok::<(), config_loader::ConfigError>(())
+/// ```
+pub fn load_with_env_expansion<P: AsRef<Path>>(path: P) -> Result<String> {
+    let content = load_config_file(path)?;
+    expand_env_vars(&content)
+}
+
 /// Busca un archivo de configuración en ubicaciones comunes.
 ///
 /// Busca en el siguiente orden:
@@ -116,6 +141,38 @@ pub fn find_and_load(filename: &str) -> Result<String> {
     load_config_file(path)
 }
 
+/// Carga un archivo TOML y lo deserializa en `T`.
+///
+/// Las variables `${VAR}` se expanden antes de parsear. El TOML se convierte a
+/// un árbol `JsonValue`, así que cualquier struct con `impl_json!` puede
+/// cargarse igual que desde JSON.
+///
+/// # Ejemplos
+///
+/// ```no_run
+/// use config_loader::load_toml;
+/// use json_parser::impl_json;
+///
+/// #[derive(Default)]
+/// struct Server {
+///     port: u32,
+/// }
+///
+/// impl_json! {
+///     Server {
+///         port: u32,
+///     }
+/// }
+///
+/// let server: Server = load_toml("./config/server.toml")?;
+/// # Ok::<(), config_loader::ConfigError>(())
+/// ```
+pub fn load_toml<T: json_parser::Deserialize, P: AsRef<Path>>(path: P) -> Result<T> {
+    let content = load_with_env_expansion(path)?;
+    let value = parse_toml(&content)?;
+    T::deserialize(&value).map_err(|e| ConfigError::ParseError(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,6 +184,13 @@ mod tests {
         assert!(matches!(result, Err(ConfigError::FileNotFound(_))));
     }
 
+    #[test]
+    fn test_load_toml_nonexistent_file() {
+        let result: Result<std::collections::HashMap<String, String>> =
+            load_toml("/path/that/does/not/exist.toml");
+        assert!(matches!(result, Err(ConfigError::FileNotFound(_))));
+    }
+
     #[test]
     fn test_find_nonexistent_file() {
         let result = find_config_file("file_that_definitely_does_not_exist_12345.json");
@@ -0,0 +1,464 @@
+//! Parser TOML mínimo que produce un árbol `JsonValue`.
+//!
+//! Así los structs con `impl_json!` se cargan igual desde JSON o TOML.
+//!
+//! Soporta:
+//! - Tablas (`[server]`, `[a.b]`) y claves con puntos (`a.b = 1`)
+//! - Strings básicos (`"..."` con escapes) y literales (`'...'`)
+//! - Enteros (con signo y `_`), floats y booleanos
+//! - Arrays (también en varias líneas) y tablas inline (`{ k = v }`)
+//! - Comentarios con `#`
+//!
+//! No soporta arrays de tablas (`[[x]]`), strings multilínea ni fechas.
+
+use crate::error::{ConfigError, Result};
+use json_parser::JsonValue;
+use std::collections::HashMap;
+
+type Table = HashMap<String, JsonValue>;
+
+/// Parsea un documento TOML a un `JsonValue::Object`.
+///
+/// # Ejemplos
+///
+/// ```
+/// use config_loader::parse_toml;
+///
+/// let value = parse_toml("[server]\nport = 8080 # comentario\n")?;
+/// assert_eq!(value.get_path("server.port").and_then(|v| v.as_number()), Some(8080.0));
+/// # Ok::<(), config_loader::ConfigError>(())
+/// ```
+pub fn parse_toml(input: &str) -> Result<JsonValue> {
+    let mut root = Table::new();
+    let mut current_path: Vec<String> = Vec::new();
+    let mut lines = input.lines().enumerate();
+
+    while let Some((index, raw_line)) = lines.next() {
+        let line_no = index + 1;
+        let line = strip_comment(raw_line).trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with("[[") {
+            return Err(parse_error(
+                line_no,
+                "arrays de tablas ([[...]]) no soportados",
+            ));
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or_else(|| parse_error(line_no, "falta ']' en el encabezado de tabla"))?;
+            current_path = parse_key(header).map_err(|e| parse_error(line_no, &e))?;
+            table_at(&mut root, &current_path).map_err(|e| parse_error(line_no, &e))?;
+            continue;
+        }
+
+        let (key_part, value_part) = line
+            .split_once('=')
+            .ok_or_else(|| parse_error(line_no, "se esperaba 'clave = valor'"))?;
+        let mut key = parse_key(key_part).map_err(|e| parse_error(line_no, &e))?;
+
+        // Un array puede continuar en las líneas siguientes
+        let mut value_text = value_part.trim().to_string();
+        while !brackets_balanced(&value_text) {
+            let (_, next) = lines
+                .next()
+                .ok_or_else(|| parse_error(line_no, "array sin cerrar"))?;
+            value_text.push(' ');
+            value_text.push_str(strip_comment(next).trim());
+        }
+
+        let value = parse_value(&value_text).map_err(|e| parse_error(line_no, &e))?;
+
+        let name = key.pop().unwrap_or_default();
+        let mut path = current_path.clone();
+        path.extend(key);
+        let table = table_at(&mut root, &path).map_err(|e| parse_error(line_no, &e))?;
+        if table.contains_key(&name) {
+            return Err(parse_error(line_no, &format!("clave duplicada '{}'", name)));
+        }
+        table.insert(name, value);
+    }
+
+    Ok(JsonValue::Object(root))
+}
+
+fn parse_error(line_no: usize, msg: &str) -> ConfigError {
+    ConfigError::ParseError(format!("línea {}: {}", line_no, msg))
+}
+
+/// Devuelve la tabla en `path`, creando las intermedias que falten
+fn table_at<'a>(
+    root: &'a mut Table,
+    path: &[String],
+) -> std::result::Result<&'a mut Table, String> {
+    let mut table = root;
+    for segment in path {
+        let entry = table
+            .entry(segment.clone())
+            .or_insert_with(|| JsonValue::Object(Table::new()));
+        table = match entry {
+            JsonValue::Object(inner) => inner,
+            _ => return Err(format!("'{}' ya está definido y no es una tabla", segment)),
+        };
+    }
+    Ok(table)
+}
+
+/// Elimina un comentario `#` que no esté dentro de un string
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+/// Indica si todos los `[`/`{` abiertos fuera de strings están cerrados
+fn brackets_balanced(text: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in text.chars() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '[' | '{' => depth += 1,
+                ']' | '}' => depth -= 1,
+                _ => {}
+            },
+        }
+    }
+    depth <= 0
+}
+
+/// Parsea una clave simple, entre comillas o con puntos (`a."b.c".d`)
+fn parse_key(text: &str) -> std::result::Result<Vec<String>, String> {
+    let mut cursor = Cursor::new(text.trim());
+    let mut segments = Vec::new();
+    loop {
+        cursor.skip_whitespace();
+        let segment = match cursor.peek() {
+            Some('"') => cursor.parse_basic_string()?,
+            Some('\'') => cursor.parse_literal_string()?,
+            _ => cursor.take_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+        };
+        if segment.is_empty() {
+            return Err(format!("clave inválida '{}'", text.trim()));
+        }
+        segments.push(segment);
+
+        cursor.skip_whitespace();
+        match cursor.next() {
+            None => return Ok(segments),
+            Some('.') => continue,
+            Some(c) => return Err(format!("carácter inesperado '{}' en la clave", c)),
+        }
+    }
+}
+
+fn parse_value(text: &str) -> std::result::Result<JsonValue, String> {
+    let mut cursor = Cursor::new(text);
+    let value = cursor.parse_value()?;
+    cursor.skip_whitespace();
+    match cursor.peek() {
+        None => Ok(value),
+        Some(c) => Err(format!("carácter inesperado '{}' después del valor", c)),
+    }
+}
+
+/// Recorre un valor TOML carácter por carácter
+struct Cursor<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        self.chars.next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> String {
+        let mut out = String::new();
+        while let Some(c) = self.peek().filter(|c| pred(*c)) {
+            out.push(c);
+            self.next();
+        }
+        out
+    }
+
+    fn parse_value(&mut self) -> std::result::Result<JsonValue, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') => self.parse_basic_string().map(JsonValue::String),
+            Some('\'') => self.parse_literal_string().map(JsonValue::String),
+            Some('[') => self.parse_array(),
+            Some('{') => self.parse_inline_table(),
+            Some(_) => self.parse_scalar(),
+            None => Err("falta el valor".to_string()),
+        }
+    }
+
+    fn parse_basic_string(&mut self) -> std::result::Result<String, String> {
+        self.next(); // comilla de apertura
+        let mut out = String::new();
+        loop {
+            match self.next() {
+                None => return Err("string sin cerrar".to_string()),
+                Some('"') => return Ok(out),
+                Some('\\') => match self.next() {
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some('u') => {
+                        let hex: String = (0..4).filter_map(|_| self.next()).collect();
+                        let c = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("escape unicode inválido '\\u{}'", hex))?;
+                        out.push(c);
+                    }
+                    Some(c) => return Err(format!("escape inválido '\\{}'", c)),
+                    None => return Err("string sin cerrar".to_string()),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn parse_literal_string(&mut self) -> std::result::Result<String, String> {
+        self.next(); // comilla de apertura
+        let mut out = String::new();
+        loop {
+            match self.next() {
+                None => return Err("string sin cerrar".to_string()),
+                Some('\'') => return Ok(out),
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> std::result::Result<JsonValue, String> {
+        self.next(); // '['
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.next();
+                return Ok(JsonValue::Array(items));
+            }
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(JsonValue::Array(items)),
+                _ => return Err("se esperaba ',' o ']' en el array".to_string()),
+            }
+        }
+    }
+
+    fn parse_inline_table(&mut self) -> std::result::Result<JsonValue, String> {
+        self.next(); // '{'
+        let mut table = Table::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some('}') {
+                self.next();
+                return Ok(JsonValue::Object(table));
+            }
+            let key = match self.peek() {
+                Some('"') => self.parse_basic_string()?,
+                Some('\'') => self.parse_literal_string()?,
+                _ => self.take_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+            };
+            if key.is_empty() {
+                return Err("clave inválida en tabla inline".to_string());
+            }
+            self.skip_whitespace();
+            if self.next() != Some('=') {
+                return Err(format!("se esperaba '=' después de '{}'", key));
+            }
+            let value = self.parse_value()?;
+            if table.insert(key.clone(), value).is_some() {
+                return Err(format!("clave duplicada '{}'", key));
+            }
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(JsonValue::Object(table)),
+                _ => return Err("se esperaba ',' o '}' en la tabla inline".to_string()),
+            }
+        }
+    }
+
+    /// Booleanos y números
+    fn parse_scalar(&mut self) -> std::result::Result<JsonValue, String> {
+        let token = self.take_while(|c| !c.is_whitespace() && !matches!(c, ',' | ']' | '}'));
+        match token.as_str() {
+            "true" => return Ok(JsonValue::Bool(true)),
+            "false" => return Ok(JsonValue::Bool(false)),
+            _ => {}
+        }
+
+        let digits = token.replace('_', "");
+        let is_float = digits.contains(['.', 'e', 'E']);
+        let number = if is_float {
+            digits.parse::<f64>().ok()
+        } else {
+            digits.parse::<i64>().ok().map(|n| n as f64)
+        };
+        number
+            .map(JsonValue::Number)
+            .ok_or_else(|| format!("valor no soportado '{}'", token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_and_scalars() {
+        let value = parse_toml(
+            r#"
+            # Configuración del servidor
+            [server]
+            bind_address = "0.0.0.0"  # todas las interfaces
+            port = 8_080
+            enable_tls = false
+            ratio = 0.5
+
+            [logging]
+            log_level = 'debug'
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value
+                .get_path("server.bind_address")
+                .and_then(|v| v.as_string()),
+            Some("0.0.0.0")
+        );
+        assert_eq!(
+            value.get_path("server.port").and_then(|v| v.as_number()),
+            Some(8080.0)
+        );
+        assert_eq!(
+            value
+                .get_path("server.enable_tls")
+                .and_then(|v| v.as_bool()),
+            Some(false)
+        );
+        assert_eq!(
+            value.get_path("server.ratio").and_then(|v| v.as_number()),
+            Some(0.5)
+        );
+        assert_eq!(
+            value
+                .get_path("logging.log_level")
+                .and_then(|v| v.as_string()),
+            Some("debug")
+        );
+    }
+
+    #[test]
+    fn test_arrays_and_inline_tables() {
+        let value = parse_toml(
+            r#"
+            ports = [5000, 6000,]
+            stun = [
+                "stun.l.google.com:19302", # principal
+                "stun1.l.google.com:19302",
+            ]
+            turn = { url = "turn:example.com:3478", username = "user" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value
+                .get_path("ports")
+                .and_then(|v| v.as_array())
+                .map(|a| a.len()),
+            Some(2)
+        );
+        assert_eq!(
+            value
+                .get_path("stun")
+                .and_then(|v| v.as_array())
+                .map(|a| a.len()),
+            Some(2)
+        );
+        assert_eq!(
+            value.get_path("turn.username").and_then(|v| v.as_string()),
+            Some("user")
+        );
+    }
+
+    #[test]
+    fn test_dotted_keys_and_strings() {
+        let value = parse_toml(
+            r#"
+            [a.b]
+            c.d = -3
+            "quoted key" = "tab\there # no es comentario"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.get_path("a.b.c.d").and_then(|v| v.as_number()),
+            Some(-3.0)
+        );
+        let obj = value.get_path("a.b").and_then(|v| v.as_object()).unwrap();
+        assert_eq!(
+            obj.get("quoted key").and_then(|v| v.as_string()),
+            Some("tab\there # no es comentario")
+        );
+    }
+
+    #[test]
+    fn test_errors_report_line() {
+        let err = parse_toml("[server]\nport = 1\nport = 2\n").unwrap_err();
+        assert!(err.to_string().contains("línea 3"), "{}", err);
+
+        assert!(parse_toml("key = \"unterminated\n").is_err());
+        assert!(parse_toml("just a line\n").is_err());
+        assert!(parse_toml("date = 1979-05-27\n").is_err());
+        assert!(parse_toml("[[servers]]\n").is_err());
+    }
+}
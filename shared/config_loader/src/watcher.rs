@@ -0,0 +1,203 @@
+//! Recarga en caliente: vigila un archivo de configuración y notifica cambios.
+
+use crate::error::{ConfigError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Cada cuánto se consulta la fecha de modificación del archivo
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Tiempo sin nuevas escrituras antes de notificar un cambio
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Handle de un watcher activo. Al hacer `stop()` o `drop` el hilo termina.
+pub struct ConfigWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ConfigWatcher {
+    /// Detiene el watcher y espera a que su hilo termine
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Vigila `path` y llama a `callback` con el contenido nuevo cuando cambia.
+///
+/// Usa `DEFAULT_POLL_INTERVAL` y `DEFAULT_DEBOUNCE`; ver `watch_with`.
+///
+/// # Ejemplos
+///
+/// ```no_run
+/// use config_loader::watch;
+///
+/// let watcher = watch("server_config.json", |content| {
+///     println!("Nueva configuración: {} bytes", content.len());
+/// })?;
+///
+/// // ...
+/// watcher.stop();
+/// # Ok::<(), config_loader::ConfigError>(())
+/// ```
+pub fn watch<P, F>(path: P, callback: F) -> Result<ConfigWatcher>
+where
+    P: AsRef<Path>,
+    F: FnMut(String) + Send + 'static,
+{
+    watch_with(path, DEFAULT_POLL_INTERVAL, DEFAULT_DEBOUNCE, callback)
+}
+
+/// Igual que `watch` con intervalo de polling y debounce configurables.
+///
+/// El archivo se consulta cada `poll_interval`. Al detectar un cambio de fecha
+/// de modificación o tamaño, se espera a que pase `debounce` sin nuevos cambios
+/// (varias escrituras seguidas cuentan como una) y recién entonces se lee. El
+/// callback solo se invoca si el contenido difiere del último notificado.
+///
+/// Retorna `ConfigError::FileNotFound` si el archivo no existe al empezar.
+pub fn watch_with<P, F>(
+    path: P,
+    poll_interval: Duration,
+    debounce: Duration,
+    mut callback: F,
+) -> Result<ConfigWatcher>
+where
+    P: AsRef<Path>,
+    F: FnMut(String) + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let mut last_stamp =
+        file_stamp(&path).ok_or_else(|| ConfigError::FileNotFound(path.display().to_string()))?;
+    let mut last_content = fs::read_to_string(&path).ok();
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = Arc::clone(&stop);
+
+    let handle = thread::spawn(move || {
+        // Momento del último cambio observado que aún no se notificó
+        let mut pending_since: Option<Instant> = None;
+
+        while !thread_stop.load(Ordering::SeqCst) {
+            thread::sleep(poll_interval);
+
+            if let Some(stamp) = file_stamp(&path)
+                && stamp != last_stamp
+            {
+                last_stamp = stamp;
+                pending_since = Some(Instant::now());
+                continue;
+            }
+
+            let Some(since) = pending_since else {
+                continue;
+            };
+            if since.elapsed() < debounce {
+                continue;
+            }
+
+            // Si la lectura falla (archivo reemplazándose) se reintenta luego
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            pending_since = None;
+
+            if last_content.as_deref() != Some(content.as_str()) {
+                last_content = Some(content.clone());
+                callback(content);
+            }
+        }
+    });
+
+    Ok(ConfigWatcher {
+        stop,
+        handle: Some(handle),
+    })
+}
+
+/// Fecha de modificación y tamaño; `None` si el archivo no es accesible
+fn file_stamp(path: &PathBuf) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    const POLL: Duration = Duration::from_millis(20);
+    const DEBOUNCE: Duration = Duration::from_millis(150);
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_callback_fires_once_with_updated_content() {
+        let path = temp_file("config_loader_watch.json", r#"{"log_level":"info"}"#);
+        let (tx, rx) = mpsc::channel();
+
+        let watcher = watch_with(&path, POLL, DEBOUNCE, move |content| {
+            tx.send(content).unwrap();
+        })
+        .unwrap();
+
+        // Escrituras rápidas sucesivas: solo debe notificarse la última
+        thread::sleep(Duration::from_millis(50));
+        fs::write(&path, r#"{"log_level":"warn"}"#).unwrap();
+        thread::sleep(Duration::from_millis(30));
+        fs::write(&path, r#"{"log_level":"debug","x":1}"#).unwrap();
+
+        let content = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(content, r#"{"log_level":"debug","x":1}"#);
+        assert!(
+            rx.recv_timeout(DEBOUNCE * 3).is_err(),
+            "callback fired twice"
+        );
+
+        watcher.stop();
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_stop_ends_watching() {
+        let path = temp_file("config_loader_watch_stop.json", "a = 1");
+        let (tx, rx) = mpsc::channel();
+
+        let watcher = watch_with(&path, POLL, DEBOUNCE, move |content| {
+            let _ = tx.send(content);
+        })
+        .unwrap();
+        watcher.stop();
+
+        fs::write(&path, "a = 2").unwrap();
+        assert!(rx.recv_timeout(DEBOUNCE * 3).is_err());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let result = watch("/path/that/does/not/exist.json", |_| {});
+        assert!(matches!(result, Err(ConfigError::FileNotFound(_))));
+    }
+}
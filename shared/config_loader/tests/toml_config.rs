@@ -0,0 +1,126 @@
+//! Carga de un `server_config.toml` representativo en structs con `impl_json!`
+
+use config_loader::{ConfigError, load_toml};
+use json_parser::impl_json;
+
+#[derive(Debug, Default)]
+struct ServerSection {
+    bind_address: String,
+    port: u32,
+    enable_tls: bool,
+    pkcs12_path: Option<String>,
+    allowed_origins: Vec<String>,
+}
+
+impl_json! {
+    ServerSection {
+        bind_address: String,
+        port: u32,
+        enable_tls: bool,
+        pkcs12_path: Option<String>,
+        allowed_origins: Vec<String>,
+    }
+}
+
+#[derive(Debug, Default)]
+struct LoggingSection {
+    log_file_path: String,
+    log_level: String,
+    enable_console: bool,
+}
+
+impl_json! {
+    LoggingSection {
+        log_file_path: String,
+        log_level: String,
+        enable_console: bool,
+    }
+}
+
+#[derive(Debug, Default)]
+struct AppConfig {
+    server: ServerSection,
+    logging: LoggingSection,
+}
+
+impl_json! {
+    AppConfig {
+        server: ServerSection,
+        logging: LoggingSection,
+    }
+}
+
+const SERVER_CONFIG_TOML: &str = r#"
+# RoomRTC server
+
+[server]
+bind_address = "0.0.0.0"
+port = 8443
+enable_tls = true
+pkcs12_path = "identity.pfx"   # certificado
+allowed_origins = [
+    "https://room.example.com",
+    "https://backup.example.com",
+]
+
+[logging]
+log_file_path = "roomrtc-server.log"
+log_level = "debug"
+enable_console = false
+"#;
+
+fn write_temp(name: &str, content: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_load_server_config_toml() {
+    let path = write_temp("config_loader_server_config.toml", SERVER_CONFIG_TOML);
+
+    let config: AppConfig = load_toml(&path).unwrap();
+
+    assert_eq!(config.server.bind_address, "0.0.0.0");
+    assert_eq!(config.server.port, 8443);
+    assert!(config.server.enable_tls);
+    assert_eq!(config.server.pkcs12_path.as_deref(), Some("identity.pfx"));
+    assert_eq!(
+        config.server.allowed_origins,
+        vec!["https://room.example.com", "https://backup.example.com"]
+    );
+    assert_eq!(config.logging.log_file_path, "roomrtc-server.log");
+    assert_eq!(config.logging.log_level, "debug");
+    assert!(!config.logging.enable_console);
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_load_toml_missing_table_uses_defaults() {
+    let path = write_temp(
+        "config_loader_partial.toml",
+        "[logging]\nlog_level = \"warn\"\n",
+    );
+
+    let config: AppConfig = load_toml(&path).unwrap();
+
+    assert_eq!(config.server.port, 0);
+    assert_eq!(config.server.pkcs12_path, None);
+    assert_eq!(config.logging.log_level, "warn");
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_load_toml_type_mismatch() {
+    let path = write_temp(
+        "config_loader_bad_type.toml",
+        "[server]\nport = \"not a number\"\n",
+    );
+
+    let result: Result<AppConfig, _> = load_toml(&path);
+    assert!(matches!(result, Err(ConfigError::ParseError(_))));
+
+    let _ = std::fs::remove_file(path);
+}
@@ -9,6 +9,13 @@ pub trait Deserialize: Sized {
     /// Deserializes a value from a JSON value.
     fn deserialize(value: &JsonValue) -> Result<Self, JsonError>;
 
+    /// Value for a struct field whose key is absent from the JSON object.
+    ///
+    /// `None` keeps the struct's `Default` value for that field.
+    fn deserialize_missing() -> Option<Self> {
+        None
+    }
+
     /// Deserializes from a JSON string.
     fn from_str(s: &str) -> Result<Self, JsonError> {
         let value = crate::parse_json(s)?;
@@ -87,16 +94,32 @@ impl Deserialize for usize {
     }
 }
 
+/// `null` deserializes to an empty vector; every element must match `T`.
 impl<T: Deserialize> Deserialize for Vec<T> {
     fn deserialize(value: &JsonValue) -> Result<Self, JsonError> {
+        if value.is_null() {
+            return Ok(Vec::new());
+        }
+
         let arr = value
             .as_array()
             .ok_or_else(|| JsonError::TypeMismatch("Expected array".to_string()))?;
 
-        arr.iter().map(|v| T::deserialize(v)).collect()
+        arr.iter()
+            .enumerate()
+            .map(|(i, v)| {
+                T::deserialize(v).map_err(|e| match e {
+                    JsonError::TypeMismatch(msg) => {
+                        JsonError::TypeMismatch(format!("{} at index {}", msg, i))
+                    }
+                    other => other,
+                })
+            })
+            .collect()
     }
 }
 
+/// Both `null` and a missing key deserialize to `None`.
 impl<T: Deserialize> Deserialize for Option<T> {
     fn deserialize(value: &JsonValue) -> Result<Self, JsonError> {
         if value.is_null() {
@@ -105,10 +128,19 @@ impl<T: Deserialize> Deserialize for Option<T> {
             Ok(Some(T::deserialize(value)?))
         }
     }
+
+    fn deserialize_missing() -> Option<Self> {
+        Some(None)
+    }
 }
 
+/// `null` deserializes to an empty map.
 impl<V: Deserialize> Deserialize for HashMap<String, V> {
     fn deserialize(value: &JsonValue) -> Result<Self, JsonError> {
+        if value.is_null() {
+            return Ok(HashMap::new());
+        }
+
         let obj = value
             .as_object()
             .ok_or_else(|| JsonError::TypeMismatch("Expected object".to_string()))?;
@@ -119,6 +151,22 @@ impl<V: Deserialize> Deserialize for HashMap<String, V> {
     }
 }
 
+/// Deserializes a required field of a tagged enum variant.
+///
+/// Absent keys use `Deserialize::deserialize_missing`, so `Option` fields may be omitted.
+#[doc(hidden)]
+pub fn deserialize_variant_field<T: Deserialize>(
+    obj: &HashMap<String, JsonValue>,
+    field: &str,
+    variant: &str,
+) -> Result<T, JsonError> {
+    match obj.get(field) {
+        Some(value) => T::deserialize(value),
+        None => T::deserialize_missing()
+            .ok_or_else(|| JsonError::MissingField(format!("{}.{}", variant, field))),
+    }
+}
+
 /// Helper function to deserialize from a JSON string
 pub fn from_str<T: Deserialize>(s: &str) -> Result<T, JsonError> {
     T::from_str(s)
@@ -174,6 +222,15 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_deserialize_missing() {
+        assert_eq!(
+            <Option<String> as Deserialize>::deserialize_missing(),
+            Some(None)
+        );
+        assert_eq!(<String as Deserialize>::deserialize_missing(), None);
+    }
+
     #[test]
     fn test_from_str() {
         let json_str = r#""hello""#;
@@ -229,6 +286,28 @@ mod tests {
         assert_eq!(result, HashMap::new());
     }
 
+    #[test]
+    fn test_deserialize_null_collections_as_empty() {
+        let result: Vec<i32> = Deserialize::deserialize(&JsonValue::Null).unwrap();
+        assert!(result.is_empty());
+
+        let result: HashMap<String, i32> = Deserialize::deserialize(&JsonValue::Null).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_heterogeneous_array_reports_index() {
+        let json = JsonValue::Array(vec![
+            JsonValue::Number(1.0),
+            JsonValue::String("two".to_string()),
+        ]);
+        let result: Result<Vec<i32>, _> = Deserialize::deserialize(&json);
+        match result {
+            Err(JsonError::TypeMismatch(msg)) => assert!(msg.ends_with("at index 1"), "{}", msg),
+            other => panic!("Expected type mismatch, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_deserialize_mixed_types_in_option() {
         // Option with string
@@ -32,6 +32,12 @@ pub enum JsonError {
     TypeMismatch(String),
     /// Missing required field during deserialization
     MissingField(String),
+    /// Tag value does not name any variant of a tagged enum
+    UnknownVariant(String),
+    /// Reading from the underlying stream failed
+    Io(String),
+    /// Input bytes are not valid UTF-8
+    InvalidUtf8(String),
 }
 
 impl std::fmt::Display for JsonError {
@@ -56,6 +62,9 @@ impl std::fmt::Display for JsonError {
             JsonError::TrailingCharacters => write!(f, "Trailing characters after JSON value"),
             JsonError::TypeMismatch(s) => write!(f, "Type mismatch: {}", s),
             JsonError::MissingField(s) => write!(f, "Missing required field: {}", s),
+            JsonError::UnknownVariant(s) => write!(f, "Unknown variant: {}", s),
+            JsonError::Io(s) => write!(f, "I/O error: {}", s),
+            JsonError::InvalidUtf8(s) => write!(f, "Invalid UTF-8: {}", s),
         }
     }
 }
@@ -76,5 +85,8 @@ mod tests {
 
         let err = JsonError::DuplicateKey("name".to_string());
         assert_eq!(err.to_string(), "Duplicate object key: name");
+
+        let err = JsonError::UnknownVariant("Foo".to_string());
+        assert_eq!(err.to_string(), "Unknown variant: Foo");
     }
 }
@@ -39,10 +39,12 @@ pub mod macros;
 mod parser;
 pub mod ser;
 mod serializer;
+mod stream;
 mod value;
 
 pub use de::{Deserialize, from_str};
 pub use error::{JsonError, Result};
 pub use parser::parse_json;
 pub use ser::{Serialize, to_string, to_string_pretty};
+pub use stream::parse_from_reader;
 pub use value::JsonValue;
@@ -33,6 +33,25 @@
 /// // config.host will be "127.0.0.1" (default)
 /// // config.port will be 3000 (from json)
 /// ```
+///
+/// `Option<T>` fields are `None` when the key is missing or `null`, regardless
+/// of the struct's `Default`, and serialize `None` as `null`.
+///
+/// Fields may be other `impl_json!` types or `Vec`s of them. A `null` nested
+/// struct deserializes to its `Default`, a `null` array to an empty `Vec`:
+///
+/// ```ignore
+/// impl_json! {
+///     RoomData {
+///         room_id: String,
+///         owner: Participant,
+///         participants: Vec<Participant>,
+///     }
+/// }
+///
+/// let json = r#"{"room_id":"ABC","owner":null,"participants":[{"name":"Alice"}]}"#;
+/// let room: RoomData = from_str(json).unwrap();
+/// ```
 #[macro_export]
 macro_rules! impl_json {
     ($struct_name:ident { $($field:ident: $field_ty:ty),* $(,)? }) => {
@@ -53,6 +72,11 @@ macro_rules! impl_json {
         // Implement Deserialize with default values
         impl $crate::Deserialize for $struct_name {
             fn deserialize(value: &$crate::JsonValue) -> Result<Self, $crate::JsonError> {
+                // A null nested object maps to the default value
+                if value.is_null() {
+                    return Ok(Self::default());
+                }
+
                 let obj = value
                     .as_object()
                     .ok_or_else(|| $crate::JsonError::TypeMismatch(
@@ -62,10 +86,17 @@ macro_rules! impl_json {
                 // Start with default values
                 let mut result = Self::default();
 
-                // Update fields that are present in JSON
+                // Update fields that are present in JSON; missing `Option` fields become `None`
                 $(
-                    if let Some(field_value) = obj.get(stringify!($field)) {
-                        result.$field = <$field_ty as $crate::Deserialize>::deserialize(field_value)?;
+                    match obj.get(stringify!($field)) {
+                        Some(field_value) => {
+                            result.$field = <$field_ty as $crate::Deserialize>::deserialize(field_value)?;
+                        }
+                        None => {
+                            if let Some(missing) = <$field_ty as $crate::Deserialize>::deserialize_missing() {
+                                result.$field = missing;
+                            }
+                        }
                     }
                 )*
 
@@ -138,3 +169,100 @@ macro_rules! impl_json_enum {
         }
     };
 }
+
+/// Macro to implement Serialize and Deserialize for an internally tagged enum.
+///
+/// The variant name is stored under the given tag key and struct-like variant
+/// fields sit next to it in the same object. Unit variants carry only the tag.
+///
+/// # Examples
+///
+/// ```ignore
+/// use json_parser::impl_json_tagged;
+///
+/// enum Request {
+///     UserList,
+///     Login { username: String, password_hash: String },
+/// }
+///
+/// impl_json_tagged! {
+///     Request, tag = "type" {
+///         UserList,
+///         Login { username: String, password_hash: String },
+///     }
+/// }
+///
+/// // Serializes to: {"type":"Login","username":"alice","password_hash":"..."}
+/// // and {"type":"UserList"}
+/// ```
+///
+/// Missing fields fail with `JsonError::MissingField` (except `Option` fields,
+/// which become `None`); an unrecognised tag fails with `JsonError::UnknownVariant`.
+#[macro_export]
+macro_rules! impl_json_tagged {
+    ($enum_name:ident, tag = $tag:literal {
+        $($variant:ident $({ $($field:ident: $field_ty:ty),* $(,)? })?),* $(,)?
+    }) => {
+        // Implement Serialize
+        impl $crate::Serialize for $enum_name {
+            fn serialize(&self) -> $crate::JsonValue {
+                let mut map = std::collections::HashMap::new();
+                match self {
+                    $(
+                        $enum_name::$variant $({ $($field),* })? => {
+                            map.insert(
+                                $tag.to_string(),
+                                $crate::JsonValue::String(stringify!($variant).to_string()),
+                            );
+                            $($(
+                                map.insert(
+                                    stringify!($field).to_string(),
+                                    $crate::Serialize::serialize($field),
+                                );
+                            )*)?
+                        }
+                    )*
+                }
+                $crate::JsonValue::Object(map)
+            }
+        }
+
+        // Implement Deserialize, dispatching on the tag value
+        impl $crate::Deserialize for $enum_name {
+            fn deserialize(value: &$crate::JsonValue) -> Result<Self, $crate::JsonError> {
+                let obj = value
+                    .as_object()
+                    .ok_or_else(|| $crate::JsonError::TypeMismatch(
+                        format!("Expected object for enum {}", stringify!($enum_name))
+                    ))?;
+
+                let tag = obj
+                    .get($tag)
+                    .ok_or_else(|| $crate::JsonError::MissingField(
+                        format!("{} (tag of {})", $tag, stringify!($enum_name))
+                    ))?
+                    .as_string()
+                    .ok_or_else(|| $crate::JsonError::TypeMismatch(
+                        format!("Expected string tag '{}' for enum {}", $tag, stringify!($enum_name))
+                    ))?;
+
+                match tag {
+                    $(
+                        stringify!($variant) => Ok($enum_name::$variant $({
+                            $(
+                                $field: $crate::de::deserialize_variant_field::<$field_ty>(
+                                    obj,
+                                    stringify!($field),
+                                    stringify!($variant),
+                                )?,
+                            )*
+                        })?),
+                    )*
+                    _ => Err($crate::JsonError::UnknownVariant(
+                        format!("'{}' for enum {}", tag, stringify!($enum_name))
+                    )),
+                }
+            }
+        }
+    };
+}
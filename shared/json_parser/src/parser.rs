@@ -400,8 +400,8 @@ mod tests {
 
     #[test]
     fn test_parse_float() {
-        let json = r#"3.14"#;
-        let result = parse_json(json).unwrap();
+        let json = PI.to_string();
+        let result = parse_json(&json).unwrap();
         assert_eq!(result.as_number(), Some(PI));
     }
 
@@ -0,0 +1,216 @@
+//! Incremental JSON parsing from a byte stream.
+
+use crate::error::JsonError;
+use crate::parser::parse_json;
+use crate::value::JsonValue;
+use std::io::{ErrorKind, Read};
+
+/// Reads exactly one JSON value from `reader` and parses it.
+///
+/// Bytes are consumed one at a time up to the end of the value, so anything
+/// after it stays in the reader for the next call. Wrap unbuffered sources
+/// (e.g. a `TcpStream`) in a `BufReader` and pass `&mut` to it to avoid a
+/// syscall per byte.
+///
+/// Objects, arrays, strings and literals end on their closing byte. A
+/// top-level number has no closing byte, so it ends at the first whitespace
+/// byte (which is consumed) or at end of input.
+///
+/// # Errors
+///
+/// - `EmptyInput` if the reader is exhausted before any value starts
+/// - `UnexpectedEndOfInput` if it is exhausted in the middle of a value
+/// - `Io` for read failures, `InvalidUtf8` for non UTF-8 bytes
+/// - any parse error for malformed JSON
+///
+/// # Examples
+///
+/// ```
+/// use json_parser::parse_from_reader;
+///
+/// let mut input = r#"{"id":1} {"id":2}"#.as_bytes();
+/// let first = parse_from_reader(&mut input).unwrap();
+/// let second = parse_from_reader(&mut input).unwrap();
+/// assert_eq!(first.get_path("id").and_then(|v| v.as_number()), Some(1.0));
+/// assert_eq!(second.get_path("id").and_then(|v| v.as_number()), Some(2.0));
+/// ```
+pub fn parse_from_reader<R: Read>(reader: &mut R) -> Result<JsonValue, JsonError> {
+    let mut scanner = ValueScanner::new(reader);
+    let bytes = scanner.scan_value()?;
+
+    let text = String::from_utf8(bytes).map_err(|e| JsonError::InvalidUtf8(e.to_string()))?;
+    parse_json(&text)
+}
+
+/// Collects the bytes of one JSON value without interpreting them
+struct ValueScanner<'a, R: Read> {
+    reader: &'a mut R,
+    buffer: Vec<u8>,
+}
+
+impl<'a, R: Read> ValueScanner<'a, R> {
+    fn new(reader: &'a mut R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn scan_value(&mut self) -> Result<Vec<u8>, JsonError> {
+        let first = loop {
+            match self.next_byte()? {
+                None => return Err(JsonError::EmptyInput),
+                Some(b) if b.is_ascii_whitespace() => continue,
+                Some(b) => break b,
+            }
+        };
+        self.buffer.push(first);
+
+        match first {
+            b'{' | b'[' => self.scan_container()?,
+            b'"' => self.scan_string()?,
+            b't' | b'n' => self.scan_exact(3)?,
+            b'f' => self.scan_exact(4)?,
+            b'-' | b'0'..=b'9' => self.scan_number()?,
+            other => return Err(JsonError::UnexpectedCharacter(other as char, 0)),
+        }
+
+        Ok(std::mem::take(&mut self.buffer))
+    }
+
+    /// Scan to the bracket closing the one already in the buffer
+    fn scan_container(&mut self) -> Result<(), JsonError> {
+        let mut depth = 1usize;
+        while depth > 0 {
+            let b = self.require_byte()?;
+            match b {
+                b'"' => self.scan_string()?,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Scan to the closing quote of a string whose opening quote was consumed
+    fn scan_string(&mut self) -> Result<(), JsonError> {
+        loop {
+            match self.require_byte()? {
+                b'\\' => {
+                    self.require_byte()?;
+                }
+                b'"' => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    fn scan_exact(&mut self, count: usize) -> Result<(), JsonError> {
+        for _ in 0..count {
+            self.require_byte()?;
+        }
+        Ok(())
+    }
+
+    fn scan_number(&mut self) -> Result<(), JsonError> {
+        while let Some(b) = self.next_byte()? {
+            if b.is_ascii_whitespace() {
+                break;
+            }
+            self.buffer.push(b);
+        }
+        Ok(())
+    }
+
+    /// Read and buffer a byte that must exist
+    fn require_byte(&mut self) -> Result<u8, JsonError> {
+        let b = self.next_byte()?.ok_or(JsonError::UnexpectedEndOfInput)?;
+        self.buffer.push(b);
+        Ok(b)
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, JsonError> {
+        let mut byte = [0u8; 1];
+        loop {
+            match self.reader.read(&mut byte) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(byte[0])),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(JsonError::Io(e.to_string())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_two_concatenated_objects() {
+        let input = r#"{"a":{"b":[1,2,{"c":"}"}]}}{"msg":"say \"{hi}\" ]"}"#;
+        let mut reader = input.as_bytes();
+
+        let first = parse_from_reader(&mut reader).unwrap();
+        assert_eq!(
+            first
+                .get_path("a.b")
+                .and_then(|v| v.as_array())
+                .map(|a| a.len()),
+            Some(3)
+        );
+
+        let second = parse_from_reader(&mut reader).unwrap();
+        assert_eq!(
+            second.get_path("msg").and_then(|v| v.as_string()),
+            Some(r#"say "{hi}" ]"#)
+        );
+
+        assert_eq!(parse_from_reader(&mut reader), Err(JsonError::EmptyInput));
+    }
+
+    #[test]
+    fn test_leaves_rest_in_reader() {
+        let mut reader = BufReader::new(r#"  ["x", true] trailing bytes"#.as_bytes());
+
+        let value = parse_from_reader(&mut reader).unwrap();
+        assert_eq!(value.as_array().map(|a| a.len()), Some(2));
+
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, " trailing bytes");
+    }
+
+    #[test]
+    fn test_scalars() {
+        let mut reader = r#""str" null false 42 -1.5"#.as_bytes();
+        assert_eq!(
+            parse_from_reader(&mut reader).unwrap(),
+            JsonValue::String("str".to_string())
+        );
+        assert_eq!(parse_from_reader(&mut reader).unwrap(), JsonValue::Null);
+        assert_eq!(
+            parse_from_reader(&mut reader).unwrap(),
+            JsonValue::Bool(false)
+        );
+        assert_eq!(
+            parse_from_reader(&mut reader).unwrap(),
+            JsonValue::Number(42.0)
+        );
+        assert_eq!(
+            parse_from_reader(&mut reader).unwrap(),
+            JsonValue::Number(-1.5)
+        );
+    }
+
+    #[test]
+    fn test_truncated_value() {
+        let mut reader = r#"{"key": "val"#.as_bytes();
+        assert_eq!(
+            parse_from_reader(&mut reader),
+            Err(JsonError::UnexpectedEndOfInput)
+        );
+    }
+}
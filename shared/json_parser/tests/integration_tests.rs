@@ -2,7 +2,10 @@
 //!
 //! Tests the complete serialize/deserialize cycle with complex types
 
-use json_parser::{from_str, impl_json, impl_json_enum, to_string_pretty};
+use json_parser::{
+    JsonError, JsonValue, from_str, impl_json, impl_json_enum, impl_json_tagged, parse_json,
+    to_string, to_string_pretty,
+};
 use std::collections::HashMap;
 
 #[derive(Debug, PartialEq)]
@@ -51,6 +54,36 @@ impl_json! {
     }
 }
 
+#[derive(Debug, Default, PartialEq)]
+struct Participant {
+    name: String,
+    role: String,
+    muted: bool,
+}
+
+impl_json! {
+    Participant {
+        name: String,
+        role: String,
+        muted: bool,
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct RoomData {
+    room_id: String,
+    owner: Participant,
+    participants: Vec<Participant>,
+}
+
+impl_json! {
+    RoomData {
+        room_id: String,
+        owner: Participant,
+        participants: Vec<Participant>,
+    }
+}
+
 #[test]
 fn test_struct_roundtrip() {
     let person = Person {
@@ -291,3 +324,210 @@ fn test_unicode_strings() {
     assert_eq!(parsed.name, "Alice 你好 🎉");
     assert_eq!(parsed.email.unwrap(), "alice@テスト.com");
 }
+
+#[test]
+fn test_nested_struct_with_vec_roundtrip() {
+    let json = r#"{
+        "room_id": "ROOM123",
+        "owner": {"name": "Alice", "role": "Owner", "muted": false},
+        "participants": [
+            {"name": "Alice", "role": "Owner", "muted": false},
+            {"name": "Bob", "role": "Guest", "muted": true}
+        ]
+    }"#;
+
+    let room: RoomData = from_str(json).unwrap();
+    assert_eq!(room.room_id, "ROOM123");
+    assert_eq!(room.owner.name, "Alice");
+    assert_eq!(room.participants.len(), 2);
+    assert_eq!(
+        room.participants[1],
+        Participant {
+            name: "Bob".to_string(),
+            role: "Guest".to_string(),
+            muted: true,
+        }
+    );
+
+    // Re-serializing yields the same JSON document
+    let original: JsonValue = parse_json(json).unwrap();
+    let reserialized: JsonValue = parse_json(&to_string(&room)).unwrap();
+    assert_eq!(reserialized, original);
+
+    let reparsed: RoomData = from_str(&to_string_pretty(&room)).unwrap();
+    assert_eq!(reparsed, room);
+}
+
+#[test]
+fn test_nested_empty_array_and_nulls() {
+    let room: RoomData =
+        from_str(r#"{"room_id":"R1","owner":{"name":"Alice"},"participants":[]}"#).unwrap();
+    assert!(room.participants.is_empty());
+    assert_eq!(room.owner.role, "");
+
+    // Null nested object and null array map to their defaults
+    let room: RoomData = from_str(r#"{"room_id":"R2","owner":null,"participants":null}"#).unwrap();
+    assert_eq!(room.owner, Participant::default());
+    assert!(room.participants.is_empty());
+}
+
+#[test]
+fn test_nested_array_element_type_mismatch() {
+    let result =
+        from_str::<RoomData>(r#"{"room_id":"R3","participants":[{"name":"Alice"},"Bob"]}"#);
+    assert!(result.is_err());
+}
+
+#[derive(Debug, PartialEq)]
+struct TlsSettings {
+    enabled: bool,
+    pkcs12_path: Option<String>,
+    pkcs12_password: Option<String>,
+}
+
+impl Default for TlsSettings {
+    fn default() -> Self {
+        TlsSettings {
+            enabled: false,
+            pkcs12_path: Some("identity.pfx".to_string()),
+            pkcs12_password: None,
+        }
+    }
+}
+
+impl_json! {
+    TlsSettings {
+        enabled: bool,
+        pkcs12_path: Option<String>,
+        pkcs12_password: Option<String>,
+    }
+}
+
+#[test]
+fn test_option_field_present() {
+    let settings: TlsSettings =
+        from_str(r#"{"enabled":true,"pkcs12_path":"server.p12","pkcs12_password":"pw"}"#).unwrap();
+    assert_eq!(settings.pkcs12_path, Some("server.p12".to_string()));
+    assert_eq!(settings.pkcs12_password, Some("pw".to_string()));
+}
+
+#[test]
+fn test_option_field_null() {
+    let settings: TlsSettings =
+        from_str(r#"{"enabled":true,"pkcs12_path":null,"pkcs12_password":null}"#).unwrap();
+    assert_eq!(settings.pkcs12_path, None);
+    assert_eq!(settings.pkcs12_password, None);
+}
+
+#[test]
+fn test_option_field_missing() {
+    // Missing Option keys are None even when the struct default is Some
+    let settings: TlsSettings = from_str(r#"{"enabled":true}"#).unwrap();
+    assert!(settings.enabled);
+    assert_eq!(settings.pkcs12_path, None);
+    assert_eq!(settings.pkcs12_password, None);
+
+    // Non-Option fields still fall back to the default
+    let settings: TlsSettings = from_str(r#"{}"#).unwrap();
+    assert!(!settings.enabled);
+}
+
+#[test]
+fn test_option_none_serializes_as_null() {
+    let settings = TlsSettings {
+        enabled: true,
+        pkcs12_path: None,
+        pkcs12_password: None,
+    };
+    let json = to_string(&settings);
+    assert!(json.contains(r#""pkcs12_path":null"#), "{}", json);
+
+    let parsed: TlsSettings = from_str(&json).unwrap();
+    assert_eq!(parsed, settings);
+}
+
+#[derive(Debug, PartialEq)]
+enum Request {
+    UserList,
+    Login {
+        username: String,
+        password_hash: String,
+    },
+    Call {
+        to_user_id: String,
+        participants: Vec<Participant>,
+        note: Option<String>,
+    },
+}
+
+impl_json_tagged! {
+    Request, tag = "type" {
+        UserList,
+        Login { username: String, password_hash: String },
+        Call { to_user_id: String, participants: Vec<Participant>, note: Option<String> },
+    }
+}
+
+#[test]
+fn test_tagged_enum_unit_variant_roundtrip() {
+    let json = to_string(&Request::UserList);
+    assert_eq!(json, r#"{"type":"UserList"}"#);
+
+    let parsed: Request = from_str(&json).unwrap();
+    assert_eq!(parsed, Request::UserList);
+}
+
+#[test]
+fn test_tagged_enum_struct_variant_roundtrip() {
+    let login = Request::Login {
+        username: "alice".to_string(),
+        password_hash: "secret".to_string(),
+    };
+    let json = to_string_pretty(&login);
+    assert!(json.contains(r#""type": "Login""#), "{}", json);
+    assert_eq!(from_str::<Request>(&json).unwrap(), login);
+
+    let call = Request::Call {
+        to_user_id: "u2".to_string(),
+        participants: vec![Participant {
+            name: "Bob".to_string(),
+            role: "Guest".to_string(),
+            muted: false,
+        }],
+        note: None,
+    };
+    let json = to_string(&call);
+    assert_eq!(from_str::<Request>(&json).unwrap(), call);
+}
+
+#[test]
+fn test_tagged_enum_optional_field_may_be_omitted() {
+    let parsed: Request =
+        from_str(r#"{"type":"Call","to_user_id":"u2","participants":[]}"#).unwrap();
+    assert_eq!(
+        parsed,
+        Request::Call {
+            to_user_id: "u2".to_string(),
+            participants: vec![],
+            note: None,
+        }
+    );
+}
+
+#[test]
+fn test_tagged_enum_errors() {
+    let err = from_str::<Request>(r#"{"type":"Logout"}"#).unwrap_err();
+    assert_eq!(
+        err,
+        JsonError::UnknownVariant("'Logout' for enum Request".to_string())
+    );
+
+    let err = from_str::<Request>(r#"{"type":"Login","username":"alice"}"#).unwrap_err();
+    assert_eq!(
+        err,
+        JsonError::MissingField("Login.password_hash".to_string())
+    );
+
+    let err = from_str::<Request>(r#"{"username":"alice"}"#).unwrap_err();
+    assert!(matches!(err, JsonError::MissingField(_)));
+}
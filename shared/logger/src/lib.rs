@@ -5,7 +5,9 @@ mod log_level;
 mod log_message;
 mod log_writer;
 mod logger;
+mod rotation;
 
 pub use error::{LoggingError, Result};
 pub use log_level::LogLevel;
-pub use logger::Logger;
+pub use logger::{Logger, LoggerBuilder};
+pub use rotation::Rotation;
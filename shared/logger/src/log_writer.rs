@@ -2,34 +2,34 @@
 
 use crate::error::Result;
 use crate::log_message::LogMessage;
-use std::fs::{File, OpenOptions};
-use std::io::Write;
-use std::path::PathBuf;
+use crate::rotation::{LogFile, Rotation, open_shared};
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
 
 /// Manages async log file writing in dedicated thread.
+///
+/// Writers for the same path share one [`LogFile`], so entries from
+/// different component loggers never interleave with a rotation.
 pub(crate) struct LogWriter {
-    file: File,
+    file: Arc<Mutex<LogFile>>,
 }
 
 impl LogWriter {
     /// Creates a new log writer, opening or creating the file in append mode.
-    pub fn new(log_path: &PathBuf) -> Result<Self> {
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(log_path)?;
+    pub fn new(log_path: &Path, rotation: Rotation) -> Result<Self> {
+        let file = open_shared(log_path, rotation)?;
         Ok(Self { file })
     }
 
-    /// Writes and flushes a message to the file.
+    /// Writes and flushes a message to the file, rotating it if needed.
     fn write_message(&mut self, message: &LogMessage) {
-        if let Err(e) = self.file.write_all(message.format().as_bytes()) {
-            eprintln!("Error writing log: {}", e);
+        let Ok(mut file) = self.file.lock() else {
+            eprintln!("Error writing log: file lock poisoned");
             return;
-        }
-        if let Err(e) = self.file.flush() {
-            eprintln!("Error flushing log: {}", e);
+        };
+        if let Err(e) = file.write_entry(&message.format()) {
+            eprintln!("Error writing log: {}", e);
         }
     }
 
@@ -42,8 +42,12 @@ impl LogWriter {
 }
 
 /// Spawns a dedicated log writer thread.
-pub(crate) fn spawn_writer_thread(log_path: PathBuf, receiver: Receiver<LogMessage>) -> Result<()> {
-    let writer = LogWriter::new(&log_path)?;
+pub(crate) fn spawn_writer_thread(
+    log_path: PathBuf,
+    rotation: Rotation,
+    receiver: Receiver<LogMessage>,
+) -> Result<()> {
+    let writer = LogWriter::new(&log_path, rotation)?;
     std::thread::spawn(move || writer.run(receiver));
     Ok(())
 }
@@ -63,7 +67,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let log_path = dir.path().join("test.log");
 
-        let writer = LogWriter::new(&log_path);
+        let writer = LogWriter::new(&log_path, Rotation::new());
         assert!(writer.is_ok());
         assert!(log_path.exists());
     }
@@ -73,7 +77,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let log_path = dir.path().join("test.log");
 
-        let mut writer = LogWriter::new(&log_path).unwrap();
+        let mut writer = LogWriter::new(&log_path, Rotation::new()).unwrap();
         let message = LogMessage::new(LogLevel::Info, "Test message".to_string());
 
         writer.write_message(&message);
@@ -89,7 +93,7 @@ mod tests {
         let log_path = dir.path().join("test.log");
         let (sender, receiver) = channel();
 
-        spawn_writer_thread(log_path.clone(), receiver).unwrap();
+        spawn_writer_thread(log_path.clone(), Rotation::new(), receiver).unwrap();
 
         sender
             .send(LogMessage::new(LogLevel::Debug, "Thread test".to_string()))
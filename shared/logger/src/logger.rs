@@ -7,8 +7,14 @@ use crate::error::Result;
 use crate::log_level::LogLevel;
 use crate::log_message::LogMessage;
 use crate::log_writer::spawn_writer_thread;
+use crate::rotation::Rotation;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::mpsc::{Sender, channel};
+use std::sync::{Arc, RwLock};
+
+/// Level overrides keyed by component name, shared by a logger and its children.
+type ComponentLevels = Arc<RwLock<HashMap<String, LogLevel>>>;
 
 /// Thread-safe, non-blocking logger.
 ///
@@ -19,7 +25,7 @@ use std::sync::mpsc::{Sender, channel};
 /// ```
 /// use logging::{Logger, LogLevel};
 ///
-/// let logger = Logger::new("app.log".into(), LogLevel::Info).unwrap();
+/// let logger = Logger::new(std::env::temp_dir().join("app.log"), LogLevel::Info).unwrap();
 /// logger.info("Application started");
 /// logger.error("Connection failed");
 /// ```
@@ -30,9 +36,33 @@ pub struct Logger {
     component: Option<String>,
     log_path: PathBuf,
     console_output: bool,
+    rotation: Rotation,
+    component_levels: ComponentLevels,
 }
 
 impl Logger {
+    /// Starts building a logger for `log_path`.
+    ///
+    /// Use the builder to enable file rotation; the other constructors never
+    /// rotate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Logger, LogLevel, Rotation};
+    ///
+    /// let logger = Logger::builder(std::env::temp_dir().join("app.log"))
+    ///     .level(LogLevel::Info)
+    ///     .component("Server")
+    ///     .rotation(Rotation::new().max_size(10 * 1024 * 1024).max_archives(5))
+    ///     .build()
+    ///     .unwrap();
+    /// logger.info("Application started");
+    /// ```
+    pub fn builder(log_path: PathBuf) -> LoggerBuilder {
+        LoggerBuilder::new(log_path)
+    }
+
     /// Creates a new logger with dedicated writer thread.
     ///
     /// # Arguments
@@ -44,15 +74,7 @@ impl Logger {
     ///
     /// Returns error if the log file cannot be created or opened.
     pub fn new(log_path: PathBuf, level: LogLevel) -> Result<Self> {
-        let (sender, receiver) = channel();
-        spawn_writer_thread(log_path.clone(), receiver)?;
-        Ok(Logger {
-            sender,
-            level,
-            component: None,
-            log_path,
-            console_output: false,
-        })
+        Self::builder(log_path).level(level).build()
     }
 
     /// Creates a new logger with component/layer identification.
@@ -72,19 +94,18 @@ impl Logger {
         component: String,
         console_output: bool,
     ) -> Result<Self> {
-        let (sender, receiver) = channel();
-        spawn_writer_thread(log_path.clone(), receiver)?;
-        Ok(Logger {
-            sender,
-            level,
-            component: Some(component),
-            log_path,
-            console_output,
-        })
+        Self::builder(log_path)
+            .level(level)
+            .component(&component)
+            .console_output(console_output)
+            .build()
     }
 
     /// Creates a new logger with a different component but sharing the same configuration.
     ///
+    /// The new logger writes to the same file as `self`, so both go through
+    /// the same rotation.
+    ///
     /// # Arguments
     ///
     /// * `component` - New component or layer name
@@ -98,16 +119,59 @@ impl Logger {
     /// ```
     /// use logging::{Logger, LogLevel};
     ///
-    /// let main_logger = Logger::new("app.log".into(), LogLevel::Info).unwrap();
+    /// let main_logger = Logger::new(std::env::temp_dir().join("app.log"), LogLevel::Info).unwrap();
     /// let db_logger = main_logger.for_component("Database").unwrap();
     /// ```
     pub fn for_component(&self, component: &str) -> Result<Self> {
-        Self::with_component(
-            self.log_path.clone(),
-            self.level,
-            component.to_string(),
-            self.console_output,
-        )
+        let mut logger = Self::builder(self.log_path.clone())
+            .level(self.level)
+            .component(component)
+            .console_output(self.console_output)
+            .rotation(self.rotation)
+            .build()?;
+        logger.component_levels = Arc::clone(&self.component_levels);
+        Ok(logger)
+    }
+
+    /// Overrides the minimum level for one component.
+    ///
+    /// The override is shared with every clone of this logger and every
+    /// logger derived from it with [`Logger::for_component`], including ones
+    /// created before the call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use logging::{Logger, LogLevel};
+    ///
+    /// let logger = Logger::new(std::env::temp_dir().join("app.log"), LogLevel::Info).unwrap();
+    /// logger.set_component_level("ICE", LogLevel::Debug);
+    ///
+    /// let ice_logger = logger.for_component("ICE").unwrap();
+    /// ice_logger.debug("Checking candidate pair"); // recorded
+    /// ```
+    pub fn set_component_level(&self, component: &str, level: LogLevel) {
+        if let Ok(mut levels) = self.component_levels.write() {
+            levels.insert(component.to_string(), level);
+        }
+    }
+
+    /// Removes a component override so it falls back to the global level.
+    pub fn clear_component_level(&self, component: &str) {
+        if let Ok(mut levels) = self.component_levels.write() {
+            levels.remove(component);
+        }
+    }
+
+    /// Minimum level for this logger: its component override, else the global level.
+    pub fn effective_level(&self) -> LogLevel {
+        self.component
+            .as_ref()
+            .and_then(|component| {
+                let levels = self.component_levels.read().ok()?;
+                levels.get(component).copied()
+            })
+            .unwrap_or(self.level)
     }
 
     /// Logs a debug message (only if level is Debug or lower).
@@ -132,7 +196,7 @@ impl Logger {
 
     /// Filters by level and sends message to writer thread.
     fn log(&self, level: LogLevel, message: &str) {
-        if level >= self.level {
+        if level >= self.effective_level() {
             let msg = if let Some(ref component) = self.component {
                 LogMessage::new_with_component(level, component.clone(), message.to_string())
             } else {
@@ -149,9 +213,77 @@ impl Logger {
     }
 }
 
+/// Builder for [`Logger`], created with [`Logger::builder`].
+pub struct LoggerBuilder {
+    log_path: PathBuf,
+    level: LogLevel,
+    component: Option<String>,
+    console_output: bool,
+    rotation: Rotation,
+}
+
+impl LoggerBuilder {
+    fn new(log_path: PathBuf) -> Self {
+        Self {
+            log_path,
+            level: LogLevel::Info,
+            component: None,
+            console_output: false,
+            rotation: Rotation::new(),
+        }
+    }
+
+    /// Minimum log level to record (default `Info`).
+    pub fn level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Component or layer name (e.g., "HTTP", "TCP", "Storage").
+    pub fn component(mut self, component: &str) -> Self {
+        self.component = Some(component.to_string());
+        self
+    }
+
+    /// Also print every recorded message to stdout (default off).
+    pub fn console_output(mut self, enabled: bool) -> Self {
+        self.console_output = enabled;
+        self
+    }
+
+    /// Rotation policy for the log file (default: never rotate).
+    ///
+    /// Loggers sharing a file share its rotation; if the file is already
+    /// open by another logger, that logger's policy stays in effect.
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Spawns the writer thread and returns the logger.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the log file cannot be created or opened.
+    pub fn build(self) -> Result<Logger> {
+        let (sender, receiver) = channel();
+        spawn_writer_thread(self.log_path.clone(), self.rotation, receiver)?;
+        Ok(Logger {
+            sender,
+            level: self.level,
+            component: self.component,
+            log_path: self.log_path,
+            console_output: self.console_output,
+            rotation: self.rotation,
+            component_levels: ComponentLevels::default(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rotation::archive_path;
     use std::fs;
     use std::thread;
     use std::time::Duration;
@@ -230,4 +362,112 @@ mod tests {
         assert!(content.contains("WARN"));
         assert!(content.contains("ERROR"));
     }
+
+    #[test]
+    fn test_rotation_past_size_threshold() {
+        let dir = tempdir().unwrap();
+        let log_path = dir.path().join("rotating.log");
+
+        let logger = Logger::builder(log_path.clone())
+            .level(LogLevel::Debug)
+            .rotation(Rotation::new().max_size(512).max_archives(3))
+            .build()
+            .unwrap();
+        for i in 0..20 {
+            logger.info(&format!("Message number {:02} padded to make it longer", i));
+        }
+        wait_for_write();
+
+        let archive = archive_path(&log_path, 1);
+        assert!(archive.exists());
+        assert!(fs::metadata(&log_path).unwrap().len() <= 512);
+        assert!(fs::metadata(&archive).unwrap().len() <= 512);
+        assert!(!archive_path(&log_path, 4).exists());
+
+        let active = fs::read_to_string(&log_path).unwrap();
+        assert!(!active.contains("Message number 00"));
+        assert!(active.contains("Message number 19"));
+    }
+
+    #[test]
+    fn test_rotation_shared_by_component_loggers() {
+        let dir = tempdir().unwrap();
+        let log_path = dir.path().join("components.log");
+
+        let main = Logger::builder(log_path.clone())
+            .rotation(Rotation::new().max_size(1024).max_archives(10))
+            .build()
+            .unwrap();
+        let handles: Vec<_> = ["ICE", "DTLS", "SCTP", "RTP"]
+            .iter()
+            .map(|name| {
+                let logger = main.for_component(name).unwrap();
+                thread::spawn(move || {
+                    for i in 0..25 {
+                        logger.info(&format!("entry {}", i));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        thread::sleep(Duration::from_millis(200));
+
+        let mut lines = 0;
+        let mut files = vec![log_path.clone()];
+        files.extend((1..=10).map(|i| archive_path(&log_path, i)));
+        for path in files.iter().filter(|p| p.exists()) {
+            let content = fs::read_to_string(path).unwrap();
+            assert!(content.len() <= 1024);
+            assert!(content.lines().all(|l| l.contains("] INFO")));
+            lines += content.lines().count();
+        }
+        assert!(archive_path(&log_path, 1).exists());
+        assert_eq!(lines, 100);
+    }
+
+    #[test]
+    fn test_component_level_override() {
+        let dir = tempdir().unwrap();
+        let log_path = dir.path().join("components.log");
+
+        let main = Logger::new(log_path.clone(), LogLevel::Info).unwrap();
+        let ice = main.for_component("ICE").unwrap();
+        let tcp = main.for_component("TCP").unwrap();
+        main.set_component_level("ICE", LogLevel::Debug);
+
+        ice.debug("ICE debug message");
+        tcp.debug("TCP debug message");
+        main.debug("Main debug message");
+        tcp.info("TCP info message");
+        wait_for_write();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("ICE debug message"));
+        assert!(!content.contains("TCP debug message"));
+        assert!(!content.contains("Main debug message"));
+        assert!(content.contains("TCP info message"));
+    }
+
+    #[test]
+    fn test_component_level_resolution() {
+        let dir = tempdir().unwrap();
+        let log_path = dir.path().join("levels.log");
+
+        let main = Logger::new(log_path, LogLevel::Info).unwrap();
+        main.set_component_level("Storage", LogLevel::Error);
+        let storage = main.for_component("Storage").unwrap();
+        let auth = storage.for_component("Auth").unwrap();
+
+        assert_eq!(main.effective_level(), LogLevel::Info);
+        assert_eq!(storage.effective_level(), LogLevel::Error);
+        assert_eq!(auth.effective_level(), LogLevel::Info);
+
+        auth.set_component_level("Auth", LogLevel::Debug);
+        assert_eq!(auth.effective_level(), LogLevel::Debug);
+
+        main.clear_component_level("Storage");
+        assert_eq!(storage.effective_level(), LogLevel::Info);
+    }
 }
@@ -0,0 +1,255 @@
+//! Log file rotation by size and by day.
+//!
+//! Every logger writing to the same path shares one [`LogFile`] through a
+//! process-wide registry, so a rotation triggered by one component logger is
+//! seen by all the others and no writer keeps appending to a renamed file.
+
+use chrono::{Local, NaiveDate};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+/// When to roll the active log file over to `name.1`, `name.2`, ...
+///
+/// The default never rotates.
+///
+/// # Examples
+///
+/// ```
+/// use logging::Rotation;
+///
+/// // Roll at 10 MiB or at midnight, keeping 5 archives
+/// let rotation = Rotation::new()
+///     .max_size(10 * 1024 * 1024)
+///     .daily()
+///     .max_archives(5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rotation {
+    max_bytes: Option<u64>,
+    daily: bool,
+    max_archives: usize,
+}
+
+impl Rotation {
+    /// Creates a policy that never rotates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rotates before a write would grow the file past `bytes`.
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+
+    /// Rotates on the first write after local midnight.
+    pub fn daily(mut self) -> Self {
+        self.daily = true;
+        self
+    }
+
+    /// Number of rotated files to keep; older ones are deleted.
+    ///
+    /// With `0` the active file is truncated instead of archived.
+    pub fn max_archives(mut self, count: usize) -> Self {
+        self.max_archives = count;
+        self
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.max_bytes.is_some() || self.daily
+    }
+}
+
+/// Active log file plus the state needed to decide when to rotate it.
+pub(crate) struct LogFile {
+    path: PathBuf,
+    file: File,
+    rotation: Rotation,
+    size: u64,
+    opened_on: NaiveDate,
+}
+
+impl LogFile {
+    fn open(path: &Path, rotation: Rotation) -> io::Result<Self> {
+        let file = open_append(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            rotation,
+            size,
+            opened_on: Local::now().date_naive(),
+        })
+    }
+
+    /// Writes one formatted entry, rotating first if the policy requires it.
+    pub fn write_entry(&mut self, entry: &str) -> io::Result<()> {
+        if self.needs_rotation(entry.len() as u64) {
+            self.rotate()?;
+        }
+        self.file.write_all(entry.as_bytes())?;
+        self.file.flush()?;
+        self.size += entry.len() as u64;
+        Ok(())
+    }
+
+    fn needs_rotation(&self, incoming: u64) -> bool {
+        if !self.rotation.is_enabled() || self.size == 0 {
+            return false;
+        }
+        let over_size = self
+            .rotation
+            .max_bytes
+            .is_some_and(|max| self.size + incoming > max);
+        let new_day = self.rotation.daily && Local::now().date_naive() != self.opened_on;
+        over_size || new_day
+    }
+
+    /// Shifts `name.N-1` to `name.N` down to `name` -> `name.1`, then reopens.
+    fn rotate(&mut self) -> io::Result<()> {
+        let keep = self.rotation.max_archives;
+        if keep == 0 {
+            self.file.set_len(0)?;
+        } else {
+            let _ = fs::remove_file(archive_path(&self.path, keep));
+            for index in (1..keep).rev() {
+                let from = archive_path(&self.path, index);
+                if from.exists() {
+                    fs::rename(&from, archive_path(&self.path, index + 1))?;
+                }
+            }
+            fs::rename(&self.path, archive_path(&self.path, 1))?;
+            self.file = open_append(&self.path)?;
+        }
+        self.size = 0;
+        self.opened_on = Local::now().date_naive();
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Path of the `index`-th archive: `server.log` -> `server.log.1`
+pub(crate) fn archive_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+type Registry = Mutex<HashMap<PathBuf, Weak<Mutex<LogFile>>>>;
+
+fn registry() -> &'static Registry {
+    static OPEN_FILES: OnceLock<Registry> = OnceLock::new();
+    OPEN_FILES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Returns the log file shared by every logger writing to `path`.
+///
+/// The first logger to open a path decides its rotation policy.
+pub(crate) fn open_shared(path: &Path, rotation: Rotation) -> io::Result<Arc<Mutex<LogFile>>> {
+    // Make sure the file exists so the path can be canonicalized
+    drop(open_append(path)?);
+    let key = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+    let mut files = registry()
+        .lock()
+        .map_err(|_| io::Error::other("log file registry poisoned"))?;
+    if let Some(shared) = files.get(&key).and_then(Weak::upgrade) {
+        return Ok(shared);
+    }
+
+    files.retain(|_, file| file.strong_count() > 0);
+    let shared = Arc::new(Mutex::new(LogFile::open(path, rotation)?));
+    files.insert(key, Arc::downgrade(&shared));
+    Ok(shared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_no_rotation_by_default() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("plain.log");
+
+        let mut file = LogFile::open(&path, Rotation::new()).unwrap();
+        for _ in 0..100 {
+            file.write_entry("0123456789\n").unwrap();
+        }
+
+        assert_eq!(fs::metadata(&path).unwrap().len(), 1100);
+        assert!(!archive_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_archives_shift_and_oldest_is_dropped() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let rotation = Rotation::new().max_size(10).max_archives(2);
+
+        let mut file = LogFile::open(&path, rotation).unwrap();
+        file.write_entry("first-----\n").unwrap();
+        file.write_entry("second----\n").unwrap();
+        file.write_entry("third-----\n").unwrap();
+        file.write_entry("fourth----\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth----\n");
+        assert_eq!(
+            fs::read_to_string(archive_path(&path, 1)).unwrap(),
+            "third-----\n"
+        );
+        assert_eq!(
+            fs::read_to_string(archive_path(&path, 2)).unwrap(),
+            "second----\n"
+        );
+        assert!(!archive_path(&path, 3).exists());
+    }
+
+    #[test]
+    fn test_zero_archives_truncates() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("trunc.log");
+
+        let mut file = LogFile::open(&path, Rotation::new().max_size(8)).unwrap();
+        file.write_entry("aaaaaa\n").unwrap();
+        file.write_entry("bbbbbb\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "bbbbbb\n");
+        assert!(!archive_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_daily_rotation_on_new_day() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("daily.log");
+
+        let mut file = LogFile::open(&path, Rotation::new().daily().max_archives(1)).unwrap();
+        file.write_entry("yesterday\n").unwrap();
+        file.opened_on = file.opened_on.pred_opt().unwrap();
+        file.write_entry("today\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "today\n");
+        assert_eq!(
+            fs::read_to_string(archive_path(&path, 1)).unwrap(),
+            "yesterday\n"
+        );
+    }
+
+    #[test]
+    fn test_same_path_is_shared() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("shared.log");
+
+        let first = open_shared(&path, Rotation::new()).unwrap();
+        let second = open_shared(&path, Rotation::new().max_size(1)).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+}
@@ -4,14 +4,14 @@
 //! ICE candidates according to RFC 5245, including parsing from and
 //! formatting to SDP format.
 
-use crate::{candidate_type::CandidateType, errors::IceError};
-use std::net::IpAddr;
+use crate::{candidate_type::CandidateType, errors::IceError, tcp_type::TcpType};
+use std::net::{IpAddr, SocketAddr};
 
 /// Represents an ICE candidate according to RFC 5245.
 ///
 /// ICE candidates are encoded as SDP attributes in the format:
 /// ```text
-/// a=candidate:<foundation> <component-id> <transport> <priority> <connection-address> <port> typ <cand-type> [raddr <rel-addr>] [rport <rel-port>]
+/// a=candidate:<foundation> <component-id> <transport> <priority> <connection-address> <port> typ <cand-type> [raddr <rel-addr>] [rport <rel-port>] [tcptype <tcp-type>]
 /// ```
 ///
 /// # Fields
@@ -24,7 +24,8 @@ use std::net::IpAddr;
 /// * `candidate_type` - Type of candidate (host, srflx, relay, prflx)
 /// * `related_address` - Related address for non-host candidates
 /// * `related_port` - Related port for non-host candidates
-#[derive(Debug, Clone)]
+/// * `tcp_type` - Connection role of TCP candidates (RFC 6544)
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Candidate {
     pub foundation: String,
     pub component_id: u32,
@@ -35,21 +36,35 @@ pub struct Candidate {
     pub candidate_type: CandidateType,
     pub related_address: Option<IpAddr>,
     pub related_port: Option<u16>,
+    pub tcp_type: Option<TcpType>,
 }
 
 impl Candidate {
+    /// Component ID of RTP (and of everything when rtcp-mux is used)
+    pub const RTP_COMPONENT: u32 = 1;
+    /// Component ID of RTCP when it has its own transport
+    pub const RTCP_COMPONENT: u32 = 2;
+
     /// Parses an ICE candidate from an SDP attribute value.
     ///
     /// Expected format:
-    /// `<foundation> <component-id> <transport> <priority> <connection-address> <port> typ <cand-type> [raddr <rel-addr>] [rport <rel-port>]`
+    /// `<foundation> <component-id> <transport> <priority> <connection-address> <port> typ <cand-type> [raddr <rel-addr>] [rport <rel-port>] [tcptype <tcp-type>]`
+    ///
+    /// The value may carry an `a=candidate:` or `candidate:` prefix, so a
+    /// full SDP line or the output of `to_string()` parse back unchanged.
+    /// The transport is case-insensitive and unknown trailing extensions
+    /// (e.g. `generation 0`) are ignored.
     ///
     /// # Arguments
-    /// * `value` - The candidate string to parse (without "a=candidate:" prefix)
+    /// * `value` - The candidate string to parse
     ///
     /// # Returns
     /// * `Ok(Candidate)` - Successfully parsed candidate
     /// * `Err(IceError)` - If the format is invalid
     pub fn parse(value: &str) -> Result<Self, IceError> {
+        let value = value.trim();
+        let value = value.strip_prefix("a=").unwrap_or(value);
+        let value = value.strip_prefix("candidate:").unwrap_or(value);
         let parts: Vec<&str> = value.split_whitespace().collect();
         if parts.len() < 8 {
             return Err(IceError::InvalidCandidateFormat);
@@ -64,7 +79,7 @@ impl Candidate {
             return Err(IceError::InvalidComponentId);
         }
 
-        let transport = parts[2].to_string();
+        let transport = parts[2].to_ascii_uppercase();
         if transport != "UDP" && transport != "TCP" {
             return Err(IceError::InvalidTransportProtocol);
         }
@@ -85,6 +100,7 @@ impl Candidate {
         // Parse optional related address and port
         let mut related_address = None;
         let mut related_port = None;
+        let mut tcp_type = None;
 
         let mut i = 8;
         while i < parts.len() {
@@ -101,6 +117,10 @@ impl Candidate {
                     related_port = Some(parts[i + 1].parse().map_err(|_| IceError::InvalidPort)?);
                     i += 2;
                 }
+                "tcptype" if i + 1 < parts.len() => {
+                    tcp_type = Some(TcpType::parse(parts[i + 1])?);
+                    i += 2;
+                }
                 _ => i += 1,
             }
         }
@@ -115,6 +135,7 @@ impl Candidate {
             candidate_type,
             related_address,
             related_port,
+            tcp_type,
         })
     }
 
@@ -136,6 +157,11 @@ impl Candidate {
             return Err(IceError::InvalidTransportProtocol);
         }
 
+        // tcptype only has meaning for TCP candidates
+        if self.tcp_type.is_some() && self.transport != "TCP" {
+            return Err(IceError::InvalidTransportProtocol);
+        }
+
         Ok(())
     }
 
@@ -153,50 +179,131 @@ impl Candidate {
         (type_pref << 24) + (local_pref << 8) + (256 - component_id)
     }
 
-    /// Returns the default type preference for this candidate type.
+    /// Recomputes the priority this candidate gets from its own fields.
     ///
-    /// According to RFC 5245:
-    /// - Host: 126
-    /// - Peer Reflexive: 110
-    /// - Server Reflexive: 100
-    /// - Relay: 0
-    pub fn default_type_preference(&self) -> u32 {
-        match self.candidate_type {
-            CandidateType::Host => 126,
-            CandidateType::Prflx => 110,
-            CandidateType::Srflx => 100,
-            CandidateType::Relay => 0,
+    /// Uses the type preference of its type, the highest local preference
+    /// (lowered for TCP candidates per RFC 6544 Section 4.2) and its
+    /// component. The `priority` field may differ when it was set explicitly
+    /// or parsed from a remote description.
+    pub fn priority(&self) -> u32 {
+        Self::calculate_priority(
+            self.default_type_preference(),
+            self.default_local_preference(),
+            self.component_id,
+        )
+    }
+
+    /// Returns the default local preference of this candidate.
+    ///
+    /// UDP candidates get the maximum (65535). TCP candidates carry their
+    /// direction preference in the top 3 bits, which keeps them below UDP.
+    fn default_local_preference(&self) -> u32 {
+        match self.tcp_type {
+            Some(tcp_type) => (tcp_type.direction_preference() << 13) + 8191,
+            None => 65535,
         }
     }
-}
 
-/// Implements the Display trait to format candidates as SDP attributes.
-///
-/// Format:
-/// `a=candidate:<foundation> <component-id> <transport> <priority> <address> <port> typ <type> [raddr <rel-addr>] [rport <rel-port>]`
-impl std::fmt::Display for Candidate {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
+    /// Computes a candidate foundation according to RFC 5245 Section 4.1.1.3.
+    ///
+    /// Candidates share a foundation when they have the same type, base IP
+    /// address, STUN/TURN server IP address and transport protocol, which is
+    /// what lets frozen checks be correlated across components. The result
+    /// is a stable hash of those fields written as a decimal string.
+    ///
+    /// # Arguments
+    /// * `candidate_type` - Type of the candidate
+    /// * `base` - IP address of the candidate's base
+    /// * `server` - IP address of the STUN/TURN server that produced it, if any
+    /// * `transport` - Transport protocol ("UDP" or "TCP")
+    pub fn compute_foundation(
+        candidate_type: &CandidateType,
+        base: IpAddr,
+        server: Option<IpAddr>,
+        transport: &str,
+    ) -> String {
+        let server = server.map(|ip| ip.to_string()).unwrap_or_default();
+        let key = format!(
+            "{}|{}|{}|{}",
+            candidate_type,
+            base,
+            server,
+            transport.to_ascii_uppercase()
+        );
+
+        // FNV-1a keeps foundations stable across runs and Rust versions
+        let hash = key.bytes().fold(0x811c_9dc5u32, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        });
+        hash.to_string()
+    }
+
+    /// Returns the transport address of the candidate's base.
+    ///
+    /// Reflexive candidates are sent from their base (the host address in
+    /// `raddr`/`rport`); host and relay candidates are their own base.
+    pub fn base_address(&self) -> SocketAddr {
+        match (&self.candidate_type, self.related_address) {
+            (CandidateType::Srflx | CandidateType::Prflx, Some(related)) => {
+                SocketAddr::new(related, self.related_port.unwrap_or(self.port))
+            }
+            _ => SocketAddr::new(self.address, self.port),
+        }
+    }
+
+    /// Formats the candidate with `address` as its connection address.
+    ///
+    /// Used to advertise host candidates under an mDNS hostname instead of
+    /// their IP address.
+    ///
+    /// # Arguments
+    /// * `address` - Connection address to write (IP or `.local` hostname)
+    pub fn to_string_with_address(&self, address: &str) -> String {
+        let mut value = format!(
             "candidate:{} {} {} {} {} {} typ {}",
             self.foundation,
             self.component_id,
             self.transport,
             self.priority,
-            self.address,
+            address,
             self.port,
             self.candidate_type
-        )?;
+        );
 
         if let Some(raddr) = self.related_address {
-            write!(f, " raddr {}", raddr)?;
+            value.push_str(&format!(" raddr {}", raddr));
         }
 
         if let Some(rport) = self.related_port {
-            write!(f, " rport {}", rport)?;
+            value.push_str(&format!(" rport {}", rport));
         }
 
-        Ok(())
+        if let Some(tcp_type) = self.tcp_type {
+            value.push_str(&format!(" tcptype {}", tcp_type));
+        }
+
+        value
+    }
+
+    /// Returns the default type preference for this candidate type.
+    ///
+    /// According to RFC 5245:
+    /// - Host: 126
+    /// - Peer Reflexive: 110
+    /// - Server Reflexive: 100
+    /// - Relay: 0
+    pub fn default_type_preference(&self) -> u32 {
+        self.candidate_type.type_preference()
+    }
+}
+
+/// Implements the Display trait to format candidates as SDP attributes.
+///
+/// Format:
+/// `a=candidate:<foundation> <component-id> <transport> <priority> <address> <port> typ <type> [raddr <rel-addr>] [rport <rel-port>] [tcptype <tcp-type>]`
+impl std::fmt::Display for Candidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_string_with_address(&self.address.to_string()))
     }
 }
 
@@ -450,6 +557,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         assert!(candidate.validate().is_ok());
@@ -467,6 +575,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         let result = candidate.validate();
@@ -486,6 +595,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         let result = candidate.validate();
@@ -505,6 +615,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         let result = candidate.validate();
@@ -559,6 +670,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         assert_eq!(candidate.default_type_preference(), 126);
@@ -576,6 +688,7 @@ mod tests {
             candidate_type: CandidateType::Prflx,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         assert_eq!(candidate.default_type_preference(), 110);
@@ -593,6 +706,7 @@ mod tests {
             candidate_type: CandidateType::Srflx,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         assert_eq!(candidate.default_type_preference(), 100);
@@ -610,11 +724,41 @@ mod tests {
             candidate_type: CandidateType::Relay,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         assert_eq!(candidate.default_type_preference(), 0);
     }
 
+    #[test]
+    fn test_priority_orders_types_and_components() {
+        let candidate = |candidate_type: CandidateType, component_id: u32| Candidate {
+            foundation: "1".to_string(),
+            component_id,
+            transport: "UDP".to_string(),
+            priority: 0,
+            address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: 8080,
+            candidate_type,
+            related_address: None,
+            related_port: None,
+            tcp_type: None,
+        };
+
+        let host = candidate(CandidateType::Host, 1).priority();
+        let prflx = candidate(CandidateType::Prflx, 1).priority();
+        let srflx = candidate(CandidateType::Srflx, 1).priority();
+        let relay = candidate(CandidateType::Relay, 1).priority();
+        assert_eq!(host, (126 << 24) + (65535 << 8) + 255);
+        assert!(host > prflx && prflx > srflx && srflx > relay);
+
+        // RTCP (component 2) ranks just below RTP of the same candidate
+        let host_rtcp = candidate(CandidateType::Host, 2).priority();
+        assert_eq!(host - host_rtcp, 1);
+        assert!(host_rtcp > prflx);
+        assert!(candidate(CandidateType::Relay, 2).priority() < relay);
+    }
+
     #[test]
     fn test_display_host_candidate() {
         let candidate = Candidate {
@@ -627,6 +771,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         let output = format!("{}", candidate);
@@ -648,6 +793,7 @@ mod tests {
             candidate_type: CandidateType::Srflx,
             related_address: Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))),
             related_port: Some(8080),
+            tcp_type: None,
         };
 
         let output = format!("{}", candidate);
@@ -669,6 +815,7 @@ mod tests {
             candidate_type: CandidateType::Srflx,
             related_address: Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))),
             related_port: None,
+            tcp_type: None,
         };
 
         let output = format!("{}", candidate);
@@ -690,6 +837,7 @@ mod tests {
             candidate_type: CandidateType::Srflx,
             related_address: None,
             related_port: Some(8080),
+            tcp_type: None,
         };
 
         let output = format!("{}", candidate);
@@ -711,6 +859,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         let output = format!("{}", candidate);
@@ -746,6 +895,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         let cloned = candidate.clone();
@@ -765,10 +915,151 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         };
 
         let debug_output = format!("{:?}", candidate);
         assert!(debug_output.contains("Candidate"));
         assert!(debug_output.contains("foundation"));
     }
+
+    fn build_candidate(candidate_type: CandidateType, related: Option<(IpAddr, u16)>) -> Candidate {
+        Candidate {
+            foundation: "4077567720".to_string(),
+            component_id: 1,
+            transport: "UDP".to_string(),
+            priority: 1686052607,
+            address: IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)),
+            port: 61234,
+            candidate_type,
+            related_address: related.map(|(addr, _)| addr),
+            related_port: related.map(|(_, port)| port),
+            tcp_type: None,
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_host_srflx_relay() {
+        let related = Some((IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)), 50000));
+        let candidates = [
+            build_candidate(CandidateType::Host, None),
+            build_candidate(CandidateType::Srflx, related),
+            build_candidate(CandidateType::Relay, related),
+            build_candidate(CandidateType::Prflx, None),
+        ];
+
+        for candidate in candidates {
+            let line = candidate.to_string();
+            assert_eq!(Candidate::parse(&line).unwrap(), candidate);
+            assert_eq!(Candidate::parse(&format!("a={}", line)).unwrap(), candidate);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_ipv6_with_related() {
+        let mut candidate = build_candidate(
+            CandidateType::Srflx,
+            Some((IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)), 9)),
+        );
+        candidate.address = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 42));
+        candidate.component_id = 2;
+
+        let reparsed = Candidate::parse(&candidate.to_string()).unwrap();
+        assert_eq!(reparsed, candidate);
+    }
+
+    #[test]
+    fn test_parse_browser_style_line() {
+        let line = "a=candidate:842163049 1 udp 1677729535 198.51.100.4 46154 typ srflx raddr 10.0.0.5 rport 46154 generation 0 network-cost 999\r\n";
+        let candidate = Candidate::parse(line).unwrap();
+
+        assert_eq!(candidate.foundation, "842163049");
+        assert_eq!(candidate.transport, "UDP");
+        assert_eq!(candidate.candidate_type, CandidateType::Srflx);
+        assert_eq!(
+            candidate.related_address,
+            Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)))
+        );
+        assert_eq!(candidate.related_port, Some(46154));
+    }
+
+    #[test]
+    fn test_tcp_candidate_string_includes_protocol_and_tcptype() {
+        let mut candidate = build_candidate(CandidateType::Host, None);
+        candidate.transport = "TCP".to_string();
+        candidate.port = 9;
+        candidate.tcp_type = Some(TcpType::Active);
+
+        let line = candidate.to_string();
+        assert!(line.contains(" TCP "));
+        assert!(line.ends_with("typ host tcptype active"));
+    }
+
+    #[test]
+    fn test_roundtrip_tcp_candidates() {
+        for tcp_type in [TcpType::Active, TcpType::Passive, TcpType::So] {
+            let mut candidate = build_candidate(CandidateType::Host, None);
+            candidate.transport = "TCP".to_string();
+            candidate.tcp_type = Some(tcp_type);
+
+            assert_eq!(Candidate::parse(&candidate.to_string()).unwrap(), candidate);
+        }
+
+        let line =
+            "candidate:1 1 tcp 1518280447 192.168.1.1 9 typ host tcptype active generation 0";
+        let parsed = Candidate::parse(line).unwrap();
+        assert_eq!(parsed.transport, "TCP");
+        assert_eq!(parsed.tcp_type, Some(TcpType::Active));
+    }
+
+    #[test]
+    fn test_parse_invalid_tcptype() {
+        let input = "1 1 TCP 2130706431 192.168.1.1 8080 typ host tcptype listen";
+        assert_eq!(
+            Candidate::parse(input),
+            Err(IceError::InvalidTcpType("listen".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_tcptype_on_udp() {
+        let mut candidate = build_candidate(CandidateType::Host, None);
+        candidate.tcp_type = Some(TcpType::Passive);
+        assert_eq!(
+            candidate.validate(),
+            Err(IceError::InvalidTransportProtocol)
+        );
+    }
+
+    #[test]
+    fn test_compute_foundation_grouping() {
+        let lan = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+        let other_lan = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5));
+        let stun = Some(IpAddr::V4(Ipv4Addr::new(74, 125, 250, 129)));
+        let other_stun = Some(IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1)));
+
+        let host = Candidate::compute_foundation(&CandidateType::Host, lan, None, "UDP");
+        let srflx = Candidate::compute_foundation(&CandidateType::Srflx, lan, stun, "UDP");
+
+        // Deterministic, and the transport case does not matter
+        assert_eq!(
+            host,
+            Candidate::compute_foundation(&CandidateType::Host, lan, None, "udp")
+        );
+        assert!(host.parse::<u32>().is_ok());
+
+        assert_ne!(host, srflx);
+        assert_ne!(
+            host,
+            Candidate::compute_foundation(&CandidateType::Host, other_lan, None, "UDP")
+        );
+        assert_ne!(
+            host,
+            Candidate::compute_foundation(&CandidateType::Host, lan, None, "TCP")
+        );
+        assert_ne!(
+            srflx,
+            Candidate::compute_foundation(&CandidateType::Srflx, lan, other_stun, "UDP")
+        );
+    }
 }
@@ -2,7 +2,9 @@
 //!
 //! Provides a fluent API for constructing ICE candidates with validation.
 
-use crate::{candidate::Candidate, candidate_type::CandidateType, errors::IceError};
+use crate::{
+    candidate::Candidate, candidate_type::CandidateType, errors::IceError, tcp_type::TcpType,
+};
 use std::net::IpAddr;
 
 /// Builder for constructing ICE candidates.
@@ -16,6 +18,8 @@ pub struct CandidateBuilder {
     candidate_type: CandidateType,
     related_address: Option<IpAddr>,
     related_port: Option<u16>,
+    tcp_type: Option<TcpType>,
+    server_address: Option<IpAddr>,
 }
 
 impl Default for CandidateBuilder {
@@ -37,11 +41,17 @@ impl CandidateBuilder {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
+            server_address: None,
         }
     }
 
     /// Sets the foundation identifier.
     ///
+    /// When not set, the foundation is derived from the candidate type, base
+    /// address, server address and transport (see
+    /// [`Candidate::compute_foundation`]).
+    ///
     /// # Arguments
     /// * `foundation` - A string representing the foundation
     pub fn foundation(mut self, foundation: impl Into<String>) -> Self {
@@ -121,44 +131,71 @@ impl CandidateBuilder {
         self
     }
 
+    /// Sets the address of the STUN/TURN server the candidate was obtained from.
+    ///
+    /// Only used to derive the foundation.
+    ///
+    /// # Arguments
+    /// * `server_address` - The server IP address
+    pub fn server_address(mut self, server_address: IpAddr) -> Self {
+        self.server_address = Some(server_address);
+        self
+    }
+
+    /// Makes this a TCP candidate with the given connection role (RFC 6544).
+    ///
+    /// Also sets the transport to "TCP".
+    ///
+    /// # Arguments
+    /// * `tcp_type` - The TCP type (Active, Passive, So)
+    pub fn tcp_type(mut self, tcp_type: TcpType) -> Self {
+        self.transport = "TCP".to_string();
+        self.tcp_type = Some(tcp_type);
+        self
+    }
+
     /// Builds and validates the candidate.
     ///
     /// # Returns
     /// * `Ok(Candidate)` - If the candidate is valid
     /// * `Err(IceError)` - If required fields are missing or validation fails
     pub fn build(self) -> Result<Candidate, IceError> {
-        let foundation = self
-            .foundation
-            .ok_or(IceError::MissingRequiredField("foundation"))?;
-
         let address = self
             .address
             .ok_or(IceError::MissingRequiredField("address"))?;
 
         let port = self.port.ok_or(IceError::MissingRequiredField("port"))?;
 
-        // Calculate priority if not provided
-        let priority = self.priority.unwrap_or_else(|| {
-            let type_pref = match self.candidate_type {
-                CandidateType::Host => 126,
-                CandidateType::Prflx => 110,
-                CandidateType::Srflx => 100,
-                CandidateType::Relay => 0,
+        let foundation = self.foundation.unwrap_or_else(|| {
+            // Reflexive candidates are based on their host address
+            let base = match self.candidate_type {
+                CandidateType::Srflx | CandidateType::Prflx => {
+                    self.related_address.unwrap_or(address)
+                }
+                _ => address,
             };
-            Candidate::calculate_priority(type_pref, 65535, self.component_id)
+            Candidate::compute_foundation(
+                &self.candidate_type,
+                base,
+                self.server_address,
+                &self.transport,
+            )
         });
 
-        let candidate = Candidate {
+        let mut candidate = Candidate {
             foundation,
             component_id: self.component_id,
             transport: self.transport,
-            priority,
+            priority: 0,
             address,
             port,
             candidate_type: self.candidate_type,
             related_address: self.related_address,
             related_port: self.related_port,
+            tcp_type: self.tcp_type,
         };
+        // Calculate priority if not provided
+        candidate.priority = self.priority.unwrap_or_else(|| candidate.priority());
 
         candidate.validate()?;
         Ok(candidate)
@@ -183,6 +220,8 @@ mod tests {
         assert!(matches!(builder.candidate_type, CandidateType::Host));
         assert!(builder.related_address.is_none());
         assert!(builder.related_port.is_none());
+        assert!(builder.tcp_type.is_none());
+        assert!(builder.server_address.is_none());
     }
 
     #[test]
@@ -200,17 +239,61 @@ mod tests {
     }
 
     #[test]
-    fn test_build_fails_without_foundation() {
-        let result = CandidateBuilder::new()
+    fn test_build_derives_foundation_per_rfc() {
+        let lan = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        let public = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+        let stun = IpAddr::V4(Ipv4Addr::new(74, 125, 250, 129));
+
+        let host = |port| {
+            CandidateBuilder::new()
+                .address(lan)
+                .port(port)
+                .build()
+                .unwrap()
+        };
+        let srflx = |port| {
+            CandidateBuilder::new()
+                .address(public)
+                .port(port)
+                .candidate_type(CandidateType::Srflx)
+                .related_address(lan)
+                .related_port(port)
+                .server_address(stun)
+                .build()
+                .unwrap()
+        };
+        let other_interface = CandidateBuilder::new()
+            .address(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)))
+            .port(5000)
+            .build()
+            .unwrap();
+        let tcp_host = CandidateBuilder::new()
+            .address(lan)
+            .port(9)
+            .tcp_type(TcpType::Active)
+            .build()
+            .unwrap();
+
+        // Same type, base, server and transport share a foundation
+        assert_eq!(host(5000).foundation, host(5002).foundation);
+        assert_eq!(srflx(5000).foundation, srflx(5002).foundation);
+
+        // Anything else gets a distinct one
+        assert_ne!(host(5000).foundation, srflx(5000).foundation);
+        assert_ne!(host(5000).foundation, other_interface.foundation);
+        assert_ne!(host(5000).foundation, tcp_host.foundation);
+    }
+
+    #[test]
+    fn test_explicit_foundation_is_kept() {
+        let candidate = CandidateBuilder::new()
+            .foundation("custom")
             .address(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)))
             .port(8080)
-            .build();
+            .build()
+            .unwrap();
 
-        assert!(result.is_err());
-        match result {
-            Err(IceError::MissingRequiredField("foundation")) => (),
-            _ => panic!("Expected MissingRequiredField error for foundation"),
-        }
+        assert_eq!(candidate.foundation, "custom");
     }
 
     #[test]
@@ -459,4 +542,74 @@ mod tests {
         );
         assert_eq!(candidate.related_port, Some(12345));
     }
+
+    #[test]
+    fn test_tcp_type_sets_tcp_transport() {
+        let candidate = CandidateBuilder::new()
+            .foundation("1")
+            .address(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)))
+            .port(9)
+            .tcp_type(TcpType::Passive)
+            .build()
+            .unwrap();
+
+        assert_eq!(candidate.transport, "TCP");
+        assert_eq!(candidate.tcp_type, Some(TcpType::Passive));
+        assert!(candidate.to_string().contains(" TCP "));
+        assert!(candidate.to_string().ends_with("tcptype passive"));
+    }
+
+    #[test]
+    fn test_tcp_candidate_build_parse_roundtrip() {
+        let candidate = CandidateBuilder::new()
+            .foundation("2")
+            .address(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))
+            .port(9)
+            .tcp_type(TcpType::Active)
+            .build()
+            .unwrap();
+
+        assert_eq!(Candidate::parse(&candidate.to_string()).unwrap(), candidate);
+    }
+
+    #[test]
+    fn test_tcp_priority_below_udp() {
+        let address = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let udp = CandidateBuilder::new()
+            .foundation("1")
+            .address(address)
+            .port(5000)
+            .build()
+            .unwrap();
+        let active = CandidateBuilder::new()
+            .foundation("2")
+            .address(address)
+            .port(9)
+            .tcp_type(TcpType::Active)
+            .build()
+            .unwrap();
+        let passive = CandidateBuilder::new()
+            .foundation("3")
+            .address(address)
+            .port(5001)
+            .tcp_type(TcpType::Passive)
+            .build()
+            .unwrap();
+
+        assert!(udp.priority > active.priority);
+        assert!(active.priority > passive.priority);
+    }
+
+    #[test]
+    fn test_build_rejects_tcp_type_with_udp_transport() {
+        let result = CandidateBuilder::new()
+            .foundation("1")
+            .address(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))
+            .port(9)
+            .tcp_type(TcpType::So)
+            .transport("UDP")
+            .build();
+
+        assert_eq!(result, Err(IceError::InvalidTransportProtocol));
+    }
 }
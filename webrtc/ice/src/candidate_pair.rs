@@ -3,7 +3,7 @@
 //! This module provides functionality for creating and managing pairs of
 //! local and remote ICE candidates with priority calculation.
 
-use crate::candidate::Candidate;
+use crate::{candidate::Candidate, pair_state::PairState};
 
 /// Represents a pair of local and remote candidates for connectivity checking.
 #[derive(Debug, Clone)]
@@ -11,6 +11,10 @@ pub struct CandidatePair {
     pub local: Candidate,
     pub remote: Candidate,
     pub priority: u64,
+    /// Progress of the connectivity checks on this pair
+    pub state: PairState,
+    /// The controlling agent nominated this pair (USE-CANDIDATE)
+    pub nominated: bool,
 }
 
 impl CandidatePair {
@@ -23,15 +27,39 @@ impl CandidatePair {
     /// # Returns
     /// A new `CandidatePair` instance with priority calculated according to RFC 5245
     pub fn new(local: Candidate, remote: Candidate) -> Self {
-        let priority = Self::calculate_priority(local.priority, remote.priority);
+        Self::with_role(local, remote, true)
+    }
+
+    /// Creates a new candidate pair for the given local ICE role.
+    ///
+    /// # Arguments
+    /// * `local` - The local candidate
+    /// * `remote` - The remote candidate
+    /// * `controlling` - Whether the local agent is the controlling agent
+    pub fn with_role(local: Candidate, remote: Candidate, controlling: bool) -> Self {
+        let priority = if controlling {
+            Self::calculate_priority(local.priority, remote.priority)
+        } else {
+            Self::calculate_priority(remote.priority, local.priority)
+        };
 
         Self {
             local,
             remote,
             priority,
+            state: PairState::Frozen,
+            nominated: false,
         }
     }
 
+    /// Returns the pair foundation: the local and remote candidate foundations.
+    ///
+    /// Pairs sharing a foundation are likely to behave alike, so a success on
+    /// one unfreezes the others (RFC 5245 Section 5.7.4).
+    pub fn foundation(&self) -> (&str, &str) {
+        (&self.local.foundation, &self.remote.foundation)
+    }
+
     /// Calculates the priority for a candidate pair.
     ///
     /// According to RFC 5245:
@@ -66,6 +94,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         }
     }
 
@@ -80,6 +109,8 @@ mod tests {
         assert_eq!(pair.remote.priority, 2000);
         assert_eq!(pair.local.port, 8080);
         assert_eq!(pair.remote.port, 9090);
+        assert_eq!(pair.state, PairState::Frozen);
+        assert_eq!(pair.foundation(), ("test", "test"));
     }
 
     #[test]
@@ -93,6 +124,18 @@ mod tests {
         assert!(pair.priority > 0);
     }
 
+    #[test]
+    fn test_with_role_swaps_controlling_candidate() {
+        let local = create_test_candidate(2000, 8080);
+        let remote = create_test_candidate(1000, 9090);
+
+        let controlling = CandidatePair::with_role(local.clone(), remote.clone(), true);
+        let controlled = CandidatePair::with_role(local, remote, false);
+
+        assert_eq!(controlling.priority, (1u64 << 32) * 1000 + 2 * 2000 + 1);
+        assert_eq!(controlled.priority, (1u64 << 32) * 1000 + 2 * 2000);
+    }
+
     #[test]
     fn test_calculate_priority_when_g_greater_than_d() {
         let g = 2000u32;
@@ -49,6 +49,21 @@ impl CandidateType {
             CandidateType::Prflx => "prflx",
         }
     }
+
+    /// Returns the recommended type preference (RFC 5245 Section 4.1.2.2).
+    ///
+    /// - Host: 126
+    /// - Peer Reflexive: 110
+    /// - Server Reflexive: 100
+    /// - Relay: 0
+    pub fn type_preference(&self) -> u32 {
+        match self {
+            CandidateType::Host => 126,
+            CandidateType::Prflx => 110,
+            CandidateType::Srflx => 100,
+            CandidateType::Relay => 0,
+        }
+    }
 }
 
 /// Provides a default candidate type of Host.
@@ -1,13 +1,53 @@
 //! Connectivity checking utilities.
 //!
 //! Provides socket management and connectivity check functionality
-//! for ICE candidate pairs.
+//! for ICE candidate pairs, over UDP and over TCP (RFC 6544).
 
-use std::net::{SocketAddr, UdpSocket};
+use rand::Rng;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
+use stun::{AttributeType, Message, MessageType};
 
+use crate::demux::{self, DatagramHandler};
+use crate::mdns::{self, MdnsRegistry};
+use crate::tcp_type::TcpType;
 use crate::{candidate::Candidate, errors::IceError};
 
+/// Message exchanged by the simplified connectivity checks.
+const CHECK_MESSAGE: &[u8] = b"ICE_CHECK";
+
+/// How long to wait for the owner of a remote `.local` name to answer.
+pub const MDNS_RESOLVE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Stops running connectivity checks from another thread.
+///
+/// Clones share the same flag, so a clone kept by the code tearing down a
+/// call cancels the checks of the agent it came from.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the checks using this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true once [`cancel`](Self::cancel) was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
 /// Represents a UDP socket bound to a local candidate.
 #[derive(Debug)]
 pub struct CandidateSocket {
@@ -41,6 +81,23 @@ impl CandidateSocket {
         Ok(Self { candidate, socket })
     }
 
+    /// Wraps a socket that is already bound, such as a clone of the media socket.
+    ///
+    /// # Arguments
+    /// * `candidate` - The local candidate the socket was bound for
+    /// * `socket` - The bound socket; it is switched to non-blocking mode
+    ///
+    /// # Returns
+    /// * `Ok(CandidateSocket)` - The wrapped socket
+    /// * `Err(IceError)` - If the socket cannot be configured
+    pub fn from_socket(candidate: Candidate, socket: UdpSocket) -> Result<Self, IceError> {
+        socket
+            .set_nonblocking(true)
+            .map_err(|e| IceError::SocketError(e.to_string()))?;
+
+        Ok(Self { candidate, socket })
+    }
+
     /// Sends data to a remote address.
     ///
     /// # Arguments
@@ -65,6 +122,41 @@ impl CandidateSocket {
             .recv_from(buf)
             .map_err(|e| IceError::SocketError(e.to_string()))
     }
+
+    /// Receives every waiting datagram and routes each to `handler`.
+    ///
+    /// STUN, DTLS and RTP/RTCP are told apart by their first byte (see
+    /// [`PacketClass`](crate::PacketClass)). Used by
+    /// [`IceAgent::poll_consent`](crate::IceAgent::poll_consent), which keeps
+    /// the STUN traffic and hands everything else to its caller.
+    ///
+    /// # Arguments
+    /// * `handler` - Receives each datagram in the method for its class
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of datagrams routed
+    /// * `Err(IceError)` - If receiving fails for a reason other than no data
+    pub fn recv_dispatch<H: DatagramHandler + ?Sized>(
+        &self,
+        handler: &mut H,
+    ) -> Result<usize, IceError> {
+        let mut buf = [0u8; 2048];
+        let mut routed = 0;
+        loop {
+            match self.socket.recv_from(&mut buf) {
+                Ok((size, from)) => {
+                    demux::dispatch(&buf[..size], from, handler);
+                    routed += 1;
+                }
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Ok(routed);
+                }
+                // ICMP port unreachable from an earlier send; the next datagram may be fine
+                Err(e) if e.kind() == ErrorKind::ConnectionRefused => continue,
+                Err(e) => return Err(IceError::SocketError(e.to_string())),
+            }
+        }
+    }
 }
 
 /// Performs a basic connectivity check between two candidates.
@@ -86,8 +178,7 @@ pub fn perform_connectivity_check(
     let remote_addr = SocketAddr::new(remote_candidate.address, remote_candidate.port);
 
     // Send a simple test message (in real WebRTC this would be a STUN Binding Request)
-    let test_message = b"ICE_CHECK";
-    local_socket.send_to(test_message, remote_addr)?;
+    local_socket.send_to(CHECK_MESSAGE, remote_addr)?;
 
     // Try to receive a response (with timeout)
     let mut buf = [0u8; 1024];
@@ -103,6 +194,318 @@ pub fn perform_connectivity_check(
     }
 }
 
+/// Attributes of an ICE connectivity check (a STUN Binding request).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckRequest {
+    /// Priority of the peer reflexive candidate the check would create
+    pub priority: u32,
+    /// Whether the sender is the controlling agent
+    pub controlling: bool,
+    /// Tie-breaker for role conflicts (ICE-CONTROLLING/ICE-CONTROLLED value)
+    pub tie_breaker: u64,
+    /// Whether the check nominates its pair (USE-CANDIDATE)
+    pub use_candidate: bool,
+}
+
+impl CheckRequest {
+    /// Encodes the check as a Binding request.
+    ///
+    /// # Arguments
+    /// * `transaction_id` - Transaction ID the response will echo
+    pub fn encode(&self, transaction_id: [u8; 12]) -> Vec<u8> {
+        let mut message = Message::new(MessageType::Request, transaction_id);
+        message.add_attribute(AttributeType::Priority, &self.priority.to_be_bytes());
+        let role = if self.controlling {
+            AttributeType::IceControlling
+        } else {
+            AttributeType::IceControlled
+        };
+        message.add_attribute(role, &self.tie_breaker.to_be_bytes());
+        if self.use_candidate {
+            message.add_attribute(AttributeType::UseCandidate, &[]);
+        }
+        message.encode()
+    }
+
+    /// Decodes a Binding request.
+    ///
+    /// Requests without ICE attributes (e.g. consent checks) decode with
+    /// priority 0 and no nomination.
+    ///
+    /// # Returns
+    /// The transaction ID and the check, or `None` if `data` is not a
+    /// Binding request
+    pub fn decode(data: &[u8]) -> Option<([u8; 12], Self)> {
+        let message = Message::decode(data).ok()?;
+        if message.message_type() != MessageType::Request {
+            return None;
+        }
+
+        let priority = message
+            .attribute(AttributeType::Priority)
+            .and_then(|value| value.try_into().ok())
+            .map(u32::from_be_bytes)
+            .unwrap_or(0);
+        let (controlling, role_value) = match message.attribute(AttributeType::IceControlling) {
+            Some(value) => (true, Some(value)),
+            None => (false, message.attribute(AttributeType::IceControlled)),
+        };
+        let tie_breaker = role_value
+            .and_then(|value| value.try_into().ok())
+            .map(u64::from_be_bytes)
+            .unwrap_or(0);
+
+        Some((
+            message.transaction_id(),
+            Self {
+                priority,
+                controlling,
+                tie_breaker,
+                use_candidate: message.attribute(AttributeType::UseCandidate).is_some(),
+            },
+        ))
+    }
+}
+
+/// Encodes the success response to a connectivity check.
+///
+/// # Arguments
+/// * `transaction_id` - Transaction ID of the request being answered
+pub fn build_check_response(transaction_id: [u8; 12]) -> Vec<u8> {
+    Message::new(MessageType::Response, transaction_id).encode()
+}
+
+/// Generates an unguessable transaction ID.
+///
+/// Responses are only trusted when they echo an ID an off-path attacker
+/// cannot predict (RFC 5389 Section 6), so all 96 bits come from the
+/// thread-local CSPRNG.
+pub(crate) fn new_transaction_id() -> [u8; 12] {
+    rand::thread_rng().r#gen()
+}
+
+/// Writes one RFC 4571 frame: a 16-bit big-endian length followed by `data`.
+///
+/// ICE over TCP (RFC 6544) frames every STUN and media packet this way so
+/// packet boundaries survive the byte stream.
+pub fn write_framed<W: Write>(stream: &mut W, data: &[u8]) -> Result<(), IceError> {
+    let len = u16::try_from(data.len())
+        .map_err(|_| IceError::SocketError("frame larger than 65535 bytes".to_string()))?;
+    stream
+        .write_all(&len.to_be_bytes())
+        .and_then(|_| stream.write_all(data))
+        .map_err(|e| IceError::SocketError(e.to_string()))
+}
+
+/// Reads one RFC 4571 frame written by [`write_framed`].
+pub fn read_framed<R: Read>(stream: &mut R) -> Result<Vec<u8>, IceError> {
+    let mut len = [0u8; 2];
+    stream
+        .read_exact(&mut len)
+        .map_err(|e| IceError::SocketError(e.to_string()))?;
+    let mut data = vec![0u8; u16::from_be_bytes(len) as usize];
+    stream
+        .read_exact(&mut data)
+        .map_err(|e| IceError::SocketError(e.to_string()))?;
+    Ok(data)
+}
+
+/// Represents a TCP listener bound to a local passive candidate.
+///
+/// Passive candidates never open connections; the remote active candidate
+/// connects to them and sends its checks over the accepted stream.
+#[derive(Debug)]
+pub struct CandidateListener {
+    pub candidate: Candidate,
+    pub listener: TcpListener,
+}
+
+impl CandidateListener {
+    /// Creates a listener bound to the candidate's address and port.
+    ///
+    /// # Arguments
+    /// * `candidate` - A TCP candidate with `tcptype passive` or `so`
+    ///
+    /// # Returns
+    /// * `Ok(CandidateListener)` - Successfully bound listener
+    /// * `Err(IceError)` - If the candidate cannot accept connections or binding fails
+    pub fn new(candidate: Candidate) -> Result<Self, IceError> {
+        if !matches!(candidate.tcp_type, Some(TcpType::Passive | TcpType::So)) {
+            return Err(IceError::InvalidTransportProtocol);
+        }
+
+        let addr = SocketAddr::new(candidate.address, candidate.port);
+        let listener =
+            TcpListener::bind(addr).map_err(|e| IceError::SocketBindError(e.to_string()))?;
+
+        Ok(Self {
+            candidate,
+            listener,
+        })
+    }
+
+    /// Accepts one connection and answers the connectivity check sent on it.
+    ///
+    /// # Arguments
+    /// * `timeout` - Read/write timeout for the accepted stream
+    ///
+    /// # Returns
+    /// * `Ok((TcpStream, SocketAddr))` - The checked stream and the peer address
+    /// * `Err(IceError)` - If accepting or the check exchange fails
+    pub fn accept_check(&self, timeout: Duration) -> Result<(TcpStream, SocketAddr), IceError> {
+        let (mut stream, peer) = self
+            .listener
+            .accept()
+            .map_err(|e| IceError::SocketError(e.to_string()))?;
+        set_stream_timeouts(&stream, timeout)?;
+
+        if read_framed(&mut stream)? != CHECK_MESSAGE {
+            return Err(IceError::ConnectivityCheckFailed);
+        }
+        write_framed(&mut stream, CHECK_MESSAGE)?;
+
+        Ok((stream, peer))
+    }
+}
+
+/// Performs a connectivity check from a local active TCP candidate.
+///
+/// Connects to the remote candidate, sends a framed check and waits for the
+/// framed answer. The pair must be TCP-compatible (active to passive, or
+/// so to so).
+///
+/// # Arguments
+/// * `local_candidate` - The local TCP candidate
+/// * `remote_candidate` - The remote TCP candidate to check
+/// * `timeout` - Connect and read timeout
+///
+/// # Returns
+/// * `Ok(Some(TcpStream))` - The checked stream, ready for media
+/// * `Ok(None)` - If the remote did not answer
+/// * `Err(IceError)` - If the pair cannot be checked over TCP
+pub fn perform_tcp_connectivity_check(
+    local_candidate: &Candidate,
+    remote_candidate: &Candidate,
+    timeout: Duration,
+) -> Result<Option<TcpStream>, IceError> {
+    let compatible = match (local_candidate.tcp_type, remote_candidate.tcp_type) {
+        (Some(local), Some(remote)) => local.pairs_with(remote) && local != TcpType::Passive,
+        _ => false,
+    };
+    if !compatible {
+        return Err(IceError::InvalidTransportProtocol);
+    }
+
+    let remote_addr = SocketAddr::new(remote_candidate.address, remote_candidate.port);
+    let mut stream = match TcpStream::connect_timeout(&remote_addr, timeout) {
+        Ok(stream) => stream,
+        Err(_) => return Ok(None),
+    };
+    set_stream_timeouts(&stream, timeout)?;
+
+    write_framed(&mut stream, CHECK_MESSAGE)?;
+    match read_framed(&mut stream) {
+        Ok(response) if response == CHECK_MESSAGE => Ok(Some(stream)),
+        _ => Ok(None),
+    }
+}
+
+fn set_stream_timeouts(stream: &TcpStream, timeout: Duration) -> Result<(), IceError> {
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| IceError::SocketError(e.to_string()))
+}
+
+/// Parses a remote candidate, resolving an mDNS hostname address first.
+///
+/// Remote `.local` names are looked up in `local_names` (our own names, e.g.
+/// when both peers share an agent in tests) and otherwise resolved with an
+/// mDNS query, so connectivity checks always run against a real IP.
+///
+/// # Arguments
+/// * `value` - Candidate attribute value (without "a=candidate:" prefix)
+/// * `local_names` - Hostnames registered by the local agent, if any
+///
+/// # Returns
+/// * `Ok(Candidate)` - Candidate with a resolved IP address
+/// * `Err(IceError)` - If parsing or mDNS resolution fails
+pub fn resolve_candidate(
+    value: &str,
+    local_names: Option<&MdnsRegistry>,
+) -> Result<Candidate, IceError> {
+    resolve_candidate_at(value, local_names, mdns::MDNS_ADDR)
+}
+
+/// Parses remote candidates, resolving their mDNS hostnames in parallel.
+///
+/// Each `.local` name that is not one of ours is queried on its own thread,
+/// so a batch of unanswered names costs one [`MDNS_RESOLVE_TIMEOUT`] rather
+/// than one per candidate.
+///
+/// # Arguments
+/// * `values` - Candidate attribute values (without "a=candidate:" prefix)
+/// * `local_names` - Hostnames registered by the local agent, if any
+/// * `group` - mDNS group (or responder address) to query
+///
+/// # Returns
+/// One result per input value, in the same order
+pub(crate) fn resolve_candidates(
+    values: &[&str],
+    local_names: Option<&MdnsRegistry>,
+    group: SocketAddr,
+) -> Vec<Result<Candidate, IceError>> {
+    let needs_query = |value: &str| {
+        value
+            .split_whitespace()
+            .nth(4)
+            .filter(|a| mdns::is_mdns_hostname(a))
+            .is_some_and(|name| local_names.and_then(|names| names.resolve(name)).is_none())
+    };
+
+    std::thread::scope(|scope| {
+        let pending: Vec<_> = values
+            .iter()
+            .map(|value| {
+                needs_query(value)
+                    .then(|| scope.spawn(move || resolve_candidate_at(value, local_names, group)))
+            })
+            .collect();
+
+        values
+            .iter()
+            .zip(pending)
+            .map(|(value, handle)| match handle {
+                Some(handle) => handle
+                    .join()
+                    .unwrap_or_else(|_| Err(IceError::MdnsResolutionFailed(value.to_string()))),
+                None => resolve_candidate_at(value, local_names, group),
+            })
+            .collect()
+    })
+}
+
+/// Same as [`resolve_candidate`], querying `group` for unknown names.
+pub(crate) fn resolve_candidate_at(
+    value: &str,
+    local_names: Option<&MdnsRegistry>,
+    group: SocketAddr,
+) -> Result<Candidate, IceError> {
+    let mut parts: Vec<&str> = value.split_whitespace().collect();
+    let Some(hostname) = parts.get(4).copied().filter(|a| mdns::is_mdns_hostname(a)) else {
+        return Candidate::parse(value);
+    };
+
+    let address = match local_names.and_then(|names| names.resolve(hostname)) {
+        Some(ip) => ip,
+        None => mdns::query_at(hostname, group, MDNS_RESOLVE_TIMEOUT)?,
+    }
+    .to_string();
+    parts[4] = &address;
+
+    Candidate::parse(&parts.join(" "))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,6 +526,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         }
     }
 
@@ -134,6 +538,73 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[derive(Default)]
+    struct RecordingHandler {
+        stun: Vec<Vec<u8>>,
+        dtls: Vec<Vec<u8>>,
+        rtp: Vec<Vec<u8>>,
+        unknown: Vec<Vec<u8>>,
+    }
+
+    impl DatagramHandler for RecordingHandler {
+        fn handle_stun(&mut self, data: &[u8], _from: SocketAddr) {
+            self.stun.push(data.to_vec());
+        }
+
+        fn handle_dtls(&mut self, data: &[u8], _from: SocketAddr) {
+            self.dtls.push(data.to_vec());
+        }
+
+        fn handle_rtp(&mut self, data: &[u8], _from: SocketAddr) {
+            self.rtp.push(data.to_vec());
+        }
+
+        fn handle_unknown(&mut self, data: &[u8], _from: SocketAddr) {
+            self.unknown.push(data.to_vec());
+        }
+    }
+
+    #[test]
+    fn test_recv_dispatch_routes_each_protocol() {
+        let socket = CandidateSocket::new(create_test_candidate(0)).unwrap();
+        let addr = socket.socket.local_addr().unwrap();
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        let binding_request = Message::new(MessageType::Request, [7; 12]).encode();
+        let dtls_client_hello = [22u8, 0xfe, 0xfd, 0, 0];
+        let rtp = [0x80u8, 96, 0, 1];
+        let rtcp_receiver_report = [0x81u8, 201, 0, 7];
+        let zrtp = [16u8, 0];
+        for datagram in [
+            &binding_request[..],
+            &dtls_client_hello,
+            &rtp,
+            &rtcp_receiver_report,
+            &zrtp,
+        ] {
+            peer.send_to(datagram, addr).unwrap();
+        }
+
+        let mut handler = RecordingHandler::default();
+        let mut routed = 0;
+        for _ in 0..50 {
+            routed += socket.recv_dispatch(&mut handler).unwrap();
+            if routed == 5 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(routed, 5);
+        assert_eq!(handler.stun, vec![binding_request]);
+        assert_eq!(handler.dtls, vec![dtls_client_hello.to_vec()]);
+        assert_eq!(
+            handler.rtp,
+            vec![rtp.to_vec(), rtcp_receiver_report.to_vec()]
+        );
+        assert_eq!(handler.unknown, vec![zrtp.to_vec()]);
+    }
+
     #[test]
     fn test_new_binds_to_localhost() {
         let candidate = create_test_candidate(0);
@@ -143,6 +614,20 @@ mod tests {
         assert_eq!(local_addr.ip(), IpAddr::V4(Ipv4Addr::LOCALHOST));
     }
 
+    #[test]
+    fn test_from_socket_shares_bound_socket() {
+        let media = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let media_addr = media.local_addr().unwrap();
+        let candidate = create_test_candidate(media_addr.port());
+        let socket_wrapper =
+            CandidateSocket::from_socket(candidate, media.try_clone().unwrap()).unwrap();
+        assert_eq!(socket_wrapper.socket.local_addr().unwrap(), media_addr);
+
+        // Non-blocking: an empty socket returns at once
+        let mut buf = [0u8; 16];
+        assert!(socket_wrapper.recv_from(&mut buf).is_err());
+    }
+
     #[test]
     fn test_new_with_invalid_address_fails() {
         let mut candidate = create_test_candidate(8080);
@@ -197,6 +682,98 @@ mod tests {
         assert_eq!(socket_wrapper.candidate.component_id, 1);
     }
 
+    #[test]
+    fn test_resolve_candidate_uses_local_names() {
+        let mut names = MdnsRegistry::new();
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 5));
+        let hostname = names.register(ip);
+
+        let value = format!("1 1 UDP 2130706431 {} 5000 typ host", hostname);
+        let candidate = resolve_candidate(&value, Some(&names)).unwrap();
+        assert_eq!(candidate.address, ip);
+        assert_eq!(candidate.port, 5000);
+
+        let plain = resolve_candidate("1 1 UDP 2130706431 10.0.0.1 5000 typ host", None).unwrap();
+        assert_eq!(plain.address, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+    }
+
+    fn create_tcp_candidate(tcp_type: TcpType, port: u16) -> Candidate {
+        Candidate {
+            transport: "TCP".to_string(),
+            tcp_type: Some(tcp_type),
+            ..create_test_candidate(port)
+        }
+    }
+
+    #[test]
+    fn test_check_request_roundtrip() {
+        let check = CheckRequest {
+            priority: 1_845_501_695,
+            controlling: true,
+            tie_breaker: 0x0123_4567_89AB_CDEF,
+            use_candidate: true,
+        };
+        let transaction_id = new_transaction_id();
+
+        let (decoded_id, decoded) = CheckRequest::decode(&check.encode(transaction_id)).unwrap();
+        assert_eq!(decoded_id, transaction_id);
+        assert_eq!(decoded, check);
+
+        let controlled = CheckRequest {
+            controlling: false,
+            use_candidate: false,
+            ..check
+        };
+        let (_, decoded) = CheckRequest::decode(&controlled.encode(transaction_id)).unwrap();
+        assert_eq!(decoded, controlled);
+
+        assert!(CheckRequest::decode(&build_check_response(transaction_id)).is_none());
+        assert_ne!(new_transaction_id(), new_transaction_id());
+    }
+
+    #[test]
+    fn test_framing_roundtrip() {
+        let mut buf = Vec::new();
+        write_framed(&mut buf, b"hello").unwrap();
+        write_framed(&mut buf, b"").unwrap();
+        assert_eq!(&buf[..2], &[0, 5]);
+
+        let mut reader = buf.as_slice();
+        assert_eq!(read_framed(&mut reader).unwrap(), b"hello");
+        assert_eq!(read_framed(&mut reader).unwrap(), b"");
+        assert!(read_framed(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_tcp_check_active_to_passive() {
+        let listener = CandidateListener::new(create_tcp_candidate(TcpType::Passive, 0)).unwrap();
+        let port = listener.listener.local_addr().unwrap().port();
+        let remote = create_tcp_candidate(TcpType::Passive, port);
+
+        let responder = thread::spawn(move || listener.accept_check(Duration::from_secs(2)));
+
+        let local = create_tcp_candidate(TcpType::Active, 9);
+        let stream = perform_tcp_connectivity_check(&local, &remote, Duration::from_secs(2))
+            .unwrap()
+            .expect("check should succeed");
+
+        let (_accepted, peer) = responder.join().unwrap().unwrap();
+        assert_eq!(peer, stream.local_addr().unwrap());
+    }
+
+    #[test]
+    fn test_tcp_check_rejects_incompatible_pairs() {
+        let active = create_tcp_candidate(TcpType::Active, 9);
+        let passive = create_tcp_candidate(TcpType::Passive, 9);
+        let udp = create_test_candidate(9);
+        let timeout = Duration::from_millis(100);
+
+        assert!(perform_tcp_connectivity_check(&active, &active, timeout).is_err());
+        assert!(perform_tcp_connectivity_check(&passive, &active, timeout).is_err());
+        assert!(perform_tcp_connectivity_check(&active, &udp, timeout).is_err());
+        assert!(CandidateListener::new(active).is_err());
+    }
+
     #[test]
     fn test_multiple_sockets_can_coexist() {
         let socket1 = CandidateSocket::new(create_test_candidate(0));
@@ -0,0 +1,213 @@
+//! Consent freshness (RFC 7675).
+//!
+//! Once a candidate pair is selected, STUN Binding requests are sent on it
+//! periodically. If responses stop for a while the path is reported as
+//! disconnected; if none arrives within the consent timeout, the path is
+//! considered dead and the connection must be declared failed.
+
+use crate::connectivity;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use stun::{Message, MessageType};
+
+/// Maximum number of outstanding consent transactions remembered.
+const MAX_PENDING_TRANSACTIONS: usize = 8;
+
+/// Timing parameters for consent freshness checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsentConfig {
+    /// Base interval between consent requests (randomized by ±20%)
+    pub interval: Duration,
+    /// Time without any response after which the path is disconnected
+    pub disconnect_timeout: Duration,
+    /// Time without any response after which consent is lost
+    pub timeout: Duration,
+}
+
+impl Default for ConsentConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            disconnect_timeout: Duration::from_secs(12),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Consent freshness state for the selected candidate pair.
+#[derive(Debug)]
+pub struct ConsentFreshness {
+    config: ConsentConfig,
+    remote_addr: SocketAddr,
+    last_consent: Instant,
+    next_request: Instant,
+    pending: Vec<[u8; 12]>,
+}
+
+impl ConsentFreshness {
+    /// Starts consent tracking for a remote address.
+    ///
+    /// Consent is considered granted at `now` (the pair just succeeded).
+    ///
+    /// # Arguments
+    /// * `remote_addr` - Remote address of the selected pair
+    /// * `config` - Request interval and consent timeout
+    /// * `now` - Current time
+    pub fn new(remote_addr: SocketAddr, config: ConsentConfig, now: Instant) -> Self {
+        Self {
+            config,
+            remote_addr,
+            last_consent: now,
+            next_request: now + jittered(config.interval),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Gets the remote address consent is being checked against.
+    pub fn remote_addr(&self) -> SocketAddr {
+        self.remote_addr
+    }
+
+    /// Returns an encoded Binding request if one is due.
+    ///
+    /// # Arguments
+    /// * `now` - Current time
+    pub fn poll_request(&mut self, now: Instant) -> Option<Vec<u8>> {
+        if now < self.next_request {
+            return None;
+        }
+
+        let transaction_id = connectivity::new_transaction_id();
+        if self.pending.len() == MAX_PENDING_TRANSACTIONS {
+            self.pending.remove(0);
+        }
+        self.pending.push(transaction_id);
+        self.next_request = now + jittered(self.config.interval);
+
+        Some(Message::new(MessageType::Request, transaction_id).encode())
+    }
+
+    /// Processes a packet received on the selected pair.
+    ///
+    /// # Returns
+    /// `true` if the packet was a Binding response to one of our consent
+    /// requests, which refreshes consent
+    pub fn handle_response(&mut self, data: &[u8], from: SocketAddr, now: Instant) -> bool {
+        if from != self.remote_addr {
+            return false;
+        }
+
+        let Ok(message) = Message::decode(data) else {
+            return false;
+        };
+        if message.message_type() != MessageType::Response {
+            return false;
+        }
+
+        let transaction_id = message.transaction_id();
+        match self.pending.iter().position(|id| *id == transaction_id) {
+            Some(index) => {
+                self.pending.remove(index);
+                self.last_consent = now;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Checks whether responses stopped long enough to report the path
+    /// as disconnected.
+    ///
+    /// # Arguments
+    /// * `now` - Current time
+    pub fn is_stale(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_consent) >= self.config.disconnect_timeout
+    }
+
+    /// Checks whether consent has expired.
+    ///
+    /// # Arguments
+    /// * `now` - Current time
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_consent) >= self.config.timeout
+    }
+}
+
+/// Randomizes an interval uniformly within 0.8–1.2 of its base value.
+fn jittered(interval: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let factor = 0.8 + (nanos % 401) as f64 / 1000.0;
+    interval.mul_f64(factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote() -> SocketAddr {
+        "127.0.0.1:5000".parse().unwrap()
+    }
+
+    fn respond_to(request: &[u8]) -> Vec<u8> {
+        let request = Message::decode(request).unwrap();
+        Message::new(MessageType::Response, request.transaction_id()).encode()
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        for _ in 0..100 {
+            let interval = jittered(Duration::from_secs(5));
+            assert!(interval >= Duration::from_secs(4));
+            assert!(interval <= Duration::from_secs(6));
+        }
+    }
+
+    #[test]
+    fn test_request_sent_after_interval() {
+        let start = Instant::now();
+        let mut consent = ConsentFreshness::new(remote(), ConsentConfig::default(), start);
+
+        assert!(consent.poll_request(start).is_none());
+
+        let request = consent
+            .poll_request(start + Duration::from_secs(6))
+            .unwrap();
+        let message = Message::decode(&request).unwrap();
+        assert_eq!(message.message_type(), MessageType::Request);
+    }
+
+    #[test]
+    fn test_response_refreshes_consent() {
+        let start = Instant::now();
+        let mut consent = ConsentFreshness::new(remote(), ConsentConfig::default(), start);
+
+        let at = start + Duration::from_secs(25);
+        let request = consent.poll_request(at).unwrap();
+        assert!(consent.handle_response(&respond_to(&request), remote(), at));
+
+        assert!(!consent.is_expired(start + Duration::from_secs(40)));
+        assert!(!consent.is_stale(at + Duration::from_secs(5)));
+        assert!(consent.is_stale(at + Duration::from_secs(12)));
+        assert!(consent.is_expired(at + Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_unsolicited_responses_are_ignored() {
+        let start = Instant::now();
+        let mut consent = ConsentFreshness::new(remote(), ConsentConfig::default(), start);
+        let request = consent
+            .poll_request(start + Duration::from_secs(6))
+            .unwrap();
+        let response = respond_to(&request);
+
+        let stranger: SocketAddr = "127.0.0.1:6000".parse().unwrap();
+        assert!(!consent.handle_response(&response, stranger, start));
+
+        let unknown = Message::new(MessageType::Response, [7; 12]).encode();
+        assert!(!consent.handle_response(&unknown, remote(), start));
+        assert!(consent.is_expired(start + Duration::from_secs(30)));
+    }
+}
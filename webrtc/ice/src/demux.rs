@@ -0,0 +1,94 @@
+//! Demultiplexing of the protocols sharing one candidate socket.
+//!
+//! After ICE completes, STUN (consent checks), DTLS and SRTP/SRTCP all flow
+//! over the socket of the selected pair. The first byte of each datagram
+//! tells them apart (RFC 7983 Section 7).
+//!
+//! [`PacketClass::classify`] is the only classifier: the media transport of
+//! the network crate builds on it to split RTP from RTCP, and hands the STUN
+//! it receives to [`IceAgent::process_consent`](crate::IceAgent::process_consent).
+
+use std::net::SocketAddr;
+
+/// Protocol of a datagram received on a shared socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketClass {
+    /// STUN message, first byte 0-3
+    Stun,
+    /// DTLS record, first byte 20-63
+    Dtls,
+    /// RTP or RTCP packet (SRTP/SRTCP once keyed), first byte 128-191
+    Rtp,
+    /// Empty datagram or first byte outside the ranges above
+    Unknown,
+}
+
+impl PacketClass {
+    /// Classifies a datagram by its first byte.
+    pub fn classify(data: &[u8]) -> Self {
+        match data.first() {
+            Some(0..=3) => PacketClass::Stun,
+            Some(20..=63) => PacketClass::Dtls,
+            Some(128..=191) => PacketClass::Rtp,
+            _ => PacketClass::Unknown,
+        }
+    }
+}
+
+/// Receives the datagrams of a shared socket, one method per protocol.
+///
+/// Used with [`CandidateSocket::recv_dispatch`](crate::CandidateSocket::recv_dispatch).
+pub trait DatagramHandler {
+    /// Handles a STUN message (connectivity or consent check).
+    fn handle_stun(&mut self, data: &[u8], from: SocketAddr);
+
+    /// Handles a DTLS record.
+    fn handle_dtls(&mut self, data: &[u8], from: SocketAddr);
+
+    /// Handles an RTP or RTCP packet.
+    fn handle_rtp(&mut self, data: &[u8], from: SocketAddr);
+
+    /// Handles a datagram of no known class; dropped by default.
+    fn handle_unknown(&mut self, data: &[u8], from: SocketAddr) {
+        let _ = (data, from);
+    }
+}
+
+/// Routes one datagram to the handler method for its class.
+///
+/// # Returns
+/// The class the datagram was routed as
+pub fn dispatch<H: DatagramHandler + ?Sized>(
+    data: &[u8],
+    from: SocketAddr,
+    handler: &mut H,
+) -> PacketClass {
+    let class = PacketClass::classify(data);
+    match class {
+        PacketClass::Stun => handler.handle_stun(data, from),
+        PacketClass::Dtls => handler.handle_dtls(data, from),
+        PacketClass::Rtp => handler.handle_rtp(data, from),
+        PacketClass::Unknown => handler.handle_unknown(data, from),
+    }
+    class
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_follows_rfc_7983_ranges() {
+        assert_eq!(PacketClass::classify(&[0x00, 0x01]), PacketClass::Stun);
+        assert_eq!(PacketClass::classify(&[0x03]), PacketClass::Stun);
+        assert_eq!(PacketClass::classify(&[20]), PacketClass::Dtls);
+        assert_eq!(PacketClass::classify(&[63]), PacketClass::Dtls);
+        assert_eq!(PacketClass::classify(&[128, 96]), PacketClass::Rtp);
+        assert_eq!(PacketClass::classify(&[191, 200]), PacketClass::Rtp);
+        // ZRTP, TURN channels and the gaps are not routed
+        for byte in [4u8, 16, 19, 64, 79, 127, 192, 255] {
+            assert_eq!(PacketClass::classify(&[byte]), PacketClass::Unknown);
+        }
+        assert_eq!(PacketClass::classify(&[]), PacketClass::Unknown);
+    }
+}
@@ -12,6 +12,8 @@ pub enum IceError {
     InvalidCandidateType(String),
     /// Invalid transport protocol
     InvalidTransportProtocol,
+    /// Invalid TCP candidate type (tcptype)
+    InvalidTcpType(String),
     /// Invalid priority value
     InvalidPriority,
     /// Invalid port number
@@ -36,6 +38,12 @@ pub enum IceError {
     StunQueryFailed,
     /// Configuration error
     Configuration(String),
+    /// mDNS hostname could not be resolved
+    MdnsResolutionFailed(String),
+    /// No candidate pair was selected before the checking deadline
+    CheckingTimeout,
+    /// Connectivity checks were cancelled
+    Cancelled,
 }
 
 impl std::fmt::Display for IceError {
@@ -44,6 +52,7 @@ impl std::fmt::Display for IceError {
             IceError::InvalidCandidateFormat => write!(f, "Invalid candidate format"),
             IceError::InvalidCandidateType(t) => write!(f, "Invalid candidate type: {}", t),
             IceError::InvalidTransportProtocol => write!(f, "Invalid transport protocol"),
+            IceError::InvalidTcpType(t) => write!(f, "Invalid TCP candidate type: {}", t),
             IceError::InvalidPriority => write!(f, "Invalid priority value"),
             IceError::InvalidPort => write!(f, "Invalid port number"),
             IceError::InvalidIpAddress => write!(f, "Invalid IP address"),
@@ -56,6 +65,11 @@ impl std::fmt::Display for IceError {
             IceError::Configuration(msg) => write!(f, "Configuration error: {}", msg),
             IceError::SocketError(e) => write!(f, "Socket error: {}", e),
             IceError::ConnectivityCheckFailed => write!(f, "Connectivity check failed"),
+            IceError::MdnsResolutionFailed(name) => {
+                write!(f, "Failed to resolve mDNS hostname: {}", name)
+            }
+            IceError::CheckingTimeout => write!(f, "ICE checking timed out"),
+            IceError::Cancelled => write!(f, "ICE checks cancelled"),
         }
     }
 }
@@ -0,0 +1,75 @@
+//! Per-server results of candidate gathering.
+//!
+//! STUN and TURN servers are queried independently, so gathering can
+//! succeed with some servers failing. The report lists what each server
+//! returned so callers can log unreachable or misconfigured servers.
+
+use crate::errors::IceError;
+use std::net::SocketAddr;
+
+/// Result of querying one STUN or TURN server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerOutcome {
+    /// Server URL as configured
+    pub server: String,
+    /// Reflexive (STUN) or relayed (TURN) address, or why the server failed
+    pub result: Result<SocketAddr, IceError>,
+}
+
+impl ServerOutcome {
+    /// Returns true if the server answered with an address
+    pub fn is_success(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Outcomes of one gathering pass, in the order the servers were configured.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GatheringReport {
+    pub outcomes: Vec<ServerOutcome>,
+}
+
+impl GatheringReport {
+    /// Servers that answered with an address
+    pub fn succeeded(&self) -> impl Iterator<Item = &ServerOutcome> {
+        self.outcomes.iter().filter(|outcome| outcome.is_success())
+    }
+
+    /// Servers that failed (unreachable, timed out, rejected the request)
+    pub fn failed(&self) -> impl Iterator<Item = &ServerOutcome> {
+        self.outcomes.iter().filter(|outcome| !outcome.is_success())
+    }
+
+    /// Returns true if some, but not all, servers failed
+    pub fn is_partial(&self) -> bool {
+        self.succeeded().next().is_some() && self.failed().next().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_splits_outcomes() {
+        let report = GatheringReport {
+            outcomes: vec![
+                ServerOutcome {
+                    server: "stun:bad.example.com".to_string(),
+                    result: Err(IceError::StunQueryFailed),
+                },
+                ServerOutcome {
+                    server: "stun:good.example.com".to_string(),
+                    result: Ok("203.0.113.5:4000".parse().unwrap()),
+                },
+            ],
+        };
+
+        assert!(report.is_partial());
+        assert_eq!(report.succeeded().count(), 1);
+        assert_eq!(
+            report.failed().next().unwrap().server,
+            "stun:bad.example.com"
+        );
+    }
+}
@@ -3,11 +3,87 @@
 //! The ICE agent is responsible for managing local and remote candidates,
 //! forming candidate pairs, and establishing connectivity.
 
+use crate::pair_state::PairState;
 use crate::{candidate::Candidate, candidate_builder::CandidateBuilder, errors::IceError};
 use crate::{candidate_pair::CandidatePair, connection_state::ConnectionState};
+use crate::{
+    candidate_type::CandidateType,
+    connectivity::{self, CancelToken, CandidateSocket, CheckRequest},
+    consent::{ConsentConfig, ConsentFreshness},
+    demux::DatagramHandler,
+    gathering::{GatheringReport, ServerOutcome},
+    ip_detection::{InterfaceEnumerator, SystemInterfaces},
+    mdns::{self, MdnsRegistry, MdnsResponder},
+    nomination::NominationMode,
+};
 use logging::Logger;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
 use stun::StunClient;
 
+/// Callback invoked with the new state on every connection state change.
+pub type StateChangeCallback = Box<dyn FnMut(ConnectionState) + Send>;
+
+/// Callback invoked with the new pair whenever the selected pair changes.
+pub type SelectedPairChangeCallback = Box<dyn FnMut(&CandidatePair) + Send>;
+
+/// Default time each STUN/TURN server gets to answer during gathering.
+const DEFAULT_SERVER_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Default time connectivity checks may take before the agent gives up.
+const DEFAULT_CHECKING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between new checks (Ta, RFC 8445 Section 14.2).
+const CHECK_PACING: Duration = Duration::from_millis(50);
+
+/// First retransmission timeout of a check, doubled on each retransmission.
+const CHECK_INITIAL_RTO: Duration = Duration::from_millis(250);
+
+/// Longest wait between retransmissions of a check.
+const CHECK_MAX_RTO: Duration = Duration::from_millis(1600);
+
+/// How long the check loop sleeps when no packet is waiting.
+const CHECK_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Default time pregathered candidates are reused before gathering again.
+///
+/// Kept below the usual 30 second NAT binding lifetime.
+const DEFAULT_PREGATHER_TTL: Duration = Duration::from_secs(25);
+
+/// Servers and port of the last pregathering, to gather again when stale.
+#[derive(Debug, Clone)]
+struct Pregather {
+    port: u16,
+    stun_servers: Vec<String>,
+    turn_servers: Vec<String>,
+    gathered_at: Instant,
+}
+
+/// A connectivity check waiting for its response.
+#[derive(Debug, Clone, Copy)]
+struct PendingCheck {
+    transaction_id: [u8; 12],
+    pair_index: usize,
+    use_candidate: bool,
+}
+
+/// A check sent by [`IceAgent::run_connectivity_checks`], retransmitted
+/// until its pair leaves In-Progress.
+#[derive(Debug, Clone, Copy)]
+struct CheckInFlight {
+    pair_index: usize,
+    retransmit_at: Instant,
+    rto: Duration,
+}
+
+/// A succeeded pair kept warm with consent checks so media can move to it
+/// if the selected pair fails.
+#[derive(Debug)]
+struct BackupPair {
+    pair: CandidatePair,
+    consent: ConsentFreshness,
+}
+
 /// ICE Agent that manages ICE candidates and connectivity.
 ///
 /// The agent is responsible for:
@@ -22,7 +98,28 @@ pub struct IceAgent {
     pub local_candidates: Vec<Candidate>,
     pub remote_candidates: Vec<Candidate>,
     candidate_pairs: Vec<CandidatePair>,
+    controlling: bool,
+    nomination: NominationMode,
+    tie_breaker: u64,
+    pending_checks: Vec<PendingCheck>,
+    selected_pair: Option<CandidatePair>,
     connection_state: ConnectionState,
+    consent_config: ConsentConfig,
+    consent: Option<ConsentFreshness>,
+    backups: Vec<BackupPair>,
+    state_callback: Option<StateChangeCallback>,
+    pair_callback: Option<SelectedPairChangeCallback>,
+    mdns: Option<MdnsRegistry>,
+    mdns_group: SocketAddr,
+    mdns_responder: Option<MdnsResponder>,
+    interfaces: Box<dyn InterfaceEnumerator + Send>,
+    excluded_interfaces: Vec<String>,
+    server_timeout: Duration,
+    rtcp_mux: bool,
+    pregather: Option<Pregather>,
+    pregather_ttl: Duration,
+    checking_timeout: Duration,
+    cancel: CancelToken,
     logger: Option<Logger>,
 }
 
@@ -35,7 +132,24 @@ impl std::fmt::Debug for IceAgent {
             .field("local_candidates", &self.local_candidates)
             .field("remote_candidates", &self.remote_candidates)
             .field("candidate_pairs", &self.candidate_pairs)
+            .field("controlling", &self.controlling)
+            .field("nomination", &self.nomination)
+            .field("selected_pair", &self.selected_pair)
             .field("connection_state", &self.connection_state)
+            .field("consent", &self.consent)
+            .field("backups", &self.backups)
+            .field("state_callback", &self.state_callback.is_some())
+            .field("pair_callback", &self.pair_callback.is_some())
+            .field("mdns", &self.mdns)
+            .field("mdns_group", &self.mdns_group)
+            .field("mdns_responder", &self.mdns_responder)
+            .field("excluded_interfaces", &self.excluded_interfaces)
+            .field("server_timeout", &self.server_timeout)
+            .field("rtcp_mux", &self.rtcp_mux)
+            .field("pregather", &self.pregather)
+            .field("pregather_ttl", &self.pregather_ttl)
+            .field("checking_timeout", &self.checking_timeout)
+            .field("cancelled", &self.cancel.is_cancelled())
             .field("logger", &self.logger.is_some())
             .finish()
     }
@@ -53,7 +167,28 @@ impl IceAgent {
             local_candidates: Vec::new(),
             remote_candidates: Vec::new(),
             candidate_pairs: Vec::new(),
+            controlling: true,
+            nomination: NominationMode::default(),
+            tie_breaker: Self::generate_tie_breaker(),
+            pending_checks: Vec::new(),
+            selected_pair: None,
             connection_state: ConnectionState::New,
+            consent_config: ConsentConfig::default(),
+            consent: None,
+            backups: Vec::new(),
+            state_callback: None,
+            pair_callback: None,
+            mdns: None,
+            mdns_group: mdns::MDNS_ADDR,
+            mdns_responder: None,
+            interfaces: Box::new(SystemInterfaces),
+            excluded_interfaces: Vec::new(),
+            server_timeout: DEFAULT_SERVER_TIMEOUT,
+            rtcp_mux: false,
+            pregather: None,
+            pregather_ttl: DEFAULT_PREGATHER_TTL,
+            checking_timeout: DEFAULT_CHECKING_TIMEOUT,
+            cancel: CancelToken::new(),
             logger: None,
         }
     }
@@ -70,7 +205,28 @@ impl IceAgent {
             local_candidates: Vec::new(),
             remote_candidates: Vec::new(),
             candidate_pairs: Vec::new(),
+            controlling: true,
+            nomination: NominationMode::default(),
+            tie_breaker: Self::generate_tie_breaker(),
+            pending_checks: Vec::new(),
+            selected_pair: None,
             connection_state: ConnectionState::New,
+            consent_config: ConsentConfig::default(),
+            consent: None,
+            backups: Vec::new(),
+            state_callback: None,
+            pair_callback: None,
+            mdns: None,
+            mdns_group: mdns::MDNS_ADDR,
+            mdns_responder: None,
+            interfaces: Box::new(SystemInterfaces),
+            excluded_interfaces: Vec::new(),
+            server_timeout: DEFAULT_SERVER_TIMEOUT,
+            rtcp_mux: false,
+            pregather: None,
+            pregather_ttl: DEFAULT_PREGATHER_TTL,
+            checking_timeout: DEFAULT_CHECKING_TIMEOUT,
+            cancel: CancelToken::new(),
             logger: None,
         }
     }
@@ -84,6 +240,54 @@ impl IceAgent {
         self
     }
 
+    /// Registers a callback invoked on every connection state change.
+    ///
+    /// # Arguments
+    /// * `callback` - Called with the new state
+    pub fn on_state_change<F>(&mut self, callback: F)
+    where
+        F: FnMut(ConnectionState) + Send + 'static,
+    {
+        self.state_callback = Some(Box::new(callback));
+    }
+
+    /// Registers a callback invoked whenever the selected pair changes.
+    ///
+    /// Fires on nomination and when media fails over to a backup pair; the
+    /// transport must then send to the new pair's remote address.
+    ///
+    /// # Arguments
+    /// * `callback` - Called with the newly selected pair
+    pub fn on_selected_pair_change<F>(&mut self, callback: F)
+    where
+        F: FnMut(&CandidatePair) + Send + 'static,
+    {
+        self.pair_callback = Some(Box::new(callback));
+    }
+
+    /// Selects a pair and notifies the registered callback.
+    fn set_selected_pair(&mut self, pair: CandidatePair) {
+        self.log_info(&format!(
+            "Selected candidate pair {}:{} -> {}:{}",
+            pair.local.address, pair.local.port, pair.remote.address, pair.remote.port
+        ));
+        if let Some(callback) = self.pair_callback.as_mut() {
+            callback(&pair);
+        }
+        self.selected_pair = Some(pair);
+    }
+
+    /// Updates the connection state and notifies the registered callback.
+    fn set_connection_state(&mut self, state: ConnectionState) {
+        if self.connection_state == state {
+            return;
+        }
+        self.connection_state = state;
+        if let Some(callback) = self.state_callback.as_mut() {
+            callback(state);
+        }
+    }
+
     /// Internal logging helper
     fn log_info(&self, message: &str) {
         if let Some(ref logger) = self.logger {
@@ -118,55 +322,243 @@ impl IceAgent {
         format!("{:x}", timestamp * 31).chars().take(24).collect()
     }
 
+    /// Generates the tie-breaker sent in ICE-CONTROLLING/ICE-CONTROLLED.
+    fn generate_tie_breaker() -> u64 {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time is before UNIX_EPOCH - clock may be incorrect")
+            .as_nanos();
+        (timestamp as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+
     /// Adds a local candidate to the agent.
     ///
     /// # Arguments
     /// * `candidate` - The candidate to add
     pub fn add_local_candidate(&mut self, candidate: Candidate) -> Result<(), IceError> {
         candidate.validate()?;
+        if let Some(mdns) = self.mdns.as_mut()
+            && candidate.candidate_type == CandidateType::Host
+        {
+            mdns.register(candidate.address);
+            if let Some(responder) = &self.mdns_responder {
+                responder.update(mdns);
+            }
+        }
         self.local_candidates.push(candidate);
         Ok(())
     }
 
+    /// Enables or disables mDNS obfuscation of host candidates.
+    ///
+    /// When enabled, host candidates are exported as random `<uuid>.local`
+    /// hostnames and server reflexive candidates hide their base address, so
+    /// private IPs never appear in SDP.
+    ///
+    /// An [`MdnsResponder`] is started on the mDNS group so the peer can
+    /// resolve those hostnames; if it cannot bind, the names are still used
+    /// but only peers sharing our registry will resolve them.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether host candidates should use mDNS hostnames
+    pub fn enable_mdns(&mut self, enabled: bool) {
+        if !enabled {
+            self.mdns = None;
+            self.mdns_responder = None;
+            return;
+        }
+
+        let mut mdns = self.mdns.take().unwrap_or_default();
+        for candidate in &self.local_candidates {
+            if candidate.candidate_type == CandidateType::Host {
+                mdns.register(candidate.address);
+            }
+        }
+
+        match &self.mdns_responder {
+            Some(responder) => responder.update(&mdns),
+            None => match MdnsResponder::bind(self.mdns_group, mdns.clone()) {
+                Ok(responder) => {
+                    self.log_info(&format!(
+                        "mDNS responder listening on {}",
+                        responder.local_addr()
+                    ));
+                    self.mdns_responder = Some(responder);
+                }
+                Err(e) => self.log_warn(&format!("Failed to start mDNS responder: {}", e)),
+            },
+        }
+        self.mdns = Some(mdns);
+    }
+
+    /// Sets the group mDNS queries are sent to and answered on.
+    ///
+    /// Defaults to [`mdns::MDNS_ADDR`]; a unicast address makes the responder
+    /// bind it directly (useful for tests and isolated networks). Restarts
+    /// the responder if mDNS is already enabled.
+    ///
+    /// # Arguments
+    /// * `group` - mDNS multicast group or unicast address
+    pub fn set_mdns_group(&mut self, group: SocketAddr) {
+        self.mdns_group = group;
+        if self.mdns_responder.take().is_some() {
+            self.enable_mdns(true);
+        }
+    }
+
+    /// Gets the address our mDNS responder is bound to, if it is running.
+    pub fn mdns_responder_addr(&self) -> Option<SocketAddr> {
+        self.mdns_responder.as_ref().map(MdnsResponder::local_addr)
+    }
+
+    /// Returns whether host candidates are obfuscated with mDNS hostnames.
+    pub fn is_mdns_enabled(&self) -> bool {
+        self.mdns.is_some()
+    }
+
+    /// Gets the mapping of generated `.local` hostnames to local IPs.
+    ///
+    /// Used to answer mDNS queries from the peer for our host candidates.
+    pub fn mdns_registry(&self) -> Option<&MdnsRegistry> {
+        self.mdns.as_ref()
+    }
+
+    /// Formats a local candidate for signaling, applying mDNS obfuscation.
+    fn format_local_candidate(&self, candidate: &Candidate) -> String {
+        let Some(mdns) = &self.mdns else {
+            return candidate.to_string();
+        };
+
+        match candidate.candidate_type {
+            CandidateType::Host => match mdns.hostname_for(candidate.address) {
+                Some(hostname) => candidate.to_string_with_address(hostname),
+                None => candidate.to_string(),
+            },
+            _ if candidate.related_address.is_some() => {
+                let mut scrubbed = candidate.clone();
+                scrubbed.related_address = Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+                scrubbed.related_port = Some(0);
+                scrubbed.to_string()
+            }
+            _ => candidate.to_string(),
+        }
+    }
+
+    /// Skips interfaces whose name matches any of the patterns when
+    /// gathering host candidates.
+    ///
+    /// # Arguments
+    /// * `patterns` - Name patterns where `*` matches any run of characters
+    ///   (e.g. `docker*`, `vmnet*`)
+    pub fn set_excluded_interfaces(&mut self, patterns: Vec<String>) {
+        self.excluded_interfaces = patterns;
+    }
+
+    /// Replaces the source of interface addresses used for host candidates.
+    ///
+    /// # Arguments
+    /// * `interfaces` - Interface enumerator (defaults to [`SystemInterfaces`])
+    pub fn set_interface_enumerator(&mut self, interfaces: Box<dyn InterfaceEnumerator + Send>) {
+        self.interfaces = interfaces;
+    }
+
+    /// Sets whether RTP and RTCP share one transport (`a=rtcp-mux`).
+    ///
+    /// Without rtcp-mux (the default) candidates are gathered for both
+    /// components: RTP (1) on the given port and RTCP (2) on the next one.
+    /// With rtcp-mux only RTP candidates are gathered.
+    ///
+    /// # Arguments
+    /// * `enabled` - True once rtcp-mux is negotiated
+    pub fn set_rtcp_mux(&mut self, enabled: bool) {
+        self.rtcp_mux = enabled;
+    }
+
+    /// Returns true if RTP and RTCP share one transport
+    pub fn is_rtcp_mux(&self) -> bool {
+        self.rtcp_mux
+    }
+
+    /// Components to gather candidates for, with their port offset
+    fn components(&self) -> &'static [(u32, u16)] {
+        if self.rtcp_mux {
+            &[(Candidate::RTP_COMPONENT, 0)]
+        } else {
+            &[
+                (Candidate::RTP_COMPONENT, 0),
+                (Candidate::RTCP_COMPONENT, 1),
+            ]
+        }
+    }
+
+    /// Sets how long each STUN/TURN server may take to answer while gathering.
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum wait per server operation (defaults to 3 seconds)
+    pub fn set_server_timeout(&mut self, timeout: Duration) {
+        self.server_timeout = timeout;
+    }
+
     /// Gathers local host candidates from network interfaces.
     ///
-    /// Discovers the actual local network IP address for LAN connections.
-    /// Falls back to 0.0.0.0 if detection fails.
+    /// Creates one host candidate per usable interface address (see
+    /// [`detect_local_ips`](crate::ip_detection::detect_local_ips)) and
+    /// component, skipping excluded interfaces. Earlier addresses get a
+    /// higher local preference. Falls back to 0.0.0.0 if no address is found.
     ///
     /// # Arguments
-    /// * `port` - The port to use for the candidate
+    /// * `port` - The RTP port; RTCP uses `port + 1` unless rtcp-mux is set
     ///
     /// # Returns
     /// * `Ok(())` - If candidates were gathered successfully
     /// * `Err(IceError)` - If gathering fails
     pub fn gather_host_candidates(&mut self, port: u16) -> Result<(), IceError> {
-        use crate::ip_detection::detect_local_ip;
-        let local_ip = detect_local_ip();
+        use crate::ip_detection::detect_local_ips_with;
 
-        let candidate = CandidateBuilder::new()
-            .foundation("1".to_string())
-            .component_id(1)
-            .transport("UDP")
-            .address(local_ip.parse().map_err(|_| IceError::InvalidIpAddress)?)
-            .port(port)
-            .candidate_type(crate::candidate_type::CandidateType::Host)
-            .build()?;
+        let mut addresses =
+            detect_local_ips_with(self.interfaces.as_ref(), &self.excluded_interfaces);
+        if addresses.is_empty() {
+            addresses.push(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        }
 
-        self.add_local_candidate(candidate)?;
+        for (index, address) in addresses.into_iter().enumerate() {
+            let local_pref = 65535u32.saturating_sub(index as u32);
+            for &(component_id, offset) in self.components() {
+                let candidate = CandidateBuilder::new()
+                    .component_id(component_id)
+                    .transport("UDP")
+                    .priority(Candidate::calculate_priority(
+                        CandidateType::Host.type_preference(),
+                        local_pref,
+                        component_id,
+                    ))
+                    .address(address)
+                    .port(port.checked_add(offset).ok_or(IceError::InvalidPort)?)
+                    .candidate_type(crate::candidate_type::CandidateType::Host)
+                    .build()?;
+
+                self.add_local_candidate(candidate)?;
+            }
+        }
         Ok(())
     }
 
     /// Gathers server reflexive candidates using STUN servers.
     ///
-    /// Queries one or more STUN servers to discover the public (reflexive) IP address
-    /// and port. This enables NAT traversal for connections through NAT/firewalls.
+    /// Queries every STUN server independently and in parallel, each with
+    /// its own timeout (see [`set_server_timeout`](Self::set_server_timeout)),
+    /// so an unreachable server does not keep the others from producing
+    /// candidates. One candidate is added per distinct public IP and
+    /// component.
     ///
     /// # Arguments
-    /// * `local_port` - Local port to bind for STUN queries
+    /// * `local_port` - Local RTP port; RTCP uses `local_port + 1` unless rtcp-mux is set
     /// * `stun_servers` - List of STUN server addresses (e.g., "stun.l.google.com:19302")
     ///
     /// # Returns
-    /// * `Ok(())` - If at least one server reflexive candidate was gathered
+    /// * `Ok(GatheringReport)` - If at least one server answered; the report
+    ///   lists the servers that failed
     /// * `Err(IceError)` - If all STUN queries fail
     ///
     /// # Example
@@ -175,16 +567,18 @@ impl IceAgent {
     ///
     /// let mut agent = IceAgent::new();
     /// let stun_servers = vec!["stun.l.google.com:19302".to_string()];
-    /// agent.gather_server_reflexive_candidates(5000, &stun_servers)
+    /// let report = agent.gather_server_reflexive_candidates(5000, &stun_servers)
     ///     .expect("Failed to gather srflx candidates");
+    /// for failed in report.failed() {
+    ///     eprintln!("{} failed: {:?}", failed.server, failed.result);
+    /// }
     /// ```
     pub fn gather_server_reflexive_candidates(
         &mut self,
         local_port: u16,
         stun_servers: &[String],
-    ) -> Result<(), IceError> {
+    ) -> Result<GatheringReport, IceError> {
         use crate::ip_detection::detect_local_ip;
-        use std::net::SocketAddr;
 
         if stun_servers.is_empty() {
             return Err(IceError::Configuration(
@@ -202,49 +596,95 @@ impl IceAgent {
             local_port
         ));
 
-        match StunClient::discover_reflexive_from_servers(bind_addr, stun_servers) {
-            Ok(reflexive_addr) => {
-                self.log_info(&format!(
-                    "STUN discovery successful! Public IP: {}",
-                    reflexive_addr.ip()
-                ));
+        let timeout = self.server_timeout;
+        let outcomes: Vec<ServerOutcome> = std::thread::scope(|scope| {
+            let queries: Vec<_> = stun_servers
+                .iter()
+                .map(|server| {
+                    scope.spawn(move || {
+                        StunClient::discover_reflexive_from_server(bind_addr, server, timeout)
+                    })
+                })
+                .collect();
+
+            stun_servers
+                .iter()
+                .zip(queries)
+                .map(|(server, query)| ServerOutcome {
+                    server: server.clone(),
+                    result: match query.join() {
+                        Ok(result) => result.map_err(|e| IceError::SocketError(e.to_string())),
+                        Err(_) => Err(IceError::StunQueryFailed),
+                    },
+                })
+                .collect()
+        });
+        let report = GatheringReport { outcomes };
+
+        for failed in report.failed() {
+            self.log_warn(&format!(
+                "STUN server {} failed: {:?}",
+                failed.server, failed.result
+            ));
+        }
+
+        let mut public_ips: Vec<IpAddr> = Vec::new();
+        for outcome in report.succeeded() {
+            let Ok(reflexive_addr) = outcome.result else {
+                continue;
+            };
+            self.log_info(&format!(
+                "STUN discovery successful via {}! Public IP: {}",
+                outcome.server,
+                reflexive_addr.ip()
+            ));
+            if !public_ips.contains(&reflexive_addr.ip()) {
+                public_ips.push(reflexive_addr.ip());
+            }
+        }
 
-                let local_ip = detect_local_ip();
-                let related_addr = local_ip.parse().map_err(|_| IceError::InvalidIpAddress)?;
+        if public_ips.is_empty() {
+            self.log_warn(
+                "STUN discovery failed on every server - Connection may not work across Internet",
+            );
+            return Err(IceError::StunQueryFailed);
+        }
+
+        let local_ip = detect_local_ip();
+        let related_addr: IpAddr = local_ip.parse().map_err(|_| IceError::InvalidIpAddress)?;
 
+        for public_ip in public_ips {
+            for &(component_id, offset) in self.components() {
+                let port = local_port
+                    .checked_add(offset)
+                    .ok_or(IceError::InvalidPort)?;
                 let candidate = CandidateBuilder::new()
-                    .foundation(format!("{}", 2))
-                    .component_id(1)
+                    .component_id(component_id)
                     .transport("UDP")
-                    .address(reflexive_addr.ip())
-                    .port(local_port)
+                    .address(public_ip)
+                    .port(port)
                     .candidate_type(crate::candidate_type::CandidateType::Srflx)
                     .related_address(related_addr)
-                    .related_port(local_port)
+                    .related_port(port)
                     .build()?;
 
                 self.log_info(&format!(
-                    "Added STUN candidate: {}:{} (type: srflx, priority: {})",
-                    candidate.address, candidate.port, candidate.priority
+                    "Added STUN candidate: {}:{} (type: srflx, component: {}, priority: {})",
+                    candidate.address, candidate.port, component_id, candidate.priority
                 ));
                 self.add_local_candidate(candidate)?;
             }
-            Err(e) => {
-                self.log_warn(&format!(
-                    "STUN discovery failed: {:?} - Connection may not work across Internet",
-                    e
-                ));
-                return Err(IceError::StunQueryFailed);
-            }
         }
 
-        Ok(())
+        Ok(report)
     }
 
     /// Gathers relay candidates using TURN servers.
     ///
-    /// Connects to TURN servers and allocates relay addresses. For each successful
+    /// Connects to TURN servers and allocates relay addresses. Each server
+    /// is tried on its own with the per-server timeout; for each successful
     /// allocation, adds a relay candidate to the local candidates list.
+    /// Relays are only allocated for RTP (component 1).
     ///
     /// # Arguments
     /// * `local_port` - Local port to bind for TURN communication
@@ -252,7 +692,8 @@ impl IceAgent {
     ///   Format: "turn:hostname:port?transport=udp&username=user&password=pass"
     ///
     /// # Returns
-    /// * `Ok(())` - If at least one relay candidate was gathered
+    /// * `Ok(GatheringReport)` - If at least one relay candidate was
+    ///   gathered; the report lists the servers that failed
     /// * `Err(IceError)` - If all TURN allocations failed
     ///
     /// # Example
@@ -269,7 +710,7 @@ impl IceAgent {
         &mut self,
         local_port: u16,
         turn_servers: &[String],
-    ) -> Result<(), IceError> {
+    ) -> Result<GatheringReport, IceError> {
         #[cfg(feature = "turn")]
         {
             self.log_info(&format!(
@@ -277,27 +718,31 @@ impl IceAgent {
                 turn_servers.len()
             ));
 
-            let mut success_count = 0;
+            let mut report = GatheringReport::default();
 
-            for (index, turn_url) in turn_servers.iter().enumerate() {
-                match self.allocate_turn_relay(local_port, turn_url, index) {
-                    Ok(()) => {
-                        success_count += 1;
-                        self.log_info(&format!("Allocated relay from {}", turn_url));
+            for turn_url in turn_servers {
+                let result = self.allocate_turn_relay(local_port, turn_url);
+                match &result {
+                    Ok(relay_addr) => {
+                        self.log_info(&format!("Allocated relay {} from {}", relay_addr, turn_url));
                     }
                     Err(e) => {
                         self.log_warn(&format!("TURN allocation to {} failed: {:?}", turn_url, e));
                     }
                 }
+                report.outcomes.push(ServerOutcome {
+                    server: turn_url.clone(),
+                    result,
+                });
             }
 
-            if success_count == 0 {
+            if report.succeeded().next().is_none() {
                 return Err(IceError::Configuration(
                     "All TURN allocations failed".to_string(),
                 ));
             }
 
-            Ok(())
+            Ok(report)
         }
 
         #[cfg(not(feature = "turn"))]
@@ -314,62 +759,44 @@ impl IceAgent {
     /// # Arguments
     /// * `local_port` - Local port to bind
     /// * `turn_url` - TURN server URL with credentials
-    /// * `foundation_index` - Index for candidate foundation
     ///
     /// # Returns
-    /// * `Ok(())` - If allocation succeeded and candidate was added
+    /// * `Ok(SocketAddr)` - Relayed address, once its candidate was added
     /// * `Err(IceError)` - If allocation fails
     #[cfg(feature = "turn")]
     fn allocate_turn_relay(
         &mut self,
         local_port: u16,
         turn_url: &str,
-        foundation_index: usize,
-    ) -> Result<(), IceError> {
+    ) -> Result<SocketAddr, IceError> {
         use crate::ip_detection::detect_local_ip;
-        use turn::TurnClient;
-
-        // Parse TURN URL (simplified parser)
-        // Format: turn:hostname:port?transport=udp&username=user&password=pass
-        let parts: Vec<&str> = turn_url.split('?').collect();
-        if parts.len() != 2 {
-            return Err(IceError::Configuration(
-                "Invalid TURN URL format".to_string(),
-            ));
+        use turn::{TransportProtocol, TurnClient, TurnUrl};
+
+        let url = TurnUrl::parse(turn_url).map_err(|e| IceError::Configuration(e.to_string()))?;
+        if url.secure || url.transport != TransportProtocol::Udp {
+            return Err(IceError::Configuration(format!(
+                "TURN over {}{} is not supported, use transport=udp",
+                url.transport.as_str(),
+                if url.secure { "/TLS" } else { "" }
+            )));
         }
 
-        let server_part = parts[0]
-            .strip_prefix("turn:")
-            .ok_or_else(|| IceError::Configuration("TURN URL must start with turn:".to_string()))?;
-
-        let server_addr: std::net::SocketAddr = server_part
-            .parse()
-            .map_err(|_| IceError::InvalidIpAddress)?;
-
-        // Parse query parameters
-        let mut username = String::new();
-        let mut password = String::new();
-
-        for param in parts[1].split('&') {
-            let kv: Vec<&str> = param.split('=').collect();
-            if kv.len() == 2 {
-                match kv[0] {
-                    "username" => username = kv[1].to_string(),
-                    "password" => password = kv[1].to_string(),
-                    _ => {}
-                }
-            }
-        }
-
-        if username.is_empty() || password.is_empty() {
+        let (Some(username), Some(_)) = (url.username.clone(), &url.password) else {
             return Err(IceError::Configuration(
                 "TURN URL must include username and password".to_string(),
             ));
-        }
+        };
+
+        let server_addr = url
+            .resolve()
+            .map_err(|e| IceError::Configuration(format!("Cannot resolve TURN server: {}", e)))?;
 
         // Create TURN client
         let mut client = TurnClient::new(server_addr, username)
             .map_err(|_| IceError::Configuration("Failed to create TURN client".to_string()))?;
+        client
+            .set_timeout(self.server_timeout)
+            .map_err(|e| IceError::SocketError(e.to_string()))?;
 
         // Attach logger if available
         #[cfg(feature = "logging")]
@@ -388,20 +815,109 @@ impl IceAgent {
 
         // Create relay candidate
         let candidate = CandidateBuilder::new()
-            .foundation(format!("{}", foundation_index + 100)) // Use high foundation number
             .component_id(1)
             .transport("UDP")
             .address(relay_addr.ip())
             .port(relay_addr.port())
             .candidate_type(crate::candidate_type::CandidateType::Relay)
+            .server_address(server_addr.ip())
             .related_address(related_addr)
             .related_port(local_port)
             .build()?;
 
         self.add_local_candidate(candidate)?;
+        Ok(relay_addr)
+    }
+
+    /// Gathers host, server reflexive and relay candidates ahead of a call.
+    ///
+    /// Replaces the local candidates. Candidate sets are reused by
+    /// [`use_pregathered`](Self::use_pregathered) while younger than the
+    /// pregather TTL. Unreachable STUN/TURN servers only cost their
+    /// candidates, as during normal gathering.
+    ///
+    /// # Arguments
+    /// * `port` - The RTP port the candidates are gathered for
+    /// * `stun_servers` - STUN servers queried for srflx candidates (may be empty)
+    /// * `turn_servers` - TURN servers allocating relay candidates (may be empty)
+    ///
+    /// # Returns
+    /// * `Ok(())` - If at least the host candidates were gathered
+    /// * `Err(IceError)` - If host gathering fails
+    pub fn pregather(
+        &mut self,
+        port: u16,
+        stun_servers: &[String],
+        turn_servers: &[String],
+    ) -> Result<(), IceError> {
+        self.local_candidates.clear();
+        self.pregather = None;
+        self.gather_host_candidates(port)?;
+
+        if !stun_servers.is_empty()
+            && let Err(e) = self.gather_server_reflexive_candidates(port, stun_servers)
+        {
+            self.log_warn(&format!("Pregathering srflx candidates failed: {:?}", e));
+        }
+        if !turn_servers.is_empty()
+            && let Err(e) = self.gather_relay_candidates(port, turn_servers)
+        {
+            self.log_warn(&format!("Pregathering relay candidates failed: {:?}", e));
+        }
+
+        self.log_info(&format!(
+            "Pregathered {} local candidates on port {}",
+            self.local_candidates.len(),
+            port
+        ));
+        self.pregather = Some(Pregather {
+            port,
+            stun_servers: stun_servers.to_vec(),
+            turn_servers: turn_servers.to_vec(),
+            gathered_at: Instant::now(),
+        });
         Ok(())
     }
 
+    /// Sets how long pregathered candidates are reused.
+    ///
+    /// # Arguments
+    /// * `ttl` - Maximum cache age (defaults to 25 seconds)
+    pub fn set_pregather_ttl(&mut self, ttl: Duration) {
+        self.pregather_ttl = ttl;
+    }
+
+    /// Returns the age of the pregathered candidates, or `None` if
+    /// [`pregather`](Self::pregather) was not called.
+    pub fn pregather_age(&self) -> Option<Duration> {
+        self.pregather
+            .as_ref()
+            .map(|pregather| pregather.gathered_at.elapsed())
+    }
+
+    /// Prepares the pregathered candidates for an offer/answer on `port`.
+    ///
+    /// A stale cache is gathered again with the same servers.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - If the local candidates are ready to use
+    /// * `Ok(false)` - If nothing was pregathered for `port`; the caller gathers
+    /// * `Err(IceError)` - If gathering a stale cache again fails
+    pub fn use_pregathered(&mut self, port: u16) -> Result<bool, IceError> {
+        let Some(pregather) = self.pregather.clone() else {
+            return Ok(false);
+        };
+        if pregather.port != port {
+            return Ok(false);
+        }
+
+        if pregather.gathered_at.elapsed() >= self.pregather_ttl {
+            self.log_info("Pregathered candidates are stale, gathering again");
+            self.pregather(port, &pregather.stun_servers, &pregather.turn_servers)?;
+        }
+        Ok(true)
+    }
+
     /// Adds a remote candidate received from the peer.
     ///
     /// # Arguments
@@ -425,57 +941,531 @@ impl IceAgent {
         &mut self,
         attributes: &[String],
     ) -> Result<(), IceError> {
+        let mut values = Vec::new();
         for attr in attributes {
             if let Some(candidate_str) = attr.strip_prefix("a=candidate:") {
                 self.log_info(&format!("Parsing remote ICE candidate: {}", candidate_str));
-                let candidate = Candidate::parse(candidate_str)?;
-                self.log_info(&format!(
-                    "Adding remote candidate: {}:{} (type: {:?})",
-                    candidate.address, candidate.port, candidate.candidate_type
-                ));
-                self.add_remote_candidate(candidate)?;
+                values.push(candidate_str);
             } else if let Some(candidate_str) = attr.strip_prefix("candidate:") {
                 self.log_info(&format!(
                     "Parsing remote ICE candidate (no a= prefix): {}",
                     candidate_str
                 ));
-                let candidate = Candidate::parse(candidate_str)?;
-                self.log_info(&format!(
-                    "Adding remote candidate: {}:{} (type: {:?})",
-                    candidate.address, candidate.port, candidate.candidate_type
-                ));
-                self.add_remote_candidate(candidate)?;
+                values.push(candidate_str);
             } else {
                 self.log_warn(&format!("Ignoring invalid candidate attribute: {}", attr));
             }
         }
+
+        // mDNS names are resolved together so unanswered ones don't add up
+        let resolved =
+            connectivity::resolve_candidates(&values, self.mdns.as_ref(), self.mdns_group);
+        for result in resolved {
+            let candidate = match result {
+                Ok(candidate) => candidate,
+                Err(IceError::MdnsResolutionFailed(name)) => {
+                    self.log_warn(&format!("Skipping unresolved mDNS candidate: {}", name));
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            self.log_info(&format!(
+                "Adding remote candidate: {}:{} (type: {:?})",
+                candidate.address, candidate.port, candidate.candidate_type
+            ));
+            self.add_remote_candidate(candidate)?;
+        }
         Ok(())
     }
 
-    /// Forms candidate pairs from local and remote candidates.
+    /// Sets the local ICE role used for pair priorities.
+    ///
+    /// # Arguments
+    /// * `controlling` - Whether this agent is the controlling agent
+    pub fn set_controlling(&mut self, controlling: bool) {
+        self.controlling = controlling;
+        self.form_candidate_pairs();
+    }
+
+    /// Returns whether this agent is the controlling agent.
+    pub fn is_controlling(&self) -> bool {
+        self.controlling
+    }
+
+    /// Forms the check list from local and remote candidates.
     ///
-    /// This creates all possible pairs and calculates their priorities
-    /// according to RFC 5245.
+    /// Pairs every local candidate with every remote candidate, computes the
+    /// RFC 5245 pair priority, sorts the list (highest first) and prunes
+    /// redundant pairs (RFC 5245 Section 5.7.3). TCP candidates are only
+    /// paired when their tcptypes can connect (RFC 6544 Section 6.2).
+    /// Finally sets the initial pair states (RFC 5245 Section 5.7.4).
     fn form_candidate_pairs(&mut self) {
         self.candidate_pairs.clear();
+        self.pending_checks.clear();
 
         for local in &self.local_candidates {
             for remote in &self.remote_candidates {
-                self.candidate_pairs
-                    .push(CandidatePair::new(local.clone(), remote.clone()));
+                // Checks only run between candidates of the same component
+                if local.component_id != remote.component_id {
+                    continue;
+                }
+                if let (Some(local_tcp), Some(remote_tcp)) = (local.tcp_type, remote.tcp_type)
+                    && !local_tcp.pairs_with(remote_tcp)
+                {
+                    continue;
+                }
+                self.candidate_pairs.push(CandidatePair::with_role(
+                    local.clone(),
+                    remote.clone(),
+                    self.controlling,
+                ));
             }
         }
 
         // Sort by priority (highest first)
         self.candidate_pairs
-            .sort_by(|a, b| b.priority.cmp(&a.priority));
+            .sort_by_key(|pair| std::cmp::Reverse(pair.priority));
+
+        self.prune_candidate_pairs();
+        self.unfreeze_initial_pairs();
     }
 
-    /// Returns the candidate pairs sorted by priority.
-    pub fn get_candidate_pairs(&self) -> &[CandidatePair] {
+    /// Moves one pair per foundation to Waiting, leaving the rest Frozen.
+    ///
+    /// Of the pairs sharing a foundation, the one with the lowest component
+    /// ID is unfrozen, the highest priority one on ties. Assumes the list is
+    /// sorted by descending priority.
+    fn unfreeze_initial_pairs(&mut self) {
+        let mut first_by_foundation: Vec<((&str, &str), usize)> = Vec::new();
+        for (index, pair) in self.candidate_pairs.iter().enumerate() {
+            let foundation = pair.foundation();
+            match first_by_foundation
+                .iter_mut()
+                .find(|(f, _)| *f == foundation)
+            {
+                Some((_, first)) => {
+                    if pair.local.component_id < self.candidate_pairs[*first].local.component_id {
+                        *first = index;
+                    }
+                }
+                None => first_by_foundation.push((foundation, index)),
+            }
+        }
+
+        let waiting: Vec<usize> = first_by_foundation.iter().map(|&(_, i)| i).collect();
+        for index in waiting {
+            self.candidate_pairs[index].state = PairState::Waiting;
+        }
+    }
+
+    /// Moves the Frozen pairs sharing a foundation with `pair_index` to Waiting.
+    fn unfreeze_foundation(&mut self, pair_index: usize) {
+        let pair = &self.candidate_pairs[pair_index];
+        let (local, remote) = (
+            pair.local.foundation.clone(),
+            pair.remote.foundation.clone(),
+        );
+        for pair in &mut self.candidate_pairs {
+            if pair.state == PairState::Frozen && pair.foundation() == (&local, &remote) {
+                pair.state = PairState::Waiting;
+            }
+        }
+    }
+
+    /// Removes pairs whose local base and remote candidate duplicate a
+    /// higher priority pair.
+    ///
+    /// Server reflexive candidates send from their base, so a srflx pair is
+    /// redundant with the host pair sharing that base. Assumes the list is
+    /// sorted by descending priority.
+    fn prune_candidate_pairs(&mut self) {
+        let mut seen = std::collections::HashSet::new();
+        self.candidate_pairs.retain(|pair| {
+            seen.insert((
+                pair.local.base_address(),
+                pair.local.transport.clone(),
+                pair.local.component_id,
+                SocketAddr::new(pair.remote.address, pair.remote.port),
+                pair.remote.transport.clone(),
+            ))
+        });
+    }
+
+    /// Returns the check list in priority order (highest first).
+    pub fn candidate_pairs(&self) -> &[CandidatePair] {
         &self.candidate_pairs
     }
 
+    /// Sets how the controlling agent nominates pairs.
+    ///
+    /// With [`NominationMode::Aggressive`] every check carries USE-CANDIDATE,
+    /// so the first pair to succeed is selected without a follow-up
+    /// nomination. A higher priority pair that succeeds afterwards replaces
+    /// it, so the selected pair can switch briefly while checks are running.
+    ///
+    /// # Arguments
+    /// * `mode` - The nomination mode
+    pub fn set_nomination(&mut self, mode: NominationMode) {
+        self.nomination = mode;
+    }
+
+    /// Returns the nomination mode.
+    pub fn nomination(&self) -> NominationMode {
+        self.nomination
+    }
+
+    /// Picks the pair to check next (RFC 5245 Section 5.8).
+    ///
+    /// This is the highest priority Waiting pair. If no pair is waiting, the
+    /// highest priority Frozen pair is unfrozen and returned.
+    ///
+    /// # Returns
+    /// Index into [`candidate_pairs`](Self::candidate_pairs), or `None` once
+    /// every pair is in progress or done
+    pub fn next_pair_to_check(&mut self) -> Option<usize> {
+        let pairs = &self.candidate_pairs;
+        if let Some(index) = pairs.iter().position(|p| p.state == PairState::Waiting) {
+            return Some(index);
+        }
+
+        let index = pairs.iter().position(|p| p.state == PairState::Frozen)?;
+        self.candidate_pairs[index].state = PairState::Waiting;
+        Some(index)
+    }
+
+    /// Marks a pair's outstanding check as failed.
+    ///
+    /// Called when the check got no response in time; error responses are
+    /// handled by [`handle_check_response`](Self::handle_check_response).
+    ///
+    /// # Arguments
+    /// * `pair_index` - Index into [`candidate_pairs`](Self::candidate_pairs)
+    pub fn fail_check(&mut self, pair_index: usize) {
+        self.pending_checks
+            .retain(|check| check.pair_index != pair_index);
+        if let Some(pair) = self.candidate_pairs.get_mut(pair_index) {
+            pair.state = PairState::Failed;
+            let remote_addr = SocketAddr::new(pair.remote.address, pair.remote.port);
+            self.log_warn(&format!("ICE check to {} failed", remote_addr));
+        }
+    }
+
+    /// Returns the nominated pair media should flow on, if any.
+    pub fn selected_pair(&self) -> Option<&CandidatePair> {
+        self.selected_pair.as_ref()
+    }
+
+    /// Builds a connectivity check for a pair of the check list.
+    ///
+    /// The check carries USE-CANDIDATE when this agent is controlling and
+    /// nominates aggressively. The pair moves to In-Progress until
+    /// the response arrives.
+    ///
+    /// # Arguments
+    /// * `pair_index` - Index into [`candidate_pairs`](Self::candidate_pairs)
+    ///
+    /// # Returns
+    /// * `Ok((Vec<u8>, SocketAddr))` - Encoded request and where to send it
+    /// * `Err(IceError)` - If the index is out of range
+    pub fn build_check(&mut self, pair_index: usize) -> Result<(Vec<u8>, SocketAddr), IceError> {
+        let use_candidate = self.controlling && self.nomination == NominationMode::Aggressive;
+        let check = self.check_request(pair_index, use_candidate)?;
+        self.candidate_pairs[pair_index].state = PairState::InProgress;
+        Ok(check)
+    }
+
+    /// Nominates a pair that already succeeded (regular nomination).
+    ///
+    /// Repeats the check with USE-CANDIDATE set; the pair is selected once
+    /// its response arrives.
+    ///
+    /// # Arguments
+    /// * `pair_index` - Index into [`candidate_pairs`](Self::candidate_pairs)
+    ///
+    /// # Returns
+    /// * `Ok((Vec<u8>, SocketAddr))` - Encoded request and where to send it
+    /// * `Err(IceError)` - If this agent is not controlling or the pair has not succeeded
+    pub fn nominate(&mut self, pair_index: usize) -> Result<(Vec<u8>, SocketAddr), IceError> {
+        let succeeded = self
+            .candidate_pairs
+            .get(pair_index)
+            .is_some_and(|pair| pair.state == PairState::Succeeded);
+        if !self.controlling || !succeeded {
+            return Err(IceError::Configuration(
+                "Only the controlling agent can nominate a succeeded pair".to_string(),
+            ));
+        }
+        self.check_request(pair_index, true)
+    }
+
+    fn check_request(
+        &mut self,
+        pair_index: usize,
+        use_candidate: bool,
+    ) -> Result<(Vec<u8>, SocketAddr), IceError> {
+        let pair = self
+            .candidate_pairs
+            .get(pair_index)
+            .ok_or(IceError::NoCandidates)?;
+
+        // PRIORITY is the priority a peer reflexive candidate would get
+        let local_pref = (pair.local.priority >> 8) & 0xFFFF;
+        let check = CheckRequest {
+            priority: Candidate::calculate_priority(
+                CandidateType::Prflx.type_preference(),
+                local_pref,
+                pair.local.component_id,
+            ),
+            controlling: self.controlling,
+            tie_breaker: self.tie_breaker,
+            use_candidate,
+        };
+        let remote_addr = SocketAddr::new(pair.remote.address, pair.remote.port);
+
+        let transaction_id = connectivity::new_transaction_id();
+        self.pending_checks.push(PendingCheck {
+            transaction_id,
+            pair_index,
+            use_candidate,
+        });
+        Ok((check.encode(transaction_id), remote_addr))
+    }
+
+    /// Processes the response to one of our connectivity checks.
+    ///
+    /// A success response marks the pair Succeeded, unfreezes the pairs
+    /// sharing its foundation and, if the check carried USE-CANDIDATE,
+    /// selects the pair. An error response marks the pair Failed.
+    ///
+    /// # Arguments
+    /// * `data` - The received packet
+    /// * `from` - Address it came from
+    ///
+    /// # Returns
+    /// The index of the pair that succeeded, or `None` if the packet does not
+    /// answer a pending check
+    pub fn handle_check_response(&mut self, data: &[u8], from: SocketAddr) -> Option<usize> {
+        let message = stun::Message::decode(data).ok()?;
+        if message.message_type() == stun::MessageType::Request {
+            return None;
+        }
+
+        let transaction_id = message.transaction_id();
+        let position = self
+            .pending_checks
+            .iter()
+            .position(|check| check.transaction_id == transaction_id)?;
+        let check = self.pending_checks[position];
+        let pair = self.candidate_pairs.get_mut(check.pair_index)?;
+        if SocketAddr::new(pair.remote.address, pair.remote.port) != from {
+            return None;
+        }
+
+        if message.message_type() == stun::MessageType::ErrorResponse {
+            self.fail_check(check.pair_index);
+            return None;
+        }
+
+        self.pending_checks.remove(position);
+        pair.state = PairState::Succeeded;
+        self.unfreeze_foundation(check.pair_index);
+        if check.use_candidate {
+            self.nominate_pair(check.pair_index);
+        }
+        if self.consent.is_some() {
+            self.warm_backup_pairs(Instant::now());
+        }
+        Some(check.pair_index)
+    }
+
+    /// Answers a connectivity check received from the peer.
+    ///
+    /// A Frozen or Failed pair the check arrived on moves to Waiting, so it is
+    /// checked back (triggered check). When this agent is controlled and the
+    /// check carries USE-CANDIDATE, that pair is selected.
+    ///
+    /// # Arguments
+    /// * `data` - The received packet
+    /// * `from` - Address it came from
+    ///
+    /// # Returns
+    /// The encoded success response, or `None` if the packet is not a
+    /// Binding request
+    pub fn handle_check_request(&mut self, data: &[u8], from: SocketAddr) -> Option<Vec<u8>> {
+        let (transaction_id, check) = CheckRequest::decode(data)?;
+
+        let pair_index = self
+            .candidate_pairs
+            .iter()
+            .position(|pair| SocketAddr::new(pair.remote.address, pair.remote.port) == from);
+        if let Some(pair_index) = pair_index {
+            let pair = &mut self.candidate_pairs[pair_index];
+            if matches!(pair.state, PairState::Frozen | PairState::Failed) {
+                pair.state = PairState::Waiting;
+            }
+            if check.use_candidate && !self.controlling {
+                pair.state = PairState::Succeeded;
+                self.nominate_pair(pair_index);
+            }
+        }
+
+        Some(connectivity::build_check_response(transaction_id))
+    }
+
+    /// Marks a pair nominated and selects it unless a higher priority pair
+    /// is already selected.
+    fn nominate_pair(&mut self, pair_index: usize) {
+        self.candidate_pairs[pair_index].nominated = true;
+        let pair = self.candidate_pairs[pair_index].clone();
+
+        let replaces = self
+            .selected_pair
+            .as_ref()
+            .is_none_or(|selected| pair.priority > selected.priority);
+        if replaces {
+            self.set_selected_pair(pair);
+            self.set_connection_state(ConnectionState::Connected);
+        }
+    }
+
+    /// Sets how long [`run_connectivity_checks`](Self::run_connectivity_checks)
+    /// may take before the agent reports failure.
+    ///
+    /// # Arguments
+    /// * `timeout` - Overall checking deadline (defaults to 30 seconds)
+    pub fn set_checking_timeout(&mut self, timeout: Duration) {
+        self.checking_timeout = timeout;
+    }
+
+    /// Returns a token that cancels this agent's connectivity checks.
+    ///
+    /// Keep it before handing the agent to the thread running the checks;
+    /// cancelling makes [`run_connectivity_checks`](Self::run_connectivity_checks)
+    /// return within a few milliseconds.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// Runs connectivity checks on `socket` until a pair is selected.
+    ///
+    /// Starts one check every Ta, retransmits unanswered checks with
+    /// exponential backoff and answers the peer's checks. The controlling
+    /// agent nominates the first pair that succeeds (regular nomination) or
+    /// nominates with every check (aggressive). Blocks the calling thread,
+    /// bounded by the checking timeout and the cancel token.
+    ///
+    /// # Arguments
+    /// * `socket` - Socket of the local candidate the checks are sent from
+    ///
+    /// # Returns
+    /// * `Ok(())` - If a pair was selected
+    /// * `Err(IceError::CheckingTimeout)` - If the deadline passed first; the state becomes Failed
+    /// * `Err(IceError::Cancelled)` - If the cancel token was used; the state becomes Closed
+    /// * `Err(IceError)` - If every pair failed or the socket failed
+    pub fn run_connectivity_checks(&mut self, socket: &CandidateSocket) -> Result<(), IceError> {
+        if self.candidate_pairs.is_empty() {
+            return Err(IceError::NoCandidates);
+        }
+
+        let deadline = Instant::now() + self.checking_timeout;
+        let mut next_check_at = Instant::now();
+        let mut in_flight: Vec<CheckInFlight> = Vec::new();
+        // Pair being nominated and when to repeat the nomination
+        let mut nominating: Option<(usize, Instant)> = None;
+        let mut buf = [0u8; 1500];
+        self.set_connection_state(ConnectionState::Checking);
+
+        loop {
+            if self.cancel.is_cancelled() {
+                self.pending_checks.clear();
+                self.log_info("ICE connectivity checks cancelled");
+                self.set_connection_state(ConnectionState::Closed);
+                return Err(IceError::Cancelled);
+            }
+            if self.selected_pair.is_some() {
+                self.pending_checks.clear();
+                return Ok(());
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                self.pending_checks.clear();
+                self.log_warn(&format!(
+                    "No ICE candidate pair selected within {:?}",
+                    self.checking_timeout
+                ));
+                self.set_connection_state(ConnectionState::Failed);
+                return Err(IceError::CheckingTimeout);
+            }
+
+            if now >= next_check_at
+                && let Some(pair_index) = self.next_pair_to_check()
+            {
+                let (request, to) = self.build_check(pair_index)?;
+                socket.send_to(&request, to)?;
+                in_flight.push(CheckInFlight {
+                    pair_index,
+                    retransmit_at: now + CHECK_INITIAL_RTO,
+                    rto: CHECK_INITIAL_RTO,
+                });
+                next_check_at = now + CHECK_PACING;
+            }
+
+            in_flight.retain(|check| {
+                self.candidate_pairs[check.pair_index].state == PairState::InProgress
+            });
+            for check in in_flight
+                .iter_mut()
+                .filter(|check| now >= check.retransmit_at)
+            {
+                // Only the latest transaction of a pair is kept pending
+                self.pending_checks
+                    .retain(|pending| pending.pair_index != check.pair_index);
+                let (request, to) = self.build_check(check.pair_index)?;
+                socket.send_to(&request, to)?;
+                check.rto = (check.rto * 2).min(CHECK_MAX_RTO);
+                check.retransmit_at = now + check.rto;
+            }
+            if let Some((pair_index, retransmit_at)) = nominating
+                && now >= retransmit_at
+            {
+                let (nomination, to) = self.nominate(pair_index)?;
+                socket.send_to(&nomination, to)?;
+                nominating = Some((pair_index, now + CHECK_MAX_RTO));
+            }
+
+            let mut received = false;
+            while let Ok((size, from)) = socket.recv_from(&mut buf) {
+                received = true;
+                let data = &buf[..size];
+                if let Some(response) = self.handle_check_request(data, from) {
+                    socket.send_to(&response, from)?;
+                } else if let Some(pair_index) = self.handle_check_response(data, from)
+                    && self.controlling
+                    && self.nomination == NominationMode::Regular
+                    && nominating.is_none()
+                {
+                    let (nomination, to) = self.nominate(pair_index)?;
+                    socket.send_to(&nomination, to)?;
+                    nominating = Some((pair_index, Instant::now() + CHECK_MAX_RTO));
+                }
+            }
+
+            let all_failed = self
+                .candidate_pairs
+                .iter()
+                .all(|pair| pair.state == PairState::Failed);
+            if all_failed {
+                self.set_connection_state(ConnectionState::Failed);
+                return Err(IceError::ConnectivityCheckFailed);
+            }
+
+            if !received {
+                std::thread::sleep(CHECK_POLL_INTERVAL);
+            }
+        }
+    }
+
     /// Exports local candidates as SDP attribute strings.
     ///
     /// # Returns
@@ -483,7 +1473,7 @@ impl IceAgent {
     pub fn get_local_candidates_sdp(&self) -> Vec<String> {
         self.local_candidates
             .iter()
-            .map(|c| format!("a={}", c))
+            .map(|c| format!("a={}", self.format_local_candidate(c)))
             .collect()
     }
 
@@ -506,7 +1496,7 @@ impl IceAgent {
     pub fn get_local_candidates_strings(&self) -> Vec<String> {
         self.local_candidates
             .iter()
-            .map(|c| c.to_string())
+            .map(|c| self.format_local_candidate(c))
             .collect()
     }
 
@@ -547,6 +1537,10 @@ impl IceAgent {
         self.local_candidates.clear();
         self.remote_candidates.clear();
         self.candidate_pairs.clear();
+        self.pending_checks.clear();
+        self.backups.clear();
+        self.selected_pair = None;
+        self.pregather = None;
     }
 
     /// Returns the current connection state.
@@ -578,7 +1572,7 @@ impl IceAgent {
             return Err(IceError::NoCandidates);
         }
 
-        self.connection_state = ConnectionState::Checking;
+        self.set_connection_state(ConnectionState::Checking);
 
         // Form and prioritize candidate pairs
         self.form_candidate_pairs();
@@ -599,7 +1593,7 @@ impl IceAgent {
             ));
         }
 
-        self.connection_state = ConnectionState::Connected;
+        self.set_connection_state(ConnectionState::Connected);
         self.log_info(&format!(
             "ICE connection established with {} candidate pairs",
             self.candidate_pairs.len()
@@ -667,9 +1661,268 @@ impl IceAgent {
         has_relay_pair || has_srflx_pair || has_host_pair
     }
 
+    /// Sets the consent freshness timing (RFC 7675).
+    ///
+    /// Takes effect the next time consent checks are started.
+    ///
+    /// # Arguments
+    /// * `config` - Request interval and consent timeout
+    pub fn set_consent_config(&mut self, config: ConsentConfig) {
+        self.consent_config = config;
+    }
+
+    /// Starts consent freshness checks on the best candidate pair.
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the agent is connected and checks were started
+    /// * `Err(IceError)` - If there is no connected pair
+    pub fn start_consent_checks(&mut self) -> Result<(), IceError> {
+        let pair = self
+            .get_best_candidate_pair()
+            .ok_or(IceError::NoCandidates)?;
+        let remote_addr = SocketAddr::new(pair.remote.address, pair.remote.port);
+
+        self.start_consent_checks_to(remote_addr)
+    }
+
+    /// Starts consent freshness checks towards the address media is sent to.
+    ///
+    /// Used when STUN shares the media socket: the owner of that socket
+    /// feeds the STUN packets it receives to
+    /// [`process_consent`](Self::process_consent) periodically and sends
+    /// what it returns.
+    ///
+    /// # Arguments
+    /// * `remote_addr` - Remote address of the media path
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the agent is connected and checks were started
+    /// * `Err(IceError)` - If the agent is not connected
+    pub fn start_consent_checks_to(&mut self, remote_addr: SocketAddr) -> Result<(), IceError> {
+        if self.connection_state != ConnectionState::Connected {
+            return Err(IceError::Configuration(
+                "Consent checks require a connected pair".to_string(),
+            ));
+        }
+
+        let now = Instant::now();
+        self.consent = Some(ConsentFreshness::new(remote_addr, self.consent_config, now));
+        self.backups.clear();
+        self.warm_backup_pairs(now);
+        Ok(())
+    }
+
+    /// Starts consent checks on succeeded RTP pairs not yet kept warm.
+    ///
+    /// Pairs towards the address media is sent to are skipped.
+    fn warm_backup_pairs(&mut self, now: Instant) {
+        let Some(media_addr) = self.consent.as_ref().map(ConsentFreshness::remote_addr) else {
+            return;
+        };
+
+        for pair in &self.candidate_pairs {
+            let remote_addr = SocketAddr::new(pair.remote.address, pair.remote.port);
+            let warm = remote_addr == media_addr
+                || self
+                    .backups
+                    .iter()
+                    .any(|backup| backup.consent.remote_addr() == remote_addr);
+            if pair.state == PairState::Succeeded
+                && pair.local.component_id == Candidate::RTP_COMPONENT
+                && !warm
+            {
+                self.backups.push(BackupPair {
+                    pair: pair.clone(),
+                    consent: ConsentFreshness::new(remote_addr, self.consent_config, now),
+                });
+            }
+        }
+    }
+
+    /// Returns the consent requests due on backup pairs.
+    ///
+    /// Keeps the pairs media can fail over to warm; send each request
+    /// alongside [`poll_consent_request`](Self::poll_consent_request).
+    ///
+    /// # Arguments
+    /// * `now` - Current time
+    pub fn poll_backup_requests(&mut self, now: Instant) -> Vec<(Vec<u8>, SocketAddr)> {
+        self.backups
+            .iter_mut()
+            .filter_map(|backup| {
+                let request = backup.consent.poll_request(now)?;
+                Some((request, backup.consent.remote_addr()))
+            })
+            .collect()
+    }
+
+    /// Moves media to the best backup pair that still has consent.
+    ///
+    /// # Returns
+    /// `true` if a backup was promoted to selected pair
+    fn fail_over(&mut self, now: Instant) -> bool {
+        let best = self
+            .backups
+            .iter()
+            .enumerate()
+            .filter(|(_, backup)| !backup.consent.is_stale(now))
+            .max_by_key(|(_, backup)| backup.pair.priority)
+            .map(|(index, _)| index);
+        let Some(index) = best else {
+            return false;
+        };
+
+        let backup = self.backups.remove(index);
+        self.log_warn(&format!(
+            "ICE selected pair lost consent, failing over to {}",
+            backup.consent.remote_addr()
+        ));
+        self.consent = Some(backup.consent);
+        self.set_selected_pair(backup.pair);
+        true
+    }
+
+    /// Runs one round of consent freshness on the selected pair's socket.
+    ///
+    /// Receives the waiting datagrams, runs
+    /// [`process_consent`](Self::process_consent) on the STUN ones and sends
+    /// what it returns. The socket must be non-blocking.
+    ///
+    /// DTLS, RTP and any other non-STUN traffic waiting on the socket is
+    /// passed on to `media`.
+    ///
+    /// # Arguments
+    /// * `socket` - Socket of the selected pair's local candidate
+    /// * `media` - Receives the datagrams that are not STUN
+    ///
+    /// # Returns
+    /// The connection state after the check
+    pub fn poll_consent<H: DatagramHandler + ?Sized>(
+        &mut self,
+        socket: &CandidateSocket,
+        media: &mut H,
+    ) -> Result<ConnectionState, IceError> {
+        let mut stun = Vec::new();
+        if self.consent.is_some() {
+            socket.recv_dispatch(&mut ConsentDispatch {
+                stun: &mut stun,
+                media,
+            })?;
+        }
+
+        for (packet, to) in self.process_consent(stun, Instant::now()) {
+            socket.send_to(&packet, to)?;
+        }
+        Ok(self.connection_state)
+    }
+
+    /// Runs one round of consent freshness over STUN received on the media path.
+    ///
+    /// Answers the peer's checks, refreshes consent with the responses to
+    /// ours, keeps backup pairs warm and updates the connection state (see
+    /// [`update_consent_state`](Self::update_consent_state)). Used directly
+    /// when another layer owns the media socket.
+    ///
+    /// # Arguments
+    /// * `received` - STUN packets received since the last round, with sender
+    /// * `now` - Current time
+    ///
+    /// # Returns
+    /// STUN packets to send on the media socket, with their destination
+    pub fn process_consent(
+        &mut self,
+        received: Vec<(Vec<u8>, SocketAddr)>,
+        now: Instant,
+    ) -> Vec<(Vec<u8>, SocketAddr)> {
+        let mut outgoing = Vec::new();
+
+        for (data, from) in received {
+            if let Some(response) = self.handle_check_request(&data, from) {
+                outgoing.push((response, from));
+            } else {
+                self.handle_consent_response(&data, from, now);
+            }
+        }
+
+        outgoing.extend(self.poll_consent_request(now));
+        outgoing.extend(self.poll_backup_requests(now));
+        self.update_consent_state(now);
+
+        outgoing
+    }
+
+    /// Returns a consent request if one is due, with the address to send it to.
+    ///
+    /// # Arguments
+    /// * `now` - Current time
+    pub fn poll_consent_request(&mut self, now: Instant) -> Option<(Vec<u8>, SocketAddr)> {
+        let consent = self.consent.as_mut()?;
+        let request = consent.poll_request(now)?;
+        Some((request, consent.remote_addr()))
+    }
+
+    /// Processes a STUN packet received on the media path.
+    ///
+    /// # Returns
+    /// `true` if the packet answered a consent request on the selected or a
+    /// backup pair and refreshed its consent
+    pub fn handle_consent_response(&mut self, data: &[u8], from: SocketAddr, now: Instant) -> bool {
+        self.consent
+            .iter_mut()
+            .chain(self.backups.iter_mut().map(|backup| &mut backup.consent))
+            .any(|consent| consent.handle_response(data, from, now))
+    }
+
+    /// Updates the connection state from the consent freshness state.
+    ///
+    /// When responses stop on the selected pair and a backup pair still has
+    /// consent, media fails over to the backup (see
+    /// [`on_selected_pair_change`](Self::on_selected_pair_change)).
+    /// Otherwise the agent moves to `Disconnected` while responses are
+    /// missing, back to `Connected` once they resume, and to `Failed` when
+    /// consent expires.
+    ///
+    /// # Arguments
+    /// * `now` - Current time
+    ///
+    /// # Returns
+    /// The connection state after the update
+    pub fn update_consent_state(&mut self, now: Instant) -> ConnectionState {
+        self.backups
+            .retain(|backup| !backup.consent.is_expired(now));
+
+        if self
+            .consent
+            .as_ref()
+            .is_some_and(|consent| consent.is_stale(now))
+        {
+            self.fail_over(now);
+        }
+
+        let Some(consent) = self.consent.as_ref() else {
+            return self.connection_state;
+        };
+
+        if consent.is_expired(now) {
+            self.consent = None;
+            self.log_warn("ICE consent expired: no responses on the selected pair");
+            self.set_connection_state(ConnectionState::Failed);
+        } else if consent.is_stale(now) {
+            if self.connection_state == ConnectionState::Connected {
+                self.log_warn("ICE consent responses missing, path disconnected");
+                self.set_connection_state(ConnectionState::Disconnected);
+            }
+        } else if self.connection_state == ConnectionState::Disconnected {
+            self.log_info("ICE consent restored, path connected again");
+            self.set_connection_state(ConnectionState::Connected);
+        }
+
+        self.connection_state
+    }
+
     /// Gets the best candidate pair for connection.
     ///
-    /// Returns the highest priority valid candidate pair.
+    /// Returns the highest priority valid RTP (component 1) candidate pair.
     /// Priority order: Relay > Srflx > Host
     ///
     /// # Returns
@@ -677,8 +1930,14 @@ impl IceAgent {
     pub fn get_best_candidate_pair(&self) -> Option<&crate::candidate_pair::CandidatePair> {
         use crate::candidate_type::CandidateType;
 
+        let rtp_pairs = || {
+            self.candidate_pairs
+                .iter()
+                .filter(|pair| pair.local.component_id == Candidate::RTP_COMPONENT)
+        };
+
         // First try relay pairs (most reliable)
-        for pair in &self.candidate_pairs {
+        for pair in rtp_pairs() {
             if matches!(
                 (&pair.local.candidate_type, &pair.remote.candidate_type),
                 (CandidateType::Relay, _) | (_, CandidateType::Relay)
@@ -688,7 +1947,7 @@ impl IceAgent {
         }
 
         // Then srflx pairs (P2P through NAT)
-        for pair in &self.candidate_pairs {
+        for pair in rtp_pairs() {
             if matches!(
                 (&pair.local.candidate_type, &pair.remote.candidate_type),
                 (CandidateType::Srflx, _) | (_, CandidateType::Srflx)
@@ -698,7 +1957,7 @@ impl IceAgent {
         }
 
         // Finally host pairs (LAN only)
-        for pair in &self.candidate_pairs {
+        for pair in rtp_pairs() {
             if matches!(
                 (&pair.local.candidate_type, &pair.remote.candidate_type),
                 (CandidateType::Host, CandidateType::Host)
@@ -708,14 +1967,41 @@ impl IceAgent {
         }
 
         // Fallback to highest priority pair
-        self.candidate_pairs.first()
+        rtp_pairs().next()
+    }
+}
+
+/// Provides a default implementation for [`IceAgent`].
+impl Default for IceAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Routes the datagrams read during a consent poll.
+///
+/// STUN is kept for the consent round; everything else goes on to the
+/// media handler untouched.
+struct ConsentDispatch<'a, H: ?Sized> {
+    stun: &'a mut Vec<(Vec<u8>, SocketAddr)>,
+    media: &'a mut H,
+}
+
+impl<H: DatagramHandler + ?Sized> DatagramHandler for ConsentDispatch<'_, H> {
+    fn handle_stun(&mut self, data: &[u8], from: SocketAddr) {
+        self.stun.push((data.to_vec(), from));
+    }
+
+    fn handle_dtls(&mut self, data: &[u8], from: SocketAddr) {
+        self.media.handle_dtls(data, from);
+    }
+
+    fn handle_rtp(&mut self, data: &[u8], from: SocketAddr) {
+        self.media.handle_rtp(data, from);
     }
-}
 
-/// Provides a default implementation for [`IceAgent`].
-impl Default for IceAgent {
-    fn default() -> Self {
-        Self::new()
+    fn handle_unknown(&mut self, data: &[u8], from: SocketAddr) {
+        self.media.handle_unknown(data, from);
     }
 }
 
@@ -723,7 +2009,7 @@ impl Default for IceAgent {
 mod tests {
     use super::*;
     use crate::candidate_type::CandidateType;
-    use std::net::{IpAddr, Ipv4Addr};
+    use std::net::{IpAddr, Ipv4Addr, UdpSocket};
 
     fn create_test_candidate(port: u16) -> Candidate {
         Candidate {
@@ -736,6 +2022,7 @@ mod tests {
             candidate_type: CandidateType::Host,
             related_address: None,
             related_port: None,
+            tcp_type: None,
         }
     }
 
@@ -817,7 +2104,7 @@ mod tests {
         let result = agent.gather_host_candidates(8080);
 
         assert!(result.is_ok());
-        assert_eq!(agent.local_candidates.len(), 1);
+        assert!(!agent.local_candidates.is_empty());
 
         let candidate = &agent.local_candidates[0];
         assert_eq!(candidate.port, 8080);
@@ -860,7 +2147,7 @@ mod tests {
             .add_remote_candidate(create_test_candidate(9090))
             .unwrap();
 
-        assert_eq!(agent.get_candidate_pairs().len(), 1);
+        assert_eq!(agent.candidate_pairs().len(), 1);
     }
 
     #[test]
@@ -882,7 +2169,29 @@ mod tests {
             .unwrap();
 
         // 2 local * 2 remote = 4 pairs
-        assert_eq!(agent.get_candidate_pairs().len(), 4);
+        assert_eq!(agent.candidate_pairs().len(), 4);
+    }
+
+    #[test]
+    fn test_form_candidate_pairs_matches_tcp_types() {
+        use crate::tcp_type::TcpType;
+
+        let tcp = |tcp_type, port| Candidate {
+            transport: "TCP".to_string(),
+            tcp_type: Some(tcp_type),
+            ..create_test_candidate(port)
+        };
+
+        let mut agent = IceAgent::new();
+        agent.add_local_candidate(tcp(TcpType::Active, 9)).unwrap();
+        agent.add_remote_candidate(tcp(TcpType::Active, 9)).unwrap();
+        agent
+            .add_remote_candidate(tcp(TcpType::Passive, 9091))
+            .unwrap();
+
+        // active/active cannot connect, only active/passive is kept
+        assert_eq!(agent.candidate_pairs().len(), 1);
+        assert_eq!(agent.candidate_pairs()[0].remote.port, 9091);
     }
 
     #[test]
@@ -897,7 +2206,7 @@ mod tests {
         high_priority.priority = 1000;
         agent.add_remote_candidate(high_priority).unwrap();
 
-        let pairs = agent.get_candidate_pairs();
+        let pairs = agent.candidate_pairs();
 
         // Verificar que están ordenados de mayor a menor
         for i in 0..pairs.len().saturating_sub(1) {
@@ -950,6 +2259,7 @@ mod tests {
     #[test]
     fn test_get_local_candidates_sdp() {
         let mut agent = IceAgent::new();
+        agent.set_rtcp_mux(true);
         agent.gather_host_candidates(8080).unwrap();
 
         let sdp = agent.get_local_candidates_sdp();
@@ -990,7 +2300,7 @@ mod tests {
 
         assert!(agent.local_candidates.is_empty());
         assert!(agent.remote_candidates.is_empty());
-        assert!(agent.get_candidate_pairs().is_empty());
+        assert!(agent.candidate_pairs().is_empty());
     }
 
     #[test]
@@ -1135,7 +2445,7 @@ mod tests {
         assert!(result.is_ok());
 
         // Should have valid pairs
-        assert!(!agent.get_candidate_pairs().is_empty());
+        assert!(!agent.candidate_pairs().is_empty());
     }
 
     #[test]
@@ -1181,7 +2491,7 @@ mod tests {
     #[test]
     fn test_get_candidate_pairs_empty_initially() {
         let agent = IceAgent::new();
-        assert_eq!(agent.get_candidate_pairs().len(), 0);
+        assert_eq!(agent.candidate_pairs().len(), 0);
     }
 
     #[test]
@@ -1194,12 +2504,444 @@ mod tests {
         agent
             .add_remote_candidate(create_test_candidate(9090))
             .unwrap();
-        assert_eq!(agent.get_candidate_pairs().len(), 1);
+        assert_eq!(agent.candidate_pairs().len(), 1);
 
         agent
             .add_remote_candidate(create_test_candidate(9091))
             .unwrap();
-        assert_eq!(agent.get_candidate_pairs().len(), 2);
+        assert_eq!(agent.candidate_pairs().len(), 2);
+    }
+
+    #[test]
+    fn test_candidate_pairs_ordered_and_pruned() {
+        let mut agent = IceAgent::new();
+
+        let host = create_test_candidate(8080);
+        let mut srflx = create_test_candidate(8080);
+        srflx.candidate_type = CandidateType::Srflx;
+        srflx.address = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 10));
+        srflx.priority = 1694498815;
+        srflx.related_address = Some(host.address);
+        srflx.related_port = Some(host.port);
+        let mut other_host = create_test_candidate(8082);
+        other_host.priority = 2130706175;
+
+        agent.add_local_candidate(srflx).unwrap();
+        agent.add_local_candidate(other_host).unwrap();
+        agent.add_local_candidate(host).unwrap();
+
+        let mut remote_host = create_test_candidate(9090);
+        remote_host.address = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        let mut remote_srflx = create_test_candidate(9090);
+        remote_srflx.candidate_type = CandidateType::Srflx;
+        remote_srflx.address = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 7));
+        remote_srflx.priority = 1694498815;
+        agent.add_remote_candidate(remote_srflx).unwrap();
+        agent.add_remote_candidate(remote_host).unwrap();
+
+        let pairs = agent.candidate_pairs();
+
+        // The local srflx shares its base with the host candidate on 8080
+        assert_eq!(pairs.len(), 4);
+        assert!(
+            pairs
+                .iter()
+                .all(|pair| pair.local.candidate_type == CandidateType::Host)
+        );
+        assert!(pairs.windows(2).all(|w| w[0].priority >= w[1].priority));
+
+        let order: Vec<(u16, CandidateType)> = pairs
+            .iter()
+            .map(|pair| (pair.local.port, pair.remote.candidate_type.clone()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (8080, CandidateType::Host),
+                (8082, CandidateType::Host),
+                (8080, CandidateType::Srflx),
+                (8082, CandidateType::Srflx),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unrelated_srflx_pair_is_kept() {
+        let mut agent = IceAgent::new();
+
+        // srflx whose base is not among the local candidates
+        let mut srflx = create_test_candidate(8080);
+        srflx.candidate_type = CandidateType::Srflx;
+        srflx.related_address = Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 50)));
+        srflx.related_port = Some(5000);
+        agent.add_local_candidate(srflx).unwrap();
+        agent
+            .add_local_candidate(create_test_candidate(8081))
+            .unwrap();
+        agent
+            .add_remote_candidate(create_test_candidate(9090))
+            .unwrap();
+
+        assert_eq!(agent.candidate_pairs().len(), 2);
+    }
+
+    #[test]
+    fn test_mdns_hides_host_ip() {
+        let mut agent = IceAgent::new();
+        let host = create_test_candidate(8080);
+        let mut srflx = create_test_candidate(8080);
+        srflx.candidate_type = CandidateType::Srflx;
+        srflx.address = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 10));
+        srflx.related_address = Some(host.address);
+        srflx.related_port = Some(8080);
+        agent.add_local_candidate(host).unwrap();
+        agent.add_local_candidate(srflx).unwrap();
+
+        agent.enable_mdns(true);
+        assert!(agent.is_mdns_enabled());
+
+        let candidates = agent.get_local_candidates_strings();
+        assert!(candidates.iter().all(|c| !c.contains("192.168.1.1")));
+        assert!(candidates[0].contains(".local 8080 typ host"));
+        assert!(candidates[1].contains("raddr 0.0.0.0 rport 0"));
+
+        let hostname = candidates[0].split_whitespace().nth(4).unwrap();
+        let registry = agent.mdns_registry().unwrap();
+        assert_eq!(
+            registry.resolve(hostname),
+            Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)))
+        );
+
+        agent.enable_mdns(false);
+        assert!(agent.get_local_candidates_strings()[0].contains("192.168.1.1"));
+    }
+
+    #[test]
+    fn test_remote_mdns_candidate_is_resolved() {
+        let mut agent = IceAgent::new();
+        agent.enable_mdns(true);
+        agent
+            .add_local_candidate(create_test_candidate(8080))
+            .unwrap();
+
+        // Our own hostname resolves without going to the network
+        let local = agent.get_local_candidates_sdp();
+        agent.add_remote_candidates_from_sdp(&local).unwrap();
+
+        assert_eq!(
+            agent.remote_candidates[0].address,
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))
+        );
+    }
+
+    #[test]
+    fn test_remote_mdns_candidate_is_resolved_over_network() {
+        let mut offerer = IceAgent::new();
+        offerer.set_mdns_group("127.0.0.1:0".parse().unwrap());
+        offerer.enable_mdns(true);
+        offerer
+            .add_local_candidate(create_test_candidate(8080))
+            .unwrap();
+        let responder = offerer.mdns_responder_addr().unwrap();
+
+        // The answerer has no registry of its own and must query the responder
+        let mut answerer = IceAgent::new();
+        answerer.set_mdns_group(responder);
+        answerer
+            .add_remote_candidates_from_sdp(&offerer.get_local_candidates_sdp())
+            .unwrap();
+
+        assert_eq!(answerer.remote_candidates.len(), 1);
+        assert_eq!(
+            answerer.remote_candidates[0].address,
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))
+        );
+    }
+
+    #[test]
+    fn test_unresolved_mdns_candidate_is_skipped() {
+        // Nothing listens here, so the query fails instead of resolving
+        let unused = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let group = unused.local_addr().unwrap();
+        drop(unused);
+
+        let mut agent = IceAgent::new();
+        agent.set_mdns_group(group);
+        agent
+            .add_remote_candidates_from_sdp(&[
+                "a=candidate:1 1 UDP 2130706431 unknown-peer.local 5000 typ host".to_string(),
+                "a=candidate:2 1 UDP 2130706431 10.0.0.2 5001 typ host".to_string(),
+            ])
+            .unwrap();
+
+        assert_eq!(agent.remote_candidates.len(), 1);
+        assert_eq!(agent.remote_candidates[0].port, 5001);
+    }
+
+    #[test]
+    fn test_state_change_callback_fires() {
+        use std::sync::{Arc, Mutex};
+
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&states);
+
+        let mut agent = IceAgent::new();
+        agent.on_state_change(move |state| recorded.lock().unwrap().push(state));
+        agent
+            .add_local_candidate(create_test_candidate(8080))
+            .unwrap();
+        agent
+            .add_remote_candidate(create_test_candidate(9090))
+            .unwrap();
+        agent.establish_connection().unwrap();
+
+        assert_eq!(
+            *states.lock().unwrap(),
+            vec![ConnectionState::Checking, ConnectionState::Connected]
+        );
+    }
+
+    /// Media handler that drops everything
+    struct IgnoredMedia;
+
+    impl DatagramHandler for IgnoredMedia {
+        fn handle_stun(&mut self, _data: &[u8], _from: SocketAddr) {}
+        fn handle_dtls(&mut self, _data: &[u8], _from: SocketAddr) {}
+        fn handle_rtp(&mut self, _data: &[u8], _from: SocketAddr) {}
+    }
+
+    /// Media handler that keeps every datagram handed to it
+    #[derive(Default)]
+    struct ForwardedMedia {
+        stun: Vec<Vec<u8>>,
+        dtls: Vec<Vec<u8>>,
+        rtp: Vec<Vec<u8>>,
+    }
+
+    impl DatagramHandler for ForwardedMedia {
+        fn handle_stun(&mut self, data: &[u8], _from: SocketAddr) {
+            self.stun.push(data.to_vec());
+        }
+
+        fn handle_dtls(&mut self, data: &[u8], _from: SocketAddr) {
+            self.dtls.push(data.to_vec());
+        }
+
+        fn handle_rtp(&mut self, data: &[u8], _from: SocketAddr) {
+            self.rtp.push(data.to_vec());
+        }
+    }
+
+    #[test]
+    fn test_consent_poll_answers_stun_and_passes_media_on() {
+        use std::time::Duration;
+        use stun::{Message, MessageType};
+
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        peer.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+        let peer_addr = peer.local_addr().unwrap();
+
+        let mut local = create_test_candidate(0);
+        local.address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let socket = CandidateSocket::new(local.clone()).unwrap();
+        let local_addr = socket.socket.local_addr().unwrap();
+        let mut remote = create_test_candidate(peer_addr.port());
+        remote.address = peer_addr.ip();
+
+        let mut agent = IceAgent::new();
+        agent.set_consent_config(ConsentConfig {
+            interval: Duration::from_millis(10),
+            ..ConsentConfig::default()
+        });
+        agent.add_local_candidate(local).unwrap();
+        agent.add_remote_candidate(remote).unwrap();
+        agent.establish_connection().unwrap();
+        agent.start_consent_checks().unwrap();
+
+        let mut media = ForwardedMedia::default();
+        std::thread::sleep(Duration::from_millis(20));
+        agent.poll_consent(&socket, &mut media).unwrap();
+
+        let mut buf = [0u8; 1500];
+        let (size, _) = peer.recv_from(&mut buf).unwrap();
+        let consent_request = Message::decode(&buf[..size]).unwrap();
+
+        // Media and a peer check arrive alongside the consent response
+        let dtls = vec![22, 254, 253, 0, 0];
+        let rtp = vec![0x80, 96, 0, 1];
+        let peer_check = Message::new(MessageType::Request, [9; 12]).encode();
+        let response = Message::new(MessageType::Response, consent_request.transaction_id());
+        for datagram in [&dtls, &rtp, &peer_check, &response.encode()] {
+            peer.send_to(datagram, local_addr).unwrap();
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        agent.poll_consent(&socket, &mut media).unwrap();
+
+        assert_eq!(media.dtls, vec![dtls]);
+        assert_eq!(media.rtp, vec![rtp]);
+        assert!(media.stun.is_empty());
+
+        // The agent answered the peer's check itself
+        let (size, _) = peer.recv_from(&mut buf).unwrap();
+        let answer = Message::decode(&buf[..size]).unwrap();
+        assert_eq!(answer.transaction_id(), [9; 12]);
+    }
+
+    #[test]
+    fn test_consent_expiry_fails_connection() {
+        use std::net::UdpSocket;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::{Arc, Mutex};
+        use std::thread;
+        use std::time::Duration;
+        use stun::{Message, MessageType};
+
+        // Mock peer: answers Binding requests until told to stop responding
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        peer.set_read_timeout(Some(Duration::from_millis(5)))
+            .unwrap();
+        let peer_addr = peer.local_addr().unwrap();
+        let responding = Arc::new(AtomicBool::new(true));
+        let running = Arc::new(AtomicBool::new(true));
+        let peer_thread = {
+            let responding = Arc::clone(&responding);
+            let running = Arc::clone(&running);
+            thread::spawn(move || {
+                let mut buf = [0u8; 1500];
+                while running.load(Ordering::SeqCst) {
+                    if let Ok((size, from)) = peer.recv_from(&mut buf)
+                        && responding.load(Ordering::SeqCst)
+                        && let Ok(request) = Message::decode(&buf[..size])
+                    {
+                        let response =
+                            Message::new(MessageType::Response, request.transaction_id());
+                        let _ = peer.send_to(&response.encode(), from);
+                    }
+                }
+            })
+        };
+
+        let mut local = create_test_candidate(0);
+        local.address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let socket = CandidateSocket::new(local.clone()).unwrap();
+        let mut remote = create_test_candidate(peer_addr.port());
+        remote.address = peer_addr.ip();
+
+        let failed = Arc::new(Mutex::new(None));
+        let failed_at = Arc::clone(&failed);
+        let mut agent = IceAgent::new();
+        agent.on_state_change(move |state| {
+            if state == ConnectionState::Failed {
+                *failed_at.lock().unwrap() = Some(Instant::now());
+            }
+        });
+        agent.set_consent_config(ConsentConfig {
+            interval: Duration::from_millis(20),
+            disconnect_timeout: Duration::from_millis(100),
+            timeout: Duration::from_millis(200),
+        });
+        agent.add_local_candidate(local).unwrap();
+        agent.add_remote_candidate(remote).unwrap();
+        agent.establish_connection().unwrap();
+        agent.start_consent_checks().unwrap();
+
+        // Responsive peer keeps consent alive well past the timeout
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(400) {
+            assert_eq!(
+                agent.poll_consent(&socket, &mut IgnoredMedia),
+                Ok(ConnectionState::Connected)
+            );
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        // Path silently dies
+        responding.store(false, Ordering::SeqCst);
+        let stopped = Instant::now();
+        while stopped.elapsed() < Duration::from_secs(2)
+            && agent.connection_state() != ConnectionState::Failed
+        {
+            agent.poll_consent(&socket, &mut IgnoredMedia).unwrap();
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        running.store(false, Ordering::SeqCst);
+        peer_thread.join().unwrap();
+
+        let failed_at = failed.lock().unwrap().expect("Failed transition not fired");
+        let elapsed = failed_at - stopped;
+        assert!(
+            elapsed >= Duration::from_millis(150),
+            "failed too early: {:?}",
+            elapsed
+        );
+        assert!(
+            elapsed <= Duration::from_millis(400),
+            "failed too late: {:?}",
+            elapsed
+        );
+    }
+
+    #[test]
+    fn test_consent_on_media_path_disconnects_recovers_and_fails() {
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+        use stun::{Message, MessageType};
+
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&states);
+        let media_remote: SocketAddr = "127.0.0.1:7000".parse().unwrap();
+
+        let mut agent = IceAgent::new();
+        agent.on_state_change(move |state| recorded.lock().unwrap().push(state));
+        agent
+            .add_local_candidate(create_test_candidate(8080))
+            .unwrap();
+        agent
+            .add_remote_candidate(create_test_candidate(9090))
+            .unwrap();
+        agent.establish_connection().unwrap();
+        agent.start_consent_checks_to(media_remote).unwrap();
+        let start = Instant::now();
+
+        let answer = |agent: &mut IceAgent, at: Instant| {
+            let (request, to) = agent.poll_consent_request(at).expect("request due");
+            assert_eq!(to, media_remote);
+            let request = Message::decode(&request).unwrap();
+            let response = Message::new(MessageType::Response, request.transaction_id());
+            assert!(agent.handle_consent_response(&response.encode(), media_remote, at));
+        };
+
+        let at = start + Duration::from_secs(6);
+        answer(&mut agent, at);
+        assert_eq!(agent.update_consent_state(at), ConnectionState::Connected);
+
+        // Responses stop
+        let at = at + Duration::from_secs(13);
+        assert_eq!(
+            agent.update_consent_state(at),
+            ConnectionState::Disconnected
+        );
+
+        // ...and resume
+        answer(&mut agent, at);
+        assert_eq!(agent.update_consent_state(at), ConnectionState::Connected);
+
+        // Path dies for good
+        let at = at + Duration::from_secs(31);
+        assert_eq!(agent.update_consent_state(at), ConnectionState::Failed);
+        assert!(agent.poll_consent_request(at).is_none());
+
+        assert_eq!(
+            *states.lock().unwrap(),
+            vec![
+                ConnectionState::Checking,
+                ConnectionState::Connected,
+                ConnectionState::Disconnected,
+                ConnectionState::Connected,
+                ConnectionState::Failed,
+            ]
+        );
     }
 
     #[test]
@@ -1210,4 +2952,498 @@ mod tests {
         assert!(debug_output.contains("IceAgent"));
         assert!(debug_output.contains("ufrag"));
     }
+
+    fn create_candidate_at(ip: [u8; 4], port: u16) -> Candidate {
+        Candidate {
+            address: IpAddr::V4(Ipv4Addr::from(ip)),
+            ..create_test_candidate(port)
+        }
+    }
+
+    /// Two agents with one host candidate each, already paired.
+    fn connected_agents(mode: NominationMode) -> (IceAgent, IceAgent, SocketAddr, SocketAddr) {
+        let a_candidate = create_candidate_at([192, 168, 1, 1], 5000);
+        let b_candidate = create_candidate_at([192, 168, 1, 2], 6000);
+        let a_addr = SocketAddr::new(a_candidate.address, a_candidate.port);
+        let b_addr = SocketAddr::new(b_candidate.address, b_candidate.port);
+
+        let mut controlling = IceAgent::new();
+        controlling.set_nomination(mode);
+        controlling
+            .add_local_candidate(a_candidate.clone())
+            .unwrap();
+        controlling
+            .add_remote_candidate(b_candidate.clone())
+            .unwrap();
+
+        let mut controlled = IceAgent::new();
+        controlled.set_controlling(false);
+        controlled.add_local_candidate(b_candidate).unwrap();
+        controlled.add_remote_candidate(a_candidate).unwrap();
+
+        (controlling, controlled, a_addr, b_addr)
+    }
+
+    #[test]
+    fn test_aggressive_nomination_selects_first_successful_pair() {
+        let (mut a, mut b, a_addr, b_addr) = connected_agents(NominationMode::Aggressive);
+
+        let (request, to) = a.build_check(0).unwrap();
+        assert_eq!(to, b_addr);
+        assert!(CheckRequest::decode(&request).unwrap().1.use_candidate);
+
+        let response = b.handle_check_request(&request, a_addr).unwrap();
+        assert_eq!(a.handle_check_response(&response, b_addr), Some(0));
+
+        // A single exchange selected the pair on both sides
+        assert!(a.selected_pair().unwrap().nominated);
+        assert_eq!(a.selected_pair().unwrap().remote.port, 6000);
+        assert_eq!(b.selected_pair().unwrap().remote.port, 5000);
+        assert_eq!(a.connection_state(), ConnectionState::Connected);
+        assert_eq!(b.connection_state(), ConnectionState::Connected);
+    }
+
+    #[test]
+    fn test_regular_nomination_needs_follow_up_check() {
+        let (mut a, mut b, a_addr, b_addr) = connected_agents(NominationMode::Regular);
+        assert!(a.nominate(0).is_err());
+
+        let (request, _) = a.build_check(0).unwrap();
+        assert!(!CheckRequest::decode(&request).unwrap().1.use_candidate);
+        let response = b.handle_check_request(&request, a_addr).unwrap();
+        assert_eq!(a.handle_check_response(&response, b_addr), Some(0));
+
+        assert_eq!(a.candidate_pairs()[0].state, PairState::Succeeded);
+        assert!(a.selected_pair().is_none());
+        assert!(b.selected_pair().is_none());
+
+        let (nomination, _) = a.nominate(0).unwrap();
+        let response = b.handle_check_request(&nomination, a_addr).unwrap();
+        a.handle_check_response(&response, b_addr);
+
+        assert!(a.selected_pair().is_some());
+        assert!(b.selected_pair().is_some());
+    }
+
+    #[test]
+    fn test_pair_state_lifecycle() {
+        // RTP and RTCP pairs share a foundation; only the RTP one starts Waiting
+        let rtcp = |candidate: Candidate| Candidate {
+            component_id: 2,
+            port: candidate.port + 1,
+            priority: candidate.priority - 1,
+            ..candidate
+        };
+        let local = create_candidate_at([192, 168, 1, 1], 5000);
+        let remote = create_candidate_at([192, 168, 1, 2], 6000);
+        let mut agent = IceAgent::new();
+        agent.add_local_candidate(local.clone()).unwrap();
+        agent.add_local_candidate(rtcp(local)).unwrap();
+        agent.add_remote_candidate(remote.clone()).unwrap();
+        agent.add_remote_candidate(rtcp(remote)).unwrap();
+
+        let states = |agent: &IceAgent| -> Vec<PairState> {
+            agent.candidate_pairs().iter().map(|p| p.state).collect()
+        };
+        let remote_addr = |agent: &IceAgent, index: usize| {
+            let pair = &agent.candidate_pairs()[index];
+            SocketAddr::new(pair.remote.address, pair.remote.port)
+        };
+        assert_eq!(agent.candidate_pairs()[0].local.component_id, 1);
+        assert_eq!(states(&agent), [PairState::Waiting, PairState::Frozen]);
+
+        // The RTP pair is checked first and succeeds
+        assert_eq!(agent.next_pair_to_check(), Some(0));
+        let (request, to) = agent.build_check(0).unwrap();
+        assert_eq!(states(&agent), [PairState::InProgress, PairState::Frozen]);
+        let (transaction_id, _) = CheckRequest::decode(&request).unwrap();
+        let response = connectivity::build_check_response(transaction_id);
+        assert_eq!(agent.handle_check_response(&response, to), Some(0));
+
+        // Success unfroze the RTCP pair, which is checked next and times out
+        assert_eq!(states(&agent), [PairState::Succeeded, PairState::Waiting]);
+        assert_eq!(agent.next_pair_to_check(), Some(1));
+        let (request, to) = agent.build_check(1).unwrap();
+        assert_eq!(to, remote_addr(&agent, 1));
+        assert_eq!(
+            states(&agent),
+            [PairState::Succeeded, PairState::InProgress]
+        );
+        agent.fail_check(1);
+        assert_eq!(states(&agent), [PairState::Succeeded, PairState::Failed]);
+        assert_eq!(agent.next_pair_to_check(), None);
+
+        // A late response to the failed check changes nothing
+        let (transaction_id, _) = CheckRequest::decode(&request).unwrap();
+        let response = connectivity::build_check_response(transaction_id);
+        assert_eq!(agent.handle_check_response(&response, to), None);
+        assert_eq!(agent.candidate_pairs()[1].state, PairState::Failed);
+    }
+
+    #[test]
+    fn test_check_response_from_wrong_address_is_ignored() {
+        let (mut a, mut b, a_addr, _) = connected_agents(NominationMode::Aggressive);
+
+        let (request, _) = a.build_check(0).unwrap();
+        let response = b.handle_check_request(&request, a_addr).unwrap();
+        let spoofed = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 9)), 6000);
+
+        assert_eq!(a.handle_check_response(&response, spoofed), None);
+        assert!(a.selected_pair().is_none());
+    }
+
+    #[test]
+    fn test_stale_selected_pair_fails_over_to_backup() {
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+        use stun::{Message, MessageType};
+
+        let primary = create_candidate_at([192, 168, 1, 2], 6000);
+        let backup = Candidate {
+            priority: primary.priority - 1000,
+            ..create_candidate_at([10, 0, 0, 2], 7000)
+        };
+        let primary_addr = SocketAddr::new(primary.address, primary.port);
+        let backup_addr = SocketAddr::new(backup.address, backup.port);
+
+        let mut agent = IceAgent::new();
+        agent.set_nomination(NominationMode::Aggressive);
+        agent
+            .add_local_candidate(create_candidate_at([192, 168, 1, 1], 5000))
+            .unwrap();
+        agent.add_remote_candidate(primary).unwrap();
+        agent.add_remote_candidate(backup).unwrap();
+
+        let switches = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&switches);
+        agent.on_selected_pair_change(move |pair| {
+            recorded
+                .lock()
+                .unwrap()
+                .push(SocketAddr::new(pair.remote.address, pair.remote.port));
+        });
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&states);
+        agent.on_state_change(move |state| recorded.lock().unwrap().push(state));
+
+        // Both pairs pass their checks; the higher priority one is selected
+        for pair_index in 0..2 {
+            let (request, to) = agent.build_check(pair_index).unwrap();
+            let (transaction_id, _) = CheckRequest::decode(&request).unwrap();
+            let response = connectivity::build_check_response(transaction_id);
+            assert_eq!(agent.handle_check_response(&response, to), Some(pair_index));
+        }
+        assert_eq!(*switches.lock().unwrap(), vec![primary_addr]);
+
+        agent.start_consent_checks_to(primary_addr).unwrap();
+        let start = Instant::now();
+
+        // Only the backup keeps answering
+        let answer = |request: &[u8]| {
+            let request = Message::decode(request).unwrap();
+            Message::new(MessageType::Response, request.transaction_id()).encode()
+        };
+        for seconds in [6, 12] {
+            let at = start + Duration::from_secs(seconds);
+            let (_, to) = agent.poll_consent_request(at).unwrap();
+            assert_eq!(to, primary_addr);
+            let keepalives = agent.poll_backup_requests(at);
+            assert_eq!(keepalives.len(), 1);
+            let (request, to) = &keepalives[0];
+            assert_eq!(*to, backup_addr);
+            assert!(agent.handle_consent_response(&answer(request), backup_addr, at));
+        }
+
+        let at = start + Duration::from_secs(13);
+        assert_eq!(agent.update_consent_state(at), ConnectionState::Connected);
+        assert_eq!(*switches.lock().unwrap(), vec![primary_addr, backup_addr]);
+        assert_eq!(agent.selected_pair().unwrap().remote.port, 7000);
+
+        // Media and consent now flow on the backup, and the call never dropped
+        let at = start + Duration::from_secs(19);
+        let (request, to) = agent.poll_consent_request(at).unwrap();
+        assert_eq!(to, backup_addr);
+        assert!(agent.handle_consent_response(&answer(&request), backup_addr, at));
+        let at = start + Duration::from_secs(25);
+        assert_eq!(agent.update_consent_state(at), ConnectionState::Connected);
+        assert!(
+            !states
+                .lock()
+                .unwrap()
+                .contains(&ConnectionState::Disconnected)
+        );
+    }
+
+    struct MockInterfaces(Vec<(&'static str, [u8; 4])>);
+
+    impl InterfaceEnumerator for MockInterfaces {
+        fn interfaces(&self) -> Vec<crate::ip_detection::NetworkInterface> {
+            self.0
+                .iter()
+                .map(|(name, ip)| crate::ip_detection::NetworkInterface {
+                    name: name.to_string(),
+                    address: IpAddr::V4(Ipv4Addr::from(*ip)),
+                    is_up: true,
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_gather_host_candidates_one_per_interface() {
+        let mut agent = IceAgent::new();
+        agent.set_interface_enumerator(Box::new(MockInterfaces(vec![
+            ("eth0", [192, 168, 1, 20]),
+            ("wg0", [10, 8, 0, 2]),
+            ("docker0", [172, 17, 0, 1]),
+            ("lo", [127, 0, 0, 1]),
+        ])));
+        agent.set_excluded_interfaces(vec!["docker*".to_string()]);
+        agent.set_rtcp_mux(true);
+
+        agent.gather_host_candidates(5000).unwrap();
+
+        let addresses: Vec<IpAddr> = agent.local_candidates.iter().map(|c| c.address).collect();
+        assert_eq!(
+            addresses,
+            vec![
+                IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)),
+                IpAddr::V4(Ipv4Addr::new(10, 8, 0, 2)),
+            ]
+        );
+        assert!(agent.local_candidates.iter().all(|c| c.port == 5000));
+        assert!(agent.local_candidates[0].priority > agent.local_candidates[1].priority);
+        assert_ne!(
+            agent.local_candidates[0].foundation,
+            agent.local_candidates[1].foundation
+        );
+    }
+
+    #[test]
+    fn test_pregathered_candidates_are_reused_until_stale() {
+        let mut agent = IceAgent::new();
+        agent.set_interface_enumerator(Box::new(MockInterfaces(vec![("eth0", [192, 168, 1, 20])])));
+        agent.set_rtcp_mux(true);
+        assert!(agent.pregather_age().is_none());
+        assert!(!agent.use_pregathered(5000).unwrap());
+
+        agent.pregather(5000, &[], &[]).unwrap();
+        assert_eq!(agent.local_candidates.len(), 1);
+        assert!(agent.pregather_age().unwrap() < DEFAULT_PREGATHER_TTL);
+
+        // Fresh: the cached set is used as is, but only for its port
+        agent.set_interface_enumerator(Box::new(MockInterfaces(vec![
+            ("eth0", [192, 168, 1, 20]),
+            ("wg0", [10, 8, 0, 2]),
+        ])));
+        assert!(agent.use_pregathered(5000).unwrap());
+        assert_eq!(agent.local_candidates.len(), 1);
+        assert!(!agent.use_pregathered(6000).unwrap());
+
+        // Stale: gathered again on use, without duplicates
+        agent.set_pregather_ttl(Duration::ZERO);
+        assert!(agent.use_pregathered(5000).unwrap());
+        assert_eq!(agent.local_candidates.len(), 2);
+        assert!(agent.pregather_age().is_some());
+    }
+
+    #[test]
+    fn test_gather_host_candidates_falls_back_to_unspecified() {
+        let mut agent = IceAgent::new();
+        agent.set_interface_enumerator(Box::new(MockInterfaces(vec![("lo", [127, 0, 0, 1])])));
+        agent.set_rtcp_mux(true);
+
+        agent.gather_host_candidates(5000).unwrap();
+
+        assert_eq!(agent.local_candidates.len(), 1);
+        assert_eq!(
+            agent.local_candidates[0].address,
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        );
+    }
+
+    #[test]
+    fn test_gather_host_candidates_both_components_without_rtcp_mux() {
+        let mut agent = IceAgent::new();
+        agent.set_interface_enumerator(Box::new(MockInterfaces(vec![
+            ("eth0", [192, 168, 1, 20]),
+            ("wg0", [10, 8, 0, 2]),
+        ])));
+
+        agent.gather_host_candidates(5000).unwrap();
+
+        let candidates: Vec<(IpAddr, u32, u16)> = agent
+            .local_candidates
+            .iter()
+            .map(|c| (c.address, c.component_id, c.port))
+            .collect();
+        let eth0 = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+        let wg0 = IpAddr::V4(Ipv4Addr::new(10, 8, 0, 2));
+        assert_eq!(
+            candidates,
+            vec![
+                (eth0, 1, 5000),
+                (eth0, 2, 5001),
+                (wg0, 1, 5000),
+                (wg0, 2, 5001)
+            ]
+        );
+        assert!(
+            agent.get_local_candidates_strings()[1].starts_with(&format!(
+                "candidate:{} 2 UDP",
+                agent.local_candidates[1].foundation
+            ))
+        );
+        assert_eq!(
+            agent.local_candidates[0].priority - agent.local_candidates[1].priority,
+            1
+        );
+
+        // Pairs only join candidates of the same component
+        let mut remote = create_test_candidate(6001);
+        remote.component_id = 2;
+        agent.add_remote_candidate(remote).unwrap();
+        assert!(
+            agent
+                .candidate_pairs()
+                .iter()
+                .all(|pair| pair.local.component_id == 2)
+        );
+        assert_eq!(agent.candidate_pairs().len(), 2);
+    }
+
+    #[test]
+    fn test_gather_srflx_survives_failing_stun_server() {
+        use std::net::UdpSocket;
+        use std::thread;
+        use std::time::Duration;
+        use stun::{Message, MessageType};
+
+        // Good server answers one Binding request with a public address
+        let reflexive: SocketAddr = "203.0.113.5:54321".parse().unwrap();
+        let good = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let good_addr = good.local_addr().unwrap();
+        let good_thread = thread::spawn(move || {
+            let mut buf = [0u8; 1500];
+            let (size, from) = good.recv_from(&mut buf).unwrap();
+            let request = Message::decode(&buf[..size]).unwrap();
+            let mut response = Message::new(MessageType::Response, request.transaction_id());
+            response.add_xor_mapped_address(reflexive);
+            good.send_to(&response.encode(), from).unwrap();
+        });
+
+        // Bad server never answers
+        let bad = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let bad_server = bad.local_addr().unwrap().to_string();
+        let good_server = good_addr.to_string();
+
+        let mut agent = IceAgent::new();
+        agent.set_rtcp_mux(true);
+        agent.set_server_timeout(Duration::from_millis(200));
+        let report = agent
+            .gather_server_reflexive_candidates(5000, &[bad_server.clone(), good_server.clone()])
+            .unwrap();
+        good_thread.join().unwrap();
+
+        assert!(report.is_partial());
+        let failed: Vec<&str> = report.failed().map(|o| o.server.as_str()).collect();
+        assert_eq!(failed, vec![bad_server.as_str()]);
+        assert_eq!(report.succeeded().next().unwrap().result, Ok(reflexive));
+
+        assert_eq!(agent.local_candidates.len(), 1);
+        let candidate = &agent.local_candidates[0];
+        assert_eq!(candidate.candidate_type, CandidateType::Srflx);
+        assert_eq!(candidate.address, reflexive.ip());
+    }
+
+    /// Agent with one host candidate bound on loopback, and its socket
+    fn loopback_agent() -> (IceAgent, CandidateSocket) {
+        let socket = CandidateSocket::new(
+            CandidateBuilder::new()
+                .component_id(1)
+                .transport("UDP")
+                .priority(2130706431)
+                .address(IpAddr::V4(Ipv4Addr::LOCALHOST))
+                .port(0)
+                .candidate_type(CandidateType::Host)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        let mut candidate = socket.candidate.clone();
+        candidate.port = socket.socket.local_addr().unwrap().port();
+
+        let mut agent = IceAgent::new();
+        agent.add_local_candidate(candidate).unwrap();
+        (agent, socket)
+    }
+
+    #[test]
+    fn test_checks_against_unresponsive_peer_fail_at_deadline() {
+        let (mut agent, socket) = loopback_agent();
+        // Bound but never read: requests are silently dropped
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut remote = create_test_candidate(silent.local_addr().unwrap().port());
+        remote.address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        agent.add_remote_candidate(remote).unwrap();
+        agent.set_checking_timeout(Duration::from_millis(300));
+
+        let start = Instant::now();
+        let result = agent.run_connectivity_checks(&socket);
+
+        assert_eq!(result, Err(IceError::CheckingTimeout));
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(agent.connection_state(), ConnectionState::Failed);
+        assert!(agent.selected_pair().is_none());
+    }
+
+    #[test]
+    fn test_cancel_token_stops_running_checks() {
+        let (mut agent, socket) = loopback_agent();
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut remote = create_test_candidate(silent.local_addr().unwrap().port());
+        remote.address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        agent.add_remote_candidate(remote).unwrap();
+        agent.set_checking_timeout(Duration::from_secs(60));
+
+        let token = agent.cancel_token();
+        let checks = std::thread::spawn(move || {
+            let result = agent.run_connectivity_checks(&socket);
+            (result, agent.connection_state())
+        });
+        std::thread::sleep(Duration::from_millis(100));
+        let cancelled_at = Instant::now();
+        token.cancel();
+
+        let (result, state) = checks.join().unwrap();
+        assert_eq!(result, Err(IceError::Cancelled));
+        assert_eq!(state, ConnectionState::Closed);
+        assert!(cancelled_at.elapsed() < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_connectivity_checks_select_pair_between_agents() {
+        let (mut a, a_socket) = loopback_agent();
+        let (mut b, b_socket) = loopback_agent();
+        b.set_controlling(false);
+        let (a_port, b_port) = (a.local_candidates[0].port, b.local_candidates[0].port);
+        a.add_remote_candidate(b.local_candidates[0].clone())
+            .unwrap();
+        b.add_remote_candidate(a.local_candidates[0].clone())
+            .unwrap();
+        for agent in [&mut a, &mut b] {
+            agent.set_checking_timeout(Duration::from_secs(5));
+        }
+
+        let controlled = std::thread::spawn(move || {
+            b.run_connectivity_checks(&b_socket)
+                .map(|_| b.selected_pair().unwrap().remote.port)
+        });
+        a.run_connectivity_checks(&a_socket).unwrap();
+        let b_selected = controlled.join().unwrap().unwrap();
+
+        assert_eq!(a.connection_state(), ConnectionState::Connected);
+        assert_eq!(a.selected_pair().unwrap().remote.port, b_port);
+        assert_eq!(b_selected, a_port);
+    }
 }
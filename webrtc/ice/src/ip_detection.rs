@@ -3,7 +3,116 @@
 //! Provides functionality to discover local network IP addresses
 //! for ICE candidate gathering.
 
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr};
+
+/// An address assigned to a network interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkInterface {
+    /// Interface name (e.g. "eth0"); empty when the platform does not report it
+    pub name: String,
+    /// Address assigned to the interface
+    pub address: IpAddr,
+    /// Whether the interface is up
+    pub is_up: bool,
+}
+
+/// Source of the machine's interface addresses.
+///
+/// Implemented by [`SystemInterfaces`]; tests provide their own list.
+pub trait InterfaceEnumerator {
+    /// Returns every address of every interface, one entry per address.
+    fn interfaces(&self) -> Vec<NetworkInterface>;
+}
+
+/// Enumerates interfaces with the platform tools ('ip', 'ifconfig', 'ipconfig').
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemInterfaces;
+
+impl InterfaceEnumerator for SystemInterfaces {
+    fn interfaces(&self) -> Vec<NetworkInterface> {
+        #[cfg(target_family = "unix")]
+        {
+            list_interfaces_unix()
+        }
+
+        #[cfg(target_family = "windows")]
+        {
+            list_interfaces_windows()
+        }
+
+        #[cfg(not(any(target_family = "unix", target_family = "windows")))]
+        {
+            Vec::new()
+        }
+    }
+}
+
+/// Detects every usable local IP address.
+///
+/// Loopback, link-local and unspecified addresses are skipped, as are
+/// interfaces that are down. Private ranges come first (see
+/// [`detect_local_ip`]).
+///
+/// # Returns
+/// The usable addresses, without duplicates
+pub fn detect_local_ips() -> Vec<IpAddr> {
+    detect_local_ips_with(&SystemInterfaces, &[])
+}
+
+/// Detects usable local IP addresses from `enumerator`.
+///
+/// # Arguments
+/// * `enumerator` - Source of interface addresses
+/// * `excluded` - Interface name patterns to skip; `*` matches any run of
+///   characters (e.g. `docker*`, `vmnet*`)
+///
+/// # Returns
+/// The usable addresses, private ranges first, without duplicates
+pub fn detect_local_ips_with(
+    enumerator: &dyn InterfaceEnumerator,
+    excluded: &[String],
+) -> Vec<IpAddr> {
+    let mut addresses: Vec<Ipv4Addr> = Vec::new();
+
+    for interface in enumerator.interfaces() {
+        if !interface.is_up
+            || excluded
+                .iter()
+                .any(|pattern| matches_pattern(&interface.name, pattern))
+        {
+            continue;
+        }
+        if let IpAddr::V4(ip) = interface.address
+            && is_valid_lan_ip(&ip)
+            && !addresses.contains(&ip)
+        {
+            addresses.push(ip);
+        }
+    }
+
+    // Stable sort keeps the interface order within each range
+    addresses.sort_by_key(range_rank);
+    addresses.into_iter().map(IpAddr::V4).collect()
+}
+
+/// Matches an interface name against a pattern where `*` matches any run
+/// of characters.
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    match pattern.split_once('*') {
+        None => name == pattern,
+        Some((prefix, rest)) => {
+            let Some(remaining) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            if rest.is_empty() {
+                return true;
+            }
+            (0..=remaining.len())
+                .filter(|&i| remaining.is_char_boundary(i))
+                .any(|i| matches_pattern(&remaining[i..], rest))
+        }
+    }
+}
 
 /// Detects the local IP address for LAN connections.
 ///
@@ -19,117 +128,133 @@ use std::net::Ipv4Addr;
 /// # Returns
 /// The local IP address as a string (e.g., "192.168.1.100")
 pub fn detect_local_ip() -> String {
-    #[cfg(target_family = "unix")]
-    {
-        if let Some(ip) = detect_local_ip_unix() {
-            return ip;
-        }
-    }
-
-    #[cfg(target_family = "windows")]
-    {
-        if let Some(ip) = detect_local_ip_windows() {
-            return ip;
-        }
-    }
-
-    "0.0.0.0".to_string()
+    detect_local_ips()
+        .first()
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| "0.0.0.0".to_string())
 }
 
-/// Detects local IP on Unix/Linux systems
+/// Lists interface addresses on Unix/Linux systems
 #[cfg(target_family = "unix")]
-fn detect_local_ip_unix() -> Option<String> {
+fn list_interfaces_unix() -> Vec<NetworkInterface> {
     use std::process::Command;
 
-    let mut candidate_ips = Vec::new();
-
-    if let Ok(output) = Command::new("ip").arg("addr").output()
+    // 'ip -o addr' prints one address per line: "2: eth0    inet 192.168.1.5/24 ..."
+    if let Ok(output) = Command::new("ip")
+        .args(["-o", "addr", "show", "up"])
+        .output()
         && output.status.success()
         && let Ok(result) = String::from_utf8(output.stdout)
     {
-        for line in result.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("inet ") && !trimmed.starts_with("inet6") {
-                let parts: Vec<&str> = trimmed.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    let ip_part = parts[1].split('/').next().unwrap_or("");
-                    if let Ok(ip) = ip_part.parse::<Ipv4Addr>()
-                        && is_valid_lan_ip(&ip)
-                    {
-                        candidate_ips.push(ip);
-                    }
+        let interfaces: Vec<NetworkInterface> = result
+            .lines()
+            .filter_map(|line| {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() < 4 || parts[2] != "inet" {
+                    return None;
                 }
-            }
+                let address = parts[3].split('/').next()?.parse().ok()?;
+                Some(NetworkInterface {
+                    name: parts[1].to_string(),
+                    address,
+                    is_up: true,
+                })
+            })
+            .collect();
+        if !interfaces.is_empty() {
+            return interfaces;
         }
     }
 
-    // Try using 'hostname -I' command (Linux)
-    if candidate_ips.is_empty()
-        && let Ok(output) = Command::new("hostname").arg("-I").output()
+    // 'ifconfig' prints a block per interface: "eth0: flags=4163<UP,...>"
+    if let Ok(output) = Command::new("ifconfig").output()
         && output.status.success()
         && let Ok(result) = String::from_utf8(output.stdout)
     {
-        for ip_str in result.split_whitespace() {
-            if let Ok(ip) = ip_str.parse::<Ipv4Addr>()
-                && is_valid_lan_ip(&ip)
-            {
-                candidate_ips.push(ip);
+        let mut interfaces = Vec::new();
+        let mut current = (String::new(), false);
+        for line in result.lines() {
+            if !line.starts_with(char::is_whitespace) && !line.is_empty() {
+                let name = line.split([':', ' ']).next().unwrap_or("").to_string();
+                let is_up = line.contains("<UP") || line.contains(",UP");
+                current = (name, is_up);
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 2 && parts[0] == "inet" {
+                let ip_str = parts[1].strip_prefix("addr:").unwrap_or(parts[1]);
+                if let Ok(address) = ip_str.parse() {
+                    interfaces.push(NetworkInterface {
+                        name: current.0.clone(),
+                        address,
+                        is_up: current.1,
+                    });
+                }
             }
         }
+        if !interfaces.is_empty() {
+            return interfaces;
+        }
     }
 
-    // Try using 'ifconfig' command
-    if candidate_ips.is_empty()
-        && let Ok(output) = Command::new("ifconfig").output()
+    // 'hostname -I' (Linux) only lists addresses, without names
+    if let Ok(output) = Command::new("hostname").arg("-I").output()
         && output.status.success()
         && let Ok(result) = String::from_utf8(output.stdout)
     {
-        for line in result.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("inet ") && !trimmed.starts_with("inet6") {
-                let parts: Vec<&str> = trimmed.split_whitespace().collect();
-                if parts.len() >= 2
-                    && let Ok(ip) = parts[1].parse::<Ipv4Addr>()
-                    && is_valid_lan_ip(&ip)
-                {
-                    candidate_ips.push(ip);
-                }
-            }
-        }
+        return result
+            .split_whitespace()
+            .filter_map(|ip_str| ip_str.parse().ok())
+            .map(|address| NetworkInterface {
+                name: String::new(),
+                address,
+                is_up: true,
+            })
+            .collect();
     }
 
-    select_best_ip(&candidate_ips)
+    Vec::new()
 }
 
-/// Detects local IP on Windows systems
+/// Lists interface addresses on Windows systems
 #[cfg(target_family = "windows")]
-fn detect_local_ip_windows() -> Option<String> {
+fn list_interfaces_windows() -> Vec<NetworkInterface> {
     use std::process::Command;
 
-    let mut candidate_ips = Vec::new();
-
-    // Try using 'ipconfig' command
-    if let Ok(output) = Command::new("ipconfig").output() {
-        if output.status.success() {
-            if let Ok(result) = String::from_utf8(output.stdout) {
-                for line in result.lines() {
-                    if line.contains("IPv4 Address") || line.contains("IPv4 address") {
-                        if let Some(colon_pos) = line.rfind(':') {
-                            let ip_str = line[colon_pos + 1..].trim();
-                            if let Ok(ip) = ip_str.parse::<Ipv4Addr>() {
-                                if is_valid_lan_ip(&ip) {
-                                    candidate_ips.push(ip);
-                                }
-                            }
-                        }
-                    }
-                }
+    let mut interfaces = Vec::new();
+
+    // 'ipconfig' prints "Ethernet adapter Ethernet:" followed by its addresses
+    if let Ok(output) = Command::new("ipconfig").output()
+        && output.status.success()
+        && let Ok(result) = String::from_utf8(output.stdout)
+    {
+        let mut name = String::new();
+        for line in result.lines() {
+            if !line.starts_with(char::is_whitespace) && line.trim_end().ends_with(':') {
+                name = line
+                    .trim_end()
+                    .trim_end_matches(':')
+                    .rsplit(" adapter ")
+                    .next()
+                    .unwrap_or("")
+                    .to_string();
+            } else if (line.contains("IPv4 Address") || line.contains("IPv4 address"))
+                && let Some(colon_pos) = line.rfind(':')
+                && let Ok(address) = line[colon_pos + 1..]
+                    .trim()
+                    .trim_end_matches("(Preferred)")
+                    .parse()
+            {
+                interfaces.push(NetworkInterface {
+                    name: name.clone(),
+                    address,
+                    is_up: true,
+                });
             }
         }
     }
 
-    // Return the best candidate
-    select_best_ip(&candidate_ips)
+    interfaces
 }
 
 /// Checks if an IP is valid for LAN connections
@@ -151,35 +276,16 @@ fn is_valid_lan_ip(ip: &Ipv4Addr) -> bool {
     true
 }
 
-/// Selects the best IP from a list of candidates
-/// Prioritizes private network ranges (192.168.x.x, 10.x.x.x, 172.16-31.x.x)
-fn select_best_ip(candidates: &[Ipv4Addr]) -> Option<String> {
-    if candidates.is_empty() {
-        return None;
-    }
-
-    for ip in candidates {
-        let octets = ip.octets();
-        if octets[0] == 192 && octets[1] == 168 {
-            return Some(ip.to_string());
-        }
-    }
-
-    for ip in candidates {
-        let octets = ip.octets();
-        if octets[0] == 10 {
-            return Some(ip.to_string());
-        }
-    }
-
-    for ip in candidates {
-        let octets = ip.octets();
-        if octets[0] == 172 && (octets[1] >= 16 && octets[1] <= 31) {
-            return Some(ip.to_string());
-        }
+/// Ranks an IP by network range, lower is preferred:
+/// 192.168.x.x, then 10.x.x.x, then 172.16-31.x.x, then everything else
+fn range_rank(ip: &Ipv4Addr) -> u8 {
+    let octets = ip.octets();
+    match octets {
+        [192, 168, ..] => 0,
+        [10, ..] => 1,
+        [172, second, ..] if (16..=31).contains(&second) => 2,
+        _ => 3,
     }
-
-    Some(candidates[0].to_string())
 }
 
 #[cfg(test)]
@@ -196,4 +302,69 @@ mod tests {
             assert_ne!(ip, "127.0.0.1");
         }
     }
+
+    struct MockInterfaces(Vec<NetworkInterface>);
+
+    impl InterfaceEnumerator for MockInterfaces {
+        fn interfaces(&self) -> Vec<NetworkInterface> {
+            self.0.clone()
+        }
+    }
+
+    fn interface(name: &str, address: &str, is_up: bool) -> NetworkInterface {
+        NetworkInterface {
+            name: name.to_string(),
+            address: address.parse().unwrap(),
+            is_up,
+        }
+    }
+
+    #[test]
+    fn test_detect_local_ips_filters_and_orders() {
+        let interfaces = MockInterfaces(vec![
+            interface("lo", "127.0.0.1", true),
+            interface("tun0", "100.64.0.7", true),
+            interface("eth0", "192.168.1.20", true),
+            interface("eth1", "10.0.0.5", false),
+            interface("wlan0", "169.254.3.3", true),
+            interface("docker0", "172.17.0.1", true),
+            interface("eth0", "192.168.1.20", true),
+        ]);
+
+        let ips = detect_local_ips_with(&interfaces, &[]);
+        let expected: Vec<IpAddr> = ["192.168.1.20", "172.17.0.1", "100.64.0.7"]
+            .iter()
+            .map(|ip| ip.parse().unwrap())
+            .collect();
+        assert_eq!(ips, expected);
+    }
+
+    #[test]
+    fn test_detect_local_ips_excludes_by_pattern() {
+        let interfaces = MockInterfaces(vec![
+            interface("eth0", "192.168.1.20", true),
+            interface("docker0", "172.17.0.1", true),
+            interface("vmnet8", "192.168.56.1", true),
+            interface("br-docker", "172.18.0.1", true),
+        ]);
+
+        let excluded = vec![
+            "docker*".to_string(),
+            "vmnet*".to_string(),
+            "*docker".to_string(),
+        ];
+        let ips = detect_local_ips_with(&interfaces, &excluded);
+        assert_eq!(ips, vec!["192.168.1.20".parse::<IpAddr>().unwrap()]);
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("docker0", "docker*"));
+        assert!(matches_pattern("eth0", "eth0"));
+        assert!(matches_pattern("veth1a2b", "veth*b"));
+        assert!(matches_pattern("anything", "*"));
+        assert!(!matches_pattern("eth0", "docker*"));
+        assert!(!matches_pattern("eth0", "eth"));
+        assert!(!matches_pattern("vethab", "veth*c"));
+    }
 }
@@ -8,16 +8,35 @@ pub mod candidate_pair;
 pub mod candidate_type;
 pub mod connection_state;
 pub mod connectivity;
+pub mod consent;
+pub mod demux;
 pub mod errors;
+pub mod gathering;
 pub mod ice_agent;
 pub mod ip_detection;
+pub mod mdns;
+pub mod nomination;
+pub mod pair_state;
+pub mod tcp_type;
 
 pub use candidate::Candidate;
 pub use candidate_builder::CandidateBuilder;
 pub use candidate_pair::CandidatePair;
 pub use candidate_type::CandidateType;
 pub use connection_state::ConnectionState;
-pub use connectivity::{CandidateSocket, perform_connectivity_check};
+pub use connectivity::{
+    CancelToken, CandidateListener, CandidateSocket, CheckRequest, build_check_response,
+    perform_connectivity_check, perform_tcp_connectivity_check, resolve_candidate,
+};
+pub use consent::{ConsentConfig, ConsentFreshness};
+pub use demux::{DatagramHandler, PacketClass};
 pub use errors::IceError;
+pub use gathering::{GatheringReport, ServerOutcome};
 pub use ice_agent::IceAgent;
-pub use ip_detection::detect_local_ip;
+pub use ip_detection::{
+    InterfaceEnumerator, NetworkInterface, SystemInterfaces, detect_local_ip, detect_local_ips,
+};
+pub use mdns::MdnsRegistry;
+pub use nomination::NominationMode;
+pub use pair_state::PairState;
+pub use tcp_type::TcpType;
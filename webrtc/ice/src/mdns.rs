@@ -0,0 +1,510 @@
+//! mDNS host candidate obfuscation.
+//!
+//! Host candidates can be advertised as random `<uuid>.local` names instead of
+//! their private IP addresses. The agent keeps the name→IP mapping and an
+//! [`MdnsResponder`] answers queries for its own names, while remote `.local`
+//! candidates are resolved with a one-shot mDNS query (RFC 6762 Section 5.1).
+
+use crate::errors::IceError;
+use rand::Rng;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// IPv4 mDNS multicast group and port.
+pub const MDNS_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(224, 0, 0, 251)), 5353);
+
+/// How often the responder thread checks whether it should stop.
+const RESPONDER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Time to live advertised for our host records (seconds).
+const RECORD_TTL: u32 = 120;
+
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+/// Cache-flush bit set on unique records in responses
+const CLASS_CACHE_FLUSH: u16 = 0x8000;
+
+/// Maps generated `.local` hostnames to the local IPs they hide.
+#[derive(Debug, Clone, Default)]
+pub struct MdnsRegistry {
+    names: HashMap<String, IpAddr>,
+}
+
+impl MdnsRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the hostname for an IP, generating one if needed.
+    ///
+    /// # Arguments
+    /// * `ip` - Local IP address to hide
+    ///
+    /// # Returns
+    /// A `<uuid>.local` hostname that stays the same for the same IP
+    pub fn register(&mut self, ip: IpAddr) -> String {
+        if let Some(name) = self.hostname_for(ip) {
+            return name.to_string();
+        }
+
+        let name = format!("{}.local", generate_uuid());
+        self.names.insert(name.clone(), ip);
+        name
+    }
+
+    /// Gets the hostname registered for an IP, if any.
+    pub fn hostname_for(&self, ip: IpAddr) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(_, registered)| **registered == ip)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Resolves one of our own hostnames.
+    ///
+    /// # Arguments
+    /// * `name` - Hostname, matched case-insensitively
+    pub fn resolve(&self, name: &str) -> Option<IpAddr> {
+        self.names
+            .iter()
+            .find(|(registered, _)| registered.eq_ignore_ascii_case(name.trim_end_matches('.')))
+            .map(|(_, ip)| *ip)
+    }
+
+    /// Builds the response to an mDNS query for one of our hostnames.
+    ///
+    /// # Arguments
+    /// * `query` - Raw mDNS query packet
+    ///
+    /// # Returns
+    /// An encoded response packet, or `None` if no question matches
+    pub fn answer(&self, query: &[u8]) -> Option<Vec<u8>> {
+        let header = Header::parse(query)?;
+        if header.flags & 0x8000 != 0 {
+            return None;
+        }
+
+        let mut offset = 12;
+        for _ in 0..header.questions {
+            let (name, next) = read_name(query, offset)?;
+            offset = next + 4;
+            if let Some(ip) = self.resolve(&name) {
+                return Some(encode_response(&name, ip));
+            }
+        }
+        None
+    }
+}
+
+/// Answers mDNS queries for the names of an [`MdnsRegistry`].
+///
+/// A background thread listens on the mDNS group and replies to queries
+/// for our hostnames: on the group for regular queriers, and directly to
+/// one-shot queriers that ask from another port (RFC 6762 Section 6.7).
+/// The thread stops when the responder is dropped.
+#[derive(Debug)]
+pub struct MdnsResponder {
+    names: Arc<Mutex<MdnsRegistry>>,
+    local_addr: SocketAddr,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MdnsResponder {
+    /// Starts answering queries on the IPv4 mDNS group.
+    ///
+    /// # Arguments
+    /// * `names` - Hostnames to answer for
+    pub fn start(names: MdnsRegistry) -> Result<Self, IceError> {
+        Self::bind(MDNS_ADDR, names)
+    }
+
+    /// Starts answering queries sent to `group`.
+    ///
+    /// A multicast group is joined on port `group.port()`, shared with other
+    /// responders on the host; any other address is bound directly.
+    ///
+    /// # Arguments
+    /// * `group` - Multicast group (or unicast address) to listen on
+    /// * `names` - Hostnames to answer for
+    pub fn bind(group: SocketAddr, names: MdnsRegistry) -> Result<Self, IceError> {
+        let socket = bind_group(group).map_err(|e| IceError::SocketBindError(e.to_string()))?;
+        let local_addr = socket
+            .local_addr()
+            .map_err(|e| IceError::SocketError(e.to_string()))?;
+        socket
+            .set_read_timeout(Some(RESPONDER_POLL_INTERVAL))
+            .map_err(|e| IceError::SocketError(e.to_string()))?;
+
+        let names = Arc::new(Mutex::new(names));
+        let running = Arc::new(AtomicBool::new(true));
+        let thread = {
+            let names = Arc::clone(&names);
+            let running = Arc::clone(&running);
+            thread::spawn(move || respond(&socket, group, &names, &running))
+        };
+
+        Ok(Self {
+            names,
+            local_addr,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// Replaces the hostnames answered for (e.g. after a new host candidate).
+    pub fn update(&self, names: &MdnsRegistry) {
+        if let Ok(mut current) = self.names.lock() {
+            *current = names.clone();
+        }
+    }
+
+    /// Gets the address the responder socket is bound to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for MdnsResponder {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Binds the responder socket, joining `group` if it is multicast.
+fn bind_group(group: SocketAddr) -> std::io::Result<UdpSocket> {
+    let IpAddr::V4(multicast) = group.ip() else {
+        return UdpSocket::bind(group);
+    };
+    if !multicast.is_multicast() {
+        return UdpSocket::bind(group);
+    }
+
+    // Other mDNS responders on the host (e.g. Avahi) share the port
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), group.port()).into())?;
+    socket.join_multicast_v4(&multicast, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_multicast_loop_v4(true)?;
+    Ok(socket.into())
+}
+
+/// Responder loop: answers queries until `running` is cleared.
+fn respond(
+    socket: &UdpSocket,
+    group: SocketAddr,
+    names: &Mutex<MdnsRegistry>,
+    running: &AtomicBool,
+) {
+    let mut buf = [0u8; 1500];
+    while running.load(Ordering::SeqCst) {
+        let Ok((size, from)) = socket.recv_from(&mut buf) else {
+            continue;
+        };
+        let response = names
+            .lock()
+            .ok()
+            .and_then(|names| names.answer(&buf[..size]));
+        if let Some(response) = response {
+            // One-shot queriers listen on their own port, not on the group
+            let to = if from.port() == group.port() {
+                group
+            } else {
+                from
+            };
+            let _ = socket.send_to(&response, to);
+        }
+    }
+}
+
+/// Returns true if the address is an mDNS hostname.
+pub fn is_mdns_hostname(address: &str) -> bool {
+    address
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+        .ends_with(".local")
+}
+
+/// Encodes a one-shot mDNS query for the A and AAAA records of a name.
+pub fn encode_query(name: &str) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(12 + 2 * (name.len() + 6));
+    write_header(&mut packet, 0, 2, 0);
+    for record_type in [TYPE_A, TYPE_AAAA] {
+        write_name(&mut packet, name);
+        packet.extend_from_slice(&record_type.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    }
+    packet
+}
+
+/// Extracts the address of `name` from an mDNS response.
+///
+/// # Returns
+/// The first A or AAAA record for the name, or `None` if absent
+pub fn parse_response(packet: &[u8], name: &str) -> Option<IpAddr> {
+    let header = Header::parse(packet)?;
+    if header.flags & 0x8000 == 0 {
+        return None;
+    }
+
+    let mut offset = 12;
+    for _ in 0..header.questions {
+        offset = read_name(packet, offset)?.1 + 4;
+    }
+
+    let wanted = name.trim_end_matches('.');
+    for _ in 0..header.answers {
+        let (record_name, next) = read_name(packet, offset)?;
+        let fixed = packet.get(next..next + 10)?;
+        let record_type = u16::from_be_bytes([fixed[0], fixed[1]]);
+        let length = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+        let data = packet.get(next + 10..next + 10 + length)?;
+        offset = next + 10 + length;
+
+        if !record_name.eq_ignore_ascii_case(wanted) {
+            continue;
+        }
+        match (record_type, length) {
+            (TYPE_A, 4) => {
+                return Some(IpAddr::V4(Ipv4Addr::new(
+                    data[0], data[1], data[2], data[3],
+                )));
+            }
+            (TYPE_AAAA, 16) => {
+                let octets: [u8; 16] = data.try_into().ok()?;
+                return Some(IpAddr::V6(Ipv6Addr::from(octets)));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Resolves a `.local` hostname with a one-shot multicast query.
+///
+/// # Arguments
+/// * `name` - Hostname to resolve
+/// * `timeout` - How long to wait for an answer
+///
+/// # Returns
+/// * `Ok(IpAddr)` - Address announced by the owner of the name
+/// * `Err(IceError)` - If no answer arrives before the timeout
+pub fn query(name: &str, timeout: Duration) -> Result<IpAddr, IceError> {
+    query_at(name, MDNS_ADDR, timeout)
+}
+
+/// Resolves a `.local` hostname with a one-shot query sent to `group`.
+///
+/// # Arguments
+/// * `name` - Hostname to resolve
+/// * `group` - mDNS group (or responder address) to query
+/// * `timeout` - How long to wait for an answer
+pub fn query_at(name: &str, group: SocketAddr, timeout: Duration) -> Result<IpAddr, IceError> {
+    let socket =
+        UdpSocket::bind("0.0.0.0:0").map_err(|e| IceError::SocketBindError(e.to_string()))?;
+    socket
+        .send_to(&encode_query(name), group)
+        .map_err(|e| IceError::SocketError(e.to_string()))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0u8; 1500];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(IceError::MdnsResolutionFailed(name.to_string()));
+        }
+        socket
+            .set_read_timeout(Some(remaining))
+            .map_err(|e| IceError::SocketError(e.to_string()))?;
+
+        match socket.recv_from(&mut buf) {
+            Ok((size, _)) => {
+                if let Some(ip) = parse_response(&buf[..size], name) {
+                    return Ok(ip);
+                }
+            }
+            Err(_) => return Err(IceError::MdnsResolutionFailed(name.to_string())),
+        }
+    }
+}
+
+/// Fixed DNS header fields used here.
+struct Header {
+    flags: u16,
+    questions: u16,
+    answers: u16,
+}
+
+impl Header {
+    fn parse(packet: &[u8]) -> Option<Self> {
+        let header = packet.get(..12)?;
+        Some(Self {
+            flags: u16::from_be_bytes([header[2], header[3]]),
+            questions: u16::from_be_bytes([header[4], header[5]]),
+            answers: u16::from_be_bytes([header[6], header[7]]),
+        })
+    }
+}
+
+fn encode_response(name: &str, ip: IpAddr) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(12 + name.len() + 2 + 10 + 16);
+    // Authoritative answer, no questions echoed (RFC 6762 Section 6)
+    write_header(&mut packet, 0x8400, 0, 1);
+    write_name(&mut packet, name);
+
+    let (record_type, data) = match ip {
+        IpAddr::V4(v4) => (TYPE_A, v4.octets().to_vec()),
+        IpAddr::V6(v6) => (TYPE_AAAA, v6.octets().to_vec()),
+    };
+    packet.extend_from_slice(&record_type.to_be_bytes());
+    packet.extend_from_slice(&(CLASS_IN | CLASS_CACHE_FLUSH).to_be_bytes());
+    packet.extend_from_slice(&RECORD_TTL.to_be_bytes());
+    packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    packet.extend_from_slice(&data);
+    packet
+}
+
+fn write_header(packet: &mut Vec<u8>, flags: u16, questions: u16, answers: u16) {
+    packet.extend_from_slice(&0u16.to_be_bytes());
+    packet.extend_from_slice(&flags.to_be_bytes());
+    packet.extend_from_slice(&questions.to_be_bytes());
+    packet.extend_from_slice(&answers.to_be_bytes());
+    packet.extend_from_slice(&[0, 0, 0, 0]);
+}
+
+fn write_name(packet: &mut Vec<u8>, name: &str) {
+    for label in name.trim_end_matches('.').split('.') {
+        packet.push(label.len().min(63) as u8);
+        packet.extend_from_slice(&label.as_bytes()[..label.len().min(63)]);
+    }
+    packet.push(0);
+}
+
+/// Reads a possibly compressed name, returning it and the offset after it.
+fn read_name(packet: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // Bound pointer chasing so malformed packets cannot loop forever
+    for _ in 0..32 {
+        let length = *packet.get(offset)? as usize;
+        if length == 0 {
+            return Some((labels.join("."), end.unwrap_or(offset + 1)));
+        }
+        if length & 0xC0 == 0xC0 {
+            let pointer = ((length & 0x3F) << 8) | *packet.get(offset + 1)? as usize;
+            end.get_or_insert(offset + 2);
+            offset = pointer;
+            continue;
+        }
+        let label = packet.get(offset + 1..offset + 1 + length)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        offset += 1 + length;
+    }
+    None
+}
+
+/// Generates a random version 4 UUID string.
+fn generate_uuid() -> String {
+    let mut bytes: [u8; 16] = rand::thread_rng().r#gen();
+    bytes[6] = (bytes[6] & 0x0F) | 0x40;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_generates_stable_uuid_name() {
+        let mut registry = MdnsRegistry::new();
+        let ip: IpAddr = "192.168.1.5".parse().unwrap();
+
+        let name = registry.register(ip);
+        let uuid = name.strip_suffix(".local").unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(uuid.split('-').count(), 5);
+        assert_eq!(&uuid[14..15], "4");
+
+        assert_eq!(registry.register(ip), name);
+        assert_ne!(registry.register("10.0.0.1".parse().unwrap()), name);
+        assert_eq!(registry.resolve(&name.to_uppercase()), Some(ip));
+    }
+
+    #[test]
+    fn test_answer_resolves_query() {
+        let mut registry = MdnsRegistry::new();
+        let ip: IpAddr = "192.168.1.5".parse().unwrap();
+        let name = registry.register(ip);
+
+        let response = registry.answer(&encode_query(&name)).unwrap();
+        assert_eq!(parse_response(&response, &name), Some(ip));
+
+        assert!(registry.answer(&encode_query("unknown.local")).is_none());
+        assert!(registry.answer(&response).is_none());
+    }
+
+    #[test]
+    fn test_parse_response_follows_compression() {
+        let name = "abc.local";
+        let mut packet = Vec::new();
+        write_header(&mut packet, 0x8400, 1, 1);
+        write_name(&mut packet, name);
+        packet.extend_from_slice(&[0, 1, 0, 1]);
+        // Answer name is a pointer back to the question
+        packet.extend_from_slice(&[0xC0, 12]);
+        packet.extend_from_slice(&[0, 28, 0x80, 1, 0, 0, 0, 120, 0, 16]);
+        packet.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+
+        assert_eq!(
+            parse_response(&packet, name),
+            Some(IpAddr::V6(Ipv6Addr::LOCALHOST))
+        );
+        assert_eq!(parse_response(&packet, "other.local"), None);
+    }
+
+    #[test]
+    fn test_responder_answers_one_shot_query() {
+        let mut names = MdnsRegistry::new();
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+        let name = names.register(ip);
+        let responder =
+            MdnsResponder::bind("127.0.0.1:0".parse().unwrap(), MdnsRegistry::new()).unwrap();
+        let group = responder.local_addr();
+
+        // Names only become resolvable once pushed to the responder
+        assert!(query_at(&name, group, Duration::from_millis(300)).is_err());
+        responder.update(&names);
+        assert_eq!(query_at(&name, group, Duration::from_secs(2)).unwrap(), ip);
+    }
+
+    #[test]
+    fn test_is_mdns_hostname() {
+        assert!(is_mdns_hostname(
+            "1f2e3d4c-0000-4000-8000-000000000000.local"
+        ));
+        assert!(is_mdns_hostname("host.LOCAL."));
+        assert!(!is_mdns_hostname("192.168.1.5"));
+    }
+}
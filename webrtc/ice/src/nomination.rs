@@ -0,0 +1,23 @@
+//! Candidate pair nomination modes.
+//!
+//! The controlling agent decides which candidate pair is used by nominating
+//! it with the USE-CANDIDATE flag (RFC 5245 Section 8.1.1).
+
+/// How the controlling agent nominates candidate pairs.
+///
+/// # Modes
+/// - **Regular**: Checks run without USE-CANDIDATE. Once a pair succeeds the
+///   controlling agent repeats the check with USE-CANDIDATE set, which costs
+///   one more round trip before the pair is usable.
+/// - **Aggressive**: Every check carries USE-CANDIDATE, so the first pair to
+///   succeed is selected right away. If a higher priority pair succeeds
+///   later, both agents switch to it, so the selected pair can change briefly
+///   while checks are still running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NominationMode {
+    /// Nominate with a follow-up check after a pair succeeds
+    #[default]
+    Regular,
+    /// Nominate on every check, selecting the first pair that succeeds
+    Aggressive,
+}
@@ -0,0 +1,60 @@
+//! Candidate pair check states.
+//!
+//! Each pair of the check list moves through these states as connectivity
+//! checks are scheduled, sent and answered (RFC 5245 Section 5.7.4).
+
+/// State of a candidate pair in the check list according to RFC 5245.
+///
+/// Pairs start frozen; a pair becomes waiting once it may be checked,
+/// in progress while its check is outstanding, and then succeeded or failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PairState {
+    /// Not checked until a pair with the same foundation succeeds
+    #[default]
+    Frozen,
+    /// Ready to be checked when its turn comes
+    Waiting,
+    /// A check was sent and has not been answered yet
+    InProgress,
+    /// A check got a success response
+    Succeeded,
+    /// A check got an error response or timed out
+    Failed,
+}
+
+impl PairState {
+    /// Returns true once the pair's check has completed, successfully or not
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed)
+    }
+}
+
+impl std::fmt::Display for PairState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Frozen => write!(f, "frozen"),
+            Self::Waiting => write!(f, "waiting"),
+            Self::InProgress => write!(f, "in-progress"),
+            Self::Succeeded => write!(f, "succeeded"),
+            Self::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_frozen() {
+        assert_eq!(PairState::default(), PairState::Frozen);
+    }
+
+    #[test]
+    fn test_display_and_final_states() {
+        assert_eq!(PairState::InProgress.to_string(), "in-progress");
+        assert_eq!(PairState::Succeeded.to_string(), "succeeded");
+        assert!(PairState::Failed.is_final());
+        assert!(!PairState::Waiting.is_final());
+    }
+}
@@ -0,0 +1,115 @@
+//! TCP candidate types.
+//!
+//! Defines the `tcptype` attribute of TCP candidates as specified in RFC 6544.
+
+/// Connection role of a TCP candidate according to RFC 6544.
+///
+/// # TCP Types
+/// - **Active**: Opens outgoing connections, never accepts them
+/// - **Passive**: Accepts incoming connections, never opens them
+/// - **So**: Simultaneous-open, both sides connect to each other at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TcpType {
+    /// Active candidate - connects to the remote peer
+    Active,
+    /// Passive candidate - listens for the remote peer
+    Passive,
+    /// Simultaneous-open candidate
+    So,
+}
+
+impl TcpType {
+    /// Parses a TCP type from a string.
+    ///
+    /// # Arguments
+    /// * `s` - The TCP type string ("active", "passive", "so")
+    ///
+    /// # Returns
+    /// * `Ok(TcpType)` - If parsing is successful
+    /// * `Err(IceError)` - If the type is invalid
+    pub fn parse(s: &str) -> Result<Self, crate::errors::IceError> {
+        match s {
+            "active" => Ok(TcpType::Active),
+            "passive" => Ok(TcpType::Passive),
+            "so" => Ok(TcpType::So),
+            _ => Err(crate::errors::IceError::InvalidTcpType(s.to_string())),
+        }
+    }
+
+    /// Returns the string representation of the TCP type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TcpType::Active => "active",
+            TcpType::Passive => "passive",
+            TcpType::So => "so",
+        }
+    }
+
+    /// Returns whether a local candidate of this type can be paired with a
+    /// remote candidate of type `remote` (RFC 6544 Section 6.2).
+    ///
+    /// Active pairs with passive, passive with active and so with so.
+    pub fn pairs_with(&self, remote: TcpType) -> bool {
+        matches!(
+            (self, remote),
+            (TcpType::Active, TcpType::Passive)
+                | (TcpType::Passive, TcpType::Active)
+                | (TcpType::So, TcpType::So)
+        )
+    }
+
+    /// Returns the direction preference used in the local preference of
+    /// host TCP candidates (RFC 6544 Section 4.2).
+    ///
+    /// - Active: 6
+    /// - Passive: 4
+    /// - So: 2
+    pub fn direction_preference(&self) -> u32 {
+        match self {
+            TcpType::Active => 6,
+            TcpType::Passive => 4,
+            TcpType::So => 2,
+        }
+    }
+}
+
+/// Implements the `Display` trait for [`TcpType`], allowing it to be
+/// formatted as a string.
+impl std::fmt::Display for TcpType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_roundtrip() {
+        for tcp_type in [TcpType::Active, TcpType::Passive, TcpType::So] {
+            assert_eq!(TcpType::parse(tcp_type.as_str()).unwrap(), tcp_type);
+            assert_eq!(format!("{}", tcp_type), tcp_type.as_str());
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_type() {
+        match TcpType::parse("listen") {
+            Err(crate::errors::IceError::InvalidTcpType(s)) => assert_eq!(s, "listen"),
+            _ => panic!("Expected InvalidTcpType error"),
+        }
+        assert!(TcpType::parse("PASSIVE").is_err());
+    }
+
+    #[test]
+    fn test_pairs_with() {
+        assert!(TcpType::Active.pairs_with(TcpType::Passive));
+        assert!(TcpType::Passive.pairs_with(TcpType::Active));
+        assert!(TcpType::So.pairs_with(TcpType::So));
+
+        assert!(!TcpType::Active.pairs_with(TcpType::Active));
+        assert!(!TcpType::Passive.pairs_with(TcpType::Passive));
+        assert!(!TcpType::So.pairs_with(TcpType::Passive));
+    }
+}
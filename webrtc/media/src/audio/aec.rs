@@ -0,0 +1,247 @@
+//! Acoustic echo cancellation.
+//!
+//! Removes the far-end signal (what the speakers play) from the near-end
+//! capture (what the microphone records) before encoding, so the remote
+//! participant does not hear themselves on speakerphone setups.
+
+use std::collections::VecDeque;
+
+/// Trait for pluggable echo cancellers
+///
+/// Both signals are interleaved 16-bit PCM with the same sample rate and
+/// channel count. Far-end samples are consumed in the order they were
+/// played, one per near-end sample.
+pub trait EchoCanceller: Send {
+    /// Feeds samples sent to the playback device (echo reference)
+    ///
+    /// # Arguments
+    /// * `samples` - Far-end samples, interleaved
+    fn process_far_end(&mut self, samples: &[i16]);
+
+    /// Removes the echo of the far-end signal from captured samples in place
+    ///
+    /// # Arguments
+    /// * `samples` - Near-end samples, interleaved
+    fn process_near_end(&mut self, samples: &mut [i16]);
+
+    /// Resets the adaptive state
+    ///
+    /// Called when the echo path changes (device switch, stream restart).
+    fn reset(&mut self) {
+        // Default: no-op, implementations can override
+    }
+}
+
+/// Adaptive filter state for one channel
+struct ChannelFilter {
+    weights: Vec<f32>,
+    /// Most recent far-end samples, newest first
+    history: VecDeque<f32>,
+    /// Running energy of `history` (f64: it is updated incrementally for hours)
+    energy: f64,
+}
+
+impl ChannelFilter {
+    fn new(taps: usize) -> Self {
+        Self {
+            weights: vec![0.0; taps],
+            history: VecDeque::from(vec![0.0; taps]),
+            energy: 0.0,
+        }
+    }
+
+    /// Filters one near-end sample against the next far-end sample
+    fn process(&mut self, far: f32, near: f32, step_size: f32) -> f32 {
+        if let Some(oldest) = self.history.pop_back() {
+            self.energy -= (oldest * oldest) as f64;
+        }
+        self.history.push_front(far);
+        self.energy = (self.energy + (far * far) as f64).max(0.0);
+
+        let estimate: f32 = self
+            .weights
+            .iter()
+            .zip(&self.history)
+            .map(|(w, x)| w * x)
+            .sum();
+        let error = near - estimate;
+
+        // Normalized step: adaptation speed does not depend on playback volume
+        let gain = step_size * error / (self.energy as f32 + NlmsEchoCanceller::REGULARIZATION);
+        for (w, x) in self.weights.iter_mut().zip(&self.history) {
+            *w += gain * x;
+        }
+
+        error
+    }
+}
+
+/// Normalized least-mean-squares (NLMS) echo canceller
+///
+/// Models the echo path as an FIR filter per channel and adapts it
+/// continuously, subtracting the estimated echo from the capture.
+pub struct NlmsEchoCanceller {
+    channels: usize,
+    taps: usize,
+    step_size: f32,
+    filters: Vec<ChannelFilter>,
+    /// Far-end samples not yet matched with a near-end sample
+    far_end: VecDeque<i16>,
+    /// Upper bound on buffered far-end samples
+    max_far_end: usize,
+}
+
+impl NlmsEchoCanceller {
+    /// Filter length: 128 ms at 16 kHz, 43 ms at 48 kHz
+    pub const DEFAULT_TAPS: usize = 2048;
+    /// Adaptation step (0.0 - 2.0 for NLMS stability)
+    pub const DEFAULT_STEP_SIZE: f32 = 0.5;
+    /// Keeps the normalized step finite when the far end is silent
+    const REGULARIZATION: f32 = 1e-3;
+    /// Seconds of far-end audio buffered while capture is not consuming it
+    const MAX_FAR_END_SECONDS: usize = 1;
+
+    /// Creates an NLMS echo canceller with default filter length and step
+    ///
+    /// # Arguments
+    /// * `sample_rate` - Sample rate of both signals in Hz
+    /// * `channels` - Number of interleaved channels
+    pub fn new(sample_rate: u32, channels: u32) -> Self {
+        Self::with_params(
+            sample_rate,
+            channels,
+            Self::DEFAULT_TAPS,
+            Self::DEFAULT_STEP_SIZE,
+        )
+    }
+
+    /// Creates an NLMS echo canceller with custom parameters
+    ///
+    /// # Arguments
+    /// * `sample_rate` - Sample rate of both signals in Hz
+    /// * `channels` - Number of interleaved channels (at least 1)
+    /// * `taps` - Filter length in samples per channel; must cover the echo delay
+    /// * `step_size` - Adaptation step (clamped to 0.0-2.0)
+    pub fn with_params(sample_rate: u32, channels: u32, taps: usize, step_size: f32) -> Self {
+        let channels = channels.max(1) as usize;
+        let taps = taps.max(1);
+
+        Self {
+            channels,
+            taps,
+            step_size: step_size.clamp(0.0, 2.0),
+            filters: (0..channels).map(|_| ChannelFilter::new(taps)).collect(),
+            far_end: VecDeque::new(),
+            max_far_end: sample_rate as usize * channels * Self::MAX_FAR_END_SECONDS,
+        }
+    }
+}
+
+impl EchoCanceller for NlmsEchoCanceller {
+    fn process_far_end(&mut self, samples: &[i16]) {
+        self.far_end.extend(samples);
+
+        // Drop whole frames so channels stay aligned
+        let excess = self.far_end.len().saturating_sub(self.max_far_end);
+        let excess = excess.div_ceil(self.channels) * self.channels;
+        self.far_end.drain(..excess.min(self.far_end.len()));
+    }
+
+    fn process_near_end(&mut self, samples: &mut [i16]) {
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let far = self.far_end.pop_front().unwrap_or(0) as f32;
+            let filter = &mut self.filters[i % self.channels];
+            let residual = filter.process(far, *sample as f32, self.step_size);
+            *sample = residual.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.filters = (0..self.channels)
+            .map(|_| ChannelFilter::new(self.taps))
+            .collect();
+        self.far_end.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 16000;
+    const FRAME: usize = 320; // 20 ms at 16 kHz
+
+    /// Deterministic white-ish noise (speech-like broadband reference)
+    fn far_end_signal(len: usize) -> Vec<i16> {
+        let mut state: u32 = 0x1234_5678;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                ((state >> 16) as i16) / 4
+            })
+            .collect()
+    }
+
+    /// Room echo: attenuated copies of the far end at 5 and 12 samples delay
+    fn echo_of(far: &[i16], index: usize) -> i16 {
+        let at = |delay: usize| index.checked_sub(delay).map_or(0.0, |i| far[i] as f32);
+        (0.6 * at(5) + 0.25 * at(12)) as i16
+    }
+
+    fn energy(samples: &[i16]) -> f64 {
+        samples.iter().map(|&s| (s as f64) * (s as f64)).sum()
+    }
+
+    #[test]
+    fn test_nlms_reduces_echo() {
+        let mut aec = NlmsEchoCanceller::with_params(SAMPLE_RATE, 1, 32, 0.5);
+        let far = far_end_signal(FRAME * 100);
+
+        let mut echo_energy = 0.0;
+        let mut residual_energy = 0.0;
+
+        for (frame_index, chunk) in far.chunks(FRAME).enumerate() {
+            let start = frame_index * FRAME;
+            let mut near: Vec<i16> = (start..start + chunk.len())
+                .map(|i| echo_of(&far, i))
+                .collect();
+
+            aec.process_far_end(chunk);
+            let echo = energy(&near);
+            aec.process_near_end(&mut near);
+
+            // Measure after the filter had half the signal to converge
+            if frame_index >= 50 {
+                echo_energy += echo;
+                residual_energy += energy(&near);
+            }
+        }
+
+        // At least 20 dB of echo return loss enhancement
+        assert!(residual_energy < echo_energy / 100.0);
+    }
+
+    #[test]
+    fn test_silent_far_end_leaves_capture_untouched() {
+        let mut aec = NlmsEchoCanceller::new(SAMPLE_RATE, 2);
+        let mut near: Vec<i16> = (0..FRAME as i16 * 2).map(|i| i * 10).collect();
+        let original = near.clone();
+
+        aec.process_far_end(&[0; FRAME * 2]);
+        aec.process_near_end(&mut near);
+
+        assert_eq!(near, original);
+    }
+
+    #[test]
+    fn test_far_end_buffer_is_bounded() {
+        let mut aec = NlmsEchoCanceller::new(SAMPLE_RATE, 2);
+
+        for _ in 0..200 {
+            aec.process_far_end(&[100; FRAME * 2]);
+        }
+
+        assert!(aec.far_end.len() <= SAMPLE_RATE as usize * 2);
+        assert_eq!(aec.far_end.len() % 2, 0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Automatic gain control.
+//!
+//! Boosts quiet microphone input towards a target speech level, so quiet
+//! participants are as easy to hear as loud ones.
+
+use super::detection::AudioDetection;
+
+/// Automatic gain control (AGC) for captured audio
+///
+/// Measures the RMS of every frame and moves the gain towards the one that
+/// brings it to the target level: quickly when the gain must drop (attack),
+/// slowly when it may rise (release), so speech does not "pump". The gain
+/// only boosts (never below 1.0), is held on frames quiet enough to be
+/// background noise, and is lowered on frames whose peak would clip.
+pub struct AutomaticGainControl {
+    sample_rate: u32,
+    channels: u32,
+    /// Target RMS in 16-bit PCM units
+    target_rms: f32,
+    /// Gain applied at the end of the last frame
+    gain: f32,
+}
+
+impl AutomaticGainControl {
+    /// Largest boost applied (+24 dB)
+    pub const MAX_GAIN: f32 = 16.0;
+    /// Time constant for lowering the gain
+    const ATTACK_SECONDS: f32 = 0.01;
+    /// Time constant for raising the gain
+    const RELEASE_SECONDS: f32 = 0.5;
+
+    /// Creates a gain control for the given stream format
+    ///
+    /// # Arguments
+    /// * `sample_rate` - Sample rate in Hz
+    /// * `channels` - Number of interleaved channels
+    /// * `target_dbfs` - Target RMS level in dB relative to full scale
+    pub fn new(sample_rate: u32, channels: u32, target_dbfs: f32) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1),
+            channels: channels.max(1),
+            target_rms: i16::MAX as f32 * 10f32.powf(target_dbfs / 20.0),
+            gain: 1.0,
+        }
+    }
+
+    /// Current gain (linear)
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Applies the gain to a captured frame in place
+    ///
+    /// # Arguments
+    /// * `samples` - Captured samples, interleaved
+    pub fn process(&mut self, samples: &mut [i16]) {
+        if samples.is_empty() {
+            return;
+        }
+
+        let energy: f32 = samples.iter().map(|&s| s as f32 * s as f32).sum();
+        let rms = (energy / samples.len() as f32).sqrt();
+        let peak = samples.iter().map(|&s| s.unsigned_abs()).max().unwrap_or(0) as f32;
+
+        // Background noise holds the gain instead of being brought up to speech level
+        let desired = if rms < AudioDetection::SILENCE_THRESHOLD as f32 {
+            self.gain
+        } else {
+            (self.target_rms / rms).clamp(1.0, Self::MAX_GAIN)
+        };
+
+        let frame_seconds = samples.len() as f32 / (self.channels as f32 * self.sample_rate as f32);
+        let time_constant = if desired < self.gain {
+            Self::ATTACK_SECONDS
+        } else {
+            Self::RELEASE_SECONDS
+        };
+        let smoothing = 1.0 - (-frame_seconds / time_constant).exp();
+
+        // Never push the frame peak past full scale
+        let clip_limit = if peak > 0.0 {
+            i16::MAX as f32 / peak
+        } else {
+            Self::MAX_GAIN
+        };
+        let start = self.gain.min(clip_limit);
+        let end = (self.gain + smoothing * (desired - self.gain))
+            .min(clip_limit)
+            .max(1.0);
+
+        // Ramp across the frame so gain changes do not click
+        let step = (end - start) / samples.len() as f32;
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let gain = start + step * (i + 1) as f32;
+            *sample = (*sample as f32 * gain)
+                .round()
+                .clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        }
+
+        self.gain = end;
+    }
+
+    /// Resets the gain to unity
+    pub fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48000;
+    const FRAME: usize = 960; // 20 ms at 48 kHz
+
+    fn sine_frame(index: usize, amplitude: f32) -> Vec<i16> {
+        (index * FRAME..(index + 1) * FRAME)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * 440.0 * i as f32 / SAMPLE_RATE as f32;
+                (phase.sin() * amplitude) as i16
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[i16]) -> f32 {
+        let energy: f32 = samples.iter().map(|&s| s as f32 * s as f32).sum();
+        (energy / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_quiet_input_is_boosted_towards_target() {
+        let mut agc = AutomaticGainControl::new(SAMPLE_RATE, 1, -18.0);
+        let target = i16::MAX as f32 * 10f32.powf(-18.0 / 20.0);
+
+        let mut last = Vec::new();
+        for index in 0..150 {
+            last = sine_frame(index, 600.0);
+            agc.process(&mut last);
+        }
+
+        assert!(agc.gain() > 1.0);
+        assert!((rms(&last) / target - 1.0).abs() < 0.1, "{}", rms(&last));
+    }
+
+    #[test]
+    fn test_loud_input_is_not_amplified_or_clipped() {
+        let mut agc = AutomaticGainControl::new(SAMPLE_RATE, 1, -18.0);
+
+        for index in 0..50 {
+            let original = sine_frame(index, 30000.0);
+            let mut frame = original.clone();
+            agc.process(&mut frame);
+            assert_eq!(frame, original);
+        }
+        assert_eq!(agc.gain(), 1.0);
+    }
+
+    #[test]
+    fn test_background_noise_is_not_amplified() {
+        let mut agc = AutomaticGainControl::new(SAMPLE_RATE, 1, -18.0);
+
+        for index in 0..50 {
+            let mut frame = sine_frame(index, 200.0);
+            agc.process(&mut frame);
+        }
+        assert_eq!(agc.gain(), 1.0);
+    }
+
+    #[test]
+    fn test_boost_stops_at_full_scale() {
+        let mut agc = AutomaticGainControl::new(SAMPLE_RATE, 1, -3.0);
+        for index in 0..100 {
+            agc.process(&mut sine_frame(index, 2000.0));
+        }
+
+        // A sudden loud burst with the boost still applied
+        let mut burst = sine_frame(100, 20000.0);
+        agc.process(&mut burst);
+
+        let saturated = burst.iter().filter(|&&s| s.unsigned_abs() >= 32767).count();
+        assert!(saturated <= 2, "{saturated} samples at full scale");
+        assert!(agc.gain() <= i16::MAX as f32 / 20000.0);
+    }
+}
@@ -1,4 +1,5 @@
 /// Audio capture using cpal
+use crate::audio::resampler::Resampler;
 use crate::error::{MediaError, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use logging::Logger;
@@ -8,6 +9,7 @@ pub struct AudioCapture {
     buffer: Arc<Mutex<Vec<i16>>>,
     _stream: Option<cpal::Stream>,
     sample_rate: u32,
+    device_sample_rate: u32,
     channels: u32,
 }
 
@@ -71,22 +73,37 @@ impl AudioCapture {
 
         let buffer_clone = Arc::clone(&buffer);
         let channels_usize = config.channels() as usize;
+        let device_sample_rate = config.sample_rate().0;
+
+        // Samples reach the buffer at the requested (codec) rate
+        let resampler = Resampler::new(device_sample_rate, sample_rate, config.channels() as u32);
+        if !resampler.is_passthrough() {
+            logger.info(&format!(
+                "Resampling capture from {} Hz to {} Hz",
+                device_sample_rate, sample_rate
+            ));
+        }
 
         let stream = match config.sample_format() {
             cpal::SampleFormat::F32 => {
                 let config: cpal::StreamConfig = config.into();
+                let mut resampler = resampler;
                 device.build_input_stream(
                     &config,
                     move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                        let mut buf = buffer_clone.lock().expect("Audio buffer lock poisoned");
                         // Convertir f32 [-1.0, 1.0] a i16 con mejor precisión
-                        for &sample in data {
-                            // Clamp y redondear para evitar distorsión
-                            let clamped = sample.clamp(-1.0, 1.0);
-                            let scaled = clamped * 32767.0;
-                            let sample_i16 = scaled.round() as i16;
-                            buf.push(sample_i16);
-                        }
+                        let samples: Vec<i16> = data
+                            .iter()
+                            .map(|&sample| {
+                                // Clamp y redondear para evitar distorsión
+                                let clamped = sample.clamp(-1.0, 1.0);
+                                let scaled = clamped * 32767.0;
+                                scaled.round() as i16
+                            })
+                            .collect();
+                        let resampled = resampler.process(&samples);
+                        let mut buf = buffer_clone.lock().expect("Audio buffer lock poisoned");
+                        buf.extend_from_slice(&resampled);
                         limit_buffer_size(&mut buf, channels_usize);
                     },
                     |err| eprintln!("Audio input error: {}", err),
@@ -95,11 +112,13 @@ impl AudioCapture {
             }
             cpal::SampleFormat::I16 => {
                 let config: cpal::StreamConfig = config.into();
+                let mut resampler = resampler;
                 device.build_input_stream(
                     &config,
                     move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                        let resampled = resampler.process(data);
                         let mut buf = buffer_clone.lock().expect("Audio buffer lock poisoned");
-                        buf.extend_from_slice(data);
+                        buf.extend_from_slice(&resampled);
                         limit_buffer_size(&mut buf, channels_usize);
                     },
                     |err| eprintln!("Audio input error: {}", err),
@@ -108,15 +127,18 @@ impl AudioCapture {
             }
             cpal::SampleFormat::U16 => {
                 let config: cpal::StreamConfig = config.into();
+                let mut resampler = resampler;
                 device.build_input_stream(
                     &config,
                     move |data: &[u16], _: &cpal::InputCallbackInfo| {
-                        let mut buf = buffer_clone.lock().expect("Audio buffer lock poisoned");
                         // Convertir u16 a i16
-                        for &sample in data {
-                            let sample_i16 = (sample as i32 - 32768) as i16;
-                            buf.push(sample_i16);
-                        }
+                        let samples: Vec<i16> = data
+                            .iter()
+                            .map(|&sample| (sample as i32 - 32768) as i16)
+                            .collect();
+                        let resampled = resampler.process(&samples);
+                        let mut buf = buffer_clone.lock().expect("Audio buffer lock poisoned");
+                        buf.extend_from_slice(&resampled);
                         limit_buffer_size(&mut buf, channels_usize);
                     },
                     |err| eprintln!("Audio input error: {}", err),
@@ -137,6 +159,7 @@ impl AudioCapture {
             buffer,
             _stream: Some(stream),
             sample_rate,
+            device_sample_rate,
             channels,
         })
     }
@@ -171,6 +194,13 @@ impl AudioCapture {
         self.sample_rate
     }
 
+    /// Returns the native sample rate of the input device
+    ///
+    /// Captured samples are resampled to `sample_rate()` when this differs.
+    pub fn device_sample_rate(&self) -> u32 {
+        self.device_sample_rate
+    }
+
     pub fn channels(&self) -> u32 {
         self.channels
     }
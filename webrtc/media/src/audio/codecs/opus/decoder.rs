@@ -1,13 +1,18 @@
 //! Opus audio decoder implementation
 //!
-//! Provides Opus decoding using FFmpeg's libopus codec.
+//! Provides Opus decoding using FFmpeg's libopus codec, with concealment of
+//! lost packets.
 
+use crate::audio::config::AudioConfig;
 use crate::audio::frame::AudioFrame;
 use crate::error::{MediaError, Result};
 use ffmpeg::decoder::audio::Audio as FfmpegAudioDecoder;
 use ffmpeg_next as ffmpeg;
 use logging::Logger;
 
+/// Gain applied to the repeated frame for each consecutive lost packet
+const CONCEALMENT_FADE: f32 = 0.5;
+
 /// Opus audio decoder for WebRTC
 ///
 /// Decodes Opus compressed audio to PCM format for playback.
@@ -17,6 +22,12 @@ pub struct OpusDecoder {
     frame_count: u64,
     sample_rate: u32,
     channels: u32,
+    fec: bool,
+    /// Last decoded (or concealed) samples, repeated when a packet is lost
+    last_samples: Option<Vec<i16>>,
+    /// Packet already decoded while recovering the frame lost before it
+    recovered: Option<(Vec<u8>, AudioFrame)>,
+    concealed_frames: u64,
 }
 
 impl OpusDecoder {
@@ -33,9 +44,33 @@ impl OpusDecoder {
     /// * `Ok(OpusDecoder)` - Successfully initialized decoder
     /// * `Err` - If FFmpeg initialization fails
     pub fn new(sample_rate: u32, channels: u32, logger: Logger) -> Result<Self> {
+        let config = AudioConfig {
+            sample_rate,
+            channels,
+            ..AudioConfig::default()
+        };
+        Self::with_config(&config, logger)
+    }
+
+    /// Creates a new Opus decoder from an audio configuration
+    ///
+    /// When `config.enable_fec` is set, lost packets are recovered from the
+    /// packet that follows them (see [`OpusDecoder::conceal`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Audio configuration
+    /// * `logger` - Logger instance
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OpusDecoder)` - Successfully initialized decoder
+    /// * `Err` - If FFmpeg initialization fails
+    pub fn with_config(config: &AudioConfig, logger: Logger) -> Result<Self> {
+        let (sample_rate, channels) = (config.sample_rate, config.channels);
         logger.info(&format!(
-            "Initializing Opus decoder: sample_rate={}, channels={}",
-            sample_rate, channels
+            "Initializing Opus decoder: sample_rate={}, channels={}, fec={}",
+            sample_rate, channels, config.enable_fec
         ));
 
         ffmpeg::init().map_err(|e| MediaError::Codec(format!("FFmpeg init error: {}", e)))?;
@@ -63,9 +98,23 @@ impl OpusDecoder {
             frame_count: 0,
             sample_rate,
             channels,
+            fec: config.enable_fec,
+            last_samples: None,
+            recovered: None,
+            concealed_frames: 0,
         })
     }
 
+    /// Returns whether lost packets are recovered with in-band FEC
+    pub fn fec_enabled(&self) -> bool {
+        self.fec
+    }
+
+    /// Returns the number of frames produced by concealment
+    pub fn concealed_frames(&self) -> u64 {
+        self.concealed_frames
+    }
+
     /// Decodes an Opus packet to PCM audio
     ///
     /// # Arguments
@@ -81,6 +130,65 @@ impl OpusDecoder {
             return Ok(None);
         }
 
+        // Already decoded while concealing the packet lost before this one
+        if let Some((packet, frame)) = self.recovered.take()
+            && packet == data
+        {
+            return Ok(Some(frame));
+        }
+
+        let frame = self.decode_packet(data)?;
+        if let Some(frame) = &frame {
+            self.last_samples = Some(frame.samples.clone());
+        }
+        Ok(frame)
+    }
+
+    /// Produces audio for a lost packet
+    ///
+    /// With FEC enabled and the following packet at hand, the lost frame is
+    /// reconstructed from that packet, which carries the redundant copy; the
+    /// decoded result is kept so the next `decode` of that packet does not
+    /// advance the decoder twice. FFmpeg does not expose libopus' per-call
+    /// FEC flag, so the packet is decoded as a whole. Otherwise the last
+    /// frame is repeated with a fade, and silence is produced if nothing was
+    /// decoded yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `next_packet` - Encoded packet following the lost one, if received
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(AudioFrame)` - Concealment audio for the lost frame
+    /// * `Err` - If decoding the following packet fails
+    pub fn conceal(&mut self, next_packet: Option<&[u8]>) -> Result<AudioFrame> {
+        self.concealed_frames += 1;
+
+        if self.fec
+            && let Some(next) = next_packet.filter(|data| !data.is_empty())
+            && let Some(frame) = self.decode_packet(next)?
+        {
+            self.recovered = Some((next.to_vec(), frame.clone()));
+            self.last_samples = Some(frame.samples.clone());
+            return Ok(frame);
+        }
+
+        let samples = match self.last_samples.take() {
+            Some(last) => last
+                .iter()
+                .map(|&sample| (sample as f32 * CONCEALMENT_FADE) as i16)
+                .collect(),
+            // Nothing to repeat yet: one 20 ms frame of silence
+            None => vec![0; (self.sample_rate / 50 * self.channels) as usize],
+        };
+        self.last_samples = Some(samples.clone());
+
+        Ok(AudioFrame::new(samples, self.channels, self.sample_rate))
+    }
+
+    /// Sends one packet to FFmpeg and converts the resulting frame
+    fn decode_packet(&mut self, data: &[u8]) -> Result<Option<AudioFrame>> {
         // Create packet from data
         let packet = ffmpeg::Packet::copy(data);
 
@@ -244,8 +352,70 @@ impl OpusDecoder {
 impl Drop for OpusDecoder {
     fn drop(&mut self) {
         self.logger.info(&format!(
-            "Opus decoder stopped. Total frames decoded: {}",
-            self.frame_count
+            "Opus decoder stopped. Total frames decoded: {}, concealed: {}",
+            self.frame_count, self.concealed_frames
         ));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::codecs::opus::OpusEncoder;
+    use logging::LogLevel;
+    use tempfile::tempdir;
+
+    const SAMPLE_RATE: u32 = 48000;
+    const CHANNELS: u32 = 1;
+    const FRAME_SAMPLES: usize = 960; // 20 ms at 48 kHz
+
+    /// 20 ms of a 440 Hz tone starting at frame `index`
+    fn tone_frame(index: usize) -> AudioFrame {
+        let samples = (0..FRAME_SAMPLES)
+            .map(|i| {
+                let t = (index * FRAME_SAMPLES + i) as f32 / SAMPLE_RATE as f32;
+                ((2.0 * std::f32::consts::PI * 440.0 * t).sin() * 12000.0) as i16
+            })
+            .collect();
+        AudioFrame::new(samples, CHANNELS, SAMPLE_RATE)
+    }
+
+    #[test]
+    fn test_fec_conceals_dropped_packet() {
+        let dir = tempdir().unwrap();
+        let logger = Logger::new(dir.path().join("test_opus_fec.log"), LogLevel::Debug).unwrap();
+        let config = AudioConfig::new(None, SAMPLE_RATE, CHANNELS)
+            .unwrap()
+            .with_fec(20)
+            .unwrap();
+
+        let (Ok(mut encoder), Ok(mut decoder)) = (
+            OpusEncoder::with_config(&config, 32000, logger.clone()),
+            OpusDecoder::with_config(&config, logger),
+        ) else {
+            // No libopus in this environment
+            return;
+        };
+        assert!(decoder.fec_enabled());
+
+        let packets: Vec<Vec<u8>> = (0..10)
+            .map(|i| encoder.encode(&tone_frame(i)).unwrap())
+            .filter(|packet| !packet.is_empty())
+            .collect();
+        assert!(packets.len() >= 6);
+
+        // Packet 4 is lost in transit
+        for packet in &packets[..4] {
+            decoder.decode(packet).unwrap();
+        }
+        let concealed = decoder.conceal(Some(&packets[5])).unwrap();
+
+        assert!(!concealed.samples.is_empty());
+        assert!(concealed.samples.iter().any(|&sample| sample != 0));
+        assert_eq!(decoder.concealed_frames(), 1);
+
+        // The recovery packet is not decoded a second time
+        let next = decoder.decode(&packets[5]).unwrap().unwrap();
+        assert_eq!(next.samples, concealed.samples);
+    }
+}
@@ -3,11 +3,16 @@
 //! Provides Opus encoding using FFmpeg's libopus codec,
 //! optimized for low-latency voice communication.
 
+use crate::audio::config::AudioConfig;
+use crate::audio::detection::AudioDetection;
 use crate::audio::frame::AudioFrame;
 use crate::error::{MediaError, Result};
 use ffmpeg_next as ffmpeg;
 use logging::Logger;
 
+/// Silent frames between comfort noise updates while DTX is active (400 ms)
+const COMFORT_NOISE_INTERVAL: u32 = 20;
+
 /// Opus audio encoder for WebRTC
 ///
 /// Encodes PCM audio frames to Opus compressed format.
@@ -19,6 +24,12 @@ pub struct OpusEncoder {
     pts: i64,
     sample_rate: u32,
     channels: u32,
+    dtx: bool,
+    /// Consecutive silent frames seen while DTX is enabled
+    silent_frames: u32,
+    /// Speech resumed after silence; the next packet starts a talk spurt
+    talkspurt_pending: bool,
+    talkspurt_start: bool,
 }
 
 impl OpusEncoder {
@@ -36,9 +47,39 @@ impl OpusEncoder {
     /// * `Ok(OpusEncoder)` - Successfully initialized encoder
     /// * `Err` - If FFmpeg initialization or codec setup fails
     pub fn new(sample_rate: u32, channels: u32, bitrate: u32, logger: Logger) -> Result<Self> {
+        let config = AudioConfig {
+            sample_rate,
+            channels,
+            ..AudioConfig::default()
+        };
+        Self::with_config(&config, bitrate, logger)
+    }
+
+    /// Creates a new Opus encoder from an audio configuration
+    ///
+    /// Besides sample rate and channels, applies the configured in-band FEC,
+    /// expected packet loss and DTX.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Audio configuration
+    /// * `bitrate` - Target bitrate in bits per second (32000-128000 typical)
+    /// * `logger` - Logger instance
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OpusEncoder)` - Successfully initialized encoder
+    /// * `Err` - If FFmpeg initialization or codec setup fails
+    pub fn with_config(config: &AudioConfig, bitrate: u32, logger: Logger) -> Result<Self> {
+        let (sample_rate, channels) = (config.sample_rate, config.channels);
         logger.info(&format!(
-            "Initializing Opus encoder: sample_rate={}, channels={}, bitrate={}",
-            sample_rate, channels, bitrate
+            "Initializing Opus encoder: sample_rate={}, channels={}, bitrate={}, fec={}, packet_loss={}%, dtx={}",
+            sample_rate,
+            channels,
+            bitrate,
+            config.enable_fec,
+            config.expected_packet_loss,
+            config.enable_dtx
         ));
 
         ffmpeg::init().map_err(|e| MediaError::Codec(format!("FFmpeg init error: {}", e)))?;
@@ -82,7 +123,8 @@ impl OpusEncoder {
         let mut opts = ffmpeg::Dictionary::new();
         opts.set("application", "voip"); // Optimize for voice
         opts.set("frame_duration", "20"); // 20ms frames (low latency)
-        opts.set("packet_loss", "15"); // Enable packet loss resilience (0-100%)
+        opts.set("packet_loss", &config.expected_packet_loss.to_string()); // Expected loss (0-100%)
+        opts.set("fec", if config.enable_fec { "1" } else { "0" }); // In-band FEC
         opts.set("complexity", "8"); // Max quality (0-10)
         opts.set("vbr", "on"); // Variable bitrate for better quality
 
@@ -104,9 +146,46 @@ impl OpusEncoder {
             pts: 0,
             sample_rate,
             channels,
+            dtx: config.enable_dtx,
+            silent_frames: 0,
+            talkspurt_pending: false,
+            talkspurt_start: false,
         })
     }
 
+    /// Returns whether DTX is currently suppressing packets for silent input
+    pub fn is_silent(&self) -> bool {
+        self.dtx && self.silent_frames > 0
+    }
+
+    /// Returns whether the last packet returned by `encode` is the first one
+    /// of a talk spurt after DTX silence (RTP marker bit, RFC 3551)
+    pub fn is_talkspurt_start(&self) -> bool {
+        self.talkspurt_start
+    }
+
+    /// Applies DTX to the next input frame
+    ///
+    /// # Returns
+    /// * `true` - If the frame must be dropped (silence between comfort noise updates)
+    /// * `false` - If the frame must be encoded
+    fn dtx_suppresses(&mut self, frame: &AudioFrame) -> bool {
+        if !self.dtx {
+            return false;
+        }
+
+        if !AudioDetection::is_silent(frame) {
+            if self.silent_frames > 0 {
+                self.talkspurt_pending = true;
+                self.silent_frames = 0;
+            }
+            return false;
+        }
+
+        self.silent_frames += 1;
+        !self.silent_frames.is_multiple_of(COMFORT_NOISE_INTERVAL)
+    }
+
     /// Encodes an audio frame to Opus format
     ///
     /// # Arguments
@@ -133,8 +212,16 @@ impl OpusEncoder {
             ));
         }
 
-        // Create FFmpeg audio frame with proper parameters
         let frame_count = frame.samples.len() / frame.channels as usize;
+        self.talkspurt_start = false;
+
+        if self.dtx_suppresses(frame) {
+            // Keep the timeline running across the dropped frame
+            self.pts += frame_count as i64;
+            return Ok(Vec::new());
+        }
+
+        // Create FFmpeg audio frame with proper parameters
         let mut audio_frame = ffmpeg::frame::Audio::new(
             ffmpeg::format::Sample::I16(ffmpeg::format::sample::Type::Packed),
             frame_count,
@@ -172,6 +259,7 @@ impl OpusEncoder {
                     ));
                 }
 
+                self.talkspurt_start = std::mem::take(&mut self.talkspurt_pending);
                 Ok(encoded_packet.data().unwrap_or(&[]).to_vec())
             }
             Err(_) => {
@@ -239,3 +327,76 @@ impl Drop for OpusEncoder {
         ));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::LogLevel;
+    use tempfile::tempdir;
+
+    const SAMPLE_RATE: u32 = 48000;
+    const FRAME_SAMPLES: usize = 960; // 20 ms mono
+
+    fn create_dtx_encoder() -> Option<OpusEncoder> {
+        let dir = tempdir().unwrap();
+        let logger = Logger::new(dir.path().join("test_opus_dtx.log"), LogLevel::Debug).unwrap();
+        let config = AudioConfig::new(None, SAMPLE_RATE, 1).unwrap().with_dtx();
+        // None when libopus is not available in this environment
+        OpusEncoder::with_config(&config, 32000, logger).ok()
+    }
+
+    fn tone_frame() -> AudioFrame {
+        let samples = (0..FRAME_SAMPLES)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                ((2.0 * std::f32::consts::PI * 440.0 * t).sin() * 12000.0) as i16
+            })
+            .collect();
+        AudioFrame::new(samples, 1, SAMPLE_RATE)
+    }
+
+    fn silent_frame() -> AudioFrame {
+        AudioFrame::new(vec![0; FRAME_SAMPLES], 1, SAMPLE_RATE)
+    }
+
+    #[test]
+    fn test_dtx_drops_silence() {
+        let Some(mut encoder) = create_dtx_encoder() else {
+            return;
+        };
+
+        for _ in 0..COMFORT_NOISE_INTERVAL - 1 {
+            assert!(encoder.encode(&silent_frame()).unwrap().is_empty());
+            assert!(encoder.is_silent());
+        }
+
+        // Periodic comfort noise update
+        encoder.encode(&silent_frame()).unwrap();
+        assert!(encoder.is_silent());
+        assert!(!encoder.is_talkspurt_start());
+    }
+
+    #[test]
+    fn test_dtx_first_packet_after_silence_starts_talkspurt() {
+        let Some(mut encoder) = create_dtx_encoder() else {
+            return;
+        };
+
+        for _ in 0..5 {
+            assert!(encoder.encode(&silent_frame()).unwrap().is_empty());
+        }
+
+        // The encoder may buffer a frame before emitting the first packet
+        let mut packets = (0..3)
+            .map(|_| {
+                let packet = encoder.encode(&tone_frame()).unwrap();
+                (packet, encoder.is_talkspurt_start())
+            })
+            .filter(|(packet, _)| !packet.is_empty());
+
+        let (_, first_marks_talkspurt) = packets.next().expect("speech must produce packets");
+        assert!(first_marks_talkspurt);
+        assert!(packets.all(|(_, talkspurt)| !talkspurt));
+        assert!(!encoder.is_silent());
+    }
+}
@@ -1,7 +1,7 @@
 //! Audio configuration types.
 //!
 //! Provides configuration options for audio capture including
-//! sample rate, channels, and device selection.
+//! sample rate, channels, device selection and Opus loss resilience.
 
 use crate::error::{MediaError, Result};
 
@@ -16,6 +16,20 @@ pub struct AudioConfig {
     pub channels: u32,
     /// Buffer size in frames
     pub buffer_size: u32,
+    /// Enable Opus in-band forward error correction
+    pub enable_fec: bool,
+    /// Expected packet loss percentage (0-100) hinted to the Opus encoder
+    pub expected_packet_loss: u8,
+    /// Enable discontinuous transmission: stop sending packets during silence
+    pub enable_dtx: bool,
+    /// Enable acoustic echo cancellation of the playback signal on the capture
+    pub enable_aec: bool,
+    /// Enable suppression of stationary background noise on the capture
+    pub enable_noise_suppression: bool,
+    /// Enable automatic gain control of the capture towards `agc_target_dbfs`
+    pub enable_agc: bool,
+    /// Speech level (RMS, dBFS) the automatic gain control aims for
+    pub agc_target_dbfs: f32,
 }
 
 impl AudioConfig {
@@ -31,6 +45,14 @@ impl AudioConfig {
     const MIN_BUFFER_SIZE: u32 = 64;
     /// Maximum valid buffer size
     const MAX_BUFFER_SIZE: u32 = 8192;
+    /// Maximum valid packet loss percentage
+    const MAX_PACKET_LOSS: u8 = 100;
+    /// Packet loss hinted to the encoder when none is configured
+    const DEFAULT_PACKET_LOSS: u8 = 15;
+    /// Lowest valid AGC target level (dBFS)
+    const MIN_AGC_TARGET_DBFS: f32 = -40.0;
+    /// AGC target level when none is configured (dBFS)
+    const DEFAULT_AGC_TARGET_DBFS: f32 = -18.0;
 
     /// Creates a new audio configuration with validation
     ///
@@ -54,6 +76,13 @@ impl AudioConfig {
             sample_rate,
             channels,
             buffer_size,
+            enable_fec: false,
+            expected_packet_loss: Self::DEFAULT_PACKET_LOSS,
+            enable_dtx: false,
+            enable_aec: false,
+            enable_noise_suppression: false,
+            enable_agc: false,
+            agc_target_dbfs: Self::DEFAULT_AGC_TARGET_DBFS,
         })
     }
 
@@ -79,6 +108,83 @@ impl AudioConfig {
         Ok(self)
     }
 
+    /// Enables Opus in-band FEC for the expected packet loss
+    ///
+    /// With FEC the encoder embeds a low-bitrate copy of each frame in the
+    /// next packet, so the decoder can reconstruct a single lost packet.
+    ///
+    /// # Arguments
+    /// * `expected_packet_loss` - Expected packet loss percentage (0-100)
+    ///
+    /// # Returns
+    /// * `Ok(AudioConfig)` - FEC enabled
+    /// * `Err(MediaError::Config)` - If the percentage is above 100
+    pub fn with_fec(mut self, expected_packet_loss: u8) -> Result<Self> {
+        if expected_packet_loss > Self::MAX_PACKET_LOSS {
+            return Err(MediaError::Config(format!(
+                "Expected packet loss must be between 0 and {}, got {}",
+                Self::MAX_PACKET_LOSS,
+                expected_packet_loss
+            )));
+        }
+
+        self.enable_fec = true;
+        self.expected_packet_loss = expected_packet_loss;
+        Ok(self)
+    }
+
+    /// Enables discontinuous transmission (DTX)
+    ///
+    /// While the input is silent the encoder only emits periodic comfort
+    /// noise updates instead of a packet every frame.
+    pub fn with_dtx(mut self) -> Self {
+        self.enable_dtx = true;
+        self
+    }
+
+    /// Enables acoustic echo cancellation (AEC)
+    ///
+    /// The played far-end audio is used as a reference to remove its echo
+    /// from the captured audio before encoding.
+    pub fn with_aec(mut self) -> Self {
+        self.enable_aec = true;
+        self
+    }
+
+    /// Enables noise suppression
+    ///
+    /// Stationary background noise (fans, hum, hiss) is attenuated in the
+    /// captured audio before encoding.
+    pub fn with_noise_suppression(mut self) -> Self {
+        self.enable_noise_suppression = true;
+        self
+    }
+
+    /// Enables automatic gain control (AGC)
+    ///
+    /// Quiet input is boosted towards the target speech level before
+    /// encoding; loud input is left untouched.
+    ///
+    /// # Arguments
+    /// * `target_dbfs` - Target RMS level in dBFS (-40 to 0)
+    ///
+    /// # Returns
+    /// * `Ok(AudioConfig)` - AGC enabled
+    /// * `Err(MediaError::Config)` - If the target is out of range
+    pub fn with_agc(mut self, target_dbfs: f32) -> Result<Self> {
+        if !(Self::MIN_AGC_TARGET_DBFS..=0.0).contains(&target_dbfs) {
+            return Err(MediaError::Config(format!(
+                "AGC target must be between {} and 0 dBFS, got {}",
+                Self::MIN_AGC_TARGET_DBFS,
+                target_dbfs
+            )));
+        }
+
+        self.enable_agc = true;
+        self.agc_target_dbfs = target_dbfs;
+        Ok(self)
+    }
+
     /// Returns the buffer duration in milliseconds
     pub fn buffer_duration_ms(&self) -> f64 {
         (self.buffer_size as f64 / self.sample_rate as f64) * 1000.0
@@ -108,6 +214,13 @@ impl Default for AudioConfig {
             sample_rate: 48000,
             channels: 2,
             buffer_size: 960, // 20ms at 48kHz
+            enable_fec: false,
+            expected_packet_loss: Self::DEFAULT_PACKET_LOSS,
+            enable_dtx: false,
+            enable_aec: false,
+            enable_noise_suppression: false,
+            enable_agc: false,
+            agc_target_dbfs: Self::DEFAULT_AGC_TARGET_DBFS,
         }
     }
 }
@@ -123,6 +236,43 @@ mod tests {
         assert_eq!(config.sample_rate, 48000);
         assert_eq!(config.channels, 2);
         assert_eq!(config.buffer_size, 960);
+        assert!(!config.enable_fec);
+        assert!(!config.enable_dtx);
+        assert!(!config.enable_aec);
+        assert!(!config.enable_noise_suppression);
+        assert!(!config.enable_agc);
+    }
+
+    #[test]
+    fn test_with_fec() {
+        let config = AudioConfig::default().with_fec(20).unwrap();
+        assert!(config.enable_fec);
+        assert_eq!(config.expected_packet_loss, 20);
+
+        assert!(AudioConfig::default().with_fec(101).is_err());
+    }
+
+    #[test]
+    fn test_with_dtx() {
+        let config = AudioConfig::default().with_dtx();
+        assert!(config.enable_dtx);
+        assert!(!config.enable_fec);
+    }
+
+    #[test]
+    fn test_with_aec() {
+        let config = AudioConfig::default().with_aec();
+        assert!(config.enable_aec);
+    }
+
+    #[test]
+    fn test_with_agc() {
+        let config = AudioConfig::default().with_agc(-20.0).unwrap();
+        assert!(config.enable_agc);
+        assert_eq!(config.agc_target_dbfs, -20.0);
+
+        assert!(AudioConfig::default().with_agc(3.0).is_err());
+        assert!(AudioConfig::default().with_agc(-60.0).is_err());
     }
 
     #[test]
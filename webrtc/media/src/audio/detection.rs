@@ -1,11 +1,13 @@
 //! Audio device detection and enumeration.
 //!
 //! Platform-specific utilities for discovering and probing available
-//! audio input devices on the system.
+//! audio input devices on the system, plus silence detection on captured
+//! frames.
 
 use crate::error::Result;
 use logging::Logger;
 
+use super::frame::AudioFrame;
 use super::info::AudioInfo;
 
 /// Audio device detection and enumeration
@@ -165,6 +167,57 @@ impl AudioDetection {
             .next()
             .ok_or_else(|| crate::error::MediaError::Audio("No audio input devices found".into()))
     }
+
+    /// RMS level (16-bit PCM) below which a frame is considered silence
+    pub const SILENCE_THRESHOLD: f64 = 300.0;
+
+    /// Checks whether a captured frame is below the silence threshold
+    ///
+    /// # Arguments
+    /// * `frame` - Audio frame to analyze
+    ///
+    /// # Returns
+    /// * `true` if the frame RMS is below `SILENCE_THRESHOLD` (or it is empty)
+    /// * `false` otherwise
+    pub fn is_silent(frame: &AudioFrame) -> bool {
+        frame.samples.is_empty() || Self::rms(frame) < Self::SILENCE_THRESHOLD
+    }
+
+    /// Level (dBFS) mapped to 0.0, anything quieter is reported as silence
+    const LEVEL_FLOOR_DB: f64 = -60.0;
+
+    /// Measures the loudness of a captured frame, for speaking indicators
+    ///
+    /// The frame RMS is converted to dBFS and mapped linearly from
+    /// `LEVEL_FLOOR_DB` (0.0) to full scale (1.0).
+    ///
+    /// # Arguments
+    /// * `frame` - Audio frame to analyze
+    ///
+    /// # Returns
+    /// Level between 0.0 and 1.0 (0.0 for an empty frame)
+    pub fn level(frame: &AudioFrame) -> f32 {
+        if frame.samples.is_empty() {
+            return 0.0;
+        }
+
+        let rms = Self::rms(frame) / i16::MAX as f64;
+        if rms <= 0.0 {
+            return 0.0;
+        }
+        let db = 20.0 * rms.log10();
+        (1.0 - db / Self::LEVEL_FLOOR_DB).clamp(0.0, 1.0) as f32
+    }
+
+    /// Root mean square of the frame samples (16-bit PCM scale)
+    fn rms(frame: &AudioFrame) -> f64 {
+        let energy: f64 = frame
+            .samples
+            .iter()
+            .map(|&sample| (sample as f64) * (sample as f64))
+            .sum();
+        (energy / frame.samples.len() as f64).sqrt()
+    }
 }
 
 #[cfg(test)]
@@ -212,4 +265,31 @@ mod tests {
         assert_eq!(devices[0].name, "HDA Intel PCH");
         assert_eq!(devices[1].name, "USB Audio");
     }
+
+    #[test]
+    fn test_is_silent() {
+        let silence = AudioFrame::new(vec![0, 12, -20, 8], 1, 48000);
+        let speech = AudioFrame::new(vec![8000, -9000, 7000, -6000], 1, 48000);
+
+        assert!(AudioDetection::is_silent(&silence));
+        assert!(!AudioDetection::is_silent(&speech));
+    }
+
+    #[test]
+    fn test_level_silence_vs_loud_sine() {
+        let silence = AudioFrame::new(vec![0; 960], 1, 48000);
+        let sine: Vec<i16> = (0..960)
+            .map(|i| {
+                let phase = 2.0 * std::f64::consts::PI * 440.0 * i as f64 / 48000.0;
+                (phase.sin() * 30000.0) as i16
+            })
+            .collect();
+        let loud = AudioFrame::new(sine, 1, 48000);
+
+        let empty = AudioFrame::new(Vec::new(), 1, 48000);
+
+        assert_eq!(AudioDetection::level(&silence), 0.0);
+        assert_eq!(AudioDetection::level(&empty), 0.0);
+        assert!(AudioDetection::level(&loud) > 0.9);
+    }
 }
@@ -7,10 +7,13 @@ use crate::common::constants::logging::AUDIO_LOG_INTERVAL;
 use crate::error::{MediaError, Result};
 use logging::Logger;
 
+use super::aec::{EchoCanceller, NlmsEchoCanceller};
+use super::agc::AutomaticGainControl;
 use super::capture::AudioCapture;
 use super::config::AudioConfig;
 use super::detection::AudioDetection;
 use super::frame::AudioFrame;
+use super::ns::{NoiseSuppressor, SpectralNoiseSuppressor};
 use super::playback::AudioPlayback;
 
 /// Audio capture and playback device
@@ -26,6 +29,9 @@ pub struct Audio {
     playback_frame_count: u64,
     playback: Option<AudioPlayback>,
     capture: Option<AudioCapture>,
+    echo_canceller: Option<Box<dyn EchoCanceller>>,
+    noise_suppressor: Option<Box<dyn NoiseSuppressor>>,
+    gain_control: Option<AutomaticGainControl>,
 }
 
 impl Audio {
@@ -56,6 +62,35 @@ impl Audio {
             )));
         }
 
+        let echo_canceller: Option<Box<dyn EchoCanceller>> = if config.enable_aec {
+            logger.info("Acoustic echo cancellation enabled (NLMS)");
+            Some(Box::new(NlmsEchoCanceller::new(
+                config.sample_rate,
+                config.channels,
+            )))
+        } else {
+            None
+        };
+
+        let noise_suppressor: Option<Box<dyn NoiseSuppressor>> = if config.enable_noise_suppression
+        {
+            logger.info("Noise suppression enabled (spectral subtraction)");
+            Some(Box::new(SpectralNoiseSuppressor::new(
+                config.sample_rate,
+                config.channels,
+            )))
+        } else {
+            None
+        };
+
+        let gain_control = config.enable_agc.then(|| {
+            logger.info(&format!(
+                "Automatic gain control enabled (target {} dBFS)",
+                config.agc_target_dbfs
+            ));
+            AutomaticGainControl::new(config.sample_rate, config.channels, config.agc_target_dbfs)
+        });
+
         logger.info("Audio device initialized successfully");
 
         Ok(Audio {
@@ -67,6 +102,9 @@ impl Audio {
             playback_frame_count: 0,
             playback: None,
             capture: None,
+            echo_canceller,
+            noise_suppressor,
+            gain_control,
         })
     }
 
@@ -140,12 +178,26 @@ impl Audio {
 
         let sample_count = self.config.buffer_size as usize * self.config.channels as usize;
 
-        let samples = if let Some(ref capture) = self.capture {
+        let mut samples = if let Some(ref capture) = self.capture {
             capture.read_samples(sample_count)
         } else {
             vec![0i16; sample_count]
         };
 
+        if let Some(aec) = self.echo_canceller.as_mut() {
+            aec.process_near_end(&mut samples);
+        }
+
+        // After AEC so the echo residue is not learned as background noise
+        if let Some(ns) = self.noise_suppressor.as_mut() {
+            ns.process(&mut samples);
+        }
+
+        // Last, so the residual noise level is what decides whether to boost
+        if let Some(agc) = self.gain_control.as_mut() {
+            agc.process(&mut samples);
+        }
+
         self.frame_count += 1;
 
         // Log progress periodically
@@ -226,6 +278,10 @@ impl Audio {
             playback.play_samples(&frame.samples);
         }
 
+        if let Some(aec) = self.echo_canceller.as_mut() {
+            aec.process_far_end(&frame.samples);
+        }
+
         self.playback_frame_count += 1;
 
         if self.playback_frame_count.is_multiple_of(AUDIO_LOG_INTERVAL) {
@@ -249,6 +305,37 @@ impl Audio {
         self.playback_frame_count
     }
 
+    /// Replaces the echo canceller used between playback and capture
+    ///
+    /// # Arguments
+    /// * `echo_canceller` - Custom implementation, or `None` to disable AEC
+    pub fn set_echo_canceller(&mut self, echo_canceller: Option<Box<dyn EchoCanceller>>) {
+        self.echo_canceller = echo_canceller;
+    }
+
+    /// Returns whether echo cancellation is active
+    pub fn is_aec_enabled(&self) -> bool {
+        self.echo_canceller.is_some()
+    }
+
+    /// Replaces the noise suppressor applied to captured audio
+    ///
+    /// # Arguments
+    /// * `noise_suppressor` - Custom implementation, or `None` to disable it
+    pub fn set_noise_suppressor(&mut self, noise_suppressor: Option<Box<dyn NoiseSuppressor>>) {
+        self.noise_suppressor = noise_suppressor;
+    }
+
+    /// Returns whether noise suppression is active
+    pub fn is_noise_suppression_enabled(&self) -> bool {
+        self.noise_suppressor.is_some()
+    }
+
+    /// Returns whether automatic gain control is active
+    pub fn is_agc_enabled(&self) -> bool {
+        self.gain_control.is_some()
+    }
+
     /// Clears the audio capture buffer
     pub fn clear_capture_buffer(&self) {
         if let Some(ref capture) = self.capture {
@@ -290,6 +377,41 @@ mod tests {
         assert!(!audio.is_capturing());
     }
 
+    #[test]
+    fn test_aec_follows_config() {
+        let audio = create_test_audio();
+        assert!(!audio.is_aec_enabled());
+
+        let config = AudioConfig::default().with_aec();
+        let mut audio = Audio::new(config, create_test_logger()).unwrap();
+        assert!(audio.is_aec_enabled());
+
+        audio.set_echo_canceller(None);
+        assert!(!audio.is_aec_enabled());
+    }
+
+    #[test]
+    fn test_noise_suppression_follows_config() {
+        let audio = create_test_audio();
+        assert!(!audio.is_noise_suppression_enabled());
+
+        let config = AudioConfig::default().with_noise_suppression();
+        let mut audio = Audio::new(config, create_test_logger()).unwrap();
+        assert!(audio.is_noise_suppression_enabled());
+
+        audio.set_noise_suppressor(None);
+        assert!(!audio.is_noise_suppression_enabled());
+    }
+
+    #[test]
+    fn test_agc_follows_config() {
+        assert!(!create_test_audio().is_agc_enabled());
+
+        let config = AudioConfig::default().with_agc(-18.0).unwrap();
+        let audio = Audio::new(config, create_test_logger()).unwrap();
+        assert!(audio.is_agc_enabled());
+    }
+
     #[test]
     fn test_audio_auto() {
         let logger = create_test_logger();
@@ -1,7 +1,10 @@
 //! Audio processing module
 //!
-//! Handles audio capture, encoding, decoding, and playback.
+//! Handles audio capture, resampling, echo cancellation, noise suppression,
+//! gain control, encoding, decoding, and playback.
 
+pub mod aec;
+pub mod agc;
 pub mod capture;
 pub mod codecs;
 pub mod config;
@@ -9,9 +12,13 @@ pub mod detection;
 pub mod device;
 pub mod frame;
 pub mod info;
+pub mod ns;
 pub mod playback;
+pub mod resampler;
 pub mod traits;
 
+pub use aec::{EchoCanceller, NlmsEchoCanceller};
+pub use agc::AutomaticGainControl;
 pub use capture::AudioCapture;
 pub use codecs::{OpusDecoder, OpusEncoder};
 pub use config::AudioConfig;
@@ -19,5 +26,7 @@ pub use detection::AudioDetection;
 pub use device::Audio;
 pub use frame::{AudioFrame, AudioSample};
 pub use info::AudioInfo;
+pub use ns::{NoiseSuppressor, SpectralNoiseSuppressor};
 pub use playback::AudioPlayback;
+pub use resampler::Resampler;
 pub use traits::{AudioDecoder, AudioEncoder};
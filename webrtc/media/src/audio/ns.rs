@@ -0,0 +1,339 @@
+//! Noise suppression.
+//!
+//! Attenuates stationary background noise (fans, hum, hiss) in the captured
+//! audio before encoding, while letting speech and other louder sounds
+//! through.
+
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+/// Trait for pluggable noise suppressors
+///
+/// Samples are interleaved 16-bit PCM. Implementations must give back as
+/// many samples as they receive, so frames keep the size and rate the
+/// encoder expects; a fixed processing delay is allowed.
+pub trait NoiseSuppressor: Send {
+    /// Removes background noise from captured samples in place
+    ///
+    /// # Arguments
+    /// * `samples` - Captured samples, interleaved
+    fn process(&mut self, samples: &mut [i16]);
+
+    /// Resets the noise estimate
+    ///
+    /// Called when the noise environment changes (device switch, stream restart).
+    fn reset(&mut self) {
+        // Default: no-op, implementations can override
+    }
+}
+
+/// Analysis state for one channel
+struct ChannelState {
+    /// Samples of the block being filled (the previous half block first)
+    input: Vec<f32>,
+    /// Second half of the previous processed block, added to the next one
+    overlap: Vec<f32>,
+    /// Processed samples not yet returned; holds the half block delay
+    output: VecDeque<f32>,
+    /// Estimated noise power per frequency bin
+    noise: Vec<f32>,
+    /// Blocks analyzed, used while the noise estimate is initialized
+    blocks: usize,
+}
+
+impl ChannelState {
+    fn new(block_size: usize) -> Self {
+        let hop = block_size / 2;
+        Self {
+            input: vec![0.0; block_size - hop],
+            overlap: vec![0.0; block_size - hop],
+            output: VecDeque::from(vec![0.0; hop]),
+            noise: vec![SpectralNoiseSuppressor::MIN_NOISE_POWER; hop + 1],
+            blocks: 0,
+        }
+    }
+
+    /// Updates the noise estimate of one bin from its power in this block
+    fn update_noise(&mut self, bin: usize, power: f32) {
+        let noise = &mut self.noise[bin];
+        if self.blocks < SpectralNoiseSuppressor::INIT_BLOCKS {
+            // Average the first blocks, assumed to be background noise
+            *noise += (power - *noise) / (self.blocks + 1) as f32;
+        } else if power < SpectralNoiseSuppressor::NOISE_UPDATE_RATIO * *noise {
+            *noise += SpectralNoiseSuppressor::NOISE_SMOOTHING * (power - *noise);
+        } else {
+            // Likely signal: only creep up so a louder noise floor is still learned
+            *noise *= SpectralNoiseSuppressor::NOISE_RISE;
+        }
+        *noise = noise.max(SpectralNoiseSuppressor::MIN_NOISE_POWER);
+    }
+
+    /// Suppresses noise in the full input block and queues half a block of output
+    fn process_block(&mut self, window: &[f32]) {
+        let size = self.input.len();
+        let hop = size / 2;
+
+        let mut re: Vec<f32> = self.input.iter().zip(window).map(|(x, w)| x * w).collect();
+        let mut im = vec![0.0; size];
+        fft(&mut re, &mut im, false);
+
+        for bin in 0..=hop {
+            let power = re[bin] * re[bin] + im[bin] * im[bin];
+            self.update_noise(bin, power);
+            let gain = spectral_gain(power, self.noise[bin]);
+
+            re[bin] *= gain;
+            im[bin] *= gain;
+            // Keep the spectrum conjugate symmetric so the output stays real
+            if bin != 0 && bin != hop {
+                re[size - bin] *= gain;
+                im[size - bin] *= gain;
+            }
+        }
+        self.blocks += 1;
+
+        fft(&mut re, &mut im, true);
+
+        // Hann windows at 50% overlap sum to one, so plain overlap-add restores the level
+        for (overlap, sample) in self.overlap.iter().zip(&re[..hop]) {
+            self.output.push_back(overlap + sample);
+        }
+        self.overlap.copy_from_slice(&re[hop..]);
+        self.input.drain(..hop);
+    }
+}
+
+/// Spectral subtraction noise suppressor
+///
+/// Splits each channel into half-overlapping Hann windowed blocks (about
+/// 10 ms), learns the noise power of every frequency bin from the bins that
+/// look like noise, and attenuates each bin by how much of its power is
+/// explained by noise. Output is delayed by half a block.
+pub struct SpectralNoiseSuppressor {
+    channels: usize,
+    block_size: usize,
+    window: Vec<f32>,
+    states: Vec<ChannelState>,
+}
+
+impl SpectralNoiseSuppressor {
+    /// Blocks averaged to initialize the noise estimate
+    const INIT_BLOCKS: usize = 10;
+    /// Bins below this multiple of the noise estimate update it
+    const NOISE_UPDATE_RATIO: f32 = 4.0;
+    /// Weight of a new noise-like block in the noise estimate
+    const NOISE_SMOOTHING: f32 = 0.05;
+    /// Per block growth of the noise estimate while a bin carries signal
+    const NOISE_RISE: f32 = 1.001;
+    /// Lower bound of the noise estimate, keeps digital silence from locking it at zero
+    const MIN_NOISE_POWER: f32 = 1.0;
+    /// Over-subtraction factor, trades residual noise for speech distortion
+    const OVER_SUBTRACTION: f32 = 2.0;
+    /// Minimum gain of a bin (-20 dB), avoids "musical noise" from fully muted bins
+    const GAIN_FLOOR: f32 = 0.1;
+
+    /// Creates a noise suppressor for the given stream format
+    ///
+    /// # Arguments
+    /// * `sample_rate` - Sample rate in Hz, sets the block length
+    /// * `channels` - Number of interleaved channels (at least 1)
+    pub fn new(sample_rate: u32, channels: u32) -> Self {
+        let channels = channels.max(1) as usize;
+        // About 10 ms per block, rounded up to a power of two for the FFT
+        let block_size = (sample_rate as usize / 100).next_power_of_two().max(64);
+        let window = (0..block_size)
+            .map(|n| 0.5 - 0.5 * (2.0 * PI * n as f32 / block_size as f32).cos())
+            .collect();
+
+        Self {
+            channels,
+            block_size,
+            window,
+            states: (0..channels)
+                .map(|_| ChannelState::new(block_size))
+                .collect(),
+        }
+    }
+
+    /// Processing delay in samples per channel
+    pub fn delay(&self) -> usize {
+        self.block_size / 2
+    }
+}
+
+impl NoiseSuppressor for SpectralNoiseSuppressor {
+    fn process(&mut self, samples: &mut [i16]) {
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let state = &mut self.states[i % self.channels];
+            state.input.push(*sample as f32);
+            if state.input.len() == self.block_size {
+                state.process_block(&self.window);
+            }
+
+            let processed = state.output.pop_front().unwrap_or(0.0);
+            *sample = processed.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.states = (0..self.channels)
+            .map(|_| ChannelState::new(self.block_size))
+            .collect();
+    }
+}
+
+/// Amplitude gain of a bin with `power`, given the estimated `noise` power
+fn spectral_gain(power: f32, noise: f32) -> f32 {
+    let floor = SpectralNoiseSuppressor::GAIN_FLOOR * SpectralNoiseSuppressor::GAIN_FLOOR;
+    if power <= 0.0 {
+        return SpectralNoiseSuppressor::GAIN_FLOOR;
+    }
+    (1.0 - SpectralNoiseSuppressor::OVER_SUBTRACTION * noise / power)
+        .max(floor)
+        .sqrt()
+}
+
+/// In-place radix-2 FFT over `re` + i`im` (length must be a power of two)
+///
+/// The inverse transform is scaled by 1/n so a round trip is lossless.
+fn fft(re: &mut [f32], im: &mut [f32], inverse: bool) {
+    let n = re.len();
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let step = sign * 2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_im, w_re) = (step * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+
+    if inverse {
+        let scale = 1.0 / n as f32;
+        for value in re.iter_mut().chain(im.iter_mut()) {
+            *value *= scale;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 16000;
+    const FRAME: usize = 320; // 20 ms at 16 kHz
+
+    /// Deterministic white noise
+    fn noise(len: usize, amplitude: i16) -> Vec<i16> {
+        let mut state: u32 = 0x1234_5678;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                ((state >> 16) as i16) / (i16::MAX / amplitude)
+            })
+            .collect()
+    }
+
+    fn tone(len: usize, frequency: f32, amplitude: f32) -> Vec<i16> {
+        (0..len)
+            .map(|i| {
+                let phase = 2.0 * PI * frequency * i as f32 / SAMPLE_RATE as f32;
+                (phase.sin() * amplitude) as i16
+            })
+            .collect()
+    }
+
+    fn energy(samples: &[i16]) -> f64 {
+        samples.iter().map(|&s| (s as f64) * (s as f64)).sum()
+    }
+
+    fn suppress(ns: &mut SpectralNoiseSuppressor, input: &[i16]) -> Vec<i16> {
+        let mut output = input.to_vec();
+        for frame in output.chunks_mut(FRAME) {
+            ns.process(frame);
+        }
+        output
+    }
+
+    #[test]
+    fn test_reduces_noise_floor_and_keeps_tone() {
+        let mut ns = SpectralNoiseSuppressor::new(SAMPLE_RATE, 1);
+        let second = SAMPLE_RATE as usize;
+
+        // One second of background noise, then a tone over the same noise
+        let background = noise(2 * second, 1000);
+        let tone = tone(second, 1000.0, 8000.0);
+        let mut input = background.clone();
+        for (sample, tone) in input[second..].iter_mut().zip(&tone) {
+            *sample = sample.saturating_add(*tone);
+        }
+        let output = suppress(&mut ns, &input);
+
+        // Noise floor, after the estimate settled
+        let noise_in = energy(&input[second / 2..second]);
+        let noise_out = energy(&output[second / 2..second]);
+        assert!(noise_out < noise_in / 4.0, "{noise_out} vs {noise_in}");
+
+        // Tone, measured past the processing delay
+        let start = second + second / 2 + ns.delay();
+        let tone_out = energy(&output[start..]);
+        let expected = energy(&tone[second / 2..second - ns.delay()]);
+        assert!(
+            (tone_out / expected - 1.0).abs() < 0.1,
+            "{tone_out} vs {expected}"
+        );
+    }
+
+    #[test]
+    fn test_keeps_frame_size_and_stereo_channels_apart() {
+        let mut ns = SpectralNoiseSuppressor::new(48000, 2);
+        let mut frame: Vec<i16> = (0..960 * 2)
+            .map(|i| if i % 2 == 0 { 6000 } else { 0 })
+            .collect();
+
+        ns.process(&mut frame);
+
+        assert_eq!(frame.len(), 960 * 2);
+        assert!(frame.iter().skip(1).step_by(2).all(|&s| s == 0));
+    }
+
+    #[test]
+    fn test_fft_round_trip() {
+        let original: Vec<f32> = (0..64).map(|i| (i as f32 * 0.3).sin() * 100.0).collect();
+        let mut re = original.clone();
+        let mut im = vec![0.0; 64];
+
+        fft(&mut re, &mut im, false);
+        fft(&mut re, &mut im, true);
+
+        for (a, b) in re.iter().zip(&original) {
+            assert!((a - b).abs() < 1e-3);
+        }
+    }
+}
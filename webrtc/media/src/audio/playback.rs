@@ -1,4 +1,5 @@
 /// Simple cpal-based audio playback handler
+use crate::audio::resampler::Resampler;
 use crate::error::{MediaError, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use logging::Logger;
@@ -8,7 +9,9 @@ use std::sync::{Arc, Mutex};
 pub struct AudioPlayback {
     buffer: Arc<Mutex<VecDeque<f32>>>,
     _stream: Option<cpal::Stream>,
+    resampler: Mutex<Resampler>,
     prebuffer_size: usize,
+    device_sample_rate: u32,
 }
 
 // SAFETY: The stream is never accessed directly after creation.
@@ -23,11 +26,6 @@ impl AudioPlayback {
             source_sample_rate, channels
         ));
 
-        // Pre-buffer 200ms worth of audio to prevent underruns
-        let prebuffer_size = (source_sample_rate as f32 * 0.20 * channels as f32) as usize;
-        let buffer = Arc::new(Mutex::new(VecDeque::with_capacity(prebuffer_size * 10)));
-        let is_prebuffering = Arc::new(Mutex::new(true));
-
         let host = cpal::default_host();
         let device = host
             .default_output_device()
@@ -51,16 +49,21 @@ impl AudioPlayback {
         ));
 
         // We use the default config for the device to ensure compatibility
-        // The callback will handle resampling if source_rate != device_rate
+        // play_samples() resamples if source_rate != device_rate
         let config: cpal::StreamConfig = default_config.into();
 
-        // Calculate resampling ratio
-        let resampling_ratio = source_sample_rate as f32 / sample_rate as f32;
+        let resampler = Resampler::new(source_sample_rate, sample_rate, channels);
+        if !resampler.is_passthrough() {
+            logger.info(&format!(
+                "Resampling playback from {} Hz to {} Hz",
+                source_sample_rate, sample_rate
+            ));
+        }
 
-        logger.info(&format!(
-            "Resampling ratio: {:.4} (Source: {}, Device: {})",
-            resampling_ratio, source_sample_rate, sample_rate
-        ));
+        // Pre-buffer 200ms worth of audio (at the device rate) to prevent underruns
+        let prebuffer_size = (sample_rate as f32 * 0.20 * channels as f32) as usize;
+        let buffer = Arc::new(Mutex::new(VecDeque::with_capacity(prebuffer_size * 10)));
+        let is_prebuffering = Arc::new(Mutex::new(true));
 
         // Warning if channel count mismatch (we don't handle channel mixing elegantly yet, just truncation/silence)
         if channels as u16 != device_channels {
@@ -74,12 +77,7 @@ impl AudioPlayback {
         let is_prebuffering_clone = Arc::clone(&is_prebuffering);
         let mut underrun_count = 0usize;
 
-        let mut fractional_pos = 0.0f32;
-
-        let channel_count = channels as usize;
-        let mut previous_frame = vec![0.0f32; channel_count];
-        let mut next_frame = vec![0.0f32; channel_count];
-        let mut has_next_frame = false;
+        let channel_count = (channels as usize).max(1);
 
         let stream = device
             .build_output_stream(
@@ -89,15 +87,10 @@ impl AudioPlayback {
                     let mut prebuffering = is_prebuffering_clone
                         .lock()
                         .expect("Prebuffering lock poisoned");
-                    let available = buf.len();
-
-                    // We need enough data to produce 'needed' output samples
-                    // needed_input ~= data.len() * ratio
-                    // But checking loose availability is safer
 
                     // Wait until prebuffer is filled before starting playback
                     if *prebuffering {
-                        if available >= prebuffer_size {
+                        if buf.len() >= prebuffer_size {
                             *prebuffering = false;
                         } else {
                             // Still prebuffering, output silence
@@ -106,87 +99,37 @@ impl AudioPlayback {
                         }
                     }
 
-                    // Process output samples
+                    // Samples are already at the device rate, copy frame by frame
+                    // data structure: [L, R, L, R...]
                     let output_channels = config.channels as usize;
                     // We assume source and output have same channel count for now or we just map 1:1 up to min
                     let process_channels = std::cmp::min(channel_count, output_channels);
 
-                    let mut output_idx = 0;
-                    while output_idx < data.len() {
-                        // Ensure we have current and next frame for interpolation
-                        if !has_next_frame {
-                            if buf.len() >= channel_count {
-                                // Shift next to previous
-                                previous_frame.copy_from_slice(&next_frame);
-
-                                // Pop new next frame
-                                for sample in next_frame.iter_mut().take(channel_count) {
-                                    *sample = buf.pop_front().unwrap_or(0.0);
-                                }
-                                has_next_frame = true;
-                            } else {
-                                // Underrun
-                                underrun_count += 1;
-                                if underrun_count > 10 {
-                                    // allow some small underruns before reset
-                                    *prebuffering = true;
-                                    underrun_count = 0;
-                                }
-
-                                // Fill remainder with silence/fade
-                                while output_idx < data.len() {
-                                    data[output_idx] = 0.0;
-                                    output_idx += 1;
-                                }
-                                return;
-                            }
+                    let available_frames = buf.len() / channel_count;
+                    let mut underrun = false;
+                    for (index, frame) in data.chunks_mut(output_channels).enumerate() {
+                        if index >= available_frames {
+                            // Fill remainder with silence
+                            frame.fill(0.0);
+                            underrun = true;
+                            continue;
                         }
 
-                        // Map channels
-                        // data structure: [L, R, L, R...]
-                        for c in 0..output_channels {
-                            if output_idx >= data.len() {
-                                break;
-                            }
-
-                            let sample_val = if c < process_channels {
-                                // Linear interpolation
-                                let prev = previous_frame[c];
-                                let next = next_frame[c];
-                                prev + (next - prev) * fractional_pos
-                            } else {
-                                0.0 // silence for extra channels
-                            };
-
-                            data[output_idx] = sample_val;
-                            output_idx += 1;
+                        for (c, sample) in frame.iter_mut().enumerate() {
+                            // silence for extra channels
+                            *sample = if c < process_channels { buf[c] } else { 0.0 };
                         }
+                        buf.drain(..channel_count);
+                    }
 
-                        // Advance position
-                        fractional_pos += resampling_ratio;
-                        while fractional_pos >= 1.0 {
-                            fractional_pos -= 1.0;
-                            // Need new frame
-                            has_next_frame = false; // logic loop will fetch next
-
-                            // Optimization: if we skipped multiple frames (downsampling), we need to consume more
-                            // But usually ratio is close to 1.0 (48k <-> 44.1k) so loop handles it naturaly
-                            // by setting has_next_frame=false and re-entering the fetch block above
-                            if fractional_pos >= 1.0 {
-                                // We are stepping very fast (output rate << source rate), consume more
-                                if buf.len() >= channel_count {
-                                    previous_frame.copy_from_slice(&next_frame);
-                                    for sample in next_frame.iter_mut().take(channel_count) {
-                                        *sample = buf.pop_front().unwrap_or(0.0);
-                                    }
-                                    fractional_pos -= 1.0;
-                                    // Loop continues to check if still >= 1.0 or fetches again
-                                } else {
-                                    // Buffer empty during skip
-                                    break;
-                                }
-                            }
+                    if underrun {
+                        underrun_count += 1;
+                        if underrun_count > 10 {
+                            // allow some small underruns before reset
+                            *prebuffering = true;
+                            underrun_count = 0;
                         }
+                        return;
                     }
 
                     underrun_count = 0;
@@ -205,12 +148,24 @@ impl AudioPlayback {
         Ok(Self {
             buffer,
             _stream: Some(stream),
+            resampler: Mutex::new(resampler),
             prebuffer_size,
+            device_sample_rate: sample_rate,
         })
     }
 
+    /// Returns the native sample rate of the output device
+    pub fn device_sample_rate(&self) -> u32 {
+        self.device_sample_rate
+    }
+
     pub fn play_samples(&self, samples: &[i16]) {
-        let samples_f32: Vec<f32> = samples
+        let resampled = self
+            .resampler
+            .lock()
+            .expect("Resampler lock poisoned")
+            .process(samples);
+        let samples_f32: Vec<f32> = resampled
             .iter()
             .map(|&s| s as f32 / i16::MAX as f32)
             .collect();
@@ -0,0 +1,203 @@
+//! Sample rate conversion.
+//!
+//! Converts interleaved PCM between the device rate (e.g. 44.1 kHz) and the
+//! codec rate (48 kHz for Opus) with linear interpolation. The resampler is
+//! streaming: state carries over between calls, so consecutive buffers join
+//! without clicks.
+
+/// Streaming linear-interpolation resampler
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    from_rate: u32,
+    to_rate: u32,
+    channels: usize,
+    /// Input frames advanced per output frame (`from_rate / to_rate`)
+    step: f64,
+    /// Position of the next output frame, in input frames, relative to `previous`
+    position: f64,
+    /// Last input frame of the previous call
+    previous: Vec<f32>,
+}
+
+impl Resampler {
+    /// Creates a resampler
+    ///
+    /// # Arguments
+    /// * `from_rate` - Input sample rate in Hz
+    /// * `to_rate` - Output sample rate in Hz
+    /// * `channels` - Number of interleaved channels
+    pub fn new(from_rate: u32, to_rate: u32, channels: u32) -> Self {
+        let from_rate = from_rate.max(1);
+        let to_rate = to_rate.max(1);
+        let channels = channels.max(1) as usize;
+
+        Self {
+            from_rate,
+            to_rate,
+            channels,
+            step: from_rate as f64 / to_rate as f64,
+            position: 1.0,
+            previous: vec![0.0; channels],
+        }
+    }
+
+    /// Returns the input sample rate
+    pub fn from_rate(&self) -> u32 {
+        self.from_rate
+    }
+
+    /// Returns the output sample rate
+    pub fn to_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    /// Returns true when input and output rates match (samples are copied)
+    pub fn is_passthrough(&self) -> bool {
+        self.from_rate == self.to_rate
+    }
+
+    /// Resamples a buffer of interleaved samples
+    ///
+    /// A trailing partial frame is ignored.
+    ///
+    /// # Arguments
+    /// * `input` - Interleaved samples at `from_rate`
+    ///
+    /// # Returns
+    /// Interleaved samples at `to_rate`; over many calls the output length
+    /// tracks `input.len() * to_rate / from_rate`
+    pub fn process(&mut self, input: &[i16]) -> Vec<i16> {
+        if self.is_passthrough() {
+            return input.to_vec();
+        }
+
+        let channels = self.channels;
+        let frames = input.len() / channels;
+        let estimated = ((frames as f64 / self.step).ceil() as usize + 1) * channels;
+        let mut output = Vec::with_capacity(estimated);
+
+        // Frame 0 is `previous`, frames 1..=frames are `input`
+        let frame_sample = |frame: usize, channel: usize| -> f32 {
+            if frame == 0 {
+                self.previous[channel]
+            } else {
+                input[(frame - 1) * channels + channel] as f32
+            }
+        };
+
+        let mut position = self.position;
+        while (position.floor() as usize) < frames {
+            let index = position.floor() as usize;
+            let fraction = (position - index as f64) as f32;
+
+            for channel in 0..channels {
+                let current = frame_sample(index, channel);
+                let next = frame_sample(index + 1, channel);
+                let sample = current + (next - current) * fraction;
+                output.push(sample.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16);
+            }
+
+            position += self.step;
+        }
+
+        if frames > 0 {
+            let last = &input[(frames - 1) * channels..frames * channels];
+            for (previous, &sample) in self.previous.iter_mut().zip(last) {
+                *previous = sample as f32;
+            }
+        }
+        self.position = position - frames as f64;
+
+        output
+    }
+
+    /// Clears the carried-over state (e.g. after a stream restart)
+    pub fn reset(&mut self) {
+        self.position = 1.0;
+        self.previous.fill(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FREQUENCY: f64 = 440.0;
+
+    fn sine(sample_rate: u32, seconds: f64) -> Vec<i16> {
+        let len = (sample_rate as f64 * seconds) as usize;
+        (0..len)
+            .map(|i| {
+                let t = i as f64 / sample_rate as f64;
+                ((2.0 * std::f64::consts::PI * FREQUENCY * t).sin() * 16000.0) as i16
+            })
+            .collect()
+    }
+
+    /// Estimates the frequency from upward zero crossings
+    fn estimate_frequency(samples: &[i16], sample_rate: u32) -> f64 {
+        let crossings = samples
+            .windows(2)
+            .filter(|pair| pair[0] < 0 && pair[1] >= 0)
+            .count();
+        crossings as f64 * sample_rate as f64 / samples.len() as f64
+    }
+
+    #[test]
+    fn test_upsample_44100_to_48000() {
+        let input = sine(44100, 1.0);
+        let mut resampler = Resampler::new(44100, 48000, 1);
+
+        let output = resampler.process(&input);
+
+        assert!((output.len() as i64 - 48000).abs() <= 2);
+        let frequency = estimate_frequency(&output, 48000);
+        assert!((frequency - FREQUENCY).abs() < FREQUENCY * 0.01);
+    }
+
+    #[test]
+    fn test_round_trip_48000_to_44100() {
+        let input = sine(44100, 1.0);
+        let upsampled = Resampler::new(44100, 48000, 1).process(&input);
+
+        let output = Resampler::new(48000, 44100, 1).process(&upsampled);
+
+        assert!((output.len() as i64 - input.len() as i64).abs() <= 2);
+        let frequency = estimate_frequency(&output, 44100);
+        assert!((frequency - FREQUENCY).abs() < FREQUENCY * 0.01);
+    }
+
+    #[test]
+    fn test_chunked_stream_matches_whole_buffer() {
+        // Stereo: interleaved channels are resampled independently
+        let input: Vec<i16> = sine(44100, 0.5)
+            .into_iter()
+            .flat_map(|sample| [sample, -sample])
+            .collect();
+
+        let whole = Resampler::new(44100, 48000, 2).process(&input);
+
+        let mut resampler = Resampler::new(44100, 48000, 2);
+        let chunked: Vec<i16> = input
+            .chunks(441 * 2) // 10 ms device callbacks
+            .flat_map(|chunk| resampler.process(chunk))
+            .collect();
+
+        assert_eq!(chunked.len(), whole.len());
+        assert!(chunked.iter().zip(&whole).all(|(a, b)| (a - b).abs() <= 1));
+        assert!(
+            chunked
+                .chunks(2)
+                .all(|frame| (frame[0] + frame[1]).abs() <= 1)
+        );
+    }
+
+    #[test]
+    fn test_passthrough_copies_samples() {
+        let input = sine(48000, 0.02);
+        let mut resampler = Resampler::new(48000, 48000, 1);
+
+        assert!(resampler.is_passthrough());
+        assert_eq!(resampler.process(&input), input);
+    }
+}
@@ -17,6 +17,8 @@ pub enum MediaError {
     Io(io::Error),
     /// Camera error
     Camera(String),
+    /// Screen capture error
+    Screen(String),
     /// Audio error
     Audio(String),
     /// Processing error
@@ -25,8 +27,10 @@ pub enum MediaError {
     Logging(String),
     /// OpenCV error
     OpenCv(opencv::Error),
-    /// Codec error
+    /// Codec error (encoding or decoding failed)
     Codec(String),
+    /// Codec not available in this FFmpeg build (e.g. compiled without libx264)
+    CodecUnavailable(String),
     /// Network error
     Network(String),
 }
@@ -37,11 +41,13 @@ impl fmt::Display for MediaError {
             MediaError::Config(msg) => write!(f, "Config error: {}", msg),
             MediaError::Io(err) => write!(f, "I/O error: {}", err),
             MediaError::Camera(msg) => write!(f, "Camera error: {}", msg),
+            MediaError::Screen(msg) => write!(f, "Screen capture error: {}", msg),
             MediaError::Audio(msg) => write!(f, "Audio error: {}", msg),
             MediaError::Processing(msg) => write!(f, "Processing error: {}", msg),
             MediaError::Logging(msg) => write!(f, "Logging error: {}", msg),
             MediaError::OpenCv(err) => write!(f, "OpenCV error: {}", err),
             MediaError::Codec(msg) => write!(f, "Codec error: {}", msg),
+            MediaError::CodecUnavailable(msg) => write!(f, "Codec unavailable: {}", msg),
             MediaError::Network(msg) => write!(f, "{}", msg),
         }
     }
@@ -83,6 +89,12 @@ mod tests {
         assert_eq!(err.to_string(), "Codec error: Encoding failed");
     }
 
+    #[test]
+    fn test_error_display_codec_unavailable() {
+        let err = MediaError::CodecUnavailable("H264 codec not found".to_string());
+        assert_eq!(err.to_string(), "Codec unavailable: H264 codec not found");
+    }
+
     #[test]
     fn test_error_display_processing() {
         let err = MediaError::Processing("Invalid frame".to_string());
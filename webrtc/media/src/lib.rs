@@ -13,15 +13,18 @@ pub use error::MediaError;
 
 // Video exports
 pub use video::{
-    Camera, CameraConfig, CameraInfo, H264Decoder, H264Encoder, VP8Decoder, VP8Encoder,
-    VideoDecoder, VideoEncoder, VideoFrame,
+    Camera, CameraCapability, CameraConfig, CameraInfo, CaptureRegion, H264Decoder, H264Encoder,
+    H264EncoderProfile, H264EncoderSettings, PixelFormat, ScreenCapture, ScreenCaptureConfig,
+    VP8Decoder, VP8Encoder, VP9Decoder, VP9Encoder, VideoCodec, VideoDecoder, VideoEncoder,
+    VideoFrame, VideoSource,
 };
 
 // Audio exports
 pub use audio::{
     Audio, AudioConfig, AudioDecoder, AudioDetection, AudioEncoder, AudioFrame, AudioInfo,
-    AudioSample, OpusDecoder, OpusEncoder,
+    AudioSample, AutomaticGainControl, EchoCanceller, NlmsEchoCanceller, NoiseSuppressor,
+    OpusDecoder, OpusEncoder, Resampler, SpectralNoiseSuppressor,
 };
 
 // Convenience re-exports for backward compatibility
-pub use video::converters::frame_to_rgb;
+pub use video::converters::{convert_frame, frame_to_rgb, rgb_to_frame};
@@ -9,7 +9,7 @@ use opencv::prelude::*;
 use opencv::videoio::{CAP_ANY, VideoCapture};
 use std::time::{Duration, Instant};
 
-use super::info::CameraInfo;
+use super::info::{CameraCapability, CameraInfo, PixelFormat};
 
 /// Camera device detection and enumeration
 pub struct CameraDetection;
@@ -78,6 +78,83 @@ impl CameraDetection {
         Some(CameraInfo::new(device_id, name, width, height))
     }
 
+    /// Probes the resolution, frame rate and pixel format combinations a device supports
+    ///
+    /// OpenCV cannot list a device's modes, so each common mode is requested
+    /// and kept if the device applies it unchanged. The device must not be
+    /// in use by a running camera.
+    ///
+    /// # Arguments
+    /// * `device_id` - Device identifier to probe
+    /// * `logger` - Logger for debug information
+    ///
+    /// # Returns
+    /// One capability per supported resolution; empty if the device cannot
+    /// be opened or accepts none of the probed modes
+    pub fn capabilities(device_id: i32, logger: &Logger) -> Vec<CameraCapability> {
+        const PROBE_FORMATS: [PixelFormat; 3] =
+            [PixelFormat::Mjpeg, PixelFormat::Yuyv, PixelFormat::Nv12];
+        const PROBE_RESOLUTIONS: [(u32, u32); 5] = [
+            (320, 240),
+            (640, 480),
+            (1280, 720),
+            (1920, 1080),
+            (3840, 2160),
+        ];
+
+        if Self::check_device_exists_linux(device_id).is_none() {
+            return Vec::new();
+        }
+        let Ok(mut capture) = VideoCapture::new(device_id, CAP_ANY) else {
+            return Vec::new();
+        };
+        if !capture.is_opened().unwrap_or(false) {
+            return Vec::new();
+        }
+
+        let mut modes = Vec::new();
+        for format in PROBE_FORMATS {
+            let fourcc = i32::from_le_bytes(format.fourcc()) as f64;
+            if !Self::apply_property(&mut capture, opencv::videoio::CAP_PROP_FOURCC, fourcc) {
+                continue;
+            }
+
+            for (width, height) in PROBE_RESOLUTIONS {
+                if !Self::apply_property(
+                    &mut capture,
+                    opencv::videoio::CAP_PROP_FRAME_WIDTH,
+                    width as f64,
+                ) || !Self::apply_property(
+                    &mut capture,
+                    opencv::videoio::CAP_PROP_FRAME_HEIGHT,
+                    height as f64,
+                ) {
+                    continue;
+                }
+
+                for fps in CameraInfo::COMMON_FPS {
+                    if Self::apply_property(&mut capture, opencv::videoio::CAP_PROP_FPS, fps) {
+                        modes.push((format, width, height, fps));
+                    }
+                }
+            }
+        }
+        let _ = capture.release();
+
+        logger.debug(&format!(
+            "Device {} accepted {} of the probed modes",
+            device_id,
+            modes.len()
+        ));
+        CameraCapability::from_modes(&modes)
+    }
+
+    /// Sets a camera property and checks the device applied it unchanged
+    fn apply_property(capture: &mut VideoCapture, prop: i32, value: f64) -> bool {
+        capture.set(prop, value).unwrap_or(false)
+            && Self::get_property(capture, prop).is_some_and(|actual| (actual - value).abs() < 0.1)
+    }
+
     /// Checks if device exists on Linux systems
     #[cfg(target_os = "linux")]
     fn check_device_exists_linux(device_id: i32) -> Option<()> {
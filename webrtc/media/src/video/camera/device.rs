@@ -6,12 +6,14 @@
 use crate::common::constants::logging::CAMERA_LOG_INTERVAL;
 use crate::error::{MediaError, Result};
 use crate::video::frame::VideoFrame;
+use crate::video::traits::VideoSource;
 use logging::Logger;
 use opencv::prelude::*;
 use opencv::videoio::{CAP_ANY, VideoCapture};
 
 use super::config::CameraConfig;
 use super::detection::CameraDetection;
+use super::info::PixelFormat;
 
 /// Video capture device
 ///
@@ -172,6 +174,17 @@ impl Camera {
         self.actual_fps
     }
 
+    /// Returns the pixel format the device delivers frames in
+    ///
+    /// This is the source format to pass to
+    /// [`convert_frame`](crate::video::converters::convert_frame) for raw
+    /// frames. `None` if the backend does not report it or the format is
+    /// not one of [`PixelFormat`].
+    pub fn pixel_format(&self) -> Option<PixelFormat> {
+        let fourcc = Self::get_property(&self.capture, opencv::videoio::CAP_PROP_FOURCC).ok()?;
+        PixelFormat::from_fourcc((fourcc as u32).to_le_bytes())
+    }
+
     /// Configures camera capture parameters
     ///
     /// Sets resolution, framerate, and verifies actual values.
@@ -251,6 +264,20 @@ impl Camera {
     }
 }
 
+impl VideoSource for Camera {
+    fn capture_frame(&mut self) -> Result<VideoFrame> {
+        Camera::capture_frame(self)
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        self.actual_resolution()
+    }
+
+    fn fps(&self) -> f64 {
+        self.actual_fps
+    }
+}
+
 impl Drop for Camera {
     /// Releases camera resources when dropped
     ///
@@ -20,7 +20,7 @@ pub struct CameraInfo {
 
 impl CameraInfo {
     /// Common frame rates supported by most cameras
-    const COMMON_FPS: [f64; 4] = [15.0, 24.0, 30.0, 60.0];
+    pub(crate) const COMMON_FPS: [f64; 4] = [15.0, 24.0, 30.0, 60.0];
 
     /// Creates camera info with detected capabilities
     ///
@@ -50,6 +50,129 @@ impl CameraInfo {
     }
 }
 
+/// Pixel layout of the frames a camera delivers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PixelFormat {
+    /// Packed 4:2:2, Y0 U Y1 V
+    Yuyv,
+    /// 4:2:0, Y plane followed by interleaved UV
+    Nv12,
+    /// Motion JPEG, one JPEG image per frame
+    Mjpeg,
+    /// Planar 4:2:0, Y then U then V planes
+    I420,
+    /// Packed 8-bit B, G, R
+    Bgr,
+}
+
+impl PixelFormat {
+    /// Returns the FOURCC code of the format (e.g. "YUYV")
+    pub fn fourcc(&self) -> [u8; 4] {
+        match self {
+            PixelFormat::Yuyv => *b"YUYV",
+            PixelFormat::Nv12 => *b"NV12",
+            PixelFormat::Mjpeg => *b"MJPG",
+            PixelFormat::I420 => *b"I420",
+            PixelFormat::Bgr => *b"BGR3",
+        }
+    }
+
+    /// Looks up a format by its FOURCC code
+    ///
+    /// Accepts the common aliases `YUY2` (YUYV) and `YU12` (I420).
+    pub fn from_fourcc(fourcc: [u8; 4]) -> Option<Self> {
+        match &fourcc {
+            b"YUYV" | b"YUY2" => Some(PixelFormat::Yuyv),
+            b"NV12" => Some(PixelFormat::Nv12),
+            b"MJPG" => Some(PixelFormat::Mjpeg),
+            b"I420" | b"YU12" => Some(PixelFormat::I420),
+            b"BGR3" => Some(PixelFormat::Bgr),
+            _ => None,
+        }
+    }
+}
+
+/// Frame rates and pixel formats a camera supports at one resolution
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraCapability {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Supported frame rates, ascending
+    pub frame_rates: Vec<f64>,
+    /// Supported pixel formats, in the order they were reported
+    pub pixel_formats: Vec<PixelFormat>,
+}
+
+impl CameraCapability {
+    /// Resolutions assumed for devices that report no modes
+    const DEFAULT_RESOLUTIONS: [(u32, u32); 2] = [(640, 480), (1280, 720)];
+
+    /// Capabilities assumed for a device that reports no modes
+    ///
+    /// 640x480 and 1280x720 at the common frame rates, in YUYV, which
+    /// nearly every UVC camera supports.
+    pub fn defaults() -> Vec<Self> {
+        Self::DEFAULT_RESOLUTIONS
+            .iter()
+            .map(|&(width, height)| Self {
+                width,
+                height,
+                frame_rates: CameraInfo::COMMON_FPS.to_vec(),
+                pixel_formats: vec![PixelFormat::Yuyv],
+            })
+            .collect()
+    }
+
+    /// Groups individual modes into one capability per resolution
+    ///
+    /// # Arguments
+    /// * `modes` - Supported `(format, width, height, fps)` combinations
+    ///
+    /// # Returns
+    /// Capabilities sorted by ascending resolution
+    pub fn from_modes(modes: &[(PixelFormat, u32, u32, f64)]) -> Vec<Self> {
+        let mut capabilities: Vec<Self> = Vec::new();
+
+        for &(format, width, height, fps) in modes {
+            let index = match capabilities
+                .iter()
+                .position(|c| c.width == width && c.height == height)
+            {
+                Some(index) => index,
+                None => {
+                    capabilities.push(Self {
+                        width,
+                        height,
+                        frame_rates: Vec::new(),
+                        pixel_formats: Vec::new(),
+                    });
+                    capabilities.len() - 1
+                }
+            };
+            let capability = &mut capabilities[index];
+            if !capability.supports_fps(fps) {
+                capability.frame_rates.push(fps);
+            }
+            if !capability.pixel_formats.contains(&format) {
+                capability.pixel_formats.push(format);
+            }
+        }
+
+        for capability in &mut capabilities {
+            capability.frame_rates.sort_by(|a, b| a.total_cmp(b));
+        }
+        capabilities.sort_by_key(|c| (c.width * c.height, c.width));
+        capabilities
+    }
+
+    /// Checks if the frame rate is supported at this resolution
+    pub fn supports_fps(&self, fps: f64) -> bool {
+        self.frame_rates.iter().any(|&f| (f - fps).abs() < 0.1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,4 +232,42 @@ mod tests {
         assert_eq!(info1.max_width, info2.max_width);
         assert_eq!(info1.max_height, info2.max_height);
     }
+
+    #[test]
+    fn test_capabilities_from_modes() {
+        let modes = [
+            (PixelFormat::Mjpeg, 1280, 720, 30.0),
+            (PixelFormat::Yuyv, 640, 480, 30.0),
+            (PixelFormat::Yuyv, 1280, 720, 10.0),
+            (PixelFormat::Mjpeg, 640, 480, 30.0),
+            (PixelFormat::Yuyv, 640, 480, 15.0),
+        ];
+
+        let capabilities = CameraCapability::from_modes(&modes);
+
+        assert_eq!(capabilities.len(), 2);
+        assert_eq!((capabilities[0].width, capabilities[0].height), (640, 480));
+        assert_eq!(capabilities[0].frame_rates, vec![15.0, 30.0]);
+        assert_eq!(
+            capabilities[0].pixel_formats,
+            vec![PixelFormat::Yuyv, PixelFormat::Mjpeg]
+        );
+        assert_eq!(capabilities[1].frame_rates, vec![10.0, 30.0]);
+        assert!(CameraCapability::from_modes(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_pixel_format_fourcc_round_trip() {
+        for format in [
+            PixelFormat::Yuyv,
+            PixelFormat::Nv12,
+            PixelFormat::Mjpeg,
+            PixelFormat::I420,
+            PixelFormat::Bgr,
+        ] {
+            assert_eq!(PixelFormat::from_fourcc(format.fourcc()), Some(format));
+        }
+        assert_eq!(PixelFormat::from_fourcc(*b"YUY2"), Some(PixelFormat::Yuyv));
+        assert_eq!(PixelFormat::from_fourcc(*b"H264"), None);
+    }
 }
@@ -11,5 +11,5 @@ pub mod pool;
 pub use config::CameraConfig;
 pub use detection::CameraDetection;
 pub use device::Camera;
-pub use info::CameraInfo;
+pub use info::{CameraCapability, CameraInfo, PixelFormat};
 pub use pool::{CameraJob, CameraThreadPool, EncodedFrame};
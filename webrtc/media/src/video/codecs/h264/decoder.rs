@@ -42,7 +42,7 @@ impl H264Decoder {
         let codec = ffmpeg::decoder::find_by_name("h264")
             .or_else(|| ffmpeg::decoder::find(ffmpeg::codec::Id::H264))
             .or_else(|| ffmpeg::decoder::find_by_name("libx264"))
-            .ok_or_else(|| MediaError::Codec("H264 decoder not found".to_string()))?;
+            .ok_or_else(|| MediaError::CodecUnavailable("H264 decoder not found".to_string()))?;
 
         let ctx = ffmpeg::codec::context::Context::new_with_codec(codec);
 
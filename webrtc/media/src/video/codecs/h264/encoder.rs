@@ -12,6 +12,37 @@ use crate::video::utils::{extract_nal_type, is_parameter_set, mat_to_yuv_frame};
 use ffmpeg_next as ffmpeg;
 use logging::Logger;
 
+/// H.264 profile produced by the encoder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum H264EncoderProfile {
+    /// Decodable by every H.264 decoder, the WebRTC baseline
+    #[default]
+    ConstrainedBaseline,
+    Main,
+    High,
+}
+
+impl H264EncoderProfile {
+    /// Name of the profile in libx264 options
+    fn x264_name(&self) -> &'static str {
+        match self {
+            // libx264's "baseline" is Constrained Baseline
+            H264EncoderProfile::ConstrainedBaseline => "baseline",
+            H264EncoderProfile::Main => "main",
+            H264EncoderProfile::High => "high",
+        }
+    }
+}
+
+/// Stream options negotiated with the remote peer (SDP fmtp)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct H264EncoderSettings {
+    /// Profile to encode with
+    pub profile: H264EncoderProfile,
+    /// Largest NAL unit in bytes, for single NAL unit packetization
+    pub max_nal_size: Option<usize>,
+}
+
 /// Represents an H.264 video encoder using FFmpeg.
 ///
 /// Handles encoding of raw video frames into H.264 NAL units.
@@ -24,6 +55,7 @@ pub struct H264Encoder {
     bitrate: u32,
     sps: Option<Vec<u8>>,
     pps: Option<Vec<u8>>,
+    keyframe_requested: bool,
 }
 
 impl H264Encoder {
@@ -49,16 +81,42 @@ impl H264Encoder {
         keyframe_interval: u32,
         fps: f64,
         logger: Logger,
+    ) -> Result<Self> {
+        Self::with_settings(
+            width,
+            height,
+            bitrate,
+            keyframe_interval,
+            fps,
+            H264EncoderSettings::default(),
+            logger,
+        )
+    }
+
+    /// Creates a new H.264 encoder with negotiated stream options
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - Profile and NAL unit size limit
+    /// * Other arguments as in [`H264Encoder::new`]
+    pub fn with_settings(
+        width: u32,
+        height: u32,
+        bitrate: u32,
+        keyframe_interval: u32,
+        fps: f64,
+        settings: H264EncoderSettings,
+        logger: Logger,
     ) -> Result<Self> {
         logger.info(&format!(
-            "Initializing H264 encoder: {}x{}, bitrate={}, fps={}, gop={}",
-            width, height, bitrate, fps, keyframe_interval
+            "Initializing H264 encoder: {}x{}, bitrate={}, fps={}, gop={}, {:?}",
+            width, height, bitrate, fps, keyframe_interval, settings
         ));
 
         ffmpeg::init().map_err(|e| MediaError::Codec(format!("Error init ffmpeg: {}", e)))?;
 
         let codec = ffmpeg::encoder::find(ffmpeg::codec::Id::H264)
-            .ok_or_else(|| MediaError::Codec("H264 codec not found".to_string()))?
+            .ok_or_else(|| MediaError::CodecUnavailable("H264 codec not found".to_string()))?
             .video()
             .map_err(|e| MediaError::Codec(format!("Not a video codec: {}", e)))?;
 
@@ -94,7 +152,17 @@ impl H264Encoder {
         opts.set("rc-lookahead", "0");
         opts.set("sliced-threads", "0");
 
-        opts.set("x264-params", "nal-hrd=cbr:force-cfr=1");
+        opts.set("profile", settings.profile.x264_name());
+
+        // Single NAL unit packetization cannot fragment, so slices must fit a packet
+        let mut x264_params = "nal-hrd=cbr:force-cfr=1".to_string();
+        if let Some(max_nal_size) = settings.max_nal_size {
+            x264_params.push_str(&format!(":slice-max-size={}", max_nal_size));
+        }
+        opts.set("x264-params", &x264_params);
+
+        // Forced I-frames (keyframe requests) must be IDR so decoders can resync
+        opts.set("forced-idr", "1");
 
         let encoder = encoder
             .open_with(opts)
@@ -108,6 +176,7 @@ impl H264Encoder {
             bitrate,
             sps: None,
             pps: None,
+            keyframe_requested: false,
         })
     }
 
@@ -126,6 +195,12 @@ impl H264Encoder {
         yuv_frame.set_pts(Some(self.pts));
         self.pts += 1;
 
+        if self.keyframe_requested {
+            self.logger.info("Forcing IDR keyframe");
+            yuv_frame.set_kind(ffmpeg::picture::Type::I);
+            self.keyframe_requested = false;
+        }
+
         self.encoder
             .send_frame(&yuv_frame)
             .map_err(|e| MediaError::Codec(format!("Error sending frame: {}", e)))?;
@@ -185,6 +260,29 @@ impl H264Encoder {
         self.pps.as_ref()
     }
 
+    /// Forces the next encoded frame to be an IDR keyframe
+    ///
+    /// Used when the remote decoder reports picture loss (RTCP PLI/FIR).
+    pub fn request_keyframe(&mut self) {
+        self.keyframe_requested = true;
+    }
+
+    /// Changes the target bitrate in bits per second
+    ///
+    /// libx264 reconfigures its rate control on the next encoded frame,
+    /// so the stream continues without a new keyframe.
+    pub fn set_bitrate(&mut self, bitrate: u32) {
+        if bitrate == self.bitrate {
+            return;
+        }
+        self.logger.info(&format!(
+            "Changing H264 bitrate: {} -> {}",
+            self.bitrate, bitrate
+        ));
+        self.encoder.set_bit_rate(bitrate as usize);
+        self.bitrate = bitrate;
+    }
+
     /// Returns true if both SPS and PPS have been cached
     pub fn has_parameter_sets(&self) -> bool {
         self.sps.is_some() && self.pps.is_some()
@@ -206,9 +304,13 @@ impl VideoEncoder for H264Encoder {
         self.bitrate
     }
 
+    fn set_bitrate(&mut self, bitrate: u32) {
+        self.set_bitrate(bitrate);
+    }
+
     fn request_keyframe(&mut self) {
-        // Force keyframe on next encode by resetting PTS
         self.logger.debug("Keyframe requested");
+        self.request_keyframe();
     }
 }
 
@@ -257,4 +359,35 @@ mod tests {
         let result = VideoEncoder::encode(&mut encoder, &frame);
         let _ = result;
     }
+
+    #[test]
+    fn test_set_bitrate_updates_target() {
+        let logger = create_test_logger();
+        let mut encoder = H264Encoder::new(640, 480, 500_000, 60, 30.0, logger).unwrap();
+        let frame = create_test_frame();
+
+        encoder.set_bitrate(250_000);
+        assert_eq!(encoder.get_bitrate(), 250_000);
+        assert!(encoder.encode(&frame).is_ok());
+    }
+
+    #[test]
+    fn test_request_keyframe_forces_idr() {
+        let logger = create_test_logger();
+        let mut encoder = H264Encoder::new(640, 480, 500_000, 300, 30.0, logger).unwrap();
+        let frame = create_test_frame();
+
+        // Consume the initial IDR
+        for _ in 0..3 {
+            encoder.encode(&frame).unwrap();
+        }
+
+        encoder.request_keyframe();
+        let packets = encoder.encode(&frame).unwrap();
+
+        assert!(packets.iter().any(|p| {
+            p.windows(5)
+                .any(|w| w[..4] == NAL_START_CODE_4 && w[4] & NAL_TYPE_MASK == NAL_TYPE_IDR)
+        }));
+    }
 }
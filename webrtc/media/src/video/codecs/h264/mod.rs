@@ -7,4 +7,4 @@ pub mod decoder;
 pub mod encoder;
 
 pub use decoder::H264Decoder;
-pub use encoder::H264Encoder;
+pub use encoder::{H264Encoder, H264EncoderProfile, H264EncoderSettings};
@@ -4,6 +4,129 @@
 
 pub mod h264;
 pub mod vp8;
+pub mod vp9;
 
-pub use h264::{H264Decoder, H264Encoder};
+pub use h264::{H264Decoder, H264Encoder, H264EncoderProfile, H264EncoderSettings};
 pub use vp8::{VP8Decoder, VP8Encoder};
+pub use vp9::{VP9Decoder, VP9Encoder};
+
+use super::traits::{VideoDecoder, VideoEncoder};
+use crate::error::Result;
+use ffmpeg_next as ffmpeg;
+use logging::Logger;
+
+/// Video codecs that can be negotiated through SDP `a=rtpmap` lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoCodec {
+    H264,
+    VP8,
+    VP9,
+}
+
+impl VideoCodec {
+    /// Looks up a codec by its rtpmap encoding name (case-insensitive)
+    pub fn from_encoding_name(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "H264" => Some(VideoCodec::H264),
+            "VP8" => Some(VideoCodec::VP8),
+            "VP9" => Some(VideoCodec::VP9),
+            _ => None,
+        }
+    }
+
+    /// Returns the rtpmap encoding name (e.g. "VP9" in `VP9/90000`)
+    pub fn encoding_name(&self) -> &'static str {
+        match self {
+            VideoCodec::H264 => "H264",
+            VideoCodec::VP8 => "VP8",
+            VideoCodec::VP9 => "VP9",
+        }
+    }
+
+    /// All codecs, in the order they are preferred
+    pub const ALL: [VideoCodec; 3] = [VideoCodec::H264, VideoCodec::VP8, VideoCodec::VP9];
+
+    /// Checks at runtime whether FFmpeg has an encoder and a decoder for this codec
+    ///
+    /// FFmpeg builds without libx264 or libvpx lack the matching codecs, and
+    /// creating them fails with `MediaError::CodecUnavailable`.
+    pub fn is_available(&self) -> bool {
+        let id = match self {
+            VideoCodec::H264 => ffmpeg::codec::Id::H264,
+            VideoCodec::VP8 => ffmpeg::codec::Id::VP8,
+            VideoCodec::VP9 => ffmpeg::codec::Id::VP9,
+        };
+        ffmpeg::init().is_ok()
+            && ffmpeg::encoder::find(id).is_some()
+            && ffmpeg::decoder::find(id).is_some()
+    }
+
+    /// Returns the codecs usable on this system, in preference order
+    pub fn available() -> Vec<VideoCodec> {
+        Self::ALL
+            .into_iter()
+            .filter(VideoCodec::is_available)
+            .collect()
+    }
+
+    /// Creates an encoder for this codec
+    pub fn create_encoder(
+        &self,
+        width: u32,
+        height: u32,
+        bitrate: u32,
+        fps: f64,
+        keyframe_interval: u32,
+        logger: Logger,
+    ) -> Result<Box<dyn VideoEncoder + Send>> {
+        Ok(match self {
+            VideoCodec::H264 => Box::new(H264Encoder::new(
+                width,
+                height,
+                bitrate,
+                keyframe_interval,
+                fps,
+                logger,
+            )?),
+            VideoCodec::VP8 => Box::new(VP8Encoder::new(
+                width,
+                height,
+                bitrate,
+                keyframe_interval,
+                logger,
+            )?),
+            VideoCodec::VP9 => Box::new(VP9Encoder::new(
+                width,
+                height,
+                bitrate,
+                keyframe_interval,
+                logger,
+            )?),
+        })
+    }
+
+    /// Creates a decoder for this codec
+    pub fn create_decoder(&self, logger: Logger) -> Result<Box<dyn VideoDecoder + Send>> {
+        Ok(match self {
+            VideoCodec::H264 => Box::new(H264Decoder::new(logger)?),
+            VideoCodec::VP8 => Box::new(VP8Decoder::new(logger)?),
+            VideoCodec::VP9 => Box::new(VP9Decoder::new(logger)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codec_from_encoding_name() {
+        assert_eq!(VideoCodec::from_encoding_name("vp9"), Some(VideoCodec::VP9));
+        assert_eq!(
+            VideoCodec::from_encoding_name("H264"),
+            Some(VideoCodec::H264)
+        );
+        assert_eq!(VideoCodec::from_encoding_name("AV1"), None);
+        assert_eq!(VideoCodec::VP8.encoding_name(), "VP8");
+    }
+}
@@ -41,7 +41,7 @@ impl VP8Decoder {
         let codec = ffmpeg::decoder::find_by_name("vp8")
             .or_else(|| ffmpeg::decoder::find(ffmpeg::codec::Id::VP8))
             .or_else(|| ffmpeg::decoder::find_by_name("libvpx"))
-            .ok_or_else(|| MediaError::Codec("VP8 decoder not found".to_string()))?;
+            .ok_or_else(|| MediaError::CodecUnavailable("VP8 decoder not found".to_string()))?;
 
         let ctx = ffmpeg::codec::context::Context::new_with_codec(codec);
 
@@ -52,7 +52,7 @@ impl VP8Encoder {
         ffmpeg::init().map_err(|e| MediaError::Codec(format!("Error init ffmpeg: {}", e)))?;
 
         let codec = ffmpeg::encoder::find(ffmpeg::codec::Id::VP8)
-            .ok_or_else(|| MediaError::Codec("VP8 codec not found".to_string()))?
+            .ok_or_else(|| MediaError::CodecUnavailable("VP8 codec not found".to_string()))?
             .video()
             .map_err(|e| MediaError::Codec(format!("Not a video codec: {}", e)))?;
 
@@ -0,0 +1,11 @@
+//! VP9 codec implementation
+//!
+//! This module provides VP9 video encoding and decoding functionality
+//! using FFmpeg's libvpx-vp9.
+//!
+
+pub mod decoder;
+pub mod encoder;
+
+pub use decoder::VP9Decoder;
+pub use encoder::VP9Encoder;
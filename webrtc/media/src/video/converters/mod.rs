@@ -2,6 +2,8 @@
 //!
 //! Utilities for converting between different pixel formats
 
+pub mod pixel_format;
 pub mod rgb_converter;
 
-pub use rgb_converter::frame_to_rgb;
+pub use pixel_format::{convert_frame, i420_size};
+pub use rgb_converter::{frame_to_rgb, rgb_to_frame};
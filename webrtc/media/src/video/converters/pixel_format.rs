@@ -0,0 +1,340 @@
+//! Camera pixel format conversion
+//!
+//! Cameras commonly deliver YUYV, NV12 or MJPEG frames while the encoders
+//! take planar I420. Converting directly keeps the chroma the camera
+//! captured instead of bouncing through RGB.
+
+use crate::error::{MediaError, Result};
+use crate::video::camera::PixelFormat;
+use ffmpeg_next as ffmpeg;
+
+/// Converts a raw frame between pixel formats
+///
+/// Supported conversions are YUYV, NV12, MJPEG and I420 to I420.
+///
+/// # Arguments
+/// * `src_format` - Format of `data`, as reported by the camera
+/// * `dst_format` - Format to convert to
+/// * `data` - Frame data, tightly packed (no row padding)
+/// * `width` - Frame width in pixels
+/// * `height` - Frame height in pixels
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - Frame in `dst_format`; I420 chroma planes are
+///   `ceil(width / 2) x ceil(height / 2)`
+/// * `Err(MediaError::Processing)` - If the conversion is not supported or
+///   `data` does not match the dimensions
+/// * `Err(MediaError::Codec)` - If an MJPEG frame cannot be decoded
+pub fn convert_frame(
+    src_format: PixelFormat,
+    dst_format: PixelFormat,
+    data: &[u8],
+    width: usize,
+    height: usize,
+) -> Result<Vec<u8>> {
+    if width == 0 || height == 0 {
+        return Err(MediaError::Processing(format!(
+            "Invalid frame size {}x{}",
+            width, height
+        )));
+    }
+
+    match (src_format, dst_format) {
+        (PixelFormat::Yuyv, PixelFormat::I420) => yuyv_to_i420(data, width, height),
+        (PixelFormat::Nv12, PixelFormat::I420) => nv12_to_i420(data, width, height),
+        (PixelFormat::Mjpeg, PixelFormat::I420) => mjpeg_to_i420(data, width, height),
+        (PixelFormat::I420, PixelFormat::I420) => {
+            check_size(PixelFormat::I420, data, i420_size(width, height))?;
+            Ok(data.to_vec())
+        }
+        _ => Err(MediaError::Processing(format!(
+            "Unsupported pixel format conversion {:?} -> {:?}",
+            src_format, dst_format
+        ))),
+    }
+}
+
+/// Size in bytes of an I420 frame
+pub fn i420_size(width: usize, height: usize) -> usize {
+    let (chroma_width, chroma_height) = chroma_size(width, height);
+    width * height + 2 * chroma_width * chroma_height
+}
+
+/// Size of the 4:2:0 chroma planes, rounded up for odd dimensions
+fn chroma_size(width: usize, height: usize) -> (usize, usize) {
+    (width.div_ceil(2), height.div_ceil(2))
+}
+
+/// Checks a source buffer has the size its format and dimensions imply
+fn check_size(format: PixelFormat, data: &[u8], expected: usize) -> Result<()> {
+    if data.len() != expected {
+        return Err(MediaError::Processing(format!(
+            "{:?} frame needs {} bytes, got {}",
+            format,
+            expected,
+            data.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Converts packed YUYV (4:2:2) to I420
+///
+/// Each pair of pixels shares one U and one V sample; vertical chroma is
+/// halved by averaging the samples of two consecutive rows.
+fn yuyv_to_i420(data: &[u8], width: usize, height: usize) -> Result<Vec<u8>> {
+    let (chroma_width, chroma_height) = chroma_size(width, height);
+    let stride = chroma_width * 4;
+    check_size(PixelFormat::Yuyv, data, stride * height)?;
+
+    let mut output = Vec::with_capacity(i420_size(width, height));
+    for row in data.chunks_exact(stride) {
+        output.extend((0..width).map(|x| row[(x / 2) * 4 + (x % 2) * 2]));
+    }
+
+    for offset in [1, 3] {
+        for chroma_y in 0..chroma_height {
+            let top = &data[2 * chroma_y * stride..][..stride];
+            let bottom = &data[(2 * chroma_y + 1).min(height - 1) * stride..][..stride];
+            output.extend((0..chroma_width).map(|chroma_x| {
+                let index = chroma_x * 4 + offset;
+                ((top[index] as u16 + bottom[index] as u16 + 1) / 2) as u8
+            }));
+        }
+    }
+
+    Ok(output)
+}
+
+/// Converts NV12 (Y plane, then interleaved UV) to I420
+fn nv12_to_i420(data: &[u8], width: usize, height: usize) -> Result<Vec<u8>> {
+    let (chroma_width, chroma_height) = chroma_size(width, height);
+    let luma_size = width * height;
+    check_size(
+        PixelFormat::Nv12,
+        data,
+        luma_size + 2 * chroma_width * chroma_height,
+    )?;
+
+    let (luma, chroma) = data.split_at(luma_size);
+    let mut output = Vec::with_capacity(i420_size(width, height));
+    output.extend_from_slice(luma);
+    output.extend(chroma.iter().step_by(2));
+    output.extend(chroma.iter().skip(1).step_by(2));
+
+    Ok(output)
+}
+
+/// Decodes an MJPEG frame and converts it to I420
+///
+/// JPEG images are usually full range 4:2:2 or 4:2:0; FFmpeg's scaler
+/// resamples the chroma and maps the range to the limited range the
+/// encoders expect.
+fn mjpeg_to_i420(data: &[u8], width: usize, height: usize) -> Result<Vec<u8>> {
+    ffmpeg::init().map_err(|e| MediaError::Codec(format!("Error init ffmpeg: {}", e)))?;
+
+    let codec = ffmpeg::decoder::find(ffmpeg::codec::Id::MJPEG)
+        .ok_or_else(|| MediaError::CodecUnavailable("MJPEG decoder not found".to_string()))?;
+    let mut decoder = ffmpeg::codec::context::Context::new_with_codec(codec)
+        .decoder()
+        .video()
+        .map_err(|e| MediaError::Codec(format!("Error creating MJPEG decoder: {}", e)))?;
+
+    decoder
+        .send_packet(&ffmpeg::Packet::copy(data))
+        .map_err(|e| MediaError::Codec(format!("Error decoding MJPEG frame: {}", e)))?;
+    let mut decoded = ffmpeg::frame::Video::empty();
+    decoder
+        .receive_frame(&mut decoded)
+        .map_err(|e| MediaError::Codec(format!("Error decoding MJPEG frame: {}", e)))?;
+
+    if decoded.width() as usize != width || decoded.height() as usize != height {
+        return Err(MediaError::Processing(format!(
+            "MJPEG frame is {}x{}, expected {}x{}",
+            decoded.width(),
+            decoded.height(),
+            width,
+            height
+        )));
+    }
+
+    let mut scaler = ffmpeg::software::scaling::Context::get(
+        decoded.format(),
+        decoded.width(),
+        decoded.height(),
+        ffmpeg::format::Pixel::YUV420P,
+        decoded.width(),
+        decoded.height(),
+        ffmpeg::software::scaling::Flags::BILINEAR,
+    )
+    .map_err(|e| MediaError::Codec(format!("Error creating scaler: {}", e)))?;
+    let mut i420 = ffmpeg::frame::Video::new(
+        ffmpeg::format::Pixel::YUV420P,
+        decoded.width(),
+        decoded.height(),
+    );
+    scaler
+        .run(&decoded, &mut i420)
+        .map_err(|e| MediaError::Codec(format!("Error scaling frame: {}", e)))?;
+
+    // FFmpeg pads rows to its alignment; copy each plane row by row
+    let (chroma_width, chroma_height) = chroma_size(width, height);
+    let mut output = Vec::with_capacity(i420_size(width, height));
+    for (plane, plane_width, plane_height) in [
+        (0, width, height),
+        (1, chroma_width, chroma_height),
+        (2, chroma_width, chroma_height),
+    ] {
+        let stride = i420.stride(plane);
+        let plane_data = i420.data(plane);
+        for y in 0..plane_height {
+            output.extend_from_slice(&plane_data[y * stride..][..plane_width]);
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 16;
+    const HEIGHT: usize = 8;
+
+    /// Test pattern: luma ramps with x, chroma depends on the half of the frame
+    fn pixel(x: usize, y: usize) -> (u8, u8, u8) {
+        let luma = (16 + x * 10) as u8;
+        if y < HEIGHT / 2 {
+            (luma, 90, 240)
+        } else {
+            (luma, 160, 40)
+        }
+    }
+
+    /// Checks dimensions and spot-checks the pixel at (x, y)
+    fn assert_i420_pixel(i420: &[u8], x: usize, y: usize) {
+        let (chroma_width, chroma_height) = chroma_size(WIDTH, HEIGHT);
+        let chroma_plane = chroma_width * chroma_height;
+        let chroma_index = (y / 2) * chroma_width + x / 2;
+        let (luma, u, v) = pixel(x, y);
+
+        assert_eq!(i420.len(), i420_size(WIDTH, HEIGHT));
+        assert_eq!(i420[y * WIDTH + x], luma);
+        assert_eq!(i420[WIDTH * HEIGHT + chroma_index], u);
+        assert_eq!(i420[WIDTH * HEIGHT + chroma_plane + chroma_index], v);
+    }
+
+    #[test]
+    fn test_yuyv_to_i420() {
+        let mut yuyv = Vec::new();
+        for y in 0..HEIGHT {
+            for x in (0..WIDTH).step_by(2) {
+                let (y0, u, v) = pixel(x, y);
+                let (y1, _, _) = pixel(x + 1, y);
+                yuyv.extend_from_slice(&[y0, u, y1, v]);
+            }
+        }
+
+        let i420 =
+            convert_frame(PixelFormat::Yuyv, PixelFormat::I420, &yuyv, WIDTH, HEIGHT).unwrap();
+
+        assert_i420_pixel(&i420, 5, 1);
+        assert_i420_pixel(&i420, 10, 6);
+    }
+
+    #[test]
+    fn test_nv12_to_i420() {
+        let mut nv12: Vec<u8> = (0..HEIGHT)
+            .flat_map(|y| (0..WIDTH).map(move |x| pixel(x, y).0))
+            .collect();
+        for y in (0..HEIGHT).step_by(2) {
+            for x in (0..WIDTH).step_by(2) {
+                let (_, u, v) = pixel(x, y);
+                nv12.extend_from_slice(&[u, v]);
+            }
+        }
+
+        let i420 =
+            convert_frame(PixelFormat::Nv12, PixelFormat::I420, &nv12, WIDTH, HEIGHT).unwrap();
+
+        assert_i420_pixel(&i420, 5, 1);
+        assert_i420_pixel(&i420, 10, 6);
+    }
+
+    #[test]
+    fn test_mjpeg_to_i420() {
+        // Encode a flat gray frame; JPEG is lossy, so compare with a tolerance
+        ffmpeg::init().unwrap();
+        let codec = ffmpeg::encoder::find(ffmpeg::codec::Id::MJPEG).unwrap();
+        let mut encoder = ffmpeg::codec::context::Context::new_with_codec(codec)
+            .encoder()
+            .video()
+            .unwrap();
+        encoder.set_width(WIDTH as u32);
+        encoder.set_height(HEIGHT as u32);
+        encoder.set_format(ffmpeg::format::Pixel::YUVJ420P);
+        encoder.set_time_base((1, 30));
+        let mut encoder = encoder.open_as(codec).unwrap();
+
+        let mut frame =
+            ffmpeg::frame::Video::new(ffmpeg::format::Pixel::YUVJ420P, WIDTH as u32, HEIGHT as u32);
+        for plane in 0..3 {
+            let value = if plane == 0 { 200 } else { 128 };
+            frame.data_mut(plane).fill(value);
+        }
+        frame.set_pts(Some(0));
+        encoder.send_frame(&frame).unwrap();
+        encoder.send_eof().unwrap();
+        let mut packet = ffmpeg::Packet::empty();
+        encoder.receive_packet(&mut packet).unwrap();
+        let jpeg = packet.data().unwrap().to_vec();
+
+        let i420 =
+            convert_frame(PixelFormat::Mjpeg, PixelFormat::I420, &jpeg, WIDTH, HEIGHT).unwrap();
+
+        assert_eq!(i420.len(), i420_size(WIDTH, HEIGHT));
+        // Full range 200 is 188 in limited range: 16 + 200 * 219 / 255
+        let luma = i420[3 * WIDTH + 7];
+        assert!(luma.abs_diff(188) <= 3, "luma {}", luma);
+        assert!(i420[WIDTH * HEIGHT].abs_diff(128) <= 3);
+    }
+
+    #[test]
+    fn test_odd_dimensions_round_chroma_up() {
+        let (width, height) = (3, 3);
+        let mut nv12 = vec![100; width * height];
+        nv12.extend_from_slice(&[50, 200, 50, 200, 50, 200, 50, 200]);
+
+        let i420 =
+            convert_frame(PixelFormat::Nv12, PixelFormat::I420, &nv12, width, height).unwrap();
+
+        assert_eq!(i420.len(), 9 + 4 + 4);
+        assert_eq!(&i420[9..13], &[50; 4]);
+        assert_eq!(&i420[13..], &[200; 4]);
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        assert!(matches!(
+            convert_frame(
+                PixelFormat::Yuyv,
+                PixelFormat::I420,
+                &[0; 10],
+                WIDTH,
+                HEIGHT
+            ),
+            Err(MediaError::Processing(_))
+        ));
+        assert!(matches!(
+            convert_frame(
+                PixelFormat::I420,
+                PixelFormat::Yuyv,
+                &[0; 192],
+                WIDTH,
+                HEIGHT
+            ),
+            Err(MediaError::Processing(_))
+        ));
+    }
+}
@@ -1,9 +1,11 @@
 //! BGR to RGB conversion
 //!
-//! High-performance conversion from OpenCV's BGR format to RGB
+//! High-performance conversion from OpenCV's BGR format to RGB, and back
+//! for frames supplied programmatically
 
 use crate::video::frame::VideoFrame;
-use opencv::prelude::{MatTraitConst, MatTraitConstManual};
+use opencv::core::{CV_8UC3, Mat, Scalar};
+use opencv::prelude::{MatTraitConst, MatTraitConstManual, MatTraitManual};
 use std::error::Error;
 
 /// Converts a BGR VideoFrame to RGB pixel data
@@ -28,6 +30,42 @@ pub fn frame_to_rgb(frame: &VideoFrame) -> Result<(usize, usize, Vec<u8>), Box<d
     Ok((width, height, rgb_pixels))
 }
 
+/// Converts RGB pixel data to a BGR VideoFrame
+///
+/// # Arguments
+/// * `width` - Frame width in pixels
+/// * `height` - Frame height in pixels
+/// * `rgb_pixels` - RGB pixel data, `width * height * 3` bytes
+///
+/// # Returns
+/// * `Ok(VideoFrame)` - Frame ready for encoding
+/// * `Err` - If the pixel data does not match the dimensions
+pub fn rgb_to_frame(
+    width: usize,
+    height: usize,
+    rgb_pixels: &[u8],
+) -> Result<VideoFrame, Box<dyn Error>> {
+    if rgb_pixels.len() != width * height * 3 {
+        return Err(format!(
+            "RGB frame of {}x{} needs {} bytes, got {}",
+            width,
+            height,
+            width * height * 3,
+            rgb_pixels.len()
+        )
+        .into());
+    }
+
+    let mut mat =
+        Mat::new_rows_cols_with_default(height as i32, width as i32, CV_8UC3, Scalar::all(0.0))?;
+    // Swapping R and B is its own inverse
+    mat.data_bytes_mut()
+        .map_err(|e| format!("Failed to get frame data: {}", e))?
+        .copy_from_slice(&convert_bgr_to_rgb(rgb_pixels));
+
+    Ok(VideoFrame::new(mat))
+}
+
 /// Converts BGR pixel data to RGB format
 ///
 /// Processes in chunks of 4 pixels (12 bytes) for better CPU cache utilization.
@@ -246,6 +284,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rgb_to_frame_round_trip() {
+        let rgb: Vec<u8> = (0..5 * 3 * 3).map(|i| i as u8).collect();
+
+        let frame = rgb_to_frame(5, 3, &rgb).unwrap();
+        assert_eq!((frame.width(), frame.height()), (5, 3));
+        assert_eq!(frame_to_rgb(&frame).unwrap(), (5, 3, rgb));
+
+        assert!(rgb_to_frame(5, 3, &[0; 10]).is_err());
+    }
+
     #[test]
     fn test_frame_to_rgb_remainder_processing() {
         // Test with 5 pixels (15 bytes) - 1 chunk + 1 remainder pixel
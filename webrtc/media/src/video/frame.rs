@@ -61,6 +61,36 @@ impl VideoFrame {
         &self.data
     }
 
+    /// Returns a copy of the frame scaled to the given resolution
+    ///
+    /// Keeps the original capture timestamp so every scaled copy of a
+    /// frame stays aligned with its source.
+    ///
+    /// # Arguments
+    /// * `width` - Target width in pixels
+    /// * `height` - Target height in pixels
+    pub fn scaled(&self, width: i32, height: i32) -> crate::error::Result<VideoFrame> {
+        use opencv::core::Size;
+        use opencv::imgproc::{INTER_AREA, resize};
+
+        let mut mat = Mat::default();
+        resize(
+            &self.data,
+            &mut mat,
+            Size::new(width, height),
+            0.0,
+            0.0,
+            INTER_AREA,
+        )?;
+
+        Ok(VideoFrame {
+            data: mat,
+            width,
+            height,
+            timestamp: self.timestamp,
+        })
+    }
+
     /// Consumes the frame and returns the internal Mat
     ///
     /// Use when transferring ownership to another component.
@@ -130,6 +160,24 @@ mod tests {
         assert_eq!(data_ref.rows(), 480);
     }
 
+    #[test]
+    fn test_frame_scaled() {
+        let mat = Mat::new_rows_cols_with_default(
+            480,
+            640,
+            CV_8UC3,
+            Scalar::new(100.0, 150.0, 200.0, 0.0),
+        )
+        .unwrap();
+        let frame = VideoFrame::new(mat);
+        let scaled = frame.scaled(320, 240).unwrap();
+
+        assert_eq!(scaled.width(), 320);
+        assert_eq!(scaled.height(), 240);
+        assert_eq!(scaled.data().cols(), 320);
+        assert_eq!(scaled.timestamp(), frame.timestamp());
+    }
+
     #[test]
     fn test_frame_timestamp() {
         let mat = Mat::default();
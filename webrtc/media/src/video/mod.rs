@@ -7,12 +7,17 @@ pub mod codecs;
 pub mod constants;
 pub mod converters;
 pub mod frame;
+pub mod screen;
 pub mod traits;
 pub mod utils;
 
 // Re-exports
-pub use camera::{Camera, CameraConfig, CameraInfo};
-pub use codecs::{H264Decoder, H264Encoder, VP8Decoder, VP8Encoder};
-pub use converters::frame_to_rgb;
+pub use camera::{Camera, CameraCapability, CameraConfig, CameraInfo, PixelFormat};
+pub use codecs::{
+    H264Decoder, H264Encoder, H264EncoderProfile, H264EncoderSettings, VP8Decoder, VP8Encoder,
+    VP9Decoder, VP9Encoder, VideoCodec,
+};
+pub use converters::{convert_frame, frame_to_rgb, rgb_to_frame};
 pub use frame::VideoFrame;
-pub use traits::{VideoDecoder, VideoEncoder};
+pub use screen::{CaptureRegion, ScreenCapture, ScreenCaptureConfig};
+pub use traits::{VideoDecoder, VideoEncoder, VideoSource};
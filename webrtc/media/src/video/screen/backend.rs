@@ -0,0 +1,155 @@
+//! Screen grabbing backends.
+//!
+//! The `ScreenBackend` trait isolates the platform-specific grabbing from
+//! `ScreenCapture`, so pacing, cropping and resolution tracking can be
+//! exercised with a synthetic display.
+
+use crate::error::{MediaError, Result};
+use ffmpeg_next as ffmpeg;
+use opencv::core::{CV_8UC3, Mat, Scalar};
+use opencv::prelude::*;
+
+/// Source of full-display images
+pub trait ScreenBackend {
+    /// Grabs the current contents of the display
+    ///
+    /// # Returns
+    /// * `Ok(Mat)` - Whole display in BGR format; its size may change between calls
+    /// * `Err(MediaError::Screen)` - If the display cannot be read
+    fn grab(&mut self) -> Result<Mat>;
+}
+
+/// Screen grabbing through FFmpeg input devices
+///
+/// Uses x11grab on Linux, gdigrab on Windows and avfoundation on macOS.
+pub struct FfmpegScreenBackend {
+    input: ffmpeg::format::context::Input,
+    decoder: ffmpeg::decoder::Video,
+    stream_index: usize,
+}
+
+impl FfmpegScreenBackend {
+    /// Opens the platform screen grabbing device
+    ///
+    /// # Arguments
+    /// * `display_id` - Display identifier
+    /// * `fps` - Frame rate requested from the device
+    ///
+    /// # Returns
+    /// * `Ok(FfmpegScreenBackend)` - Device opened and decoder ready
+    /// * `Err(MediaError::Screen)` - If no screen grabbing device is available
+    pub fn open(display_id: i32, fps: f64) -> Result<Self> {
+        ffmpeg::init().map_err(|e| MediaError::Screen(format!("Error init ffmpeg: {}", e)))?;
+        ffmpeg::device::register_all();
+
+        let (device, url) = Self::device_url(display_id);
+        let format = ffmpeg::device::input::video()
+            .find(|format| format.name() == device)
+            .ok_or_else(|| MediaError::Screen(format!("Input device '{}' not found", device)))?;
+
+        let mut options = ffmpeg::Dictionary::new();
+        options.set("framerate", &format!("{}", fps.round() as u32));
+        if device != "avfoundation" {
+            options.set("draw_mouse", "1");
+        }
+
+        let input = ffmpeg::format::open_with(&url, &format, options)
+            .map_err(|e| MediaError::Screen(format!("Failed to open {}: {}", url, e)))?
+            .input();
+
+        let stream = input
+            .streams()
+            .best(ffmpeg::media::Type::Video)
+            .ok_or_else(|| MediaError::Screen("Screen device has no video stream".to_string()))?;
+        let stream_index = stream.index();
+
+        let decoder = ffmpeg::codec::context::Context::from_parameters(stream.parameters())
+            .and_then(|context| context.decoder().video())
+            .map_err(|e| MediaError::Screen(format!("Error creating decoder: {}", e)))?;
+
+        Ok(Self {
+            input,
+            decoder,
+            stream_index,
+        })
+    }
+
+    /// Returns the FFmpeg device name and URL for the display
+    fn device_url(display_id: i32) -> (&'static str, String) {
+        if cfg!(target_os = "windows") {
+            ("gdigrab", "desktop".to_string())
+        } else if cfg!(target_os = "macos") {
+            (
+                "avfoundation",
+                format!("Capture screen {}:none", display_id),
+            )
+        } else {
+            // DISPLAY is ":<display>[.<screen>]"; select the screen by id
+            let display = std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string());
+            let base = display.split('.').next().unwrap_or(":0").to_string();
+            ("x11grab", format!("{}.{}", base, display_id))
+        }
+    }
+
+    /// Converts a decoded frame of any pixel format to a BGR Mat
+    fn frame_to_bgr(frame: &ffmpeg::frame::Video) -> Result<Mat> {
+        let (width, height) = (frame.width(), frame.height());
+
+        let mut scaler = ffmpeg::software::scaling::Context::get(
+            frame.format(),
+            width,
+            height,
+            ffmpeg::format::Pixel::BGR24,
+            width,
+            height,
+            ffmpeg::software::scaling::Flags::BILINEAR,
+        )
+        .map_err(|e| MediaError::Screen(format!("Error creating scaler: {}", e)))?;
+
+        let mut bgr = ffmpeg::frame::Video::new(ffmpeg::format::Pixel::BGR24, width, height);
+        scaler
+            .run(frame, &mut bgr)
+            .map_err(|e| MediaError::Screen(format!("Error scaling frame: {}", e)))?;
+
+        // Copy row by row: FFmpeg lines may be padded beyond width * 3
+        let row_bytes = width as usize * 3;
+        let stride = bgr.stride(0);
+        let mut mat = Mat::new_rows_cols_with_default(
+            height as i32,
+            width as i32,
+            CV_8UC3,
+            Scalar::all(0.0),
+        )?;
+        let dst = mat.data_bytes_mut()?;
+        for (row, chunk) in dst.chunks_exact_mut(row_bytes).enumerate() {
+            chunk.copy_from_slice(&bgr.data(0)[row * stride..row * stride + row_bytes]);
+        }
+
+        Ok(mat)
+    }
+}
+
+impl ScreenBackend for FfmpegScreenBackend {
+    fn grab(&mut self) -> Result<Mat> {
+        let mut decoded = ffmpeg::frame::Video::empty();
+
+        loop {
+            if self.decoder.receive_frame(&mut decoded).is_ok() {
+                return Self::frame_to_bgr(&decoded);
+            }
+
+            let mut packet = ffmpeg::Packet::empty();
+            packet
+                .read(&mut self.input)
+                .map_err(|e| MediaError::Screen(format!("Failed to read screen: {}", e)))?;
+
+            if packet.stream() != self.stream_index {
+                continue;
+            }
+
+            self.decoder
+                .send_packet(&packet)
+                .map_err(|e| MediaError::Screen(format!("Error decoding screen: {}", e)))?;
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Screen capture source.
+//!
+//! Paces a `ScreenBackend` to the configured frame rate, crops the configured
+//! region and tracks resolution changes of the captured display.
+
+use crate::error::{MediaError, Result};
+use crate::video::frame::VideoFrame;
+use crate::video::traits::VideoSource;
+use logging::Logger;
+use opencv::core::{Mat, Rect};
+use opencv::prelude::*;
+use std::time::{Duration, Instant};
+
+use super::backend::{FfmpegScreenBackend, ScreenBackend};
+use super::config::ScreenCaptureConfig;
+
+/// Frames between progress log messages
+const SCREEN_LOG_INTERVAL: u64 = 300;
+
+/// Screen capture video source
+///
+/// Produces frames of the whole display or of the configured region. When
+/// the display is resized mid-stream the region is clipped to the new size
+/// and `resolution()` reports the new output size, so the caller can
+/// reinitialize its encoder.
+pub struct ScreenCapture {
+    backend: Box<dyn ScreenBackend + Send>,
+    config: ScreenCaptureConfig,
+    logger: Logger,
+    frame_interval: Duration,
+    next_frame_at: Option<Instant>,
+    resolution: (u32, u32),
+    frame_count: u64,
+}
+
+impl ScreenCapture {
+    /// Creates a screen capture on top of a backend
+    ///
+    /// # Arguments
+    /// * `config` - Display, frame rate and region
+    /// * `backend` - Source of display images
+    /// * `logger` - Logger instance for monitoring
+    pub fn new(
+        config: ScreenCaptureConfig,
+        backend: Box<dyn ScreenBackend + Send>,
+        logger: Logger,
+    ) -> Self {
+        logger.info(&format!(
+            "Initializing screen capture: display {} @ {} fps",
+            config.display_id, config.fps
+        ));
+
+        Self {
+            frame_interval: Duration::from_secs_f64(1.0 / config.fps),
+            backend,
+            config,
+            logger,
+            next_frame_at: None,
+            resolution: (0, 0),
+            frame_count: 0,
+        }
+    }
+
+    /// Opens the platform screen grabbing backend for the configured display
+    ///
+    /// # Returns
+    /// * `Ok(ScreenCapture)` - Capture ready
+    /// * `Err(MediaError::Screen)` - If screen grabbing is unavailable
+    pub fn open(config: ScreenCaptureConfig, logger: Logger) -> Result<Self> {
+        let backend = FfmpegScreenBackend::open(config.display_id, config.fps)?;
+        Ok(Self::new(config, Box::new(backend), logger))
+    }
+
+    /// Returns the capture configuration
+    pub fn config(&self) -> &ScreenCaptureConfig {
+        &self.config
+    }
+
+    /// Returns the total number of frames captured
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Sleeps until the next frame is due at the configured frame rate
+    fn wait_for_next_frame(&mut self) {
+        let now = Instant::now();
+        self.next_frame_at = match self.next_frame_at {
+            Some(due) if due > now => {
+                std::thread::sleep(due - now);
+                Some(due + self.frame_interval)
+            }
+            _ => Some(now + self.frame_interval),
+        };
+    }
+
+    /// Crops the configured region, clipped to the display bounds
+    fn crop(&self, display: Mat) -> Result<Mat> {
+        let Some(region) = self.config.region else {
+            return Ok(display);
+        };
+
+        let (display_width, display_height) = (display.cols() as u32, display.rows() as u32);
+        if region.x >= display_width || region.y >= display_height {
+            return Err(MediaError::Screen(format!(
+                "Capture region at ({}, {}) is outside the {}x{} display",
+                region.x, region.y, display_width, display_height
+            )));
+        }
+
+        let rect = Rect::new(
+            region.x as i32,
+            region.y as i32,
+            region.width.min(display_width - region.x) as i32,
+            region.height.min(display_height - region.y) as i32,
+        );
+
+        Ok(Mat::roi(&display, rect)?.try_clone()?)
+    }
+}
+
+impl VideoSource for ScreenCapture {
+    fn capture_frame(&mut self) -> Result<VideoFrame> {
+        self.wait_for_next_frame();
+
+        let display = self.backend.grab()?;
+        if display.empty() {
+            return Err(MediaError::Screen("Empty display image".to_string()));
+        }
+
+        let mat = self.crop(display)?;
+        let resolution = (mat.cols() as u32, mat.rows() as u32);
+        if resolution != self.resolution {
+            if self.frame_count > 0 {
+                self.logger.info(&format!(
+                    "Screen capture resolution changed: {}x{} -> {}x{}",
+                    self.resolution.0, self.resolution.1, resolution.0, resolution.1
+                ));
+            }
+            self.resolution = resolution;
+        }
+
+        self.frame_count += 1;
+        if self.frame_count.is_multiple_of(SCREEN_LOG_INTERVAL) {
+            self.logger
+                .debug(&format!("Screen frames captured: {}", self.frame_count));
+        }
+
+        Ok(VideoFrame::new(mat))
+    }
+
+    fn resolution(&self) -> (u32, u32) {
+        self.resolution
+    }
+
+    fn fps(&self) -> f64 {
+        self.config.fps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::video::screen::config::CaptureRegion;
+    use logging::LogLevel;
+    use opencv::core::{CV_8UC3, Scalar};
+    use std::sync::{Arc, Mutex};
+    use tempfile::tempdir;
+
+    /// Synthetic display whose size can be changed while capturing
+    struct SyntheticDisplay {
+        size: Arc<Mutex<(i32, i32)>>,
+    }
+
+    impl ScreenBackend for SyntheticDisplay {
+        fn grab(&mut self) -> Result<Mat> {
+            let (width, height) = *self.size.lock().unwrap();
+            Ok(Mat::new_rows_cols_with_default(
+                height,
+                width,
+                CV_8UC3,
+                Scalar::new(30.0, 60.0, 90.0, 0.0),
+            )?)
+        }
+    }
+
+    fn create_capture(
+        config: ScreenCaptureConfig,
+        size: (i32, i32),
+    ) -> (ScreenCapture, Arc<Mutex<(i32, i32)>>) {
+        let dir = tempdir().unwrap();
+        let logger = Logger::new(dir.path().join("test_screen.log"), LogLevel::Debug).unwrap();
+        let size = Arc::new(Mutex::new(size));
+        let backend = SyntheticDisplay {
+            size: Arc::clone(&size),
+        };
+        (ScreenCapture::new(config, Box::new(backend), logger), size)
+    }
+
+    #[test]
+    fn test_capture_whole_display() {
+        let config = ScreenCaptureConfig::new(0, 60.0).unwrap();
+        let (mut capture, _) = create_capture(config, (1920, 1080));
+
+        let frame = capture.capture_frame().unwrap();
+
+        assert_eq!((frame.width(), frame.height()), (1920, 1080));
+        assert_eq!(capture.resolution(), (1920, 1080));
+        assert_eq!(capture.frame_count(), 1);
+    }
+
+    #[test]
+    fn test_capture_region_has_requested_dimensions() {
+        let region = CaptureRegion {
+            x: 100,
+            y: 50,
+            width: 640,
+            height: 360,
+        };
+        let config = ScreenCaptureConfig::new(0, 60.0)
+            .unwrap()
+            .with_region(region)
+            .unwrap();
+        let (mut capture, _) = create_capture(config, (1920, 1080));
+
+        for _ in 0..3 {
+            let frame = capture.capture_frame().unwrap();
+            assert_eq!((frame.width(), frame.height()), (640, 360));
+        }
+    }
+
+    #[test]
+    fn test_capture_is_paced_to_fps() {
+        let config = ScreenCaptureConfig::new(0, 50.0).unwrap();
+        let (mut capture, _) = create_capture(config, (320, 240));
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            capture.capture_frame().unwrap();
+        }
+
+        // First frame is immediate, the next three wait 20 ms each
+        assert!(start.elapsed() >= Duration::from_millis(55));
+    }
+
+    #[test]
+    fn test_display_resize_mid_stream() {
+        let region = CaptureRegion {
+            x: 0,
+            y: 0,
+            width: 1280,
+            height: 720,
+        };
+        let config = ScreenCaptureConfig::new(0, 60.0)
+            .unwrap()
+            .with_region(region)
+            .unwrap();
+        let (mut capture, size) = create_capture(config, (1920, 1080));
+
+        capture.capture_frame().unwrap();
+        assert_eq!(capture.resolution(), (1280, 720));
+
+        // Display shrinks below the region: output is clipped
+        *size.lock().unwrap() = (1024, 768);
+        let frame = capture.capture_frame().unwrap();
+        assert_eq!((frame.width(), frame.height()), (1024, 720));
+        assert_eq!(capture.resolution(), (1024, 720));
+    }
+}
@@ -0,0 +1,103 @@
+//! Screen capture configuration types.
+//!
+//! Provides configuration options for screen capture including display
+//! selection, framerate and the captured region.
+
+use crate::error::{MediaError, Result};
+
+/// Rectangular region of a display, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Screen capture configuration
+#[derive(Debug, Clone)]
+pub struct ScreenCaptureConfig {
+    /// Display (monitor) identifier (0 for the primary display)
+    pub display_id: i32,
+    /// Target frames per second
+    pub fps: f64,
+    /// Region to capture (None = whole display)
+    pub region: Option<CaptureRegion>,
+}
+
+impl ScreenCaptureConfig {
+    /// Minimum valid FPS value
+    const MIN_FPS: f64 = 1.0;
+    /// Maximum valid FPS value (screen content rarely benefits from more)
+    const MAX_FPS: f64 = 60.0;
+
+    /// Creates a new screen capture configuration with validation
+    ///
+    /// # Arguments
+    /// * `display_id` - Display identifier
+    /// * `fps` - Target frames per second (clamped to 1.0-60.0)
+    ///
+    /// # Returns
+    /// * `Ok(ScreenCaptureConfig)` - Successfully created configuration
+    /// * `Err(MediaError::Config)` - If fps is not finite (NaN or infinite)
+    pub fn new(display_id: i32, fps: f64) -> Result<Self> {
+        if !fps.is_finite() {
+            return Err(MediaError::Config(
+                "FPS must be a finite number (not NaN or infinite)".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            display_id,
+            fps: fps.clamp(Self::MIN_FPS, Self::MAX_FPS),
+            region: None,
+        })
+    }
+
+    /// Restricts capture to a region of the display
+    ///
+    /// # Arguments
+    /// * `region` - Region to capture; must have a non-zero size
+    ///
+    /// # Returns
+    /// * `Ok(ScreenCaptureConfig)` - Successfully set region
+    /// * `Err(MediaError::Config)` - If the region is empty
+    pub fn with_region(mut self, region: CaptureRegion) -> Result<Self> {
+        if region.width == 0 || region.height == 0 {
+            return Err(MediaError::Config(format!(
+                "Capture region must not be empty, got {}x{}",
+                region.width, region.height
+            )));
+        }
+
+        self.region = Some(region);
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fps_clamping() {
+        assert_eq!(ScreenCaptureConfig::new(0, 0.0).unwrap().fps, 1.0);
+        assert_eq!(ScreenCaptureConfig::new(0, 144.0).unwrap().fps, 60.0);
+        assert_eq!(ScreenCaptureConfig::new(0, 15.0).unwrap().fps, 15.0);
+        assert!(ScreenCaptureConfig::new(0, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_empty_region_rejected() {
+        let region = CaptureRegion {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 480,
+        };
+        let result = ScreenCaptureConfig::new(0, 15.0)
+            .unwrap()
+            .with_region(region);
+        assert!(matches!(result, Err(MediaError::Config(_))));
+    }
+}
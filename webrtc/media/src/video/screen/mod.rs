@@ -0,0 +1,12 @@
+//! Screen capture module
+//!
+//! Provides screen sharing as a video source: a monitor (or a region of it)
+//! is grabbed at a configured frame rate through a platform backend.
+
+pub mod backend;
+pub mod capture;
+pub mod config;
+
+pub use backend::{FfmpegScreenBackend, ScreenBackend};
+pub use capture::ScreenCapture;
+pub use config::{CaptureRegion, ScreenCaptureConfig};
@@ -42,6 +42,13 @@ pub trait VideoEncoder {
         0 // Default implementation for backward compatibility
     }
 
+    /// Changes the target bitrate for subsequent frames
+    ///
+    /// Used by congestion control to follow the available bandwidth.
+    fn set_bitrate(&mut self, _bitrate: u32) {
+        // Default: no-op, codecs can override
+    }
+
     /// Requests a keyframe (I-frame) on the next encode call
     ///
     /// Used when packet loss is detected or when a new peer joins.
@@ -87,3 +94,25 @@ pub trait VideoDecoder {
         // Default: no-op, codecs can override
     }
 }
+
+/// Trait for video capture sources
+///
+/// Implemented by every device that produces raw frames for the encode
+/// pipeline (cameras, screen capture), so the pipeline does not depend on
+/// where frames come from.
+pub trait VideoSource {
+    /// Captures the next frame
+    ///
+    /// # Returns
+    /// * `Ok(VideoFrame)` - Captured frame (BGR format)
+    /// * `Err` - If the device fails or returns an empty frame
+    fn capture_frame(&mut self) -> Result<VideoFrame>;
+
+    /// Returns the current output resolution (width, height)
+    ///
+    /// May change between frames, e.g. when a captured display is resized.
+    fn resolution(&self) -> (u32, u32);
+
+    /// Returns the capture frame rate
+    fn fps(&self) -> f64;
+}
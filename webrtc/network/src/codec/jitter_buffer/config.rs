@@ -4,7 +4,9 @@
 #[derive(Debug, Clone)]
 pub struct JitterBufferConfig {
     pub clock_rate: u32,
+    /// Minimum playout delay (frames for video, packets for audio)
     pub min_delay_frames: u32,
+    /// Maximum playout delay (frames for video, packets for audio)
     pub max_delay_frames: u32,
     /// Target jitter (milliseconds) - buffer grows to accommodate this
     pub target_jitter_ms: f64,
@@ -14,10 +16,22 @@ pub struct JitterBufferConfig {
     pub adaptation_speed: f64,
     /// Ultra-low latency mode: release packets immediately if in order (for local/LAN)
     pub ultra_low_latency: bool,
+    /// Packet loss concealment: report missing packets as `PopResult::Concealed`
+    pub enable_plc: bool,
+    /// Audio mode: delay is counted in fixed-duration packets instead of
+    /// estimated video frame durations
+    pub is_audio: bool,
+    /// Duration of one audio packet (milliseconds), used when `is_audio` is set
+    pub packet_duration_ms: u32,
+    /// Hard cap on the playout delay (milliseconds): packets arriving after it
+    /// are dropped instead of growing the buffer, while earlier ones are still
+    /// reordered. `None` lets the delay grow up to `max_delay_frames`
+    pub max_latency_ms: Option<u32>,
 }
 
-impl Default for JitterBufferConfig {
-    fn default() -> Self {
+impl JitterBufferConfig {
+    /// Preset for 90kHz video
+    pub fn video_defaults() -> Self {
         Self {
             clock_rate: 90000,        // Standard video clock rate
             min_delay_frames: 3,      // Minimum 3 frame times
@@ -26,6 +40,33 @@ impl Default for JitterBufferConfig {
             max_capacity: 200,        // Max 200 packets in buffer
             adaptation_speed: 0.1,    // Slow adaptation (10% per adjustment)
             ultra_low_latency: false, // Default: normal jitter buffering
+            enable_plc: false,        // Default: silently skip missing packets
+            is_audio: false,
+            packet_duration_ms: 20,
+            max_latency_ms: None,
+        }
+    }
+
+    /// Preset for 48kHz Opus audio with 20ms packets
+    pub fn audio_defaults() -> Self {
+        Self {
+            clock_rate: 48000,      // Opus RTP clock rate
+            min_delay_frames: 2,    // Minimum 2 packets (40ms)
+            max_delay_frames: 10,   // Maximum 10 packets (200ms)
+            target_jitter_ms: 20.0, // Target 20ms jitter accommodation
+            max_capacity: 50,       // 1 second of 20ms packets
+            adaptation_speed: 0.2,  // Audio reacts faster to jitter changes
+            ultra_low_latency: false,
+            enable_plc: false,
+            is_audio: true,
+            packet_duration_ms: 20,
+            max_latency_ms: None,
         }
     }
 }
+
+impl Default for JitterBufferConfig {
+    fn default() -> Self {
+        Self::video_defaults()
+    }
+}
@@ -10,6 +10,28 @@ use crate::codec::rtp::RtpPacket;
 use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
 
+/// Result of popping from the jitter buffer
+#[derive(Debug)]
+pub enum PopResult {
+    /// Next packet in sequence order
+    Packet(RtpPacket),
+    /// A packet was lost; the consumer should conceal the gap
+    /// (e.g., repeat the last decoded frame or request recovery)
+    Concealed,
+    /// Nothing ready for playout yet
+    Empty,
+}
+
+impl PopResult {
+    /// Returns the packet, if any
+    pub fn into_packet(self) -> Option<RtpPacket> {
+        match self {
+            PopResult::Packet(packet) => Some(packet),
+            PopResult::Concealed | PopResult::Empty => None,
+        }
+    }
+}
+
 /// Buffered packet with metadata
 struct TimestampedPacket {
     packet: RtpPacket,
@@ -29,6 +51,13 @@ pub struct JitterBuffer {
     prev_timestamp: Option<u32>,
     stats: JitterBufferStats,
     last_frame_duration: Option<u32>,
+    /// Audio underruns held back until the next packet shows whether the
+    /// sender was in DTX silence
+    pending_underruns: u64,
+    /// Extra delay added to keep this stream in sync with another one
+    sync_offset: Duration,
+    /// RTP timestamp and unsynchronized playout time of the newest packet
+    latest_playout: Option<(u32, Instant)>,
 }
 
 impl JitterBuffer {
@@ -37,7 +66,14 @@ impl JitterBuffer {
     }
 
     pub fn with_config(config: JitterBufferConfig) -> Self {
-        let initial_delay = config.min_delay_frames * (config.clock_rate / 30);
+        let initial_delay = Self::cap_delay(
+            &config,
+            config.min_delay_frames * Self::default_delay_unit(&config),
+        );
+        let stats = JitterBufferStats {
+            playout_delay_ms: (initial_delay as f64 * 1000.0) / config.clock_rate as f64,
+            ..JitterBufferStats::default()
+        };
 
         Self {
             config,
@@ -49,8 +85,11 @@ impl JitterBuffer {
             jitter: 0.0,
             prev_arrival: None,
             prev_timestamp: None,
-            stats: JitterBufferStats::default(),
+            stats,
             last_frame_duration: None,
+            pending_underruns: 0,
+            sync_offset: Duration::ZERO,
+            latest_playout: None,
         }
     }
 
@@ -65,6 +104,7 @@ impl JitterBuffer {
             return;
         }
 
+        self.account_dtx_gap(&packet);
         self.update_jitter_estimate(arrival_time, timestamp);
 
         if self.is_too_late(arrival_time, timestamp) {
@@ -74,9 +114,10 @@ impl JitterBuffer {
         self.add_to_buffer(packet, sequence, timestamp);
         self.detect_frame_rate_if_needed();
         self.adapt_playout_delay();
+        self.latest_playout = Some((timestamp, self.base_playout_time(timestamp)));
     }
 
-    pub fn pop(&mut self) -> Option<RtpPacket> {
+    pub fn pop(&mut self) -> PopResult {
         if self.config.ultra_low_latency {
             self.pop_ultra_low_latency()
         } else {
@@ -98,6 +139,44 @@ impl JitterBuffer {
         false
     }
 
+    /// Returns true when every packet of the next frame is buffered
+    ///
+    /// A frame is complete once the packets from the next expected sequence
+    /// number up to one carrying the marker bit are all present.
+    pub fn has_complete_frame(&self) -> bool {
+        let Some(mut sequence) = self.next_sequence else {
+            return false;
+        };
+
+        while let Some(buffered) = self.buffer.get(&sequence) {
+            if buffered.packet.header.marker {
+                return true;
+            }
+            sequence = sequence.wrapping_add(1);
+        }
+        false
+    }
+
+    /// Delay playout by `offset` to line this stream up with another one
+    /// (see [`crate::codec::lip_sync`])
+    pub fn set_sync_offset(&mut self, offset: Duration) {
+        self.sync_offset = offset;
+    }
+
+    pub fn sync_offset(&self) -> Duration {
+        self.sync_offset
+    }
+
+    /// RTP timestamp and playout time (without sync offset) of the newest
+    /// buffered packet, used to compute lip-sync offsets
+    pub fn latest_playout(&self) -> Option<(u32, Instant)> {
+        self.latest_playout
+    }
+
+    pub fn clock_rate(&self) -> u32 {
+        self.config.clock_rate
+    }
+
     pub fn stats(&self) -> &JitterBufferStats {
         &self.stats
     }
@@ -109,6 +188,8 @@ impl JitterBuffer {
         self.base_arrival = None;
         self.prev_arrival = None;
         self.prev_timestamp = None;
+        self.pending_underruns = 0;
+        self.latest_playout = None;
         self.stats.buffer_size = 0;
     }
 
@@ -129,6 +210,32 @@ impl JitterBuffer {
         }
     }
 
+    /// Settles underruns held back during an audio silence
+    ///
+    /// A marker bit on an audio packet that follows a timestamp gap means the
+    /// sender resumed after DTX: the empty buffer was expected silence, not
+    /// loss, and the silence must not feed the jitter estimate.
+    fn account_dtx_gap(&mut self, packet: &RtpPacket) {
+        if !self.config.is_audio {
+            return;
+        }
+
+        let packet_units = Self::default_delay_unit(&self.config);
+        let resumes_after_dtx = packet.header.marker
+            && self
+                .prev_timestamp
+                .is_some_and(|prev| packet.header.timestamp.wrapping_sub(prev) > packet_units);
+
+        if resumes_after_dtx {
+            self.stats.dtx_gaps += 1;
+            self.pending_underruns = 0;
+            self.prev_arrival = None;
+            self.prev_timestamp = None;
+        } else {
+            self.stats.underruns += std::mem::take(&mut self.pending_underruns);
+        }
+    }
+
     fn update_jitter_estimate(&mut self, arrival_time: Instant, timestamp: u32) {
         if let (Some(prev_arrival), Some(prev_ts)) = (self.prev_arrival, self.prev_timestamp) {
             let arrival_delta = arrival_time.duration_since(prev_arrival).as_secs_f64();
@@ -159,6 +266,10 @@ impl JitterBuffer {
     }
 
     fn calculate_playout_time(&self, timestamp: u32) -> Instant {
+        self.base_playout_time(timestamp) + self.sync_offset
+    }
+
+    fn base_playout_time(&self, timestamp: u32) -> Instant {
         let base_arrival = self.base_arrival.expect("Base arrival initialized");
         let base_ts = self.base_timestamp.expect("Base timestamp initialized");
         let ts_delta = timestamp.wrapping_sub(base_ts) as u64;
@@ -192,34 +303,43 @@ impl JitterBuffer {
     }
 
     fn detect_frame_rate_if_needed(&mut self) {
-        if self.buffer.len() == 3 {
+        if !self.config.is_audio && self.buffer.len() == 3 {
             let actual_frame_duration = self.estimate_frame_duration();
-            self.playout_delay_units = self.config.min_delay_frames * actual_frame_duration;
+            self.set_playout_delay(self.config.min_delay_frames * actual_frame_duration);
         }
     }
 
-    fn pop_ultra_low_latency(&mut self) -> Option<RtpPacket> {
+    fn pop_ultra_low_latency(&mut self) -> PopResult {
         if let Some(next_seq) = self.next_sequence
             && let Some(buffered) = self.buffer.remove(&next_seq)
         {
-            return Some(self.consume_packet(buffered.packet, next_seq.wrapping_add(1)));
+            return PopResult::Packet(
+                self.consume_packet(buffered.packet, next_seq.wrapping_add(1)),
+            );
         }
 
         self.pop_first_available_packet()
     }
 
-    fn pop_first_available_packet(&mut self) -> Option<RtpPacket> {
-        let (&first_seq, _) = self.buffer.iter().next()?;
-        let buffered = self.buffer.remove(&first_seq)?;
+    fn pop_first_available_packet(&mut self) -> PopResult {
+        let Some(&first_seq) = self.buffer.keys().next() else {
+            return PopResult::Empty;
+        };
 
         if let Some(expected) = self.next_sequence {
             let skipped = first_seq.wrapping_sub(expected) as u64;
             if skipped > 0 && skipped < 1000 {
                 self.stats.underruns += skipped;
+
+                if self.config.enable_plc {
+                    // Jump over the gap; the packet is returned on the next pop
+                    return self.conceal_gap(first_seq);
+                }
             }
         }
 
-        Some(self.consume_packet(buffered.packet, first_seq.wrapping_add(1)))
+        let buffered = self.buffer.remove(&first_seq).expect("Packet exists");
+        PopResult::Packet(self.consume_packet(buffered.packet, first_seq.wrapping_add(1)))
     }
 
     fn consume_packet(&mut self, packet: RtpPacket, next_seq: u16) -> RtpPacket {
@@ -229,19 +349,31 @@ impl JitterBuffer {
         packet
     }
 
-    fn pop_normal_mode(&mut self) -> Option<RtpPacket> {
-        let next_seq = self.next_sequence?;
+    fn pop_normal_mode(&mut self) -> PopResult {
+        let Some(next_seq) = self.next_sequence else {
+            return PopResult::Empty;
+        };
 
         if self.is_packet_ready(next_seq) {
             let packet = self.buffer.remove(&next_seq).expect("Packet exists").packet;
-            return Some(self.consume_packet(packet, next_seq.wrapping_add(1)));
+            return PopResult::Packet(self.consume_packet(packet, next_seq.wrapping_add(1)));
+        }
+
+        if self.config.enable_plc && self.buffer.is_empty() {
+            // Nothing arrived yet, so no packet is known to be lost
+            if self.config.is_audio {
+                self.pending_underruns += 1;
+            } else {
+                self.stats.underruns += 1;
+            }
+            return PopResult::Empty;
         }
 
         if !self.buffer.contains_key(&next_seq) {
-            self.handle_missing_packet(next_seq);
+            return self.handle_missing_packet(next_seq);
         }
 
-        None
+        PopResult::Empty
     }
 
     fn is_packet_ready(&self, sequence: u16) -> bool {
@@ -252,30 +384,78 @@ impl JitterBuffer {
         }
     }
 
-    fn handle_missing_packet(&mut self, sequence: u16) {
+    fn handle_missing_packet(&mut self, sequence: u16) -> PopResult {
+        if self.config.enable_plc
+            && let Some(resume_at) = self.first_buffered_after(sequence)
+        {
+            self.stats.underruns += resume_at.wrapping_sub(sequence) as u64;
+            return self.conceal_gap(resume_at);
+        }
+
         self.stats.underruns += 1;
         self.next_sequence = Some(sequence.wrapping_add(1));
+        PopResult::Empty
+    }
+
+    /// Closest buffered sequence number following `sequence`
+    fn first_buffered_after(&self, sequence: u16) -> Option<u16> {
+        self.buffer
+            .keys()
+            .copied()
+            .min_by_key(|&buffered| buffered.wrapping_sub(sequence))
+    }
+
+    /// Reports a gap as one concealed frame and resumes playout at `resume_at`
+    fn conceal_gap(&mut self, resume_at: u16) -> PopResult {
+        self.next_sequence = Some(resume_at);
+        self.stats.frames_concealed += 1;
+        PopResult::Concealed
+    }
+
+    /// Clock units per delay step: one packet for audio, one 30fps frame for video
+    fn default_delay_unit(config: &JitterBufferConfig) -> u32 {
+        if config.is_audio {
+            config.clock_rate * config.packet_duration_ms / 1000
+        } else {
+            config.clock_rate / 30
+        }
     }
 
     fn adapt_playout_delay(&mut self) {
+        let delay_unit = Self::default_delay_unit(&self.config);
         let jitter_units = (self.jitter * self.config.clock_rate as f64) as u32;
-        let target_delay =
-            self.config.min_delay_frames * (self.config.clock_rate / 30) + jitter_units * 2;
+        let target_delay = self.config.min_delay_frames * delay_unit + jitter_units * 2;
 
-        let max_delay = self.config.max_delay_frames * (self.config.clock_rate / 30);
-        let min_delay = self.config.min_delay_frames * (self.config.clock_rate / 30);
+        let max_delay = self.config.max_delay_frames * delay_unit;
+        let min_delay = self.config.min_delay_frames * delay_unit;
         let target_delay = target_delay.clamp(min_delay, max_delay);
 
         let adjustment = ((target_delay as i64 - self.playout_delay_units as i64) as f64
             * self.config.adaptation_speed) as i32;
 
-        self.playout_delay_units =
-            (self.playout_delay_units as i32 + adjustment).max(min_delay as i32) as u32;
+        self.set_playout_delay(
+            (self.playout_delay_units as i32 + adjustment).max(min_delay as i32) as u32,
+        );
+    }
 
+    /// Sets the playout delay, within the latency cap, and reports it in the stats
+    fn set_playout_delay(&mut self, delay_units: u32) {
+        self.playout_delay_units = Self::cap_delay(&self.config, delay_units);
         self.stats.playout_delay_ms =
             (self.playout_delay_units as f64 * 1000.0) / self.config.clock_rate as f64;
     }
 
+    /// Limits a delay to `max_latency_ms`; the cap wins over `min_delay_frames`
+    fn cap_delay(config: &JitterBufferConfig, delay_units: u32) -> u32 {
+        match config.max_latency_ms {
+            Some(max_ms) => {
+                let max_units = (max_ms as u64 * config.clock_rate as u64 / 1000) as u32;
+                delay_units.min(max_units)
+            }
+            None => delay_units,
+        }
+    }
+
     fn estimate_frame_duration(&mut self) -> u32 {
         if self.buffer.len() >= 2 {
             let packets: Vec<&TimestampedPacket> = self.buffer.values().take(2).collect();
@@ -313,10 +493,7 @@ mod tests {
         header.sequence_number = seq;
         header.timestamp = timestamp;
 
-        RtpPacket {
-            header,
-            payload: vec![1, 2, 3, 4],
-        }
+        RtpPacket::new(header, vec![1, 2, 3, 4])
     }
 
     #[test]
@@ -331,6 +508,127 @@ mod tests {
         assert_eq!(jb.stats().packets_played, 0);
     }
 
+    #[test]
+    fn test_audio_preset_has_smaller_delay() {
+        let audio = JitterBuffer::with_config(JitterBufferConfig::audio_defaults());
+        let video = JitterBuffer::with_config(JitterBufferConfig::video_defaults());
+
+        // 2 packets x 20ms vs 3 frames at 30fps
+        assert_eq!(audio.stats().playout_delay_ms, 40.0);
+        assert_eq!(video.stats().playout_delay_ms, 100.0);
+    }
+
+    #[test]
+    fn test_audio_delay_is_packet_count_driven() {
+        let config = JitterBufferConfig {
+            packet_duration_ms: 10,
+            ..JitterBufferConfig::audio_defaults()
+        };
+        let min_delay_ms = (config.min_delay_frames * config.packet_duration_ms) as f64;
+        let max_delay_ms = (config.max_delay_frames * config.packet_duration_ms) as f64;
+        let mut jb = JitterBuffer::with_config(config);
+
+        // Three 10ms Opus packets: video would re-derive delay from frame spacing
+        for seq in 0..3u16 {
+            let mut packet = create_test_packet(seq as u32 * 480, seq);
+            packet.header.payload_type = 111;
+            jb.push(packet);
+        }
+
+        let delay = jb.stats().playout_delay_ms;
+        assert!(delay >= min_delay_ms && delay <= max_delay_ms);
+        assert!(delay < JitterBuffer::new().stats().playout_delay_ms);
+    }
+
+    #[test]
+    fn test_has_complete_frame_waits_for_marker() {
+        let mut jb = JitterBuffer::new();
+        assert!(!jb.has_complete_frame());
+
+        jb.push(create_test_packet(1000, 1));
+        jb.push(create_test_packet(1000, 2));
+        assert!(!jb.has_complete_frame());
+
+        // Last packet of the frame arrives before the middle one
+        let mut last = create_test_packet(1000, 4);
+        last.header.marker = true;
+        jb.push(last);
+        assert!(!jb.has_complete_frame());
+
+        jb.push(create_test_packet(1000, 3));
+        assert!(jb.has_complete_frame());
+    }
+
+    #[test]
+    fn test_sync_offset_delays_playout() {
+        let mut jb = JitterBuffer::new();
+        jb.push(create_test_packet(1000, 1));
+        let (timestamp, base_playout) = jb.latest_playout().unwrap();
+        assert_eq!(timestamp, 1000);
+        assert_eq!(jb.calculate_playout_time(1000), base_playout);
+
+        jb.set_sync_offset(Duration::from_millis(40));
+        assert_eq!(
+            jb.calculate_playout_time(1000),
+            base_playout + Duration::from_millis(40)
+        );
+
+        // The reported playout time stays unsynchronized
+        jb.push(create_test_packet(4000, 2));
+        let (_, playout) = jb.latest_playout().unwrap();
+        assert_eq!(
+            jb.calculate_playout_time(4000),
+            playout + Duration::from_millis(40)
+        );
+    }
+
+    /// Pushes packets arriving back to back but stamped 100ms apart
+    fn push_jittery_packets(jb: &mut JitterBuffer, max_delay_ms: Option<f64>) {
+        for seq in 0..20u16 {
+            jb.push(create_test_packet(seq as u32 * 9000, seq));
+            if let Some(max_delay_ms) = max_delay_ms {
+                assert!(jb.stats().playout_delay_ms <= max_delay_ms);
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_latency_caps_playout_delay() {
+        let capped_config = JitterBufferConfig {
+            max_latency_ms: Some(50),
+            ..JitterBufferConfig::video_defaults()
+        };
+        let mut capped = JitterBuffer::with_config(capped_config);
+        let mut uncapped = JitterBuffer::new();
+        assert_eq!(capped.stats().playout_delay_ms, 50.0);
+
+        push_jittery_packets(&mut capped, Some(50.0));
+        push_jittery_packets(&mut uncapped, None);
+
+        assert!(capped.stats().jitter_ms > 50.0);
+        assert!(uncapped.stats().playout_delay_ms > 50.0);
+    }
+
+    #[test]
+    fn test_max_latency_drops_packets_past_the_cap() {
+        let mut jb = JitterBuffer::with_config(JitterBufferConfig {
+            max_latency_ms: Some(50),
+            ..JitterBufferConfig::video_defaults()
+        });
+        jb.push(create_test_packet(1000, 1));
+        // Still reorders within the budget
+        jb.push(create_test_packet(1000, 3));
+        jb.push(create_test_packet(1000, 2));
+        assert_eq!(jb.stats().packets_late, 0);
+
+        // Would still be in time with the default 100ms delay
+        std::thread::sleep(Duration::from_millis(80));
+        jb.push(create_test_packet(1000, 4));
+
+        assert_eq!(jb.stats().packets_late, 1);
+        assert_eq!(jb.buffer.len(), 3);
+    }
+
     #[test]
     fn test_duplicate_detection() {
         let mut jb = JitterBuffer::new();
@@ -341,4 +639,164 @@ mod tests {
         assert_eq!(jb.buffer.len(), 1);
         assert_eq!(jb.stats().packets_duplicate, 1);
     }
+
+    fn drain(jb: &mut JitterBuffer) -> Vec<PopResult> {
+        let mut results = Vec::new();
+        loop {
+            match jb.pop() {
+                PopResult::Empty => break,
+                result => results.push(result),
+            }
+        }
+        results
+    }
+
+    fn count_concealed(results: &[PopResult]) -> usize {
+        results
+            .iter()
+            .filter(|r| matches!(r, PopResult::Concealed))
+            .count()
+    }
+
+    #[test]
+    fn test_plc_conceals_missing_packet_once() {
+        let mut jb = JitterBuffer::with_config(JitterBufferConfig {
+            enable_plc: true,
+            ..JitterBufferConfig::default()
+        });
+
+        // Packets of one frame share a timestamp and play out together
+        jb.push(create_test_packet(1000, 1));
+        jb.push(create_test_packet(1000, 3));
+        jb.push(create_test_packet(1000, 4));
+
+        std::thread::sleep(Duration::from_millis(200));
+        let results = drain(&mut jb);
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(count_concealed(&results), 1);
+        assert!(matches!(results[1], PopResult::Concealed));
+        assert_eq!(jb.stats().frames_concealed, 1);
+        assert_eq!(jb.stats().packets_played, 3);
+    }
+
+    #[test]
+    fn test_plc_conceals_gap_in_ultra_low_latency() {
+        let mut jb = JitterBuffer::with_config(JitterBufferConfig {
+            ultra_low_latency: true,
+            enable_plc: true,
+            ..JitterBufferConfig::default()
+        });
+
+        jb.push(create_test_packet(1000, 1));
+        jb.push(create_test_packet(4000, 3));
+
+        let results = drain(&mut jb);
+
+        assert_eq!(count_concealed(&results), 1);
+        assert_eq!(jb.stats().frames_concealed, 1);
+        assert_eq!(jb.stats().packets_played, 2);
+    }
+
+    #[test]
+    fn test_missing_packet_skipped_without_plc() {
+        let mut jb = JitterBuffer::with_config(JitterBufferConfig {
+            ultra_low_latency: true,
+            ..JitterBufferConfig::default()
+        });
+
+        jb.push(create_test_packet(1000, 1));
+        jb.push(create_test_packet(4000, 3));
+
+        let results = drain(&mut jb);
+
+        assert_eq!(count_concealed(&results), 0);
+        assert_eq!(jb.stats().frames_concealed, 0);
+        assert_eq!(jb.stats().underruns, 1);
+    }
+
+    #[test]
+    fn test_gap_concealed_once_in_both_modes() {
+        for ultra_low_latency in [false, true] {
+            let mut jb = JitterBuffer::with_config(JitterBufferConfig {
+                ultra_low_latency,
+                enable_plc: true,
+                ..JitterBufferConfig::default()
+            });
+
+            // Packets 2-4 are lost
+            jb.push(create_test_packet(1000, 1));
+            jb.push(create_test_packet(1000, 5));
+
+            std::thread::sleep(Duration::from_millis(200));
+            let results = drain(&mut jb);
+
+            assert_eq!(count_concealed(&results), 1);
+            assert_eq!(jb.stats().frames_concealed, 1);
+            assert!(jb.stats().underruns >= 3);
+            assert_eq!(jb.stats().packets_played, 2);
+        }
+    }
+
+    #[test]
+    fn test_empty_buffer_advances_sequence_without_plc() {
+        let mut jb = JitterBuffer::new();
+        jb.push(create_test_packet(1000, 1));
+
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(matches!(jb.pop(), PopResult::Packet(_)));
+
+        // Polling an empty buffer skips the expected packet
+        assert!(matches!(jb.pop(), PopResult::Empty));
+        assert_eq!(jb.stats().underruns, 1);
+        assert_eq!(jb.next_sequence, Some(3));
+    }
+
+    fn create_audio_packet(seq: u16, marker: bool) -> RtpPacket {
+        let mut packet = create_test_packet(seq as u32 * 960, seq);
+        packet.header.payload_type = 111;
+        packet.header.marker = marker;
+        packet
+    }
+
+    /// Plays out two packets, then polls the empty buffer during a silence
+    fn audio_buffer_after_silence() -> JitterBuffer {
+        let mut jb = JitterBuffer::with_config(JitterBufferConfig {
+            enable_plc: true,
+            ..JitterBufferConfig::audio_defaults()
+        });
+        jb.push(create_audio_packet(0, false));
+        jb.push(create_audio_packet(1, false));
+
+        std::thread::sleep(Duration::from_millis(80));
+        drain(&mut jb);
+        for _ in 0..5 {
+            assert!(matches!(jb.pop(), PopResult::Empty));
+        }
+        jb
+    }
+
+    #[test]
+    fn test_dtx_gap_signalled_by_marker_is_not_loss() {
+        let mut jb = audio_buffer_after_silence();
+
+        // 50 frames of DTX silence; sequence numbers stay contiguous
+        let mut resumed = create_audio_packet(2, true);
+        resumed.header.timestamp = 52 * 960;
+        jb.push(resumed);
+
+        assert_eq!(jb.stats().dtx_gaps, 1);
+        assert_eq!(jb.stats().underruns, 0);
+        assert_eq!(jb.stats().frames_concealed, 0);
+    }
+
+    #[test]
+    fn test_audio_gap_without_marker_counts_underruns() {
+        let mut jb = audio_buffer_after_silence();
+
+        jb.push(create_audio_packet(2, false));
+
+        assert_eq!(jb.stats().dtx_gaps, 0);
+        assert!(jb.stats().underruns >= 5);
+    }
 }
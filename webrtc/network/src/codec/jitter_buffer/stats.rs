@@ -14,6 +14,10 @@ pub struct JitterBufferStats {
     pub packets_duplicate: u64,
     /// Number of underruns (buffer empty when playout requested)
     pub underruns: u64,
+    /// Gaps reported to the consumer as concealed (PLC mode only)
+    pub frames_concealed: u64,
+    /// Audio silence periods signalled by the sender (DTX), not counted as underruns
+    pub dtx_gaps: u64,
 }
 
 impl Default for JitterBufferStats {
@@ -26,6 +30,8 @@ impl Default for JitterBufferStats {
             packets_late: 0,
             packets_duplicate: 0,
             underruns: 0,
+            frames_concealed: 0,
+            dtx_gaps: 0,
         }
     }
 }
@@ -0,0 +1,229 @@
+//! Audio/video synchronization from RTCP Sender Reports (RFC 3550 Section 6.4.1)
+//!
+//! Every Sender Report pairs an NTP wall clock time with the RTP timestamp of
+//! the same instant. Streams of one sender share the NTP clock, so the capture
+//! time of any packet can be placed on a common timeline:
+//!
+//! ```text
+//! capture = sr_ntp + (rtp_timestamp - sr_rtp) / clock_rate
+//! ```
+//!
+//! Comparing capture times with the playout times chosen by each jitter buffer
+//! tells which stream is played out earlier; that stream is delayed by the
+//! difference so both are presented in sync.
+
+use std::time::{Duration, Instant};
+
+/// Upper bound for the delay added to one stream
+pub const MAX_SYNC_OFFSET: Duration = Duration::from_secs(1);
+
+/// NTP/RTP timestamp pair taken from a Sender Report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NtpRtpMapping {
+    /// 64-bit NTP timestamp (32.32 fixed point seconds)
+    pub ntp_timestamp: u64,
+    /// RTP timestamp sampled at the same instant
+    pub rtp_timestamp: u32,
+}
+
+impl NtpRtpMapping {
+    /// Capture time of `rtp_timestamp` on the sender's NTP clock, in seconds
+    ///
+    /// # Arguments
+    /// * `rtp_timestamp` - RTP timestamp of a packet of the same stream
+    /// * `clock_rate` - RTP clock rate of the stream (e.g. 90000 for video)
+    pub fn capture_time(&self, rtp_timestamp: u32, clock_rate: u32) -> f64 {
+        let ntp_secs = self.ntp_timestamp as f64 / (1u64 << 32) as f64;
+        // Signed difference handles packets sampled before the report and wraparound
+        let rtp_delta = rtp_timestamp.wrapping_sub(self.rtp_timestamp) as i32;
+        ntp_secs + rtp_delta as f64 / clock_rate as f64
+    }
+}
+
+/// Timing of the most recent packet of one stream
+#[derive(Debug, Clone, Copy)]
+pub struct SyncSample {
+    /// Latest Sender Report mapping of the stream
+    pub mapping: NtpRtpMapping,
+    /// RTP clock rate of the stream
+    pub clock_rate: u32,
+    /// RTP timestamp of the packet
+    pub rtp_timestamp: u32,
+    /// Local playout time the jitter buffer scheduled for the packet,
+    /// without any synchronization offset
+    pub playout_time: Instant,
+}
+
+impl SyncSample {
+    fn capture_time(&self) -> f64 {
+        self.mapping
+            .capture_time(self.rtp_timestamp, self.clock_rate)
+    }
+}
+
+/// Extra playout delay for each stream of a sender
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PlayoutOffsets {
+    pub audio: Duration,
+    pub video: Duration,
+}
+
+/// Computes the playout delay to add to audio and video so that samples
+/// captured at the same time are played out at the same time.
+///
+/// Only one of the two offsets is non-zero: the stream that would play
+/// earlier waits for the other one. Offsets are capped at [`MAX_SYNC_OFFSET`].
+pub fn playout_offsets(audio: &SyncSample, video: &SyncSample) -> PlayoutOffsets {
+    let playout_diff = signed_secs(video.playout_time, audio.playout_time);
+    let capture_diff = video.capture_time() - audio.capture_time();
+
+    // Positive: video is played later than audio relative to capture
+    let relative_delay = playout_diff - capture_diff;
+    let offset = Duration::from_secs_f64(relative_delay.abs()).min(MAX_SYNC_OFFSET);
+
+    if relative_delay > 0.0 {
+        PlayoutOffsets {
+            audio: offset,
+            video: Duration::ZERO,
+        }
+    } else {
+        PlayoutOffsets {
+            audio: Duration::ZERO,
+            video: offset,
+        }
+    }
+}
+
+/// `a - b` in seconds, negative when `a` is earlier
+fn signed_secs(a: Instant, b: Instant) -> f64 {
+    if a >= b {
+        a.duration_since(b).as_secs_f64()
+    } else {
+        -b.duration_since(a).as_secs_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// NTP timestamp `secs` seconds after an arbitrary reference
+    fn ntp(secs: f64) -> u64 {
+        ((3_900_000_000.0 + secs) * (1u64 << 32) as f64) as u64
+    }
+
+    fn sample(
+        mapping: NtpRtpMapping,
+        clock_rate: u32,
+        rtp_timestamp: u32,
+        playout_time: Instant,
+    ) -> SyncSample {
+        SyncSample {
+            mapping,
+            clock_rate,
+            rtp_timestamp,
+            playout_time,
+        }
+    }
+
+    fn assert_close(actual: Duration, expected_ms: u64) {
+        let diff = actual.as_secs_f64() * 1000.0 - expected_ms as f64;
+        assert!(diff.abs() < 0.5, "{:?} != {}ms", actual, expected_ms);
+    }
+
+    #[test]
+    fn test_capture_time_follows_rtp_clock() {
+        let mapping = NtpRtpMapping {
+            ntp_timestamp: ntp(10.0),
+            rtp_timestamp: 90_000,
+        };
+        let base = mapping.capture_time(90_000, 90_000);
+
+        assert!((mapping.capture_time(180_000, 90_000) - base - 1.0).abs() < 1e-6);
+        assert!((mapping.capture_time(45_000, 90_000) - base + 0.5).abs() < 1e-6);
+        // Wraparound after the report
+        let wrapping = NtpRtpMapping {
+            ntp_timestamp: ntp(10.0),
+            rtp_timestamp: u32::MAX - 44_999,
+        };
+        assert!((wrapping.capture_time(45_000, 90_000) - base - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_offsets_from_known_sender_reports() {
+        // Both reports sampled at the same NTP time, with unrelated RTP bases
+        let audio_sr = NtpRtpMapping {
+            ntp_timestamp: ntp(0.0),
+            rtp_timestamp: 1_000,
+        };
+        let video_sr = NtpRtpMapping {
+            ntp_timestamp: ntp(0.0),
+            rtp_timestamp: 500_000,
+        };
+        let now = Instant::now();
+
+        // Audio and video captured 100ms after the report; the video jitter
+        // buffer schedules its frame 80ms later than audio
+        let audio = sample(audio_sr, 48_000, 1_000 + 4_800, now);
+        let video = sample(
+            video_sr,
+            90_000,
+            500_000 + 9_000,
+            now + Duration::from_millis(80),
+        );
+
+        let offsets = playout_offsets(&audio, &video);
+        assert_close(offsets.audio, 80);
+        assert_eq!(offsets.video, Duration::ZERO);
+
+        // A later pair of packets on the same clocks yields the same offset
+        let audio = sample(
+            audio_sr,
+            48_000,
+            1_000 + 48_000,
+            now + Duration::from_millis(900),
+        );
+        let video = sample(
+            video_sr,
+            90_000,
+            500_000 + 90_000,
+            now + Duration::from_millis(980),
+        );
+        assert_eq!(playout_offsets(&audio, &video), offsets);
+    }
+
+    #[test]
+    fn test_video_delayed_when_audio_lags() {
+        let audio_sr = NtpRtpMapping {
+            ntp_timestamp: ntp(5.0),
+            rtp_timestamp: 0,
+        };
+        // Video report sampled 20ms after the audio one
+        let video_sr = NtpRtpMapping {
+            ntp_timestamp: ntp(5.02),
+            rtp_timestamp: 0,
+        };
+        let now = Instant::now();
+
+        // Same capture instant, audio scheduled 50ms after video
+        let audio = sample(audio_sr, 48_000, 960, now + Duration::from_millis(50));
+        let video = sample(video_sr, 90_000, 0, now);
+
+        let offsets = playout_offsets(&audio, &video);
+        assert_eq!(offsets.audio, Duration::ZERO);
+        assert_close(offsets.video, 50);
+    }
+
+    #[test]
+    fn test_offset_is_capped() {
+        let mapping = NtpRtpMapping {
+            ntp_timestamp: ntp(0.0),
+            rtp_timestamp: 0,
+        };
+        let now = Instant::now();
+        let audio = sample(mapping, 48_000, 0, now);
+        let video = sample(mapping, 90_000, 0, now + Duration::from_secs(5));
+
+        assert_eq!(playout_offsets(&audio, &video).audio, MAX_SYNC_OFFSET);
+    }
+}
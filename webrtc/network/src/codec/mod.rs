@@ -1,14 +1,23 @@
 //! Codec module - RTP/RTCP and packetizers
 
 pub mod jitter_buffer;
+pub mod lip_sync;
 pub mod packet_handler;
 pub mod packetizers;
 pub mod rtcp;
 pub mod rtp;
 
-pub use jitter_buffer::{JitterBuffer, JitterBufferConfig, JitterBufferStats};
+pub use jitter_buffer::{JitterBuffer, JitterBufferConfig, JitterBufferStats, PopResult};
+pub use lip_sync::{NtpRtpMapping, PlayoutOffsets, SyncSample};
 pub use packet_handler::{PacketHandler, PacketStats};
-pub use packetizers::h264::{H264RtpDepacketizer, H264RtpPacketizer};
+pub use packetizers::h264::{H264RtpDepacketizer, H264RtpPacketizer, PacketizationMode};
 pub use packetizers::opus::{OpusRtpDepacketizer, OpusRtpPacketizer};
-pub use rtcp::{ByePacket, ReceiverReport, ReportBlock, RtcpPacketType, RtcpStats, SenderReport};
+pub use packetizers::rtx::{RtxRtpDepacketizer, RtxRtpPacketizer};
+pub use packetizers::vp8::{Vp8RtpDepacketizer, Vp8RtpPacketizer};
+pub use packetizers::vp9::{Vp9RtpDepacketizer, Vp9RtpPacketizer};
+pub use rtcp::{
+    BandwidthUsage, BitrateController, ByePacket, CompoundRtcpPacket, DelayBasedController,
+    FullIntraRequest, PacketResult, PictureLossIndication, ReceiverReport, ReportBlock, RtcpPacket,
+    RtcpPacketType, RtcpStats, SdesPacket, SenderReport, TransportCcFeedback,
+};
 pub use rtp::RtpPacket;
@@ -1,24 +1,33 @@
 //! H.264 RTP Depacketizer Implementation
 //!
-//! Reconstructs H.264 NAL units from RTP packets according to RFC 6184.
+//! Reconstructs H.264 access units (frames) from RTP packets according to RFC 6184.
+//!
+//! # Frame Boundaries
+//! Complete NAL units are collected until a packet with the RTP marker bit
+//! arrives. The marker bit is set on the last packet of an access unit, so the
+//! collected NAL units are then handed out together as one frame.
 //!
 //! # Depacketization Modes
 //! This implementation handles both packetization modes:
 //!
 //! ## Single NAL Unit Mode
 //! Complete NAL units received in a single RTP packet are returned immediately
-//! with an Annex B start code prepended.
+//! to the current frame with an Annex B start code prepended.
 //!
 //! ## FU-A Fragmentation Mode
 //! Fragmented NAL units are reassembled across multiple RTP packets:
 //! 1. First fragment (S bit set): Initialize buffer with start code + NAL header
 //! 2. Middle fragments: Append payload to buffer
-//! 3. Last fragment (E bit set): Return complete NAL unit
+//! 3. Last fragment (E bit set): Add the complete NAL unit to the current frame
 //!
 //! # Packet Loss Handling
 //! - If a fragment is lost mid-frame, the next start fragment discards incomplete data
-//! - Timestamp changes also trigger buffer reset
+//! - Timestamp changes with a partial frame buffered also trigger buffer reset
+//!   (the packet carrying the marker bit was lost)
 //! - Out-of-order packets are NOT reordered (assumes ordered transport or external reordering)
+//! - Sequence gaps and broken fragments put the depacketizer in a "waiting for keyframe"
+//!   state: non-IDR slices are dropped and a keyframe request (PLI) is raised until an
+//!   IDR slice arrives
 //!
 //! # Examples
 //! ```rust,ignore
@@ -30,10 +39,10 @@
 //! for packet_data in udp_transport.receive() {
 //!     let packet = RtpPacket::from_bytes(&packet_data)?;
 //!     
-//!     if let Some(nal_unit) = depacketizer.process_packet(&packet) {
-//!         // Complete NAL unit ready for decoder
-//!         // nal_unit starts with 0x00000001 (Annex B start code)
-//!         h264_decoder.decode(&nal_unit)?;
+//!     if let Some(frame) = depacketizer.process_packet(&packet) {
+//!         // Complete access unit ready for decoder
+//!         // Every NAL unit starts with 0x00000001 (Annex B start code)
+//!         h264_decoder.decode(&frame)?;
 //!     }
 //! }
 //! ```
@@ -45,6 +54,10 @@ use crate::traits::RtpDepacketizer;
 const FU_A_TYPE: u8 = 28;
 /// H.264 NAL unit start code (Annex B format)
 const NAL_START_CODE: &[u8] = &[0x00, 0x00, 0x00, 0x01];
+/// NAL unit type: Non-IDR coded slice
+const NAL_TYPE_NON_IDR: u8 = 1;
+/// NAL unit type: IDR coded slice (keyframe)
+const NAL_TYPE_IDR: u8 = 5;
 
 /// Represents an H.264 RTP depacketizer
 ///
@@ -55,6 +68,14 @@ pub struct H264RtpDepacketizer {
     current_timestamp: Option<u32>,
     /// Buffer for reassembling fragmented NAL units
     nal_buffer: Vec<u8>,
+    /// Complete NAL units of the current access unit, emitted on the marker bit
+    frame_buffer: Vec<u8>,
+    /// Sequence number of the last processed packet (for loss detection)
+    last_sequence: Option<u16>,
+    /// True until an IDR slice is received (stream start or after loss)
+    waiting_for_keyframe: bool,
+    /// Set when the decoder cannot continue without a new keyframe
+    keyframe_request_pending: bool,
 }
 
 impl H264RtpDepacketizer {
@@ -66,6 +87,59 @@ impl H264RtpDepacketizer {
         H264RtpDepacketizer {
             current_timestamp: None,
             nal_buffer: Vec::new(),
+            frame_buffer: Vec::new(),
+            last_sequence: None,
+            waiting_for_keyframe: true,
+            keyframe_request_pending: false,
+        }
+    }
+
+    /// Returns true once if a keyframe should be requested from the sender (PLI)
+    ///
+    /// Raised when a non-IDR slice arrives while no valid reference picture is
+    /// available, i.e. at stream start or after packet loss.
+    pub fn take_keyframe_request(&mut self) -> bool {
+        std::mem::take(&mut self.keyframe_request_pending)
+    }
+
+    /// Check if the depacketizer is dropping slices until the next IDR
+    pub fn is_waiting_for_keyframe(&self) -> bool {
+        self.waiting_for_keyframe
+    }
+
+    /// Mark the reference chain as broken after detected loss
+    fn mark_loss(&mut self) {
+        self.nal_buffer.clear();
+        self.frame_buffer.clear();
+        self.waiting_for_keyframe = true;
+    }
+
+    /// Track sequence numbers and flag gaps as loss
+    fn check_sequence(&mut self, sequence_number: u16) {
+        if let Some(last) = self.last_sequence
+            && sequence_number != last.wrapping_add(1)
+            && sequence_number != last
+        {
+            self.mark_loss();
+        }
+        self.last_sequence = Some(sequence_number);
+    }
+
+    /// Filter a complete NAL unit according to keyframe state
+    ///
+    /// Non-IDR slices are useless to the decoder while waiting for a keyframe,
+    /// so they are dropped and a keyframe request is raised instead.
+    fn filter_complete_nal(&mut self, nal: Vec<u8>) -> Option<Vec<u8>> {
+        match nal.get(NAL_START_CODE.len()).map(|b| b & 0x1F) {
+            Some(NAL_TYPE_IDR) => {
+                self.waiting_for_keyframe = false;
+                Some(nal)
+            }
+            Some(NAL_TYPE_NON_IDR) if self.waiting_for_keyframe => {
+                self.keyframe_request_pending = true;
+                None
+            }
+            _ => Some(nal),
         }
     }
 
@@ -118,12 +192,16 @@ impl H264RtpDepacketizer {
 
         if is_start {
             self.start_new_fragment(timestamp, fu_indicator, nal_type);
+        } else if self.nal_buffer.is_empty() {
+            // Continuation without a start fragment: the start was lost
+            self.mark_loss();
+            return None;
         }
 
         self.nal_buffer.extend_from_slice(&payload[2..]);
 
         if is_end {
-            Some(self.nal_buffer.clone())
+            Some(std::mem::take(&mut self.nal_buffer))
         } else {
             None
         }
@@ -156,34 +234,52 @@ impl RtpDepacketizer for H264RtpDepacketizer {
             return None;
         }
 
+        self.check_sequence(packet.header.sequence_number);
+
         // Detect timestamp change (indicates new frame or packet loss recovery)
         if let Some(current_ts) = self.current_timestamp
             && timestamp != current_ts
-            && !self.nal_buffer.is_empty()
+            && self.has_pending_data()
         {
-            // Timestamp changed with incomplete buffer - discard stale data
-            self.nal_buffer.clear();
+            // New frame before the marker bit closed the previous one -
+            // discard stale data
+            self.mark_loss();
         }
 
         // Check NAL unit type from first byte
         let nal_type = payload[0] & 0x1F;
 
-        if nal_type == FU_A_TYPE {
+        let nal = if nal_type == FU_A_TYPE {
             // FU-A Fragmentation Mode
             self.process_fu_a(payload, timestamp)
         } else {
             // Single NAL Unit Mode
             Some(self.process_single_nal(payload, timestamp))
+        };
+
+        if let Some(nal) = nal.and_then(|nal| self.filter_complete_nal(nal)) {
+            self.frame_buffer.extend_from_slice(&nal);
+        }
+
+        // The marker bit closes the access unit
+        if packet.header.marker && !self.frame_buffer.is_empty() {
+            Some(std::mem::take(&mut self.frame_buffer))
+        } else {
+            None
         }
     }
 
     fn reset(&mut self) {
         self.current_timestamp = None;
         self.nal_buffer.clear();
+        self.frame_buffer.clear();
+        self.last_sequence = None;
+        self.waiting_for_keyframe = true;
+        self.keyframe_request_pending = false;
     }
 
     fn has_pending_data(&self) -> bool {
-        !self.nal_buffer.is_empty()
+        !self.nal_buffer.is_empty() || !self.frame_buffer.is_empty()
     }
 }
 
@@ -196,7 +292,9 @@ impl Default for H264RtpDepacketizer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::codec::packetizers::h264::H264RtpPacketizer;
     use crate::codec::rtp::RtpHeader;
+    use crate::traits::RtpPacketizer;
 
     #[test]
     fn test_single_nal_unit() {
@@ -327,6 +425,101 @@ mod tests {
         assert_eq!(&nal[4..], &[0x67, 0x01, 0x02]); // New NAL data
     }
 
+    #[test]
+    fn test_non_idr_before_keyframe_requests_pli() {
+        let mut depacketizer = H264RtpDepacketizer::new();
+
+        // P-slice without any prior IDR cannot be decoded
+        let mut header = RtpHeader::new(96, 12345);
+        header.sequence_number = 10;
+        header.timestamp = 1000;
+        let packet = RtpPacket::new(header, vec![0x41, 0x9A, 0x00]);
+
+        assert!(depacketizer.process_packet(&packet).is_none());
+        assert!(depacketizer.take_keyframe_request());
+        assert!(!depacketizer.take_keyframe_request());
+
+        // IDR slice restores decoding
+        let mut header = RtpHeader::new(96, 12345);
+        header.sequence_number = 11;
+        header.timestamp = 4000;
+        header.marker = true;
+        let packet = RtpPacket::new(header, vec![0x65, 0x88, 0x00]);
+
+        assert!(depacketizer.process_packet(&packet).is_some());
+        assert!(!depacketizer.is_waiting_for_keyframe());
+    }
+
+    #[test]
+    fn test_sequence_gap_waits_for_keyframe() {
+        let mut depacketizer = H264RtpDepacketizer::new();
+
+        let mut header = RtpHeader::new(96, 12345);
+        header.sequence_number = 20;
+        header.timestamp = 1000;
+        depacketizer.process_packet(&RtpPacket::new(header, vec![0x65, 0x88]));
+        assert!(!depacketizer.is_waiting_for_keyframe());
+
+        // Sequence 21 lost
+        let mut header = RtpHeader::new(96, 12345);
+        header.sequence_number = 22;
+        header.timestamp = 4000;
+        let result = depacketizer.process_packet(&RtpPacket::new(header, vec![0x41, 0x9A]));
+
+        assert!(result.is_none());
+        assert!(depacketizer.is_waiting_for_keyframe());
+        assert!(depacketizer.take_keyframe_request());
+    }
+
+    #[test]
+    fn test_fragmented_frame_emitted_on_marker() {
+        let mut packetizer = H264RtpPacketizer::new(96, 100, 30.0);
+        let mut depacketizer = H264RtpDepacketizer::new();
+
+        // IDR slice split into three FU-A fragments of at most 98 bytes
+        let mut frame = NAL_START_CODE.to_vec();
+        frame.push(0x65);
+        frame.extend((0..250).map(|i| i as u8));
+
+        let packets = packetizer.packetize(&frame);
+        assert_eq!(packets.len(), 3);
+        let markers: Vec<bool> = packets.iter().map(|p| p.header.marker).collect();
+        assert_eq!(markers, vec![false, false, true]);
+
+        let frames: Vec<Vec<u8>> = packets
+            .iter()
+            .filter_map(|packet| depacketizer.process_packet(packet))
+            .collect();
+
+        assert_eq!(frames, vec![frame]);
+        assert!(!depacketizer.has_pending_data());
+    }
+
+    #[test]
+    fn test_nal_units_of_one_frame_are_emitted_together() {
+        let mut packetizer = H264RtpPacketizer::new(96, 1200, 30.0);
+        let mut depacketizer = H264RtpDepacketizer::new();
+
+        // SPS + PPS + IDR in one access unit
+        let mut frame = Vec::new();
+        for nal in [
+            &[0x67, 0x42, 0x00][..],
+            &[0x68, 0xCE][..],
+            &[0x65, 0x88, 0x84][..],
+        ] {
+            frame.extend_from_slice(NAL_START_CODE);
+            frame.extend_from_slice(nal);
+        }
+
+        let packets = packetizer.packetize(&frame);
+        assert_eq!(packets.len(), 3);
+
+        assert!(depacketizer.process_packet(&packets[0]).is_none());
+        assert!(depacketizer.process_packet(&packets[1]).is_none());
+        assert!(depacketizer.has_pending_data());
+        assert_eq!(depacketizer.process_packet(&packets[2]), Some(frame));
+    }
+
     #[test]
     fn test_reset() {
         let mut depacketizer = H264RtpDepacketizer::new();
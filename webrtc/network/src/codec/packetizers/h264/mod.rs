@@ -7,4 +7,4 @@ mod depacketizer;
 mod packetizer;
 
 pub use depacketizer::H264RtpDepacketizer;
-pub use packetizer::H264RtpPacketizer;
+pub use packetizer::{H264RtpPacketizer, PacketizationMode};
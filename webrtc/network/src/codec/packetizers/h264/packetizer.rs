@@ -57,6 +57,11 @@
 //! - E: End bit (1 for last fragment)
 //! - R: Reserved (must be 0)
 //! - Type: NAL unit type from original NAL header
+//!
+//! FU-A packets are only allowed in non-interleaved mode
+//! (`packetization-mode=1`). In single NAL unit mode (`packetization-mode=0`)
+//! every NAL unit is sent in its own packet, so the encoder must keep NAL
+//! units within the payload size.
 
 use crate::codec::rtp::{RtpHeader, RtpPacket};
 use crate::traits::RtpPacketizer;
@@ -68,6 +73,27 @@ const FU_A_TYPE: u8 = 28;
 const NAL_START_CODE_4: &[u8] = &[0x00, 0x00, 0x00, 0x01];
 const NAL_START_CODE_3: &[u8] = &[0x00, 0x00, 0x01];
 
+/// H.264 packetization mode negotiated in the SDP fmtp line (RFC 6184 Section 6.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PacketizationMode {
+    /// `packetization-mode=0`: single NAL unit packets only
+    SingleNalUnit,
+    /// `packetization-mode=1`: single NAL unit and FU-A packets
+    #[default]
+    NonInterleaved,
+}
+
+impl PacketizationMode {
+    /// Maps the `packetization-mode` fmtp value (0 or 1)
+    pub fn from_fmtp(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(PacketizationMode::SingleNalUnit),
+            1 => Some(PacketizationMode::NonInterleaved),
+            _ => None,
+        }
+    }
+}
+
 /// Represents an H.264 RTP packetizer
 ///
 /// Handles both Single NAL Unit and FU-A Fragmentation modes
@@ -85,6 +111,8 @@ pub struct H264RtpPacketizer {
     max_payload_size: usize,
     /// Timestamp increment per frame (90000 / fps)
     timestamp_increment: u32,
+    /// Whether large NAL units may be fragmented
+    mode: PacketizationMode,
 }
 
 impl H264RtpPacketizer {
@@ -111,9 +139,20 @@ impl H264RtpPacketizer {
             payload_type,
             max_payload_size,
             timestamp_increment,
+            mode: PacketizationMode::default(),
         }
     }
 
+    /// Sets the packetization mode negotiated with the remote peer
+    pub fn set_packetization_mode(&mut self, mode: PacketizationMode) {
+        self.mode = mode;
+    }
+
+    /// Current packetization mode
+    pub fn packetization_mode(&self) -> PacketizationMode {
+        self.mode
+    }
+
     /// Extract NAL units from H.264 Annex B byte stream
     ///
     /// Searches for NAL unit start codes (0x000001 or 0x00000001) and
@@ -293,8 +332,10 @@ impl RtpPacketizer for H264RtpPacketizer {
         for (i, nal_unit) in nal_units.iter().enumerate() {
             let is_last_nal = i == nal_units.len() - 1;
 
-            if nal_unit.len() <= self.max_payload_size {
-                // Single NAL Unit Mode (fits in one packet)
+            if nal_unit.len() <= self.max_payload_size
+                || self.mode == PacketizationMode::SingleNalUnit
+            {
+                // Single NAL Unit Mode (fits in one packet, or FU-A not negotiated)
                 packets.extend(self.packetize_single_nal(nal_unit, is_last_nal));
             } else {
                 // FU-A Fragmentation Mode (requires multiple packets)
@@ -373,6 +414,23 @@ mod tests {
         assert!(last.header.marker); // Marker bit set
     }
 
+    #[test]
+    fn test_single_nal_unit_mode_never_fragments() {
+        let mut packetizer = H264RtpPacketizer::new(96, 1000, 30.0);
+        packetizer.set_packetization_mode(PacketizationMode::SingleNalUnit);
+
+        let mut large_nal = Vec::new();
+        large_nal.extend_from_slice(NAL_START_CODE_4);
+        large_nal.push(0x65);
+        large_nal.extend(vec![0xAA; 2000]);
+
+        let packets = packetizer.packetize(&large_nal);
+
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].payload[0] & 0x1F, 0x05);
+        assert_eq!(packets[0].payload.len(), 2001);
+    }
+
     #[test]
     fn test_multiple_nal_units() {
         let mut packetizer = H264RtpPacketizer::new(96, 1000, 30.0);
@@ -4,6 +4,12 @@
 //! Each codec follows its respective RFC specification for RTP payload format.
 pub mod h264;
 pub mod opus;
+pub mod rtx;
+pub mod vp8;
+pub mod vp9;
 
-pub use h264::{H264RtpDepacketizer, H264RtpPacketizer};
+pub use h264::{H264RtpDepacketizer, H264RtpPacketizer, PacketizationMode};
 pub use opus::{OpusRtpDepacketizer, OpusRtpPacketizer};
+pub use rtx::{RtxRtpDepacketizer, RtxRtpPacketizer};
+pub use vp8::{Vp8RtpDepacketizer, Vp8RtpPacketizer};
+pub use vp9::{Vp9RtpDepacketizer, Vp9RtpPacketizer};
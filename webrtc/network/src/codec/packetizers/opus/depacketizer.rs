@@ -67,13 +67,14 @@ mod tests {
         let header = RtpHeader {
             version: 2,
             padding: false,
-            csrc_count: 0,
             extension: false,
             marker: false,
             payload_type: 111,
             sequence_number: 1000,
             timestamp: 48000,
             ssrc: 12345,
+            csrc: Vec::new(),
+            extensions: Vec::new(),
         };
 
         let audio_data = vec![1, 2, 3, 4, 5];
@@ -27,6 +27,8 @@ pub struct OpusRtpPacketizer {
     /// Timestamp increment per frame (based on sample rate and frame size)
     /// For 48kHz with 20ms frames: 48000 * 0.02 = 960 samples
     timestamp_increment: u32,
+    /// Set the marker bit on the next packet (first of a talk spurt)
+    marker_pending: bool,
 }
 
 impl OpusRtpPacketizer {
@@ -59,9 +61,23 @@ impl OpusRtpPacketizer {
             payload_type,
             max_payload_size,
             timestamp_increment,
+            marker_pending: false,
         }
     }
 
+    /// Advance the timestamp for a frame that is not sent (DTX silence)
+    ///
+    /// Sequence numbers stay contiguous, so the receiver sees a timestamp
+    /// gap rather than lost packets.
+    pub fn skip_frame(&mut self) {
+        self.timestamp = self.timestamp.wrapping_add(self.timestamp_increment);
+    }
+
+    /// Mark the next packet as the first of a talk spurt (RFC 3551 marker bit)
+    pub fn mark_talkspurt(&mut self) {
+        self.marker_pending = true;
+    }
+
     /// Packetize Opus audio data
     ///
     /// Opus frames typically fit in a single RTP packet.
@@ -74,7 +90,8 @@ impl OpusRtpPacketizer {
             let mut header = RtpHeader::new(self.payload_type, self.ssrc);
             header.sequence_number = self.sequence_number;
             header.timestamp = self.timestamp;
-            header.marker = false; // Audio doesn't typically use marker bit
+            // Audio only uses the marker bit to flag talk-spurt starts
+            header.marker = std::mem::take(&mut self.marker_pending);
 
             self.sequence_number = self.sequence_number.wrapping_add(1);
 
@@ -89,7 +106,7 @@ impl OpusRtpPacketizer {
                 let mut header = RtpHeader::new(self.payload_type, self.ssrc);
                 header.sequence_number = self.sequence_number;
                 header.timestamp = self.timestamp;
-                header.marker = false;
+                header.marker = offset == 0 && std::mem::take(&mut self.marker_pending);
 
                 self.sequence_number = self.sequence_number.wrapping_add(1);
 
@@ -170,4 +187,33 @@ mod tests {
         assert_eq!(packets[0].header.sequence_number, initial_seq);
         assert_eq!(packetizer.sequence_number, initial_seq.wrapping_add(1));
     }
+
+    #[test]
+    fn test_opus_talkspurt_after_skipped_frames() {
+        let mut packetizer = OpusRtpPacketizer::new(111, 1400, 48000, 20);
+        let audio_data = vec![0u8; 100];
+
+        let first = packetizer.packetize(&audio_data);
+        assert!(!first[0].header.marker);
+
+        // 10 frames of DTX silence
+        for _ in 0..10 {
+            packetizer.skip_frame();
+        }
+        packetizer.mark_talkspurt();
+
+        let resumed = packetizer.packetize(&audio_data);
+        assert!(resumed[0].header.marker);
+        assert_eq!(
+            resumed[0].header.sequence_number,
+            first[0].header.sequence_number.wrapping_add(1)
+        );
+        assert_eq!(
+            resumed[0].header.timestamp,
+            first[0].header.timestamp.wrapping_add(11 * 960)
+        );
+
+        let next = packetizer.packetize(&audio_data);
+        assert!(!next[0].header.marker);
+    }
 }
@@ -0,0 +1,11 @@
+//! RTX retransmission packetization module (RFC 4588)
+
+mod depacketizer;
+mod packetizer;
+
+pub use depacketizer::RtxRtpDepacketizer;
+pub use packetizer::RtxRtpPacketizer;
+
+/// RTX payload types paired with the video payload type they retransmit
+/// (`a=fmtp:<rtx> apt=<primary>`)
+pub const RTX_PAYLOAD_TYPES: [(u8, u8); 3] = [(99, 96), (100, 97), (101, 98)];
@@ -0,0 +1,116 @@
+//! RTX RTP Packetizer Implementation
+//!
+//! Implements RFC 4588 - RTP Retransmission Payload Format.
+//!
+//! Lost packets are resent on a separate stream (own SSRC, payload type and
+//! sequence numbers) so the original stream's statistics stay intact. The
+//! RTX payload starts with the original sequence number (OSN):
+//!
+//! ```text
+//!  0                   1                   2                   3
+//!  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |            OSN                |                               |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               |
+//! |                  Original RTP Packet Payload                  |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! ```
+
+use crate::codec::rtp::{RtpHeader, RtpPacket};
+use rand::Rng;
+
+/// RTX RTP packetizer
+///
+/// Wraps packets of the primary stream for retransmission.
+pub struct RtxRtpPacketizer {
+    /// Synchronization source identifier of the RTX stream
+    ssrc: u32,
+    /// RTP sequence number of the RTX stream
+    sequence_number: u16,
+    /// RTX payload type (associated to the primary one with `apt`)
+    payload_type: u8,
+}
+
+impl RtxRtpPacketizer {
+    /// Create a new RTX packetizer with a random SSRC
+    ///
+    /// # Arguments
+    /// * `payload_type` - RTX payload type (e.g. 99 for `apt=96`)
+    pub fn new(payload_type: u8) -> Self {
+        let mut rng = rand::thread_rng();
+
+        RtxRtpPacketizer {
+            ssrc: rng.gen_range(0..=u32::MAX),
+            sequence_number: rng.gen_range(0..=u16::MAX),
+            payload_type,
+        }
+    }
+
+    /// Wrap an original packet into an RTX packet
+    ///
+    /// The timestamp, marker bit, CSRCs and header extensions are kept; the
+    /// original sequence number is prepended to the payload.
+    ///
+    /// # Arguments
+    /// * `original` - Packet of the primary stream to retransmit
+    ///
+    /// # Returns
+    /// RTX packet ready for transmission
+    pub fn packetize(&mut self, original: &RtpPacket) -> RtpPacket {
+        let header = RtpHeader {
+            payload_type: self.payload_type,
+            sequence_number: self.sequence_number,
+            ssrc: self.ssrc,
+            padding: false,
+            ..original.header.clone()
+        };
+        self.sequence_number = self.sequence_number.wrapping_add(1);
+
+        let mut payload = Vec::with_capacity(2 + original.payload.len());
+        payload.extend_from_slice(&original.header.sequence_number.to_be_bytes());
+        payload.extend_from_slice(&original.payload);
+
+        RtpPacket::new(header, payload)
+    }
+
+    /// Get the RTX payload type
+    pub fn get_payload_type(&self) -> u8 {
+        self.payload_type
+    }
+
+    /// Get the SSRC of the RTX stream
+    pub fn get_ssrc(&self) -> u32 {
+        self.ssrc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtx_wraps_original_sequence_number() {
+        let mut packetizer = RtxRtpPacketizer::new(99);
+
+        let mut header = RtpHeader::new(96, 1234);
+        header.sequence_number = 0xBEEF;
+        header.timestamp = 90_000;
+        header.marker = true;
+        let original = RtpPacket::new(header, vec![1, 2, 3]);
+
+        let first = packetizer.packetize(&original);
+        assert_eq!(first.header.payload_type, 99);
+        assert_eq!(first.header.ssrc, packetizer.get_ssrc());
+        assert_ne!(first.header.ssrc, 1234);
+        assert_eq!(first.header.timestamp, 90_000);
+        assert!(first.header.marker);
+        assert_eq!(first.payload, vec![0xBE, 0xEF, 1, 2, 3]);
+
+        // RTX stream has its own contiguous sequence numbers
+        let second = packetizer.packetize(&original);
+        assert_eq!(
+            second.header.sequence_number,
+            first.header.sequence_number.wrapping_add(1)
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! VP8 RTP Depacketizer Implementation
+//!
+//! Reconstructs VP8 frames from RTP packets according to RFC 7741.
+//!
+//! # Payload Descriptor
+//! The descriptor is parsed and stripped from every packet. Optional fields
+//! (picture ID, TL0PICIDX, temporal layer and key index) are skipped. A frame
+//! starts with the S bit set on partition 0 and ends with the RTP marker bit.
+//!
+//! # Packet Loss Handling
+//! - A sequence gap or a fragment without a preceding start discards the frame
+//! - Timestamp changes with an incomplete buffer also discard stale data
+//! - Until the first keyframe, and again after loss, interframes are dropped
+//!   and a keyframe request (PLI) is raised until the next keyframe arrives
+
+use super::{
+    EXT_I, EXT_K, EXT_L, EXT_T, FLAG_S, FLAG_X, PARTITION_ID_MASK, PICTURE_ID_M, is_keyframe,
+};
+use crate::codec::rtp::RtpPacket;
+use crate::traits::RtpDepacketizer;
+
+/// Parsed fields of a VP8 payload descriptor
+struct PayloadDescriptor {
+    /// First packet of a frame (start of partition 0)
+    start: bool,
+    /// Offset of the VP8 data within the RTP payload
+    header_size: usize,
+}
+
+/// Represents a VP8 RTP depacketizer
+pub struct Vp8RtpDepacketizer {
+    /// Current RTP timestamp for tracking frame boundaries
+    current_timestamp: Option<u32>,
+    /// Buffer for reassembling fragmented frames
+    frame_buffer: Vec<u8>,
+    /// True if the last assembled frame was a keyframe
+    last_frame_keyframe: bool,
+    /// Sequence number of the last processed packet (for loss detection)
+    last_sequence: Option<u16>,
+    /// True until a keyframe is received (stream start or after loss)
+    waiting_for_keyframe: bool,
+    /// Set when the decoder cannot continue without a new keyframe
+    keyframe_request_pending: bool,
+}
+
+impl Vp8RtpDepacketizer {
+    /// Create a new VP8 RTP depacketizer
+    ///
+    /// # Returns
+    /// New depacketizer instance with empty buffers
+    pub fn new() -> Self {
+        Vp8RtpDepacketizer {
+            current_timestamp: None,
+            frame_buffer: Vec::new(),
+            last_frame_keyframe: false,
+            last_sequence: None,
+            waiting_for_keyframe: true,
+            keyframe_request_pending: false,
+        }
+    }
+
+    /// Returns true if the last frame returned by `process_packet` is a keyframe
+    pub fn is_keyframe(&self) -> bool {
+        self.last_frame_keyframe
+    }
+
+    /// Returns true once if a keyframe should be requested from the sender (PLI)
+    pub fn take_keyframe_request(&mut self) -> bool {
+        std::mem::take(&mut self.keyframe_request_pending)
+    }
+
+    /// Check if the depacketizer is dropping frames until the next keyframe
+    pub fn is_waiting_for_keyframe(&self) -> bool {
+        self.waiting_for_keyframe
+    }
+
+    /// Mark the reference chain as broken after detected loss
+    fn mark_loss(&mut self) {
+        self.frame_buffer.clear();
+        self.waiting_for_keyframe = true;
+    }
+
+    /// Track sequence numbers and flag gaps as loss
+    fn check_sequence(&mut self, sequence_number: u16) {
+        if let Some(last) = self.last_sequence
+            && sequence_number != last.wrapping_add(1)
+            && sequence_number != last
+        {
+            self.mark_loss();
+        }
+        self.last_sequence = Some(sequence_number);
+    }
+
+    /// Filter a complete frame according to keyframe state
+    fn filter_complete_frame(&mut self, frame: Vec<u8>) -> Option<Vec<u8>> {
+        if is_keyframe(&frame) {
+            self.waiting_for_keyframe = false;
+            self.last_frame_keyframe = true;
+            return Some(frame);
+        }
+
+        if self.waiting_for_keyframe {
+            self.keyframe_request_pending = true;
+            return None;
+        }
+
+        self.last_frame_keyframe = false;
+        Some(frame)
+    }
+}
+
+/// Parse the payload descriptor at the start of a VP8 RTP payload
+///
+/// # Returns
+/// - `Some(PayloadDescriptor)` - Parsed descriptor
+/// - `None` - Payload is truncated
+fn parse_descriptor(payload: &[u8]) -> Option<PayloadDescriptor> {
+    let flags = *payload.first()?;
+    let mut offset = 1;
+
+    if flags & FLAG_X != 0 {
+        let extension = *payload.get(offset)?;
+        offset += 1;
+
+        if extension & EXT_I != 0 {
+            let picture_id = *payload.get(offset)?;
+            offset += if picture_id & PICTURE_ID_M != 0 { 2 } else { 1 };
+        }
+        if extension & EXT_L != 0 {
+            offset += 1;
+        }
+        if extension & (EXT_T | EXT_K) != 0 {
+            // TID/Y/KEYIDX share one byte
+            offset += 1;
+        }
+    }
+
+    if offset > payload.len() {
+        return None;
+    }
+
+    Some(PayloadDescriptor {
+        start: flags & FLAG_S != 0 && flags & PARTITION_ID_MASK == 0,
+        header_size: offset,
+    })
+}
+
+impl RtpDepacketizer for Vp8RtpDepacketizer {
+    fn process_packet(&mut self, packet: &RtpPacket) -> Option<Vec<u8>> {
+        let timestamp = packet.header.timestamp;
+        let descriptor = parse_descriptor(&packet.payload)?;
+
+        self.check_sequence(packet.header.sequence_number);
+
+        // Timestamp changed with incomplete buffer - discard stale data
+        if let Some(current_ts) = self.current_timestamp
+            && timestamp != current_ts
+            && !self.frame_buffer.is_empty()
+        {
+            self.mark_loss();
+        }
+
+        if descriptor.start {
+            self.frame_buffer.clear();
+            self.current_timestamp = Some(timestamp);
+        } else if self.frame_buffer.is_empty() {
+            // Continuation without a start packet: the start was lost
+            self.mark_loss();
+            return None;
+        }
+
+        self.frame_buffer
+            .extend_from_slice(&packet.payload[descriptor.header_size..]);
+
+        if !packet.header.marker {
+            return None;
+        }
+
+        let frame = std::mem::take(&mut self.frame_buffer);
+        self.filter_complete_frame(frame)
+    }
+
+    fn reset(&mut self) {
+        self.current_timestamp = None;
+        self.frame_buffer.clear();
+        self.last_frame_keyframe = false;
+        self.last_sequence = None;
+        self.waiting_for_keyframe = true;
+        self.keyframe_request_pending = false;
+    }
+
+    fn has_pending_data(&self) -> bool {
+        !self.frame_buffer.is_empty()
+    }
+}
+
+impl Default for Vp8RtpDepacketizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::rtp::RtpHeader;
+
+    /// Synthetic keyframe: frame tag, start code and 640x480 dimensions
+    const KEYFRAME: [u8; 12] = [
+        0x50, 0x2D, 0x00, 0x9D, 0x01, 0x2A, 0x80, 0x02, 0xE0, 0x01, 0xAA, 0xBB,
+    ];
+    /// Synthetic interframe: frame tag with the P bit set
+    const INTERFRAME: [u8; 6] = [0x31, 0x0A, 0x00, 0xCC, 0xDD, 0xEE];
+
+    /// Splits a frame into packets with a descriptor carrying a 15-bit picture ID
+    fn packetize(frame: &[u8], first_seq: u16, timestamp: u32) -> Vec<RtpPacket> {
+        let chunks: Vec<&[u8]> = frame.chunks(5).collect();
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut header = RtpHeader::new(97, 1234);
+                header.sequence_number = first_seq.wrapping_add(i as u16);
+                header.timestamp = timestamp;
+                header.marker = i == chunks.len() - 1;
+
+                let start = if i == 0 { FLAG_S } else { 0 };
+                let mut payload = vec![FLAG_X | start, EXT_I, PICTURE_ID_M | 0x01, 0x23];
+                payload.extend_from_slice(chunk);
+                RtpPacket::new(header, payload)
+            })
+            .collect()
+    }
+
+    fn receive(depacketizer: &mut Vp8RtpDepacketizer, packets: &[RtpPacket]) -> Option<Vec<u8>> {
+        packets
+            .iter()
+            .fold(None, |_, packet| depacketizer.process_packet(packet))
+    }
+
+    #[test]
+    fn test_classifies_keyframes_and_interframes() {
+        let mut depacketizer = Vp8RtpDepacketizer::new();
+
+        let keyframe = receive(&mut depacketizer, &packetize(&KEYFRAME, 0, 0));
+        assert_eq!(keyframe, Some(KEYFRAME.to_vec()));
+        assert!(depacketizer.is_keyframe());
+        assert!(!depacketizer.is_waiting_for_keyframe());
+
+        let interframe = receive(&mut depacketizer, &packetize(&INTERFRAME, 3, 3000));
+        assert_eq!(interframe, Some(INTERFRAME.to_vec()));
+        assert!(!depacketizer.is_keyframe());
+    }
+
+    #[test]
+    fn test_interframes_dropped_until_first_keyframe() {
+        let mut depacketizer = Vp8RtpDepacketizer::new();
+
+        assert!(receive(&mut depacketizer, &packetize(&INTERFRAME, 0, 0)).is_none());
+        assert!(depacketizer.take_keyframe_request());
+        assert!(!depacketizer.take_keyframe_request());
+
+        assert!(receive(&mut depacketizer, &packetize(&KEYFRAME, 2, 3000)).is_some());
+        assert!(depacketizer.is_keyframe());
+    }
+
+    #[test]
+    fn test_lost_packet_waits_for_keyframe() {
+        let mut depacketizer = Vp8RtpDepacketizer::new();
+        receive(&mut depacketizer, &packetize(&KEYFRAME, 0, 0));
+
+        let mut interframe = INTERFRAME.to_vec();
+        interframe.extend_from_slice(&[0x11; 8]);
+        let packets = packetize(&interframe, 3, 3000);
+        assert_eq!(packets.len(), 3);
+
+        assert!(depacketizer.process_packet(&packets[0]).is_none());
+        assert!(depacketizer.process_packet(&packets[2]).is_none());
+        assert!(depacketizer.is_waiting_for_keyframe());
+    }
+}
@@ -0,0 +1,56 @@
+//! VP8 RTP Packetization (RFC 7741)
+//!
+//! This module implements RTP packetization and depacketization for VP8
+//! video, including keyframe detection from the payload header of the first
+//! partition.
+
+mod depacketizer;
+mod packetizer;
+
+pub use depacketizer::Vp8RtpDepacketizer;
+pub use packetizer::Vp8RtpPacketizer;
+
+/// Descriptor flag: extended control bits present
+const FLAG_X: u8 = 0x80;
+/// Descriptor flag: start of a VP8 partition
+const FLAG_S: u8 = 0x10;
+/// Descriptor mask: partition index
+const PARTITION_ID_MASK: u8 = 0x0F;
+/// Extension flag: picture ID present
+const EXT_I: u8 = 0x80;
+/// Extension flag: TL0PICIDX present
+const EXT_L: u8 = 0x40;
+/// Extension flag: temporal layer index present
+const EXT_T: u8 = 0x20;
+/// Extension flag: key index present
+const EXT_K: u8 = 0x10;
+/// Picture ID flag: 15-bit picture ID follows
+const PICTURE_ID_M: u8 = 0x80;
+/// Start code following the frame tag of every keyframe
+const KEYFRAME_START_CODE: [u8; 3] = [0x9D, 0x01, 0x2A];
+
+/// Returns true if the VP8 frame is a keyframe
+///
+/// Reads the inverse key frame flag (P bit) of the frame tag and checks the
+/// keyframe start code (RFC 6386 Section 9.1).
+pub(crate) fn is_keyframe(frame: &[u8]) -> bool {
+    match frame.first() {
+        Some(tag) if tag & 0x01 == 0 => frame.get(3..6) == Some(&KEYFRAME_START_CODE[..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_keyframe() {
+        assert!(is_keyframe(&[0x50, 0x2D, 0x00, 0x9D, 0x01, 0x2A, 0x80]));
+        // P bit set (interframe)
+        assert!(!is_keyframe(&[0x51, 0x2D, 0x00, 0x9D, 0x01, 0x2A]));
+        // Missing start code
+        assert!(!is_keyframe(&[0x50, 0x2D, 0x00, 0x00, 0x00, 0x00]));
+        assert!(!is_keyframe(&[]));
+    }
+}
@@ -0,0 +1,188 @@
+//! VP8 RTP Packetizer Implementation
+//!
+//! Implements RFC 7741 - RTP Payload Format for VP8 Video, sending each frame
+//! as a single partition.
+//!
+//! Every packet starts with a payload descriptor followed by a fragment of the
+//! VP8 frame. Frames larger than the MTU are split across packets, with the S
+//! bit on the first fragment and the RTP marker bit on the last.
+//!
+//! ```text
+//!  0 1 2 3 4 5 6 7
+//! +-+-+-+-+-+-+-+-+
+//! |X|R|N|S|R| PID | (REQUIRED)
+//! +-+-+-+-+-+-+-+-+
+//! |I|L|T|K| RSV   | (OPTIONAL, X=1)
+//! +-+-+-+-+-+-+-+-+
+//! |M| PictureID   | (OPTIONAL, I=1)
+//! +-+-+-+-+-+-+-+-+
+//! |   PictureID   | (M=1: 15-bit picture ID)
+//! +-+-+-+-+-+-+-+-+
+//! ```
+//! - X/I: Extended control bits with a picture ID (always set)
+//! - S: Start of partition, set on the first packet of a frame
+//! - PID: Partition index (always 0)
+
+use super::{EXT_I, FLAG_S, FLAG_X, PICTURE_ID_M};
+use crate::codec::rtp::{RtpHeader, RtpPacket};
+use crate::traits::RtpPacketizer;
+use rand::Rng;
+
+/// Payload descriptor size: flags + extension + 15-bit picture ID
+const DESCRIPTOR_SIZE: usize = 4;
+
+/// Represents a VP8 RTP packetizer
+pub struct Vp8RtpPacketizer {
+    /// Synchronization source identifier (randomly generated)
+    ssrc: u32,
+    /// RTP sequence number (incremented per packet)
+    sequence_number: u16,
+    /// RTP timestamp in 90 kHz clock units
+    timestamp: u32,
+    /// RTP payload type (typically 97 for dynamic VP8)
+    payload_type: u8,
+    /// Maximum RTP payload size in bytes (MTU - RTP header)
+    max_payload_size: usize,
+    /// Timestamp increment per frame (90000 / fps)
+    timestamp_increment: u32,
+    /// 15-bit picture ID, incremented per frame
+    picture_id: u16,
+}
+
+impl Vp8RtpPacketizer {
+    /// Create a new VP8 RTP packetizer
+    ///
+    /// # Arguments
+    /// * `payload_type` - RTP payload type (96-127 for dynamic mappings)
+    /// * `max_payload_size` - Maximum payload size in bytes, descriptor included
+    /// * `fps` - Video frame rate for timestamp calculation
+    pub fn new(payload_type: u8, max_payload_size: usize, fps: f64) -> Self {
+        let mut rng = rand::thread_rng();
+
+        Vp8RtpPacketizer {
+            ssrc: rng.gen_range(0..=u32::MAX),
+            sequence_number: rng.gen_range(0..=u16::MAX),
+            timestamp: 0,
+            payload_type,
+            max_payload_size,
+            timestamp_increment: (90000.0 / fps).round() as u32,
+            picture_id: rng.gen_range(0..=0x7FFF),
+        }
+    }
+
+    /// Sends with the given SSRC instead of a random one
+    ///
+    /// Used to keep the SSRC of a stream when its codec changes.
+    pub fn with_ssrc(mut self, ssrc: u32) -> Self {
+        self.ssrc = ssrc;
+        self
+    }
+
+    fn build_descriptor(&self, is_first: bool) -> [u8; DESCRIPTOR_SIZE] {
+        let start = if is_first { FLAG_S } else { 0 };
+        [
+            FLAG_X | start,
+            EXT_I,
+            PICTURE_ID_M | (self.picture_id >> 8) as u8,
+            self.picture_id as u8,
+        ]
+    }
+}
+
+impl RtpPacketizer for Vp8RtpPacketizer {
+    fn packetize(&mut self, data: &[u8]) -> Vec<RtpPacket> {
+        if data.is_empty() {
+            return Vec::new();
+        }
+
+        let fragment_size = self.max_payload_size.saturating_sub(DESCRIPTOR_SIZE).max(1);
+        let fragments: Vec<&[u8]> = data.chunks(fragment_size).collect();
+        let mut packets = Vec::with_capacity(fragments.len());
+
+        for (i, fragment) in fragments.iter().enumerate() {
+            let is_last = i == fragments.len() - 1;
+
+            let mut payload = Vec::with_capacity(DESCRIPTOR_SIZE + fragment.len());
+            payload.extend_from_slice(&self.build_descriptor(i == 0));
+            payload.extend_from_slice(fragment);
+
+            let mut header = RtpHeader::new(self.payload_type, self.ssrc);
+            header.sequence_number = self.sequence_number;
+            header.timestamp = self.timestamp;
+            header.marker = is_last; // Last packet of the frame
+
+            self.sequence_number = self.sequence_number.wrapping_add(1);
+            packets.push(RtpPacket::new(header, payload));
+        }
+
+        self.picture_id = (self.picture_id + 1) & 0x7FFF;
+        self.timestamp = self.timestamp.wrapping_add(self.timestamp_increment);
+
+        packets
+    }
+
+    fn get_payload_type(&self) -> u8 {
+        self.payload_type
+    }
+
+    fn get_ssrc(&self) -> u32 {
+        self.ssrc
+    }
+
+    fn get_timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    fn get_sequence_number(&self) -> u16 {
+        self.sequence_number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::packetizers::vp8::Vp8RtpDepacketizer;
+    use crate::traits::RtpDepacketizer;
+
+    /// Synthetic keyframe: frame tag, start code and 640x480 dimensions
+    const KEYFRAME: [u8; 12] = [
+        0x50, 0x2D, 0x00, 0x9D, 0x01, 0x2A, 0x80, 0x02, 0xE0, 0x01, 0xAA, 0xBB,
+    ];
+
+    #[test]
+    fn test_large_frame_round_trips_through_depacketizer() {
+        let mut packetizer = Vp8RtpPacketizer::new(97, DESCRIPTOR_SIZE + 5, 30.0).with_ssrc(42);
+        let mut depacketizer = Vp8RtpDepacketizer::new();
+
+        let packets = packetizer.packetize(&KEYFRAME);
+        assert_eq!(packets.len(), 3);
+        assert!(packets.iter().all(|p| p.header.ssrc == 42));
+        assert_ne!(packets[0].payload[0] & FLAG_S, 0);
+        assert_eq!(packets[1].payload[0] & FLAG_S, 0);
+        assert!(packets[2].header.marker);
+
+        let frame = packets
+            .iter()
+            .fold(None, |_, packet| depacketizer.process_packet(packet));
+        assert_eq!(frame, Some(KEYFRAME.to_vec()));
+        assert!(depacketizer.is_keyframe());
+    }
+
+    #[test]
+    fn test_picture_id_and_timestamp_advance() {
+        let mut packetizer = Vp8RtpPacketizer::new(97, 1200, 30.0);
+
+        let first = packetizer.packetize(&KEYFRAME);
+        let second = packetizer.packetize(&[0x31, 0x0A, 0x00]);
+
+        let picture_id = |p: &RtpPacket| u16::from_be_bytes([p.payload[2] & 0x7F, p.payload[3]]);
+        assert_eq!(picture_id(&second[0]), (picture_id(&first[0]) + 1) & 0x7FFF);
+        assert_eq!(
+            second[0]
+                .header
+                .timestamp
+                .wrapping_sub(first[0].header.timestamp),
+            3000
+        );
+    }
+}
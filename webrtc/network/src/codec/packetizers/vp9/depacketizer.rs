@@ -0,0 +1,331 @@
+//! VP9 RTP Depacketizer Implementation
+//!
+//! Reconstructs VP9 frames from RTP packets according to RFC 9628.
+//!
+//! # Payload Descriptor
+//! The descriptor is parsed and stripped from every packet. Optional fields
+//! (picture ID, layer indices, reference indices and the scalability
+//! structure) are skipped; only the B/E bits drive reassembly.
+//!
+//! # Packet Loss Handling
+//! - A sequence gap or a fragment without a preceding start discards the frame
+//! - Timestamp changes with an incomplete buffer also discard stale data
+//! - After loss, inter frames (P bit set) are dropped and a keyframe request
+//!   (PLI) is raised until the next keyframe arrives
+
+use super::{FLAG_B, FLAG_E, FLAG_F, FLAG_I, FLAG_L, FLAG_P, FLAG_V, PICTURE_ID_M};
+use crate::codec::rtp::RtpPacket;
+use crate::traits::RtpDepacketizer;
+
+/// Parsed fields of a VP9 payload descriptor
+struct PayloadDescriptor {
+    /// Inter-picture predicted frame
+    inter_predicted: bool,
+    /// First packet of a frame
+    start: bool,
+    /// Last packet of a frame
+    end: bool,
+    /// Offset of the VP9 data within the RTP payload
+    header_size: usize,
+}
+
+/// Represents a VP9 RTP depacketizer
+pub struct Vp9RtpDepacketizer {
+    /// Current RTP timestamp for tracking frame boundaries
+    current_timestamp: Option<u32>,
+    /// Buffer for reassembling fragmented frames
+    frame_buffer: Vec<u8>,
+    /// True while the current frame is inter-predicted
+    frame_inter_predicted: bool,
+    /// Sequence number of the last processed packet (for loss detection)
+    last_sequence: Option<u16>,
+    /// True until a keyframe is received (stream start or after loss)
+    waiting_for_keyframe: bool,
+    /// Set when the decoder cannot continue without a new keyframe
+    keyframe_request_pending: bool,
+}
+
+impl Vp9RtpDepacketizer {
+    /// Create a new VP9 RTP depacketizer
+    ///
+    /// # Returns
+    /// New depacketizer instance with empty buffers
+    pub fn new() -> Self {
+        Vp9RtpDepacketizer {
+            current_timestamp: None,
+            frame_buffer: Vec::new(),
+            frame_inter_predicted: false,
+            last_sequence: None,
+            waiting_for_keyframe: true,
+            keyframe_request_pending: false,
+        }
+    }
+
+    /// Returns true once if a keyframe should be requested from the sender (PLI)
+    pub fn take_keyframe_request(&mut self) -> bool {
+        std::mem::take(&mut self.keyframe_request_pending)
+    }
+
+    /// Check if the depacketizer is dropping frames until the next keyframe
+    pub fn is_waiting_for_keyframe(&self) -> bool {
+        self.waiting_for_keyframe
+    }
+
+    /// Mark the reference chain as broken after detected loss
+    fn mark_loss(&mut self) {
+        self.frame_buffer.clear();
+        self.waiting_for_keyframe = true;
+    }
+
+    /// Track sequence numbers and flag gaps as loss
+    fn check_sequence(&mut self, sequence_number: u16) {
+        if let Some(last) = self.last_sequence
+            && sequence_number != last.wrapping_add(1)
+            && sequence_number != last
+        {
+            self.mark_loss();
+        }
+        self.last_sequence = Some(sequence_number);
+    }
+
+    /// Filter a complete frame according to keyframe state
+    fn filter_complete_frame(&mut self, frame: Vec<u8>) -> Option<Vec<u8>> {
+        if !self.frame_inter_predicted {
+            self.waiting_for_keyframe = false;
+            return Some(frame);
+        }
+
+        if self.waiting_for_keyframe {
+            self.keyframe_request_pending = true;
+            return None;
+        }
+
+        Some(frame)
+    }
+}
+
+/// Parse the payload descriptor at the start of a VP9 RTP payload
+///
+/// # Returns
+/// - `Some(PayloadDescriptor)` - Parsed descriptor
+/// - `None` - Payload is truncated
+fn parse_descriptor(payload: &[u8]) -> Option<PayloadDescriptor> {
+    let flags = *payload.first()?;
+    let flexible = flags & FLAG_F != 0;
+    let mut offset = 1;
+
+    if flags & FLAG_I != 0 {
+        let picture_id = *payload.get(offset)?;
+        offset += if picture_id & PICTURE_ID_M != 0 { 2 } else { 1 };
+    }
+
+    if flags & FLAG_L != 0 {
+        // TID/U/SID/D, plus TL0PICIDX in non-flexible mode
+        offset += if flexible { 1 } else { 2 };
+    }
+
+    if flexible && flags & FLAG_P != 0 {
+        // Up to three P_DIFF bytes, N bit signals another follows
+        for _ in 0..3 {
+            let p_diff = *payload.get(offset)?;
+            offset += 1;
+            if p_diff & 0x01 == 0 {
+                break;
+            }
+        }
+    }
+
+    if flags & FLAG_V != 0 {
+        offset = skip_scalability_structure(payload, offset)?;
+    }
+
+    if offset > payload.len() {
+        return None;
+    }
+
+    Some(PayloadDescriptor {
+        inter_predicted: flags & FLAG_P != 0,
+        start: flags & FLAG_B != 0,
+        end: flags & FLAG_E != 0,
+        header_size: offset,
+    })
+}
+
+/// Skip the scalability structure (SS) and return the offset past it
+fn skip_scalability_structure(payload: &[u8], mut offset: usize) -> Option<usize> {
+    let header = *payload.get(offset)?;
+    offset += 1;
+
+    let spatial_layers = ((header >> 5) & 0x07) as usize + 1;
+    if header & 0x10 != 0 {
+        // WIDTH and HEIGHT per spatial layer
+        offset += spatial_layers * 4;
+    }
+
+    if header & 0x08 != 0 {
+        let pictures = *payload.get(offset)?;
+        offset += 1;
+        for _ in 0..pictures {
+            let picture = *payload.get(offset)?;
+            let references = ((picture >> 2) & 0x03) as usize;
+            offset += 1 + references;
+        }
+    }
+
+    Some(offset)
+}
+
+impl RtpDepacketizer for Vp9RtpDepacketizer {
+    fn process_packet(&mut self, packet: &RtpPacket) -> Option<Vec<u8>> {
+        let timestamp = packet.header.timestamp;
+        let descriptor = parse_descriptor(&packet.payload)?;
+
+        self.check_sequence(packet.header.sequence_number);
+
+        // Timestamp changed with incomplete buffer - discard stale data
+        if let Some(current_ts) = self.current_timestamp
+            && timestamp != current_ts
+            && !self.frame_buffer.is_empty()
+        {
+            self.mark_loss();
+        }
+
+        if descriptor.start {
+            self.frame_buffer.clear();
+            self.current_timestamp = Some(timestamp);
+            self.frame_inter_predicted = descriptor.inter_predicted;
+        } else if self.frame_buffer.is_empty() {
+            // Continuation without a start packet: the start was lost
+            self.mark_loss();
+            return None;
+        }
+
+        self.frame_buffer
+            .extend_from_slice(&packet.payload[descriptor.header_size..]);
+
+        if !descriptor.end {
+            return None;
+        }
+
+        let frame = std::mem::take(&mut self.frame_buffer);
+        self.filter_complete_frame(frame)
+    }
+
+    fn reset(&mut self) {
+        self.current_timestamp = None;
+        self.frame_buffer.clear();
+        self.frame_inter_predicted = false;
+        self.last_sequence = None;
+        self.waiting_for_keyframe = true;
+        self.keyframe_request_pending = false;
+    }
+
+    fn has_pending_data(&self) -> bool {
+        !self.frame_buffer.is_empty()
+    }
+}
+
+impl Default for Vp9RtpDepacketizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::packetizers::vp9::Vp9RtpPacketizer;
+    use crate::codec::rtp::RtpHeader;
+    use crate::traits::RtpPacketizer;
+
+    /// Synthetic keyframe: valid uncompressed header followed by filler
+    fn keyframe(size: usize) -> Vec<u8> {
+        let mut frame = vec![0x82, 0x49, 0x83, 0x42, 0x00];
+        frame.extend((0..size - frame.len()).map(|i| (i % 251) as u8));
+        frame
+    }
+
+    #[test]
+    fn test_packetize_depacketize_multi_packet_frame() {
+        let mut packetizer = Vp9RtpPacketizer::new(98, 1200, 30.0);
+        let mut depacketizer = Vp9RtpDepacketizer::new();
+        let frame = keyframe(3000);
+
+        let packets = packetizer.packetize(&frame);
+        assert_eq!(packets.len(), 3);
+
+        let mut output = None;
+        for (i, packet) in packets.iter().enumerate() {
+            let bytes = packet.to_bytes();
+            let received = RtpPacket::from_bytes(&bytes).unwrap();
+            output = depacketizer.process_packet(&received);
+            if i < packets.len() - 1 {
+                assert!(output.is_none());
+                assert!(depacketizer.has_pending_data());
+            }
+        }
+
+        assert_eq!(output, Some(frame));
+        assert!(!depacketizer.is_waiting_for_keyframe());
+    }
+
+    #[test]
+    fn test_lost_packet_drops_frame_and_requests_keyframe() {
+        let mut packetizer = Vp9RtpPacketizer::new(98, 1200, 30.0);
+        let mut depacketizer = Vp9RtpDepacketizer::new();
+
+        let key_packets = packetizer.packetize(&keyframe(2000));
+        for packet in &key_packets {
+            depacketizer.process_packet(packet);
+        }
+        assert!(!depacketizer.is_waiting_for_keyframe());
+
+        // Inter frame with its middle packet lost
+        let mut inter = vec![0x86];
+        inter.extend(vec![0xAB; 2999]);
+        let inter_packets = packetizer.packetize(&inter);
+        assert_eq!(inter_packets.len(), 3);
+
+        assert!(depacketizer.process_packet(&inter_packets[0]).is_none());
+        assert!(depacketizer.process_packet(&inter_packets[2]).is_none());
+        assert!(depacketizer.is_waiting_for_keyframe());
+
+        // Next inter frame is useless without a keyframe
+        for packet in packetizer.packetize(&[0x86, 0x01, 0x02]) {
+            assert!(depacketizer.process_packet(&packet).is_none());
+        }
+        assert!(depacketizer.take_keyframe_request());
+        assert!(!depacketizer.take_keyframe_request());
+    }
+
+    #[test]
+    fn test_parse_descriptor_with_layers_and_scalability_structure() {
+        let mut header = RtpHeader::new(98, 1234);
+        header.marker = true;
+
+        let payload = vec![
+            FLAG_I | FLAG_L | FLAG_B | FLAG_E | FLAG_V,
+            0x05, // 7-bit picture ID
+            0x00, // TID/U/SID/D
+            0x00, // TL0PICIDX
+            0x18, // SS: N_S=0, Y=1, G=1
+            0x02,
+            0x80,
+            0x01,
+            0xE0, // 640x480
+            0x01, // N_G=1
+            0x04, // one reference
+            0x01, // P_DIFF
+            0x82,
+            0x49,
+            0x83,
+        ];
+        let packet = RtpPacket::new(header, payload);
+
+        let mut depacketizer = Vp9RtpDepacketizer::new();
+        assert_eq!(
+            depacketizer.process_packet(&packet),
+            Some(vec![0x82, 0x49, 0x83])
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! VP9 RTP Packetization (RFC 9628)
+//!
+//! This module implements RTP packetization and depacketization for VP9 video
+//! using the non-flexible payload descriptor mode.
+
+mod depacketizer;
+mod packetizer;
+
+pub use depacketizer::Vp9RtpDepacketizer;
+pub use packetizer::Vp9RtpPacketizer;
+
+/// Descriptor flag: picture ID present
+const FLAG_I: u8 = 0x80;
+/// Descriptor flag: inter-picture predicted frame
+const FLAG_P: u8 = 0x40;
+/// Descriptor flag: layer indices present
+const FLAG_L: u8 = 0x20;
+/// Descriptor flag: flexible mode
+const FLAG_F: u8 = 0x10;
+/// Descriptor flag: start of a frame
+const FLAG_B: u8 = 0x08;
+/// Descriptor flag: end of a frame
+const FLAG_E: u8 = 0x04;
+/// Descriptor flag: scalability structure present
+const FLAG_V: u8 = 0x02;
+/// Picture ID flag: 15-bit picture ID follows
+const PICTURE_ID_M: u8 = 0x80;
+
+/// Returns true if the VP9 frame is a keyframe
+///
+/// Reads `frame_type` from the uncompressed header (VP9 bitstream
+/// specification Section 6.2).
+pub(crate) fn is_keyframe(frame: &[u8]) -> bool {
+    let bit = |index: usize| {
+        frame
+            .get(index / 8)
+            .map(|byte| (byte >> (7 - index % 8)) & 1)
+    };
+
+    // frame_marker must be 0b10
+    if frame.first().map(|b| b >> 6) != Some(0b10) {
+        return false;
+    }
+
+    let profile = bit(2).unwrap_or(0) | (bit(3).unwrap_or(0) << 1);
+    let mut index = if profile == 3 { 5 } else { 4 };
+
+    // show_existing_frame repeats an old frame and carries no frame_type
+    if bit(index) != Some(0) {
+        return false;
+    }
+    index += 1;
+
+    bit(index) == Some(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_keyframe() {
+        // frame_marker=10, profile 0, show_existing=0, frame_type=0
+        assert!(is_keyframe(&[0x82, 0x49, 0x83]));
+        // frame_type=1 (inter frame)
+        assert!(!is_keyframe(&[0x86, 0x00]));
+        // show_existing_frame=1
+        assert!(!is_keyframe(&[0x88]));
+        assert!(!is_keyframe(&[]));
+    }
+}
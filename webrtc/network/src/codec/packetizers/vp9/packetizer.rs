@@ -0,0 +1,190 @@
+//! VP9 RTP Packetizer Implementation
+//!
+//! Implements RFC 9628 - RTP Payload Format for VP9 Video, non-flexible mode
+//! without spatial layers.
+//!
+//! Every packet starts with a payload descriptor followed by a fragment of the
+//! VP9 frame. Frames larger than the MTU are split across packets, with the B
+//! bit on the first fragment and the E bit on the last.
+//!
+//! ```text
+//!  0 1 2 3 4 5 6 7
+//! +-+-+-+-+-+-+-+-+
+//! |I|P|L|F|B|E|V|Z| (REQUIRED)
+//! +-+-+-+-+-+-+-+-+
+//! |M| PICTURE ID  | (RECOMMENDED, I=1)
+//! +-+-+-+-+-+-+-+-+
+//! |   EXTENDED    | (M=1: 15-bit picture ID)
+//! +-+-+-+-+-+-+-+-+
+//! ```
+//! - I: Picture ID present (always set)
+//! - P: Inter-picture predicted frame (cleared on keyframes)
+//! - B/E: Start/end of a frame
+//! - L, F, V, Z: Unused (no layers, non-flexible mode)
+
+use super::{FLAG_B, FLAG_E, FLAG_I, FLAG_P, PICTURE_ID_M, is_keyframe};
+use crate::codec::rtp::{RtpHeader, RtpPacket};
+use crate::traits::RtpPacketizer;
+use rand::Rng;
+
+/// Payload descriptor size: flags + 15-bit picture ID
+const DESCRIPTOR_SIZE: usize = 3;
+
+/// Represents a VP9 RTP packetizer
+pub struct Vp9RtpPacketizer {
+    /// Synchronization source identifier (randomly generated)
+    ssrc: u32,
+    /// RTP sequence number (incremented per packet)
+    sequence_number: u16,
+    /// RTP timestamp in 90 kHz clock units
+    timestamp: u32,
+    /// RTP payload type (typically 98 for dynamic VP9)
+    payload_type: u8,
+    /// Maximum RTP payload size in bytes (MTU - RTP header)
+    max_payload_size: usize,
+    /// Timestamp increment per frame (90000 / fps)
+    timestamp_increment: u32,
+    /// 15-bit picture ID, incremented per frame
+    picture_id: u16,
+}
+
+impl Vp9RtpPacketizer {
+    /// Create a new VP9 RTP packetizer
+    ///
+    /// # Arguments
+    /// * `payload_type` - RTP payload type (96-127 for dynamic mappings)
+    /// * `max_payload_size` - Maximum payload size in bytes, descriptor included
+    /// * `fps` - Video frame rate for timestamp calculation
+    pub fn new(payload_type: u8, max_payload_size: usize, fps: f64) -> Self {
+        let mut rng = rand::thread_rng();
+
+        Vp9RtpPacketizer {
+            ssrc: rng.gen_range(0..=u32::MAX),
+            sequence_number: rng.gen_range(0..=u16::MAX),
+            timestamp: 0,
+            payload_type,
+            max_payload_size,
+            timestamp_increment: (90000.0 / fps).round() as u32,
+            picture_id: rng.gen_range(0..=0x7FFF),
+        }
+    }
+
+    /// Sends with the given SSRC instead of a random one
+    ///
+    /// Used to keep the SSRC of a stream when its codec changes.
+    pub fn with_ssrc(mut self, ssrc: u32) -> Self {
+        self.ssrc = ssrc;
+        self
+    }
+
+    fn build_descriptor(&self, inter_predicted: bool, is_first: bool, is_last: bool) -> [u8; 3] {
+        let mut flags = FLAG_I;
+        if inter_predicted {
+            flags |= FLAG_P;
+        }
+        if is_first {
+            flags |= FLAG_B;
+        }
+        if is_last {
+            flags |= FLAG_E;
+        }
+
+        [
+            flags,
+            PICTURE_ID_M | (self.picture_id >> 8) as u8,
+            self.picture_id as u8,
+        ]
+    }
+}
+
+impl RtpPacketizer for Vp9RtpPacketizer {
+    fn packetize(&mut self, data: &[u8]) -> Vec<RtpPacket> {
+        if data.is_empty() {
+            return Vec::new();
+        }
+
+        let inter_predicted = !is_keyframe(data);
+        let fragment_size = self.max_payload_size.saturating_sub(DESCRIPTOR_SIZE).max(1);
+        let fragments: Vec<&[u8]> = data.chunks(fragment_size).collect();
+        let mut packets = Vec::with_capacity(fragments.len());
+
+        for (i, fragment) in fragments.iter().enumerate() {
+            let is_first = i == 0;
+            let is_last = i == fragments.len() - 1;
+
+            let mut payload = Vec::with_capacity(DESCRIPTOR_SIZE + fragment.len());
+            payload.extend_from_slice(&self.build_descriptor(inter_predicted, is_first, is_last));
+            payload.extend_from_slice(fragment);
+
+            let mut header = RtpHeader::new(self.payload_type, self.ssrc);
+            header.sequence_number = self.sequence_number;
+            header.timestamp = self.timestamp;
+            header.marker = is_last; // Last packet of the picture
+
+            self.sequence_number = self.sequence_number.wrapping_add(1);
+            packets.push(RtpPacket::new(header, payload));
+        }
+
+        self.picture_id = (self.picture_id + 1) & 0x7FFF;
+        self.timestamp = self.timestamp.wrapping_add(self.timestamp_increment);
+
+        packets
+    }
+
+    fn get_payload_type(&self) -> u8 {
+        self.payload_type
+    }
+
+    fn get_ssrc(&self) -> u32 {
+        self.ssrc
+    }
+
+    fn get_timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    fn get_sequence_number(&self) -> u16 {
+        self.sequence_number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_frame_single_packet() {
+        let mut packetizer = Vp9RtpPacketizer::new(98, 1200, 30.0);
+
+        let packets = packetizer.packetize(&[0x82, 0x49, 0x83, 0x42, 0x00]);
+
+        assert_eq!(packets.len(), 1);
+        let descriptor = packets[0].payload[0];
+        assert_eq!(descriptor & (FLAG_B | FLAG_E), FLAG_B | FLAG_E);
+        assert_eq!(descriptor & FLAG_P, 0); // Keyframe
+        assert!(packets[0].header.marker);
+        assert_eq!(
+            &packets[0].payload[DESCRIPTOR_SIZE..],
+            &[0x82, 0x49, 0x83, 0x42, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_picture_id_and_timestamp_advance() {
+        let mut packetizer = Vp9RtpPacketizer::new(98, 1200, 30.0);
+
+        let first = packetizer.packetize(&[0x86, 0x01]);
+        let second = packetizer.packetize(&[0x86, 0x02]);
+
+        let picture_id = |p: &RtpPacket| u16::from_be_bytes([p.payload[1] & 0x7F, p.payload[2]]);
+        assert_eq!(picture_id(&second[0]), (picture_id(&first[0]) + 1) & 0x7FFF);
+        assert_eq!(
+            second[0]
+                .header
+                .timestamp
+                .wrapping_sub(first[0].header.timestamp),
+            3000
+        );
+        assert_ne!(first[0].payload[0] & FLAG_P, 0); // Inter frame
+    }
+}
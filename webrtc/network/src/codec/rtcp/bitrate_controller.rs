@@ -0,0 +1,159 @@
+//! Loss-based send bitrate adaptation
+//!
+//! Additive-increase / multiplicative-decrease (AIMD) driven by the
+//! fraction lost the remote peer reports in RTCP Receiver Reports.
+
+use super::stats::RtcpStats;
+
+/// Loss above which the target bitrate is reduced (10%)
+const HIGH_LOSS_FRACTION: f64 = 0.10;
+
+/// Loss below which the report counts towards a ramp-up (2%)
+const LOW_LOSS_FRACTION: f64 = 0.02;
+
+/// Consecutive low-loss reports required before increasing
+const CLEAN_REPORTS_BEFORE_INCREASE: u32 = 3;
+
+/// Additive increase per step, as a fraction of the maximum bitrate
+const INCREASE_STEP_FRACTION: f64 = 0.05;
+
+/// Congestion controller that turns receiver-reported loss into a target bitrate
+#[derive(Debug, Clone)]
+pub struct BitrateController {
+    target_bitrate: u32,
+    min_bitrate: u32,
+    max_bitrate: u32,
+    /// Consecutive reports with loss under `LOW_LOSS_FRACTION`
+    clean_reports: u32,
+    /// `RtcpStats::receiver_reports_received` at the last update
+    reports_seen: u32,
+}
+
+impl BitrateController {
+    /// Create a controller starting at `initial_bitrate` (bits per second)
+    pub fn new(initial_bitrate: u32, min_bitrate: u32, max_bitrate: u32) -> Self {
+        let max_bitrate = max_bitrate.max(min_bitrate);
+        Self {
+            target_bitrate: initial_bitrate.clamp(min_bitrate, max_bitrate),
+            min_bitrate,
+            max_bitrate,
+            clean_reports: 0,
+            reports_seen: 0,
+        }
+    }
+
+    /// Current target bitrate in bits per second
+    pub fn target_bitrate(&self) -> u32 {
+        self.target_bitrate
+    }
+
+    /// Process a new Receiver Report from `stats`, if one arrived since the last call
+    ///
+    /// Returns the new target bitrate when it changed.
+    pub fn update(&mut self, stats: &RtcpStats) -> Option<u32> {
+        if stats.receiver_reports_received == self.reports_seen {
+            return None;
+        }
+        self.reports_seen = stats.receiver_reports_received;
+
+        let previous = self.target_bitrate;
+        let target = self.on_loss_report(stats.remote_fraction_lost);
+        (target != previous).then_some(target)
+    }
+
+    /// Apply one loss report (RTCP fraction lost, 0-255) and return the target bitrate
+    pub fn on_loss_report(&mut self, fraction_lost: u8) -> u32 {
+        let loss = fraction_lost as f64 / 256.0;
+
+        if loss > HIGH_LOSS_FRACTION {
+            // Multiplicative decrease, deeper for heavier loss
+            self.clean_reports = 0;
+            let reduced = self.target_bitrate as f64 * (1.0 - 0.5 * loss);
+            self.target_bitrate = (reduced as u32).max(self.min_bitrate);
+        } else if loss < LOW_LOSS_FRACTION {
+            self.clean_reports += 1;
+            if self.clean_reports >= CLEAN_REPORTS_BEFORE_INCREASE {
+                // Additive increase once the path has been clean for a while
+                let step = (self.max_bitrate as f64 * INCREASE_STEP_FRACTION) as u32;
+                self.target_bitrate = self
+                    .target_bitrate
+                    .saturating_add(step.max(1))
+                    .min(self.max_bitrate);
+            }
+        } else {
+            // Moderate loss: hold the current rate
+            self.clean_reports = 0;
+        }
+
+        self.target_bitrate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loss_percent(percent: u32) -> u8 {
+        (percent * 256 / 100).min(255) as u8
+    }
+
+    #[test]
+    fn test_rising_loss_decreases_bitrate_monotonically() {
+        let mut controller = BitrateController::new(2_000_000, 100_000, 5_000_000);
+        let mut previous = controller.target_bitrate();
+
+        for percent in [12, 15, 20, 30, 45, 60] {
+            let target = controller.on_loss_report(loss_percent(percent));
+            assert!(target < previous);
+            previous = target;
+        }
+    }
+
+    #[test]
+    fn test_bitrate_bounded_by_min_and_max() {
+        let mut controller = BitrateController::new(300_000, 250_000, 400_000);
+
+        for _ in 0..20 {
+            controller.on_loss_report(loss_percent(50));
+        }
+        assert_eq!(controller.target_bitrate(), 250_000);
+
+        for _ in 0..100 {
+            controller.on_loss_report(0);
+        }
+        assert_eq!(controller.target_bitrate(), 400_000);
+    }
+
+    #[test]
+    fn test_ramp_up_requires_sustained_clean_window() {
+        let mut controller = BitrateController::new(1_000_000, 100_000, 2_000_000);
+
+        for _ in 0..CLEAN_REPORTS_BEFORE_INCREASE - 1 {
+            assert_eq!(controller.on_loss_report(0), 1_000_000);
+        }
+        // Moderate loss resets the window
+        assert_eq!(controller.on_loss_report(loss_percent(5)), 1_000_000);
+        assert_eq!(controller.on_loss_report(0), 1_000_000);
+
+        for _ in 0..CLEAN_REPORTS_BEFORE_INCREASE {
+            controller.on_loss_report(0);
+        }
+        assert!(controller.target_bitrate() > 1_000_000);
+    }
+
+    #[test]
+    fn test_update_only_consumes_new_reports() {
+        let mut controller = BitrateController::new(1_000_000, 100_000, 2_000_000);
+        let mut stats = RtcpStats::new(1);
+
+        assert_eq!(controller.update(&stats), None);
+
+        stats.remote_fraction_lost = loss_percent(25);
+        stats.receiver_reports_received = 1;
+        let reduced = controller.update(&stats).expect("bitrate should drop");
+        assert!(reduced < 1_000_000);
+
+        // Same report seen again: no further change
+        assert_eq!(controller.update(&stats), None);
+    }
+}
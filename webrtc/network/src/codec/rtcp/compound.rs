@@ -0,0 +1,287 @@
+//! RTCP compound packets (RFC 3550 Section 6.1)
+//!
+//! RTCP packets are sent stacked in a single datagram, e.g. a Sender Report
+//! followed by a Source Description. Each packet carries its own length, so
+//! the datagram is split by walking the headers one after another.
+
+use super::feedback::{self, FIR_FMT, PLI_FMT};
+use super::transport_cc::{self, TRANSPORT_CC_FMT};
+use super::{
+    ByePacket, FullIntraRequest, PictureLossIndication, ReceiverReport, RtcpPacketType, SdesPacket,
+    SenderReport, TransportCcFeedback,
+};
+
+/// Size of the common RTCP header in bytes
+const RTCP_HEADER_SIZE: usize = 4;
+
+/// A single RTCP packet inside a compound packet
+#[derive(Debug, Clone)]
+pub enum RtcpPacket {
+    SenderReport(SenderReport),
+    ReceiverReport(ReceiverReport),
+    SourceDescription(SdesPacket),
+    Bye(ByePacket),
+    PictureLossIndication(PictureLossIndication),
+    FullIntraRequest(FullIntraRequest),
+    TransportCc(TransportCcFeedback),
+    /// Packet type this implementation does not decode, kept as raw bytes
+    Unknown {
+        packet_type: u8,
+        data: Vec<u8>,
+    },
+}
+
+impl RtcpPacket {
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            RtcpPacket::SenderReport(sr) => sr.to_bytes(),
+            RtcpPacket::ReceiverReport(rr) => rr.to_bytes(),
+            RtcpPacket::SourceDescription(sdes) => sdes.to_bytes(),
+            RtcpPacket::Bye(bye) => bye.to_bytes(),
+            RtcpPacket::PictureLossIndication(pli) => pli.to_bytes(),
+            RtcpPacket::FullIntraRequest(fir) => fir.to_bytes(),
+            RtcpPacket::TransportCc(feedback) => feedback.to_bytes(),
+            RtcpPacket::Unknown { data, .. } => data.clone(),
+        }
+    }
+
+    /// Parse a single RTCP packet
+    ///
+    /// `data` must hold exactly one packet, as delimited by its length field.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() < RTCP_HEADER_SIZE {
+            return Err("RTCP packet too short".to_string());
+        }
+
+        let packet = match RtcpPacketType::from_u8(data[1]) {
+            Some(RtcpPacketType::SR) => RtcpPacket::SenderReport(SenderReport::from_bytes(data)?),
+            Some(RtcpPacketType::RR) => {
+                RtcpPacket::ReceiverReport(ReceiverReport::from_bytes(data)?)
+            }
+            Some(RtcpPacketType::SDES) => {
+                RtcpPacket::SourceDescription(SdesPacket::from_bytes(data)?)
+            }
+            Some(RtcpPacketType::BYE) => RtcpPacket::Bye(ByePacket::from_bytes(data)?),
+            Some(RtcpPacketType::PSFB) => match feedback::payload_feedback_fmt(data) {
+                Some(PLI_FMT) => {
+                    RtcpPacket::PictureLossIndication(PictureLossIndication::from_bytes(data)?)
+                }
+                Some(FIR_FMT) => RtcpPacket::FullIntraRequest(FullIntraRequest::from_bytes(data)?),
+                _ => Self::unknown(data),
+            },
+            Some(RtcpPacketType::RTPFB) => match transport_cc::transport_feedback_fmt(data) {
+                Some(TRANSPORT_CC_FMT) => {
+                    RtcpPacket::TransportCc(TransportCcFeedback::from_bytes(data)?)
+                }
+                _ => Self::unknown(data),
+            },
+            _ => Self::unknown(data),
+        };
+
+        Ok(packet)
+    }
+
+    /// Returns the RTCP packet type byte
+    pub fn packet_type(&self) -> u8 {
+        match self {
+            RtcpPacket::SenderReport(_) => RtcpPacketType::SR as u8,
+            RtcpPacket::ReceiverReport(_) => RtcpPacketType::RR as u8,
+            RtcpPacket::SourceDescription(_) => RtcpPacketType::SDES as u8,
+            RtcpPacket::Bye(_) => RtcpPacketType::BYE as u8,
+            RtcpPacket::PictureLossIndication(_) | RtcpPacket::FullIntraRequest(_) => {
+                RtcpPacketType::PSFB as u8
+            }
+            RtcpPacket::TransportCc(_) => RtcpPacketType::RTPFB as u8,
+            RtcpPacket::Unknown { packet_type, .. } => *packet_type,
+        }
+    }
+
+    fn unknown(data: &[u8]) -> Self {
+        RtcpPacket::Unknown {
+            packet_type: data[1],
+            data: data.to_vec(),
+        }
+    }
+}
+
+/// Several RTCP packets sent together in one datagram
+#[derive(Debug, Clone, Default)]
+pub struct CompoundRtcpPacket {
+    pub packets: Vec<RtcpPacket>,
+}
+
+impl CompoundRtcpPacket {
+    /// Create an empty compound packet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a packet (builder style)
+    pub fn with_packet(mut self, packet: RtcpPacket) -> Self {
+        self.packets.push(packet);
+        self
+    }
+
+    /// Append a packet
+    pub fn push(&mut self, packet: RtcpPacket) {
+        self.packets.push(packet);
+    }
+
+    /// Serialize all packets back to back into one datagram
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.packets.iter().flat_map(RtcpPacket::to_bytes).collect()
+    }
+
+    /// Split a datagram into its RTCP packets
+    ///
+    /// Every packet must have version 2 and a length that fits in the datagram.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let mut packets = Vec::new();
+        let mut offset = 0;
+
+        while offset < data.len() {
+            let packet_len = packet_length(&data[offset..])?;
+            packets.push(RtcpPacket::from_bytes(&data[offset..offset + packet_len])?);
+            offset += packet_len;
+        }
+
+        if packets.is_empty() {
+            return Err("Empty RTCP compound packet".to_string());
+        }
+
+        Ok(Self { packets })
+    }
+}
+
+/// Total size in bytes of the RTCP packet at the start of `data`
+fn packet_length(data: &[u8]) -> Result<usize, String> {
+    if data.len() < RTCP_HEADER_SIZE {
+        return Err("RTCP header truncated".to_string());
+    }
+    if data[0] >> 6 != 2 {
+        return Err("Invalid RTCP version".to_string());
+    }
+
+    let length_words = crate::codec::rtp::parse_u16_be(data, 2) as usize;
+    let packet_len = (length_words + 1) * 4;
+    if packet_len > data.len() {
+        return Err("RTCP packet length exceeds datagram".to_string());
+    }
+
+    Ok(packet_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::rtcp::RtcpStats;
+
+    fn sender_report_with_block() -> SenderReport {
+        let mut stats = RtcpStats::new(4242);
+        stats.packets_sent = 10;
+        stats.bytes_sent = 1000;
+        stats.last_rtp_timestamp = 90000;
+
+        let mut sr = SenderReport::new(&stats);
+        sr.report_blocks = ReceiverReport::new(&RtcpStats::new(4242), 7777).report_blocks;
+        sr
+    }
+
+    #[test]
+    fn test_sr_and_sdes_round_trip() {
+        let sr = sender_report_with_block();
+        let sdes = SdesPacket::new(4242, "user@host");
+
+        let compound = CompoundRtcpPacket::new()
+            .with_packet(RtcpPacket::SenderReport(sr.clone()))
+            .with_packet(RtcpPacket::SourceDescription(sdes.clone()));
+
+        let bytes = compound.to_bytes();
+        assert_eq!(bytes.len(), 28 + 24 + sdes.to_bytes().len());
+
+        let parsed = CompoundRtcpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.packets.len(), 2);
+
+        match &parsed.packets[0] {
+            RtcpPacket::SenderReport(parsed_sr) => {
+                assert_eq!(parsed_sr.ssrc, sr.ssrc);
+                assert_eq!(parsed_sr.rtp_timestamp, sr.rtp_timestamp);
+                assert_eq!(parsed_sr.report_blocks.len(), 1);
+                assert_eq!(parsed_sr.report_blocks[0].ssrc, 7777);
+            }
+            other => panic!("Expected SR, got {:?}", other),
+        }
+
+        match &parsed.packets[1] {
+            RtcpPacket::SourceDescription(parsed_sdes) => assert_eq!(parsed_sdes, &sdes),
+            other => panic!("Expected SDES, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rr_bye_and_feedback_round_trip() {
+        let compound = CompoundRtcpPacket::new()
+            .with_packet(RtcpPacket::ReceiverReport(ReceiverReport::new(
+                &RtcpStats::new(1),
+                2,
+            )))
+            .with_packet(RtcpPacket::PictureLossIndication(
+                PictureLossIndication::new(1, 2),
+            ))
+            .with_packet(RtcpPacket::FullIntraRequest(FullIntraRequest::new(1, 2, 3)))
+            .with_packet(RtcpPacket::Bye(ByePacket::new(1, Some("bye".to_string()))));
+
+        let parsed = CompoundRtcpPacket::from_bytes(&compound.to_bytes()).unwrap();
+        let types: Vec<u8> = parsed.packets.iter().map(RtcpPacket::packet_type).collect();
+        assert_eq!(types, vec![201, 206, 206, 203]);
+
+        assert!(matches!(
+            &parsed.packets[2],
+            RtcpPacket::FullIntraRequest(fir) if fir.entries[0].sequence_number == 3
+        ));
+        assert!(matches!(
+            &parsed.packets[3],
+            RtcpPacket::Bye(bye) if bye.reason.as_deref() == Some("bye")
+        ));
+    }
+
+    #[test]
+    fn test_transport_cc_in_compound() {
+        let feedback = TransportCcFeedback {
+            sender_ssrc: 1,
+            media_ssrc: 2,
+            base_sequence: 10,
+            reference_time: 1,
+            feedback_count: 0,
+            arrivals: vec![Some(64_000), None, Some(65_000)],
+        };
+        let compound = CompoundRtcpPacket::new()
+            .with_packet(RtcpPacket::ReceiverReport(ReceiverReport::new(
+                &RtcpStats::new(1),
+                2,
+            )))
+            .with_packet(RtcpPacket::TransportCc(feedback.clone()));
+
+        let parsed = CompoundRtcpPacket::from_bytes(&compound.to_bytes()).unwrap();
+        assert_eq!(parsed.packets[1].packet_type(), 205);
+        assert!(matches!(
+            &parsed.packets[1],
+            RtcpPacket::TransportCc(parsed_feedback) if parsed_feedback == &feedback
+        ));
+    }
+
+    #[test]
+    fn test_rejects_truncated_compound() {
+        let bytes = CompoundRtcpPacket::new()
+            .with_packet(RtcpPacket::SenderReport(sender_report_with_block()))
+            .to_bytes();
+
+        assert!(CompoundRtcpPacket::from_bytes(&bytes[..bytes.len() - 4]).is_err());
+        assert!(CompoundRtcpPacket::from_bytes(&[]).is_err());
+
+        let mut bad_version = bytes.clone();
+        bad_version[0] &= 0x3F;
+        assert!(CompoundRtcpPacket::from_bytes(&bad_version).is_err());
+    }
+}
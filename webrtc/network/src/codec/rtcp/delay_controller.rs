@@ -0,0 +1,467 @@
+//! Delay-based send bitrate adaptation
+//!
+//! Transport-cc feedback tells the sender when each packet arrived. Packets
+//! are grouped into send bursts and the change in one-way delay between
+//! groups is fed to a trendline filter (as in Google Congestion Control): a
+//! rising trend means a queue is building up on the path, which is detected
+//! before it overflows into loss.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::transport_cc::TransportCcFeedback;
+
+/// Sent packets remembered for matching feedback
+const SEND_HISTORY_SIZE: usize = 4096;
+
+/// Packets sent within this interval form one group (microseconds)
+const BURST_INTERVAL_US: i64 = 5_000;
+
+/// Delay samples the trendline regression runs over
+const TRENDLINE_WINDOW: usize = 20;
+
+/// Smoothing factor of the accumulated delay
+const SMOOTHING_COEFFICIENT: f64 = 0.9;
+
+/// Gain applied to the trend before comparing it to the threshold
+const TRENDLINE_GAIN: f64 = 4.0;
+
+/// Cap on the number of deltas scaling the trend
+const MAX_DELTAS: usize = 60;
+
+/// Modified trend above which the path counts as overused (milliseconds)
+const OVERUSE_THRESHOLD_MS: f64 = 12.5;
+
+/// Consecutive over-threshold samples before signalling overuse
+const OVERUSE_SAMPLES: u32 = 2;
+
+/// Factor applied to the target bitrate on overuse
+const DECREASE_FACTOR: f64 = 0.85;
+
+/// Minimum time between two decreases (microseconds)
+const DECREASE_INTERVAL_US: i64 = 200_000;
+
+/// Multiplicative increase per second while the path is not congested
+const INCREASE_PER_SECOND: f64 = 0.08;
+
+/// Outcome of one sent packet, as reported by transport-cc feedback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketResult {
+    /// Transport-wide sequence number
+    pub sequence: u16,
+    /// Send time in microseconds on the sender's clock
+    pub send_time_us: i64,
+    /// Arrival time in microseconds on the receiver's clock, if received
+    pub arrival_us: Option<i64>,
+    /// Packet size in bytes
+    pub size: usize,
+}
+
+/// Send side of transport-wide congestion control
+///
+/// Hands out transport-wide sequence numbers and remembers when each packet
+/// was sent, to match the arrival times reported back.
+#[derive(Debug, Clone, Default)]
+pub struct SendHistory {
+    next_sequence: u16,
+    packets: HashMap<u16, (i64, usize)>,
+    order: VecDeque<u16>,
+}
+
+impl SendHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a sent packet and returns its transport-wide sequence number
+    pub fn on_packet_sent(&mut self, size: usize, send_time_us: i64) -> u16 {
+        let sequence = self.next_sequence;
+        self.next_sequence = self.next_sequence.wrapping_add(1);
+
+        if self.order.len() == SEND_HISTORY_SIZE
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.packets.remove(&oldest);
+        }
+        self.order.push_back(sequence);
+        self.packets.insert(sequence, (send_time_us, size));
+
+        sequence
+    }
+
+    /// Matches feedback against the history
+    ///
+    /// Packets no longer in the history are left out.
+    pub fn on_feedback(&self, feedback: &TransportCcFeedback) -> Vec<PacketResult> {
+        feedback
+            .packets()
+            .filter_map(|(sequence, arrival_us)| {
+                let &(send_time_us, size) = self.packets.get(&sequence)?;
+                Some(PacketResult {
+                    sequence,
+                    send_time_us,
+                    arrival_us,
+                    size,
+                })
+            })
+            .collect()
+    }
+}
+
+/// State of the network path as seen by the delay detector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandwidthUsage {
+    /// Delay is stable
+    Normal,
+    /// Delay is increasing: a queue is building up
+    Overusing,
+    /// Delay is decreasing: a queue is draining
+    Underusing,
+}
+
+/// Trendline filter over the one-way delay variation
+#[derive(Debug, Clone)]
+pub struct TrendlineEstimator {
+    accumulated_delay_ms: f64,
+    smoothed_delay_ms: f64,
+    first_arrival_ms: Option<f64>,
+    /// (arrival time, smoothed accumulated delay) samples
+    samples: VecDeque<(f64, f64)>,
+    num_deltas: usize,
+    trend: f64,
+    overuse_count: u32,
+    state: BandwidthUsage,
+}
+
+impl TrendlineEstimator {
+    pub fn new() -> Self {
+        Self {
+            accumulated_delay_ms: 0.0,
+            smoothed_delay_ms: 0.0,
+            first_arrival_ms: None,
+            samples: VecDeque::with_capacity(TRENDLINE_WINDOW),
+            num_deltas: 0,
+            trend: 0.0,
+            overuse_count: 0,
+            state: BandwidthUsage::Normal,
+        }
+    }
+
+    /// Current path state
+    pub fn state(&self) -> BandwidthUsage {
+        self.state
+    }
+
+    /// Adds the delay variation between two packet groups
+    ///
+    /// # Arguments
+    /// * `delay_variation_ms` - Arrival delta minus send delta of the groups
+    /// * `arrival_time_ms` - Arrival time of the newer group
+    pub fn update(&mut self, delay_variation_ms: f64, arrival_time_ms: f64) -> BandwidthUsage {
+        self.num_deltas = (self.num_deltas + 1).min(MAX_DELTAS);
+        let first_arrival_ms = *self.first_arrival_ms.get_or_insert(arrival_time_ms);
+
+        self.accumulated_delay_ms += delay_variation_ms;
+        self.smoothed_delay_ms = SMOOTHING_COEFFICIENT * self.smoothed_delay_ms
+            + (1.0 - SMOOTHING_COEFFICIENT) * self.accumulated_delay_ms;
+
+        if self.samples.len() == TRENDLINE_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples
+            .push_back((arrival_time_ms - first_arrival_ms, self.smoothed_delay_ms));
+
+        let previous_trend = self.trend;
+        if self.samples.len() == TRENDLINE_WINDOW
+            && let Some(slope) = linear_fit_slope(&self.samples)
+        {
+            self.trend = slope;
+        }
+
+        self.detect(previous_trend);
+        self.state
+    }
+
+    /// Compares the scaled trend against the overuse threshold
+    fn detect(&mut self, previous_trend: f64) {
+        let modified_trend = self.num_deltas as f64 * self.trend * TRENDLINE_GAIN;
+
+        if modified_trend > OVERUSE_THRESHOLD_MS {
+            self.overuse_count += 1;
+            // Only signal overuse while the trend keeps growing
+            if self.overuse_count >= OVERUSE_SAMPLES && self.trend >= previous_trend {
+                self.state = BandwidthUsage::Overusing;
+            }
+        } else if modified_trend < -OVERUSE_THRESHOLD_MS {
+            self.overuse_count = 0;
+            self.state = BandwidthUsage::Underusing;
+        } else {
+            self.overuse_count = 0;
+            self.state = BandwidthUsage::Normal;
+        }
+    }
+}
+
+impl Default for TrendlineEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Least-squares slope of `(x, y)` samples
+fn linear_fit_slope(samples: &VecDeque<(f64, f64)>) -> Option<f64> {
+    let n = samples.len() as f64;
+    let mean_x = samples.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = samples.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let (numerator, denominator) = samples.iter().fold((0.0, 0.0), |(num, den), (x, y)| {
+        (
+            num + (x - mean_x) * (y - mean_y),
+            den + (x - mean_x) * (x - mean_x),
+        )
+    });
+
+    (denominator != 0.0).then(|| numerator / denominator)
+}
+
+/// Packets sent in one burst, compared as a whole against the previous burst
+#[derive(Debug, Clone, Copy)]
+struct PacketGroup {
+    first_send_us: i64,
+    last_send_us: i64,
+    last_arrival_us: i64,
+}
+
+/// Congestion controller that turns transport-cc feedback into a target bitrate
+#[derive(Debug, Clone)]
+pub struct DelayBasedController {
+    target_bitrate: u32,
+    min_bitrate: u32,
+    max_bitrate: u32,
+    trendline: TrendlineEstimator,
+    current_group: Option<PacketGroup>,
+    previous_group: Option<PacketGroup>,
+    /// Receiver clock of the last rate update and the last decrease
+    last_update_us: Option<i64>,
+    last_decrease_us: Option<i64>,
+}
+
+impl DelayBasedController {
+    /// Create a controller starting at `initial_bitrate` (bits per second)
+    pub fn new(initial_bitrate: u32, min_bitrate: u32, max_bitrate: u32) -> Self {
+        let max_bitrate = max_bitrate.max(min_bitrate);
+        Self {
+            target_bitrate: initial_bitrate.clamp(min_bitrate, max_bitrate),
+            min_bitrate,
+            max_bitrate,
+            trendline: TrendlineEstimator::new(),
+            current_group: None,
+            previous_group: None,
+            last_update_us: None,
+            last_decrease_us: None,
+        }
+    }
+
+    /// Current target bitrate in bits per second
+    pub fn target_bitrate(&self) -> u32 {
+        self.target_bitrate
+    }
+
+    /// Current path state
+    pub fn state(&self) -> BandwidthUsage {
+        self.trendline.state()
+    }
+
+    /// Process the packet results of one feedback message
+    ///
+    /// Returns the new target bitrate when it changed.
+    pub fn on_packet_results(&mut self, results: &[PacketResult]) -> Option<u32> {
+        let mut received: Vec<&PacketResult> =
+            results.iter().filter(|r| r.arrival_us.is_some()).collect();
+        if received.is_empty() {
+            return None;
+        }
+        received.sort_by_key(|r| r.send_time_us);
+
+        let mut now_us = i64::MIN;
+        for result in received {
+            let arrival_us = result.arrival_us.unwrap_or_default();
+            now_us = now_us.max(arrival_us);
+            self.add_to_group(result.send_time_us, arrival_us);
+        }
+
+        let previous = self.target_bitrate;
+        let target = self.update_rate(now_us);
+        (target != previous).then_some(target)
+    }
+
+    /// Adds a packet to the current group, closing it when a new burst starts
+    fn add_to_group(&mut self, send_time_us: i64, arrival_us: i64) {
+        let Some(group) = self.current_group.as_mut() else {
+            self.current_group = Some(PacketGroup {
+                first_send_us: send_time_us,
+                last_send_us: send_time_us,
+                last_arrival_us: arrival_us,
+            });
+            return;
+        };
+
+        if send_time_us - group.first_send_us < BURST_INTERVAL_US {
+            group.last_send_us = group.last_send_us.max(send_time_us);
+            group.last_arrival_us = group.last_arrival_us.max(arrival_us);
+            return;
+        }
+
+        let finished = *group;
+        if let Some(previous) = self.previous_group {
+            let send_delta_ms = (finished.last_send_us - previous.last_send_us) as f64 / 1000.0;
+            let arrival_delta_ms =
+                (finished.last_arrival_us - previous.last_arrival_us) as f64 / 1000.0;
+            self.trendline.update(
+                arrival_delta_ms - send_delta_ms,
+                finished.last_arrival_us as f64 / 1000.0,
+            );
+        }
+        self.previous_group = Some(finished);
+        self.current_group = Some(PacketGroup {
+            first_send_us: send_time_us,
+            last_send_us: send_time_us,
+            last_arrival_us: arrival_us,
+        });
+    }
+
+    /// Decrease on overuse, hold on underuse, increase otherwise
+    fn update_rate(&mut self, now_us: i64) -> u32 {
+        let elapsed_secs = self
+            .last_update_us
+            .map_or(0.0, |last| ((now_us - last) as f64 / 1e6).clamp(0.0, 1.0));
+        self.last_update_us = Some(now_us);
+
+        match self.trendline.state() {
+            BandwidthUsage::Overusing => {
+                let can_decrease = self
+                    .last_decrease_us
+                    .is_none_or(|last| now_us - last >= DECREASE_INTERVAL_US);
+                if can_decrease {
+                    let reduced = self.target_bitrate as f64 * DECREASE_FACTOR;
+                    self.target_bitrate = (reduced as u32).max(self.min_bitrate);
+                    self.last_decrease_us = Some(now_us);
+                }
+            }
+            BandwidthUsage::Underusing => {
+                // Let the queue drain before probing for more
+            }
+            BandwidthUsage::Normal => {
+                let increased =
+                    self.target_bitrate as f64 * (1.0 + INCREASE_PER_SECOND * elapsed_secs);
+                self.target_bitrate = (increased as u32).min(self.max_bitrate);
+            }
+        }
+
+        self.target_bitrate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Packets sent every 10ms; the one-way delay grows by `delay_growth_us`
+    /// per packet. Feedback covers 10 packets at a time.
+    fn run_path(controller: &mut DelayBasedController, delay_growth_us: i64, packets: i64) {
+        let mut history = SendHistory::new();
+        let mut arrivals = Vec::new();
+        let mut base_sequence = 0;
+
+        for i in 0..packets {
+            let send_time_us = i * 10_000;
+            let sequence = history.on_packet_sent(1200, send_time_us);
+            if arrivals.is_empty() {
+                base_sequence = sequence;
+            }
+            // Receiver clock is offset from the sender's
+            arrivals.push(Some(
+                5_000_000 + send_time_us + 20_000 + i * delay_growth_us,
+            ));
+
+            if arrivals.len() == 10 {
+                let feedback = TransportCcFeedback {
+                    sender_ssrc: 1,
+                    media_ssrc: 2,
+                    base_sequence,
+                    reference_time: 0,
+                    feedback_count: 0,
+                    arrivals: std::mem::take(&mut arrivals),
+                };
+                controller.on_packet_results(&history.on_feedback(&feedback));
+            }
+        }
+    }
+
+    #[test]
+    fn test_send_history_matches_feedback() {
+        let mut history = SendHistory::new();
+        assert_eq!(history.on_packet_sent(100, 1_000), 0);
+        assert_eq!(history.on_packet_sent(200, 2_000), 1);
+
+        let feedback = TransportCcFeedback {
+            sender_ssrc: 1,
+            media_ssrc: 2,
+            base_sequence: 0,
+            reference_time: 0,
+            feedback_count: 0,
+            arrivals: vec![Some(50_000), None, Some(60_000)],
+        };
+        let results = history.on_feedback(&feedback);
+
+        assert_eq!(
+            results,
+            vec![
+                PacketResult {
+                    sequence: 0,
+                    send_time_us: 1_000,
+                    arrival_us: Some(50_000),
+                    size: 100,
+                },
+                PacketResult {
+                    sequence: 1,
+                    send_time_us: 2_000,
+                    arrival_us: None,
+                    size: 200,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trendline_detects_increasing_delay() {
+        let mut trendline = TrendlineEstimator::new();
+        for i in 0..TRENDLINE_WINDOW * 2 {
+            trendline.update(0.0, i as f64 * 10.0);
+        }
+        assert_eq!(trendline.state(), BandwidthUsage::Normal);
+
+        // Each group arrives 2ms later than it was spaced at the sender
+        for i in 0..TRENDLINE_WINDOW * 2 {
+            trendline.update(2.0, (TRENDLINE_WINDOW * 2 + i) as f64 * 12.0);
+        }
+        assert_eq!(trendline.state(), BandwidthUsage::Overusing);
+    }
+
+    #[test]
+    fn test_increasing_one_way_delay_lowers_target() {
+        let mut controller = DelayBasedController::new(1_000_000, 100_000, 2_000_000);
+        run_path(&mut controller, 2_000, 200);
+
+        assert_eq!(controller.state(), BandwidthUsage::Overusing);
+        assert!(controller.target_bitrate() < 1_000_000);
+    }
+
+    #[test]
+    fn test_constant_delay_ramps_up_to_max() {
+        let mut controller = DelayBasedController::new(1_000_000, 100_000, 1_200_000);
+        run_path(&mut controller, 0, 500);
+
+        assert_eq!(controller.state(), BandwidthUsage::Normal);
+        assert_eq!(controller.target_bitrate(), 1_200_000);
+    }
+}
@@ -0,0 +1,190 @@
+//! RTCP Payload-Specific Feedback messages (RFC 4585, RFC 5104)
+//!
+//! Implements the keyframe request messages used for video recovery:
+//! - Picture Loss Indication (PLI, PT 206 / FMT 1)
+//! - Full Intra Request (FIR, PT 206 / FMT 4)
+
+use super::RtcpPacketType;
+
+/// Feedback message type for Picture Loss Indication
+pub const PLI_FMT: u8 = 1;
+/// Feedback message type for Full Intra Request
+pub const FIR_FMT: u8 = 4;
+
+/// Picture Loss Indication (RFC 4585 Section 6.3.1)
+///
+/// Tells the media sender that the receiver lost an unknown amount of
+/// coded video and needs a fresh keyframe to resume decoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PictureLossIndication {
+    /// SSRC of the packet sender (the receiver of the media)
+    pub sender_ssrc: u32,
+    /// SSRC of the media source the indication refers to
+    pub media_ssrc: u32,
+}
+
+impl PictureLossIndication {
+    /// Create a new PLI for the given media source
+    pub fn new(sender_ssrc: u32, media_ssrc: u32) -> Self {
+        Self {
+            sender_ssrc,
+            media_ssrc,
+        }
+    }
+
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12);
+
+        write_feedback_header(&mut bytes, PLI_FMT, 2);
+        bytes.extend_from_slice(&self.sender_ssrc.to_be_bytes());
+        bytes.extend_from_slice(&self.media_ssrc.to_be_bytes());
+
+        bytes
+    }
+
+    /// Parse from bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        check_feedback_header(data, PLI_FMT, "PLI")?;
+
+        Ok(Self {
+            sender_ssrc: crate::codec::rtp::parse_u32_be(data, 4),
+            media_ssrc: crate::codec::rtp::parse_u32_be(data, 8),
+        })
+    }
+}
+
+/// Single FCI entry of a Full Intra Request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirEntry {
+    /// SSRC of the media sender that should send a decoder refresh point
+    pub ssrc: u32,
+    /// Command sequence number, incremented for every new request
+    pub sequence_number: u8,
+}
+
+/// Full Intra Request (RFC 5104 Section 4.3.1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FullIntraRequest {
+    /// SSRC of the packet sender
+    pub sender_ssrc: u32,
+    /// Requested media senders
+    pub entries: Vec<FirEntry>,
+}
+
+impl FullIntraRequest {
+    /// Create a new FIR for a single media source
+    pub fn new(sender_ssrc: u32, media_ssrc: u32, sequence_number: u8) -> Self {
+        Self {
+            sender_ssrc,
+            entries: vec![FirEntry {
+                ssrc: media_ssrc,
+                sequence_number,
+            }],
+        }
+    }
+
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + self.entries.len() * 8);
+
+        write_feedback_header(&mut bytes, FIR_FMT, 2 + self.entries.len() * 2);
+        bytes.extend_from_slice(&self.sender_ssrc.to_be_bytes());
+        // Media source SSRC is unused for FIR and must be zero
+        bytes.extend_from_slice(&0u32.to_be_bytes());
+
+        for entry in &self.entries {
+            bytes.extend_from_slice(&entry.ssrc.to_be_bytes());
+            bytes.push(entry.sequence_number);
+            bytes.extend_from_slice(&[0, 0, 0]);
+        }
+
+        bytes
+    }
+
+    /// Parse from bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        check_feedback_header(data, FIR_FMT, "FIR")?;
+
+        let sender_ssrc = crate::codec::rtp::parse_u32_be(data, 4);
+        let length_words = crate::codec::rtp::parse_u16_be(data, 2) as usize;
+        let packet_end = ((length_words + 1) * 4).min(data.len());
+
+        let mut entries = Vec::new();
+        let mut offset = 12;
+        while offset + 8 <= packet_end {
+            entries.push(FirEntry {
+                ssrc: crate::codec::rtp::parse_u32_be(data, offset),
+                sequence_number: data[offset + 4],
+            });
+            offset += 8;
+        }
+
+        Ok(Self {
+            sender_ssrc,
+            entries,
+        })
+    }
+}
+
+/// Returns the feedback message type (FMT) of a PSFB packet, if it is one
+pub fn payload_feedback_fmt(data: &[u8]) -> Option<u8> {
+    if data.len() < 2 || data[1] != RtcpPacketType::PSFB as u8 {
+        return None;
+    }
+    Some(data[0] & 0x1F)
+}
+
+fn write_feedback_header(bytes: &mut Vec<u8>, fmt: u8, length_words: usize) {
+    let version = 2u8;
+    let padding = 0u8;
+    bytes.push((version << 6) | (padding << 5) | (fmt & 0x1F));
+    bytes.push(RtcpPacketType::PSFB as u8);
+    bytes.extend_from_slice(&(length_words as u16).to_be_bytes());
+}
+
+fn check_feedback_header(data: &[u8], fmt: u8, name: &str) -> Result<(), String> {
+    if data.len() < 12 {
+        return Err(format!("{} packet too short", name));
+    }
+    if payload_feedback_fmt(data) != Some(fmt) {
+        return Err(format!("Not a {} packet", name));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pli_round_trip() {
+        let pli = PictureLossIndication::new(1111, 2222);
+        let bytes = pli.to_bytes();
+
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(bytes[0] & 0x1F, PLI_FMT);
+        assert_eq!(bytes[1], RtcpPacketType::PSFB as u8);
+
+        let parsed = PictureLossIndication::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, pli);
+    }
+
+    #[test]
+    fn test_fir_round_trip() {
+        let fir = FullIntraRequest::new(1111, 2222, 7);
+        let bytes = fir.to_bytes();
+
+        assert_eq!(bytes.len(), 20);
+        assert_eq!(payload_feedback_fmt(&bytes), Some(FIR_FMT));
+
+        let parsed = FullIntraRequest::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, fir);
+    }
+
+    #[test]
+    fn test_pli_rejects_fir() {
+        let fir = FullIntraRequest::new(1111, 2222, 0);
+        assert!(PictureLossIndication::from_bytes(&fir.to_bytes()).is_err());
+    }
+}
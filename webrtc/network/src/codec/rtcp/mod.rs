@@ -2,15 +2,29 @@
 //!
 //! Provides control and statistics for RTP sessions
 
+pub mod bitrate_controller;
 pub mod bye;
+pub mod compound;
+pub mod delay_controller;
+pub mod feedback;
 pub mod receiver_report;
+pub mod sdes;
 pub mod sender_report;
 pub mod stats;
+pub mod transport_cc;
 
+pub use bitrate_controller::BitrateController;
 pub use bye::ByePacket;
+pub use compound::{CompoundRtcpPacket, RtcpPacket};
+pub use delay_controller::{
+    BandwidthUsage, DelayBasedController, PacketResult, SendHistory, TrendlineEstimator,
+};
+pub use feedback::{FirEntry, FullIntraRequest, PictureLossIndication};
 pub use receiver_report::ReceiverReport;
+pub use sdes::{SdesChunk, SdesPacket};
 pub use sender_report::{ReportBlock, SenderReport};
 pub use stats::RtcpStats;
+pub use transport_cc::{TransportCcFeedback, TransportCcRecorder};
 
 /// Common RTCP header writing function to eliminate duplication
 pub(crate) fn write_rtcp_header(bytes: &mut Vec<u8>, packet_type: RtcpPacketType, rc: usize) {
@@ -18,7 +32,13 @@ pub(crate) fn write_rtcp_header(bytes: &mut Vec<u8>, packet_type: RtcpPacketType
     let padding = 0u8;
     bytes.push((version << 6) | (padding << 5) | rc as u8);
     bytes.push(packet_type as u8);
-    let length = (1 + rc * 6) as u16;
+    // Length in 32-bit words minus one: SSRC (+ 5 words of sender info for SR)
+    // followed by 6 words per report block
+    let fixed_words = match packet_type {
+        RtcpPacketType::SR => 6,
+        _ => 1,
+    };
+    let length = (fixed_words + rc * 6) as u16;
     bytes.extend_from_slice(&length.to_be_bytes());
 }
 
@@ -36,6 +56,10 @@ pub enum RtcpPacketType {
     BYE = 203,
     /// Application-defined (204)
     APP = 204,
+    /// Transport-layer feedback (205)
+    RTPFB = 205,
+    /// Payload-specific feedback (206)
+    PSFB = 206,
 }
 
 impl RtcpPacketType {
@@ -46,6 +70,8 @@ impl RtcpPacketType {
             202 => Some(RtcpPacketType::SDES),
             203 => Some(RtcpPacketType::BYE),
             204 => Some(RtcpPacketType::APP),
+            205 => Some(RtcpPacketType::RTPFB),
+            206 => Some(RtcpPacketType::PSFB),
             _ => None,
         }
     }
@@ -0,0 +1,207 @@
+//! RTCP Source Description (SDES) packet implementation (RFC 3550 Section 6.5)
+//!
+//! Only the CNAME and NAME items are interpreted; other items are skipped
+//! when parsing.
+
+use super::RtcpPacketType;
+
+/// SDES item type: end of the item list of a chunk
+const SDES_END: u8 = 0;
+/// SDES item type: canonical end-point identifier
+pub const SDES_CNAME: u8 = 1;
+/// SDES item type: user name
+pub const SDES_NAME: u8 = 2;
+
+/// Source description of one SSRC
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdesChunk {
+    pub ssrc: u32,
+    /// Canonical name, shared by all streams of one participant
+    pub cname: Option<String>,
+    /// Display name of the participant
+    pub name: Option<String>,
+}
+
+/// RTCP SDES packet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdesPacket {
+    pub chunks: Vec<SdesChunk>,
+}
+
+impl SdesPacket {
+    /// Create an SDES packet with a single CNAME chunk
+    pub fn new(ssrc: u32, cname: &str) -> Self {
+        Self {
+            chunks: vec![SdesChunk {
+                ssrc,
+                cname: Some(cname.to_string()),
+                name: None,
+            }],
+        }
+    }
+
+    /// Returns the CNAME announced for `ssrc`, if any
+    pub fn cname_for(&self, ssrc: u32) -> Option<&str> {
+        self.chunks
+            .iter()
+            .find(|chunk| chunk.ssrc == ssrc)
+            .and_then(|chunk| chunk.cname.as_deref())
+    }
+
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + self.chunks.len() * 16);
+
+        write_header(&mut bytes, self.chunks.len());
+        for chunk in &self.chunks {
+            write_chunk(&mut bytes, chunk);
+        }
+
+        let length_words = (bytes.len() / 4 - 1) as u16;
+        bytes[2..4].copy_from_slice(&length_words.to_be_bytes());
+
+        bytes
+    }
+
+    /// Parse from bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() < 4 {
+            return Err("SDES packet too short".to_string());
+        }
+
+        let sc = data[0] & 0x1F;
+        let mut chunks = Vec::with_capacity(sc as usize);
+        let mut offset = 4;
+
+        for _ in 0..sc {
+            let (chunk, next) = parse_chunk(data, offset)?;
+            chunks.push(chunk);
+            offset = next;
+        }
+
+        Ok(Self { chunks })
+    }
+}
+
+fn write_header(bytes: &mut Vec<u8>, chunk_count: usize) {
+    let version = 2u8;
+    let padding = 0u8;
+    bytes.push((version << 6) | (padding << 5) | chunk_count as u8);
+    bytes.push(RtcpPacketType::SDES as u8);
+    // Length is filled in once the chunks are written
+    bytes.extend_from_slice(&[0, 0]);
+}
+
+fn write_chunk(bytes: &mut Vec<u8>, chunk: &SdesChunk) {
+    bytes.extend_from_slice(&chunk.ssrc.to_be_bytes());
+
+    for (item_type, text) in [(SDES_CNAME, &chunk.cname), (SDES_NAME, &chunk.name)] {
+        if let Some(text) = text {
+            // Item text is limited to 255 bytes by the length octet
+            let text = &text.as_bytes()[..text.len().min(255)];
+            bytes.push(item_type);
+            bytes.push(text.len() as u8);
+            bytes.extend_from_slice(text);
+        }
+    }
+
+    // End item, then pad the chunk to a 32-bit boundary
+    bytes.push(SDES_END);
+    while !bytes.len().is_multiple_of(4) {
+        bytes.push(0);
+    }
+}
+
+/// Parses the chunk at `offset`, returning it and the offset of the next chunk
+fn parse_chunk(data: &[u8], offset: usize) -> Result<(SdesChunk, usize), String> {
+    if offset + 4 > data.len() {
+        return Err("SDES chunk truncated".to_string());
+    }
+
+    let mut chunk = SdesChunk {
+        ssrc: crate::codec::rtp::parse_u32_be(data, offset),
+        cname: None,
+        name: None,
+    };
+    let mut offset = offset + 4;
+
+    loop {
+        let Some(&item_type) = data.get(offset) else {
+            return Err("SDES item list not terminated".to_string());
+        };
+        if item_type == SDES_END {
+            break;
+        }
+
+        let Some(&len) = data.get(offset + 1) else {
+            return Err("SDES item truncated".to_string());
+        };
+        let text_start = offset + 2;
+        let text_end = text_start + len as usize;
+        if text_end > data.len() {
+            return Err("SDES item truncated".to_string());
+        }
+
+        let text = String::from_utf8_lossy(&data[text_start..text_end]).to_string();
+        match item_type {
+            SDES_CNAME => chunk.cname = Some(text),
+            SDES_NAME => chunk.name = Some(text),
+            _ => {}
+        }
+        offset = text_end;
+    }
+
+    // Skip the end item and the padding up to the next 32-bit boundary
+    let next = (offset + 1).next_multiple_of(4);
+    Ok((chunk, next))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sdes_cname_round_trip() {
+        let sdes = SdesPacket::new(12345, "alice@roomrtc");
+        let bytes = sdes.to_bytes();
+
+        assert_eq!(bytes[1], RtcpPacketType::SDES as u8);
+        assert_eq!(bytes.len() % 4, 0);
+        // SSRC + CNAME item (2 + 13) + end item, padded to 20 bytes
+        assert_eq!(bytes.len(), 4 + 20);
+        assert_eq!(u16::from_be_bytes([bytes[2], bytes[3]]), 5);
+
+        let parsed = SdesPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, sdes);
+        assert_eq!(parsed.cname_for(12345), Some("alice@roomrtc"));
+        assert_eq!(parsed.cname_for(1), None);
+    }
+
+    #[test]
+    fn test_sdes_multiple_chunks_with_name() {
+        let sdes = SdesPacket {
+            chunks: vec![
+                SdesChunk {
+                    ssrc: 1,
+                    cname: Some("abcd".to_string()),
+                    name: Some("Alice".to_string()),
+                },
+                SdesChunk {
+                    ssrc: 2,
+                    cname: Some("abcd".to_string()),
+                    name: None,
+                },
+            ],
+        };
+
+        let bytes = sdes.to_bytes();
+        assert_eq!(bytes[0] & 0x1F, 2);
+        assert_eq!(SdesPacket::from_bytes(&bytes).unwrap(), sdes);
+    }
+
+    #[test]
+    fn test_sdes_truncated_item() {
+        let bytes = SdesPacket::new(1, "some-cname").to_bytes();
+        assert!(SdesPacket::from_bytes(&bytes[..12]).is_err());
+    }
+}
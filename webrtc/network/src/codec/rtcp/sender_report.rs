@@ -2,8 +2,12 @@
 
 use super::RtcpPacketType;
 use super::stats::RtcpStats;
+use crate::codec::lip_sync::NtpRtpMapping;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Seconds between the NTP epoch (1900) and the UNIX epoch (1970)
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
 /// Report block (used in SR and RR)
 #[derive(Debug, Clone)]
 pub struct ReportBlock {
@@ -86,16 +90,12 @@ pub struct SenderReport {
 impl SenderReport {
     /// Create a new Sender Report
     pub fn new(stats: &RtcpStats) -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("System time is before UNIX_EPOCH");
-
-        let ntp_secs = now.as_secs() + 2_208_988_800;
+        let ntp = ntp_timestamp(SystemTime::now());
 
         Self {
             ssrc: stats.ssrc,
-            ntp_timestamp_msw: (ntp_secs >> 32) as u32,
-            ntp_timestamp_lsw: (ntp_secs & 0xFFFFFFFF) as u32,
+            ntp_timestamp_msw: (ntp >> 32) as u32,
+            ntp_timestamp_lsw: ntp as u32,
             rtp_timestamp: stats.last_rtp_timestamp,
             sender_packet_count: stats.packets_sent,
             sender_byte_count: stats.bytes_sent as u32,
@@ -103,6 +103,46 @@ impl SenderReport {
         }
     }
 
+    /// Create a Sender Report for one media stream
+    ///
+    /// # Arguments
+    /// * `ssrc` - SSRC of the media stream
+    /// * `rtp_timestamp` - RTP timestamp of a packet of the stream
+    /// * `sampled_at` - Wall clock time matching `rtp_timestamp`
+    /// * `packets` / `bytes` - Sender counters of the stream
+    pub fn for_stream(
+        ssrc: u32,
+        rtp_timestamp: u32,
+        sampled_at: SystemTime,
+        packets: u32,
+        bytes: u32,
+    ) -> Self {
+        let ntp = ntp_timestamp(sampled_at);
+
+        Self {
+            ssrc,
+            ntp_timestamp_msw: (ntp >> 32) as u32,
+            ntp_timestamp_lsw: ntp as u32,
+            rtp_timestamp,
+            sender_packet_count: packets,
+            sender_byte_count: bytes,
+            report_blocks: Vec::new(),
+        }
+    }
+
+    /// Full 64-bit NTP timestamp (32.32 fixed point seconds since 1900)
+    pub fn ntp_timestamp(&self) -> u64 {
+        ((self.ntp_timestamp_msw as u64) << 32) | self.ntp_timestamp_lsw as u64
+    }
+
+    /// NTP/RTP timestamp pair used to align streams of the same sender
+    pub fn ntp_rtp_mapping(&self) -> NtpRtpMapping {
+        NtpRtpMapping {
+            ntp_timestamp: self.ntp_timestamp(),
+            rtp_timestamp: self.rtp_timestamp,
+        }
+    }
+
     /// Serialize to bytes
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::with_capacity(28 + self.report_blocks.len() * 24);
@@ -147,6 +187,17 @@ impl SenderReport {
     }
 }
 
+/// Converts a wall clock time to a 64-bit NTP timestamp
+pub fn ntp_timestamp(time: SystemTime) -> u64 {
+    let since_epoch = time
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before UNIX_EPOCH");
+
+    let secs = since_epoch.as_secs() + NTP_UNIX_OFFSET_SECS;
+    let fraction = ((since_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+    (secs << 32) | fraction
+}
+
 fn write_sender_info(bytes: &mut Vec<u8>, sr: &SenderReport) {
     bytes.extend_from_slice(&sr.ntp_timestamp_msw.to_be_bytes());
     bytes.extend_from_slice(&sr.ntp_timestamp_lsw.to_be_bytes());
@@ -197,4 +248,18 @@ mod tests {
         assert_eq!(parsed.ssrc, stats.ssrc);
         assert_eq!(parsed.sender_packet_count, stats.packets_sent);
     }
+
+    #[test]
+    fn test_stream_report_exposes_ntp_rtp_mapping() {
+        let sampled_at = UNIX_EPOCH + std::time::Duration::from_millis(1_500);
+        let sr = SenderReport::for_stream(777, 90_000, sampled_at, 10, 1000);
+
+        let parsed = SenderReport::from_bytes(&sr.to_bytes()).unwrap();
+        let mapping = parsed.ntp_rtp_mapping();
+
+        assert_eq!(mapping.rtp_timestamp, 90_000);
+        assert_eq!(mapping.ntp_timestamp >> 32, NTP_UNIX_OFFSET_SECS + 1);
+        // Half a second in 32-bit fixed point
+        assert_eq!(mapping.ntp_timestamp as u32, 1 << 31);
+    }
 }
@@ -1,5 +1,6 @@
 //! RTCP statistics tracking
 
+use super::sender_report::ReportBlock;
 use std::time::SystemTime;
 
 /// Statistics tracker for RTCP
@@ -23,6 +24,11 @@ pub struct RtcpStats {
     pub last_sr_timestamp: u32,
     pub last_sr_received_at: Option<SystemTime>,
 
+    // Remote feedback (from Receiver Reports about our stream)
+    pub remote_fraction_lost: u8,
+    pub remote_jitter: u32,
+    pub receiver_reports_received: u32,
+
     // Jitter calculation state
     pub(super) last_packet_arrival: Option<SystemTime>,
     pub(super) last_packet_timestamp: Option<u32>,
@@ -48,6 +54,9 @@ impl RtcpStats {
             jitter: 0.0,
             last_sr_timestamp: 0,
             last_sr_received_at: None,
+            remote_fraction_lost: 0,
+            remote_jitter: 0,
+            receiver_reports_received: 0,
             last_packet_arrival: None,
             last_packet_timestamp: None,
             last_sr_sent_at: None,
@@ -123,6 +132,26 @@ impl RtcpStats {
         fraction.min(255) as u8
     }
 
+    /// Record the feedback of a received Receiver Report
+    ///
+    /// Keeps the worst fraction lost and jitter across the report blocks.
+    pub fn update_from_receiver_report(&mut self, report_blocks: &[ReportBlock]) {
+        if report_blocks.is_empty() {
+            return;
+        }
+        self.remote_fraction_lost = report_blocks
+            .iter()
+            .map(|block| block.fraction_lost)
+            .max()
+            .unwrap_or(0);
+        self.remote_jitter = report_blocks
+            .iter()
+            .map(|block| block.jitter)
+            .max()
+            .unwrap_or(0);
+        self.receiver_reports_received += 1;
+    }
+
     /// Calculate RTT from SR/RR exchange
     pub fn calculate_rtt(&self) -> Option<f64> {
         let last_sr_sent = self.last_sr_sent_at?;
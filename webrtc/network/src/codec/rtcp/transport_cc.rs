@@ -0,0 +1,472 @@
+//! RTCP Transport-wide Congestion Control feedback
+//! (draft-holmer-rmcat-transport-wide-cc-extensions-01)
+//!
+//! The sender numbers every RTP packet with a transport-wide sequence number
+//! carried in a header extension. The receiver answers with a Transport
+//! Feedback message (PT 205 / FMT 15) telling which of those packets arrived
+//! and when, which drives the sender's delay-based bandwidth estimation.
+//!
+//! ```text
+//!  0                   1                   2                   3
+//!  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |V=2|P|  FMT=15 |    PT=205     |           length              |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |                     SSRC of packet sender                     |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |                      SSRC of media source                     |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |      base sequence number     |      packet status count      |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |                 reference time                | fb pkt. count |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |  packet status chunks ...     |  receive deltas ...           |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! ```
+
+use std::collections::BTreeMap;
+
+use super::RtcpPacketType;
+use crate::codec::rtp::{parse_u16_be, parse_u32_be};
+
+/// Feedback message type for transport-wide congestion control
+pub const TRANSPORT_CC_FMT: u8 = 15;
+
+/// Header extension ID carrying the transport-wide sequence number
+pub const TRANSPORT_CC_EXTENSION_ID: u8 = 5;
+
+/// Resolution of the reference time (microseconds)
+const REFERENCE_TIME_UNIT_US: i64 = 64_000;
+
+/// Resolution of the receive deltas (microseconds)
+const DELTA_UNIT_US: i64 = 250;
+
+/// Maximum run length a run-length chunk can describe
+const MAX_RUN_LENGTH: usize = 0x1FFF;
+
+/// Symbols carried by a two-bit status vector chunk
+const TWO_BIT_SYMBOLS: usize = 7;
+
+/// Fixed part of the packet: header, SSRCs, base sequence, count and reference time
+const FIXED_SIZE: usize = 20;
+
+/// Packet status symbol (how the receive delta of a packet is encoded)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    NotReceived = 0,
+    SmallDelta = 1,
+    LargeDelta = 2,
+}
+
+impl Status {
+    fn from_bits(bits: u16) -> Result<Self, String> {
+        match bits {
+            0 => Ok(Status::NotReceived),
+            1 => Ok(Status::SmallDelta),
+            2 => Ok(Status::LargeDelta),
+            _ => Err("Reserved transport-cc packet status".to_string()),
+        }
+    }
+
+    fn delta_size(self) -> usize {
+        self as usize
+    }
+}
+
+/// Transport-wide congestion control feedback (PT 205 / FMT 15)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportCcFeedback {
+    /// SSRC of the packet sender (the receiver of the media)
+    pub sender_ssrc: u32,
+    /// SSRC of the media source
+    pub media_ssrc: u32,
+    /// Transport-wide sequence number of the first packet reported
+    pub base_sequence: u16,
+    /// Reference time in multiples of 64ms (24 bits)
+    pub reference_time: u32,
+    /// Counter incremented for every feedback sent
+    pub feedback_count: u8,
+    /// Arrival time of each packet from `base_sequence` on, in microseconds
+    /// on the receiver's clock, or `None` if it was not received
+    ///
+    /// Arrival times are expressed with 250µs resolution.
+    pub arrivals: Vec<Option<i64>>,
+}
+
+impl TransportCcFeedback {
+    /// Serialize to bytes
+    ///
+    /// Receive deltas are relative to the reference time and truncated to
+    /// 250µs resolution.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let reference_us = self.reference_time as i64 * REFERENCE_TIME_UNIT_US;
+        let mut statuses = Vec::with_capacity(self.arrivals.len());
+        let mut deltas = Vec::new();
+        let mut previous_us = reference_us;
+
+        for arrival in &self.arrivals {
+            match arrival {
+                None => statuses.push(Status::NotReceived),
+                Some(arrival_us) => {
+                    let delta = ((arrival_us - previous_us) / DELTA_UNIT_US)
+                        .clamp(i16::MIN as i64, i16::MAX as i64);
+                    previous_us += delta * DELTA_UNIT_US;
+                    if (0..=u8::MAX as i64).contains(&delta) {
+                        statuses.push(Status::SmallDelta);
+                        deltas.push(delta as u8);
+                    } else {
+                        statuses.push(Status::LargeDelta);
+                        deltas.extend_from_slice(&(delta as i16).to_be_bytes());
+                    }
+                }
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(FIXED_SIZE + statuses.len() + deltas.len() + 4);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&self.sender_ssrc.to_be_bytes());
+        bytes.extend_from_slice(&self.media_ssrc.to_be_bytes());
+        bytes.extend_from_slice(&self.base_sequence.to_be_bytes());
+        bytes.extend_from_slice(&(statuses.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&(self.reference_time & 0x00FF_FFFF).to_be_bytes()[1..]);
+        bytes.push(self.feedback_count);
+        write_status_chunks(&mut bytes, &statuses);
+        bytes.extend_from_slice(&deltas);
+
+        // Pad to a 32-bit boundary; the last padding byte holds the count
+        let padding = (4 - bytes.len() % 4) % 4;
+        if padding > 0 {
+            bytes.resize(bytes.len() + padding - 1, 0);
+            bytes.push(padding as u8);
+        }
+
+        let version = 2u8;
+        bytes[0] = (version << 6) | (((padding > 0) as u8) << 5) | TRANSPORT_CC_FMT;
+        bytes[1] = RtcpPacketType::RTPFB as u8;
+        let length_words = (bytes.len() / 4 - 1) as u16;
+        bytes[2..4].copy_from_slice(&length_words.to_be_bytes());
+
+        bytes
+    }
+
+    /// Parse from bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() < FIXED_SIZE {
+            return Err("Transport-cc packet too short".to_string());
+        }
+        if transport_feedback_fmt(data) != Some(TRANSPORT_CC_FMT) {
+            return Err("Not a transport-cc packet".to_string());
+        }
+
+        let length = (parse_u16_be(data, 2) as usize + 1) * 4;
+        if length < FIXED_SIZE || data.len() < length {
+            return Err("Transport-cc packet truncated".to_string());
+        }
+        let mut end = length;
+        if data[0] & 0x20 != 0 {
+            let padding = data[length - 1] as usize;
+            if padding == 0 || padding > length - FIXED_SIZE {
+                return Err("Invalid transport-cc padding".to_string());
+            }
+            end -= padding;
+        }
+
+        let sender_ssrc = parse_u32_be(data, 4);
+        let media_ssrc = parse_u32_be(data, 8);
+        let base_sequence = parse_u16_be(data, 12);
+        let status_count = parse_u16_be(data, 14) as usize;
+        let reference_time = parse_u32_be(data, 16) >> 8;
+        let feedback_count = data[19];
+
+        let mut offset = FIXED_SIZE;
+        let mut statuses = Vec::with_capacity(status_count);
+        while statuses.len() < status_count {
+            if offset + 2 > end {
+                return Err("Transport-cc status chunks truncated".to_string());
+            }
+            read_status_chunk(parse_u16_be(data, offset), status_count, &mut statuses)?;
+            offset += 2;
+        }
+
+        let mut arrivals = Vec::with_capacity(status_count);
+        let mut arrival_us = reference_time as i64 * REFERENCE_TIME_UNIT_US;
+        for status in statuses {
+            let size = status.delta_size();
+            if offset + size > end {
+                return Err("Transport-cc receive deltas truncated".to_string());
+            }
+            let delta = match status {
+                Status::NotReceived => {
+                    arrivals.push(None);
+                    continue;
+                }
+                Status::SmallDelta => data[offset] as i64,
+                Status::LargeDelta => parse_u16_be(data, offset) as i16 as i64,
+            };
+            offset += size;
+            arrival_us += delta * DELTA_UNIT_US;
+            arrivals.push(Some(arrival_us));
+        }
+
+        Ok(Self {
+            sender_ssrc,
+            media_ssrc,
+            base_sequence,
+            reference_time,
+            feedback_count,
+            arrivals,
+        })
+    }
+
+    /// Iterates over `(sequence number, arrival time)` of the reported packets
+    pub fn packets(&self) -> impl Iterator<Item = (u16, Option<i64>)> + '_ {
+        self.arrivals
+            .iter()
+            .enumerate()
+            .map(|(i, arrival)| (self.base_sequence.wrapping_add(i as u16), *arrival))
+    }
+}
+
+/// Returns the feedback message type (FMT) of an RTPFB packet, if it is one
+pub fn transport_feedback_fmt(data: &[u8]) -> Option<u8> {
+    if data.len() < 2 || data[1] != RtcpPacketType::RTPFB as u8 {
+        return None;
+    }
+    Some(data[0] & 0x1F)
+}
+
+/// Encodes the statuses as run-length chunks for long runs and two-bit
+/// status vector chunks otherwise
+fn write_status_chunks(bytes: &mut Vec<u8>, statuses: &[Status]) {
+    let mut i = 0;
+    while i < statuses.len() {
+        let run = statuses[i..]
+            .iter()
+            .take_while(|&&status| status == statuses[i])
+            .count()
+            .min(MAX_RUN_LENGTH);
+
+        let chunk = if run >= TWO_BIT_SYMBOLS {
+            // T=0, status (2 bits), run length (13 bits)
+            let chunk = ((statuses[i] as u16) << 13) | run as u16;
+            i += run;
+            chunk
+        } else {
+            // T=1, S=1, seven two-bit symbols (unused ones are zero)
+            let mut chunk = 0xC000u16;
+            for (slot, status) in statuses[i..].iter().take(TWO_BIT_SYMBOLS).enumerate() {
+                chunk |= (*status as u16) << (12 - 2 * slot);
+            }
+            i += TWO_BIT_SYMBOLS.min(statuses.len() - i);
+            chunk
+        };
+        bytes.extend_from_slice(&chunk.to_be_bytes());
+    }
+}
+
+/// Decodes one packet status chunk, never producing more than `total` statuses
+fn read_status_chunk(chunk: u16, total: usize, statuses: &mut Vec<Status>) -> Result<(), String> {
+    let remaining = total - statuses.len();
+
+    if chunk & 0x8000 == 0 {
+        // Run length chunk
+        let status = Status::from_bits((chunk >> 13) & 0x03)?;
+        let run = (chunk & 0x1FFF) as usize;
+        statuses.extend(std::iter::repeat_n(status, run.min(remaining)));
+    } else if chunk & 0x4000 == 0 {
+        // Status vector chunk with fourteen one-bit symbols
+        for slot in 0..14.min(remaining) {
+            let bit = (chunk >> (13 - slot)) & 0x01;
+            statuses.push(Status::from_bits(bit)?);
+        }
+    } else {
+        // Status vector chunk with seven two-bit symbols
+        for slot in 0..TWO_BIT_SYMBOLS.min(remaining) {
+            let bits = (chunk >> (12 - 2 * slot)) & 0x03;
+            statuses.push(Status::from_bits(bits)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Receive side of transport-wide congestion control
+///
+/// Records the arrival time of every packet carrying a transport-wide
+/// sequence number and turns them into feedback messages.
+#[derive(Debug, Clone, Default)]
+pub struct TransportCcRecorder {
+    /// Arrival times (µs) by unwrapped sequence number, not yet reported
+    arrivals: BTreeMap<i64, i64>,
+    /// Last unwrapped sequence number seen, for unwrapping
+    last_sequence: Option<i64>,
+    /// First sequence number the next feedback starts at
+    next_base: Option<i64>,
+    feedback_count: u8,
+}
+
+impl TransportCcRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the arrival of a packet
+    ///
+    /// # Arguments
+    /// * `sequence` - Transport-wide sequence number from the header extension
+    /// * `arrival_us` - Arrival time in microseconds on a monotonic clock
+    pub fn record(&mut self, sequence: u16, arrival_us: i64) {
+        let unwrapped = match self.last_sequence {
+            None => sequence as i64,
+            Some(last) => last + sequence.wrapping_sub(last as u16) as i16 as i64,
+        };
+        self.last_sequence = Some(self.last_sequence.map_or(unwrapped, |l| l.max(unwrapped)));
+
+        // Already reported: too late to be included
+        if self.next_base.is_some_and(|base| unwrapped < base) {
+            return;
+        }
+        self.arrivals.insert(unwrapped, arrival_us);
+    }
+
+    /// Builds feedback for all packets recorded since the previous feedback
+    ///
+    /// Returns `None` if nothing new arrived.
+    pub fn build_feedback(
+        &mut self,
+        sender_ssrc: u32,
+        media_ssrc: u32,
+    ) -> Option<TransportCcFeedback> {
+        let (&first, _) = self.arrivals.first_key_value()?;
+        let (&last, _) = self.arrivals.last_key_value()?;
+        let base = self.next_base.unwrap_or(first).min(first);
+        // A single feedback describes at most u16::MAX packets
+        let base = base.max(last - u16::MAX as i64 + 1);
+
+        let reference_time = (self.arrivals[&first] / REFERENCE_TIME_UNIT_US) as u32 & 0x00FF_FFFF;
+        let arrivals = (base..=last)
+            .map(|sequence| self.arrivals.get(&sequence).copied())
+            .collect();
+
+        let feedback = TransportCcFeedback {
+            sender_ssrc,
+            media_ssrc,
+            base_sequence: base as u16,
+            reference_time,
+            feedback_count: self.feedback_count,
+            arrivals,
+        };
+
+        self.feedback_count = self.feedback_count.wrapping_add(1);
+        self.next_base = Some(last + 1);
+        self.arrivals.clear();
+
+        Some(feedback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feedback(arrivals: Vec<Option<i64>>) -> TransportCcFeedback {
+        TransportCcFeedback {
+            sender_ssrc: 1111,
+            media_ssrc: 2222,
+            base_sequence: 65530,
+            reference_time: 10,
+            feedback_count: 3,
+            arrivals,
+        }
+    }
+
+    #[test]
+    fn test_transport_cc_packet_format() {
+        // Reference time 10 * 64ms = 640ms; deltas of 1ms, lost, 2ms, 1ms
+        let fb = feedback(vec![Some(641_000), None, Some(643_000), Some(644_000)]);
+        let bytes = fb.to_bytes();
+
+        assert_eq!(bytes[0] & 0x1F, TRANSPORT_CC_FMT);
+        assert_eq!(bytes[1], RtcpPacketType::RTPFB as u8);
+        assert_eq!(bytes.len() % 4, 0);
+        assert_eq!(parse_u16_be(&bytes, 2) as usize, bytes.len() / 4 - 1);
+        assert_eq!(parse_u16_be(&bytes, 12), 65530);
+        assert_eq!(parse_u16_be(&bytes, 14), 4);
+        assert_eq!(&bytes[16..20], &[0, 0, 10, 3]);
+        // Two-bit status vector: small, not received, small, small
+        assert_eq!(
+            parse_u16_be(&bytes, 20),
+            0xC000 | (1 << 12) | (1 << 8) | (1 << 6)
+        );
+        // Small deltas in 250µs units
+        assert_eq!(&bytes[22..25], &[4, 8, 4]);
+        // Padded with the P bit set and the count in the last byte
+        assert_eq!(bytes.len(), 28);
+        assert_eq!(bytes[0] & 0x20, 0x20);
+        assert_eq!(bytes[27], 3);
+    }
+
+    #[test]
+    fn test_transport_cc_round_trip() {
+        let mut arrivals: Vec<Option<i64>> = (0..20).map(|i| Some(640_000 + i * 5_000)).collect();
+        arrivals[3] = None;
+        // Large positive and negative deltas (reordering)
+        arrivals[10] = Some(800_000);
+        arrivals[11] = Some(700_000);
+        let fb = feedback(arrivals);
+
+        let parsed = TransportCcFeedback::from_bytes(&fb.to_bytes()).unwrap();
+        assert_eq!(parsed, fb);
+
+        let sequences: Vec<u16> = parsed.packets().map(|(seq, _)| seq).collect();
+        assert_eq!(
+            sequences[..7],
+            [65530, 65531, 65532, 65533, 65534, 65535, 0]
+        );
+    }
+
+    #[test]
+    fn test_transport_cc_long_runs_use_run_length() {
+        let mut arrivals = vec![None; 100];
+        arrivals.extend((0..100).map(|i| Some(640_000 + i * 1_000)));
+        let fb = feedback(arrivals);
+
+        let bytes = fb.to_bytes();
+        // One run-length chunk per status run
+        assert_eq!(parse_u16_be(&bytes, 20), 100);
+        assert_eq!(parse_u16_be(&bytes, 22), (1 << 13) | 100);
+        assert_eq!(TransportCcFeedback::from_bytes(&bytes).unwrap(), fb);
+    }
+
+    #[test]
+    fn test_transport_cc_rejects_truncated() {
+        let bytes = feedback(vec![Some(641_000), Some(642_000)]).to_bytes();
+        assert!(TransportCcFeedback::from_bytes(&bytes[..bytes.len() - 4]).is_err());
+        assert!(TransportCcFeedback::from_bytes(&bytes[..12]).is_err());
+    }
+
+    #[test]
+    fn test_recorder_reports_losses_and_advances() {
+        let mut recorder = TransportCcRecorder::new();
+        recorder.record(65534, 1_000_000);
+        recorder.record(0, 1_010_000);
+        recorder.record(1, 1_015_000);
+
+        let fb = recorder.build_feedback(1, 2).unwrap();
+        assert_eq!(fb.base_sequence, 65534);
+        assert_eq!(fb.feedback_count, 0);
+        assert_eq!(
+            fb.arrivals,
+            vec![Some(1_000_000), None, Some(1_010_000), Some(1_015_000)]
+        );
+        assert!(recorder.build_feedback(1, 2).is_none());
+
+        // Late arrival of an already reported packet is not reported again
+        recorder.record(65535, 1_020_000);
+        recorder.record(2, 1_030_000);
+        let fb = recorder.build_feedback(1, 2).unwrap();
+        assert_eq!(fb.base_sequence, 2);
+        assert_eq!(fb.feedback_count, 1);
+        assert_eq!(fb.arrivals, vec![Some(1_030_000)]);
+    }
+}
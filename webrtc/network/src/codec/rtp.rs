@@ -15,6 +15,20 @@
 //! |                           Timestamp                           |
 //! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 //! |           SSRC (Synchronization Source)                       |
+//! +=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+
+//! |            CSRC list (0-15 items, 32 bits each)               |
+//! |                             ....                              |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! ```
+//!
+//! When the X bit is set, the CSRC list is followed by a header extension
+//! block. Elements use the one-byte form of RFC 8285 (profile `0xBEDE`):
+//!
+//! ```text
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |       0xBE    |    0xDE       |           length              |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |  ID   |  L-1  |     data      |  ID   |  L-1  |  data ...     |
 //! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 //! ```
 //!
@@ -73,6 +87,18 @@ pub mod control_payload {
     pub const AUDIO_UNMUTED: u8 = 9;
 }
 
+/// Profile identifying one-byte header extensions (RFC 8285 Section 4.2)
+pub const ONE_BYTE_EXTENSION_PROFILE: u16 = 0xBEDE;
+
+/// Single RTP header extension element (RFC 8285 one-byte form)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderExtension {
+    /// Local identifier negotiated with `a=extmap` (1-14)
+    pub id: u8,
+    /// Element data (1-16 bytes)
+    pub data: Vec<u8>,
+}
+
 /// RTP packet header according to RFC 3550.
 #[derive(Debug, Clone)]
 pub struct RtpHeader {
@@ -82,8 +108,6 @@ pub struct RtpHeader {
     pub padding: bool,
     /// Extension flag
     pub extension: bool,
-    /// CSRC count (0-15)
-    pub csrc_count: u8,
     /// Marker bit (interpretation depends on payload type)
     pub marker: bool,
     /// Payload type (0-127)
@@ -94,12 +118,20 @@ pub struct RtpHeader {
     pub timestamp: u32,
     /// Synchronization source identifier
     pub ssrc: u32,
+    /// Contributing source identifiers (at most 15, e.g. the streams
+    /// combined by a mixer)
+    pub csrc: Vec<u32>,
+    /// Header extension elements, written when non-empty
+    pub extensions: Vec<HeaderExtension>,
 }
 
 impl RtpHeader {
     /// Fixed size of RTP header in bytes.
     const HEADER_SIZE: usize = 12;
 
+    /// Maximum number of CSRC identifiers the CC field can describe.
+    pub const MAX_CSRC: usize = 15;
+
     /// Creates a new RTP header with default values.
     ///
     /// # Arguments
@@ -113,29 +145,85 @@ impl RtpHeader {
             version: 2,
             padding: false,
             extension: false,
-            csrc_count: 0,
             marker: false,
             payload_type,
             sequence_number: 0,
             timestamp: 0,
             ssrc,
+            csrc: Vec::new(),
+            extensions: Vec::new(),
+        }
+    }
+
+    /// Returns the CSRC count (CC) written to the header.
+    ///
+    /// Identifiers beyond [`Self::MAX_CSRC`] are not serialized.
+    pub fn csrc_count(&self) -> u8 {
+        self.csrc.len().min(Self::MAX_CSRC) as u8
+    }
+
+    /// Returns the serialized size of the header in bytes, including the
+    /// CSRC list and the extension block.
+    ///
+    /// This is the size [`to_bytes`](Self::to_bytes) writes. A received
+    /// header may be longer on the wire (another extension profile, padding
+    /// between elements); [`from_bytes_with_len`](Self::from_bytes_with_len)
+    /// reports that length.
+    pub fn size(&self) -> usize {
+        let mut size = Self::HEADER_SIZE + 4 * self.csrc_count() as usize;
+        if self.has_extension() {
+            size += 4 + 4 * self.extension_words();
+        }
+        size
+    }
+
+    /// Returns the data of the extension element with the given ID
+    pub fn extension(&self, id: u8) -> Option<&[u8]> {
+        self.extensions
+            .iter()
+            .find(|ext| ext.id == id)
+            .map(|ext| ext.data.as_slice())
+    }
+
+    /// Adds or replaces the extension element with the given ID
+    ///
+    /// IDs outside 1-14 and data outside 1-16 bytes cannot be expressed in
+    /// the one-byte form and are ignored.
+    pub fn set_extension(&mut self, id: u8, data: Vec<u8>) {
+        if !(1..=14).contains(&id) || !(1..=16).contains(&data.len()) {
+            return;
         }
+        match self.extensions.iter_mut().find(|ext| ext.id == id) {
+            Some(ext) => ext.data = data,
+            None => self.extensions.push(HeaderExtension { id, data }),
+        }
+    }
+
+    fn has_extension(&self) -> bool {
+        self.extension || !self.extensions.is_empty()
+    }
+
+    /// Length of the extension elements in 32-bit words, padding included
+    fn extension_words(&self) -> usize {
+        let bytes: usize = self.extensions.iter().map(|ext| 1 + ext.data.len()).sum();
+        bytes.div_ceil(4)
     }
 
     /// Serializes the RTP header to bytes.
     ///
-    /// Converts the header structure to a 12-byte array following RFC 3550 format.
+    /// Converts the header structure to bytes following RFC 3550 format:
+    /// 12 fixed bytes followed by 4 bytes per CSRC.
     ///
     /// # Returns
-    /// A `Vec<u8>` containing the serialized header (12 bytes).
+    /// A `Vec<u8>` containing the serialized header (12 + 4 * CC bytes).
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity(Self::HEADER_SIZE);
+        let mut bytes = Vec::with_capacity(self.size());
 
         // Byte 0: V(2) + P(1) + X(1) + CC(4)
         let byte0 = (self.version << 6)
             | ((self.padding as u8) << 5)
-            | ((self.extension as u8) << 4)
-            | self.csrc_count;
+            | ((self.has_extension() as u8) << 4)
+            | self.csrc_count();
         bytes.push(byte0);
 
         // Byte 1: M(1) + PT(7)
@@ -151,6 +239,15 @@ impl RtpHeader {
         // Bytes 8-11: SSRC
         bytes.extend_from_slice(&self.ssrc.to_be_bytes());
 
+        // Bytes 12+: CSRC list
+        for csrc in self.csrc.iter().take(Self::MAX_CSRC) {
+            bytes.extend_from_slice(&csrc.to_be_bytes());
+        }
+
+        if self.has_extension() {
+            self.write_extensions(&mut bytes);
+        }
+
         bytes
     }
 
@@ -162,6 +259,20 @@ impl RtpHeader {
     /// # Returns
     /// A `Result` containing the deserialized `RtpHeader` or an error
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        Self::from_bytes_with_len(data).map(|(header, _)| header)
+    }
+
+    /// Deserializes a header and returns its length on the wire
+    ///
+    /// The length follows the extension length word, so it covers extension
+    /// blocks of any profile and padding between elements.
+    ///
+    /// # Arguments
+    /// * `data` - Byte slice starting with the serialized RTP header
+    ///
+    /// # Returns
+    /// A `Result` containing the header and the offset of the payload
+    pub fn from_bytes_with_len(data: &[u8]) -> Result<(Self, usize)> {
         if data.len() < Self::HEADER_SIZE {
             return Err(NetworkError::Rtp("Header too short".to_string()));
         }
@@ -169,7 +280,10 @@ impl RtpHeader {
         let version = (data[0] >> 6) & 0x03;
         let padding = ((data[0] >> 5) & 0x01) == 1;
         let extension = ((data[0] >> 4) & 0x01) == 1;
-        let csrc_count = data[0] & 0x0F;
+        let csrc_count = (data[0] & 0x0F) as usize;
+        if data.len() < Self::HEADER_SIZE + 4 * csrc_count {
+            return Err(NetworkError::Rtp("CSRC list truncated".to_string()));
+        }
 
         let marker = ((data[1] >> 7) & 0x01) == 1;
         let payload_type = data[1] & 0x7F;
@@ -177,18 +291,94 @@ impl RtpHeader {
         let sequence_number = parse_u16_be(data, 2);
         let timestamp = parse_u32_be(data, 4);
         let ssrc = parse_u32_be(data, 8);
+        let csrc = (0..csrc_count)
+            .map(|i| parse_u32_be(data, Self::HEADER_SIZE + 4 * i))
+            .collect();
+
+        let csrc_end = Self::HEADER_SIZE + 4 * csrc_count;
+        let (extensions, len) = if extension {
+            Self::parse_extensions(data, csrc_end)?
+        } else {
+            (Vec::new(), csrc_end)
+        };
 
-        Ok(RtpHeader {
+        let header = RtpHeader {
             version,
             padding,
             extension,
-            csrc_count,
             marker,
             payload_type,
             sequence_number,
             timestamp,
             ssrc,
-        })
+            csrc,
+            extensions,
+        };
+        Ok((header, len))
+    }
+
+    /// Writes the extension block (profile, length and padded elements)
+    fn write_extensions(&self, bytes: &mut Vec<u8>) {
+        let words = self.extension_words();
+        bytes.extend_from_slice(&ONE_BYTE_EXTENSION_PROFILE.to_be_bytes());
+        bytes.extend_from_slice(&(words as u16).to_be_bytes());
+
+        let start = bytes.len();
+        for ext in &self.extensions {
+            bytes.push((ext.id << 4) | (ext.data.len() as u8 - 1));
+            bytes.extend_from_slice(&ext.data);
+        }
+        bytes.resize(start + words * 4, 0);
+    }
+
+    /// Parses the extension block starting at `offset`
+    ///
+    /// Blocks with a profile other than one-byte are skipped.
+    ///
+    /// # Returns
+    /// The one-byte elements and the offset where the block ends
+    fn parse_extensions(data: &[u8], offset: usize) -> Result<(Vec<HeaderExtension>, usize)> {
+        if data.len() < offset + 4 {
+            return Err(NetworkError::Rtp("Header extension truncated".to_string()));
+        }
+        let profile = parse_u16_be(data, offset);
+        let length = 4 * parse_u16_be(data, offset + 2) as usize;
+        let start = offset + 4;
+        if data.len() < start + length {
+            return Err(NetworkError::Rtp("Header extension truncated".to_string()));
+        }
+        if profile != ONE_BYTE_EXTENSION_PROFILE {
+            return Ok((Vec::new(), start + length));
+        }
+
+        let block = &data[start..start + length];
+        let mut extensions = Vec::new();
+        let mut i = 0;
+        while i < block.len() {
+            let id = block[i] >> 4;
+            if id == 0 {
+                // Padding byte
+                i += 1;
+                continue;
+            }
+            if id == 15 {
+                // Reserved: stop processing the block
+                break;
+            }
+            let len = (block[i] & 0x0F) as usize + 1;
+            if i + 1 + len > block.len() {
+                return Err(NetworkError::Rtp(
+                    "Header extension element truncated".to_string(),
+                ));
+            }
+            extensions.push(HeaderExtension {
+                id,
+                data: block[i + 1..i + 1 + len].to_vec(),
+            });
+            i += 1 + len;
+        }
+
+        Ok((extensions, start + length))
     }
 }
 
@@ -197,26 +387,67 @@ impl RtpHeader {
 pub struct RtpPacket {
     pub header: RtpHeader,
     pub payload: Vec<u8>,
+    /// Padding bytes appended after the payload, including the trailing
+    /// count byte (RFC 3550 Section 5.1). Zero means no padding.
+    pub padding_len: u8,
 }
 
 impl RtpPacket {
     /// Create new RTP Packet
     pub fn new(header: RtpHeader, payload: Vec<u8>) -> Self {
-        RtpPacket { header, payload }
+        RtpPacket {
+            header,
+            payload,
+            padding_len: 0,
+        }
+    }
+
+    /// Pad the packet with `len` bytes (builder style)
+    ///
+    /// Used to align packets to a cipher block size. The last padding byte
+    /// holds the count, so `len` includes it; zero removes the padding.
+    pub fn with_padding(mut self, len: u8) -> Self {
+        self.padding_len = len;
+        self.header.padding = len > 0;
+        self
     }
 
     /// Serialization Packet to Bytes
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = self.header.to_bytes();
+        // The P bit follows the padding actually written
+        bytes[0] = (bytes[0] & !0x20) | (((self.padding_len > 0) as u8) << 5);
         bytes.extend_from_slice(&self.payload);
+        if self.padding_len > 0 {
+            bytes.resize(bytes.len() + self.padding_len as usize - 1, 0);
+            bytes.push(self.padding_len);
+        }
         bytes
     }
 
     /// Deserialization Packet from bytes
+    ///
+    /// When the P bit is set, the padding indicated by the last byte is
+    /// stripped from the payload.
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
-        let header = RtpHeader::from_bytes(data)?;
-        let payload = data[RtpHeader::HEADER_SIZE..].to_vec();
-        Ok(RtpPacket { header, payload })
+        let (header, header_len) = RtpHeader::from_bytes_with_len(data)?;
+        let body = &data[header_len..];
+
+        let padding_len = if header.padding {
+            match body.last() {
+                Some(&len) if len > 0 && len as usize <= body.len() => len,
+                _ => return Err(NetworkError::Rtp("Invalid padding length".to_string())),
+            }
+        } else {
+            0
+        };
+
+        let payload = body[..body.len() - padding_len as usize].to_vec();
+        Ok(RtpPacket {
+            header,
+            payload,
+            padding_len,
+        })
     }
 }
 
@@ -230,12 +461,13 @@ mod tests {
             version: 2,
             padding: false,
             extension: false,
-            csrc_count: 0,
             marker: true,
             payload_type: 96,
             sequence_number: 1234,
             timestamp: 5678,
             ssrc: 9999,
+            csrc: Vec::new(),
+            extensions: Vec::new(),
         };
 
         let bytes = header.to_bytes();
@@ -261,7 +493,8 @@ mod tests {
         assert!(!header.padding);
         assert!(!header.extension);
         assert!(!header.marker);
-        assert_eq!(header.csrc_count, 0);
+        assert_eq!(header.csrc_count(), 0);
+        assert!(header.csrc.is_empty());
     }
 
     #[test]
@@ -331,12 +564,182 @@ mod tests {
     #[test]
     fn test_rtp_header_csrc_count() {
         let mut header = RtpHeader::new(96, 1000);
-        header.csrc_count = 15; // Max value
+        header.csrc = (1..=15).collect(); // Max value
 
         let bytes = header.to_bytes();
+        assert_eq!(bytes[0] & 0x0F, 15);
+        assert_eq!(bytes.len(), 12 + 15 * 4);
+
         let decoded = RtpHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.csrc_count(), 15);
+        assert_eq!(decoded.csrc, header.csrc);
+    }
+
+    #[test]
+    fn test_rtp_header_csrc_list_capped() {
+        let mut header = RtpHeader::new(96, 1000);
+        header.csrc = (1..=20).collect();
+
+        let decoded = RtpHeader::from_bytes(&header.to_bytes()).unwrap();
+        assert_eq!(decoded.csrc, (1..=15).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_rtp_header_csrc_list_truncated() {
+        let mut header = RtpHeader::new(96, 1000);
+        header.csrc = vec![1, 2];
+
+        let bytes = header.to_bytes();
+        assert!(RtpHeader::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_rtp_packet_csrc_roundtrip() {
+        for csrc in [vec![], vec![0xDEADBEEF], vec![11, 22, 33, 44, 55]] {
+            let mut header = RtpHeader::new(96, 4242);
+            header.csrc = csrc.clone();
+            let payload = vec![0xAA, 0xBB, 0xCC];
+            let packet = RtpPacket::new(header, payload.clone());
+
+            let bytes = packet.to_bytes();
+            let payload_offset = 12 + 4 * csrc.len();
+            assert_eq!(bytes[0] & 0x0F, csrc.len() as u8);
+            assert_eq!(&bytes[payload_offset..], &payload[..]);
+
+            let decoded = RtpPacket::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.header.ssrc, 4242);
+            assert_eq!(decoded.header.csrc, csrc);
+            assert_eq!(decoded.header.size(), payload_offset);
+            assert_eq!(decoded.payload, payload);
+        }
+    }
+
+    #[test]
+    fn test_rtp_header_extensions_roundtrip() {
+        let mut header = RtpHeader::new(96, 1000);
+        header.csrc = vec![7];
+        header.set_extension(3, vec![0x12, 0x34]);
+        header.set_extension(5, vec![0xAA]);
+        let packet = RtpPacket::new(header, vec![1, 2, 3]);
+
+        let bytes = packet.to_bytes();
+        assert_eq!((bytes[0] >> 4) & 0x01, 1);
+        // Profile and length (5 bytes of elements padded to 2 words)
+        assert_eq!(&bytes[16..20], &[0xBE, 0xDE, 0x00, 0x02]);
+        assert_eq!(&bytes[20..25], &[0x31, 0x12, 0x34, 0x50, 0xAA]);
+        assert_eq!(packet.header.size(), 28);
+
+        let decoded = RtpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.header.extension(3), Some(&[0x12, 0x34][..]));
+        assert_eq!(decoded.header.extension(5), Some(&[0xAA][..]));
+        assert_eq!(decoded.header.extension(4), None);
+        assert_eq!(decoded.header.csrc, vec![7]);
+        assert_eq!(decoded.payload, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rtp_two_byte_extension_profile_not_in_payload() {
+        let mut bytes = RtpHeader::new(96, 1000).to_bytes();
+        bytes[0] |= 0x10;
+        // Two-byte profile, one word: ID 3, length 2, data
+        bytes.extend_from_slice(&[0x10, 0x00, 0x00, 0x01, 0x03, 0x02, 0x12, 0x34]);
+        bytes.extend_from_slice(&[1, 2, 3]);
 
-        assert_eq!(decoded.csrc_count, 15);
+        let (header, header_len) = RtpHeader::from_bytes_with_len(&bytes).unwrap();
+        assert_eq!(header_len, 20);
+        assert!(header.extensions.is_empty());
+
+        let decoded = RtpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.payload, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rtp_extension_padding_between_elements_not_in_payload() {
+        let mut bytes = RtpHeader::new(96, 1000).to_bytes();
+        bytes[0] |= 0x10;
+        // Three words: ID 3 (2 bytes), four padding bytes, ID 5 (1 byte), padding
+        bytes.extend_from_slice(&[0xBE, 0xDE, 0x00, 0x03]);
+        bytes.extend_from_slice(&[0x31, 0x12, 0x34, 0x00, 0x00, 0x00, 0x00, 0x50, 0xAA, 0, 0, 0]);
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let decoded = RtpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.header.extension(3), Some(&[0x12, 0x34][..]));
+        assert_eq!(decoded.header.extension(5), Some(&[0xAA][..]));
+        assert_eq!(decoded.payload, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rtp_extension_reserved_id_ends_elements_not_block() {
+        let mut bytes = RtpHeader::new(96, 1000).to_bytes();
+        bytes[0] |= 0x10;
+        // ID 15 stops element parsing; the rest of the word is still header
+        bytes.extend_from_slice(&[0xBE, 0xDE, 0x00, 0x01, 0x50, 0xAA, 0xF0, 0x77]);
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let decoded = RtpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.header.extension(5), Some(&[0xAA][..]));
+        assert_eq!(decoded.payload, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rtp_header_extension_truncated() {
+        let mut header = RtpHeader::new(96, 1000);
+        header.set_extension(3, vec![0x12, 0x34]);
+
+        let bytes = header.to_bytes();
+        assert!(RtpHeader::from_bytes(&bytes[..bytes.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn test_rtp_packet_padding_stripped() {
+        // P bit set, 3 payload bytes followed by 4 bytes of padding
+        let mut bytes = RtpHeader::new(96, 1000).to_bytes();
+        bytes[0] |= 0x20;
+        bytes.extend_from_slice(&[7, 8, 9, 0, 0, 0, 4]);
+
+        let packet = RtpPacket::from_bytes(&bytes).unwrap();
+        assert!(packet.header.padding);
+        assert_eq!(packet.padding_len, 4);
+        assert_eq!(packet.payload, vec![7, 8, 9]);
+    }
+
+    #[test]
+    fn test_rtp_packet_with_padding_roundtrip() {
+        let packet = RtpPacket::new(RtpHeader::new(96, 1000), vec![1, 2, 3, 4, 5]).with_padding(11);
+
+        let bytes = packet.to_bytes();
+        assert_eq!((bytes[0] >> 5) & 0x01, 1);
+        assert_eq!(bytes.len(), 12 + 5 + 11);
+        assert_eq!(bytes.len() % 16, 12);
+        assert_eq!(*bytes.last().unwrap(), 11);
+
+        let decoded = RtpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.payload, vec![1, 2, 3, 4, 5]);
+        assert_eq!(decoded.padding_len, 11);
+
+        // Removing the padding clears the P bit
+        let unpadded = decoded.with_padding(0).to_bytes();
+        assert_eq!((unpadded[0] >> 5) & 0x01, 0);
+        assert_eq!(unpadded.len(), 12 + 5);
+    }
+
+    #[test]
+    fn test_rtp_packet_invalid_padding_length() {
+        let mut bytes = RtpHeader::new(96, 1000).to_bytes();
+        bytes[0] |= 0x20;
+
+        // No room for the count byte
+        assert!(RtpPacket::from_bytes(&bytes).is_err());
+
+        // Zero padding count
+        let mut zero = bytes.clone();
+        zero.extend_from_slice(&[1, 2, 0]);
+        assert!(RtpPacket::from_bytes(&zero).is_err());
+
+        // Padding longer than the payload area
+        let mut too_long = bytes.clone();
+        too_long.extend_from_slice(&[1, 2, 10]);
+        assert!(RtpPacket::from_bytes(&too_long).is_err());
     }
 
     #[test]
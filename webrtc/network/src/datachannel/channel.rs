@@ -2,7 +2,12 @@
 //!
 //! A data channel represents a bidirectional data stream within an SCTP association.
 
+use crate::sctp::{ChannelType, DataChannelOpen, ReliabilityPolicy};
 use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Low-water mark for file transfer channels (256 KB)
+const FILE_TRANSFER_LOW_THRESHOLD: usize = 256 * 1024;
 
 /// Data channel state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +39,8 @@ pub struct DataChannelConfig {
     pub id: Option<u16>,
     /// Sub-protocol
     pub protocol: String,
+    /// Buffered amount (bytes) at or below which `BufferedAmountLow` fires
+    pub buffered_amount_low_threshold: usize,
 }
 
 impl Default for DataChannelConfig {
@@ -46,6 +53,7 @@ impl Default for DataChannelConfig {
             negotiated: false,
             id: None,
             protocol: String::new(),
+            buffered_amount_low_threshold: 0,
         }
     }
 }
@@ -61,12 +69,80 @@ impl DataChannelConfig {
             negotiated: false,
             id: None,
             protocol: String::new(),
+            buffered_amount_low_threshold: 0,
+        }
+    }
+
+    /// Create configuration for an unordered channel with limited retransmissions
+    pub fn unreliable(label: impl Into<String>, max_retransmits: u16) -> Self {
+        Self {
+            ordered: false,
+            max_retransmits: Some(max_retransmits),
+            ..Self::reliable(label)
         }
     }
 
     /// Create configuration for a file transfer channel
     pub fn file_transfer() -> Self {
-        Self::reliable("file-transfer")
+        Self {
+            buffered_amount_low_threshold: FILE_TRANSFER_LOW_THRESHOLD,
+            ..Self::reliable("file-transfer")
+        }
+    }
+
+    /// Create configuration from a remote DATA_CHANNEL_OPEN request
+    pub fn from_open(stream_id: u16, open: &DataChannelOpen) -> Self {
+        let param = open.reliability_param;
+        let (max_retransmits, max_packet_lifetime) = match open.channel_type {
+            ChannelType::PartialReliableRexmit | ChannelType::PartialReliableRexmitUnordered => {
+                (Some(param.min(u16::MAX as u32) as u16), None)
+            }
+            ChannelType::PartialReliableTimed | ChannelType::PartialReliableTimedUnordered => {
+                (None, Some(param.min(u16::MAX as u32) as u16))
+            }
+            ChannelType::Reliable | ChannelType::ReliableUnordered => (None, None),
+        };
+
+        Self {
+            label: open.label.clone(),
+            ordered: open.channel_type.is_ordered(),
+            max_retransmits,
+            max_packet_lifetime,
+            negotiated: false,
+            id: Some(stream_id),
+            protocol: open.protocol.clone(),
+            buffered_amount_low_threshold: 0,
+        }
+    }
+
+    /// DCEP channel type matching this configuration
+    ///
+    /// `max_retransmits` takes precedence if both limits are set.
+    pub fn channel_type(&self) -> ChannelType {
+        match (self.ordered, self.max_retransmits, self.max_packet_lifetime) {
+            (true, Some(_), _) => ChannelType::PartialReliableRexmit,
+            (false, Some(_), _) => ChannelType::PartialReliableRexmitUnordered,
+            (true, None, Some(_)) => ChannelType::PartialReliableTimed,
+            (false, None, Some(_)) => ChannelType::PartialReliableTimedUnordered,
+            (true, None, None) => ChannelType::Reliable,
+            (false, None, None) => ChannelType::ReliableUnordered,
+        }
+    }
+
+    /// DCEP reliability parameter (retransmit count or lifetime in ms)
+    pub fn reliability_param(&self) -> u32 {
+        self.max_retransmits
+            .or(self.max_packet_lifetime)
+            .map_or(0, u32::from)
+    }
+
+    /// SCTP reliability policy for the channel's stream
+    pub fn reliability_policy(&self) -> ReliabilityPolicy {
+        match (self.max_retransmits, self.max_packet_lifetime) {
+            (Some(max), _) => ReliabilityPolicy::MaxRetransmits(max),
+            (None, Some(ms)) => ReliabilityPolicy::MaxLifetime(Duration::from_millis(ms as u64)),
+            (None, None) => ReliabilityPolicy::Reliable,
+        }
     }
 }
 
@@ -87,6 +163,12 @@ pub struct DataChannel {
     bytes_sent: u64,
     /// Bytes received
     bytes_received: u64,
+    /// Bytes handed to SCTP but not yet put on the wire
+    buffered_amount: usize,
+    /// Our outgoing SCTP stream was reset
+    outgoing_reset: bool,
+    /// The peer's outgoing SCTP stream was reset
+    incoming_reset: bool,
 }
 
 impl DataChannel {
@@ -100,6 +182,9 @@ impl DataChannel {
             recv_buffer: VecDeque::new(),
             bytes_sent: 0,
             bytes_received: 0,
+            buffered_amount: 0,
+            outgoing_reset: false,
+            incoming_reset: false,
         }
     }
 
@@ -138,6 +223,26 @@ impl DataChannel {
         self.bytes_received
     }
 
+    /// Get bytes queued in SCTP that have not been transmitted yet
+    pub fn buffered_amount(&self) -> usize {
+        self.buffered_amount
+    }
+
+    /// Get the low-water mark for `BufferedAmountLow` events
+    pub fn buffered_amount_low_threshold(&self) -> usize {
+        self.config.buffered_amount_low_threshold
+    }
+
+    /// Set the low-water mark for `BufferedAmountLow` events
+    pub fn set_buffered_amount_low_threshold(&mut self, threshold: usize) {
+        self.config.buffered_amount_low_threshold = threshold;
+    }
+
+    /// Check if the buffered amount is above the low-water mark
+    pub fn is_buffered_amount_high(&self) -> bool {
+        self.buffered_amount > self.config.buffered_amount_low_threshold
+    }
+
     /// Queue data for sending
     pub fn send(&mut self, data: Vec<u8>) -> Result<(), &'static str> {
         if self.state != DataChannelState::Open {
@@ -169,6 +274,20 @@ impl DataChannel {
         self.state = DataChannelState::Open;
     }
 
+    /// Called when a message is handed to the SCTP association
+    pub(crate) fn on_buffered(&mut self, bytes: usize) {
+        self.buffered_amount += bytes;
+    }
+
+    /// Called when queued bytes leave the SCTP send queue
+    ///
+    /// Returns true if the buffered amount just dropped to the low-water mark.
+    pub(crate) fn on_drained(&mut self, bytes: usize) -> bool {
+        let was_high = self.is_buffered_amount_high();
+        self.buffered_amount = self.buffered_amount.saturating_sub(bytes);
+        was_high && !self.is_buffered_amount_high()
+    }
+
     /// Called when data is received
     pub(crate) fn on_data(&mut self, data: Vec<u8>) {
         self.bytes_received += data.len() as u64;
@@ -185,11 +304,33 @@ impl DataChannel {
         }
     }
 
-    // /// Mark channel as fully closed
-    // TODO: I should close the channel when the SCTP association is closed at the very list when dropping the connection
-    // pub(crate) fn on_close(&mut self) {
-    //     self.state = DataChannelState::Closed;
-    // }
+    /// Called when our outgoing stream was reset
+    ///
+    /// Returns true if the channel is now closed.
+    pub(crate) fn on_outgoing_reset(&mut self) -> bool {
+        self.outgoing_reset = true;
+        self.close_if_reset()
+    }
+
+    /// Called when the peer's outgoing stream was reset
+    ///
+    /// Returns true if the channel is now closed.
+    pub(crate) fn on_incoming_reset(&mut self) -> bool {
+        self.incoming_reset = true;
+        self.close_if_reset()
+    }
+
+    /// Mark the channel closed once both streams are reset, dropping buffered data
+    fn close_if_reset(&mut self) -> bool {
+        if !(self.outgoing_reset && self.incoming_reset) {
+            return false;
+        }
+
+        self.state = DataChannelState::Closed;
+        self.send_buffer.clear();
+        self.recv_buffer.clear();
+        true
+    }
 }
 
 #[cfg(test)]
@@ -228,10 +369,68 @@ mod tests {
         assert_eq!(channel.recv(), Some(vec![4, 5, 6]));
     }
 
+    #[test]
+    fn test_partial_reliability_mapping() {
+        let config = DataChannelConfig::unreliable("game-state", 0);
+        assert_eq!(
+            config.channel_type(),
+            ChannelType::PartialReliableRexmitUnordered
+        );
+        assert_eq!(config.reliability_param(), 0);
+        assert_eq!(
+            config.reliability_policy(),
+            ReliabilityPolicy::MaxRetransmits(0)
+        );
+
+        let timed = DataChannelConfig {
+            max_packet_lifetime: Some(150),
+            ..DataChannelConfig::reliable("telemetry")
+        };
+        assert_eq!(timed.channel_type(), ChannelType::PartialReliableTimed);
+        assert_eq!(timed.reliability_param(), 150);
+
+        let open = DataChannelOpen {
+            channel_type: timed.channel_type(),
+            priority: 0,
+            reliability_param: timed.reliability_param(),
+            label: timed.label.clone(),
+            protocol: String::new(),
+        };
+        let parsed = DataChannelConfig::from_open(3, &open);
+        assert!(parsed.ordered);
+        assert_eq!(parsed.max_packet_lifetime, Some(150));
+        assert_eq!(parsed.max_retransmits, None);
+        assert_eq!(parsed.id, Some(3));
+    }
+
     #[test]
     fn test_file_transfer_config() {
         let config = DataChannelConfig::file_transfer();
         assert_eq!(config.label, "file-transfer");
         assert!(config.ordered);
+        assert_eq!(
+            config.buffered_amount_low_threshold,
+            FILE_TRANSFER_LOW_THRESHOLD
+        );
+    }
+
+    #[test]
+    fn test_buffered_amount_crossing() {
+        let config = DataChannelConfig {
+            buffered_amount_low_threshold: 10,
+            ..DataChannelConfig::reliable("test")
+        };
+        let mut channel = DataChannel::new(0, config);
+
+        // Draining while already below the threshold does not signal
+        channel.on_buffered(8);
+        assert!(!channel.on_drained(8));
+
+        channel.on_buffered(30);
+        assert!(channel.is_buffered_amount_high());
+        assert!(!channel.on_drained(15));
+        assert!(channel.on_drained(5));
+        assert_eq!(channel.buffered_amount(), 10);
+        assert!(!channel.on_drained(10));
     }
 }
@@ -2,11 +2,38 @@
 //!
 //! Manages multiple data channels over a single SCTP association.
 
-use super::channel::{DataChannel, DataChannelConfig};
+use super::channel::{DataChannel, DataChannelConfig, DataChannelState};
 use crate::sctp::{
-    ChannelType, DataChannelAck, DataChannelOpen, SctpAssociation, SctpPacket, ppid,
+    DataChannelAck, DataChannelOpen, SctpAssociation, SctpChunk, SctpPacket, StreamReset, ppid,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+
+/// User message content, typed by the SCTP PPID it was sent with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payload {
+    /// UTF-8 text (PPID 51 / 56)
+    Text(String),
+    /// Raw bytes (PPID 53 / 57)
+    Binary(Vec<u8>),
+}
+
+impl Payload {
+    /// Get the message bytes regardless of type
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Payload::Text(text) => text.as_bytes(),
+            Payload::Binary(data) => data,
+        }
+    }
+
+    /// Consume the payload into its bytes
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            Payload::Text(text) => text.into_bytes(),
+            Payload::Binary(data) => data,
+        }
+    }
+}
 
 /// Events emitted by the data channel manager
 #[derive(Debug, Clone)]
@@ -15,8 +42,10 @@ pub enum DataChannelEvent {
     ChannelOpened { id: u16, label: String },
     /// A channel was closed
     ChannelClosed { id: u16 },
-    /// Data received on a channel
-    DataReceived { id: u16, data: Vec<u8> },
+    /// Message received on a channel
+    Message { id: u16, payload: Payload },
+    /// A channel's buffered amount dropped to its low-water mark
+    BufferedAmountLow { id: u16 },
     /// Error occurred
     Error { message: String },
 }
@@ -30,6 +59,8 @@ pub struct DataChannelManager {
     channels: HashMap<u16, DataChannel>,
     /// Next stream ID to allocate (for initiator, use even; for responder, use odd)
     next_stream_id: u16,
+    /// Stream IDs of our closed channels, reused before allocating new ones
+    free_stream_ids: BTreeSet<u16>,
     // /// Whether we are the DTLS client -> If DTLS wrapping is necessary
     // is_client: bool,
     /// Pending events
@@ -46,6 +77,7 @@ impl DataChannelManager {
             association,
             channels: HashMap::new(),
             next_stream_id,
+            free_stream_ids: BTreeSet::new(),
             events: Vec::new(),
         }
     }
@@ -55,6 +87,11 @@ impl DataChannelManager {
         self.association.is_established()
     }
 
+    /// Current SCTP path MTU, for diagnostics
+    pub fn path_mtu(&self) -> usize {
+        self.association.path_mtu()
+    }
+
     /// Initialize SCTP association (send INIT packet)
     ///
     /// Call this after DTLS handshake to start SCTP association
@@ -75,19 +112,17 @@ impl DataChannelManager {
 
         // Send DATA_CHANNEL_OPEN message
         let open_msg = DataChannelOpen {
-            channel_type: if config.ordered {
-                ChannelType::Reliable
-            } else {
-                ChannelType::ReliableUnordered
-            },
+            channel_type: config.channel_type(),
             priority: 0,
-            reliability_param: 0,
-            label: config.label,
-            protocol: config.protocol,
+            reliability_param: config.reliability_param(),
+            label: config.label.clone(),
+            protocol: config.protocol.clone(),
         };
 
         self.association
             .send(stream_id, ppid::DCEP, open_msg.to_bytes())?;
+        self.association
+            .set_stream_reliability(stream_id, config.reliability_policy());
 
         self.channels.insert(stream_id, channel);
         Ok(stream_id)
@@ -98,31 +133,33 @@ impl DataChannelManager {
         self.create_channel(DataChannelConfig::file_transfer())
     }
 
-    /// Send data on a channel
-    pub fn send(&mut self, channel_id: u16, data: &[u8]) -> Result<(), &'static str> {
-        let channel = self
-            .channels
-            .get_mut(&channel_id)
-            .ok_or("Channel not found")?;
-
-        if !channel.is_open() {
-            return Err("Channel not open");
-        }
-
-        // Determine PPID (binary for file data)
+    /// Send a binary message on a channel (PPID 53)
+    pub fn send_binary(&mut self, channel_id: u16, data: &[u8]) -> Result<(), &'static str> {
         let ppid_value = if data.is_empty() {
             ppid::BINARY_EMPTY
         } else {
             ppid::BINARY
         };
+        self.send_with_ppid(channel_id, ppid_value, data)
+    }
 
-        self.association
-            .send(channel_id, ppid_value, data.to_vec())?;
-        Ok(())
+    /// Send a text message on a channel (PPID 51)
+    pub fn send_text(&mut self, channel_id: u16, text: &str) -> Result<(), &'static str> {
+        let ppid_value = if text.is_empty() {
+            ppid::STRING_EMPTY
+        } else {
+            ppid::STRING
+        };
+        self.send_with_ppid(channel_id, ppid_value, text.as_bytes())
     }
 
-    /// Send string data on a channel
-    pub fn send_string(&mut self, channel_id: u16, data: &str) -> Result<(), &'static str> {
+    /// Queue a user message with the given PPID
+    fn send_with_ppid(
+        &mut self,
+        channel_id: u16,
+        ppid_value: u32,
+        data: &[u8],
+    ) -> Result<(), &'static str> {
         let channel = self
             .channels
             .get_mut(&channel_id)
@@ -132,14 +169,9 @@ impl DataChannelManager {
             return Err("Channel not open");
         }
 
-        let ppid_value = if data.is_empty() {
-            ppid::STRING_EMPTY
-        } else {
-            ppid::STRING
-        };
-
         self.association
-            .send(channel_id, ppid_value, data.as_bytes().to_vec())?;
+            .send(channel_id, ppid_value, data.to_vec())?;
+        channel.on_buffered(data.len());
         Ok(())
     }
 
@@ -152,16 +184,66 @@ impl DataChannelManager {
             self.handle_received_data(stream_id, ppid_value, data);
         }
 
+        // Resets come after the data sent before them
+        while let Some(reset) = self.association.poll_stream_reset() {
+            self.handle_stream_reset(reset);
+        }
+
         responses
     }
 
+    /// Handle a completed SCTP stream reset
+    ///
+    /// The peer resetting its stream closes the channel from our side too.
+    /// Once both streams are reset the channel is removed and its stream ID
+    /// can be reused.
+    fn handle_stream_reset(&mut self, reset: StreamReset) {
+        let (stream_id, closed) = match reset {
+            StreamReset::Incoming(stream_id) => {
+                let Some(channel) = self.channels.get_mut(&stream_id) else {
+                    return;
+                };
+                if matches!(
+                    channel.state(),
+                    DataChannelState::Connecting | DataChannelState::Open
+                ) {
+                    channel.close();
+                    let _ = self.association.reset_streams(&[stream_id]);
+                }
+                (stream_id, channel.on_incoming_reset())
+            }
+            StreamReset::Outgoing(stream_id) => {
+                let Some(channel) = self.channels.get_mut(&stream_id) else {
+                    return;
+                };
+                (stream_id, channel.on_outgoing_reset())
+            }
+        };
+
+        if closed {
+            self.channels.remove(&stream_id);
+            // Only IDs from our half of the space are ours to allocate
+            if stream_id % 2 == self.next_stream_id % 2 {
+                self.free_stream_ids.insert(stream_id);
+            }
+            self.events
+                .push(DataChannelEvent::ChannelClosed { id: stream_id });
+        }
+    }
+
     /// Handle received data
     fn handle_received_data(&mut self, stream_id: u16, ppid_value: u32, data: Vec<u8>) {
         match ppid_value {
             ppid::DCEP => self.handle_dcep_message(stream_id, &data),
-            ppid::STRING | ppid::STRING_EMPTY | ppid::BINARY | ppid::BINARY_EMPTY => {
-                self.handle_user_data(stream_id, data);
-            }
+            ppid::STRING => match String::from_utf8(data) {
+                Ok(text) => self.handle_user_data(stream_id, Payload::Text(text)),
+                Err(_) => self.events.push(DataChannelEvent::Error {
+                    message: format!("Invalid UTF-8 text on channel {}", stream_id),
+                }),
+            },
+            ppid::STRING_EMPTY => self.handle_user_data(stream_id, Payload::Text(String::new())),
+            ppid::BINARY => self.handle_user_data(stream_id, Payload::Binary(data)),
+            ppid::BINARY_EMPTY => self.handle_user_data(stream_id, Payload::Binary(Vec::new())),
             _ => {
                 self.events.push(DataChannelEvent::Error {
                     message: format!("Unknown PPID: {}", ppid_value),
@@ -194,15 +276,9 @@ impl DataChannelManager {
     /// Handle DATA_CHANNEL_OPEN from remote
     fn handle_channel_open(&mut self, stream_id: u16, open: DataChannelOpen) {
         // Create channel for the incoming request
-        let config = DataChannelConfig {
-            label: open.label.clone(),
-            ordered: open.channel_type.is_ordered(),
-            max_retransmits: None,
-            max_packet_lifetime: None,
-            negotiated: false,
-            id: Some(stream_id),
-            protocol: open.protocol,
-        };
+        let config = DataChannelConfig::from_open(stream_id, &open);
+        self.association
+            .set_stream_reliability(stream_id, config.reliability_policy());
 
         let mut channel = DataChannel::new(stream_id, config);
         channel.on_open(); // Immediately open for incoming channels
@@ -233,12 +309,12 @@ impl DataChannelManager {
     }
 
     /// Handle user data
-    fn handle_user_data(&mut self, stream_id: u16, data: Vec<u8>) {
+    fn handle_user_data(&mut self, stream_id: u16, payload: Payload) {
         if let Some(channel) = self.channels.get_mut(&stream_id) {
-            channel.on_data(data.clone());
-            self.events.push(DataChannelEvent::DataReceived {
+            channel.on_data(payload.as_bytes().to_vec());
+            self.events.push(DataChannelEvent::Message {
                 id: stream_id,
-                data,
+                payload,
             });
         }
     }
@@ -268,8 +344,54 @@ impl DataChannelManager {
     }
 
     /// Get packet to send (if any)
+    ///
+    /// Drains the buffered amount of the channels whose data is in the packet,
+    /// or whose queued data PR-SCTP abandoned, and emits `BufferedAmountLow`
+    /// when one drops to its low-water mark.
     pub fn poll_send(&mut self) -> Option<SctpPacket> {
-        self.association.poll_send()
+        let packet = self.association.poll_send();
+
+        while let Some((stream_id, bytes)) = self.association.poll_abandoned_unsent() {
+            self.drain_buffered(stream_id, bytes);
+        }
+
+        let packet = packet?;
+        for chunk in &packet.chunks {
+            if let SctpChunk::Data(data) = chunk
+                && data.ppid != ppid::DCEP
+            {
+                self.drain_buffered(data.stream_id, data.data.len());
+            }
+        }
+
+        Some(packet)
+    }
+
+    /// Release `bytes` of a channel's buffered amount
+    fn drain_buffered(&mut self, stream_id: u16, bytes: usize) {
+        if let Some(channel) = self.channels.get_mut(&stream_id)
+            && channel.on_drained(bytes)
+        {
+            self.events
+                .push(DataChannelEvent::BufferedAmountLow { id: stream_id });
+        }
+    }
+
+    /// Take a pending `BufferedAmountLow` event for a channel, if any
+    ///
+    /// Lets a sender resume without draining unrelated events.
+    pub fn take_buffered_amount_low(&mut self, channel_id: u16) -> bool {
+        let position = self.events.iter().position(|event| {
+            matches!(event, DataChannelEvent::BufferedAmountLow { id } if *id == channel_id)
+        });
+
+        match position {
+            Some(index) => {
+                self.events.remove(index);
+                true
+            }
+            None => false,
+        }
     }
 
     /// Find any open file-transfer channel
@@ -277,27 +399,49 @@ impl DataChannelManager {
     /// This searches ALL channels in the manager, not just tracked IDs,
     /// to handle the case where the channel is open but the event hasn't been processed yet.
     pub fn find_open_file_channel(&self) -> Option<u16> {
-        for (&id, channel) in self.channels.iter() {
-            if channel.is_open() && channel.label() == "file-transfer" {
-                return Some(id);
-            }
-        }
-        None
+        self.find_open_channel("file-transfer")
+    }
+
+    /// Find any open channel with the given label
+    ///
+    /// Channels opened by either peer are considered, with the lowest stream
+    /// ID first so both peers pick the same one.
+    pub fn find_open_channel(&self, label: &str) -> Option<u16> {
+        self.channels
+            .iter()
+            .filter(|(_, channel)| channel.is_open() && channel.label() == label)
+            .map(|(&id, _)| id)
+            .min()
     }
 
     /// Close a channel
+    ///
+    /// Resets the channel's outgoing SCTP stream (RFC 8831 Section 6.7). The
+    /// channel closes, and `ChannelClosed` is emitted, once the peer reset its
+    /// stream as well.
     pub fn close_channel(&mut self, channel_id: u16) -> Result<(), &'static str> {
-        if let Some(channel) = self.channels.get_mut(&channel_id) {
-            channel.close();
-            // Note: SCTP doesn't have per-stream close, just stop using it
-            Ok(())
-        } else {
-            Err("Channel not found")
+        let channel = self
+            .channels
+            .get_mut(&channel_id)
+            .ok_or("Channel not found")?;
+
+        if matches!(
+            channel.state(),
+            DataChannelState::Closing | DataChannelState::Closed
+        ) {
+            return Ok(());
         }
+
+        channel.close();
+        self.association.reset_streams(&[channel_id])
     }
 
-    /// Allocate next stream ID
+    /// Allocate next stream ID, reusing those of closed channels first
     fn allocate_stream_id(&mut self) -> u16 {
+        if let Some(id) = self.free_stream_ids.pop_first() {
+            return id;
+        }
+
         let id = self.next_stream_id;
         self.next_stream_id += 2; // Skip by 2 (even/odd separation)
         id
@@ -330,6 +474,140 @@ mod tests {
         assert_eq!(manager.allocate_stream_id(), 4);
     }
 
+    /// Runs the SCTP handshake between two managers
+    fn establish_pair() -> (DataChannelManager, DataChannelManager) {
+        let mut client =
+            DataChannelManager::new(SctpAssociation::new(AssociationConfig::default()), true);
+        let mut server =
+            DataChannelManager::new(SctpAssociation::new(AssociationConfig::default()), false);
+
+        let init = SctpPacket::from_bytes(&client.init_association().unwrap()).unwrap();
+        let init_ack = server.process_packet(&init);
+        let cookie_echo = client.process_packet(&init_ack[0]);
+        let cookie_ack = server.process_packet(&cookie_echo[0]);
+        client.process_packet(&cookie_ack[0]);
+
+        assert!(client.is_established() && server.is_established());
+        (client, server)
+    }
+
+    /// Delivers every queued packet from `from` to `to` and feeds back the responses
+    fn exchange(from: &mut DataChannelManager, to: &mut DataChannelManager) {
+        while let Some(packet) = from.poll_send() {
+            for response in to.process_packet(&packet) {
+                from.process_packet(&response);
+            }
+        }
+    }
+
+    #[test]
+    fn test_buffered_amount_low_fires_after_draining() {
+        let (mut client, mut server) = establish_pair();
+        let config = DataChannelConfig {
+            buffered_amount_low_threshold: 100,
+            ..DataChannelConfig::reliable("chat")
+        };
+        let id = client.create_channel(config).unwrap();
+        exchange(&mut client, &mut server);
+        exchange(&mut server, &mut client);
+        client.drain_events();
+
+        for _ in 0..3 {
+            client.send_binary(id, &[0u8; 60]).unwrap();
+        }
+        let channel = client.get_channel(id).unwrap();
+        assert_eq!(channel.buffered_amount(), 180);
+        assert!(channel.is_buffered_amount_high());
+
+        // 120 bytes still queued: above the threshold
+        client.poll_send().unwrap();
+        assert!(!client.take_buffered_amount_low(id));
+
+        // 60 bytes queued: crossed the threshold
+        client.poll_send().unwrap();
+        assert!(client.take_buffered_amount_low(id));
+
+        client.poll_send().unwrap();
+        assert_eq!(client.get_channel(id).unwrap().buffered_amount(), 0);
+        assert!(client.drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_abandoned_queued_message_leaves_buffered_amount() {
+        let (mut client, mut server) = establish_pair();
+        let config = DataChannelConfig {
+            max_packet_lifetime: Some(0),
+            buffered_amount_low_threshold: 50,
+            ..DataChannelConfig::reliable("live")
+        };
+        let id = client.create_channel(config).unwrap();
+        exchange(&mut client, &mut server);
+        exchange(&mut server, &mut client);
+        client.drain_events();
+
+        client.send_binary(id, &[0u8; 80]).unwrap();
+        assert_eq!(client.get_channel(id).unwrap().buffered_amount(), 80);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+
+        // The message expires in the queue and is never sent
+        while let Some(packet) = client.poll_send() {
+            assert!(
+                !packet
+                    .chunks
+                    .iter()
+                    .any(|chunk| matches!(chunk, SctpChunk::Data(_)))
+            );
+        }
+        assert_eq!(client.get_channel(id).unwrap().buffered_amount(), 0);
+        assert!(client.take_buffered_amount_low(id));
+    }
+
+    #[test]
+    fn test_text_and_binary_messages() {
+        let (mut client, mut server) = establish_pair();
+        let id = client
+            .create_channel(DataChannelConfig::reliable("control"))
+            .unwrap();
+        exchange(&mut client, &mut server);
+        exchange(&mut server, &mut client);
+        server.drain_events();
+
+        client.send_text(id, r#"{"type":"ping"}"#).unwrap();
+        client.send_binary(id, &[0xDE, 0xAD]).unwrap();
+
+        let mut ppids = Vec::new();
+        while let Some(packet) = client.poll_send() {
+            for chunk in &packet.chunks {
+                if let SctpChunk::Data(data) = chunk {
+                    ppids.push(data.ppid);
+                }
+            }
+            for response in server.process_packet(&packet) {
+                client.process_packet(&response);
+            }
+        }
+        assert_eq!(ppids, vec![ppid::STRING, ppid::BINARY]);
+
+        let payloads: Vec<Payload> = server
+            .drain_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                DataChannelEvent::Message {
+                    id: msg_id,
+                    payload,
+                } if msg_id == id => Some(payload),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            payloads,
+            vec![
+                Payload::Text(r#"{"type":"ping"}"#.to_string()),
+                Payload::Binary(vec![0xDE, 0xAD]),
+            ]
+        );
+    }
+
     #[test]
     fn test_server_stream_ids() {
         let config = AssociationConfig::default();
@@ -340,4 +618,64 @@ mod tests {
         assert_eq!(manager.allocate_stream_id(), 1);
         assert_eq!(manager.allocate_stream_id(), 3);
     }
+
+    /// Payloads received on a channel, from the drained events
+    fn messages_on(manager: &mut DataChannelManager, channel_id: u16) -> Vec<Payload> {
+        manager
+            .drain_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                DataChannelEvent::Message { id, payload } if id == channel_id => Some(payload),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_closed_channel_id_reused_without_stale_data() {
+        let (mut client, mut server) = establish_pair();
+        let id = client
+            .create_channel(DataChannelConfig::reliable("chat"))
+            .unwrap();
+        exchange(&mut client, &mut server);
+        exchange(&mut server, &mut client);
+        server.drain_events();
+
+        // Data queued before the close still arrives
+        client.send_text(id, "old").unwrap();
+        client.close_channel(id).unwrap();
+        assert_eq!(
+            client.get_channel(id).map(|channel| channel.state()),
+            Some(DataChannelState::Closing)
+        );
+        exchange(&mut client, &mut server);
+        exchange(&mut server, &mut client);
+
+        assert_eq!(
+            messages_on(&mut server, id),
+            vec![Payload::Text("old".to_string())]
+        );
+        assert!(client.get_channel(id).is_none());
+        assert!(server.get_channel(id).is_none());
+        assert!(client.drain_events().iter().any(
+            |event| matches!(event, DataChannelEvent::ChannelClosed { id: closed } if *closed == id)
+        ));
+
+        let reopened = client
+            .create_channel(DataChannelConfig::reliable("chat"))
+            .unwrap();
+        assert_eq!(reopened, id);
+        exchange(&mut client, &mut server);
+        exchange(&mut server, &mut client);
+        server.drain_events();
+
+        client.send_text(id, "new").unwrap();
+        exchange(&mut client, &mut server);
+
+        assert_eq!(
+            messages_on(&mut server, id),
+            vec![Payload::Text("new".to_string())]
+        );
+        assert_eq!(server.get_channel(id).unwrap().bytes_received(), 3);
+    }
 }
@@ -7,11 +7,12 @@
 //! ```ignore
 //! let mut manager = DataChannelManager::new(sctp_association);
 //! let channel_id = manager.create_channel("file-transfer")?;
-//! manager.send(channel_id, &data)?;
+//! manager.send_binary(channel_id, &data)?;
+//! manager.send_text(channel_id, "hello")?;
 //! ```
 
 mod channel;
 mod manager;
 
 pub use channel::{DataChannel, DataChannelConfig, DataChannelState};
-pub use manager::{DataChannelEvent, DataChannelManager};
+pub use manager::{DataChannelEvent, DataChannelManager, Payload};
@@ -16,14 +16,20 @@ pub mod utils;
 
 // Re-export main types from submodules for backward compatibility
 pub use codec::{
-    ByePacket, H264RtpDepacketizer, H264RtpPacketizer, JitterBuffer, JitterBufferConfig,
-    JitterBufferStats, OpusRtpDepacketizer, OpusRtpPacketizer, PacketHandler, PacketStats,
-    ReceiverReport, RtcpPacketType, RtcpStats, RtpPacket, SenderReport,
+    BandwidthUsage, BitrateController, ByePacket, CompoundRtcpPacket, DelayBasedController,
+    FullIntraRequest, H264RtpDepacketizer, H264RtpPacketizer, JitterBuffer, JitterBufferConfig,
+    JitterBufferStats, OpusRtpDepacketizer, OpusRtpPacketizer, PacketHandler, PacketResult,
+    PacketStats, PacketizationMode, PictureLossIndication, PopResult, ReceiverReport, RtcpPacket,
+    RtcpPacketType, RtcpStats, RtpPacket, RtxRtpDepacketizer, RtxRtpPacketizer, SdesPacket,
+    SenderReport, TransportCcFeedback, Vp8RtpDepacketizer, Vp8RtpPacketizer, Vp9RtpDepacketizer,
+    Vp9RtpPacketizer,
 };
 pub use error::NetworkError;
-pub use security::{DtlsContext, SrtpContext, SrtpKeys};
+pub use security::{DtlsContext, SrtpCipherSuite, SrtpContext, SrtpKeys};
 pub use traits::{RtpDepacketizer, RtpPacketizer};
-pub use transport::{BasicUdpTransport, SecureUdpTransport, UdpTransport};
+pub use transport::{
+    BasicUdpTransport, Dscp, DscpConfig, MediaKind, SecureUdpTransport, UdpTransport,
+};
 pub use utils::find_available_port;
 
 pub type Result<T> = std::result::Result<T, NetworkError>;
@@ -3,10 +3,14 @@
 //! An SCTP association represents a connection between two endpoints.
 //! This implements the minimal state machine needed for WebRTC data channels.
 
-use super::chunk::{DataChunk, InitChunk, SackChunk, SctpChunk};
-use super::packet::SctpPacket;
-use std::collections::{BTreeMap, VecDeque};
-use std::time::Instant;
+use super::chunk::{
+    DataChunk, ForwardTsnChunk, InitChunk, ReconfigChunk, ReconfigParam, SackChunk, SctpChunk,
+    ppid, reconfig_result,
+};
+use super::packet::{SCTP_HEADER_SIZE, SctpPacket};
+use super::pmtu::PathMtuProber;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
 
 /// SCTP association states
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,6 +33,44 @@ pub enum AssociationState {
     ShutdownAckSent,
 }
 
+/// Partial reliability policy for an outbound stream (RFC 3758)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReliabilityPolicy {
+    /// Retransmit until acknowledged
+    #[default]
+    Reliable,
+    /// Abandon a message after this many retransmissions
+    MaxRetransmits(u16),
+    /// Abandon a message once it has been queued longer than this
+    MaxLifetime(Duration),
+}
+
+/// A completed stream reset (RFC 6525)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamReset {
+    /// The peer reset its outgoing stream: nothing more arrives on it
+    Incoming(u16),
+    /// The peer answered the reset of our outgoing stream
+    Outgoing(u16),
+}
+
+/// Outbound DATA chunk with partial reliability bookkeeping
+#[derive(Debug)]
+struct OutboundChunk {
+    chunk: DataChunk,
+    queued_at: Instant,
+    retransmits: u16,
+}
+
+/// DATA chunk header size in bytes
+const DATA_CHUNK_HEADER_SIZE: usize = 16;
+/// Heartbeat info parameter type (RFC 4960 Section 3.3.5)
+const HEARTBEAT_INFO_PARAM: u16 = 1;
+/// Size of a probe's HEARTBEAT chunk: header plus an 8-byte heartbeat info
+const PROBE_HEARTBEAT_SIZE: usize = 12;
+/// Size of a PAD chunk header
+const PAD_CHUNK_HEADER_SIZE: usize = 4;
+
 /// Configuration for SCTP association
 #[derive(Debug, Clone)]
 pub struct AssociationConfig {
@@ -42,6 +84,14 @@ pub struct AssociationConfig {
     pub max_inbound_streams: u16,
     /// Receiver window size
     pub recv_window: u32,
+    /// Reliability policy per outbound stream (streams not listed are reliable)
+    pub stream_reliability: HashMap<u16, ReliabilityPolicy>,
+    /// Path MTU used until a larger one is discovered (SCTP packet bytes)
+    pub initial_pmtu: usize,
+    /// Largest path MTU to probe for (SCTP packet bytes)
+    pub max_pmtu: usize,
+    /// Time without an acknowledgment before a PMTU probe counts as lost
+    pub pmtu_probe_timeout: Duration,
 }
 
 impl Default for AssociationConfig {
@@ -52,6 +102,10 @@ impl Default for AssociationConfig {
             max_outbound_streams: 65535,
             max_inbound_streams: 65535,
             recv_window: 131072, // 128KB
+            stream_reliability: HashMap::new(),
+            initial_pmtu: 1200,
+            max_pmtu: 1400, // Leaves room for DTLS, UDP and IP headers
+            pmtu_probe_timeout: Duration::from_secs(1),
         }
     }
 }
@@ -78,9 +132,17 @@ pub struct SctpAssociation {
     /// Number of inbound streams negotiated
     num_inbound_streams: u16,
     /// Outbound send queue
-    send_queue: VecDeque<DataChunk>,
+    send_queue: VecDeque<OutboundChunk>,
     /// Chunks waiting for acknowledgment
-    in_flight: BTreeMap<u32, DataChunk>,
+    in_flight: BTreeMap<u32, OutboundChunk>,
+    /// Highest abandoned stream sequence number per ordered stream
+    abandoned_streams: BTreeMap<u16, u16>,
+    /// Chunks were abandoned and the peer has not moved past them yet
+    forward_tsn_pending: bool,
+    /// Queued chunks abandoned before being sent, not yet polled (stream, bytes)
+    abandoned_unsent: VecDeque<(u16, usize)>,
+    /// New cumulative TSN of the last FORWARD-TSN sent from `poll_send`
+    last_forward_tsn: Option<u32>,
     /// Received chunks buffer (for reordering)
     receive_buffer: BTreeMap<u32, DataChunk>,
     /// Stream sequence numbers for outbound
@@ -92,6 +154,20 @@ pub struct SctpAssociation {
     cookie: Vec<u8>,
     /// Last activity time
     last_activity: Instant,
+    /// Path MTU discovery state
+    pmtu: PathMtuProber,
+    /// Sequence number of our next RE-CONFIG request
+    reconfig_request_seq: u32,
+    /// Sequence number of the last RE-CONFIG request received from the peer
+    peer_reconfig_seq: u32,
+    /// Outbound streams waiting for a reset request to be sent
+    pending_resets: Vec<u16>,
+    /// Our reset request awaiting its response (request seq, streams)
+    outgoing_reset: Option<(u32, Vec<u16>)>,
+    /// Peer reset request waiting for earlier DATA (request seq, last TSN, streams)
+    deferred_reset: Option<(u32, u32, Vec<u16>)>,
+    /// Completed stream resets not yet polled
+    stream_resets: VecDeque<StreamReset>,
 }
 
 impl SctpAssociation {
@@ -99,6 +175,11 @@ impl SctpAssociation {
     pub fn new(config: AssociationConfig) -> Self {
         let local_verification_tag: u32 = rand::random();
         let initial_tsn: u32 = rand::random();
+        let pmtu = PathMtuProber::new(
+            config.initial_pmtu,
+            config.max_pmtu,
+            config.pmtu_probe_timeout,
+        );
 
         Self {
             state: AssociationState::Closed,
@@ -112,11 +193,22 @@ impl SctpAssociation {
             num_inbound_streams: 0,
             send_queue: VecDeque::new(),
             in_flight: BTreeMap::new(),
+            abandoned_streams: BTreeMap::new(),
+            forward_tsn_pending: false,
+            abandoned_unsent: VecDeque::new(),
+            last_forward_tsn: None,
             receive_buffer: BTreeMap::new(),
             outbound_stream_seq: vec![0; 65536],
             // inbound_stream_seq: vec![0; 65536],
             cookie: Vec::new(),
             last_activity: Instant::now(),
+            pmtu,
+            reconfig_request_seq: initial_tsn,
+            peer_reconfig_seq: 0,
+            pending_resets: Vec::new(),
+            outgoing_reset: None,
+            deferred_reset: None,
+            stream_resets: VecDeque::new(),
         }
     }
 
@@ -130,6 +222,38 @@ impl SctpAssociation {
         self.state == AssociationState::Established
     }
 
+    /// Current path MTU: the largest SCTP packet sent, and the size DATA is
+    /// fragmented to
+    pub fn path_mtu(&self) -> usize {
+        self.pmtu.pmtu()
+    }
+
+    /// Report that a packet of `size` bytes could not reach the peer
+    ///
+    /// For transports that learn about oversized packets, e.g. through an
+    /// ICMP "packet too big" message or a failed send.
+    pub fn report_packet_too_big(&mut self, size: usize) {
+        self.pmtu.on_packet_too_big(size);
+    }
+
+    /// Set the reliability policy for an outbound stream
+    pub fn set_stream_reliability(&mut self, stream_id: u16, policy: ReliabilityPolicy) {
+        if policy == ReliabilityPolicy::Reliable {
+            self.config.stream_reliability.remove(&stream_id);
+        } else {
+            self.config.stream_reliability.insert(stream_id, policy);
+        }
+    }
+
+    /// Get the reliability policy for an outbound stream
+    pub fn stream_reliability(&self, stream_id: u16) -> ReliabilityPolicy {
+        self.config
+            .stream_reliability
+            .get(&stream_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Create INIT packet to start association
     pub fn create_init(&mut self) -> SctpPacket {
         self.state = AssociationState::CookieWait;
@@ -170,6 +294,10 @@ impl SctpAssociation {
             SctpChunk::Sack(sack) => self.handle_sack(sack),
             SctpChunk::Shutdown { cumulative_tsn } => self.handle_shutdown(*cumulative_tsn),
             SctpChunk::ShutdownAck => self.handle_shutdown_ack(),
+            SctpChunk::ForwardTsn(forward_tsn) => self.handle_forward_tsn(forward_tsn),
+            SctpChunk::Heartbeat(info) => self.handle_heartbeat(info),
+            SctpChunk::HeartbeatAck(info) => self.handle_heartbeat_ack(info),
+            SctpChunk::Reconfig(reconfig) => self.handle_reconfig(reconfig),
             _ => None,
         }
     }
@@ -282,14 +410,40 @@ impl SctpAssociation {
         // Store in receive buffer
         self.receive_buffer.insert(data.tsn, data.clone());
 
-        // Update cumulative TSN
+        self.advance_peer_last_tsn();
+        let mut packet = self.create_sack_packet();
+        self.complete_deferred_reset(&mut packet);
+        Some(packet)
+    }
+
+    /// Handle FORWARD-TSN chunk: skip TSNs the peer abandoned
+    fn handle_forward_tsn(&mut self, forward_tsn: &ForwardTsnChunk) -> Option<SctpPacket> {
+        if self.state != AssociationState::Established {
+            return None;
+        }
+
+        if tsn_gt(forward_tsn.new_cumulative_tsn, self.peer_last_tsn) {
+            self.peer_last_tsn = forward_tsn.new_cumulative_tsn;
+            self.advance_peer_last_tsn();
+        }
+
+        let mut packet = self.create_sack_packet();
+        self.complete_deferred_reset(&mut packet);
+        Some(packet)
+    }
+
+    /// Move the cumulative TSN over contiguously received chunks
+    fn advance_peer_last_tsn(&mut self) {
         while self
             .receive_buffer
             .contains_key(&self.peer_last_tsn.wrapping_add(1))
         {
             self.peer_last_tsn = self.peer_last_tsn.wrapping_add(1);
         }
+    }
 
+    /// Build a SACK acknowledging everything received so far
+    fn create_sack_packet(&self) -> SctpPacket {
         // Send SACK with gap blocks for out-of-order packets
         let mut sack = SackChunk::new(self.peer_last_tsn, self.config.recv_window);
 
@@ -343,7 +497,7 @@ impl SctpAssociation {
         );
         packet.add_chunk(SctpChunk::Sack(sack));
 
-        Some(packet)
+        packet
     }
 
     /// Handle SACK chunk
@@ -356,6 +510,7 @@ impl SctpAssociation {
             .retain(|tsn, _| tsn_gt(*tsn, self.cumulative_tsn_ack));
 
         // Process gap blocks: mark selectively acknowledged chunks
+        let mut highest_acked = sack.cumulative_tsn;
         for (start_offset, end_offset) in &sack.gap_ack_blocks {
             let start_tsn = sack.cumulative_tsn.wrapping_add(*start_offset as u32);
             let end_tsn = sack.cumulative_tsn.wrapping_add(*end_offset as u32);
@@ -363,25 +518,289 @@ impl SctpAssociation {
             // Remove acknowledged chunks in this gap
             self.in_flight
                 .retain(|tsn, _| !(*tsn >= start_tsn && *tsn <= end_tsn));
+
+            if tsn_gt(end_tsn, highest_acked) {
+                highest_acked = end_tsn;
+            }
+        }
+
+        // Give up on partially reliable chunks that exceeded their limits
+        self.abandon_in_flight(highest_acked);
+
+        let mut packet = SctpPacket::new(
+            self.config.local_port,
+            self.config.remote_port,
+            self.peer_verification_tag,
+        );
+
+        if let Some(forward_tsn) = self.create_forward_tsn() {
+            self.last_forward_tsn = Some(forward_tsn.new_cumulative_tsn);
+            packet.add_chunk(SctpChunk::ForwardTsn(forward_tsn));
         }
 
         // Retransmit missing packets (those still in in_flight after gap processing)
-        if !self.in_flight.is_empty() {
-            // Get the first missing chunk to retransmit
-            if let Some((&_tsn, chunk)) = self.in_flight.iter().next() {
-                let mut packet = SctpPacket::new(
-                    self.config.local_port,
-                    self.config.remote_port,
-                    self.peer_verification_tag,
-                );
-                packet.add_chunk(SctpChunk::Data(chunk.clone()));
-                return Some(packet);
+        if let Some(outbound) = self.in_flight.values_mut().next() {
+            outbound.retransmits = outbound.retransmits.saturating_add(1);
+            packet.add_chunk(SctpChunk::Data(outbound.chunk.clone()));
+        }
+
+        if packet.chunks.is_empty() {
+            None
+        } else {
+            Some(packet)
+        }
+    }
+
+    /// Abandon in-flight chunks whose stream policy says to stop retransmitting
+    ///
+    /// Only chunks below `highest_acked` count as lost for the retransmit limit;
+    /// later ones may simply still be on the wire.
+    fn abandon_in_flight(&mut self, highest_acked: u32) {
+        let abandoned: Vec<u32> = self
+            .in_flight
+            .iter()
+            .filter(|(tsn, outbound)| self.should_abandon(outbound, tsn_gt(highest_acked, **tsn)))
+            .map(|(tsn, _)| *tsn)
+            .collect();
+
+        for tsn in abandoned {
+            if let Some(outbound) = self.in_flight.remove(&tsn) {
+                self.abandon(&outbound.chunk);
+            }
+        }
+    }
+
+    /// Abandon queued chunks whose lifetime expired before they were sent
+    fn abandon_expired_queued(&mut self) {
+        let (expired, kept): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut self.send_queue)
+            .into_iter()
+            .partition(|outbound| self.should_abandon(outbound, false));
+
+        self.send_queue = kept;
+        for outbound in expired {
+            self.abandon(&outbound.chunk);
+            self.abandoned_unsent
+                .push_back((outbound.chunk.stream_id, outbound.chunk.data.len()));
+        }
+    }
+
+    fn should_abandon(&self, outbound: &OutboundChunk, missing: bool) -> bool {
+        // DCEP messages must always arrive
+        if outbound.chunk.ppid == ppid::DCEP {
+            return false;
+        }
+
+        match self.stream_reliability(outbound.chunk.stream_id) {
+            ReliabilityPolicy::Reliable => false,
+            ReliabilityPolicy::MaxRetransmits(max) => missing && outbound.retransmits >= max,
+            ReliabilityPolicy::MaxLifetime(lifetime) => outbound.queued_at.elapsed() > lifetime,
+        }
+    }
+
+    fn abandon(&mut self, chunk: &DataChunk) {
+        if !chunk.unordered {
+            self.abandoned_streams
+                .insert(chunk.stream_id, chunk.stream_seq);
+        }
+        self.forward_tsn_pending = true;
+    }
+
+    /// Highest TSN the peer can treat as received (RFC 3758 Advanced.Peer.Ack.Point)
+    fn advanced_peer_ack_point(&self) -> u32 {
+        let mut point = self.cumulative_tsn_ack;
+        loop {
+            let next = point.wrapping_add(1);
+            let outstanding = !tsn_gt(self.next_tsn, next)
+                || self.in_flight.contains_key(&next)
+                || self.send_queue.iter().any(|o| o.chunk.tsn == next);
+            if outstanding {
+                return point;
             }
+            point = next;
+        }
+    }
+
+    /// Build a FORWARD-TSN if abandoned chunks block the peer's cumulative TSN
+    fn create_forward_tsn(&mut self) -> Option<ForwardTsnChunk> {
+        if !self.forward_tsn_pending {
+            return None;
+        }
+
+        let point = self.advanced_peer_ack_point();
+        if point == self.cumulative_tsn_ack {
+            self.forward_tsn_pending = false;
+            self.abandoned_streams.clear();
+            return None;
+        }
+
+        let mut forward_tsn = ForwardTsnChunk::new(point);
+        forward_tsn.streams = self
+            .abandoned_streams
+            .iter()
+            .map(|(&stream_id, &stream_seq)| (stream_id, stream_seq))
+            .collect();
+        Some(forward_tsn)
+    }
+
+    /// Handle HEARTBEAT chunk: echo its heartbeat info
+    fn handle_heartbeat(&mut self, info: &[u8]) -> Option<SctpPacket> {
+        if self.state != AssociationState::Established {
+            return None;
         }
 
+        let mut packet = SctpPacket::new(
+            self.config.local_port,
+            self.config.remote_port,
+            self.peer_verification_tag,
+        );
+        packet.add_chunk(SctpChunk::HeartbeatAck(info.to_vec()));
+
+        Some(packet)
+    }
+
+    /// Handle HEARTBEAT-ACK chunk: a PMTU probe reached the peer
+    fn handle_heartbeat_ack(&mut self, info: &[u8]) -> Option<SctpPacket> {
+        if let [0x00, 0x01, 0x00, 0x08, size @ ..] = info
+            && let Ok(size) = <[u8; 4]>::try_from(size)
+        {
+            self.pmtu.on_probe_ack(u32::from_be_bytes(size) as usize);
+        }
         None
     }
 
+    /// Build a PMTU probe: a HEARTBEAT carrying the probe size, padded to it
+    fn create_pmtu_probe(&self, size: usize) -> SctpPacket {
+        let mut info = Vec::with_capacity(8);
+        info.extend_from_slice(&HEARTBEAT_INFO_PARAM.to_be_bytes());
+        info.extend_from_slice(&8u16.to_be_bytes());
+        info.extend_from_slice(&(size as u32).to_be_bytes());
+
+        let mut packet = SctpPacket::new(
+            self.config.local_port,
+            self.config.remote_port,
+            self.peer_verification_tag,
+        );
+        packet.add_chunk(SctpChunk::Heartbeat(info));
+        packet.add_chunk(SctpChunk::Pad(
+            size - SCTP_HEADER_SIZE - PROBE_HEARTBEAT_SIZE - PAD_CHUNK_HEADER_SIZE,
+        ));
+        packet
+    }
+
+    /// Handle RE-CONFIG chunk: reset requests from the peer and responses to ours
+    fn handle_reconfig(&mut self, reconfig: &ReconfigChunk) -> Option<SctpPacket> {
+        if self.state != AssociationState::Established {
+            return None;
+        }
+
+        let mut response = ReconfigChunk::default();
+        for param in &reconfig.params {
+            match param {
+                ReconfigParam::OutgoingReset {
+                    request_seq,
+                    last_tsn,
+                    streams,
+                    ..
+                } => {
+                    self.peer_reconfig_seq = *request_seq;
+                    if tsn_gt(*last_tsn, self.peer_last_tsn) {
+                        // DATA sent before the reset is still on its way
+                        self.deferred_reset = Some((*request_seq, *last_tsn, streams.clone()));
+                    } else {
+                        response
+                            .params
+                            .push(self.perform_incoming_reset(*request_seq, streams));
+                    }
+                }
+                ReconfigParam::Response {
+                    response_seq,
+                    result,
+                } => self.handle_reset_response(*response_seq, *result),
+            }
+        }
+
+        if response.params.is_empty() {
+            return None;
+        }
+
+        let mut packet = SctpPacket::new(
+            self.config.local_port,
+            self.config.remote_port,
+            self.peer_verification_tag,
+        );
+        packet.add_chunk(SctpChunk::Reconfig(response));
+        Some(packet)
+    }
+
+    /// Reset incoming streams and build the response to the peer's request
+    fn perform_incoming_reset(&mut self, request_seq: u32, streams: &[u16]) -> ReconfigParam {
+        self.stream_resets.extend(
+            streams
+                .iter()
+                .map(|&stream_id| StreamReset::Incoming(stream_id)),
+        );
+
+        ReconfigParam::Response {
+            response_seq: request_seq,
+            result: reconfig_result::SUCCESS_PERFORMED,
+        }
+    }
+
+    /// Perform a deferred peer reset once all DATA before it arrived
+    fn complete_deferred_reset(&mut self, packet: &mut SctpPacket) {
+        let ready = self
+            .deferred_reset
+            .as_ref()
+            .is_some_and(|(_, last_tsn, _)| !tsn_gt(*last_tsn, self.peer_last_tsn));
+
+        if ready && let Some((request_seq, _, streams)) = self.deferred_reset.take() {
+            let response = self.perform_incoming_reset(request_seq, &streams);
+            packet.add_chunk(SctpChunk::Reconfig(ReconfigChunk {
+                params: vec![response],
+            }));
+        }
+    }
+
+    /// Handle the peer's response to our reset request
+    fn handle_reset_response(&mut self, response_seq: u32, result: u32) {
+        let Some((_, streams)) = self
+            .outgoing_reset
+            .take_if(|(request_seq, _)| *request_seq == response_seq)
+        else {
+            return;
+        };
+
+        if result == reconfig_result::IN_PROGRESS {
+            // Ask again with the next poll
+            self.pending_resets.extend(streams);
+        } else {
+            // A denied reset leaves the stream unusable just the same
+            self.stream_resets
+                .extend(streams.into_iter().map(StreamReset::Outgoing));
+        }
+    }
+
+    /// Build the reset request for pending streams, one request at a time
+    fn create_reset_request(&mut self) -> Option<ReconfigChunk> {
+        if self.outgoing_reset.is_some() || self.pending_resets.is_empty() {
+            return None;
+        }
+
+        let streams = std::mem::take(&mut self.pending_resets);
+        let request_seq = self.reconfig_request_seq;
+        self.reconfig_request_seq = request_seq.wrapping_add(1);
+        self.outgoing_reset = Some((request_seq, streams.clone()));
+
+        Some(ReconfigChunk {
+            params: vec![ReconfigParam::OutgoingReset {
+                request_seq,
+                response_seq: self.peer_reconfig_seq,
+                last_tsn: self.next_tsn.wrapping_sub(1),
+                streams,
+            }],
+        })
+    }
+
     /// Handle SHUTDOWN chunk
     fn handle_shutdown(&mut self, _cumulative_tsn: u32) -> Option<SctpPacket> {
         self.state = AssociationState::ShutdownReceived;
@@ -411,6 +830,9 @@ impl SctpAssociation {
     }
 
     /// Queue data for sending on a stream
+    ///
+    /// Messages that do not fit in one packet at the current path MTU are
+    /// split into fragments with consecutive TSNs.
     pub fn send(&mut self, stream_id: u16, ppid: u32, data: Vec<u8>) -> Result<(), &'static str> {
         if self.state != AssociationState::Established {
             return Err("Association not established");
@@ -419,18 +841,70 @@ impl SctpAssociation {
         let stream_seq = self.outbound_stream_seq[stream_id as usize];
         self.outbound_stream_seq[stream_id as usize] = stream_seq.wrapping_add(1);
 
-        let chunk = DataChunk::new(self.next_tsn, stream_id, stream_seq, ppid, data);
-        self.next_tsn = self.next_tsn.wrapping_add(1);
+        let max_fragment = self.path_mtu() - SCTP_HEADER_SIZE - DATA_CHUNK_HEADER_SIZE;
+        let fragments: Vec<&[u8]> = if data.is_empty() {
+            vec![&[]]
+        } else {
+            data.chunks(max_fragment).collect()
+        };
+        let queued_at = Instant::now();
+
+        for (index, fragment) in fragments.iter().enumerate() {
+            let mut chunk = DataChunk::new(
+                self.next_tsn,
+                stream_id,
+                stream_seq,
+                ppid,
+                fragment.to_vec(),
+            );
+            chunk.beginning = index == 0;
+            chunk.ending = index == fragments.len() - 1;
+            self.next_tsn = self.next_tsn.wrapping_add(1);
+
+            self.send_queue.push_back(OutboundChunk {
+                chunk,
+                queued_at,
+                retransmits: 0,
+            });
+        }
+        Ok(())
+    }
+
+    /// Reset outbound streams (RFC 6525), e.g. when closing their data channels
+    ///
+    /// Their sequence numbers restart at 0, and the peer resets the matching
+    /// incoming streams once it received the DATA already queued. Completion
+    /// is reported by [`Self::poll_stream_reset`].
+    pub fn reset_streams(&mut self, streams: &[u16]) -> Result<(), &'static str> {
+        if self.state != AssociationState::Established {
+            return Err("Association not established");
+        }
 
-        self.send_queue.push_back(chunk);
+        for &stream_id in streams {
+            self.outbound_stream_seq[stream_id as usize] = 0;
+            if !self.pending_resets.contains(&stream_id) {
+                self.pending_resets.push(stream_id);
+            }
+        }
         Ok(())
     }
 
+    /// Take the next completed stream reset (if any)
+    pub fn poll_stream_reset(&mut self) -> Option<StreamReset> {
+        self.stream_resets.pop_front()
+    }
+
+    /// Take the next queued chunk abandoned before it was sent (if any)
+    ///
+    /// Returns its stream ID and user data length, so the sender can release
+    /// what it buffered for it.
+    pub fn poll_abandoned_unsent(&mut self) -> Option<(u16, usize)> {
+        self.abandoned_unsent.pop_front()
+    }
+
     /// Get next packet to send (if any)
     pub fn poll_send(&mut self) -> Option<SctpPacket> {
-        if self.send_queue.is_empty() {
-            return None;
-        }
+        self.abandon_expired_queued();
 
         let mut packet = SctpPacket::new(
             self.config.local_port,
@@ -438,34 +912,111 @@ impl SctpAssociation {
             self.peer_verification_tag,
         );
 
+        // Announce a new ack point once; later SACKs repeat it if it got lost
+        if let Some(forward_tsn) = self.create_forward_tsn()
+            && self.last_forward_tsn != Some(forward_tsn.new_cumulative_tsn)
+        {
+            self.last_forward_tsn = Some(forward_tsn.new_cumulative_tsn);
+            packet.add_chunk(SctpChunk::ForwardTsn(forward_tsn));
+            return Some(packet);
+        }
+
+        if let Some(reconfig) = self.create_reset_request() {
+            packet.add_chunk(SctpChunk::Reconfig(reconfig));
+            return Some(packet);
+        }
+
+        if self.is_established()
+            && let Some(size) = self.pmtu.poll_probe(Instant::now())
+        {
+            return Some(self.create_pmtu_probe(size));
+        }
+
         // Add chunks up to MTU
         // Simplified: only one chunk per packet for now
-        if let Some(chunk) = self.send_queue.pop_front() {
-            self.in_flight.insert(chunk.tsn, chunk.clone());
-            packet.add_chunk(SctpChunk::Data(chunk));
-        }
+        let outbound = self.send_queue.pop_front()?;
+        packet.add_chunk(SctpChunk::Data(outbound.chunk.clone()));
+        self.in_flight.insert(outbound.chunk.tsn, outbound);
 
         Some(packet)
     }
 
     /// Receive data from a stream
+    ///
+    /// Fragmented messages are returned once all their fragments arrived.
     pub fn recv(&mut self) -> Option<(u16, u32, Vec<u8>)> {
-        // Find all TSNs that are <= peer_last_tsn (in-order packets)
-        // Return the lowest one to maintain order
-        let in_order_tsns: Vec<u32> = self
-            .receive_buffer
-            .keys()
-            .copied()
-            .filter(|&tsn| !tsn_gt(tsn, self.peer_last_tsn))
-            .collect();
+        loop {
+            // Find all TSNs that are <= peer_last_tsn (in-order packets)
+            // Start from the lowest one to maintain order
+            let first_tsn = self
+                .receive_buffer
+                .keys()
+                .copied()
+                .filter(|&tsn| !tsn_gt(tsn, self.peer_last_tsn))
+                .min()?;
+
+            match self.message_end(first_tsn) {
+                Ok(last_tsn) => return Some(self.reassemble(first_tsn, last_tsn)),
+                Err(true) => return None,
+                Err(false) => self.discard_fragments(first_tsn),
+            }
+        }
+    }
 
-        if let Some(&min_tsn) = in_order_tsns.iter().min()
-            && let Some(chunk) = self.receive_buffer.remove(&min_tsn)
-        {
-            return Some((chunk.stream_id, chunk.ppid, chunk.data));
+    /// Find the TSN of the last fragment of the message starting at `first_tsn`
+    ///
+    /// # Returns
+    /// - `Ok(tsn)` - All fragments were received
+    /// - `Err(true)` - Later fragments are still expected
+    /// - `Err(false)` - The message can never complete: the peer abandoned some
+    ///   of its fragments (FORWARD-TSN)
+    fn message_end(&self, first_tsn: u32) -> Result<u32, bool> {
+        if !self.receive_buffer[&first_tsn].beginning {
+            return Err(false);
         }
 
-        None
+        let mut tsn = first_tsn;
+        loop {
+            match self.receive_buffer.get(&tsn) {
+                Some(chunk) if chunk.ending => return Ok(tsn),
+                Some(chunk) if tsn != first_tsn && chunk.beginning => return Err(false),
+                Some(_) => tsn = tsn.wrapping_add(1),
+                None => return Err(tsn_gt(tsn, self.peer_last_tsn)),
+            }
+        }
+    }
+
+    /// Remove the fragments from `first_tsn` to `last_tsn` and join their data
+    fn reassemble(&mut self, first_tsn: u32, last_tsn: u32) -> (u16, u32, Vec<u8>) {
+        let first = self
+            .receive_buffer
+            .remove(&first_tsn)
+            .expect("Fragment exists");
+        let (stream_id, ppid, mut data) = (first.stream_id, first.ppid, first.data);
+
+        let mut tsn = first_tsn;
+        while tsn != last_tsn {
+            tsn = tsn.wrapping_add(1);
+            if let Some(fragment) = self.receive_buffer.remove(&tsn) {
+                data.extend_from_slice(&fragment.data);
+            }
+        }
+
+        (stream_id, ppid, data)
+    }
+
+    /// Drop the buffered fragments of an incomplete message from `first_tsn` on
+    fn discard_fragments(&mut self, first_tsn: u32) {
+        self.receive_buffer.remove(&first_tsn);
+
+        let mut tsn = first_tsn.wrapping_add(1);
+        while let Some(chunk) = self.receive_buffer.get(&tsn) {
+            if chunk.beginning {
+                break;
+            }
+            self.receive_buffer.remove(&tsn);
+            tsn = tsn.wrapping_add(1);
+        }
     }
 
     /// Initiate shutdown
@@ -520,6 +1071,212 @@ mod tests {
         assert_eq!(assoc.state(), AssociationState::CookieWait);
     }
 
+    /// Configuration without PMTU probes, so only DATA and SACKs are exchanged
+    fn fixed_pmtu_config() -> AssociationConfig {
+        AssociationConfig {
+            max_pmtu: 1200,
+            ..AssociationConfig::default()
+        }
+    }
+
+    fn establish_pair() -> (SctpAssociation, SctpAssociation) {
+        establish_pair_with(fixed_pmtu_config())
+    }
+
+    fn establish_pair_with(config: AssociationConfig) -> (SctpAssociation, SctpAssociation) {
+        let mut client = SctpAssociation::new(config);
+        let mut server = SctpAssociation::new(fixed_pmtu_config());
+
+        let init = client.create_init();
+        let init_ack = server.process_packet(&init);
+        let cookie_echo = client.process_packet(&init_ack[0]);
+        let cookie_ack = server.process_packet(&cookie_echo[0]);
+        client.process_packet(&cookie_ack[0]);
+
+        assert!(client.is_established() && server.is_established());
+        (client, server)
+    }
+
+    /// Sends three messages on stream 1 and loses the first one on the wire.
+    /// Returns the last SACK from the receiver.
+    fn send_with_first_lost(
+        sender: &mut SctpAssociation,
+        receiver: &mut SctpAssociation,
+    ) -> SctpPacket {
+        for msg in [b"one", b"two", b"six"] {
+            sender.send(1, ppid::BINARY, msg.to_vec()).unwrap();
+        }
+
+        let _lost = sender.poll_send().unwrap();
+        let mut sack = None;
+        while let Some(packet) = sender.poll_send() {
+            sack = receiver.process_packet(&packet).pop();
+        }
+
+        // The gap stalls delivery of the later messages
+        assert!(receiver.recv().is_none());
+        sack.unwrap()
+    }
+
+    #[test]
+    fn test_abandoned_message_does_not_stall_stream() {
+        let (mut sender, mut receiver) = establish_pair();
+        sender.set_stream_reliability(1, ReliabilityPolicy::MaxRetransmits(0));
+
+        let sack = send_with_first_lost(&mut sender, &mut receiver);
+        let responses = sender.process_packet(&sack);
+
+        assert_eq!(responses.len(), 1);
+        assert!(
+            responses[0]
+                .chunks
+                .iter()
+                .any(|c| matches!(c, SctpChunk::ForwardTsn(_)))
+        );
+        assert!(
+            !responses[0]
+                .chunks
+                .iter()
+                .any(|c| matches!(c, SctpChunk::Data(_)))
+        );
+
+        receiver.process_packet(&responses[0]);
+        assert_eq!(
+            receiver.recv().map(|(_, _, data)| data),
+            Some(b"two".to_vec())
+        );
+        assert_eq!(
+            receiver.recv().map(|(_, _, data)| data),
+            Some(b"six".to_vec())
+        );
+        assert!(receiver.recv().is_none());
+
+        // Nothing left to send once the peer acknowledged the new ack point
+        assert!(sender.poll_send().is_none());
+    }
+
+    #[test]
+    fn test_reliable_stream_retransmits_lost_message() {
+        let (mut sender, mut receiver) = establish_pair();
+
+        let sack = send_with_first_lost(&mut sender, &mut receiver);
+        let responses = sender.process_packet(&sack);
+
+        assert_eq!(responses.len(), 1);
+        assert!(
+            !responses[0]
+                .chunks
+                .iter()
+                .any(|c| matches!(c, SctpChunk::ForwardTsn(_)))
+        );
+
+        receiver.process_packet(&responses[0]);
+        assert_eq!(
+            receiver.recv().map(|(_, _, data)| data),
+            Some(b"one".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_expired_lifetime_abandons_queued_message() {
+        let (mut sender, mut receiver) = establish_pair();
+        sender.set_stream_reliability(1, ReliabilityPolicy::MaxLifetime(Duration::ZERO));
+
+        sender.send(1, ppid::BINARY, b"stale".to_vec()).unwrap();
+        std::thread::sleep(Duration::from_millis(2));
+        sender.send(3, ppid::BINARY, b"fresh".to_vec()).unwrap();
+
+        // The expired message never goes out; the peer is told to skip it
+        while let Some(packet) = sender.poll_send() {
+            for sack in receiver.process_packet(&packet) {
+                sender.process_packet(&sack);
+            }
+        }
+
+        assert_eq!(
+            receiver.recv().map(|(_, _, data)| data),
+            Some(b"fresh".to_vec())
+        );
+    }
+
+    /// Mock transport carrying SCTP packets up to `max_size` bytes
+    ///
+    /// Larger packets are rejected and reported back to the sender, as an ICMP
+    /// "packet too big" would be.
+    struct MockTransport {
+        max_size: usize,
+    }
+
+    impl MockTransport {
+        /// Delivers everything the sender has queued, and the replies to it
+        fn pump(&self, sender: &mut SctpAssociation, receiver: &mut SctpAssociation) {
+            while let Some(packet) = sender.poll_send() {
+                let bytes = packet.to_bytes();
+                if bytes.len() > self.max_size {
+                    sender.report_packet_too_big(bytes.len());
+                    continue;
+                }
+
+                let received = SctpPacket::from_bytes(&bytes).unwrap();
+                for response in receiver.process_packet(&received) {
+                    sender.process_packet(&response);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pmtu_discovery_settles_on_largest_accepted_size() {
+        let (mut sender, mut receiver) = establish_pair_with(AssociationConfig::default());
+        assert_eq!(sender.path_mtu(), 1200);
+
+        let transport = MockTransport { max_size: 1320 };
+        transport.pump(&mut sender, &mut receiver);
+
+        assert_eq!(sender.path_mtu(), 1320);
+    }
+
+    #[test]
+    fn test_lost_pmtu_probes_back_off() {
+        let (mut sender, mut receiver) = establish_pair_with(AssociationConfig {
+            pmtu_probe_timeout: Duration::ZERO,
+            ..AssociationConfig::default()
+        });
+
+        // Oversized packets vanish without a report
+        while let Some(packet) = sender.poll_send() {
+            if packet.to_bytes().len() <= 1256 {
+                for response in receiver.process_packet(&packet) {
+                    sender.process_packet(&response);
+                }
+            }
+        }
+
+        assert_eq!(sender.path_mtu(), 1256);
+    }
+
+    #[test]
+    fn test_data_fragmented_to_path_mtu() {
+        let (mut sender, mut receiver) = establish_pair_with(AssociationConfig::default());
+        let transport = MockTransport { max_size: 1320 };
+        transport.pump(&mut sender, &mut receiver);
+
+        let message: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+        sender.send(1, ppid::BINARY, message.clone()).unwrap();
+
+        let mut fragments = 0;
+        while let Some(packet) = sender.poll_send() {
+            let bytes = packet.to_bytes();
+            assert!(bytes.len() <= 1320);
+            fragments += 1;
+            receiver.process_packet(&SctpPacket::from_bytes(&bytes).unwrap());
+        }
+
+        assert_eq!(fragments, 4);
+        assert_eq!(receiver.recv(), Some((1, ppid::BINARY, message)));
+        assert!(receiver.recv().is_none());
+    }
+
     #[test]
     fn test_tsn_comparison() {
         // Normal cases
@@ -35,6 +35,10 @@ pub enum SctpChunkType {
     CookieAck = 11,
     /// Shutdown complete
     ShutdownComplete = 14,
+    /// Stream re-configuration (RFC 6525)
+    Reconfig = 130,
+    /// Padding (RFC 4820), used to size path MTU probes
+    Pad = 132,
     /// Forward TSN (RFC 3758)
     ForwardTsn = 192,
 }
@@ -56,6 +60,8 @@ impl SctpChunkType {
             10 => Some(Self::CookieEcho),
             11 => Some(Self::CookieAck),
             14 => Some(Self::ShutdownComplete),
+            130 => Some(Self::Reconfig),
+            132 => Some(Self::Pad),
             192 => Some(Self::ForwardTsn),
             _ => None,
         }
@@ -380,6 +386,242 @@ impl SackChunk {
     }
 }
 
+/// FORWARD-TSN chunk for partial reliability (RFC 3758)
+///
+/// Tells the receiver to move its cumulative TSN forward past abandoned chunks.
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   Type = 192  |  Flags = 0x00 |        Length = Variable      |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                      New Cumulative TSN                       |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |         Stream-1              |       Stream Sequence-1       |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwardTsnChunk {
+    /// New cumulative TSN the receiver should assume
+    pub new_cumulative_tsn: u32,
+    /// Highest skipped stream sequence number per ordered stream
+    pub streams: Vec<(u16, u16)>,
+}
+
+impl ForwardTsnChunk {
+    /// Create new FORWARD-TSN chunk
+    pub fn new(new_cumulative_tsn: u32) -> Self {
+        Self {
+            new_cumulative_tsn,
+            streams: Vec::new(),
+        }
+    }
+
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let length = 8 + (self.streams.len() as u16 * 4);
+        let mut buf = Vec::with_capacity(length as usize);
+
+        buf.push(SctpChunkType::ForwardTsn as u8);
+        buf.push(0); // Flags
+        buf.extend_from_slice(&length.to_be_bytes());
+        buf.extend_from_slice(&self.new_cumulative_tsn.to_be_bytes());
+
+        for (stream_id, stream_seq) in &self.streams {
+            buf.extend_from_slice(&stream_id.to_be_bytes());
+            buf.extend_from_slice(&stream_seq.to_be_bytes());
+        }
+
+        buf
+    }
+
+    /// Parse from bytes
+    pub fn from_bytes(data: &[u8]) -> io::Result<Self> {
+        if data.len() < 8 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "FORWARD-TSN chunk too short",
+            ));
+        }
+
+        let declared_length = u16::from_be_bytes([data[2], data[3]]) as usize;
+        let new_cumulative_tsn = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+
+        let end = declared_length.min(data.len());
+        let mut streams = Vec::new();
+        let mut offset = 8;
+        while offset + 4 <= end {
+            let stream_id = u16::from_be_bytes([data[offset], data[offset + 1]]);
+            let stream_seq = u16::from_be_bytes([data[offset + 2], data[offset + 3]]);
+            streams.push((stream_id, stream_seq));
+            offset += 4;
+        }
+
+        Ok(Self {
+            new_cumulative_tsn,
+            streams,
+        })
+    }
+}
+
+/// Re-configuration response results (RFC 6525 Section 4.4)
+pub mod reconfig_result {
+    /// Success - Nothing to do
+    pub const SUCCESS_NOTHING_TO_DO: u32 = 0;
+    /// Success - Performed
+    pub const SUCCESS_PERFORMED: u32 = 1;
+    /// Denied
+    pub const DENIED: u32 = 2;
+    /// In progress: the request will be answered again later
+    pub const IN_PROGRESS: u32 = 6;
+}
+
+/// Outgoing SSN Reset Request parameter type
+const PARAM_OUTGOING_RESET: u16 = 13;
+/// Re-configuration Response parameter type
+const PARAM_RESPONSE: u16 = 16;
+
+/// Parameter of a RE-CONFIG chunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReconfigParam {
+    /// Outgoing SSN Reset Request: the sender restarts the sequence numbers of
+    /// `streams` after the DATA up to `last_tsn`
+    OutgoingReset {
+        request_seq: u32,
+        response_seq: u32,
+        last_tsn: u32,
+        streams: Vec<u16>,
+    },
+    /// Re-configuration Response to the request numbered `response_seq`
+    Response { response_seq: u32, result: u32 },
+}
+
+/// RE-CONFIG chunk for stream resets (RFC 6525)
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   Type = 130  |  Chunk Flags  |      Chunk Length             |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// \                                                               \
+/// /                  Re-configuration Parameter                   /
+/// \                                                               \
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// \                                                               \
+/// /             Re-configuration Parameter (optional)             /
+/// \                                                               \
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReconfigChunk {
+    /// One or two re-configuration parameters
+    pub params: Vec<ReconfigParam>,
+}
+
+impl ReconfigChunk {
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![SctpChunkType::Reconfig as u8, 0, 0, 0];
+
+        for param in &self.params {
+            // Parameters start on a 4-byte boundary; the chunk length leaves
+            // out the padding of the last one
+            if buf.len() % 4 != 0 {
+                buf.extend_from_slice(&[0, 0]);
+            }
+
+            let start = buf.len();
+            buf.extend_from_slice(&[0; 4]);
+            let param_type = match param {
+                ReconfigParam::OutgoingReset {
+                    request_seq,
+                    response_seq,
+                    last_tsn,
+                    streams,
+                } => {
+                    buf.extend_from_slice(&request_seq.to_be_bytes());
+                    buf.extend_from_slice(&response_seq.to_be_bytes());
+                    buf.extend_from_slice(&last_tsn.to_be_bytes());
+                    for stream_id in streams {
+                        buf.extend_from_slice(&stream_id.to_be_bytes());
+                    }
+                    PARAM_OUTGOING_RESET
+                }
+                ReconfigParam::Response {
+                    response_seq,
+                    result,
+                } => {
+                    buf.extend_from_slice(&response_seq.to_be_bytes());
+                    buf.extend_from_slice(&result.to_be_bytes());
+                    PARAM_RESPONSE
+                }
+            };
+            let param_length = (buf.len() - start) as u16;
+            buf[start..start + 2].copy_from_slice(&param_type.to_be_bytes());
+            buf[start + 2..start + 4].copy_from_slice(&param_length.to_be_bytes());
+        }
+
+        let length = buf.len() as u16;
+        buf[2..4].copy_from_slice(&length.to_be_bytes());
+        buf
+    }
+
+    /// Parse from bytes, skipping unknown parameters
+    pub fn from_bytes(data: &[u8]) -> io::Result<Self> {
+        let declared_length = u16::from_be_bytes([data[2], data[3]]) as usize;
+        let end = declared_length.min(data.len());
+        let read_u32 = |offset: usize| {
+            u32::from_be_bytes([
+                data[offset],
+                data[offset + 1],
+                data[offset + 2],
+                data[offset + 3],
+            ])
+        };
+
+        let mut params = Vec::new();
+        let mut offset = 4;
+        while offset + 4 <= end {
+            let param_type = u16::from_be_bytes([data[offset], data[offset + 1]]);
+            let param_length = u16::from_be_bytes([data[offset + 2], data[offset + 3]]) as usize;
+            if param_length < 4 || offset + param_length > end {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "RE-CONFIG parameter length exceeds chunk",
+                ));
+            }
+
+            match param_type {
+                PARAM_OUTGOING_RESET if param_length >= 16 => {
+                    let streams = data[offset + 16..offset + param_length]
+                        .chunks_exact(2)
+                        .map(|id| u16::from_be_bytes([id[0], id[1]]))
+                        .collect();
+                    params.push(ReconfigParam::OutgoingReset {
+                        request_seq: read_u32(offset + 4),
+                        response_seq: read_u32(offset + 8),
+                        last_tsn: read_u32(offset + 12),
+                        streams,
+                    });
+                }
+                PARAM_RESPONSE if param_length >= 12 => {
+                    params.push(ReconfigParam::Response {
+                        response_seq: read_u32(offset + 4),
+                        result: read_u32(offset + 8),
+                    });
+                }
+                _ => {}
+            }
+
+            offset += (param_length + 3) & !3;
+        }
+
+        Ok(Self { params })
+    }
+}
+
 /// Generic SCTP chunk wrapper
 #[derive(Debug, Clone)]
 pub enum SctpChunk {
@@ -395,12 +637,22 @@ pub enum SctpChunk {
     CookieEcho(Vec<u8>),
     /// Cookie Ack
     CookieAck,
+    /// Heartbeat request (opaque heartbeat info parameter)
+    Heartbeat(Vec<u8>),
+    /// Heartbeat acknowledgment echoing the request's heartbeat info
+    HeartbeatAck(Vec<u8>),
+    /// Padding bytes, ignored by the receiver
+    Pad(usize),
     /// Shutdown
     Shutdown { cumulative_tsn: u32 },
     /// Shutdown Ack
     ShutdownAck,
     /// Shutdown Complete
     ShutdownComplete,
+    /// Forward TSN (partial reliability)
+    ForwardTsn(ForwardTsnChunk),
+    /// Stream re-configuration (stream reset)
+    Reconfig(ReconfigChunk),
     /// Unknown chunk type
     Unknown { chunk_type: u8, data: Vec<u8> },
 }
@@ -429,6 +681,18 @@ impl SctpChunk {
                 Ok(SctpChunk::CookieEcho(cookie_data))
             }
             Some(SctpChunkType::CookieAck) => Ok(SctpChunk::CookieAck),
+            Some(SctpChunkType::Heartbeat) => Ok(SctpChunk::Heartbeat(
+                data[4..declared_length.min(data.len())].to_vec(),
+            )),
+            Some(SctpChunkType::HeartbeatAck) => Ok(SctpChunk::HeartbeatAck(
+                data[4..declared_length.min(data.len())].to_vec(),
+            )),
+            Some(SctpChunkType::Reconfig) => {
+                Ok(SctpChunk::Reconfig(ReconfigChunk::from_bytes(data)?))
+            }
+            Some(SctpChunkType::Pad) => Ok(SctpChunk::Pad(
+                declared_length.min(data.len()).saturating_sub(4),
+            )),
             Some(SctpChunkType::Shutdown) => {
                 if data.len() < 8 {
                     return Err(io::Error::new(
@@ -441,6 +705,9 @@ impl SctpChunk {
             }
             Some(SctpChunkType::ShutdownAck) => Ok(SctpChunk::ShutdownAck),
             Some(SctpChunkType::ShutdownComplete) => Ok(SctpChunk::ShutdownComplete),
+            Some(SctpChunkType::ForwardTsn) => {
+                Ok(SctpChunk::ForwardTsn(ForwardTsnChunk::from_bytes(data)?))
+            }
             _ => {
                 let chunk_len = declared_length.min(data.len());
                 Ok(SctpChunk::Unknown {
@@ -474,6 +741,9 @@ impl SctpChunk {
             SctpChunk::CookieAck => {
                 vec![SctpChunkType::CookieAck as u8, 0, 0, 4]
             }
+            SctpChunk::Heartbeat(info) => with_header(SctpChunkType::Heartbeat, info),
+            SctpChunk::HeartbeatAck(info) => with_header(SctpChunkType::HeartbeatAck, info),
+            SctpChunk::Pad(len) => with_header(SctpChunkType::Pad, &vec![0; *len]),
             SctpChunk::Shutdown { cumulative_tsn } => {
                 let mut buf = vec![SctpChunkType::Shutdown as u8, 0, 0, 8];
                 buf.extend_from_slice(&cumulative_tsn.to_be_bytes());
@@ -485,6 +755,8 @@ impl SctpChunk {
             SctpChunk::ShutdownComplete => {
                 vec![SctpChunkType::ShutdownComplete as u8, 0, 0, 4]
             }
+            SctpChunk::ForwardTsn(chunk) => chunk.to_bytes(),
+            SctpChunk::Reconfig(chunk) => chunk.to_bytes(),
             SctpChunk::Unknown { chunk_type, data } => {
                 let length = 4 + data.len() as u16;
                 let mut buf = Vec::with_capacity(length as usize);
@@ -504,6 +776,17 @@ impl SctpChunk {
     }
 }
 
+/// Serialize a chunk without flags made of a header and an opaque value
+fn with_header(chunk_type: SctpChunkType, value: &[u8]) -> Vec<u8> {
+    let length = 4 + value.len() as u16;
+    let mut buf = Vec::with_capacity(length as usize);
+    buf.push(chunk_type as u8);
+    buf.push(0);
+    buf.extend_from_slice(&length.to_be_bytes());
+    buf.extend_from_slice(value);
+    buf
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -543,4 +826,61 @@ mod tests {
         assert_eq!(parsed.cumulative_tsn, 100);
         assert_eq!(parsed.gap_ack_blocks.len(), 2);
     }
+
+    #[test]
+    fn test_reconfig_chunk_roundtrip() {
+        let chunk = ReconfigChunk {
+            params: vec![
+                ReconfigParam::OutgoingReset {
+                    request_seq: 7,
+                    response_seq: 3,
+                    last_tsn: 1000,
+                    streams: vec![2],
+                },
+                ReconfigParam::Response {
+                    response_seq: 3,
+                    result: reconfig_result::SUCCESS_PERFORMED,
+                },
+            ],
+        };
+
+        let bytes = SctpChunk::Reconfig(chunk.clone()).to_bytes();
+        // 18-byte reset request padded to 20, then a 12-byte response
+        assert_eq!(bytes.len(), 4 + 20 + 12);
+
+        match SctpChunk::from_bytes(&bytes).unwrap() {
+            SctpChunk::Reconfig(parsed) => assert_eq!(parsed, chunk),
+            other => panic!("Unexpected chunk: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_heartbeat_and_pad_chunks_roundtrip() {
+        let heartbeat = SctpChunk::Heartbeat(vec![0, 1, 0, 8, 0, 0, 5, 0xDC]);
+        match SctpChunk::from_bytes(&heartbeat.to_bytes()).unwrap() {
+            SctpChunk::Heartbeat(info) => assert_eq!(info, vec![0, 1, 0, 8, 0, 0, 5, 0xDC]),
+            other => panic!("Unexpected chunk: {:?}", other),
+        }
+
+        let pad = SctpChunk::Pad(100);
+        assert_eq!(pad.padded_len(), 104);
+        assert!(matches!(
+            SctpChunk::from_bytes(&pad.to_bytes()).unwrap(),
+            SctpChunk::Pad(100)
+        ));
+    }
+
+    #[test]
+    fn test_forward_tsn_chunk_roundtrip() {
+        let mut chunk = ForwardTsnChunk::new(4242);
+        chunk.streams.push((1, 7));
+
+        let bytes = SctpChunk::ForwardTsn(chunk.clone()).to_bytes();
+        assert_eq!(bytes.len(), 12);
+
+        match SctpChunk::from_bytes(&bytes).unwrap() {
+            SctpChunk::ForwardTsn(parsed) => assert_eq!(parsed, chunk),
+            other => panic!("Unexpected chunk: {:?}", other),
+        }
+    }
 }
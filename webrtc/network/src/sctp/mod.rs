@@ -21,19 +21,26 @@
 //! - DATA chunks with fragmentation
 //! - SACK (Selective Acknowledgment)
 //! - DCEP (Data Channel Establishment Protocol)
+//! - Partial reliability with FORWARD-TSN (RFC 3758)
+//! - Path MTU discovery with padded HEARTBEAT probes (RFC 8899)
+//! - Outgoing stream reset for closing data channels (RFC 6525)
 //!
 //! ## Not Implemented
 //!
 //! - Multi-homing
-//! - Path MTU discovery
-//! - Partial reliability extensions
 
 pub mod association;
 pub mod chunk;
 pub mod dcep;
 pub mod packet;
+mod pmtu;
 
-pub use association::{AssociationConfig, AssociationState, SctpAssociation};
-pub use chunk::{DataChunk, SctpChunk, SctpChunkType, ppid};
+pub use association::{
+    AssociationConfig, AssociationState, ReliabilityPolicy, SctpAssociation, StreamReset,
+};
+pub use chunk::{
+    DataChunk, ForwardTsnChunk, ReconfigChunk, ReconfigParam, SctpChunk, SctpChunkType, ppid,
+    reconfig_result,
+};
 pub use dcep::{ChannelType, DataChannelAck, DataChannelOpen};
 pub use packet::SctpPacket;
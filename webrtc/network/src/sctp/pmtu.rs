@@ -0,0 +1,151 @@
+//! Path MTU discovery for an SCTP association
+//!
+//! Packetization Layer PMTU Discovery (RFC 8899 Section 6.2.1): the
+//! association starts at a size every path is expected to carry and sends
+//! probe packets (a HEARTBEAT padded with a PAD chunk) of increasing size. An
+//! acknowledged probe raises the path MTU; a probe that is reported too big,
+//! or is lost `MAX_PROBES` times in a row, caps the search. Probe sizes are
+//! chosen by binary search between the two, in steps of 4 bytes since SCTP
+//! packets are 4-byte aligned.
+
+use std::time::{Duration, Instant};
+
+/// Attempts per probe size before the size is considered too big
+const MAX_PROBES: u8 = 3;
+/// SCTP packets are always a multiple of this size
+const PACKET_ALIGNMENT: usize = 4;
+
+/// A probe packet waiting for its HEARTBEAT-ACK
+#[derive(Debug)]
+struct Probe {
+    size: usize,
+    sent_at: Instant,
+    attempts: u8,
+}
+
+/// Tracks the discovered path MTU and drives the probe search
+#[derive(Debug)]
+pub(super) struct PathMtuProber {
+    /// Size every path is assumed to carry, used again when the path shrinks
+    base_pmtu: usize,
+    /// Largest packet size confirmed to reach the peer
+    pmtu: usize,
+    /// Smallest packet size known not to reach the peer
+    too_big: usize,
+    /// Time without an acknowledgment before a probe counts as lost
+    probe_timeout: Duration,
+    /// Probe in flight, if any
+    probe: Option<Probe>,
+}
+
+impl PathMtuProber {
+    /// Create a prober that searches between `base_pmtu` and `max_pmtu`
+    pub(super) fn new(base_pmtu: usize, max_pmtu: usize, probe_timeout: Duration) -> Self {
+        let base_pmtu = align_down(base_pmtu);
+        Self {
+            base_pmtu,
+            pmtu: base_pmtu,
+            too_big: align_down(max_pmtu.max(base_pmtu)) + PACKET_ALIGNMENT,
+            probe_timeout,
+            probe: None,
+        }
+    }
+
+    /// Current path MTU (largest SCTP packet size to send)
+    pub(super) fn pmtu(&self) -> usize {
+        self.pmtu
+    }
+
+    /// Returns the size of the next probe packet to send, if one is due
+    ///
+    /// Resends a lost probe, gives up on its size after `MAX_PROBES`
+    /// attempts, and starts a new probe while the search is not finished.
+    pub(super) fn poll_probe(&mut self, now: Instant) -> Option<usize> {
+        if let Some(probe) = &mut self.probe {
+            if now.duration_since(probe.sent_at) < self.probe_timeout {
+                return None;
+            }
+            if probe.attempts < MAX_PROBES {
+                probe.attempts += 1;
+                probe.sent_at = now;
+                return Some(probe.size);
+            }
+            self.too_big = probe.size;
+            self.probe = None;
+        }
+
+        let size = align_down((self.pmtu + self.too_big) / 2);
+        if size <= self.pmtu {
+            return None;
+        }
+
+        self.probe = Some(Probe {
+            size,
+            sent_at: now,
+            attempts: 1,
+        });
+        Some(size)
+    }
+
+    /// A probe of `size` bytes was acknowledged by the peer
+    pub(super) fn on_probe_ack(&mut self, size: usize) {
+        if self.probe.as_ref().is_some_and(|probe| probe.size == size) {
+            self.probe = None;
+        }
+        if size > self.pmtu && size < self.too_big {
+            self.pmtu = size;
+        }
+    }
+
+    /// A packet of `size` bytes could not be sent (e.g. ICMP "packet too big")
+    ///
+    /// A rejected probe just caps the search. A rejected regular packet means
+    /// the path shrank, so the search restarts from the base size.
+    pub(super) fn on_packet_too_big(&mut self, size: usize) {
+        if self.probe.as_ref().is_some_and(|probe| probe.size >= size) {
+            self.probe = None;
+        }
+        if size <= self.pmtu {
+            self.pmtu = self.base_pmtu;
+        }
+        self.too_big = self.too_big.min(size.max(self.pmtu + PACKET_ALIGNMENT));
+    }
+}
+
+/// Round a packet size down to the SCTP packet alignment
+fn align_down(size: usize) -> usize {
+    size - size % PACKET_ALIGNMENT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lost_probes_cap_the_search() {
+        let mut prober = PathMtuProber::new(1200, 1500, Duration::ZERO);
+        let now = Instant::now();
+
+        let size = prober.poll_probe(now).unwrap();
+        assert_eq!(size, 1352);
+        assert_eq!(prober.poll_probe(now), Some(size));
+        assert_eq!(prober.poll_probe(now), Some(size));
+
+        // Third loss: the next probe is smaller
+        assert_eq!(prober.poll_probe(now), Some(1276));
+        prober.on_probe_ack(1276);
+        assert_eq!(prober.pmtu(), 1276);
+    }
+
+    #[test]
+    fn test_packet_too_big_below_pmtu_restarts_search() {
+        let mut prober = PathMtuProber::new(1200, 1500, Duration::from_secs(1));
+        let probe = prober.poll_probe(Instant::now()).unwrap();
+        prober.on_probe_ack(probe);
+
+        prober.on_packet_too_big(1300);
+
+        assert_eq!(prober.pmtu(), 1200);
+        assert_eq!(prober.poll_probe(Instant::now()), Some(1248));
+    }
+}
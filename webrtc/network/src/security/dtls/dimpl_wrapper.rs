@@ -2,7 +2,8 @@
 //!
 //! Provides a Sans-IO DTLS engine that integrates with our UDP demultiplexer
 
-use super::SrtpKeys;
+use super::{DtlsContext, SrtpKeys};
+use crate::security::SrtpCipherSuite;
 use dimpl::{Config, Dtls, DtlsCertificate, KeyingMaterial, Output, SrtpProfile};
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -13,21 +14,48 @@ pub struct DtlsEngine {
     dtls: Dtls,
     cert: DtlsCertificate,
     srtp_keys: Option<SrtpKeys>,
+    peer_certificate: Option<Vec<u8>>,
     connected: bool,
     pending_packets: Vec<Vec<u8>>,
-    is_server: bool,             // Track role for SRTP key ordering
-    incoming_sctp: Vec<Vec<u8>>, // Buffer for received SCTP packets
+    is_server: bool,               // Track role for SRTP key ordering
+    incoming_sctp: Vec<Vec<u8>>,   // Buffer for received SCTP packets
+    next_timeout: Option<Instant>, // When dimpl's flight retransmission timer fires
+    handshake_retransmits: u32,
 }
 
 impl DtlsEngine {
     /// Create new DTLS engine using existing certificate (MUST match SDP fingerprint!)
     pub fn new(
+        is_server: bool,
+        remote_addr: SocketAddr,
+        cert: DtlsCertificate,
+    ) -> Result<Self, String> {
+        Self::with_config(is_server, remote_addr, cert, Config::default())
+    }
+
+    /// Create new DTLS engine with the certificate and handshake bounds of `context`
+    ///
+    /// The retransmission bounds and handshake timeout are applied by dimpl,
+    /// which resends lost flights itself.
+    pub fn from_context(
+        is_server: bool,
+        remote_addr: SocketAddr,
+        context: &DtlsContext,
+    ) -> Result<Self, String> {
+        let config = context
+            .retransmit_config()
+            .dimpl_config(context.handshake_timeout())?;
+        let cert = context.get_dimpl_certificate().clone();
+        Self::with_config(is_server, remote_addr, cert, config)
+    }
+
+    fn with_config(
         is_server: bool,
         _remote_addr: SocketAddr,
         cert: DtlsCertificate,
+        config: Config,
     ) -> Result<Self, String> {
-        let config = Arc::new(Config::default());
-        let mut dtls = Dtls::new(config, cert.clone());
+        let mut dtls = Dtls::new(Arc::new(config), cert.clone());
 
         // Set active (client) or passive (server)
         dtls.set_active(!is_server);
@@ -36,17 +64,21 @@ impl DtlsEngine {
             dtls,
             cert,
             srtp_keys: None,
+            peer_certificate: None,
             connected: false,
             pending_packets: Vec::new(),
             is_server,                 // Store role for key extraction
             incoming_sctp: Vec::new(), // Initialize SCTP buffer
+            next_timeout: None,
+            handshake_retransmits: 0,
         };
 
         // dimpl requires handle_timeout before poll_output
         // For client, this generates the initial ClientHello
+        let now = Instant::now();
         engine
             .dtls
-            .handle_timeout(Instant::now())
+            .handle_timeout(now)
             .map_err(|e| format!("Failed to initialize DTLS timeout: {:?}", e))?;
 
         // Drain the initial packets (ClientHello for client)
@@ -82,15 +114,34 @@ impl DtlsEngine {
     }
 
     /// Handle timeout (for retransmissions)
+    ///
+    /// dimpl resends the last handshake flight when its timer fires and fails
+    /// once the retry budget or the handshake timeout is exhausted.
     pub fn handle_timeout(&mut self, now: Instant) -> Result<(), String> {
+        let queued = self.pending_packets.len();
         self.dtls
             .handle_timeout(now)
             .map_err(|e| format!("DTLS timeout handling failed: {:?}", e))?;
 
         self.process_output()?;
+
+        // Output produced by a timer tick during the handshake is a resent flight
+        if !self.connected && self.pending_packets.len() > queued {
+            self.handshake_retransmits += 1;
+        }
         Ok(())
     }
 
+    /// Time at which `handle_timeout` should be called next, if a flight is pending
+    pub fn next_timeout(&self) -> Option<Instant> {
+        self.next_timeout
+    }
+
+    /// Total handshake flight retransmissions
+    pub fn handshake_retransmits(&self) -> u32 {
+        self.handshake_retransmits
+    }
+
     /// Process all pending output from dimpl
     fn process_output(&mut self) -> Result<(), String> {
         let mut out_buf = vec![0u8; 2048];
@@ -101,16 +152,18 @@ impl DtlsEngine {
                     // Store packet to send via UDP
                     self.pending_packets.push(packet.to_vec());
                 }
-                Output::Timeout(_instant) => {
-                    // Would schedule timer, but we'll poll regularly instead
+                Output::Timeout(instant) => {
+                    // Only the handshake needs timer ticks; afterwards we poll regularly
+                    self.next_timeout = (!self.connected).then_some(instant);
                     break;
                 }
                 Output::Connected => {
                     self.connected = true;
+                    self.next_timeout = None;
                 }
-                Output::PeerCert(_der) => {
-                    // Could validate peer certificate here
-                    // For WebRTC we verify fingerprint from SDP
+                Output::PeerCert(der) => {
+                    // Kept for fingerprint verification against the remote SDP
+                    self.peer_certificate = Some(der.to_vec());
                 }
                 Output::KeyingMaterial(km, profile) => {
                     // Extract SRTP keys
@@ -136,6 +189,11 @@ impl DtlsEngine {
         self.connected
     }
 
+    /// Get the DER certificate presented by the peer during the handshake
+    pub fn peer_certificate(&self) -> Option<&[u8]> {
+        self.peer_certificate.as_deref()
+    }
+
     /// Get extracted SRTP keys (if handshake complete)
     pub fn get_srtp_keys(&self) -> Option<&SrtpKeys> {
         self.srtp_keys.as_ref()
@@ -172,15 +230,15 @@ fn extract_srtp_keys(
     match profile {
         SrtpProfile::Aes128CmSha1_80 => {
             // AES-128: 16-byte keys, 14-byte salts (or 5 for dimpl compact format)
-            extract_keys_with_params(km, 16, 14, is_server)
+            extract_keys_with_params(km, 16, 14, SrtpCipherSuite::AesCm128HmacSha1_80, is_server)
         }
         SrtpProfile::AeadAes128Gcm => {
             // AES-128-GCM: 16-byte keys, 12-byte salts
-            extract_keys_with_params(km, 16, 12, is_server)
+            extract_keys_with_params(km, 16, 12, SrtpCipherSuite::AeadAes128Gcm, is_server)
         }
         SrtpProfile::AeadAes256Gcm => {
             // AES-256-GCM: 32-byte keys, 12-byte salts
-            extract_keys_with_params(km, 32, 12, is_server)
+            extract_keys_with_params(km, 32, 12, SrtpCipherSuite::AeadAes256Gcm, is_server)
         }
     }
 }
@@ -189,6 +247,7 @@ fn extract_keys_with_params(
     km: &KeyingMaterial,
     key_len: usize,
     salt_len: usize,
+    cipher_suite: SrtpCipherSuite,
     is_server: bool,
 ) -> Result<SrtpKeys, String> {
     // Validate total length
@@ -204,7 +263,7 @@ fn extract_keys_with_params(
     }
 
     // Extract from KeyingMaterial: client_key | client_salt | server_key | server_salt
-    let actual_key_len = std::cmp::min(key_len, 16);
+    let actual_key_len = std::cmp::min(key_len, 32);
     let actual_salt_len = std::cmp::min(salt_len, 14);
 
     let client_key_offset = 0;
@@ -212,9 +271,9 @@ fn extract_keys_with_params(
     let server_key_offset = key_len + salt_len;
     let server_salt_offset = key_len + salt_len + key_len;
 
-    let mut client_key = [0u8; 16];
+    let mut client_key = [0u8; 32];
     let mut client_salt = [0u8; 14];
-    let mut server_key = [0u8; 16];
+    let mut server_key = [0u8; 32];
     let mut server_salt = [0u8; 14];
 
     client_key[..actual_key_len]
@@ -241,5 +300,69 @@ fn extract_keys_with_params(
         local_master_salt,
         remote_master_key,
         remote_master_salt,
+        cipher_suite,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::dtls::RetransmitConfig;
+    use std::time::Duration;
+
+    #[test]
+    fn test_handshake_survives_lost_server_flight() {
+        let config = RetransmitConfig {
+            initial_timeout: Duration::from_millis(20),
+            max_retransmits: 6,
+        };
+        let addr: SocketAddr = "127.0.0.1:5000".parse().unwrap();
+        let mut client_ctx = DtlsContext::new().unwrap();
+        let mut server_ctx = DtlsContext::new().unwrap();
+        client_ctx.set_retransmit_config(config);
+        server_ctx.set_retransmit_config(config);
+        let mut client = DtlsEngine::from_context(false, addr, &client_ctx).unwrap();
+        let mut server = DtlsEngine::from_context(true, addr, &server_ctx).unwrap();
+
+        // Lossy in-memory link: the server's first flight never arrives
+        let mut dropped_server_flight = false;
+        let give_up = Instant::now() + Duration::from_secs(5);
+
+        while !(client.is_connected() && server.is_connected()) {
+            assert!(Instant::now() < give_up, "DTLS handshake stalled");
+
+            for packet in client.take_pending_packets() {
+                server.handle_packet(&packet).unwrap();
+            }
+
+            let server_flight = server.take_pending_packets();
+            if !dropped_server_flight && !server_flight.is_empty() {
+                dropped_server_flight = true;
+            } else {
+                for packet in server_flight {
+                    client.handle_packet(&packet).unwrap();
+                }
+            }
+
+            std::thread::sleep(Duration::from_millis(5));
+            client.handle_timeout(Instant::now()).unwrap();
+            server.handle_timeout(Instant::now()).unwrap();
+        }
+
+        assert!(dropped_server_flight);
+        assert!(client.handshake_retransmits() + server.handshake_retransmits() > 0);
+        let client_keys = client.get_srtp_keys().unwrap();
+        let server_keys = server.get_srtp_keys().unwrap();
+        // dimpl prefers AES-256-GCM; keys must keep their full 256 bits
+        assert_eq!(client_keys.cipher_suite, SrtpCipherSuite::AeadAes256Gcm);
+        assert_eq!(client_keys.local_master_key, server_keys.remote_master_key);
+        assert_ne!(client_keys.local_master_key[16..], [0u8; 16]);
+        assert!(client.next_timeout().is_none());
+
+        let server_cert = client.peer_certificate().unwrap();
+        assert!(
+            DtlsContext::verify_remote_fingerprint(server_ctx.get_fingerprint(), server_cert)
+                .is_ok()
+        );
+    }
+}
@@ -4,26 +4,41 @@
 
 mod certificate;
 mod dimpl_wrapper;
+mod retransmit;
 
 pub use certificate::compute_fingerprint;
 pub use dimpl::DtlsCertificate;
 pub use dimpl_wrapper::DtlsEngine;
+pub use retransmit::RetransmitConfig;
+
+use crate::security::SrtpCipherSuite;
+use std::time::Duration;
 
 /// SRTP key material extracted from DTLS
+///
+/// AES-128 suites use the first 16 bytes of each key and GCM suites the
+/// first 12 bytes of each salt.
 #[derive(Debug, Clone)]
 pub struct SrtpKeys {
-    pub local_master_key: [u8; 16],
+    pub local_master_key: [u8; 32],
     pub local_master_salt: [u8; 14],
-    pub remote_master_key: [u8; 16],
+    pub remote_master_key: [u8; 32],
     pub remote_master_salt: [u8; 14],
+    /// Negotiated SRTP protection profile
+    pub cipher_suite: SrtpCipherSuite,
 }
 
 /// DTLS context for WebRTC
 pub struct DtlsContext {
     dimpl_cert: dimpl::DtlsCertificate,
     local_fingerprint: String,
+    retransmit_config: RetransmitConfig,
+    handshake_timeout: Duration,
 }
 
+/// Overall bound on a DTLS handshake, independent of flight retransmissions
+const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl DtlsContext {
     /// Create a new DTLS context with self-signed certificate (uses dimpl for compatibility)
     pub fn new() -> Result<Self, String> {
@@ -43,6 +58,8 @@ impl DtlsContext {
         Ok(DtlsContext {
             dimpl_cert,
             local_fingerprint,
+            retransmit_config: RetransmitConfig::default(),
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
         })
     }
 
@@ -51,10 +68,62 @@ impl DtlsContext {
         &self.local_fingerprint
     }
 
+    /// Verify a peer certificate against the fingerprint from its SDP
+    ///
+    /// `expected` is colon-separated SHA-256 hex, optionally prefixed with
+    /// `sha-256 `; case is ignored. Digests are compared in constant time.
+    pub fn verify_remote_fingerprint(expected: &str, peer_cert_der: &[u8]) -> Result<(), String> {
+        let expected = expected.trim();
+        let hex = expected
+            .get(..8)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("sha-256 "))
+            .map_or(expected, |_| expected[8..].trim_start());
+
+        let expected_digest = hex
+            .split(':')
+            .map(|byte| match byte.len() {
+                2 => u8::from_str_radix(byte, 16).ok(),
+                _ => None,
+            })
+            .collect::<Option<Vec<u8>>>()
+            .filter(|digest| digest.len() == 32)
+            .ok_or_else(|| format!("Malformed SHA-256 fingerprint: {}", expected))?;
+
+        let actual_digest = openssl::sha::sha256(peer_cert_der);
+        if openssl::memcmp::eq(&actual_digest, &expected_digest) {
+            Ok(())
+        } else {
+            Err("Remote DTLS certificate does not match SDP fingerprint".to_string())
+        }
+    }
+
     /// Get dimpl certificate for DTLS engine
     pub fn get_dimpl_certificate(&self) -> &dimpl::DtlsCertificate {
         &self.dimpl_cert
     }
+
+    /// Get handshake retransmission bounds for DTLS engines
+    pub fn retransmit_config(&self) -> RetransmitConfig {
+        self.retransmit_config
+    }
+
+    /// Override handshake retransmission bounds (e.g. shorter timeouts in tests)
+    pub fn set_retransmit_config(&mut self, config: RetransmitConfig) {
+        self.retransmit_config = config;
+    }
+
+    /// Get the overall deadline for completing a DTLS handshake
+    pub fn handshake_timeout(&self) -> Duration {
+        self.handshake_timeout
+    }
+
+    /// Override the overall handshake deadline
+    ///
+    /// Bounds the handshake even when no flight is armed (e.g. a server that
+    /// never receives a ClientHello).
+    pub fn set_handshake_timeout(&mut self, timeout: Duration) {
+        self.handshake_timeout = timeout;
+    }
 }
 
 #[cfg(test)]
@@ -74,6 +143,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_verify_matching_fingerprint() {
+        let ctx = DtlsContext::new().unwrap();
+        let der = &ctx.get_dimpl_certificate().certificate;
+
+        assert!(DtlsContext::verify_remote_fingerprint(ctx.get_fingerprint(), der).is_ok());
+
+        // SDP values may carry the hash prefix and lowercase hex
+        let sdp_value = format!("sha-256 {}", ctx.get_fingerprint().to_lowercase());
+        assert!(DtlsContext::verify_remote_fingerprint(&sdp_value, der).is_ok());
+    }
+
+    #[test]
+    fn test_verify_mismatching_fingerprint() {
+        let ctx = DtlsContext::new().unwrap();
+        let attacker = DtlsContext::new().unwrap();
+        let attacker_der = &attacker.get_dimpl_certificate().certificate;
+
+        assert!(
+            DtlsContext::verify_remote_fingerprint(ctx.get_fingerprint(), attacker_der).is_err()
+        );
+        assert!(DtlsContext::verify_remote_fingerprint("AB:CD", attacker_der).is_err());
+    }
+
     #[test]
     fn test_fingerprint_format() {
         let ctx = DtlsContext::new().unwrap();
@@ -0,0 +1,61 @@
+//! DTLS handshake retransmission bounds (RFC 6347 Section 4.2.4)
+//!
+//! dimpl resends the last flight whenever its timer fires without progress
+//! from the peer. The timeout starts at `initial_timeout` and doubles (with
+//! jitter) for each retry; these bounds are passed to it through its config.
+
+use std::time::Duration;
+
+/// Retransmission timer bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetransmitConfig {
+    /// Timeout before the first retransmission of each flight
+    pub initial_timeout: Duration,
+    /// Retransmissions of a flight before the handshake is abandoned
+    pub max_retransmits: u32,
+}
+
+impl Default for RetransmitConfig {
+    fn default() -> Self {
+        Self {
+            initial_timeout: Duration::from_secs(1),
+            max_retransmits: 6,
+        }
+    }
+}
+
+impl RetransmitConfig {
+    /// Builds the dimpl configuration applying these bounds
+    ///
+    /// # Arguments
+    /// * `handshake_timeout` - Overall deadline for the handshake
+    pub(crate) fn dimpl_config(
+        &self,
+        handshake_timeout: Duration,
+    ) -> Result<dimpl::Config, String> {
+        dimpl::Config::builder()
+            .flight_start_rto(self.initial_timeout)
+            .flight_retries(self.max_retransmits as usize)
+            .handshake_timeout(handshake_timeout)
+            .build()
+            .map_err(|e| format!("Invalid DTLS configuration: {:?}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_reach_dimpl_config() {
+        let config = RetransmitConfig {
+            initial_timeout: Duration::from_millis(250),
+            max_retransmits: 3,
+        };
+
+        let dimpl_config = config.dimpl_config(Duration::from_secs(7)).unwrap();
+        assert_eq!(dimpl_config.flight_start_rto(), Duration::from_millis(250));
+        assert_eq!(dimpl_config.flight_retries(), 3);
+        assert_eq!(dimpl_config.handshake_timeout(), Duration::from_secs(7));
+    }
+}
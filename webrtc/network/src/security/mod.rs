@@ -6,4 +6,4 @@ pub mod dtls;
 pub mod srtp;
 
 pub use dtls::{DtlsContext, SrtpKeys};
-pub use srtp::SrtpContext;
+pub use srtp::{SrtpCipherSuite, SrtpContext};
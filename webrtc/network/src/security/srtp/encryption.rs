@@ -1,7 +1,9 @@
 //! SRTP encryption and decryption operations
 
 use crate::error::{NetworkError, Result};
-use aes::Aes128;
+use aes::{Aes128, Aes256};
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::{Aes128Gcm, Aes256Gcm, Nonce, Tag};
 use ctr::Ctr128BE;
 use ctr::cipher::{KeyIvInit, StreamCipher};
 use hmac::{Hmac, Mac};
@@ -9,14 +11,23 @@ use sha1::Sha1;
 
 type HmacSha1 = Hmac<Sha1>;
 type Aes128Ctr = Ctr128BE<Aes128>;
+type Aes256Ctr = Ctr128BE<Aes256>;
 
-/// Derives session encryption key from  master key using AES-CTR
-pub fn derive_session_key(master_key: &[u8; 16], ssrc: u32, label: u8) -> [u8; 16] {
-    // Build key derivation input: label || ssrc || 0x00...
+/// Key derivation input: label || ssrc || 0x00... || r (48 bits)
+fn derivation_input(label: u8, ssrc: u32, r: u64) -> [u8; 16] {
     let mut input = [0u8; 16];
     input[0] = label;
-    let ssrc_bytes = ssrc.to_be_bytes();
-    input[1..5].copy_from_slice(&ssrc_bytes);
+    input[1..5].copy_from_slice(&ssrc.to_be_bytes());
+    input[10..16].copy_from_slice(&r.to_be_bytes()[2..]);
+    input
+}
+
+/// Derives session encryption key from  master key using AES-CTR
+///
+/// `r` is the packet index divided by the key derivation rate, so keys roll
+/// every time the index crosses a rate boundary.
+pub fn derive_session_key(master_key: &[u8; 16], ssrc: u32, label: u8, r: u64) -> [u8; 16] {
+    let input = derivation_input(label, ssrc, r);
 
     // AES-CTR mode with master key and input as IV
     let cipher = Aes128Ctr::new(master_key.into(), &input.into());
@@ -32,8 +43,19 @@ pub fn derive_session_key(master_key: &[u8; 16], ssrc: u32, label: u8) -> [u8; 1
     output
 }
 
+/// Derives a 256-bit session key from a 256-bit master key (AES-256-CTR)
+pub fn derive_session_key_256(master_key: &[u8; 32], ssrc: u32, label: u8, r: u64) -> [u8; 32] {
+    let input = derivation_input(label, ssrc, r);
+
+    let mut cipher = Aes256Ctr::new(master_key.into(), &input.into());
+    let mut output = [0u8; 32];
+    cipher.apply_keystream(&mut output);
+
+    output
+}
+
 /// Derives session salt from master salt
-pub fn derive_session_salt(master_salt: &[u8; 14], ssrc: u32) -> [u8; 14] {
+pub fn derive_session_salt(master_salt: &[u8; 14], ssrc: u32, r: u64) -> [u8; 14] {
     let mut salt = [0u8; 14];
     salt.copy_from_slice(master_salt);
 
@@ -43,12 +65,18 @@ pub fn derive_session_salt(master_salt: &[u8; 14], ssrc: u32) -> [u8; 14] {
         salt[i] ^= ssrc_bytes[i];
     }
 
+    // XOR with key derivation index
+    let r_bytes = r.to_be_bytes();
+    for i in 0..6 {
+        salt[8 + i] ^= r_bytes[2 + i];
+    }
+
     salt
 }
 
 /// Derives authentication key (20 bytes for HMAC-SHA1)
-pub fn derive_auth_key(master_key: &[u8; 16], ssrc: u32) -> [u8; 20] {
-    let key16 = derive_session_key(master_key, ssrc, 0x01);
+pub fn derive_auth_key(master_key: &[u8; 16], ssrc: u32, r: u64) -> [u8; 20] {
+    let key16 = derive_session_key(master_key, ssrc, 0x01, r);
     let mut key20 = [0u8; 20];
     key20[..16].copy_from_slice(&key16);
     // Extend to 20 bytes by repeating first 4 bytes
@@ -57,13 +85,17 @@ pub fn derive_auth_key(master_key: &[u8; 16], ssrc: u32) -> [u8; 20] {
 }
 
 /// Builds IV for CTR mode encryption
-pub fn build_iv(salt: &[u8; 14], _ssrc: u32, seq: u16) -> [u8; 16] {
+pub fn build_iv(salt: &[u8; 14], roc: u32, seq: u16) -> [u8; 16] {
     let mut iv = [0u8; 16];
 
     // Copy salt
     iv[..14].copy_from_slice(salt);
 
-    // XOR with packet index
+    // XOR with packet index (ROC || SEQ)
+    let roc_bytes = roc.to_be_bytes();
+    for i in 0..4 {
+        iv[i] ^= roc_bytes[i];
+    }
     let seq_bytes = seq.to_be_bytes();
     iv[4] ^= seq_bytes[0];
     iv[5] ^= seq_bytes[1];
@@ -95,3 +127,72 @@ pub fn compute_auth_tag(key: &[u8; 20], data: &[u8]) -> Result<[u8; 10]> {
 
     Ok(tag)
 }
+
+/// Builds the 12-byte AEAD nonce (RFC 7714 Section 8.1)
+///
+/// `00 00 || SSRC || ROC || SEQ`, XORed with the 12-byte session salt.
+pub fn build_gcm_iv(salt: &[u8; 12], ssrc: u32, roc: u32, seq: u16) -> [u8; 12] {
+    let mut iv = [0u8; 12];
+    iv[2..6].copy_from_slice(&ssrc.to_be_bytes());
+    iv[6..10].copy_from_slice(&roc.to_be_bytes());
+    iv[10..12].copy_from_slice(&seq.to_be_bytes());
+
+    for (byte, salt_byte) in iv.iter_mut().zip(salt) {
+        *byte ^= salt_byte;
+    }
+
+    iv
+}
+
+/// Encrypts payload in place with AES-GCM, authenticating `aad`
+///
+/// A 16-byte key selects AES-128-GCM and a 32-byte key AES-256-GCM.
+/// Returns the 16-byte authentication tag.
+pub fn gcm_encrypt(key: &[u8], iv: &[u8; 12], aad: &[u8], payload: &mut [u8]) -> Result<[u8; 16]> {
+    let nonce = Nonce::from_slice(iv);
+    let tag = match key.len() {
+        16 => <Aes128Gcm as aes_gcm::KeyInit>::new(key.into())
+            .encrypt_in_place_detached(nonce, aad, payload),
+        32 => <Aes256Gcm as aes_gcm::KeyInit>::new(key.into())
+            .encrypt_in_place_detached(nonce, aad, payload),
+        len => {
+            return Err(NetworkError::CryptoError(format!(
+                "Invalid AES-GCM key length: {}",
+                len
+            )));
+        }
+    }
+    .map_err(|_| NetworkError::CryptoError("AES-GCM encryption failed".into()))?;
+
+    let mut tag_bytes = [0u8; 16];
+    tag_bytes.copy_from_slice(&tag);
+    Ok(tag_bytes)
+}
+
+/// Decrypts payload in place with AES-GCM, verifying `aad` and the tag
+pub fn gcm_decrypt(
+    key: &[u8],
+    iv: &[u8; 12],
+    aad: &[u8],
+    payload: &mut [u8],
+    tag: &[u8],
+) -> Result<()> {
+    if tag.len() != 16 {
+        return Err(NetworkError::InvalidPacket("Invalid GCM tag length".into()));
+    }
+
+    let (nonce, tag) = (Nonce::from_slice(iv), Tag::from_slice(tag));
+    match key.len() {
+        16 => <Aes128Gcm as aes_gcm::KeyInit>::new(key.into())
+            .decrypt_in_place_detached(nonce, aad, payload, tag),
+        32 => <Aes256Gcm as aes_gcm::KeyInit>::new(key.into())
+            .decrypt_in_place_detached(nonce, aad, payload, tag),
+        len => {
+            return Err(NetworkError::CryptoError(format!(
+                "Invalid AES-GCM key length: {}",
+                len
+            )));
+        }
+    }
+    .map_err(|_| NetworkError::InvalidPacket("Authentication failed".into()))
+}
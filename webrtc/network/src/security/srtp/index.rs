@@ -0,0 +1,99 @@
+//! SRTP packet index tracking (RFC 3711 Section 3.3.1)
+//!
+//! The 48-bit packet index is `ROC * 65536 + SEQ`, where the rollover
+//! counter (ROC) counts how many times the 16-bit sequence number wrapped.
+
+/// Rollover counter state for a single SSRC
+pub struct RolloverCounter {
+    roc: u32,
+    /// Highest sequence number seen so far (`s_l` in RFC 3711)
+    highest_seq: Option<u16>,
+}
+
+impl RolloverCounter {
+    pub fn new() -> Self {
+        Self {
+            roc: 0,
+            highest_seq: None,
+        }
+    }
+
+    /// Estimates the packet index for a sequence number (RFC 3711 Appendix A)
+    ///
+    /// Does not modify state; call `update` once the packet is accepted.
+    pub fn estimate(&self, seq: u16) -> u64 {
+        let Some(highest) = self.highest_seq else {
+            return seq as u64;
+        };
+
+        let roc = if highest < 0x8000 {
+            if seq > highest && seq - highest > 0x8000 {
+                self.roc.saturating_sub(1)
+            } else {
+                self.roc
+            }
+        } else if highest - 0x8000 > seq {
+            self.roc.wrapping_add(1)
+        } else {
+            self.roc
+        };
+
+        ((roc as u64) << 16) | seq as u64
+    }
+
+    /// Records an accepted packet index
+    pub fn update(&mut self, index: u64) {
+        let roc = (index >> 16) as u32;
+        let seq = index as u16;
+
+        match self.highest_seq {
+            None => {
+                self.roc = roc;
+                self.highest_seq = Some(seq);
+            }
+            Some(highest) => {
+                if roc > self.roc {
+                    self.roc = roc;
+                    self.highest_seq = Some(seq);
+                } else if roc == self.roc && seq > highest {
+                    self.highest_seq = Some(seq);
+                }
+            }
+        }
+    }
+}
+
+/// Splits a packet index into its rollover counter and sequence number
+pub fn split_index(index: u64) -> (u32, u16) {
+    ((index >> 16) as u32, index as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_follows_sequence() {
+        let mut counter = RolloverCounter::new();
+        for seq in 0..100u16 {
+            let index = counter.estimate(seq);
+            assert_eq!(index, seq as u64);
+            counter.update(index);
+        }
+    }
+
+    #[test]
+    fn test_rollover_increments_roc() {
+        let mut counter = RolloverCounter::new();
+        counter.update(counter.estimate(65534));
+        counter.update(counter.estimate(65535));
+
+        let index = counter.estimate(0);
+        assert_eq!(split_index(index), (1, 0));
+        counter.update(index);
+
+        // Late packet from before the wrap keeps the old ROC
+        assert_eq!(split_index(counter.estimate(65533)), (0, 65533));
+        assert_eq!(split_index(counter.estimate(1)), (1, 1));
+    }
+}
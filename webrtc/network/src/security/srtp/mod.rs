@@ -4,82 +4,301 @@
 //! and integrity of media streams.
 
 mod encryption;
+mod index;
 mod replay;
 
-use crate::codec::rtp::RtpPacket;
+use crate::codec::rtp::{RtpHeader, RtpPacket};
 use crate::error::{NetworkError, Result};
+use index::RolloverCounter;
 use replay::ReplayWindow;
 use std::collections::HashMap;
 
+/// RTP header length covered as additional authenticated data
+const RTP_HEADER_LEN: usize = 12;
+
+/// SRTP protection profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SrtpCipherSuite {
+    /// AES-128 counter mode with 80-bit HMAC-SHA1 tag (RFC 3711)
+    #[default]
+    AesCm128HmacSha1_80,
+    /// AEAD AES-128-GCM with 128-bit tag (RFC 7714)
+    AeadAes128Gcm,
+    /// AEAD AES-256-GCM with 128-bit tag (RFC 7714)
+    AeadAes256Gcm,
+}
+
+impl SrtpCipherSuite {
+    /// Length in bytes of the authentication tag appended to each packet
+    pub fn auth_tag_len(&self) -> usize {
+        match self {
+            SrtpCipherSuite::AesCm128HmacSha1_80 => 10,
+            SrtpCipherSuite::AeadAes128Gcm | SrtpCipherSuite::AeadAes256Gcm => 16,
+        }
+    }
+
+    /// Length in bytes of the master key
+    pub fn key_len(&self) -> usize {
+        match self {
+            SrtpCipherSuite::AesCm128HmacSha1_80 | SrtpCipherSuite::AeadAes128Gcm => 16,
+            SrtpCipherSuite::AeadAes256Gcm => 32,
+        }
+    }
+}
+
+/// Largest key derivation rate allowed by RFC 3711 (2^24)
+const MAX_KEY_DERIVATION_RATE: u64 = 1 << 24;
+
 /// SRTP context for encrypting/decrypting RTP packets
 pub struct SrtpContext {
-    master_key: [u8; 16],
+    cipher_suite: SrtpCipherSuite,
+    /// Master key; AES-128 suites use the first 16 bytes
+    master_key: [u8; 32],
     master_salt: [u8; 14],
+    /// Packets per session key; 0 derives session keys only once
+    key_derivation_rate: u64,
+    rollover_counters: HashMap<u32, RolloverCounter>,
     replay_windows: HashMap<u32, ReplayWindow>,
 }
 
 impl SrtpContext {
-    /// Creates a new SRTP context with the given master key and salt
+    /// Creates a new AES-CM/HMAC-SHA1 SRTP context with the given master key and salt
     pub fn new(master_key: [u8; 16], master_salt: [u8; 14]) -> Self {
+        Self::with_cipher_suite(
+            SrtpCipherSuite::AesCm128HmacSha1_80,
+            widen_key(master_key),
+            master_salt,
+        )
+    }
+
+    /// Creates a new AEAD_AES_128_GCM SRTP context (RFC 7714)
+    pub fn new_gcm(master_key: [u8; 16], master_salt: [u8; 12]) -> Self {
+        let mut salt = [0u8; 14];
+        salt[..12].copy_from_slice(&master_salt);
+        Self::with_cipher_suite(SrtpCipherSuite::AeadAes128Gcm, widen_key(master_key), salt)
+    }
+
+    /// Creates a new AEAD_AES_256_GCM SRTP context (RFC 7714)
+    pub fn new_gcm256(master_key: [u8; 32], master_salt: [u8; 12]) -> Self {
+        let mut salt = [0u8; 14];
+        salt[..12].copy_from_slice(&master_salt);
+        Self::with_cipher_suite(SrtpCipherSuite::AeadAes256Gcm, master_key, salt)
+    }
+
+    /// Creates a new SRTP context for the given cipher suite
+    ///
+    /// AES-128 suites use the first 16 bytes of `master_key` and GCM uses
+    /// the first 12 bytes of `master_salt`.
+    pub fn with_cipher_suite(
+        cipher_suite: SrtpCipherSuite,
+        master_key: [u8; 32],
+        master_salt: [u8; 14],
+    ) -> Self {
         Self {
+            cipher_suite,
             master_key,
             master_salt,
+            key_derivation_rate: 0,
+            rollover_counters: HashMap::new(),
             replay_windows: HashMap::new(),
         }
     }
 
+    /// Get the configured cipher suite
+    pub fn cipher_suite(&self) -> SrtpCipherSuite {
+        self.cipher_suite
+    }
+
+    /// Set the key derivation rate (RFC 3711 Section 4.3.1)
+    ///
+    /// Session keys are re-derived every `rate` packets. The rate must be
+    /// zero (derive once) or a power of two no larger than 2^24.
+    pub fn set_key_derivation_rate(&mut self, rate: u64) -> Result<()> {
+        if rate != 0 && (!rate.is_power_of_two() || rate > MAX_KEY_DERIVATION_RATE) {
+            return Err(NetworkError::Config(format!(
+                "Invalid SRTP key derivation rate: {}",
+                rate
+            )));
+        }
+        self.key_derivation_rate = rate;
+        Ok(())
+    }
+
+    /// Get the key derivation rate
+    pub fn key_derivation_rate(&self) -> u64 {
+        self.key_derivation_rate
+    }
+
+    /// Replace the master key and salt (e.g., after DTLS renegotiation)
+    ///
+    /// The key must be as long as the negotiated profile requires (see
+    /// [`SrtpCipherSuite::key_len`]). Packet indices keep counting, but replay
+    /// windows start over since packets protected with the old key can no
+    /// longer be authenticated.
+    pub fn rekey(&mut self, new_master_key: &[u8], new_master_salt: [u8; 14]) -> Result<()> {
+        let key_len = self.cipher_suite.key_len();
+        if new_master_key.len() != key_len {
+            return Err(NetworkError::Config(format!(
+                "SRTP master key for {:?} must be {} bytes, got {}",
+                self.cipher_suite,
+                key_len,
+                new_master_key.len()
+            )));
+        }
+
+        self.master_key = [0u8; 32];
+        self.master_key[..key_len].copy_from_slice(new_master_key);
+        self.master_salt = new_master_salt;
+        self.replay_windows.clear();
+        Ok(())
+    }
+
     /// Encrypts an RTP packet into an SRTP packet
     pub fn protect(&mut self, packet: &RtpPacket) -> Result<Vec<u8>> {
         let mut rtp_bytes = packet.to_bytes();
         self.validate_packet_size(&rtp_bytes)?;
 
         let ssrc = packet.header.ssrc;
-        let seq_num = packet.header.sequence_number;
-
-        self.encrypt_packet_payload(&mut rtp_bytes, ssrc, seq_num);
-        let auth_tag = self.authenticate_packet(&rtp_bytes, ssrc)?;
-
+        let index = self.estimate_index(ssrc, packet.header.sequence_number);
+
+        let auth_tag = match self.cipher_suite {
+            SrtpCipherSuite::AesCm128HmacSha1_80 => {
+                self.encrypt_packet_payload(&mut rtp_bytes, ssrc, index)?;
+                self.authenticate_packet(&rtp_bytes, ssrc, index)?.to_vec()
+            }
+            SrtpCipherSuite::AeadAes128Gcm | SrtpCipherSuite::AeadAes256Gcm => {
+                self.seal_packet_gcm(&mut rtp_bytes, ssrc, index)?.to_vec()
+            }
+        };
+
+        self.update_index(ssrc, index);
         rtp_bytes.extend_from_slice(&auth_tag);
         Ok(rtp_bytes)
     }
 
     /// Decrypts an SRTP packet into an RTP packet
     pub fn unprotect(&mut self, srtp_bytes: &[u8]) -> Result<RtpPacket> {
-        const AUTH_TAG_LEN: usize = 10;
+        let tag_len = self.cipher_suite.auth_tag_len();
 
-        if srtp_bytes.len() < 12 + AUTH_TAG_LEN {
+        if srtp_bytes.len() < RTP_HEADER_LEN + tag_len {
             return Err(NetworkError::InvalidPacket("SRTP packet too short".into()));
         }
 
-        let (rtp_bytes, received_tag) = self.split_packet_and_tag(srtp_bytes, AUTH_TAG_LEN);
+        let (rtp_bytes, received_tag) = self.split_packet_and_tag(srtp_bytes, tag_len);
         let (ssrc, seq_num) = self.parse_header_fields(rtp_bytes)?;
+        let index = self.estimate_index(ssrc, seq_num);
+
+        // Authenticate before touching the replay window so forged packets
+        // can't block legitimate sequence numbers
+        let packet = match self.cipher_suite {
+            SrtpCipherSuite::AesCm128HmacSha1_80 => {
+                self.verify_authentication(rtp_bytes, received_tag, ssrc, index)?;
+                self.decrypt_and_parse(rtp_bytes, ssrc, index)?
+            }
+            SrtpCipherSuite::AeadAes128Gcm | SrtpCipherSuite::AeadAes256Gcm => {
+                self.open_packet_gcm(rtp_bytes, received_tag, ssrc, index)?
+            }
+        };
+
+        self.check_replay(ssrc, index)?;
+        self.update_index(ssrc, index);
+        Ok(packet)
+    }
 
-        self.check_replay(ssrc, seq_num)?;
-        self.verify_authentication(rtp_bytes, received_tag, ssrc)?;
+    fn estimate_index(&self, ssrc: u32, seq_num: u16) -> u64 {
+        self.rollover_counters
+            .get(&ssrc)
+            .map_or(seq_num as u64, |counter| counter.estimate(seq_num))
+    }
 
-        self.decrypt_and_parse(rtp_bytes, ssrc, seq_num)
+    fn update_index(&mut self, ssrc: u32, index: u64) {
+        self.rollover_counters
+            .entry(ssrc)
+            .or_insert_with(RolloverCounter::new)
+            .update(index);
+    }
+
+    /// Key derivation input `r = index DIV key_derivation_rate`
+    fn derivation_index(&self, index: u64) -> u64 {
+        index.checked_div(self.key_derivation_rate).unwrap_or(0)
     }
 
     fn validate_packet_size(&self, rtp_bytes: &[u8]) -> Result<()> {
-        if rtp_bytes.len() < 12 {
+        if rtp_bytes.len() < RTP_HEADER_LEN {
             return Err(NetworkError::InvalidPacket("RTP packet too short".into()));
         }
         Ok(())
     }
 
-    fn encrypt_packet_payload(&self, rtp_bytes: &mut [u8], ssrc: u32, seq_num: u16) {
-        let session_key = encryption::derive_session_key(&self.master_key, ssrc, 0x00);
-        let session_salt = encryption::derive_session_salt(&self.master_salt, ssrc);
-        let iv = encryption::build_iv(&session_salt, ssrc, seq_num);
+    /// Master key for the AES-128 suites
+    fn master_key_128(&self) -> [u8; 16] {
+        let mut key = [0u8; 16];
+        key.copy_from_slice(&self.master_key[..16]);
+        key
+    }
 
-        let header_len = 12;
-        if rtp_bytes.len() > header_len {
-            encryption::encrypt_payload(&mut rtp_bytes[header_len..], &session_key, &iv);
-        }
+    /// Session key and IV for the GCM suites; the key is `key_len` bytes long
+    fn gcm_session_params(&self, ssrc: u32, index: u64) -> (Vec<u8>, [u8; 12]) {
+        let r = self.derivation_index(index);
+        let session_key = match self.cipher_suite {
+            SrtpCipherSuite::AeadAes256Gcm => {
+                encryption::derive_session_key_256(&self.master_key, ssrc, 0x00, r).to_vec()
+            }
+            _ => encryption::derive_session_key(&self.master_key_128(), ssrc, 0x00, r).to_vec(),
+        };
+        let mut session_salt = [0u8; 12];
+        session_salt.copy_from_slice(&self.master_salt[..12]);
+        let (roc, seq_num) = index::split_index(index);
+        let iv = encryption::build_gcm_iv(&session_salt, ssrc, roc, seq_num);
+        (session_key, iv)
+    }
+
+    fn seal_packet_gcm(&self, rtp_bytes: &mut [u8], ssrc: u32, index: u64) -> Result<[u8; 16]> {
+        let (session_key, iv) = self.gcm_session_params(ssrc, index);
+        // The whole header, CSRCs and extensions included, is AAD (RFC 7714 Section 8.2)
+        let header_len = header_len(rtp_bytes)?;
+        let (header, payload) = rtp_bytes.split_at_mut(header_len);
+        encryption::gcm_encrypt(&session_key, &iv, header, payload)
+    }
+
+    fn open_packet_gcm(
+        &self,
+        rtp_bytes: &[u8],
+        received_tag: &[u8],
+        ssrc: u32,
+        index: u64,
+    ) -> Result<RtpPacket> {
+        let (session_key, iv) = self.gcm_session_params(ssrc, index);
+
+        let header_len = header_len(rtp_bytes)?;
+        let mut decrypted = rtp_bytes.to_vec();
+        let (header, payload) = decrypted.split_at_mut(header_len);
+        encryption::gcm_decrypt(&session_key, &iv, header, payload, received_tag)?;
+
+        RtpPacket::from_bytes(&decrypted)
+    }
+
+    fn ctr_session_params(&self, ssrc: u32, index: u64) -> ([u8; 16], [u8; 16]) {
+        let r = self.derivation_index(index);
+        let session_key = encryption::derive_session_key(&self.master_key_128(), ssrc, 0x00, r);
+        let session_salt = encryption::derive_session_salt(&self.master_salt, ssrc, r);
+        let (roc, seq_num) = index::split_index(index);
+        let iv = encryption::build_iv(&session_salt, roc, seq_num);
+        (session_key, iv)
+    }
+
+    fn encrypt_packet_payload(&self, rtp_bytes: &mut [u8], ssrc: u32, index: u64) -> Result<()> {
+        let (session_key, iv) = self.ctr_session_params(ssrc, index);
+
+        // CSRCs and header extensions stay in the clear (RFC 3711 Section 3.1)
+        let header_len = header_len(rtp_bytes)?;
+        encryption::encrypt_payload(&mut rtp_bytes[header_len..], &session_key, &iv);
+        Ok(())
     }
 
-    fn authenticate_packet(&self, rtp_bytes: &[u8], ssrc: u32) -> Result<[u8; 10]> {
-        let auth_key = encryption::derive_auth_key(&self.master_key, ssrc);
+    fn authenticate_packet(&self, rtp_bytes: &[u8], ssrc: u32, index: u64) -> Result<[u8; 10]> {
+        let auth_key =
+            encryption::derive_auth_key(&self.master_key_128(), ssrc, self.derivation_index(index));
         encryption::compute_auth_tag(&auth_key, rtp_bytes)
     }
 
@@ -98,13 +317,13 @@ impl SrtpContext {
         Ok((ssrc, seq_num))
     }
 
-    fn check_replay(&mut self, ssrc: u32, seq_num: u16) -> Result<()> {
+    fn check_replay(&mut self, ssrc: u32, index: u64) -> Result<()> {
         let window = self
             .replay_windows
             .entry(ssrc)
             .or_insert_with(|| ReplayWindow::new(64));
 
-        if !window.check_and_update(seq_num as u64) {
+        if !window.check_and_update(index) {
             return Err(NetworkError::InvalidPacket("Replay attack detected".into()));
         }
         Ok(())
@@ -115,9 +334,9 @@ impl SrtpContext {
         rtp_bytes: &[u8],
         received_tag: &[u8],
         ssrc: u32,
+        index: u64,
     ) -> Result<()> {
-        let auth_key = encryption::derive_auth_key(&self.master_key, ssrc);
-        let computed_tag = encryption::compute_auth_tag(&auth_key, rtp_bytes)?;
+        let computed_tag = self.authenticate_packet(rtp_bytes, ssrc, index)?;
 
         if received_tag != &computed_tag[..] {
             return Err(NetworkError::InvalidPacket("Authentication failed".into()));
@@ -125,16 +344,12 @@ impl SrtpContext {
         Ok(())
     }
 
-    fn decrypt_and_parse(&self, rtp_bytes: &[u8], ssrc: u32, seq_num: u16) -> Result<RtpPacket> {
-        let session_key = encryption::derive_session_key(&self.master_key, ssrc, 0x00);
-        let session_salt = encryption::derive_session_salt(&self.master_salt, ssrc);
-        let iv = encryption::build_iv(&session_salt, ssrc, seq_num);
+    fn decrypt_and_parse(&self, rtp_bytes: &[u8], ssrc: u32, index: u64) -> Result<RtpPacket> {
+        let (session_key, iv) = self.ctr_session_params(ssrc, index);
 
+        let header_len = header_len(rtp_bytes)?;
         let mut decrypted = rtp_bytes.to_vec();
-        let header_len = 12;
-        if decrypted.len() > header_len {
-            encryption::encrypt_payload(&mut decrypted[header_len..], &session_key, &iv);
-        }
+        encryption::encrypt_payload(&mut decrypted[header_len..], &session_key, &iv);
 
         RtpPacket::from_bytes(&decrypted)
     }
@@ -143,15 +358,30 @@ impl SrtpContext {
     /// Call this when the remote peer's stream restarts (e.g., camera toggled off/on)
     pub fn reset_replay_protection(&mut self) {
         self.replay_windows.clear();
+        self.rollover_counters.clear();
     }
 
     /// Reset replay protection for a specific SSRC
     /// Useful when only one stream restarts
     pub fn reset_replay_protection_for_ssrc(&mut self, ssrc: u32) {
         self.replay_windows.remove(&ssrc);
+        self.rollover_counters.remove(&ssrc);
     }
 }
 
+/// Pads a 128-bit master key into the 256-bit key storage
+fn widen_key(master_key: [u8; 16]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key[..16].copy_from_slice(&master_key);
+    key
+}
+
+/// Length of the RTP header on the wire, CSRC list and extension block included
+fn header_len(rtp_bytes: &[u8]) -> Result<usize> {
+    let (_, len) = RtpHeader::from_bytes_with_len(rtp_bytes)?;
+    Ok(len)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,6 +418,238 @@ mod tests {
         assert_eq!(decrypted.header.sequence_number, 100);
     }
 
+    #[test]
+    fn test_srtp_gcm_encrypt_decrypt() {
+        let master_key = [0x2Au8; 16];
+        let master_salt = [0x3Bu8; 12];
+
+        let mut tx_context = SrtpContext::new_gcm(master_key, master_salt);
+        let mut rx_context = SrtpContext::new_gcm(master_key, master_salt);
+        assert_eq!(tx_context.cipher_suite(), SrtpCipherSuite::AeadAes128Gcm);
+
+        let mut header = RtpHeader::new(96, 12345);
+        header.sequence_number = 200;
+        header.timestamp = 3000;
+        let payload = b"Hello, AES-GCM!".to_vec();
+        let packet = RtpPacket::new(header, payload.clone());
+
+        let encrypted = tx_context.protect(&packet).unwrap();
+
+        // 12-byte header + ciphertext + 16-byte tag
+        assert_eq!(encrypted.len(), 12 + payload.len() + 16);
+        assert_ne!(&encrypted[12..12 + payload.len()], &payload[..]);
+
+        let decrypted = rx_context.unprotect(&encrypted).unwrap();
+        assert_eq!(decrypted.payload, payload);
+        assert_eq!(decrypted.header.sequence_number, 200);
+    }
+
+    #[test]
+    fn test_srtp_gcm_detects_header_tampering() {
+        let mut tx_context = SrtpContext::new_gcm([7u8; 16], [9u8; 12]);
+        let mut rx_context = SrtpContext::new_gcm([7u8; 16], [9u8; 12]);
+
+        let mut header = RtpHeader::new(96, 12345);
+        header.sequence_number = 1;
+        let packet = RtpPacket::new(header, vec![1, 2, 3, 4]);
+        let mut encrypted = tx_context.protect(&packet).unwrap();
+
+        // Flip the marker bit: header is AAD, so authentication must fail
+        encrypted[1] ^= 0x80;
+        assert!(rx_context.unprotect(&encrypted).is_err());
+
+        // The original packet is still accepted afterwards
+        encrypted[1] ^= 0x80;
+        assert!(rx_context.unprotect(&encrypted).is_ok());
+    }
+
+    #[test]
+    fn test_srtp_gcm_detects_payload_tampering() {
+        let mut tx_context = SrtpContext::new_gcm([7u8; 16], [9u8; 12]);
+        let mut rx_context = SrtpContext::new_gcm([7u8; 16], [9u8; 12]);
+
+        let packet = RtpPacket::new(RtpHeader::new(96, 12345), vec![1, 2, 3, 4]);
+        let mut encrypted = tx_context.protect(&packet).unwrap();
+
+        encrypted[13] ^= 0x01;
+        assert!(rx_context.unprotect(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_srtp_gcm256_encrypt_decrypt() {
+        let mut tx_context = SrtpContext::new_gcm256([0x4Cu8; 32], [0x5Du8; 12]);
+        let mut rx_context = SrtpContext::new_gcm256([0x4Cu8; 32], [0x5Du8; 12]);
+        assert_eq!(tx_context.cipher_suite().key_len(), 32);
+
+        let payload = b"Hello, AES-256-GCM!".to_vec();
+        let packet = RtpPacket::new(RtpHeader::new(96, 4321), payload.clone());
+        let encrypted = tx_context.protect(&packet).unwrap();
+        assert_eq!(encrypted.len(), 12 + payload.len() + 16);
+
+        let decrypted = rx_context.unprotect(&encrypted).unwrap();
+        assert_eq!(decrypted.payload, payload);
+
+        // The upper half of the key matters, unlike a truncated AES-128 key
+        let mut other_key = [0x4Cu8; 32];
+        other_key[31] ^= 0x01;
+        let mut wrong_context = SrtpContext::new_gcm256(other_key, [0x5Du8; 12]);
+        assert!(wrong_context.unprotect(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_srtp_gcm_authenticates_csrcs_and_extensions() {
+        let mut tx_context = SrtpContext::new_gcm([7u8; 16], [9u8; 12]);
+        let mut rx_context = SrtpContext::new_gcm([7u8; 16], [9u8; 12]);
+
+        let mut header = RtpHeader::new(96, 12345);
+        header.csrc = vec![0x1111_1111, 0x2222_2222];
+        header.set_extension(3, vec![0xAB, 0xCD]);
+        let header_size = header.size();
+        let payload = vec![1, 2, 3, 4];
+        let packet = RtpPacket::new(header, payload.clone());
+        let mut encrypted = tx_context.protect(&packet).unwrap();
+
+        // Header stays in the clear; the payload starts after the extension
+        assert_eq!(&encrypted[..header_size], &packet.to_bytes()[..header_size]);
+        assert_ne!(&encrypted[header_size..header_size + 4], &payload[..]);
+
+        // Tampering with a CSRC must fail authentication
+        encrypted[13] ^= 0x01;
+        assert!(rx_context.unprotect(&encrypted).is_err());
+        encrypted[13] ^= 0x01;
+
+        let decrypted = rx_context.unprotect(&encrypted).unwrap();
+        assert_eq!(decrypted.payload, payload);
+        assert_eq!(decrypted.header.csrc, vec![0x1111_1111, 0x2222_2222]);
+        assert_eq!(decrypted.header.extension(3), Some(&[0xAB, 0xCD][..]));
+    }
+
+    #[test]
+    fn test_srtp_leaves_csrcs_and_extensions_in_the_clear() {
+        let mut tx_context = SrtpContext::new([7u8; 16], [9u8; 14]);
+        let mut rx_context = SrtpContext::new([7u8; 16], [9u8; 14]);
+
+        let mut header = RtpHeader::new(96, 12345);
+        header.csrc = vec![0x1111_1111, 0x2222_2222];
+        header.set_extension(3, vec![0xAB, 0xCD]);
+        let header_size = header.size();
+        let payload = vec![1, 2, 3, 4];
+        let packet = RtpPacket::new(header, payload.clone());
+        let mut encrypted = tx_context.protect(&packet).unwrap();
+
+        // Encryption starts after the extension, so the header parses as sent
+        assert_eq!(&encrypted[..header_size], &packet.to_bytes()[..header_size]);
+        assert_ne!(&encrypted[header_size..header_size + 4], &payload[..]);
+
+        // CSRCs are still covered by the authentication tag
+        encrypted[13] ^= 0x01;
+        assert!(rx_context.unprotect(&encrypted).is_err());
+        encrypted[13] ^= 0x01;
+
+        let decrypted = rx_context.unprotect(&encrypted).unwrap();
+        assert_eq!(decrypted.payload, payload);
+        assert_eq!(decrypted.header.csrc, vec![0x1111_1111, 0x2222_2222]);
+        assert_eq!(decrypted.header.extension(3), Some(&[0xAB, 0xCD][..]));
+    }
+
+    #[test]
+    fn test_srtp_suites_are_not_interchangeable() {
+        let mut tx_context = SrtpContext::new_gcm([1u8; 16], [2u8; 12]);
+        let mut rx_context = SrtpContext::new([1u8; 16], [2u8; 14]);
+
+        let packet = RtpPacket::new(RtpHeader::new(96, 1), vec![5; 32]);
+        let encrypted = tx_context.protect(&packet).unwrap();
+
+        assert!(rx_context.unprotect(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_key_derivation_rate_crosses_boundary() {
+        let master_key = [0x11u8; 16];
+        let master_salt = [0x22u8; 14];
+
+        let mut tx_context = SrtpContext::new(master_key, master_salt);
+        let mut rx_context = SrtpContext::new(master_key, master_salt);
+        let mut fixed_context = SrtpContext::new(master_key, master_salt);
+        tx_context.set_key_derivation_rate(16).unwrap();
+        rx_context.set_key_derivation_rate(16).unwrap();
+
+        for seq in 0..40u16 {
+            let mut header = RtpHeader::new(96, 4242);
+            header.sequence_number = seq;
+            let packet = RtpPacket::new(header, vec![seq as u8; 20]);
+
+            let encrypted = tx_context.protect(&packet).unwrap();
+            let unrolled = fixed_context.protect(&packet).unwrap();
+
+            // Session keys only differ once the first boundary is crossed
+            assert_eq!(encrypted == unrolled, seq < 16);
+
+            let decrypted = rx_context.unprotect(&encrypted).unwrap();
+            assert_eq!(decrypted.payload, packet.payload);
+        }
+    }
+
+    #[test]
+    fn test_sequence_rollover_decrypts() {
+        let mut tx_context = SrtpContext::new_gcm([5u8; 16], [6u8; 12]);
+        let mut rx_context = SrtpContext::new_gcm([5u8; 16], [6u8; 12]);
+        tx_context.set_key_derivation_rate(1 << 16).unwrap();
+        rx_context.set_key_derivation_rate(1 << 16).unwrap();
+
+        for seq in (65530..=65535u16).chain(0..5) {
+            let mut header = RtpHeader::new(96, 99);
+            header.sequence_number = seq;
+            let packet = RtpPacket::new(header, vec![1, 2, 3]);
+
+            let encrypted = tx_context.protect(&packet).unwrap();
+            assert!(rx_context.unprotect(&encrypted).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_invalid_key_derivation_rate() {
+        let mut context = SrtpContext::new([0u8; 16], [0u8; 14]);
+        assert!(context.set_key_derivation_rate(3).is_err());
+        assert!(context.set_key_derivation_rate(1 << 25).is_err());
+        assert!(context.set_key_derivation_rate(0).is_ok());
+        assert_eq!(context.key_derivation_rate(), 0);
+    }
+
+    #[test]
+    fn test_rekey_resets_replay_window() {
+        let mut tx_context = SrtpContext::new([1u8; 16], [1u8; 14]);
+        let mut rx_context = SrtpContext::new([1u8; 16], [1u8; 14]);
+
+        let mut header = RtpHeader::new(96, 777);
+        header.sequence_number = 10;
+        let packet = RtpPacket::new(header, vec![9; 8]);
+
+        let old_encrypted = tx_context.protect(&packet).unwrap();
+        assert!(rx_context.unprotect(&old_encrypted).is_ok());
+
+        tx_context.rekey(&[2u8; 16], [2u8; 14]).unwrap();
+        rx_context.rekey(&[2u8; 16], [2u8; 14]).unwrap();
+
+        // Old-key packets no longer authenticate
+        assert!(rx_context.unprotect(&old_encrypted).is_err());
+
+        // Same sequence number under the new key is not treated as a replay
+        let new_encrypted = tx_context.protect(&packet).unwrap();
+        assert!(rx_context.unprotect(&new_encrypted).is_ok());
+    }
+
+    #[test]
+    fn test_rekey_checks_key_length_for_profile() {
+        let mut context = SrtpContext::new([1u8; 16], [1u8; 14]);
+        assert!(context.rekey(&[2u8; 32], [2u8; 14]).is_err());
+        assert!(context.rekey(&[2u8; 15], [2u8; 14]).is_err());
+
+        let mut gcm256 = SrtpContext::new_gcm256([1u8; 32], [1u8; 12]);
+        assert!(gcm256.rekey(&[2u8; 16], [2u8; 14]).is_err());
+        assert!(gcm256.rekey(&[2u8; 32], [2u8; 14]).is_ok());
+    }
+
     #[test]
     fn test_replay_protection() {
         let master_key = [1u8; 16];
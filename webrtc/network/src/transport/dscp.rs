@@ -0,0 +1,228 @@
+//! DSCP marking of media packets (RFC 8837)
+//!
+//! The DSCP value is written into the IP header through `IP_TOS` (IPv4) or
+//! `IPV6_TCLASS` (IPv6). Platforms without those options report
+//! `ErrorKind::Unsupported` and packets are sent unmarked.
+
+use socket2::SockRef;
+use std::io;
+use std::net::UdpSocket;
+
+/// Differentiated Services Code Point (6 bits)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Dscp(u8);
+
+impl Dscp {
+    /// Best effort, no marking
+    pub const DEFAULT: Dscp = Dscp(0);
+    /// Low-priority data (CS1)
+    pub const CS1: Dscp = Dscp(8);
+    /// Interactive video (AF41), recommended for video by RFC 8837
+    pub const AF41: Dscp = Dscp(34);
+    /// Expedited forwarding, recommended for audio by RFC 8837
+    pub const EF: Dscp = Dscp(46);
+
+    /// Create a code point, `None` if it does not fit in 6 bits
+    pub fn new(value: u8) -> Option<Self> {
+        (value < 64).then_some(Dscp(value))
+    }
+
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// Value of the IPv4 TOS / IPv6 traffic class byte (ECN bits cleared)
+    pub fn tos(&self) -> u32 {
+        u32::from(self.0) << 2
+    }
+}
+
+/// Kind of media carried by an RTP packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Audio,
+    Video,
+}
+
+/// DSCP value used for each media kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DscpConfig {
+    pub audio: Dscp,
+    pub video: Dscp,
+}
+
+impl DscpConfig {
+    /// EF for audio and AF41 for video, as recommended by RFC 8837
+    pub fn recommended() -> Self {
+        Self {
+            audio: Dscp::EF,
+            video: Dscp::AF41,
+        }
+    }
+
+    pub fn for_kind(&self, kind: MediaKind) -> Dscp {
+        match kind {
+            MediaKind::Audio => self.audio,
+            MediaKind::Video => self.video,
+        }
+    }
+}
+
+/// Mark every packet sent on `socket` with `dscp`
+pub fn set_socket_dscp(socket: &UdpSocket, dscp: Dscp) -> io::Result<()> {
+    let sock = SockRef::from(socket);
+    if socket.local_addr()?.is_ipv6() {
+        set_tclass_v6(&sock, dscp.tos())
+    } else {
+        set_tos_v4(&sock, dscp.tos())
+    }
+}
+
+/// DSCP currently applied to `socket`
+pub fn socket_dscp(socket: &UdpSocket) -> io::Result<Dscp> {
+    let sock = SockRef::from(socket);
+    let tos = if socket.local_addr()?.is_ipv6() {
+        tclass_v6(&sock)?
+    } else {
+        tos_v4(&sock)?
+    };
+    Ok(Dscp(((tos >> 2) & 0x3f) as u8))
+}
+
+#[cfg(not(any(
+    target_os = "fuchsia",
+    target_os = "redox",
+    target_os = "solaris",
+    target_os = "illumos",
+    target_os = "haiku",
+)))]
+fn set_tos_v4(sock: &SockRef<'_>, tos: u32) -> io::Result<()> {
+    sock.set_tos_v4(tos)
+}
+
+#[cfg(not(any(
+    target_os = "fuchsia",
+    target_os = "redox",
+    target_os = "solaris",
+    target_os = "illumos",
+    target_os = "haiku",
+)))]
+fn tos_v4(sock: &SockRef<'_>) -> io::Result<u32> {
+    sock.tos_v4()
+}
+
+#[cfg(any(
+    target_os = "fuchsia",
+    target_os = "redox",
+    target_os = "solaris",
+    target_os = "illumos",
+    target_os = "haiku",
+))]
+fn set_tos_v4(_sock: &SockRef<'_>, _tos: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(any(
+    target_os = "fuchsia",
+    target_os = "redox",
+    target_os = "solaris",
+    target_os = "illumos",
+    target_os = "haiku",
+))]
+fn tos_v4(_sock: &SockRef<'_>) -> io::Result<u32> {
+    Err(unsupported())
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "fuchsia",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+fn set_tclass_v6(sock: &SockRef<'_>, tclass: u32) -> io::Result<()> {
+    sock.set_tclass_v6(tclass)
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "fuchsia",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+fn tclass_v6(sock: &SockRef<'_>) -> io::Result<u32> {
+    sock.tclass_v6()
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "fuchsia",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+)))]
+fn set_tclass_v6(_sock: &SockRef<'_>, _tclass: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "fuchsia",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd",
+)))]
+fn tclass_v6(_sock: &SockRef<'_>) -> io::Result<u32> {
+    Err(unsupported())
+}
+
+// Only used on platforms missing one of the options
+#[allow(dead_code)]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "DSCP marking is not supported on this platform",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dscp_values() {
+        assert_eq!(Dscp::EF.tos(), 0xb8);
+        assert_eq!(Dscp::AF41.tos(), 0x88);
+        assert_eq!(Dscp::new(46), Some(Dscp::EF));
+        assert_eq!(Dscp::new(64), None);
+
+        let config = DscpConfig::recommended();
+        assert_eq!(config.for_kind(MediaKind::Audio), Dscp::EF);
+        assert_eq!(config.for_kind(MediaKind::Video), Dscp::AF41);
+        assert_eq!(DscpConfig::default().audio, Dscp::DEFAULT);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_socket_option_is_set() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        set_socket_dscp(&socket, Dscp::EF).unwrap();
+        assert_eq!(socket_dscp(&socket).unwrap(), Dscp::EF);
+
+        set_socket_dscp(&socket, Dscp::AF41).unwrap();
+        assert_eq!(socket_dscp(&socket).unwrap(), Dscp::AF41);
+    }
+}
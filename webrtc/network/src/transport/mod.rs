@@ -1,8 +1,10 @@
 //! Transport module - UDP transport implementations
 
+pub mod dscp;
 pub mod secure;
 pub mod udp;
 
+pub use dscp::{Dscp, DscpConfig, MediaKind};
 pub use secure::{
     PacketType, SecureUdpTransport, UdpTransport as BasicUdpTransport, classify_packet,
 };
@@ -1,12 +1,30 @@
-use crate::codec::rtcp::{ByePacket, ReceiverReport, RtcpPacketType, RtcpStats, SenderReport};
-use crate::codec::rtp::RtpPacket;
+use crate::codec::rtcp::transport_cc::TRANSPORT_CC_EXTENSION_ID;
+use crate::codec::rtcp::{
+    ByePacket, CompoundRtcpPacket, PacketResult, PictureLossIndication, ReceiverReport,
+    RtcpPacket, RtcpStats, SdesChunk, SdesPacket, SendHistory, SenderReport, TransportCcRecorder,
+};
+use crate::codec::lip_sync::NtpRtpMapping;
+use crate::codec::rtp::{RtpPacket, control_payload};
 use crate::error::MediaError;
 use crate::security::dtls::SrtpKeys;
 use crate::security::srtp::SrtpContext;
+use crate::transport::dscp::{self, Dscp, DscpConfig, MediaKind};
+use crate::utils::{bind_udp, unspecified_for};
+use ice::PacketClass;
+use std::collections::{HashMap, VecDeque};
 use std::net::{SocketAddr, UdpSocket};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime};
 
+/// Maximum STUN packets kept until the ICE layer collects them
+const MAX_BUFFERED_STUN: usize = 32;
+
+/// Interval between transport-cc feedback messages
+const TRANSPORT_CC_FEEDBACK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Maximum packet results kept until the send side collects them
+const MAX_PACKET_RESULTS: usize = 4096;
+
 /// Packet type classification for demultiplexing
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PacketType {
@@ -18,49 +36,21 @@ pub enum PacketType {
     Rtp,
     /// RTCP control packet
     Rtcp,
-    /// SCTP packet (raw, not wrapped in DTLS)
-    Sctp,
     Unknown,
 }
 
-/// Classify packet type by first byte (RFC 7983 + SCTP)
+/// Classify packet type by first byte (RFC 7983)
 ///
-/// - DTLS: 20-63 (content type)
-/// - STUN: 0-3 (first two bits 00)
-/// - RTP: 128-191 (version 2, first bit of padding)
-/// - RTCP: RTP with payload type 200-204
-/// - SCTP: Check for SCTP common header pattern
+/// Uses the ICE demultiplexing rule shared with candidate sockets
+/// ([`PacketClass`]) and splits its RTP class by payload type:
+/// RTCP packet types are 200-206.
 pub fn classify_packet(data: &[u8]) -> PacketType {
-    if data.is_empty() {
-        return PacketType::Unknown;
-    }
-
-    // Check for SCTP first (12-byte header minimum)
-    if data.len() >= 12 {
-        // SCTP packets often use port 5000 (0x1388) for WebRTC DataChannels
-        // Check if it looks like an SCTP packet structure
-        let first_byte = data[0];
-        if first_byte < 20 && first_byte != 0 && first_byte != 1 {
-            // Likely SCTP - not DTLS (20-63), not STUN (0-1), not RTP (128+)
-            return PacketType::Sctp;
-        }
-    }
-
-    match data[0] {
-        // DTLS records start with content type 20-63
-        20..=63 => PacketType::Dtls,
-        // STUN messages start with 0x00 or 0x01
-        0 | 1 => PacketType::Stun,
-        // RTP/RTCP have version 2 (bits 10xxxxxx = 128-191)
-        128..=191 => {
-            // Distinguish RTP from RTCP by payload type
-            if data.len() > 1 && data[1] >= 200 && data[1] <= 204 {
-                PacketType::Rtcp
-            } else {
-                PacketType::Rtp
-            }
-        }
-        _ => PacketType::Unknown,
+    match PacketClass::classify(data) {
+        PacketClass::Dtls => PacketType::Dtls,
+        PacketClass::Stun => PacketType::Stun,
+        PacketClass::Rtp if data.len() > 1 && (200..=206).contains(&data[1]) => PacketType::Rtcp,
+        PacketClass::Rtp => PacketType::Rtp,
+        PacketClass::Unknown => PacketType::Unknown,
     }
 }
 
@@ -81,6 +71,33 @@ impl UdpTransport {
         })
     }
 
+    /// Bind on `port` with the address family of `remote`
+    pub fn for_remote(port: u16, remote: SocketAddr) -> Result<Self, MediaError> {
+        let socket = bind_udp(SocketAddr::new(unspecified_for(remote.ip()), port))
+            .map_err(|e| MediaError::Network(format!("Failed to bind UDP: {}", e)))?;
+
+        Ok(UdpTransport {
+            socket,
+            remote_addr: None,
+        })
+    }
+
+    /// Rebind on the same port if `remote` is in the other address family
+    ///
+    /// Returns whether the socket was replaced.
+    pub fn rebind_for(&mut self, remote: SocketAddr) -> Result<bool, MediaError> {
+        let local = self
+            .socket
+            .local_addr()
+            .map_err(|e| MediaError::Network(format!("Failed to read local address: {}", e)))?;
+        if local.is_ipv6() == remote.is_ipv6() {
+            return Ok(false);
+        }
+
+        self.socket = UdpTransport::for_remote(local.port(), remote)?.socket;
+        Ok(true)
+    }
+
     pub fn set_remote(&mut self, addr: SocketAddr) {
         self.remote_addr = Some(addr);
     }
@@ -89,6 +106,12 @@ impl UdpTransport {
         &self.socket
     }
 
+    /// Mark every packet sent from now on with `dscp`
+    pub fn set_dscp(&self, dscp: Dscp) -> Result<(), MediaError> {
+        dscp::set_socket_dscp(&self.socket, dscp)
+            .map_err(|e| MediaError::Network(format!("Failed to set DSCP: {}", e)))
+    }
+
     pub fn send(&self, data: &[u8]) -> Result<(), MediaError> {
         if let Some(addr) = self.remote_addr {
             self.socket
@@ -128,14 +151,47 @@ pub struct SecureUdpTransport {
     last_sr_sent: Option<Instant>, // Last Sender Report time
     sr_interval: Duration,       // Sender Report interval (default: 5 seconds)
     rtp_buffer: Arc<Mutex<std::collections::VecDeque<RtpPacket>>>, // Buffer for RTP packets from unified receive
+    keyframe_requested: bool,    // Set when the peer sends a PLI or FIR
+    remote_ssrc: Option<u32>,    // SSRC of the last received RTP stream (for RR blocks)
+    cname: String,               // Stable CNAME announced in SDES for this session
+    remote_cnames: HashMap<u32, String>, // CNAMEs announced by the peer, by SSRC
+    sent_streams: HashMap<u32, SentStream>, // Per-SSRC sender state for stream SRs
+    remote_mappings: HashMap<u32, NtpRtpMapping>, // Latest SR NTP/RTP pair per remote SSRC
+    stun_buffer: VecDeque<(Vec<u8>, SocketAddr)>, // STUN packets for ICE consent, with sender
+    send_history: SendHistory,   // Transport-wide sequence numbers and send times
+    cc_recorder: TransportCcRecorder, // Arrival times of the peer's packets
+    cc_epoch: Instant,           // Origin of the transport-cc clock
+    last_cc_feedback: Option<Instant>, // Last transport-cc feedback time
+    packet_results: VecDeque<PacketResult>, // Feedback on our packets for the delay-based controller
+    bye_ssrcs: VecDeque<u32>,    // Remote SSRCs ended with RTCP BYE, not yet collected
+    transport_cc_extension_id: Option<u8>, // Header extension ID negotiated for transport-cc
+    dscp_config: Option<DscpConfig>, // DSCP per media kind, None leaves packets unmarked
+    marked_dscp: Option<Dscp>,   // DSCP currently set on the socket
+    audio_stream: Option<(u8, u32)>, // Payload type and SSRC of our audio stream
+}
+
+/// Sender state of one outgoing media stream
+struct SentStream {
+    rtp_timestamp: u32,
+    sent_at: SystemTime,
+    packets: u32,
+    bytes: u32,
 }
 
 impl SecureUdpTransport {
     /// Create a new secure transport from DTLS-derived keys
     pub fn new_from_dtls(udp_transport: UdpTransport, srtp_keys: SrtpKeys) -> Self {
-        let srtp_tx = SrtpContext::new(srtp_keys.local_master_key, srtp_keys.local_master_salt);
-
-        let srtp_rx = SrtpContext::new(srtp_keys.remote_master_key, srtp_keys.remote_master_salt);
+        let srtp_tx = SrtpContext::with_cipher_suite(
+            srtp_keys.cipher_suite,
+            srtp_keys.local_master_key,
+            srtp_keys.local_master_salt,
+        );
+
+        let srtp_rx = SrtpContext::with_cipher_suite(
+            srtp_keys.cipher_suite,
+            srtp_keys.remote_master_key,
+            srtp_keys.remote_master_salt,
+        );
 
         // Use a random SSRC for RTCP stats
         let ssrc = rand::random();
@@ -148,9 +204,97 @@ impl SecureUdpTransport {
             last_sr_sent: None,
             sr_interval: Duration::from_secs(5),
             rtp_buffer: Arc::new(Mutex::new(std::collections::VecDeque::new())),
+            keyframe_requested: false,
+            remote_ssrc: None,
+            cname: generate_cname(),
+            remote_cnames: HashMap::new(),
+            sent_streams: HashMap::new(),
+            remote_mappings: HashMap::new(),
+            stun_buffer: VecDeque::new(),
+            send_history: SendHistory::new(),
+            cc_recorder: TransportCcRecorder::new(),
+            cc_epoch: Instant::now(),
+            last_cc_feedback: None,
+            packet_results: VecDeque::new(),
+            bye_ssrcs: VecDeque::new(),
+            transport_cc_extension_id: Some(TRANSPORT_CC_EXTENSION_ID),
+            dscp_config: None,
+            marked_dscp: None,
+            audio_stream: None,
+        }
+    }
+
+    /// Sets the header extension ID negotiated for transport-wide sequence numbers
+    ///
+    /// `None` when the peer did not agree to the extension: packets are sent
+    /// unnumbered and no transport-cc feedback is generated.
+    pub fn set_transport_cc_extension_id(&mut self, id: Option<u8>) {
+        self.transport_cc_extension_id = id;
+    }
+
+    /// Marks outgoing audio and video with the DSCP values in `config`
+    ///
+    /// Fails without changing anything when the platform cannot set the
+    /// option, so callers can keep sending unmarked packets.
+    pub fn set_dscp_config(&mut self, config: DscpConfig) -> Result<(), MediaError> {
+        self.udp_transport.set_dscp(config.video)?;
+        self.marked_dscp = Some(config.video);
+        self.dscp_config = Some(config);
+        Ok(())
+    }
+
+    pub fn dscp_config(&self) -> Option<DscpConfig> {
+        self.dscp_config
+    }
+
+    /// Identifies our audio stream, taken from the audio packetizer
+    ///
+    /// RTP packets with this payload type and SSRC get the audio DSCP, every
+    /// other RTP packet the video one.
+    pub fn set_audio_stream(&mut self, payload_type: u8, ssrc: u32) {
+        self.audio_stream = Some((payload_type, ssrc));
+    }
+
+    /// Media kind an outgoing RTP packet is marked as
+    fn media_kind(&self, packet: &RtpPacket) -> MediaKind {
+        if self.audio_stream == Some((packet.header.payload_type, packet.header.ssrc)) {
+            MediaKind::Audio
+        } else {
+            MediaKind::Video
+        }
+    }
+
+    /// Switch the socket marking to `dscp` if it differs
+    fn mark(&mut self, dscp: Dscp) {
+        if self.dscp_config.is_none() || self.marked_dscp == Some(dscp) {
+            return;
+        }
+        // A failed switch only leaves the packet with the previous marking
+        if self.udp_transport.set_dscp(dscp).is_ok() {
+            self.marked_dscp = Some(dscp);
         }
     }
 
+    /// CNAME sent in SDES packets, stable for the lifetime of this transport
+    pub fn cname(&self) -> &str {
+        &self.cname
+    }
+
+    /// Override the CNAME sent in SDES packets
+    pub fn set_cname(&mut self, cname: impl Into<String>) {
+        self.cname = cname.into();
+    }
+
+    /// CNAME the peer announced for `ssrc`, if an SDES was received for it
+    pub fn remote_cname(&self, ssrc: u32) -> Option<&str> {
+        self.remote_cnames.get(&ssrc).map(String::as_str)
+    }
+
+    /// NTP/RTP timestamp pair from the latest Sender Report for `ssrc`
+    pub fn remote_ntp_mapping(&self, ssrc: u32) -> Option<NtpRtpMapping> {
+        self.remote_mappings.get(&ssrc).copied()
+    }
+
     /// Get reference to media socket for DTLS handshake (Sans-IO dimpl)
     pub fn socket(&self) -> &UdpSocket {
         self.udp_transport.socket()
@@ -158,8 +302,16 @@ impl SecureUdpTransport {
 
     /// Update SRTP keys after DTLS handshake completes
     pub fn update_srtp_keys(&mut self, srtp_keys: SrtpKeys) {
-        self.srtp_tx = SrtpContext::new(srtp_keys.local_master_key, srtp_keys.local_master_salt);
-        self.srtp_rx = SrtpContext::new(srtp_keys.remote_master_key, srtp_keys.remote_master_salt);
+        self.srtp_tx = SrtpContext::with_cipher_suite(
+            srtp_keys.cipher_suite,
+            srtp_keys.local_master_key,
+            srtp_keys.local_master_salt,
+        );
+        self.srtp_rx = SrtpContext::with_cipher_suite(
+            srtp_keys.cipher_suite,
+            srtp_keys.remote_master_key,
+            srtp_keys.remote_master_salt,
+        );
     }
 
     /// Get remote address from UDP transport
@@ -220,6 +372,17 @@ impl SecureUdpTransport {
                             packet.header.timestamp,
                             arrival_time,
                         );
+                        self.remote_ssrc = Some(packet.header.ssrc);
+
+                        if let Some(&[high, low]) = self
+                            .transport_cc_extension_id
+                            .and_then(|id| packet.header.extension(id))
+                        {
+                            self.cc_recorder
+                                .record(u16::from_be_bytes([high, low]), self.clock_us());
+                        }
+                        // Best effort: feedback not sent now goes out with the next one
+                        let _ = self.check_and_send_cc_feedback();
 
                         if let Ok(mut buffer) = self.rtp_buffer.lock() {
                             buffer.push_back(packet);
@@ -240,7 +403,17 @@ impl SecureUdpTransport {
                 let _ = self.handle_rtcp_packet(&encrypted);
                 Ok(false)
             }
-            PacketType::Stun | PacketType::Sctp | PacketType::Unknown => {
+            PacketType::Stun => {
+                // Keep for ICE consent freshness, dropping the oldest when full
+                if let Some(packet) = self.udp_transport.receive()? {
+                    if self.stun_buffer.len() == MAX_BUFFERED_STUN {
+                        self.stun_buffer.pop_front();
+                    }
+                    self.stun_buffer.push_back(packet);
+                }
+                Ok(false)
+            }
+            PacketType::Unknown => {
                 // Consume and ignore
                 let _ = self.udp_transport.receive();
                 Ok(false)
@@ -254,13 +427,28 @@ impl SecureUdpTransport {
         let packet_size = packet.payload.len() + 12; // RTP header + payload
         self.rtcp_stats
             .update_sender(packet_size, packet.header.timestamp);
+        self.track_sent_stream(packet, packet_size);
+
+        // Number the packet for transport-wide congestion control
+        let mut packet = packet.clone();
+        if let Some(id) = self.transport_cc_extension_id {
+            let sequence = self.send_history.on_packet_sent(packet_size, self.clock_us());
+            packet
+                .header
+                .set_extension(id, sequence.to_be_bytes().to_vec());
+        }
 
         // Encrypt with SRTP (works directly with codec::rtp::RtpPacket)
         let encrypted = self
             .srtp_tx
-            .protect(packet)
+            .protect(&packet)
             .map_err(|e| MediaError::Network(format!("SRTP encryption failed: {:?}", e)))?;
 
+        if let Some(config) = self.dscp_config {
+            let kind = self.media_kind(&packet);
+            self.mark(config.for_kind(kind));
+        }
+
         // Send over UDP
         self.udp_transport.send(&encrypted)?;
 
@@ -332,6 +520,40 @@ impl SecureUdpTransport {
         Ok(())
     }
 
+    /// Take the STUN packets received since the last call, with their sender
+    pub fn take_stun_packets(&mut self) -> Vec<(Vec<u8>, SocketAddr)> {
+        self.stun_buffer.drain(..).collect()
+    }
+
+    /// Send a STUN packet on the media socket (ICE consent checks)
+    pub fn send_stun(&mut self, data: &[u8], to: SocketAddr) -> Result<(), MediaError> {
+        self.mark(Dscp::DEFAULT);
+        self.udp_transport
+            .socket()
+            .send_to(data, to)
+            .map_err(|e| MediaError::Network(format!("Failed to send STUN: {}", e)))?;
+        Ok(())
+    }
+
+    /// Send a DTLS record to the remote peer (SCTP data channels)
+    pub fn send_dtls(&mut self, data: &[u8]) -> Result<(), MediaError> {
+        self.mark(Dscp::DEFAULT);
+        self.udp_transport
+            .socket()
+            .send_to(data, self.remote_addr())
+            .map_err(|e| MediaError::Network(format!("Failed to send DTLS: {}", e)))?;
+        Ok(())
+    }
+
+    /// Send an RTCP packet
+    ///
+    /// Control traffic (RTCP, STUN, DTLS) is sent best effort, so it never
+    /// carries the marking of the media packet sent before it.
+    fn send_rtcp(&mut self, data: &[u8]) -> Result<(), MediaError> {
+        self.mark(Dscp::DEFAULT);
+        self.udp_transport.send(data)
+    }
+
     /// Receive SCTP packet - will be handled by DtlsEngine in session layer
     pub fn receive_sctp(&mut self) -> Result<Option<Vec<u8>>, MediaError> {
         // SCTP over DTLS is now handled by DtlsEngine in the session layer
@@ -352,12 +574,87 @@ impl SecureUdpTransport {
         };
 
         let bytes = bye_packet.to_bytes();
-        self.udp_transport.send(&bytes)?;
+        self.send_rtcp(&bytes)?;
 
         Ok(())
     }
 
-    /// Check if it's time to send a Sender Report
+    /// Send a BYE ending one of our streams (e.g. a removed video track)
+    pub fn send_stream_bye(&mut self, ssrc: u32, reason: Option<String>) -> Result<(), MediaError> {
+        let bye_packet = ByePacket {
+            ssrcs: vec![ssrc],
+            reason,
+        };
+
+        self.send_rtcp(&bye_packet.to_bytes())
+    }
+
+    /// Send a Picture Loss Indication asking the peer for a new keyframe
+    pub fn send_pli(&mut self, media_ssrc: u32) -> Result<(), MediaError> {
+        let pli = PictureLossIndication::new(self.rtcp_stats.ssrc, media_ssrc);
+        self.send_rtcp(&pli.to_bytes())
+    }
+
+    /// Returns true once if the peer requested a keyframe (PLI/FIR) since the last call
+    pub fn take_keyframe_request(&mut self) -> bool {
+        std::mem::take(&mut self.keyframe_requested)
+    }
+
+    /// Take the remote SSRCs the peer ended with RTCP BYE since the last call
+    pub fn take_bye_ssrcs(&mut self) -> Vec<u32> {
+        self.bye_ssrcs.drain(..).collect()
+    }
+
+    /// Take the transport-cc results on our packets received since the last call
+    pub fn take_packet_results(&mut self) -> Vec<PacketResult> {
+        self.packet_results.drain(..).collect()
+    }
+
+    /// Microseconds on the clock used for transport-cc send and arrival times
+    fn clock_us(&self) -> i64 {
+        self.cc_epoch.elapsed().as_micros() as i64
+    }
+
+    /// Send transport-cc feedback on the packets received since the last one
+    fn check_and_send_cc_feedback(&mut self) -> Result<(), MediaError> {
+        let now = Instant::now();
+        if let Some(last) = self.last_cc_feedback
+            && now.duration_since(last) < TRANSPORT_CC_FEEDBACK_INTERVAL
+        {
+            return Ok(());
+        }
+
+        let media_ssrc = self.remote_ssrc.unwrap_or(0);
+        let Some(feedback) = self.cc_recorder.build_feedback(self.rtcp_stats.ssrc, media_ssrc)
+        else {
+            return Ok(());
+        };
+        self.last_cc_feedback = Some(now);
+        self.send_rtcp(&feedback.to_bytes())
+    }
+
+    /// Remember the newest RTP timestamp of each media stream for its SR
+    fn track_sent_stream(&mut self, packet: &RtpPacket, packet_size: usize) {
+        if packet.header.payload_type == control_payload::CONTROL {
+            return;
+        }
+
+        let stream = self
+            .sent_streams
+            .entry(packet.header.ssrc)
+            .or_insert(SentStream {
+                rtp_timestamp: 0,
+                sent_at: SystemTime::now(),
+                packets: 0,
+                bytes: 0,
+            });
+        stream.rtp_timestamp = packet.header.timestamp;
+        stream.sent_at = SystemTime::now();
+        stream.packets = stream.packets.wrapping_add(1);
+        stream.bytes = stream.bytes.wrapping_add(packet_size as u32);
+    }
+
+    /// Check if it's time to send a Sender Report (and a Receiver Report for the peer's stream)
     fn check_and_send_sr(&mut self) -> Result<(), MediaError> {
         let now = Instant::now();
 
@@ -367,63 +664,133 @@ impl SecureUdpTransport {
                 return Ok(());
             }
 
-        // Generate and send Sender Report
-        let sr = SenderReport::new(&self.rtcp_stats);
-        let bytes = sr.to_bytes();
-        self.udp_transport.send(&bytes)?;
+        // Sender Report, plus a Receiver Report on the peer's stream so it can
+        // adapt its bitrate, and our CNAME, sent together as one compound packet
+        let mut compound = CompoundRtcpPacket::new()
+            .with_packet(RtcpPacket::SenderReport(SenderReport::new(&self.rtcp_stats)));
+        if let Some(remote_ssrc) = self.remote_ssrc {
+            compound.push(RtcpPacket::ReceiverReport(ReceiverReport::new(
+                &self.rtcp_stats,
+                remote_ssrc,
+            )));
+        }
+        // One report per media stream so the peer can align audio and video
+        for (&ssrc, stream) in &self.sent_streams {
+            compound.push(RtcpPacket::SenderReport(SenderReport::for_stream(
+                ssrc,
+                stream.rtp_timestamp,
+                stream.sent_at,
+                stream.packets,
+                stream.bytes,
+            )));
+        }
+        // Same CNAME for every stream: they belong to one participant
+        let mut sdes = SdesPacket::new(self.rtcp_stats.ssrc, &self.cname);
+        for &ssrc in self.sent_streams.keys() {
+            sdes.chunks.push(SdesChunk {
+                ssrc,
+                cname: Some(self.cname.clone()),
+                name: None,
+            });
+        }
+        compound.push(RtcpPacket::SourceDescription(sdes));
+        self.send_rtcp(&compound.to_bytes())?;
 
         // Track when SR was sent for RTT calculation
         self.rtcp_stats.last_sr_sent_at = Some(SystemTime::now());
         self.last_sr_sent = Some(now);
+        if self.remote_ssrc.is_some() {
+            self.rtcp_stats.last_rr_sent_at = Some(SystemTime::now());
+        }
 
         Ok(())
     }
 
-    /// Handle received RTCP packet
+    /// Handle received RTCP packet (a single packet or a compound)
     fn handle_rtcp_packet(&mut self, bytes: &[u8]) -> Result<(), MediaError> {
         if bytes.len() < 8 {
             return Ok(()); // Too short to be valid RTCP
         }
 
-        let packet_type = bytes[1];
+        let Ok(compound) = CompoundRtcpPacket::from_bytes(bytes) else {
+            return Ok(()); // Malformed compound, drop the whole datagram
+        };
 
-        match RtcpPacketType::from_u8(packet_type) {
-            Some(RtcpPacketType::SR) => {
-                // Sender Report received - could send RR in response
-                if let Ok(sr) = SenderReport::from_bytes(bytes) {
-                    // Update last SR timestamp for RTT calculation
-                    self.rtcp_stats.last_sr_timestamp =
-                        ((sr.ntp_timestamp_msw as u64) << 32 | sr.ntp_timestamp_lsw as u64) as u32;
-                    self.rtcp_stats.last_sr_received_at = Some(std::time::SystemTime::now());
-                }
+        for packet in compound.packets {
+            self.handle_rtcp_component(packet);
+        }
+
+        Ok(())
+    }
+
+    /// Handle one packet of a received RTCP compound
+    fn handle_rtcp_component(&mut self, packet: RtcpPacket) {
+        match packet {
+            RtcpPacket::SenderReport(sr) => {
+                // Update last SR timestamp (middle 32 bits of NTP) for RTT calculation
+                self.rtcp_stats.last_sr_timestamp = (sr.ntp_timestamp() >> 16) as u32;
+                self.rtcp_stats.last_sr_received_at = Some(std::time::SystemTime::now());
+                // Clock correlation for lip-sync
+                self.remote_mappings.insert(sr.ssrc, sr.ntp_rtp_mapping());
+            }
+            RtcpPacket::ReceiverReport(rr) => {
+                // Receiver Report received - feedback about our stream
+                // Consumed by the send side's BitrateController
+                self.rtcp_stats.update_from_receiver_report(&rr.report_blocks);
             }
-            Some(RtcpPacketType::RR) => {
-                // Receiver Report received - could use for RTT calculation
-                if let Ok(_rr) = ReceiverReport::from_bytes(bytes) {
-                    // Process receiver feedback
-                    // This could be used to adjust sending rate
+            RtcpPacket::SourceDescription(sdes) => {
+                // Remember which streams belong to the same participant
+                for chunk in sdes.chunks {
+                    if let Some(cname) = chunk.cname {
+                        self.remote_cnames.insert(chunk.ssrc, cname);
+                    }
                 }
             }
-            Some(RtcpPacketType::BYE) => {
-                // Peer is ending the session
-                if let Ok(bye) = ByePacket::from_bytes(bytes)
-                    && let Some(reason) = bye.reason {
-                        println!("Peer sent BYE: {}", reason);
+            RtcpPacket::PictureLossIndication(_) | RtcpPacket::FullIntraRequest(_) => {
+                // Keyframe request from the receiver of our video
+                self.keyframe_requested = true;
+            }
+            RtcpPacket::TransportCc(feedback) => {
+                // Arrival times of our packets
+                // Consumed by the send side's DelayBasedController
+                self.packet_results
+                    .extend(self.send_history.on_feedback(&feedback));
+                let excess = self.packet_results.len().saturating_sub(MAX_PACKET_RESULTS);
+                self.packet_results.drain(..excess);
+            }
+            RtcpPacket::Bye(bye) => {
+                // Peer ended these streams (e.g. camera off): forget their
+                // state so a restarted stream is accepted from scratch
+                if let Some(reason) = bye.reason {
+                    println!("Peer sent BYE: {}", reason);
+                }
+                for ssrc in bye.ssrcs {
+                    self.srtp_rx.reset_replay_protection_for_ssrc(ssrc);
+                    self.remote_mappings.remove(&ssrc);
+                    if self.remote_ssrc == Some(ssrc) {
+                        self.remote_ssrc = None;
                     }
+                    self.bye_ssrcs.push_back(ssrc);
+                }
             }
-            _ => {
+            RtcpPacket::Unknown { .. } => {
                 // Unknown or unsupported RTCP packet type
             }
         }
-
-        Ok(())
     }
 }
 
+/// Random CNAME (RFC 7022 style short-term persistent identifier)
+fn generate_cname() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::codec::rtcp::FullIntraRequest;
     use crate::codec::rtp::RtpHeader;
+    use std::net::Ipv6Addr;
 
     #[test]
     fn test_rtp_packet_serialization() {
@@ -431,12 +798,13 @@ mod tests {
             version: 2,
             padding: false,
             extension: false,
-            csrc_count: 0,
             marker: false,
             payload_type: 96,
             sequence_number: 100,
             timestamp: 1000,
             ssrc: 12345,
+            csrc: Vec::new(),
+            extensions: Vec::new(),
         };
 
         let packet = RtpPacket::new(header, vec![1, 2, 3, 4, 5]);
@@ -458,6 +826,292 @@ mod tests {
         assert!(transport.is_ok());
     }
 
+    fn create_test_transport() -> SecureUdpTransport {
+        let udp = UdpTransport::new("127.0.0.1:0").unwrap();
+        let keys = SrtpKeys {
+            local_master_key: [1; 32],
+            local_master_salt: [2; 14],
+            remote_master_key: [3; 32],
+            remote_master_salt: [4; 14],
+            cipher_suite: Default::default(),
+        };
+        SecureUdpTransport::new_from_dtls(udp, keys)
+    }
+
+    #[test]
+    fn test_pli_triggers_keyframe_request() {
+        let mut transport = create_test_transport();
+        assert!(!transport.take_keyframe_request());
+
+        let pli = PictureLossIndication::new(1111, 2222);
+        transport.handle_rtcp_packet(&pli.to_bytes()).unwrap();
+
+        assert!(transport.take_keyframe_request());
+        assert!(!transport.take_keyframe_request());
+    }
+
+    #[test]
+    fn test_fir_triggers_keyframe_request() {
+        let mut transport = create_test_transport();
+
+        let fir = FullIntraRequest::new(1111, 2222, 1);
+        transport.handle_rtcp_packet(&fir.to_bytes()).unwrap();
+
+        assert!(transport.take_keyframe_request());
+    }
+
+    #[test]
+    fn test_compound_rtcp_handles_every_packet() {
+        let mut transport = create_test_transport();
+
+        let mut peer_stats = RtcpStats::new(3333);
+        peer_stats.packets_sent = 10;
+        let compound = CompoundRtcpPacket::new()
+            .with_packet(RtcpPacket::SenderReport(SenderReport::new(&peer_stats)))
+            .with_packet(RtcpPacket::PictureLossIndication(PictureLossIndication::new(
+                3333, 4444,
+            )));
+        transport.handle_rtcp_packet(&compound.to_bytes()).unwrap();
+
+        assert!(transport.get_stats().last_sr_received_at.is_some());
+        assert!(transport.take_keyframe_request());
+    }
+
+    #[test]
+    fn test_sdes_records_remote_cname() {
+        let mut transport = create_test_transport();
+        assert_eq!(transport.cname().len(), 16);
+        assert_ne!(transport.cname(), create_test_transport().cname());
+
+        let compound = CompoundRtcpPacket::new()
+            .with_packet(RtcpPacket::ReceiverReport(ReceiverReport::new(
+                &RtcpStats::new(5555),
+                1,
+            )))
+            .with_packet(RtcpPacket::SourceDescription(SdesPacket::new(
+                5555,
+                "peer-cname",
+            )));
+        transport.handle_rtcp_packet(&compound.to_bytes()).unwrap();
+
+        assert_eq!(transport.remote_cname(5555), Some("peer-cname"));
+        assert_eq!(transport.remote_cname(6666), None);
+    }
+
+    #[test]
+    fn test_sender_report_records_ntp_mapping() {
+        let mut transport = create_test_transport();
+        assert!(transport.remote_ntp_mapping(8888).is_none());
+
+        let sent_at = SystemTime::now();
+        let sr = SenderReport::for_stream(8888, 123_456, sent_at, 1, 100);
+        let compound = CompoundRtcpPacket::new().with_packet(RtcpPacket::SenderReport(sr));
+        transport.handle_rtcp_packet(&compound.to_bytes()).unwrap();
+
+        let mapping = transport.remote_ntp_mapping(8888).unwrap();
+        assert_eq!(mapping.rtp_timestamp, 123_456);
+        assert_eq!(
+            mapping.ntp_timestamp,
+            crate::codec::rtcp::sender_report::ntp_timestamp(sent_at)
+        );
+    }
+
+    #[test]
+    fn test_stun_packets_kept_for_ice() {
+        let mut transport = create_test_transport();
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let local_addr = transport.socket().local_addr().unwrap();
+
+        // Binding request header (type 0x0001, magic cookie)
+        let mut request = vec![0x00, 0x01, 0x00, 0x00, 0x21, 0x12, 0xA4, 0x42];
+        request.extend_from_slice(&[7; 12]);
+        peer.send_to(&request, local_addr).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while transport.stun_buffer.is_empty() && Instant::now() < deadline {
+            transport.unified_receive().unwrap();
+        }
+
+        let packets = transport.take_stun_packets();
+        assert_eq!(packets, vec![(request, peer.local_addr().unwrap())]);
+        assert!(transport.take_stun_packets().is_empty());
+
+        transport.send_stun(&[0, 1, 0, 0], peer.local_addr().unwrap()).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_dscp_follows_audio_stream_and_control_traffic() {
+        let mut transport = create_test_transport();
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        transport.set_remote(peer.local_addr().unwrap());
+        transport.set_dscp_config(DscpConfig::recommended()).unwrap();
+        transport.set_audio_stream(100, 1111);
+        let marking =
+            |transport: &SecureUdpTransport| dscp::socket_dscp(transport.socket()).unwrap();
+
+        // The first packet is followed by a Sender Report, sent best effort
+        let audio = RtpPacket::new(RtpHeader::new(100, 1111), vec![0; 20]);
+        transport.send_rtp(&audio).unwrap();
+        assert_eq!(marking(&transport), Dscp::DEFAULT);
+
+        transport.send_rtp(&audio).unwrap();
+        assert_eq!(marking(&transport), Dscp::EF);
+
+        // Same payload type on another SSRC is not the audio stream
+        let video = RtpPacket::new(RtpHeader::new(100, 2222), vec![0; 20]);
+        transport.send_rtp(&video).unwrap();
+        assert_eq!(marking(&transport), Dscp::AF41);
+
+        transport.send_rtp(&audio).unwrap();
+        transport.send_pli(2222).unwrap();
+        assert_eq!(marking(&transport), Dscp::DEFAULT);
+
+        transport.send_rtp(&audio).unwrap();
+        transport.send_stun(&[0, 1, 0, 0], peer.local_addr().unwrap()).unwrap();
+        assert_eq!(marking(&transport), Dscp::DEFAULT);
+    }
+
+    #[test]
+    fn test_ipv6_loopback_round_trip() {
+        let Ok(peer) = UdpSocket::bind("[::1]:0") else {
+            return; // No IPv6 on this host
+        };
+        let peer_addr = peer.local_addr().unwrap();
+
+        // Bound for IPv4 until the selected pair turns out to be IPv6
+        let mut transport = UdpTransport::new("0.0.0.0:0").unwrap();
+        let port = transport.socket().local_addr().unwrap().port();
+        assert!(transport.rebind_for(peer_addr).unwrap());
+        assert!(!transport.rebind_for(peer_addr).unwrap());
+        let local = transport.socket().local_addr().unwrap();
+        assert!(local.is_ipv6());
+        assert_eq!(local.port(), port);
+
+        transport.set_remote(peer_addr);
+        transport.send(b"ping").unwrap();
+        let mut buf = [0u8; 16];
+        peer.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+        let (size, from) = peer.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..size], b"ping");
+        assert_eq!(from.port(), port);
+
+        peer.send_to(b"pong", (Ipv6Addr::LOCALHOST, port)).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(1);
+        let received = loop {
+            if let Some(received) = transport.receive().unwrap() {
+                break received;
+            }
+            assert!(Instant::now() < deadline, "no reply over IPv6");
+        };
+        assert_eq!(received, (b"pong".to_vec(), peer_addr));
+    }
+
+    #[test]
+    fn test_transport_cc_feedback_reaches_sender() {
+        let udp_a = UdpTransport::new("127.0.0.1:0").unwrap();
+        let udp_b = UdpTransport::new("127.0.0.1:0").unwrap();
+        let addr_a = udp_a.socket().local_addr().unwrap();
+        let addr_b = udp_b.socket().local_addr().unwrap();
+        let keys = |local: u8, remote: u8| SrtpKeys {
+            local_master_key: [local; 32],
+            local_master_salt: [local; 14],
+            remote_master_key: [remote; 32],
+            remote_master_salt: [remote; 14],
+            cipher_suite: Default::default(),
+        };
+        let mut sender = SecureUdpTransport::new_from_dtls(udp_a, keys(1, 2));
+        let mut receiver = SecureUdpTransport::new_from_dtls(udp_b, keys(2, 1));
+        sender.set_remote(addr_b);
+        receiver.set_remote(addr_a);
+
+        let mut header = RtpHeader::new(96, 7777);
+        header.sequence_number = 1;
+        sender
+            .send_rtp(&RtpPacket::new(header, vec![0; 100]))
+            .unwrap();
+
+        // Receiving the packet triggers the first feedback
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while !receiver.unified_receive().unwrap() && Instant::now() < deadline {}
+        let packet = receiver.receive_rtp().unwrap().unwrap();
+        assert_eq!(packet.header.extension(TRANSPORT_CC_EXTENSION_ID), Some(&[0, 0][..]));
+
+        let mut results = Vec::new();
+        while results.is_empty() && Instant::now() < deadline {
+            sender.unified_receive().unwrap();
+            results = sender.take_packet_results();
+        }
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].sequence, 0);
+        assert_eq!(results[0].size, 112);
+        assert!(results[0].arrival_us.is_some());
+    }
+
+    #[test]
+    fn test_transport_cc_uses_negotiated_extension_id() {
+        let udp_a = UdpTransport::new("127.0.0.1:0").unwrap();
+        let udp_b = UdpTransport::new("127.0.0.1:0").unwrap();
+        let addr_b = udp_b.socket().local_addr().unwrap();
+        let keys = |local: u8, remote: u8| SrtpKeys {
+            local_master_key: [local; 32],
+            local_master_salt: [local; 14],
+            remote_master_key: [remote; 32],
+            remote_master_salt: [remote; 14],
+            cipher_suite: Default::default(),
+        };
+        let mut sender = SecureUdpTransport::new_from_dtls(udp_a, keys(1, 2));
+        let mut receiver = SecureUdpTransport::new_from_dtls(udp_b, keys(2, 1));
+        sender.set_remote(addr_b);
+
+        let mut receive = |sender: &mut SecureUdpTransport, sequence_number: u16| {
+            let mut header = RtpHeader::new(96, 7777);
+            header.sequence_number = sequence_number;
+            sender
+                .send_rtp(&RtpPacket::new(header, vec![0; 100]))
+                .unwrap();
+            let deadline = Instant::now() + Duration::from_secs(1);
+            while !receiver.unified_receive().unwrap() && Instant::now() < deadline {}
+            receiver.receive_rtp().unwrap().unwrap()
+        };
+
+        sender.set_transport_cc_extension_id(Some(9));
+        let packet = receive(&mut sender, 1);
+        assert_eq!(packet.header.extension(9), Some(&[0, 0][..]));
+        assert_eq!(packet.header.extension(TRANSPORT_CC_EXTENSION_ID), None);
+
+        // Without an agreed ID packets are not numbered
+        sender.set_transport_cc_extension_id(None);
+        let packet = receive(&mut sender, 2);
+        assert!(packet.header.extensions.is_empty());
+    }
+
+    #[test]
+    fn test_bye_resets_remote_stream() {
+        let mut transport = create_test_transport();
+        // Peer encrypts with the key our receive context expects
+        let mut peer_srtp = SrtpContext::with_cipher_suite(Default::default(), [3; 32], [4; 14]);
+
+        let mut header = RtpHeader::new(96, 7777);
+        header.sequence_number = 10;
+        let encrypted = peer_srtp
+            .protect(&RtpPacket::new(header, vec![1, 2, 3]))
+            .unwrap();
+        assert!(transport.srtp_rx.unprotect(&encrypted).is_ok());
+        // Same packet again is a replay
+        assert!(transport.srtp_rx.unprotect(&encrypted).is_err());
+        transport.remote_ssrc = Some(7777);
+
+        let bye = ByePacket::new(7777, Some("camera off".to_string()));
+        transport.handle_rtcp_packet(&bye.to_bytes()).unwrap();
+
+        assert_eq!(transport.take_bye_ssrcs(), vec![7777]);
+        assert!(transport.take_bye_ssrcs().is_empty());
+        assert_eq!(transport.remote_ssrc, None);
+        // A restarted stream may reuse the sequence numbers
+        assert!(transport.srtp_rx.unprotect(&encrypted).is_ok());
+    }
+
     #[test]
     fn test_packet_classification() {
         assert_eq!(classify_packet(&[22, 3, 1]), PacketType::Dtls);
@@ -468,6 +1122,12 @@ mod tests {
 
         assert_eq!(classify_packet(&[0x80, 200, 0, 0]), PacketType::Rtcp);
 
+        assert_eq!(classify_packet(&[0x81, 206, 0, 2]), PacketType::Rtcp);
+
         assert_eq!(classify_packet(&[]), PacketType::Unknown);
+
+        // STUN takes the whole 0-3 range, whatever the length
+        assert_eq!(classify_packet(&[3; 20]), PacketType::Stun);
+        assert_eq!(classify_packet(&[16; 20]), PacketType::Unknown);
     }
 }
@@ -3,5 +3,7 @@
 //! Common network helper functions
 
 mod port_finder;
+mod socket;
 
 pub use port_finder::find_available_port;
+pub use socket::{bind_udp, unspecified_for};
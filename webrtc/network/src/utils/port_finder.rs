@@ -1,12 +1,17 @@
 //! UDP port discovery utilities
 
+use super::socket::bind_udp;
 use logging::Logger;
 use std::error::Error;
-use std::net::UdpSocket;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 
 /// Finds an available UDP port starting from the given port.
 ///
 /// Tries up to 100 sequential ports to find one that's available.
+/// The port is free for both IPv4 and IPv6, so the media socket can bind it
+/// in whichever family the selected candidate pair uses. Hosts without IPv6
+/// only need the port free for IPv4.
 ///
 /// # Arguments
 /// * `start` - Starting port number to try
@@ -27,13 +32,21 @@ pub fn find_available_port(start: u16, logger: &Logger) -> Result<u16, Box<dyn E
 }
 
 fn is_port_available(port: u16) -> bool {
-    UdpSocket::bind(("0.0.0.0", port)).is_ok()
+    let Ok(_v4) = bind_udp(SocketAddr::from((Ipv4Addr::UNSPECIFIED, port))) else {
+        return false;
+    };
+    // Any other error means IPv6 is disabled on this host
+    !matches!(
+        bind_udp(SocketAddr::from((Ipv6Addr::UNSPECIFIED, port))),
+        Err(e) if e.kind() == ErrorKind::AddrInUse
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use logging::LogLevel;
+    use std::net::UdpSocket;
 
     fn create_test_logger() -> Logger {
         use tempfile::tempdir;
@@ -131,6 +144,20 @@ mod tests {
         assert!(port_num < 65535);
     }
 
+    #[test]
+    fn test_port_occupied_by_ipv6_is_skipped() {
+        let logger = create_test_logger();
+
+        let occupied_port = 55200;
+        let Ok(_socket) = bind_udp(SocketAddr::from((Ipv6Addr::UNSPECIFIED, occupied_port))) else {
+            return; // No IPv6 on this host
+        };
+
+        let port = find_available_port(occupied_port, &logger).unwrap();
+        assert_ne!(port, occupied_port);
+        assert!(UdpSocket::bind(("::", port)).is_ok());
+    }
+
     #[test]
     fn test_port_actually_bindable() {
         let logger = create_test_logger();
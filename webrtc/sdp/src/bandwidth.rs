@@ -0,0 +1,138 @@
+//! SDP bandwidth representation.
+//!
+//! Bandwidth lines (b=) signal the maximum bitrate a session or media
+//! stream is expected to use.
+
+/// Bandwidth modifier of a `b=` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BandwidthModifier {
+    /// Application specific maximum in kilobits per second (RFC 4566)
+    As,
+    /// Conference total in kilobits per second (RFC 4566)
+    Ct,
+    /// Transport independent application maximum in bits per second (RFC 3890)
+    Tias,
+    /// Any other modifier, kept verbatim
+    Other(String),
+}
+
+impl BandwidthModifier {
+    /// Returns the modifier token as written in SDP.
+    pub fn as_str(&self) -> &str {
+        match self {
+            BandwidthModifier::As => "AS",
+            BandwidthModifier::Ct => "CT",
+            BandwidthModifier::Tias => "TIAS",
+            BandwidthModifier::Other(token) => token,
+        }
+    }
+}
+
+impl From<&str> for BandwidthModifier {
+    fn from(token: &str) -> Self {
+        match token {
+            "AS" => BandwidthModifier::As,
+            "CT" => BandwidthModifier::Ct,
+            "TIAS" => BandwidthModifier::Tias,
+            other => BandwidthModifier::Other(other.to_string()),
+        }
+    }
+}
+
+/// Represents a bandwidth line (b=) in an SDP message as defined in RFC 4566.
+///
+/// # Format
+/// `b=<bwtype>:<bandwidth>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bandwidth {
+    pub modifier: BandwidthModifier,
+    pub value: u64,
+}
+
+impl Bandwidth {
+    /// Parses a bandwidth string into a `Bandwidth` struct.
+    ///
+    /// # Arguments
+    /// * `value` - The bandwidth string to parse, without the leading "b="
+    ///
+    /// # Returns
+    /// * `Ok(Bandwidth)` - Successfully parsed bandwidth
+    /// * `Err(SdpError::InvalidBandwidthFormat)` - If the format is invalid
+    pub fn parse(value: &str) -> Result<Self, crate::errors::SdpError> {
+        let (modifier, bandwidth) = value
+            .split_once(':')
+            .ok_or(crate::errors::SdpError::InvalidBandwidthFormat)?;
+
+        if modifier.is_empty() {
+            return Err(crate::errors::SdpError::InvalidBandwidthFormat);
+        }
+
+        let value = bandwidth
+            .trim()
+            .parse()
+            .map_err(|_| crate::errors::SdpError::InvalidBandwidthFormat)?;
+
+        Ok(Bandwidth {
+            modifier: BandwidthModifier::from(modifier),
+            value,
+        })
+    }
+
+    /// Returns the bandwidth in bits per second.
+    ///
+    /// # Returns
+    /// * `Some(bps)` - For AS, CT and TIAS modifiers
+    /// * `None` - For unknown modifiers, whose unit is not known
+    pub fn bits_per_second(&self) -> Option<u64> {
+        match self.modifier {
+            BandwidthModifier::As | BandwidthModifier::Ct => Some(self.value.saturating_mul(1000)),
+            BandwidthModifier::Tias => Some(self.value),
+            BandwidthModifier::Other(_) => None,
+        }
+    }
+}
+
+/// Implements the Display trait to format bandwidth lines according to RFC 4566.
+impl std::fmt::Display for Bandwidth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "b={}:{}", self.modifier.as_str(), self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bandwidth_parse_as() {
+        let bandwidth = Bandwidth::parse("AS:512").unwrap();
+        assert_eq!(bandwidth.modifier, BandwidthModifier::As);
+        assert_eq!(bandwidth.value, 512);
+        assert_eq!(bandwidth.bits_per_second(), Some(512_000));
+    }
+
+    #[test]
+    fn test_bandwidth_parse_tias() {
+        let bandwidth = Bandwidth::parse("TIAS:1500000").unwrap();
+        assert_eq!(bandwidth.modifier, BandwidthModifier::Tias);
+        assert_eq!(bandwidth.bits_per_second(), Some(1_500_000));
+    }
+
+    #[test]
+    fn test_bandwidth_parse_unknown_modifier() {
+        let bandwidth = Bandwidth::parse("X-YZ:42").unwrap();
+        assert_eq!(
+            bandwidth.modifier,
+            BandwidthModifier::Other("X-YZ".to_string())
+        );
+        assert_eq!(bandwidth.bits_per_second(), None);
+        assert_eq!(format!("{}", bandwidth), "b=X-YZ:42\n");
+    }
+
+    #[test]
+    fn test_bandwidth_parse_invalid() {
+        assert!(Bandwidth::parse("AS").is_err());
+        assert!(Bandwidth::parse("AS:fast").is_err());
+        assert!(Bandwidth::parse(":128").is_err());
+    }
+}
@@ -46,6 +46,16 @@ pub enum SdpError {
     InvalidPort,
     /// Error when parsing attribute format
     InvalidAttributeFormat,
+    /// Error when parsing bandwidth format
+    InvalidBandwidthFormat,
+    /// Error when no media description has the given mid
+    UnknownMediaId(String),
+    /// Error when an offered media description shares no codec with the answerer
+    NoCommonCodecs(String),
+    /// Error when a known format parameter (`a=fmtp`) has an invalid value
+    InvalidFmtp(String),
+    /// Error when an `a=extmap` attribute is malformed
+    InvalidExtmap(String),
 }
 
 impl std::fmt::Display for SdpError {
@@ -72,6 +82,11 @@ impl std::fmt::Display for SdpError {
             InvalidMediaFormat => "Invalid media description format",
             InvalidPort => "Invalid port number",
             InvalidAttributeFormat => "Invalid attribute format",
+            InvalidBandwidthFormat => "Invalid bandwidth format",
+            UnknownMediaId(mid) => return write!(f, "No media description with mid: {}", mid),
+            NoCommonCodecs(media) => return write!(f, "No common codecs for media: {}", media),
+            InvalidFmtp(param) => return write!(f, "Invalid format parameter: {}", param),
+            InvalidExtmap(value) => return write!(f, "Invalid extmap: {}", value),
         };
         write!(f, "{}", msg)
     }
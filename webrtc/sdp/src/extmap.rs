@@ -0,0 +1,216 @@
+//! RTP header extension mapping.
+//!
+//! Parses the `a=extmap` attribute (RFC 8285 Section 8), which binds a
+//! header extension URI to the local identifier carried in RTP packets,
+//! and computes the mapping both sides agreed on.
+
+use crate::{errors::SdpError, negotiator::Direction};
+
+/// URI of the audio level extension (RFC 6464)
+pub const AUDIO_LEVEL_URI: &str = "urn:ietf:params:rtp-hdrext:ssrc-audio-level";
+/// URI of the transport-wide sequence number extension used by transport-cc
+pub const TRANSPORT_CC_URI: &str =
+    "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01";
+
+/// One `a=extmap` entry.
+///
+/// # Arguments
+/// * `id` - Identifier used in RTP packets (1-14 for one-byte headers, up to 255 for two-byte)
+/// * `direction` - Optional direction restriction (`a=extmap:1/sendonly ...`)
+/// * `uri` - URI naming the extension
+/// * `attributes` - Extension-specific attributes after the URI, if any
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extmap {
+    pub id: u8,
+    pub direction: Option<Direction>,
+    pub uri: String,
+    pub attributes: Option<String>,
+}
+
+impl Extmap {
+    /// Creates a mapping without direction or extension attributes.
+    pub fn new(id: u8, uri: &str) -> Self {
+        Self {
+            id,
+            direction: None,
+            uri: uri.to_string(),
+            attributes: None,
+        }
+    }
+
+    /// Parses the value of an `a=extmap` attribute.
+    ///
+    /// # Arguments
+    /// * `value` - Attribute value, e.g. `3/recvonly urn:ietf:params:rtp-hdrext:ssrc-audio-level`
+    ///
+    /// # Returns
+    /// * `Ok(Extmap)` - The parsed mapping
+    /// * `Err(SdpError::InvalidExtmap)` - If the id, direction or URI is missing or invalid
+    pub fn parse(value: &str) -> Result<Self, SdpError> {
+        let invalid = || SdpError::InvalidExtmap(value.to_string());
+
+        let mut parts = value.trim().splitn(3, ' ');
+        let mapping = parts.next().unwrap_or_default();
+        let (id, direction) = match mapping.split_once('/') {
+            Some((id, direction)) => (
+                id,
+                Some(Direction::from_attribute(direction).ok_or_else(invalid)?),
+            ),
+            None => (mapping, None),
+        };
+        let id = id
+            .parse::<u8>()
+            .ok()
+            .filter(|&id| id != 0)
+            .ok_or_else(invalid)?;
+        let uri = parts
+            .next()
+            .filter(|uri| !uri.is_empty())
+            .ok_or_else(invalid)?;
+        let attributes = parts.next().map(|attrs| attrs.trim().to_string());
+
+        Ok(Self {
+            id,
+            direction,
+            uri: uri.to_string(),
+            attributes,
+        })
+    }
+
+    /// Formats the mapping as the value of an `a=extmap` attribute.
+    pub fn to_value(&self) -> String {
+        let mut value = self.id.to_string();
+        if let Some(direction) = self.direction {
+            value.push('/');
+            value.push_str(direction.as_str());
+        }
+        value.push(' ');
+        value.push_str(&self.uri);
+        if let Some(attributes) = &self.attributes {
+            value.push(' ');
+            value.push_str(attributes);
+        }
+        value
+    }
+
+    /// Selects the offered extensions an answerer supports.
+    ///
+    /// The answer reuses the offered ids, so both directions share one mapping.
+    ///
+    /// # Arguments
+    /// * `offered` - Mappings of the offered media description
+    /// * `supported` - URIs of the extensions the answerer implements
+    pub fn select(offered: &[Extmap], supported: &[&str]) -> Vec<Extmap> {
+        offered
+            .iter()
+            .filter(|extmap| supported.contains(&extmap.uri.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Computes the mapping agreed in an offer/answer exchange.
+    ///
+    /// Only extensions present in both descriptions survive. The answered
+    /// id wins if the answerer remapped an extension (RFC 8285 Section 6).
+    ///
+    /// # Arguments
+    /// * `offered` - Mappings of the offered media description
+    /// * `answered` - Mappings of the answered media description
+    ///
+    /// # Returns
+    /// Agreed mappings in the offered order
+    pub fn negotiate(offered: &[Extmap], answered: &[Extmap]) -> Vec<Extmap> {
+        offered
+            .iter()
+            .filter_map(|offer| answered.iter().find(|answer| answer.uri == offer.uri))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the id agreed for `uri` in a negotiated mapping.
+    pub fn id_of(extmaps: &[Extmap], uri: &str) -> Option<u8> {
+        extmaps
+            .iter()
+            .find(|extmap| extmap.uri == uri)
+            .map(|extmap| extmap.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_extmap() {
+        let extmap = Extmap::parse(&format!("5 {}", TRANSPORT_CC_URI)).unwrap();
+        assert_eq!(extmap, Extmap::new(5, TRANSPORT_CC_URI));
+
+        let extmap =
+            Extmap::parse("3/recvonly urn:ietf:params:rtp-hdrext:ssrc-audio-level vad=on").unwrap();
+        assert_eq!(extmap.id, 3);
+        assert_eq!(extmap.direction, Some(Direction::RecvOnly));
+        assert_eq!(extmap.uri, AUDIO_LEVEL_URI);
+        assert_eq!(extmap.attributes.as_deref(), Some("vad=on"));
+        assert_eq!(
+            extmap.to_value(),
+            "3/recvonly urn:ietf:params:rtp-hdrext:ssrc-audio-level vad=on"
+        );
+    }
+
+    #[test]
+    fn test_malformed_extmap() {
+        for value in [
+            "",
+            "5",
+            "0 urn:x",
+            "x urn:x",
+            "300 urn:x",
+            "4/sideways urn:x",
+        ] {
+            assert!(
+                matches!(Extmap::parse(value), Err(SdpError::InvalidExtmap(_))),
+                "{:?} should be rejected",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_negotiate_keeps_common_uris() {
+        let offered = vec![
+            Extmap::new(1, AUDIO_LEVEL_URI),
+            Extmap::new(4, "urn:3gpp:video-orientation"),
+            Extmap::new(5, TRANSPORT_CC_URI),
+        ];
+        let answered = vec![
+            Extmap::new(5, TRANSPORT_CC_URI),
+            Extmap::new(2, "urn:ietf:params:rtp-hdrext:toffset"),
+            Extmap::new(1, AUDIO_LEVEL_URI),
+        ];
+
+        let agreed = Extmap::negotiate(&offered, &answered);
+
+        assert_eq!(
+            agreed,
+            vec![
+                Extmap::new(1, AUDIO_LEVEL_URI),
+                Extmap::new(5, TRANSPORT_CC_URI)
+            ]
+        );
+        assert_eq!(Extmap::id_of(&agreed, TRANSPORT_CC_URI), Some(5));
+        assert_eq!(Extmap::id_of(&agreed, "urn:3gpp:video-orientation"), None);
+    }
+
+    #[test]
+    fn test_select_supported() {
+        let offered = vec![
+            Extmap::new(1, AUDIO_LEVEL_URI),
+            Extmap::new(3, TRANSPORT_CC_URI),
+        ];
+
+        assert_eq!(
+            Extmap::select(&offered, &[TRANSPORT_CC_URI]),
+            vec![Extmap::new(3, TRANSPORT_CC_URI)]
+        );
+    }
+}
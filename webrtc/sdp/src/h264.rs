@@ -0,0 +1,304 @@
+//! H.264 format parameters.
+//!
+//! Parses the `a=fmtp` parameters of H.264 payload types (RFC 6184
+//! Section 8.1) and selects the payload type to send with, given the
+//! profiles the local encoder can produce.
+
+use crate::{errors::SdpError, media_description::MediaDescription};
+
+/// H.264 profile signalled in `profile-level-id` (RFC 6184 Section 8.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum H264Profile {
+    ConstrainedBaseline,
+    Baseline,
+    Main,
+    Extended,
+    High,
+    High10,
+    High422,
+    High444,
+}
+
+impl H264Profile {
+    /// Classifies a `profile_idc` and `profile-iop` (constraint flags) pair.
+    ///
+    /// Constrained Baseline can be signalled with the Baseline, Main or
+    /// Extended `profile_idc`, depending on the constraint flags set.
+    pub fn from_idc(profile_idc: u8, profile_iop: u8) -> Option<Self> {
+        const CONSTRAINT_SET0: u8 = 0x80;
+        const CONSTRAINT_SET1: u8 = 0x40;
+
+        let profile = match profile_idc {
+            0x42 if profile_iop & CONSTRAINT_SET1 != 0 => H264Profile::ConstrainedBaseline,
+            0x42 => H264Profile::Baseline,
+            0x4D if profile_iop & CONSTRAINT_SET0 != 0 => H264Profile::ConstrainedBaseline,
+            0x4D => H264Profile::Main,
+            0x58 if profile_iop & (CONSTRAINT_SET0 | CONSTRAINT_SET1) == 0xC0 => {
+                H264Profile::ConstrainedBaseline
+            }
+            0x58 if profile_iop & CONSTRAINT_SET0 != 0 => H264Profile::Baseline,
+            0x58 => H264Profile::Extended,
+            0x64 => H264Profile::High,
+            0x6E => H264Profile::High10,
+            0x7A => H264Profile::High422,
+            0xF4 => H264Profile::High444,
+            _ => return None,
+        };
+        Some(profile)
+    }
+}
+
+/// Format parameters of an H.264 payload type.
+///
+/// # Arguments
+/// * `profile` - Profile from `profile-level-id`
+/// * `profile_level_id` - The three `profile-level-id` bytes
+///   (`profile_idc`, `profile-iop`, `level_idc`)
+/// * `packetization_mode` - 0 (single NAL unit) or 1 (non-interleaved, allows FU-A)
+/// * `level_asymmetry_allowed` - The peer accepts a different level in each direction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct H264Fmtp {
+    pub profile: H264Profile,
+    pub profile_level_id: [u8; 3],
+    pub packetization_mode: u8,
+    pub level_asymmetry_allowed: bool,
+}
+
+impl Default for H264Fmtp {
+    /// Parameters implied by an H.264 payload type without `a=fmtp`
+    /// (Baseline level 1, single NAL unit mode).
+    fn default() -> Self {
+        Self {
+            profile: H264Profile::Baseline,
+            profile_level_id: [0x42, 0x00, 0x0A],
+            packetization_mode: 0,
+            level_asymmetry_allowed: false,
+        }
+    }
+}
+
+impl H264Fmtp {
+    /// Parses the parameters of an H.264 `a=fmtp` line.
+    ///
+    /// Parameters that are absent keep the RFC 6184 defaults; unknown
+    /// parameters are ignored.
+    ///
+    /// # Arguments
+    /// * `params` - Parameter list after the payload type, e.g.
+    ///   `level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f`
+    ///
+    /// # Returns
+    /// * `Ok(H264Fmtp)` - The parsed parameters
+    /// * `Err(SdpError::InvalidFmtp)` - If a known parameter has an invalid value
+    pub fn parse(params: &str) -> Result<Self, SdpError> {
+        let mut fmtp = Self::default();
+
+        for param in params.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((key, value)) = param.split_once('=') else {
+                continue;
+            };
+            let invalid = || SdpError::InvalidFmtp(param.to_string());
+
+            match key.trim() {
+                "profile-level-id" => {
+                    let value = value.trim();
+                    if value.len() != 6 {
+                        return Err(invalid());
+                    }
+                    let byte = |i: usize| u8::from_str_radix(&value[i..i + 2], 16);
+                    fmtp.profile_level_id = [
+                        byte(0).map_err(|_| invalid())?,
+                        byte(2).map_err(|_| invalid())?,
+                        byte(4).map_err(|_| invalid())?,
+                    ];
+                    let [profile_idc, profile_iop, _] = fmtp.profile_level_id;
+                    fmtp.profile =
+                        H264Profile::from_idc(profile_idc, profile_iop).ok_or_else(invalid)?;
+                }
+                "packetization-mode" => {
+                    fmtp.packetization_mode = match value.trim() {
+                        "0" => 0,
+                        "1" => 1,
+                        "2" => 2,
+                        _ => return Err(invalid()),
+                    };
+                }
+                "level-asymmetry-allowed" => fmtp.level_asymmetry_allowed = value.trim() == "1",
+                _ => {}
+            }
+        }
+
+        Ok(fmtp)
+    }
+
+    /// Returns the `profile-level-id` as six hex digits (e.g. "42e01f").
+    pub fn profile_level_id_hex(&self) -> String {
+        self.profile_level_id
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Returns the `level_idc` byte (e.g. 31 for level 3.1).
+    pub fn level_idc(&self) -> u8 {
+        self.profile_level_id[2]
+    }
+
+    /// Formats the parameters as they appear after the payload type in `a=fmtp`.
+    pub fn to_params(&self) -> String {
+        format!(
+            "level-asymmetry-allowed={};packetization-mode={};profile-level-id={}",
+            u8::from(self.level_asymmetry_allowed),
+            self.packetization_mode,
+            self.profile_level_id_hex()
+        )
+    }
+
+    /// Selects the H.264 payload type to send with from a remote media description.
+    ///
+    /// Only payload types whose profile is in `supported` and whose
+    /// packetization mode is 0 or 1 are considered. Packetization mode 1 is
+    /// preferred, since it lets large NAL units be fragmented; otherwise the
+    /// remote order decides.
+    ///
+    /// # Arguments
+    /// * `media` - Remote video media description
+    /// * `supported` - Profiles the local encoder can produce
+    ///
+    /// # Returns
+    /// * `Ok((u8, H264Fmtp))` - Selected payload type and its parameters
+    /// * `Err(SdpError::NoCommonCodecs)` - If no H.264 payload type can be sent
+    /// * `Err(SdpError::InvalidFmtp)` - If an H.264 `a=fmtp` line is malformed
+    pub fn negotiate(
+        media: &MediaDescription,
+        supported: &[H264Profile],
+    ) -> Result<(u8, H264Fmtp), SdpError> {
+        let mut candidates = Vec::new();
+        for (payload_type, encoding) in media.rtpmaps() {
+            if !encoding.eq_ignore_ascii_case("H264") {
+                continue;
+            }
+            let fmtp = match media.fmtp(payload_type) {
+                Some(params) => Self::parse(params)?,
+                None => Self::default(),
+            };
+            if supported.contains(&fmtp.profile) && fmtp.packetization_mode <= 1 {
+                candidates.push((payload_type, fmtp));
+            }
+        }
+
+        candidates.sort_by_key(|(_, fmtp)| fmtp.packetization_mode != 1);
+        candidates
+            .into_iter()
+            .next()
+            .ok_or_else(|| SdpError::NoCommonCodecs(format!("{} (H264)", media.media_type)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribute::Attribute;
+
+    fn video_media(codecs: &[(u8, Option<&str>)]) -> MediaDescription {
+        let mut attributes = Vec::new();
+        for (payload_type, params) in codecs {
+            attributes
+                .push(Attribute::parse(&format!("rtpmap:{} H264/90000", payload_type)).unwrap());
+            if let Some(params) = params {
+                attributes
+                    .push(Attribute::parse(&format!("fmtp:{} {}", payload_type, params)).unwrap());
+            }
+        }
+        MediaDescription {
+            media_type: "video".to_string(),
+            port: 9,
+            protocol: "UDP/TLS/RTP/SAVPF".to_string(),
+            formats: codecs.iter().map(|(pt, _)| pt.to_string()).collect(),
+            connection: None,
+            bandwidths: Vec::new(),
+            attributes,
+        }
+    }
+
+    #[test]
+    fn test_parse_chrome_fmtp() {
+        let fmtp = H264Fmtp::parse(
+            "level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f",
+        )
+        .unwrap();
+
+        assert_eq!(fmtp.profile, H264Profile::ConstrainedBaseline);
+        assert_eq!(fmtp.level_idc(), 31);
+        assert_eq!(fmtp.packetization_mode, 1);
+        assert!(fmtp.level_asymmetry_allowed);
+        assert_eq!(
+            fmtp.to_params(),
+            "level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f"
+        );
+    }
+
+    #[test]
+    fn test_missing_fmtp_uses_defaults() {
+        let fmtp = H264Fmtp::parse("").unwrap();
+        assert_eq!(fmtp, H264Fmtp::default());
+        assert_eq!(fmtp.packetization_mode, 0);
+    }
+
+    #[test]
+    fn test_negotiate_prefers_packetization_mode_1() {
+        // Chrome offers every profile in both packetization modes
+        let media = video_media(&[
+            (
+                127,
+                Some("level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42e01f"),
+            ),
+            (
+                125,
+                Some("level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f"),
+            ),
+            (
+                123,
+                Some("level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=640032"),
+            ),
+        ]);
+
+        let (payload_type, fmtp) =
+            H264Fmtp::negotiate(&media, &[H264Profile::ConstrainedBaseline]).unwrap();
+
+        assert_eq!(payload_type, 125);
+        assert_eq!(fmtp.packetization_mode, 1);
+    }
+
+    #[test]
+    fn test_negotiate_rejects_unsupported_profile() {
+        let media = video_media(&[(102, Some("packetization-mode=1;profile-level-id=f4001f"))]);
+
+        assert_eq!(
+            H264Fmtp::parse("profile-level-id=f4001f").unwrap().profile,
+            H264Profile::High444
+        );
+        assert!(matches!(
+            H264Fmtp::negotiate(
+                &media,
+                &[H264Profile::ConstrainedBaseline, H264Profile::High]
+            ),
+            Err(SdpError::NoCommonCodecs(_))
+        ));
+    }
+
+    #[test]
+    fn test_malformed_profile_level_id() {
+        for params in [
+            "profile-level-id=42e0",
+            "profile-level-id=zze01f",
+            "profile-level-id=01e01f",
+        ] {
+            assert!(
+                matches!(H264Fmtp::parse(params), Err(SdpError::InvalidFmtp(_))),
+                "{} should be rejected",
+                params
+            );
+        }
+    }
+}
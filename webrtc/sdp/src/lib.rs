@@ -3,9 +3,13 @@
 //! Implementation of Session Description Protocol according to RFC 4566
 
 pub mod attribute;
+pub mod bandwidth;
 pub mod connection;
 pub mod errors;
+pub mod extmap;
+pub mod h264;
 pub mod media_description;
+pub mod negotiator;
 pub mod origin;
 pub mod sdp_type;
 pub mod session_description;
@@ -13,9 +17,13 @@ pub mod session_description_builder;
 pub mod timing;
 
 pub use attribute::Attribute;
+pub use bandwidth::{Bandwidth, BandwidthModifier};
 pub use connection::Connection;
 pub use errors::SdpError;
+pub use extmap::Extmap;
+pub use h264::{H264Fmtp, H264Profile};
 pub use media_description::MediaDescription;
+pub use negotiator::{Codec, Direction, LocalCapabilities, Negotiator, SetupRole};
 pub use origin::Origin;
 pub use sdp_type::SdpType;
 pub use session_description::SessionDescription;
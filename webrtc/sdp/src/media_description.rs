@@ -3,7 +3,12 @@
 //! Media descriptions define the properties of individual media streams
 //! within an SDP session.
 
-use crate::{attribute::Attribute, connection::Connection};
+use crate::{
+    attribute::Attribute,
+    bandwidth::{Bandwidth, BandwidthModifier},
+    connection::Connection,
+    extmap::Extmap,
+};
 
 /// Represents a media description (m=) in an SDP message as defined in RFC 4566.
 ///
@@ -19,6 +24,7 @@ use crate::{attribute::Attribute, connection::Connection};
 /// * `protocol` - Transport protocol (e.g., "RTP/AVP", "udp")
 /// * `formats` - Media format descriptions (codec types, payload types)
 /// * `connection` - Optional connection information specific to this media
+/// * `bandwidths` - Bandwidth limits (b=) for this media stream
 /// * `attributes` - Media-level attributes that apply only to this media stream
 #[derive(Debug, Clone)]
 pub struct MediaDescription {
@@ -27,6 +33,7 @@ pub struct MediaDescription {
     pub protocol: String,
     pub formats: Vec<String>,
     pub connection: Option<Connection>,
+    pub bandwidths: Vec<Bandwidth>,
     pub attributes: Vec<Attribute>,
 }
 
@@ -63,10 +70,115 @@ impl MediaDescription {
             protocol: parts[2].to_string(),
             formats: parts[3..].iter().map(|s| s.to_string()).collect(),
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         })
     }
 
+    /// Returns the bandwidth lines (b=) of this media description.
+    pub fn bandwidth(&self) -> &[Bandwidth] {
+        &self.bandwidths
+    }
+
+    /// Returns the maximum bitrate signaled for this media, in bits per second.
+    ///
+    /// TIAS is preferred over AS when both are present (RFC 3890 Section 6.2.2).
+    ///
+    /// # Returns
+    /// * `Some(bps)` - The signaled limit
+    /// * `None` - If no TIAS or AS line is present
+    pub fn max_bitrate(&self) -> Option<u64> {
+        let find = |modifier: BandwidthModifier| {
+            self.bandwidths
+                .iter()
+                .find(|bandwidth| bandwidth.modifier == modifier)
+                .and_then(Bandwidth::bits_per_second)
+        };
+        find(BandwidthModifier::Tias).or_else(|| find(BandwidthModifier::As))
+    }
+
+    /// Returns the media identification (`a=mid`) of this media description.
+    pub fn mid(&self) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.name == "mid")
+            .and_then(|attr| attr.value.as_deref())
+    }
+
+    /// Returns the payload types and encoding names mapped by `a=rtpmap`.
+    ///
+    /// Entries follow the order of the m= line formats, which is the
+    /// offerer's preference order (RFC 3264 Section 5.1).
+    ///
+    /// # Returns
+    /// * `Vec<(u8, &str)>` - Payload type and encoding name (e.g. "VP9") pairs
+    pub fn rtpmaps(&self) -> Vec<(u8, &str)> {
+        self.formats
+            .iter()
+            .filter_map(|format| {
+                let payload_type = format.parse::<u8>().ok()?;
+                let encoding = self
+                    .attributes
+                    .iter()
+                    .filter(|attr| attr.name == "rtpmap")
+                    .filter_map(|attr| attr.value.as_deref()?.split_once(' '))
+                    .find(|(pt, _)| *pt == format)?
+                    .1
+                    .split('/')
+                    .next()?;
+                Some((payload_type, encoding))
+            })
+            .collect()
+    }
+
+    /// Returns the format parameters of a payload type.
+    ///
+    /// # Arguments
+    /// * `payload_type` - Payload type whose `a=fmtp` line is wanted
+    ///
+    /// # Returns
+    /// * `Option<&str>` - Parameters after the payload type, if the line exists
+    pub fn fmtp(&self, payload_type: u8) -> Option<&str> {
+        self.attributes
+            .iter()
+            .filter(|attr| attr.name == "fmtp")
+            .filter_map(|attr| attr.value.as_deref()?.split_once(' '))
+            .find(|(pt, _)| pt.parse::<u8>() == Ok(payload_type))
+            .map(|(_, params)| params.trim())
+    }
+
+    /// Returns the RTX payload types and the payload type each one retransmits.
+    ///
+    /// Read from the `apt` parameter of `a=fmtp` lines (RFC 4588 Section 8.1),
+    /// e.g. `a=fmtp:99 apt=96`.
+    ///
+    /// # Returns
+    /// * `Vec<(u8, u8)>` - RTX and associated primary payload type pairs
+    pub fn rtx_associations(&self) -> Vec<(u8, u8)> {
+        self.attributes
+            .iter()
+            .filter(|attr| attr.name == "fmtp")
+            .filter_map(|attr| {
+                let (payload_type, params) = attr.value.as_deref()?.split_once(' ')?;
+                let apt = params
+                    .split(';')
+                    .find_map(|param| param.trim().strip_prefix("apt="))?;
+                Some((payload_type.parse().ok()?, apt.parse().ok()?))
+            })
+            .collect()
+    }
+
+    /// Returns the RTP header extension mappings (`a=extmap`, RFC 8285).
+    ///
+    /// Malformed `a=extmap` lines are skipped.
+    pub fn extmaps(&self) -> Vec<Extmap> {
+        self.attributes
+            .iter()
+            .filter(|attr| attr.name == "extmap")
+            .filter_map(|attr| Extmap::parse(attr.value.as_deref()?).ok())
+            .collect()
+    }
+
     /// Validates the media description according to RFC 4566 specifications.
     ///
     /// This method performs the following checks:
@@ -99,7 +211,8 @@ impl MediaDescription {
 /// The media description is formatted in the following order:
 /// 1. Media line ('m=') with type, port, protocol, and formats
 /// 2. Connection data ('c=') if present
-/// 3. Media-level attributes ('a=')
+/// 3. Bandwidth lines ('b=')
+/// 4. Media-level attributes ('a=')
 impl std::fmt::Display for MediaDescription {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -115,6 +228,10 @@ impl std::fmt::Display for MediaDescription {
             write!(f, "{}", conn)?;
         }
 
+        for bandwidth in &self.bandwidths {
+            write!(f, "{}", bandwidth)?;
+        }
+
         for attr in &self.attributes {
             write!(f, "{}", attr)?;
         }
@@ -127,6 +244,31 @@ impl std::fmt::Display for MediaDescription {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_media_description_rtpmaps() {
+        let mut media = MediaDescription::parse("video 9 UDP/TLS/RTP/SAVPF 98 96 100").unwrap();
+        media.attributes = vec![
+            Attribute::parse("rtpmap:96 H264/90000").unwrap(),
+            Attribute::parse("rtpmap:98 VP9/90000").unwrap(),
+            Attribute::parse("mid:0").unwrap(),
+        ];
+
+        assert_eq!(media.rtpmaps(), vec![(98, "VP9"), (96, "H264")]);
+    }
+
+    #[test]
+    fn test_media_description_rtx_associations() {
+        let mut media = MediaDescription::parse("video 9 UDP/TLS/RTP/SAVPF 96 99").unwrap();
+        media.attributes = vec![
+            Attribute::parse("rtpmap:96 H264/90000").unwrap(),
+            Attribute::parse("fmtp:96 packetization-mode=1").unwrap(),
+            Attribute::parse("rtpmap:99 rtx/90000").unwrap(),
+            Attribute::parse("fmtp:99 apt=96").unwrap(),
+        ];
+
+        assert_eq!(media.rtx_associations(), vec![(99, 96)]);
+    }
+
     #[test]
     fn test_media_description_parse_audio() {
         let media = MediaDescription::parse("audio 49170 RTP/AVP 0").unwrap();
@@ -178,6 +320,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["0".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         };
         assert!(media.validate().is_ok());
@@ -191,6 +334,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["99".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         };
         assert!(media.validate().is_ok());
@@ -205,6 +349,7 @@ mod tests {
                 protocol: "RTP/AVP".to_string(),
                 formats: vec!["0".to_string()],
                 connection: None,
+                bandwidths: Vec::new(),
                 attributes: Vec::new(),
             };
             assert!(media.validate().is_ok());
@@ -219,6 +364,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["0".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         };
         assert!(media.validate().is_err());
@@ -232,6 +378,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: Vec::new(),
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         };
         assert!(media.validate().is_err());
@@ -245,6 +392,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["0".to_string(), "8".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         };
         let display = format!("{}", media);
@@ -265,6 +413,7 @@ mod tests {
                 ttl: None,
                 num_addresses: None,
             }),
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         };
         let display = format!("{}", media);
@@ -280,6 +429,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["0".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: vec![
                 Attribute {
                     name: "rtpmap".to_string(),
@@ -296,4 +446,35 @@ mod tests {
         assert!(display.contains("a=rtpmap:0 PCMU/8000"));
         assert!(display.contains("a=sendrecv"));
     }
+
+    #[test]
+    fn test_media_description_display_bandwidth_between_connection_and_attributes() {
+        let media = MediaDescription {
+            media_type: "video".to_string(),
+            port: 9,
+            protocol: "UDP/TLS/RTP/SAVPF".to_string(),
+            formats: vec!["96".to_string()],
+            connection: Some(Connection::parse("IN IP4 0.0.0.0").unwrap()),
+            bandwidths: vec![
+                Bandwidth::parse("AS:512").unwrap(),
+                Bandwidth::parse("TIAS:480000").unwrap(),
+            ],
+            attributes: vec![Attribute::parse("mid:0").unwrap()],
+        };
+
+        let display = format!("{}", media);
+        let lines: Vec<&str> = display.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "m=video 9 UDP/TLS/RTP/SAVPF 96",
+                "c=IN IP4 0.0.0.0",
+                "b=AS:512",
+                "b=TIAS:480000",
+                "a=mid:0",
+            ]
+        );
+        assert_eq!(media.mid(), Some("0"));
+        assert_eq!(media.max_bitrate(), Some(480_000));
+    }
 }
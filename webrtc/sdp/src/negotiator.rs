@@ -0,0 +1,536 @@
+//! Offer/answer negotiation.
+//!
+//! Computes an SDP answer from a remote offer and the local capabilities
+//! following the offer/answer model of RFC 3264. Negotiation is pure: it
+//! does not touch sockets, ICE agents or DTLS state.
+
+use crate::{
+    attribute::Attribute, errors::SdpError, extmap::Extmap, media_description::MediaDescription,
+    origin::Origin, sdp_type::SdpType, session_description::SessionDescription,
+    session_description_builder::SessionDescriptionBuilder,
+};
+
+/// Media direction attribute of a session or media description (RFC 3264 Section 5.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    SendRecv,
+    SendOnly,
+    RecvOnly,
+    Inactive,
+}
+
+impl Direction {
+    /// Parses a direction from an attribute name, returning `None` for other attributes.
+    pub fn from_attribute(name: &str) -> Option<Self> {
+        match name {
+            "sendrecv" => Some(Direction::SendRecv),
+            "sendonly" => Some(Direction::SendOnly),
+            "recvonly" => Some(Direction::RecvOnly),
+            "inactive" => Some(Direction::Inactive),
+            _ => None,
+        }
+    }
+
+    /// Returns the SDP attribute name of this direction.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::SendRecv => "sendrecv",
+            Direction::SendOnly => "sendonly",
+            Direction::RecvOnly => "recvonly",
+            Direction::Inactive => "inactive",
+        }
+    }
+
+    /// Returns true if media may be sent in this direction.
+    pub fn can_send(&self) -> bool {
+        matches!(self, Direction::SendRecv | Direction::SendOnly)
+    }
+
+    /// Returns true if media may be received in this direction.
+    pub fn can_receive(&self) -> bool {
+        matches!(self, Direction::SendRecv | Direction::RecvOnly)
+    }
+
+    /// Selects the answer direction for an offered direction.
+    ///
+    /// The answerer may only send if the offerer receives and may only
+    /// receive if the offerer sends, further restricted by `self`, the
+    /// direction the answerer is willing to use.
+    ///
+    /// # Arguments
+    /// * `offered` - Direction found in the offer
+    pub fn answer_to(&self, offered: Direction) -> Direction {
+        let send = self.can_send() && offered.can_receive();
+        let receive = self.can_receive() && offered.can_send();
+        match (send, receive) {
+            (true, true) => Direction::SendRecv,
+            (true, false) => Direction::SendOnly,
+            (false, true) => Direction::RecvOnly,
+            (false, false) => Direction::Inactive,
+        }
+    }
+}
+
+/// DTLS role negotiated through the `a=setup` attribute (RFC 5763 Section 5).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupRole {
+    Active,
+    Passive,
+    ActPass,
+}
+
+impl SetupRole {
+    /// Parses a setup role from the value of an `a=setup` attribute.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "active" => Some(SetupRole::Active),
+            "passive" => Some(SetupRole::Passive),
+            "actpass" => Some(SetupRole::ActPass),
+            _ => None,
+        }
+    }
+
+    /// Returns the `a=setup` attribute value of this role.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SetupRole::Active => "active",
+            SetupRole::Passive => "passive",
+            SetupRole::ActPass => "actpass",
+        }
+    }
+
+    /// Selects the answerer's role for an offered role.
+    ///
+    /// An `actpass` offer is answered with `active`, so the answerer
+    /// becomes the DTLS client; otherwise the answerer takes the opposite
+    /// of the role the offerer claimed.
+    pub fn answer_to(offered: SetupRole) -> SetupRole {
+        match offered {
+            SetupRole::Active => SetupRole::Passive,
+            SetupRole::Passive | SetupRole::ActPass => SetupRole::Active,
+        }
+    }
+}
+
+/// A codec the local endpoint supports for a media type.
+///
+/// # Arguments
+/// * `media_type` - Media type the codec applies to ("audio" or "video")
+/// * `encoding_name` - Encoding name as found in `a=rtpmap` (e.g. "H264")
+#[derive(Debug, Clone)]
+pub struct Codec {
+    pub media_type: String,
+    pub encoding_name: String,
+}
+
+impl Codec {
+    /// Creates a codec entry for the given media type and encoding name.
+    pub fn new(media_type: &str, encoding_name: &str) -> Self {
+        Self {
+            media_type: media_type.to_string(),
+            encoding_name: encoding_name.to_string(),
+        }
+    }
+}
+
+/// Local capabilities used to answer an offer.
+///
+/// # Arguments
+/// * `origin` - Origin line of the answer
+/// * `ice_ufrag` - Local ICE username fragment
+/// * `ice_pwd` - Local ICE password
+/// * `candidates` - Local ICE candidates in SDP format
+/// * `fingerprint` - Local DTLS certificate fingerprint as `(hash_func, value)`
+/// * `codecs` - Codecs the local endpoint can decode and encode
+/// * `rtp_extensions` - URIs of the RTP header extensions the local endpoint implements
+/// * `direction` - Direction the local endpoint is willing to use
+#[derive(Debug, Clone)]
+pub struct LocalCapabilities {
+    pub origin: Origin,
+    pub ice_ufrag: String,
+    pub ice_pwd: String,
+    pub candidates: Vec<String>,
+    pub fingerprint: Option<(String, String)>,
+    pub codecs: Vec<Codec>,
+    pub rtp_extensions: Vec<String>,
+    pub direction: Direction,
+}
+
+impl LocalCapabilities {
+    /// Returns true if `encoding_name` is supported for `media_type`.
+    ///
+    /// Encoding names are compared case-insensitively (RFC 4855 Section 3).
+    pub fn supports(&self, media_type: &str, encoding_name: &str) -> bool {
+        self.codecs.iter().any(|codec| {
+            codec.media_type == media_type
+                && codec.encoding_name.eq_ignore_ascii_case(encoding_name)
+        })
+    }
+}
+
+/// Attributes copied verbatim from an offered media description into the answer.
+const COPIED_MEDIA_ATTRIBUTES: &[&str] = &["mid", "rtcp-mux", "sctp-port", "max-message-size"];
+
+/// Negotiates SDP answers following the offer/answer model (RFC 3264).
+pub struct Negotiator;
+
+impl Negotiator {
+    /// Creates an answer to `offer` using `local_caps`.
+    ///
+    /// For every RTP media description the answer keeps, in the offerer's
+    /// order and with the offerer's payload types, only the codecs whose
+    /// `a=rtpmap` encoding name is supported locally, together with their
+    /// `a=rtpmap` and `a=fmtp` lines. RTX payload types are kept when the
+    /// payload type they retransmit is kept, and so are the offered header
+    /// extensions (`a=extmap`) that are supported locally, with the
+    /// offered ids. Non-RTP media descriptions
+    /// (e.g. data channels) are accepted as offered.
+    ///
+    /// The answer carries the local ICE credentials, candidates and DTLS
+    /// fingerprint, the setup role chosen by [`SetupRole::answer_to`] and the
+    /// direction chosen by [`Direction::answer_to`].
+    ///
+    /// # Arguments
+    /// * `offer` - Remote offer
+    /// * `local_caps` - Local capabilities
+    ///
+    /// # Returns
+    /// * `Ok(SessionDescription)` - The answer
+    /// * `Err(SdpError::NoCommonCodecs)` - If a media description shares no codec
+    ///   with the local capabilities
+    /// * `Err(SdpError)` - If the resulting answer fails validation
+    pub fn create_answer(
+        offer: &SessionDescription,
+        local_caps: &LocalCapabilities,
+    ) -> Result<SessionDescription, SdpError> {
+        let offered_setup = Self::find_attribute(offer, "setup")
+            .and_then(SetupRole::parse)
+            .unwrap_or(SetupRole::ActPass);
+        let setup = SetupRole::answer_to(offered_setup);
+
+        let mut builder = SessionDescriptionBuilder::new(SdpType::Answer)
+            .origin(local_caps.origin.clone())
+            .session_name(offer.session_name.clone())
+            .ice_credentials(&local_caps.ice_ufrag, &local_caps.ice_pwd)
+            .ice_candidates(&local_caps.candidates);
+        if let Some((hash_func, value)) = &local_caps.fingerprint {
+            builder = builder.dtls_fingerprint(hash_func, value);
+        }
+        builder = builder.add_attribute(Attribute {
+            name: "setup".to_string(),
+            value: Some(setup.as_str().to_string()),
+        });
+
+        let session_direction = Self::direction_of(&offer.attributes);
+        for media in &offer.media {
+            builder = builder.add_media(Self::answer_media(media, session_direction, local_caps)?);
+        }
+        builder.build()
+    }
+
+    /// Builds the answer for a single offered media description.
+    fn answer_media(
+        media: &MediaDescription,
+        session_direction: Option<Direction>,
+        local_caps: &LocalCapabilities,
+    ) -> Result<MediaDescription, SdpError> {
+        let mut attributes: Vec<Attribute> = media
+            .attributes
+            .iter()
+            .filter(|attr| COPIED_MEDIA_ATTRIBUTES.contains(&attr.name.as_str()))
+            .cloned()
+            .collect();
+
+        if !media.protocol.contains("RTP") {
+            return Ok(MediaDescription {
+                attributes,
+                connection: None,
+                bandwidths: Vec::new(),
+                ..media.clone()
+            });
+        }
+
+        let mut formats: Vec<String> = media
+            .rtpmaps()
+            .into_iter()
+            .filter(|(_, encoding)| {
+                !encoding.eq_ignore_ascii_case("rtx")
+                    && local_caps.supports(&media.media_type, encoding)
+            })
+            .map(|(payload_type, _)| payload_type.to_string())
+            .collect();
+        if formats.is_empty() {
+            return Err(SdpError::NoCommonCodecs(media.media_type.clone()));
+        }
+        if local_caps.supports(&media.media_type, "rtx") {
+            let rtx_formats: Vec<String> = media
+                .rtx_associations()
+                .into_iter()
+                .filter(|(_, apt)| formats.contains(&apt.to_string()))
+                .map(|(rtx, _)| rtx.to_string())
+                .collect();
+            formats.extend(rtx_formats);
+        }
+
+        attributes.extend(
+            media
+                .attributes
+                .iter()
+                .filter(|attr| attr.name == "rtpmap" || attr.name == "fmtp")
+                .filter(|attr| {
+                    attr.value
+                        .as_deref()
+                        .and_then(|value| value.split_once(' '))
+                        .is_some_and(|(pt, _)| formats.iter().any(|format| format == pt))
+                })
+                .cloned(),
+        );
+
+        let supported_extensions: Vec<&str> = local_caps
+            .rtp_extensions
+            .iter()
+            .map(String::as_str)
+            .collect();
+        attributes.extend(
+            Extmap::select(&media.extmaps(), &supported_extensions)
+                .into_iter()
+                .map(|extmap| Attribute {
+                    name: "extmap".to_string(),
+                    value: Some(extmap.to_value()),
+                }),
+        );
+
+        let offered_direction = Self::direction_of(&media.attributes)
+            .or(session_direction)
+            .unwrap_or(Direction::SendRecv);
+        attributes.push(Attribute {
+            name: local_caps
+                .direction
+                .answer_to(offered_direction)
+                .as_str()
+                .to_string(),
+            value: None,
+        });
+
+        Ok(MediaDescription {
+            media_type: media.media_type.clone(),
+            port: media.port,
+            protocol: media.protocol.clone(),
+            formats,
+            connection: None,
+            bandwidths: Vec::new(),
+            attributes,
+        })
+    }
+
+    /// Returns the direction attribute among `attributes`, if any.
+    fn direction_of(attributes: &[Attribute]) -> Option<Direction> {
+        attributes
+            .iter()
+            .find_map(|attr| Direction::from_attribute(&attr.name))
+    }
+
+    /// Returns the value of a session- or media-level attribute of `offer`.
+    fn find_attribute<'a>(offer: &'a SessionDescription, name: &str) -> Option<&'a str> {
+        offer
+            .attributes
+            .iter()
+            .chain(offer.media.iter().flat_map(|media| media.attributes.iter()))
+            .find(|attr| attr.name == name)
+            .and_then(|attr| attr.value.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offer(video_codecs: &[(u8, &str)], extra: &str) -> SessionDescription {
+        let formats: Vec<String> = video_codecs.iter().map(|(pt, _)| pt.to_string()).collect();
+        let mut sdp = format!(
+            "v=0\r\n\
+             o=- 1 1 IN IP4 127.0.0.1\r\n\
+             s=-\r\n\
+             t=0 0\r\n\
+             a=ice-ufrag:remote\r\n\
+             a=ice-pwd:remotepassword\r\n\
+             {extra}\
+             m=video 9 UDP/TLS/RTP/SAVPF {}\r\n\
+             a=mid:0\r\n\
+             a=rtcp-mux\r\n",
+            formats.join(" ")
+        );
+        for (pt, name) in video_codecs {
+            sdp.push_str(&format!("a=rtpmap:{pt} {name}/90000\r\n"));
+        }
+        sdp.push_str("a=fmtp:102 profile-level-id=42e01f\r\n");
+        SessionDescription::parse(SdpType::Offer, &sdp).unwrap()
+    }
+
+    fn local_caps(codecs: &[&str]) -> LocalCapabilities {
+        LocalCapabilities {
+            origin: Origin {
+                session_id: 2,
+                ..Origin::default()
+            },
+            ice_ufrag: "local".to_string(),
+            ice_pwd: "localpassword".to_string(),
+            candidates: vec!["1 1 UDP 2130706431 192.168.1.5 5000 typ host".to_string()],
+            fingerprint: Some(("sha-256".to_string(), "AB:CD".to_string())),
+            codecs: codecs
+                .iter()
+                .map(|name| Codec::new("video", name))
+                .collect(),
+            rtp_extensions: vec![crate::extmap::TRANSPORT_CC_URI.to_string()],
+            direction: Direction::SendRecv,
+        }
+    }
+
+    fn attribute<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a str> {
+        attributes
+            .iter()
+            .find(|attr| attr.name == name)
+            .and_then(|attr| attr.value.as_deref())
+    }
+
+    #[test]
+    fn test_answer_keeps_only_common_codecs() {
+        let offer = offer(&[(96, "VP8"), (98, "VP9"), (102, "H264")], "");
+
+        let answer = Negotiator::create_answer(&offer, &local_caps(&["H264"])).unwrap();
+
+        let video = &answer.media[0];
+        assert_eq!(video.formats, vec!["102"]);
+        assert_eq!(video.rtpmaps(), vec![(102, "H264")]);
+        assert_eq!(
+            attribute(&video.attributes, "fmtp"),
+            Some("102 profile-level-id=42e01f")
+        );
+        assert_eq!(video.mid(), Some("0"));
+    }
+
+    #[test]
+    fn test_answer_rejects_empty_intersection() {
+        let offer = offer(&[(98, "VP9")], "");
+
+        let result = Negotiator::create_answer(&offer, &local_caps(&["H264"]));
+
+        assert!(matches!(result, Err(SdpError::NoCommonCodecs(media)) if media == "video"));
+    }
+
+    #[test]
+    fn test_answer_copies_local_ice_credentials_and_fingerprint() {
+        let offer = offer(&[(102, "H264")], "");
+
+        let answer = Negotiator::create_answer(&offer, &local_caps(&["H264"])).unwrap();
+
+        assert_eq!(answer.sdp_type, SdpType::Answer);
+        assert_eq!(attribute(&answer.attributes, "ice-ufrag"), Some("local"));
+        assert_eq!(
+            attribute(&answer.attributes, "ice-pwd"),
+            Some("localpassword")
+        );
+        assert!(attribute(&answer.attributes, "candidate").is_some());
+        assert_eq!(
+            answer.fingerprint(),
+            Some(("sha-256".to_string(), "AB:CD".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_answer_setup_role() {
+        let cases = [
+            ("", "active"),
+            ("a=setup:actpass\r\n", "active"),
+            ("a=setup:active\r\n", "passive"),
+            ("a=setup:passive\r\n", "active"),
+        ];
+        for (extra, expected) in cases {
+            let offer = offer(&[(102, "H264")], extra);
+
+            let answer = Negotiator::create_answer(&offer, &local_caps(&["H264"])).unwrap();
+
+            assert_eq!(attribute(&answer.attributes, "setup"), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_answer_direction() {
+        let offer = offer(&[(102, "H264")], "a=sendonly\r\n");
+
+        let answer = Negotiator::create_answer(&offer, &local_caps(&["H264"])).unwrap();
+
+        assert!(
+            answer.media[0]
+                .attributes
+                .iter()
+                .any(|attr| attr.name == "recvonly")
+        );
+        assert_eq!(
+            Direction::RecvOnly.answer_to(Direction::RecvOnly),
+            Direction::Inactive
+        );
+        assert_eq!(
+            Direction::SendRecv.answer_to(Direction::RecvOnly),
+            Direction::SendOnly
+        );
+    }
+
+    #[test]
+    fn test_answer_keeps_rtx_for_kept_codecs() {
+        let sdp = "v=0\r\n\
+                   o=- 1 1 IN IP4 127.0.0.1\r\n\
+                   s=-\r\n\
+                   t=0 0\r\n\
+                   m=video 9 UDP/TLS/RTP/SAVPF 96 98 99 100\r\n\
+                   a=rtpmap:96 VP8/90000\r\n\
+                   a=rtpmap:98 VP9/90000\r\n\
+                   a=rtpmap:99 rtx/90000\r\n\
+                   a=fmtp:99 apt=96\r\n\
+                   a=rtpmap:100 rtx/90000\r\n\
+                   a=fmtp:100 apt=98\r\n";
+        let offer = SessionDescription::parse(SdpType::Offer, sdp).unwrap();
+
+        let answer = Negotiator::create_answer(&offer, &local_caps(&["VP8", "rtx"])).unwrap();
+
+        assert_eq!(answer.media[0].formats, vec!["96", "99"]);
+        assert_eq!(answer.media[0].rtx_associations(), vec![(99, 96)]);
+    }
+
+    #[test]
+    fn test_answer_keeps_supported_extmaps() {
+        let mut offer = offer(&[(102, "H264")], "");
+        offer.media[0].attributes.extend([
+            Attribute::parse("extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level").unwrap(),
+            Attribute::parse(&format!("extmap:3 {}", crate::extmap::TRANSPORT_CC_URI)).unwrap(),
+        ]);
+
+        let answer = Negotiator::create_answer(&offer, &local_caps(&["H264"])).unwrap();
+
+        assert_eq!(
+            answer.media[0].extmaps(),
+            vec![Extmap::new(3, crate::extmap::TRANSPORT_CC_URI)]
+        );
+    }
+
+    #[test]
+    fn test_answer_accepts_data_channel() {
+        let sdp = "v=0\r\n\
+                   o=- 1 1 IN IP4 127.0.0.1\r\n\
+                   s=-\r\n\
+                   t=0 0\r\n\
+                   m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n\
+                   a=mid:1\r\n\
+                   a=sctp-port:5000\r\n";
+        let offer = SessionDescription::parse(SdpType::Offer, sdp).unwrap();
+
+        let answer = Negotiator::create_answer(&offer, &local_caps(&[])).unwrap();
+
+        let application = &answer.media[0];
+        assert_eq!(application.formats, vec!["webrtc-datachannel"]);
+        assert_eq!(
+            attribute(&application.attributes, "sctp-port"),
+            Some("5000")
+        );
+    }
+}
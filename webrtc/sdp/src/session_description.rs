@@ -4,7 +4,7 @@
 //! a complete SDP message according to RFC 4566.
 
 use crate::{
-    attribute::Attribute, connection::Connection, errors::SdpError,
+    attribute::Attribute, bandwidth::Bandwidth, connection::Connection, errors::SdpError,
     media_description::MediaDescription, origin::Origin, sdp_type::SdpType,
     session_description_builder::SessionDescriptionBuilder, timing::Timing,
 };
@@ -20,6 +20,7 @@ pub struct SessionDescription {
     pub media: Vec<MediaDescription>,
     pub attributes: Vec<Attribute>,
     pub connection: Option<Connection>,
+    pub bandwidths: Vec<Bandwidth>,
 }
 
 /// Represents the different types of SDP lines for pattern matching.
@@ -29,6 +30,7 @@ enum SdpLineType {
     SessionName,
     Timing,
     Connection,
+    Bandwidth,
     Media,
     Attribute,
     Unknown,
@@ -42,6 +44,7 @@ impl From<char> for SdpLineType {
             's' => Self::SessionName,
             't' => Self::Timing,
             'c' => Self::Connection,
+            'b' => Self::Bandwidth,
             'm' => Self::Media,
             'a' => Self::Attribute,
             _ => Self::Unknown,
@@ -105,6 +108,58 @@ impl SessionDescription {
         Ok(session)
     }
 
+    /// Returns the DTLS certificate fingerprint (`a=fingerprint`).
+    ///
+    /// Session-level placement takes precedence over media-level placement.
+    /// The hash function is lowercased and the hex digest uppercased so values
+    /// compare equal regardless of the case used by the remote peer.
+    ///
+    /// # Returns
+    /// * `Some((hash_func, value))` - e.g. `("sha-256", "AB:CD:...")`
+    /// * `None` - If no well-formed fingerprint attribute is present
+    pub fn fingerprint(&self) -> Option<(String, String)> {
+        self.all_attributes()
+            .filter(|attr| attr.name == "fingerprint")
+            .find_map(|attr| {
+                let (hash_func, value) = attr.value.as_deref()?.trim().split_once(' ')?;
+                Some((hash_func.to_lowercase(), value.trim().to_uppercase()))
+            })
+    }
+
+    /// Returns the ICE option tags (`a=ice-options`) of the session and its media.
+    pub fn ice_options(&self) -> Vec<String> {
+        let mut options: Vec<String> = Vec::new();
+        for attr in self
+            .all_attributes()
+            .filter(|attr| attr.name == "ice-options")
+        {
+            for option in attr.value.as_deref().unwrap_or("").split_whitespace() {
+                if !options.iter().any(|known| known == option) {
+                    options.push(option.to_string());
+                }
+            }
+        }
+        options
+    }
+
+    /// Returns true if the peer accepts trickled candidates (`a=ice-options:trickle`).
+    pub fn supports_trickle(&self) -> bool {
+        self.ice_options().iter().any(|option| option == "trickle")
+    }
+
+    /// Returns true if the candidate list is marked complete (`a=end-of-candidates`).
+    pub fn has_end_of_candidates(&self) -> bool {
+        self.all_attributes()
+            .any(|attr| attr.name == "end-of-candidates")
+    }
+
+    /// Session-level attributes followed by those of every media description
+    fn all_attributes(&self) -> impl Iterator<Item = &Attribute> {
+        self.attributes
+            .iter()
+            .chain(self.media.iter().flat_map(|media| media.attributes.iter()))
+    }
+
     /// Splits an SDP line into its type character and value components.
     ///
     /// Each SDP line must be in the format `<type>=<value>` where `type` is
@@ -154,6 +209,7 @@ impl SessionDescription {
             SdpLineType::SessionName => session.set_session_name(value),
             SdpLineType::Timing => session.set_timing(value),
             SdpLineType::Connection => session.set_connection(current_media, value),
+            SdpLineType::Bandwidth => session.set_bandwidth(current_media, value),
             SdpLineType::Media => session.set_media(current_media, value),
             SdpLineType::Attribute => session.set_attribute(current_media, value),
             SdpLineType::Unknown => Ok(()),
@@ -224,6 +280,26 @@ impl SessionDescription {
         Ok(())
     }
 
+    /// Adds a bandwidth line.
+    ///
+    /// Bandwidth lines can appear at the session level and/or in media descriptions.
+    ///
+    /// # Arguments
+    /// * `current_media` - The current media section being parsed, if any
+    /// * `value` - The bandwidth string to parse
+    fn set_bandwidth(
+        &mut self,
+        current_media: &mut Option<MediaDescription>,
+        value: &str,
+    ) -> Result<(), SdpError> {
+        let bandwidth = Bandwidth::parse(value)?;
+        match current_media.as_mut() {
+            Some(media) => media.bandwidths.push(bandwidth),
+            None => self.bandwidths.push(bandwidth),
+        }
+        Ok(())
+    }
+
     /// Sets a media description.
     ///
     /// # Arguments
@@ -314,6 +390,7 @@ impl Default for SessionDescription {
             media: Vec::new(),
             attributes: Vec::new(),
             connection: None,
+            bandwidths: Vec::new(),
         }
     }
 }
@@ -325,9 +402,10 @@ impl Default for SessionDescription {
 /// 2. Origin ('o=')
 /// 3. Session name ('s=')
 /// 4. Connection data ('c=') if present
-/// 5. Timing ('t=')
-/// 6. Session-level attributes ('a=')
-/// 7. Media descriptions ('m=') with their attributes
+/// 5. Bandwidth lines ('b=')
+/// 6. Timing ('t=')
+/// 7. Session-level attributes ('a=')
+/// 8. Media descriptions ('m=') with their attributes
 ///
 /// Each line is terminated with a CRLF as per RFC 4566.
 impl std::fmt::Display for SessionDescription {
@@ -338,6 +416,9 @@ impl std::fmt::Display for SessionDescription {
         if let Some(ref conn) = self.connection {
             write!(f, "{}", conn)?;
         }
+        for bandwidth in &self.bandwidths {
+            write!(f, "{}", bandwidth)?;
+        }
         write!(f, "{}", self.timing)?;
 
         for attr in &self.attributes {
@@ -355,6 +436,7 @@ impl std::fmt::Display for SessionDescription {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bandwidth::BandwidthModifier;
 
     fn create_simple_sdp() -> String {
         "v=0\r\n\
@@ -434,6 +516,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["0".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         });
 
@@ -451,6 +534,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["0".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         });
 
@@ -477,6 +561,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["0".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         });
 
@@ -519,6 +604,7 @@ mod tests {
                 protocol: "RTP/AVP".to_string(),
                 formats: vec!["0".to_string()],
                 connection: None,
+                bandwidths: Vec::new(),
                 attributes: Vec::new(),
             })
             .build();
@@ -541,6 +627,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["0".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         });
 
@@ -552,6 +639,108 @@ mod tests {
         assert!(display.contains("m=audio 49170 RTP/AVP 0"));
     }
 
+    /// Fingerprint as produced by `DtlsContext::get_fingerprint` (uppercase, no prefix)
+    const DTLS_FINGERPRINT: &str = "4F:2A:9B:00:C1:DE:77:10:AB:CD:EF:01:23:45:67:89:\
+                                    9A:BC:DE:F0:11:22:33:44:55:66:77:88:99:AA:BB:CC";
+
+    #[test]
+    fn test_session_description_fingerprint_round_trip() {
+        let session = SessionDescription::builder(SdpType::Offer)
+            .origin(Origin {
+                session_id: 1,
+                ..Default::default()
+            })
+            .session_name("Test")
+            .dtls_fingerprint("sha-256", DTLS_FINGERPRINT)
+            .add_media(MediaDescription {
+                media_type: "application".to_string(),
+                port: 9,
+                protocol: "UDP/DTLS/SCTP".to_string(),
+                formats: vec!["webrtc-datachannel".to_string()],
+                connection: None,
+                bandwidths: Vec::new(),
+                attributes: Vec::new(),
+            })
+            .build()
+            .unwrap();
+
+        let parsed = SessionDescription::parse(SdpType::Offer, &session.to_string()).unwrap();
+        assert_eq!(
+            parsed.fingerprint(),
+            Some(("sha-256".to_string(), DTLS_FINGERPRINT.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_session_description_fingerprint_media_level() {
+        let sdp = format!(
+            "v=0\r\n\
+             o=- 123456 1 IN IP4 192.168.1.1\r\n\
+             s=-\r\n\
+             t=0 0\r\n\
+             m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+             a=fingerprint:SHA-256 {}\r\n",
+            DTLS_FINGERPRINT.to_lowercase()
+        );
+        let session = SessionDescription::parse(SdpType::Answer, &sdp).unwrap();
+
+        let (hash_func, value) = session.fingerprint().unwrap();
+        assert_eq!(hash_func, "sha-256");
+        assert!(value.eq_ignore_ascii_case(DTLS_FINGERPRINT));
+        assert_eq!(value, DTLS_FINGERPRINT);
+    }
+
+    #[test]
+    fn test_session_description_no_fingerprint() {
+        let session = SessionDescription::parse(SdpType::Offer, &create_simple_sdp()).unwrap();
+        assert_eq!(session.fingerprint(), None);
+    }
+
+    #[test]
+    fn test_session_description_parse_bandwidth() {
+        let sdp = "v=0\r\n\
+                   o=- 123456 1 IN IP4 192.168.1.1\r\n\
+                   s=-\r\n\
+                   b=CT:2000\r\n\
+                   t=0 0\r\n\
+                   m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                   c=IN IP4 0.0.0.0\r\n\
+                   b=AS:800\r\n\
+                   b=TIAS:750000\r\n\
+                   a=mid:0\r\n";
+        let session = SessionDescription::parse(SdpType::Answer, sdp).unwrap();
+
+        assert_eq!(session.bandwidths[0].modifier, BandwidthModifier::Ct);
+        let bandwidth = session.media[0].bandwidth();
+        assert_eq!(bandwidth.len(), 2);
+        assert_eq!(bandwidth[0].modifier, BandwidthModifier::As);
+        assert_eq!(bandwidth[0].value, 800);
+        assert_eq!(bandwidth[1].modifier, BandwidthModifier::Tias);
+        assert_eq!(bandwidth[1].value, 750_000);
+        assert_eq!(session.media[0].max_bitrate(), Some(750_000));
+    }
+
+    #[test]
+    fn test_session_description_parse_trickle_and_end_of_candidates() {
+        let sdp = "v=0\r\n\
+                   o=- 123456 1 IN IP4 192.168.1.1\r\n\
+                   s=-\r\n\
+                   t=0 0\r\n\
+                   a=ice-options:trickle\r\n\
+                   m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                   a=candidate:1 1 UDP 2130706431 192.168.1.5 5000 typ host\r\n\
+                   a=end-of-candidates\r\n";
+        let session = SessionDescription::parse(SdpType::Offer, sdp).unwrap();
+
+        assert_eq!(session.ice_options(), vec!["trickle".to_string()]);
+        assert!(session.supports_trickle());
+        assert!(session.has_end_of_candidates());
+
+        let plain = SessionDescription::parse(SdpType::Offer, &create_simple_sdp()).unwrap();
+        assert!(!plain.supports_trickle());
+        assert!(!plain.has_end_of_candidates());
+    }
+
     #[test]
     fn test_session_description_default() {
         let session = SessionDescription::default();
@@ -4,14 +4,23 @@
 //! with validation.
 
 use crate::{
-    attribute::Attribute, connection::Connection, errors::SdpError,
-    media_description::MediaDescription, origin::Origin, sdp_type::SdpType,
-    session_description::SessionDescription, timing::Timing,
+    attribute::Attribute,
+    bandwidth::{Bandwidth, BandwidthModifier},
+    connection::Connection,
+    errors::SdpError,
+    extmap::Extmap,
+    media_description::MediaDescription,
+    origin::Origin,
+    sdp_type::SdpType,
+    session_description::SessionDescription,
+    timing::Timing,
 };
 
 /// Builder for constructing `SessionDescription` instances.
 pub struct SessionDescriptionBuilder {
     session: SessionDescription,
+    media_bandwidths: Vec<(String, Bandwidth)>,
+    rtp_extensions: Vec<Extmap>,
 }
 
 impl SessionDescriptionBuilder {
@@ -22,6 +31,8 @@ impl SessionDescriptionBuilder {
     pub fn new(sdp_type: SdpType) -> Self {
         Self {
             session: SessionDescription::new(sdp_type),
+            media_bandwidths: Vec::new(),
+            rtp_extensions: Vec::new(),
         }
     }
 
@@ -147,12 +158,100 @@ impl SessionDescriptionBuilder {
         self
     }
 
+    /// Adds the ICE options the agent supports (`a=ice-options`).
+    ///
+    /// # Arguments
+    /// * `options` - Option tags, e.g. `&["trickle"]` for trickle ICE (RFC 8840)
+    pub fn ice_options(mut self, options: &[&str]) -> Self {
+        self.session.attributes.push(Attribute {
+            name: "ice-options".to_string(),
+            value: Some(options.join(" ")),
+        });
+        self
+    }
+
+    /// Marks the candidate list as complete (`a=end-of-candidates`).
+    ///
+    /// Tells a trickle ICE peer that no more candidates will follow.
+    pub fn end_of_candidates(mut self) -> Self {
+        self.session.attributes.push(Attribute {
+            name: "end-of-candidates".to_string(),
+            value: None,
+        });
+        self
+    }
+
+    /// Adds the DTLS certificate fingerprint (`a=fingerprint`) to the session.
+    ///
+    /// # Arguments
+    /// * `hash_func` - Hash function name (e.g. "sha-256")
+    /// * `value` - Colon-separated hex digest of the certificate
+    pub fn dtls_fingerprint(mut self, hash_func: &str, value: &str) -> Self {
+        self.session.attributes.push(Attribute {
+            name: "fingerprint".to_string(),
+            value: Some(format!("{} {}", hash_func, value)),
+        });
+        self
+    }
+
+    /// Adds a bandwidth line (b=) to the media description with the given mid.
+    ///
+    /// The media description is looked up by its `a=mid` attribute when the
+    /// session is built, so this may be called before or after `add_media`.
+    ///
+    /// # Arguments
+    /// * `mid` - Media identification of the target media description
+    /// * `modifier` - Bandwidth modifier (e.g. AS or TIAS)
+    /// * `value` - Bandwidth in the unit of the modifier
+    pub fn media_bandwidth(mut self, mid: &str, modifier: BandwidthModifier, value: u64) -> Self {
+        self.media_bandwidths
+            .push((mid.to_string(), Bandwidth { modifier, value }));
+        self
+    }
+
+    /// Maps an RTP header extension to an id (`a=extmap`, RFC 8285).
+    ///
+    /// The mapping is added to every RTP media description when the session
+    /// is built, so this may be called before or after `add_media`.
+    ///
+    /// # Arguments
+    /// * `id` - Identifier carried in RTP packets (1-14 for one-byte headers)
+    /// * `uri` - URI naming the extension
+    pub fn rtp_extension(mut self, id: u8, uri: &str) -> Self {
+        self.rtp_extensions.push(Extmap::new(id, uri));
+        self
+    }
+
     /// Builds and validates the `SessionDescription`.
     ///
     /// # Returns
     /// * `Ok(SessionDescription)` - If the session description is valid
-    /// * `Err(SdpError)` - If validation fails
-    pub fn build(self) -> Result<SessionDescription, SdpError> {
+    /// * `Err(SdpError)` - If validation fails or a bandwidth targets an unknown mid
+    pub fn build(mut self) -> Result<SessionDescription, SdpError> {
+        for (mid, bandwidth) in self.media_bandwidths {
+            let media = self
+                .session
+                .media
+                .iter_mut()
+                .find(|media| media.mid() == Some(mid.as_str()))
+                .ok_or(SdpError::UnknownMediaId(mid))?;
+            media.bandwidths.push(bandwidth);
+        }
+
+        for media in self
+            .session
+            .media
+            .iter_mut()
+            .filter(|media| media.protocol.contains("RTP"))
+        {
+            media
+                .attributes
+                .extend(self.rtp_extensions.iter().map(|extmap| Attribute {
+                    name: "extmap".to_string(),
+                    value: Some(extmap.to_value()),
+                }));
+        }
+
         self.session.validate()?;
         Ok(self.session)
     }
@@ -189,6 +288,7 @@ mod tests {
                 protocol: "RTP/AVP".to_string(),
                 formats: vec!["0".to_string()],
                 connection: None,
+                bandwidths: Vec::new(),
                 attributes: Vec::new(),
             })
             .build()
@@ -214,6 +314,7 @@ mod tests {
                 protocol: "RTP/AVP".to_string(),
                 formats: vec!["0".to_string()],
                 connection: None,
+                bandwidths: Vec::new(),
                 attributes: Vec::new(),
             })
             .build()
@@ -244,6 +345,7 @@ mod tests {
                 protocol: "RTP/AVP".to_string(),
                 formats: vec!["0".to_string()],
                 connection: None,
+                bandwidths: Vec::new(),
                 attributes: Vec::new(),
             })
             .build()
@@ -278,6 +380,7 @@ mod tests {
                 protocol: "RTP/AVP".to_string(),
                 formats: vec!["0".to_string()],
                 connection: None,
+                bandwidths: Vec::new(),
                 attributes: Vec::new(),
             })
             .build()
@@ -303,6 +406,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["0".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         };
 
@@ -312,6 +416,7 @@ mod tests {
             protocol: "RTP/AVP".to_string(),
             formats: vec!["99".to_string()],
             connection: None,
+            bandwidths: Vec::new(),
             attributes: Vec::new(),
         };
 
@@ -356,6 +461,7 @@ mod tests {
                 protocol: "RTP/AVP".to_string(),
                 formats: vec!["0".to_string()],
                 connection: None,
+                bandwidths: Vec::new(),
                 attributes: Vec::new(),
             })
             .build()
@@ -399,6 +505,7 @@ mod tests {
                 protocol: "RTP/AVP".to_string(),
                 formats: vec!["0".to_string()],
                 connection: None,
+                bandwidths: Vec::new(),
                 attributes: Vec::new(),
             })
             .build()
@@ -412,6 +519,65 @@ mod tests {
         assert_eq!(session.media.len(), 1);
     }
 
+    #[test]
+    fn test_builder_dtls_fingerprint() {
+        let session = SessionDescriptionBuilder::new(SdpType::Offer)
+            .origin(Origin {
+                session_id: 1,
+                ..Default::default()
+            })
+            .session_name("Test")
+            .dtls_fingerprint("sha-256", "AB:CD:EF")
+            .add_media(MediaDescription {
+                media_type: "audio".to_string(),
+                port: 49170,
+                protocol: "RTP/AVP".to_string(),
+                formats: vec!["0".to_string()],
+                connection: None,
+                bandwidths: Vec::new(),
+                attributes: Vec::new(),
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(session.attributes[0].name, "fingerprint");
+        assert_eq!(
+            session.attributes[0].value,
+            Some("sha-256 AB:CD:EF".to_string())
+        );
+    }
+
+    #[test]
+    fn test_builder_ice_options_and_end_of_candidates() {
+        let session = SessionDescriptionBuilder::new(SdpType::Offer)
+            .origin(Origin {
+                session_id: 1,
+                ..Default::default()
+            })
+            .session_name("Test")
+            .ice_options(&["trickle", "renomination"])
+            .add_ice_candidate("candidate:1 1 UDP 2130706431 192.168.1.5 5000 typ host")
+            .end_of_candidates()
+            .add_media(MediaDescription {
+                media_type: "audio".to_string(),
+                port: 49170,
+                protocol: "RTP/AVP".to_string(),
+                formats: vec!["0".to_string()],
+                connection: None,
+                bandwidths: Vec::new(),
+                attributes: Vec::new(),
+            })
+            .build()
+            .unwrap();
+
+        let sdp = session.to_string();
+        let lines: Vec<&str> = sdp.lines().collect();
+        assert!(lines.contains(&"a=ice-options:trickle renomination"));
+        assert!(lines.contains(&"a=end-of-candidates"));
+        assert!(session.supports_trickle());
+        assert!(session.has_end_of_candidates());
+    }
+
     #[test]
     fn test_builder_validation_fails_no_media() {
         let result = SessionDescriptionBuilder::new(SdpType::Offer)
@@ -431,10 +597,119 @@ mod tests {
                 protocol: "RTP/AVP".to_string(),
                 formats: vec!["0".to_string()],
                 connection: None,
+                bandwidths: Vec::new(),
                 attributes: Vec::new(),
             })
             .build();
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_builder_media_bandwidth() {
+        let media = MediaDescription {
+            media_type: "video".to_string(),
+            port: 9,
+            protocol: "UDP/TLS/RTP/SAVPF".to_string(),
+            formats: vec!["96".to_string()],
+            connection: Some(Connection::parse("IN IP4 0.0.0.0").unwrap()),
+            bandwidths: Vec::new(),
+            attributes: vec![
+                Attribute::parse("mid:video").unwrap(),
+                Attribute::parse("rtpmap:96 H264/90000").unwrap(),
+            ],
+        };
+
+        let session = SessionDescriptionBuilder::new(SdpType::Answer)
+            .origin(Origin {
+                session_id: 1,
+                ..Default::default()
+            })
+            .media_bandwidth("video", BandwidthModifier::As, 512)
+            .add_media(media)
+            .media_bandwidth("video", BandwidthModifier::Tias, 500_000)
+            .build()
+            .unwrap();
+
+        let sdp = session.to_string();
+        let media_lines: Vec<&str> = sdp.lines().skip_while(|l| !l.starts_with("m=")).collect();
+        assert_eq!(
+            media_lines,
+            vec![
+                "m=video 9 UDP/TLS/RTP/SAVPF 96",
+                "c=IN IP4 0.0.0.0",
+                "b=AS:512",
+                "b=TIAS:500000",
+                "a=mid:video",
+                "a=rtpmap:96 H264/90000",
+            ]
+        );
+
+        let parsed = SessionDescription::parse(SdpType::Answer, &sdp).unwrap();
+        assert_eq!(parsed.media[0].bandwidth(), session.media[0].bandwidth());
+    }
+
+    #[test]
+    fn test_builder_media_bandwidth_unknown_mid() {
+        let result = SessionDescriptionBuilder::new(SdpType::Offer)
+            .origin(Origin {
+                session_id: 1,
+                ..Default::default()
+            })
+            .add_media(MediaDescription {
+                media_type: "audio".to_string(),
+                port: 49170,
+                protocol: "RTP/AVP".to_string(),
+                formats: vec!["0".to_string()],
+                connection: None,
+                bandwidths: Vec::new(),
+                attributes: Vec::new(),
+            })
+            .media_bandwidth("video", BandwidthModifier::As, 512)
+            .build();
+
+        assert!(matches!(result, Err(SdpError::UnknownMediaId(mid)) if mid == "video"));
+    }
+
+    #[test]
+    fn test_builder_rtp_extension() {
+        let media = |media_type: &str, protocol: &str, format: &str| MediaDescription {
+            media_type: media_type.to_string(),
+            port: 9,
+            protocol: protocol.to_string(),
+            formats: vec![format.to_string()],
+            connection: None,
+            bandwidths: Vec::new(),
+            attributes: Vec::new(),
+        };
+
+        let session = SessionDescriptionBuilder::new(SdpType::Offer)
+            .origin(Origin {
+                session_id: 1,
+                ..Default::default()
+            })
+            .rtp_extension(1, crate::extmap::AUDIO_LEVEL_URI)
+            .add_media(media("audio", "UDP/TLS/RTP/SAVPF", "111"))
+            .add_media(media("application", "DTLS/SCTP", "webrtc-datachannel"))
+            .rtp_extension(5, crate::extmap::TRANSPORT_CC_URI)
+            .build()
+            .unwrap();
+
+        let sdp = session.to_string();
+        assert!(
+            sdp.lines()
+                .any(|line| line == "a=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level")
+        );
+
+        let parsed = SessionDescription::parse(SdpType::Offer, &sdp).unwrap();
+        assert_eq!(
+            parsed.media[0].extmaps(),
+            vec![
+                Extmap::new(1, crate::extmap::AUDIO_LEVEL_URI),
+                Extmap::new(5, crate::extmap::TRANSPORT_CC_URI),
+            ]
+        );
+        // Header extensions only apply to RTP media
+        assert!(parsed.media[1].extmaps().is_empty());
+    }
 }
@@ -1,11 +1,12 @@
 //! Audio management module
 //!
-//! Handles audio device initialization, discovery, and lifecycle management
+//! Handles audio device initialization, discovery, lifecycle management and
+//! echo cancellation between playback and capture
 
 use crate::audio_info::AudioInfo;
 use logging::Logger;
 use media::audio::{AudioCapture, AudioPlayback};
-use media::{AudioDetection, AudioInfo as MediaAudioInfo};
+use media::{AudioDetection, AudioInfo as MediaAudioInfo, EchoCanceller, NlmsEchoCanceller};
 use std::error::Error;
 
 /// Manages audio devices and their lifecycle
@@ -16,6 +17,10 @@ pub struct AudioManager {
     playback: Option<AudioPlayback>,
     /// Cached list of available audio devices
     available_devices: Vec<AudioInfo>,
+    /// Create an NLMS echo canceller when capture starts
+    aec_enabled: bool,
+    /// Removes the played audio from the capture (speakerphone echo)
+    echo_canceller: Option<Box<dyn EchoCanceller>>,
     /// Logger instance
     logger: Logger,
 }
@@ -27,10 +32,26 @@ impl AudioManager {
             capture: None,
             playback: None,
             available_devices: Vec::new(),
+            aec_enabled: false,
+            echo_canceller: None,
             logger,
         }
     }
 
+    /// Enables or disables acoustic echo cancellation for the next capture
+    pub fn set_aec_enabled(&mut self, enabled: bool) {
+        self.aec_enabled = enabled;
+        if !enabled {
+            self.echo_canceller = None;
+        }
+    }
+
+    /// Installs a custom echo canceller, replacing the NLMS default
+    pub fn set_echo_canceller(&mut self, echo_canceller: Box<dyn EchoCanceller>) {
+        self.aec_enabled = true;
+        self.echo_canceller = Some(echo_canceller);
+    }
+
     /// Discovers all available audio input devices on the system
     pub fn discover_devices(&mut self) -> Result<Vec<AudioInfo>, Box<dyn Error>> {
         self.logger.info("Discovering available audio devices...");
@@ -72,15 +93,25 @@ impl AudioManager {
         // Log the actual settings
         let settings = AudioSettings {
             sample_rate: capture.sample_rate(),
+            device_sample_rate: capture.device_sample_rate(),
             channels: capture.channels(),
             buffer_size: 0, // Not exposed by AudioCapture directly currently
         };
 
         self.logger.info(&format!(
-            "Audio capture started: {} Hz, {} channel(s)",
-            settings.sample_rate, settings.channels
+            "Audio capture started: {} Hz (device {} Hz), {} channel(s)",
+            settings.sample_rate, settings.device_sample_rate, settings.channels
         ));
 
+        if self.aec_enabled && self.echo_canceller.is_none() {
+            self.logger
+                .info("Acoustic echo cancellation enabled (NLMS)");
+            self.echo_canceller = Some(Box::new(NlmsEchoCanceller::new(
+                settings.sample_rate,
+                settings.channels,
+            )));
+        }
+
         self.capture = Some(capture);
 
         Ok(settings)
@@ -115,6 +146,9 @@ impl AudioManager {
         if self.capture.is_some() {
             self.logger.info("Stopping audio capture");
             self.capture = None;
+            if let Some(aec) = self.echo_canceller.as_mut() {
+                aec.reset();
+            }
         }
     }
 
@@ -130,7 +164,11 @@ impl AudioManager {
         // Assuming ~20ms buffer target for WebRTC
         let samples_needed =
             (capture.sample_rate() as usize * capture.channels() as usize * 20) / 1000;
-        let samples = capture.read_samples(samples_needed);
+        let mut samples = capture.read_samples(samples_needed);
+
+        if let Some(aec) = self.echo_canceller.as_mut() {
+            aec.process_near_end(&mut samples);
+        }
 
         Ok(media::AudioFrame::new(
             samples,
@@ -175,6 +213,9 @@ impl AudioManager {
         match self.playback.as_ref() {
             Some(playback) => {
                 playback.play_samples(&frame.samples);
+                if let Some(aec) = self.echo_canceller.as_mut() {
+                    aec.process_far_end(&frame.samples);
+                }
                 Ok(())
             }
             None => {
@@ -194,6 +235,7 @@ impl AudioManager {
     pub fn get_settings(&self) -> Option<AudioSettings> {
         self.capture.as_ref().map(|c| AudioSettings {
             sample_rate: c.sample_rate(),
+            device_sample_rate: c.device_sample_rate(),
             channels: c.channels(),
             buffer_size: 0,
         })
@@ -209,7 +251,10 @@ impl AudioManager {
 
 #[derive(Debug, Clone, Copy)]
 pub struct AudioSettings {
+    /// Negotiated codec sample rate (what frames are captured and played at)
     pub sample_rate: u32,
+    /// Native rate of the capture device; resampled to `sample_rate` when different
+    pub device_sample_rate: u32,
     pub channels: u32,
     pub buffer_size: u32,
 }
@@ -5,15 +5,30 @@
 use crate::camera_info::CameraInfo;
 use logging::Logger;
 use media::video::camera::CameraDetection;
-use media::{Camera, CameraConfig};
+use media::{Camera, CameraCapability, CameraConfig, VideoSource};
 use std::error::Error;
 
+/// Opens a camera device at the given frame rate
+pub(crate) type OpenCamera =
+    fn(i32, f64, Logger) -> Result<Box<dyn VideoSource + Send>, Box<dyn Error>>;
+
+/// Probes the modes a camera device supports
+pub(crate) type ProbeCapabilities = fn(i32, &Logger) -> Vec<CameraCapability>;
+
 /// Manages camera devices and their lifecycle
 pub struct CameraManager {
     /// Currently active camera
-    active_camera: Option<Camera>,
+    active_camera: Option<Box<dyn VideoSource + Send>>,
+    /// Device ID of the active camera
+    active_device: Option<i32>,
     /// Cached list of available cameras
     available_cameras: Vec<CameraInfo>,
+    /// Checks whether a device exists before it is opened
+    is_device_available: fn(i32) -> bool,
+    /// Opens a device
+    open_camera: OpenCamera,
+    /// Lists the modes a device supports
+    probe_capabilities: ProbeCapabilities,
     /// Logger instance
     logger: Logger,
 }
@@ -21,9 +36,22 @@ pub struct CameraManager {
 impl CameraManager {
     /// Creates a new CameraManager
     pub fn new(logger: Logger) -> Self {
+        Self::with_devices(logger, CameraDetection::is_device_available, open_camera)
+    }
+
+    /// Creates a CameraManager that finds and opens devices with the given functions
+    pub(crate) fn with_devices(
+        logger: Logger,
+        is_device_available: fn(i32) -> bool,
+        open_camera: OpenCamera,
+    ) -> Self {
         Self {
             active_camera: None,
+            active_device: None,
             available_cameras: Vec::new(),
+            is_device_available,
+            open_camera,
+            probe_capabilities: CameraDetection::capabilities,
             logger,
         }
     }
@@ -47,6 +75,34 @@ impl CameraManager {
         Ok(self.available_cameras.clone())
     }
 
+    /// Lists the resolutions a device supports, with their frame rates and pixel formats
+    ///
+    /// A device that reports no modes (or cannot be probed) is assumed to
+    /// support the defaults of [`CameraCapability::defaults`].
+    ///
+    /// # Arguments
+    /// * `device_id` - Device identifier
+    ///
+    /// # Returns
+    /// Capabilities sorted by ascending resolution
+    pub fn capabilities(&self, device_id: i32) -> Vec<CameraCapability> {
+        let capabilities = (self.probe_capabilities)(device_id, &self.logger);
+        if capabilities.is_empty() {
+            self.logger.warn(&format!(
+                "Camera {} reported no modes, assuming defaults",
+                device_id
+            ));
+            return CameraCapability::defaults();
+        }
+
+        self.logger.info(&format!(
+            "Camera {} supports {} resolution(s)",
+            device_id,
+            capabilities.len()
+        ));
+        capabilities
+    }
+
     /// Starts a camera with the given configuration
     pub fn start_camera(
         &mut self,
@@ -61,11 +117,10 @@ impl CameraManager {
         self.logger
             .info(&format!("Starting camera {} at {:.1} fps", device_id, fps));
 
-        let config = CameraConfig::new(device_id, fps)?;
-        let camera = Camera::new(config, self.logger.clone())?;
+        let camera = (self.open_camera)(device_id, fps, self.logger.clone())?;
 
-        let (width, height) = camera.actual_resolution();
-        let actual_fps = camera.actual_fps();
+        let (width, height) = camera.resolution();
+        let actual_fps = camera.fps();
 
         self.logger.info(&format!(
             "Camera {} started: {}x{} @ {:.1} fps",
@@ -79,6 +134,7 @@ impl CameraManager {
         };
 
         self.active_camera = Some(camera);
+        self.active_device = Some(device_id);
 
         Ok(resolution)
     }
@@ -107,8 +163,54 @@ impl CameraManager {
             fps: actual_fps,
         };
 
+        self.active_device = Some(camera.config().device_id);
+        self.active_camera = Some(Box::new(camera));
+
+        Ok(resolution)
+    }
+
+    /// Switches the running camera to another device
+    ///
+    /// The new device is opened at the current frame rate before the old
+    /// one is released, so a device that fails to open leaves the current
+    /// camera running.
+    ///
+    /// # Errors
+    /// If no camera is running, or the device does not exist or cannot be opened
+    pub fn switch_camera(&mut self, device_id: i32) -> Result<CameraResolution, Box<dyn Error>> {
+        let current = self.active_camera.as_ref().ok_or("Camera not started")?;
+        let fps = current.fps();
+        if self.active_device == Some(device_id) {
+            let (width, height) = current.resolution();
+            return Ok(CameraResolution { width, height, fps });
+        }
+
+        if !(self.is_device_available)(device_id) {
+            self.logger
+                .warn(&format!("Cannot switch to camera {}: not found", device_id));
+            return Err(format!("Camera {} not found", device_id).into());
+        }
+
+        self.logger.info(&format!(
+            "Switching camera {:?} -> {} at {:.1} fps",
+            self.active_device, device_id, fps
+        ));
+        let camera = (self.open_camera)(device_id, fps, self.logger.clone())?;
+        let (width, height) = camera.resolution();
+        let resolution = CameraResolution {
+            width,
+            height,
+            fps: camera.fps(),
+        };
+
+        // Dropping the previous camera releases its hardware
         self.active_camera = Some(camera);
+        self.active_device = Some(device_id);
 
+        self.logger.info(&format!(
+            "Camera {} active: {}x{} @ {:.1} fps",
+            device_id, width, height, resolution.fps
+        ));
         Ok(resolution)
     }
 
@@ -118,6 +220,7 @@ impl CameraManager {
             self.logger.info("Stopping camera and releasing hardware");
             drop(camera);
         }
+        self.active_device = None;
     }
 
     /// Checks if camera is currently running
@@ -125,6 +228,11 @@ impl CameraManager {
         self.active_camera.is_some()
     }
 
+    /// Returns the device ID of the running camera
+    pub fn active_device(&self) -> Option<i32> {
+        self.active_device
+    }
+
     /// Captures a frame from the active camera
     pub fn capture_frame(&mut self) -> Result<media::VideoFrame, Box<dyn Error>> {
         let camera = self.active_camera.as_mut().ok_or("Camera not started")?;
@@ -132,6 +240,16 @@ impl CameraManager {
     }
 }
 
+/// Opens a camera device with OpenCV
+fn open_camera(
+    device_id: i32,
+    fps: f64,
+    logger: Logger,
+) -> Result<Box<dyn VideoSource + Send>, Box<dyn Error>> {
+    let config = CameraConfig::new(device_id, fps)?;
+    Ok(Box::new(Camera::new(config, logger)?))
+}
+
 /// Camera resolution information
 #[derive(Debug, Clone, Copy)]
 pub struct CameraResolution {
@@ -139,3 +257,76 @@ pub struct CameraResolution {
     pub height: u32,
     pub fps: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::LogLevel;
+    use media::PixelFormat;
+
+    fn create_test_logger() -> Logger {
+        let log_path = std::env::temp_dir().join("test_camera_manager.log");
+        Logger::new(log_path, LogLevel::Debug).unwrap()
+    }
+
+    fn no_camera(_device_id: i32) -> bool {
+        false
+    }
+
+    fn open_no_camera(
+        device_id: i32,
+        _fps: f64,
+        _logger: Logger,
+    ) -> Result<Box<dyn VideoSource + Send>, Box<dyn Error>> {
+        Err(format!("Camera {} not found", device_id).into())
+    }
+
+    /// Device 0 supports 640x480 at 15/30 fps and 1920x1080 at 30 fps in MJPEG
+    fn probe_known_modes(device_id: i32, _logger: &Logger) -> Vec<CameraCapability> {
+        if device_id != 0 {
+            return Vec::new();
+        }
+        CameraCapability::from_modes(&[
+            (PixelFormat::Mjpeg, 1920, 1080, 30.0),
+            (PixelFormat::Yuyv, 640, 480, 30.0),
+            (PixelFormat::Yuyv, 640, 480, 15.0),
+            (PixelFormat::Mjpeg, 640, 480, 30.0),
+        ])
+    }
+
+    fn manager() -> CameraManager {
+        let mut manager =
+            CameraManager::with_devices(create_test_logger(), no_camera, open_no_camera);
+        manager.probe_capabilities = probe_known_modes;
+        manager
+    }
+
+    #[test]
+    fn test_capabilities_from_detection() {
+        let capabilities = manager().capabilities(0);
+
+        assert_eq!(capabilities.len(), 2);
+        assert_eq!((capabilities[0].width, capabilities[0].height), (640, 480));
+        assert_eq!(capabilities[0].frame_rates, vec![15.0, 30.0]);
+        assert_eq!(
+            capabilities[0].pixel_formats,
+            vec![PixelFormat::Yuyv, PixelFormat::Mjpeg]
+        );
+        assert_eq!(
+            (capabilities[1].width, capabilities[1].height),
+            (1920, 1080)
+        );
+        assert!(capabilities[1].supports_fps(30.0));
+        assert!(!capabilities[1].supports_fps(15.0));
+        assert_eq!(capabilities[1].pixel_formats, vec![PixelFormat::Mjpeg]);
+    }
+
+    #[test]
+    fn test_device_without_modes_falls_back_to_defaults() {
+        let capabilities = manager().capabilities(2);
+
+        assert_eq!(capabilities, CameraCapability::defaults());
+        assert!(capabilities.iter().all(|c| !c.frame_rates.is_empty()));
+        assert!(capabilities.iter().all(|c| !c.pixel_formats.is_empty()));
+    }
+}
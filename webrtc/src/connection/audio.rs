@@ -14,8 +14,12 @@ pub(super) struct AudioHandler {
 
 impl AudioHandler {
     pub fn new(logger: Logger) -> Self {
+        let mut audio_manager = AudioManager::new(logger.clone());
+        // Laptops play and capture in the same room: cancel speaker echo
+        audio_manager.set_aec_enabled(true);
+
         Self {
-            audio_manager: AudioManager::new(logger.clone()),
+            audio_manager,
             logger,
             is_muted: false,
         }
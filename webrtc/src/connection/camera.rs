@@ -1,22 +1,30 @@
-//! Camera management functionality for WebRTC connection
+//! Camera and screen capture management for WebRTC connection
 
 use crate::camera_info::CameraInfo;
 use crate::camera_manager::{CameraManager, CameraResolution};
 use logging::Logger;
-use media::VideoFrame;
 use media::video::camera::CameraDetection;
+use media::{ScreenCapture, ScreenCaptureConfig, VideoFrame, VideoSource};
 use std::error::Error;
 
 /// Handles all camera-related operations
+///
+/// An active screen share takes precedence over the camera as video source.
 pub(super) struct CameraHandler {
     camera_manager: CameraManager,
+    screen_capture: Option<ScreenCapture>,
     logger: Logger,
 }
 
 impl CameraHandler {
     pub fn new(logger: Logger) -> Self {
+        Self::with_camera_manager(CameraManager::new(logger.clone()), logger)
+    }
+
+    pub fn with_camera_manager(camera_manager: CameraManager, logger: Logger) -> Self {
         Self {
-            camera_manager: CameraManager::new(logger.clone()),
+            camera_manager,
+            screen_capture: None,
             logger,
         }
     }
@@ -54,12 +62,59 @@ impl CameraHandler {
         Ok(resolution)
     }
 
+    /// Swaps the running camera for another device, keeping its frame rate
+    pub fn switch_camera(&mut self, device_id: i32) -> Result<CameraResolution, Box<dyn Error>> {
+        self.logger
+            .info(&format!("SWITCH_CAMERA called: device={}", device_id));
+        self.camera_manager.switch_camera(device_id)
+    }
+
+    pub fn active_camera_device(&self) -> Option<i32> {
+        self.camera_manager.active_device()
+    }
+
     pub fn stop_camera(&mut self) {
         self.camera_manager.stop_camera();
         self.logger.info("Camera stopped");
     }
 
+    pub fn is_screen_sharing(&self) -> bool {
+        self.screen_capture.is_some()
+    }
+
+    pub fn start_screen_share(
+        &mut self,
+        display_id: i32,
+        fps: f64,
+    ) -> Result<CameraResolution, Box<dyn Error>> {
+        self.logger.info(&format!(
+            "START_SCREEN_SHARE called: display={}, fps={}",
+            display_id, fps
+        ));
+
+        let config = ScreenCaptureConfig::new(display_id, fps)?;
+        let fps = config.fps;
+        let mut capture = ScreenCapture::open(config, self.logger.clone())?;
+
+        // Grab once so the encoder can be sized to the display
+        capture.capture_frame()?;
+        let (width, height) = capture.resolution();
+        self.screen_capture = Some(capture);
+
+        self.logger.info("Screen share started");
+        Ok(CameraResolution { width, height, fps })
+    }
+
+    pub fn stop_screen_share(&mut self) {
+        if self.screen_capture.take().is_some() {
+            self.logger.info("Screen share stopped");
+        }
+    }
+
     pub fn capture_frame(&mut self) -> Result<VideoFrame, Box<dyn Error>> {
-        self.camera_manager.capture_frame()
+        match self.screen_capture.as_mut() {
+            Some(capture) => Ok(capture.capture_frame()?),
+            None => self.camera_manager.capture_frame(),
+        }
     }
 }
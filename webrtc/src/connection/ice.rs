@@ -1,14 +1,25 @@
 //! ICE candidate management for WebRTC connection
 
-use ice::{Candidate, IceAgent};
+use ice::{Candidate, CandidateSocket, ConnectionState, GatheringReport, IceAgent, IceError};
 use logging::Logger;
 use std::error::Error;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::mpsc::{Receiver, channel};
+use std::time::{Duration, Instant};
 
 /// Handles all ICE-related operations
 pub(super) struct IceHandler {
     pub(super) ice_agent: IceAgent,
     stun_servers: Vec<String>,
     turn_servers: Vec<String>,
+    /// Connection state changes reported by the ICE agent
+    state_changes: Receiver<ConnectionState>,
+    /// Remote addresses of newly selected pairs (nomination or failover)
+    pair_changes: Receiver<SocketAddr>,
+    /// The remote description carried `a=ice-options:trickle`
+    remote_trickle: bool,
+    /// The remote peer signalled `a=end-of-candidates`
+    remote_candidates_complete: bool,
     logger: Logger,
 }
 
@@ -31,10 +42,26 @@ impl IceHandler {
             turn_servers.len()
         ));
 
+        let (state_tx, state_changes) = channel();
+        let mut ice_agent = IceAgent::new();
+        // Offers always carry a=rtcp-mux, so only RTP candidates are gathered
+        ice_agent.set_rtcp_mux(true);
+        ice_agent.on_state_change(move |state| {
+            let _ = state_tx.send(state);
+        });
+        let (pair_tx, pair_changes) = channel();
+        ice_agent.on_selected_pair_change(move |pair| {
+            let _ = pair_tx.send(SocketAddr::new(pair.remote.address, pair.remote.port));
+        });
+
         Self {
-            ice_agent: IceAgent::new(),
+            ice_agent,
             stun_servers,
             turn_servers,
+            state_changes,
+            pair_changes,
+            remote_trickle: false,
+            remote_candidates_complete: false,
             logger,
         }
     }
@@ -61,11 +88,16 @@ impl IceHandler {
         _sdp_mid: &str,
         _sdp_mline_index: u16,
     ) -> Result<(), Box<dyn Error>> {
+        if Self::is_end_of_candidates(candidate) {
+            self.logger.info("Remote peer signalled end-of-candidates");
+            self.remote_candidates_complete = true;
+            return Ok(());
+        }
+
         self.logger
             .info(&format!("Adding ICE candidate: {}", candidate));
 
-        let cleaned = candidate.trim_start_matches("candidate:");
-        let parsed_candidate = Candidate::parse(cleaned)?;
+        let parsed_candidate = Candidate::parse(candidate)?;
 
         self.logger.info(&format!(
             "Remote ICE candidate added: {}:{} (type: {:?}, priority: {})",
@@ -81,6 +113,14 @@ impl IceHandler {
     }
 
     pub fn gather_candidates(&mut self, port: u16) -> Result<(), Box<dyn Error>> {
+        if self.ice_agent.use_pregathered(port)? {
+            self.logger.info(&format!(
+                "Using {} pregathered ICE candidates",
+                self.ice_agent.local_candidates.len()
+            ));
+            return Ok(());
+        }
+
         if self.ice_agent.local_candidates.is_empty() {
             self.ice_agent.gather_host_candidates(port)?;
         }
@@ -92,6 +132,24 @@ impl IceHandler {
         Ok(())
     }
 
+    /// Gathers candidates with the configured servers before they are needed
+    pub fn pregather(&mut self, port: u16) -> Result<(), Box<dyn Error>> {
+        self.logger
+            .info(&format!("Pregathering ICE candidates on port {}", port));
+        self.ice_agent
+            .pregather(port, &self.stun_servers, &self.turn_servers)?;
+        self.logger.info(&format!(
+            "Pregathered {} ICE candidates",
+            self.ice_agent.local_candidates.len()
+        ));
+        Ok(())
+    }
+
+    /// Age of the pregathered candidates, `None` if none were pregathered
+    pub fn pregather_age(&self) -> Option<Duration> {
+        self.ice_agent.pregather_age()
+    }
+
     fn gather_stun_candidates(&mut self, port: u16) -> Result<(), Box<dyn Error>> {
         if !self.stun_servers.is_empty() {
             self.logger.info(&format!(
@@ -103,7 +161,8 @@ impl IceHandler {
                 .ice_agent
                 .gather_server_reflexive_candidates(port, &self.stun_servers)
             {
-                Ok(_) => {
+                Ok(report) => {
+                    self.log_failed_servers("STUN", &report);
                     self.logger.info(
                         "STUN candidates gathered successfully - Internet connectivity enabled",
                     );
@@ -132,7 +191,10 @@ impl IceHandler {
                 .ice_agent
                 .gather_relay_candidates(port, &self.turn_servers)
             {
-                Ok(_) => self.logger.info("TURN candidates gathered (relay enabled)"),
+                Ok(report) => {
+                    self.log_failed_servers("TURN", &report);
+                    self.logger.info("TURN candidates gathered (relay enabled)");
+                }
                 Err(e) => self.logger.warn(&format!(
                     "TURN gathering failed: {} (direct/STUN will be tried)",
                     e
@@ -142,6 +204,147 @@ impl IceHandler {
         Ok(())
     }
 
+    /// Logs the servers that failed in an otherwise successful gathering pass
+    fn log_failed_servers(&self, kind: &str, report: &GatheringReport) {
+        for failed in report.failed() {
+            if let Err(e) = &failed.result {
+                self.logger
+                    .warn(&format!("{} server {} failed: {}", kind, failed.server, e));
+            }
+        }
+    }
+
+    /// Runs ICE connectivity checks from the media socket until a pair is
+    /// selected
+    ///
+    /// Blocks for at most the agent's checking timeout; [`cancel_checks`](Self::cancel_checks)
+    /// stops it early.
+    ///
+    /// # Arguments
+    /// * `socket` - Clone of the media socket the checks are sent from
+    /// * `controlling` - Whether this side is the controlling agent (the offerer)
+    ///
+    /// # Returns
+    /// * `Ok(Some(addr))` - Remote address of the selected pair
+    /// * `Ok(None)` - Nothing could be checked; the caller uses the signalled address
+    /// * `Err` - If the checking timeout passed or the checks were cancelled
+    pub fn run_connectivity_checks(
+        &mut self,
+        socket: UdpSocket,
+        controlling: bool,
+    ) -> Result<Option<SocketAddr>, Box<dyn Error>> {
+        let Some(local) = self.ice_agent.local_candidates.first().cloned() else {
+            return Ok(None);
+        };
+        self.ice_agent.set_controlling(controlling);
+        let socket = CandidateSocket::from_socket(local, socket)?;
+
+        self.logger.info(&format!(
+            "Running ICE connectivity checks on {} candidate pairs",
+            self.ice_agent.candidate_pairs().len()
+        ));
+        match self.ice_agent.run_connectivity_checks(&socket) {
+            Ok(()) => {
+                // The selection is returned here; it is not a later pair change
+                self.pair_changes.try_iter().for_each(drop);
+                Ok(self
+                    .ice_agent
+                    .selected_pair()
+                    .map(|pair| SocketAddr::new(pair.remote.address, pair.remote.port)))
+            }
+            Err(e @ (IceError::CheckingTimeout | IceError::Cancelled)) => Err(e.into()),
+            Err(e) => {
+                self.logger.warn(&format!(
+                    "ICE connectivity checks not run: {}. Using the signalled address.",
+                    e
+                ));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Stops connectivity checks that are running or about to run
+    pub fn cancel_checks(&self) {
+        self.ice_agent.cancel_token().cancel();
+    }
+
+    /// Moves the agent to connected and starts consent freshness on the
+    /// media path
+    ///
+    /// # Arguments
+    /// * `remote_addr` - Address media is sent to
+    pub fn start_consent_checks(&mut self, remote_addr: SocketAddr) -> Result<(), Box<dyn Error>> {
+        // Connectivity checks already selected a pair; keep their results
+        if self.ice_agent.selected_pair().is_none() {
+            self.ice_agent.establish_connection()?;
+        }
+        self.ice_agent.start_consent_checks_to(remote_addr)?;
+        self.logger.info(&format!(
+            "ICE consent freshness started towards {}",
+            remote_addr
+        ));
+        Ok(())
+    }
+
+    /// Runs consent freshness over the STUN packets received on the media socket
+    ///
+    /// Answers the peer's consent checks, refreshes our consent with its
+    /// responses, keeps backup pairs warm and updates the connection state.
+    ///
+    /// # Arguments
+    /// * `received` - STUN packets received since the last call, with sender
+    /// * `now` - Current time
+    ///
+    /// # Returns
+    /// STUN packets to send on the media socket, with their destination
+    pub fn process_consent(
+        &mut self,
+        received: Vec<(Vec<u8>, SocketAddr)>,
+        now: Instant,
+    ) -> Vec<(Vec<u8>, SocketAddr)> {
+        self.ice_agent.process_consent(received, now)
+    }
+
+    /// Records the trickle ICE signals of a remote description
+    pub fn apply_remote_ice_options(&mut self, session: &sdp::SessionDescription) {
+        self.remote_trickle = session.supports_trickle();
+        if session.has_end_of_candidates() {
+            self.remote_candidates_complete = true;
+        }
+    }
+
+    /// Returns whether the remote peer accepts trickled candidates
+    pub fn remote_supports_trickle(&self) -> bool {
+        self.remote_trickle
+    }
+
+    /// Returns whether the remote peer has sent all its candidates
+    pub fn remote_candidates_complete(&self) -> bool {
+        self.remote_candidates_complete
+    }
+
+    /// Checks for the end-of-candidates marker of trickle ICE
+    ///
+    /// Signaling carries it as an empty candidate (as browsers do) or as the
+    /// `end-of-candidates` attribute itself.
+    fn is_end_of_candidates(candidate: &str) -> bool {
+        let candidate = candidate.trim();
+        let candidate = candidate.strip_prefix("a=").unwrap_or(candidate);
+        candidate.is_empty() || candidate == "end-of-candidates"
+    }
+
+    /// Returns the next connection state change, if any
+    pub fn poll_state_change(&self) -> Option<ConnectionState> {
+        self.state_changes.try_recv().ok()
+    }
+
+    /// Returns the remote address of the latest selected pair change, if any
+    ///
+    /// Media must be sent to this address from now on.
+    pub fn poll_selected_pair_change(&self) -> Option<SocketAddr> {
+        self.pair_changes.try_iter().last()
+    }
+
     pub fn get_remote_address(&self) -> Option<(String, u16)> {
         self.ice_agent
             .remote_candidates
@@ -164,3 +367,99 @@ impl IceHandler {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::LogLevel;
+
+    fn create_test_logger() -> Logger {
+        let log_path = std::env::temp_dir().join("test_ice_handler.log");
+        Logger::new(log_path, LogLevel::Debug).unwrap()
+    }
+
+    fn candidate(port: u16) -> Candidate {
+        Candidate::parse(&format!(
+            "candidate:1 1 udp 2130706431 127.0.0.1 {} typ host",
+            port
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_end_of_candidates_marker() {
+        let mut handler = IceHandler::new(create_test_logger());
+        assert!(!handler.remote_candidates_complete());
+
+        handler
+            .add_ice_candidate("a=end-of-candidates", "0", 0)
+            .unwrap();
+
+        assert!(handler.remote_candidates_complete());
+        assert!(handler.ice_agent.remote_candidates.is_empty());
+    }
+
+    #[test]
+    fn test_gathering_uses_fresh_pregathered_candidates() {
+        let mut handler = IceHandler::new(create_test_logger());
+        handler.set_stun_servers(Vec::new());
+        handler.set_turn_servers(Vec::new());
+
+        handler.pregather(5000).unwrap();
+        let cached = handler.ice_agent.get_local_candidates_sdp();
+        assert!(!cached.is_empty());
+        let age = handler.pregather_age().unwrap();
+
+        handler.gather_candidates(5000).unwrap();
+        assert_eq!(handler.ice_agent.get_local_candidates_sdp(), cached);
+        assert!(handler.pregather_age().unwrap() >= age);
+    }
+
+    #[test]
+    fn test_consent_failure_emits_failed_state() {
+        let mut handler = IceHandler::new(create_test_logger());
+        handler
+            .ice_agent
+            .add_local_candidate(candidate(5000))
+            .unwrap();
+        handler
+            .add_ice_candidate(
+                "candidate:1 1 udp 2130706431 127.0.0.1 6000 typ host",
+                "0",
+                0,
+            )
+            .unwrap();
+        assert_eq!(handler.poll_state_change(), None);
+
+        let remote: SocketAddr = "127.0.0.1:6000".parse().unwrap();
+        handler.start_consent_checks(remote).unwrap();
+        assert_eq!(handler.poll_state_change(), Some(ConnectionState::Checking));
+        assert_eq!(
+            handler.poll_state_change(),
+            Some(ConnectionState::Connected)
+        );
+
+        // The peer's own consent checks are answered
+        let (check, _) = {
+            let mut peer = IceAgent::new();
+            peer.add_local_candidate(candidate(6000)).unwrap();
+            peer.add_remote_candidate(candidate(5000)).unwrap();
+            peer.establish_connection().unwrap();
+            peer.build_check(0).unwrap()
+        };
+        let start = Instant::now();
+        let outgoing = handler.process_consent(vec![(check, remote)], start);
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing[0].1, remote);
+
+        // Peer stops answering: disconnected, then failed
+        handler.process_consent(Vec::new(), start + Duration::from_secs(15));
+        assert_eq!(
+            handler.poll_state_change(),
+            Some(ConnectionState::Disconnected)
+        );
+        handler.process_consent(Vec::new(), start + Duration::from_secs(31));
+        assert_eq!(handler.poll_state_change(), Some(ConnectionState::Failed));
+        assert_eq!(handler.poll_state_change(), None);
+    }
+}
@@ -0,0 +1,104 @@
+//! Local echo peer for loopback tests
+//!
+//! Lets one machine exercise the whole media pipeline without a second
+//! participant: a headless connection on localhost decodes everything it
+//! receives and sends it straight back.
+
+use super::webrtc_connection::{RgbFrame, WebRtcConnection};
+use logging::Logger;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Wait between polls when nothing was received
+const IDLE_POLL: Duration = Duration::from_millis(5);
+
+/// Headless connection that sends back the media it receives
+pub(super) struct EchoPeer {
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EchoPeer {
+    /// Starts the echo thread
+    ///
+    /// The thread first completes the DTLS handshake of `peer` (which must
+    /// have negotiated with the local connection and be headless), then
+    /// echoes media until stopped.
+    pub(super) fn start(mut peer: WebRtcConnection, logger: Logger) -> Self {
+        let running = Arc::new(AtomicBool::new(true));
+        let (video_tx, video_rx) = mpsc::channel();
+        let (audio_tx, audio_rx) = mpsc::channel();
+        peer.on_video_frame(move |frame| {
+            let _ = video_tx.send(frame);
+        });
+        peer.on_audio_frame(move |frame| {
+            let _ = audio_tx.send(frame);
+        });
+
+        let thread_running = Arc::clone(&running);
+        let thread = thread::spawn(move || {
+            if let Err(e) = peer.establish_connection() {
+                logger.error(&format!("Loopback echo peer failed to connect: {}", e));
+                return;
+            }
+            logger.info("Loopback echo peer connected");
+
+            while thread_running.load(Ordering::Relaxed) {
+                match Self::echo(&mut peer, &video_rx, &audio_rx) {
+                    Ok(0) => thread::sleep(IDLE_POLL),
+                    Ok(_) => {}
+                    Err(e) => {
+                        logger.warn(&format!("Loopback echo failed: {}", e));
+                        thread::sleep(IDLE_POLL);
+                    }
+                }
+            }
+            peer.close();
+        });
+
+        Self {
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    /// Sends back every frame received since the last call
+    ///
+    /// # Returns
+    /// Number of frames echoed
+    fn echo(
+        peer: &mut WebRtcConnection,
+        video_rx: &Receiver<RgbFrame>,
+        audio_rx: &Receiver<media::AudioFrame>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        peer.deliver_received_media()?;
+
+        let mut echoed = 0;
+        for frame in video_rx.try_iter() {
+            peer.push_video_frame(frame)?;
+            echoed += 1;
+        }
+        for frame in audio_rx.try_iter() {
+            peer.push_audio_frame(frame)?;
+            echoed += 1;
+        }
+        Ok(echoed)
+    }
+
+    /// Stops echoing and closes the echo peer's connection
+    pub(super) fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for EchoPeer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -5,7 +5,10 @@
 mod audio;
 mod camera;
 mod ice;
+mod loopback;
 mod sdp;
+mod stats;
 mod webrtc_connection;
 
-pub use webrtc_connection::{RgbFrame, WebRtcConnection};
+pub use stats::ConnectionStats;
+pub use webrtc_connection::{AudioFrameCallback, RgbFrame, VideoFrameCallback, WebRtcConnection};
@@ -1,28 +1,229 @@
 //! SDP (Session Description Protocol) handling for WebRTC connection
 
+use crate::session::VIDEO_CODECS;
 use ice::{IceAgent, detect_local_ip};
 use logging::Logger;
-use sdp::{Attribute, MediaDescription, Origin, SdpType, SessionDescription, Timing};
+use media::{H264EncoderProfile, H264EncoderSettings, VideoCodec};
+use network::PacketizationMode;
+use network::codec::packetizers::rtx::RTX_PAYLOAD_TYPES;
+use network::codec::rtcp::transport_cc::TRANSPORT_CC_EXTENSION_ID;
+use sdp::extmap::TRANSPORT_CC_URI;
+use sdp::{
+    Attribute, Direction, Extmap, H264Fmtp, H264Profile, MediaDescription, Origin, SdpType,
+    SessionDescription, Timing,
+};
 use std::error::Error;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// H.264 profiles the encoder can send
+///
+/// The encoder produces Constrained Baseline for Baseline peers, which is a
+/// subset of Baseline.
+const H264_PROFILES: [H264Profile; 4] = [
+    H264Profile::ConstrainedBaseline,
+    H264Profile::Baseline,
+    H264Profile::Main,
+    H264Profile::High,
+];
+
+/// H.264 parameters advertised in the offer: Constrained Baseline level 3.1
+const LOCAL_H264_FMTP: H264Fmtp = H264Fmtp {
+    profile: H264Profile::ConstrainedBaseline,
+    profile_level_id: [0x42, 0xe0, 0x1f],
+    packetization_mode: 1,
+    level_asymmetry_allowed: true,
+};
+
+/// Largest NAL unit sent with single NAL unit packetization (bytes)
+const SINGLE_NAL_MAX_SIZE: usize = 1200;
+
+/// RTP header extensions implemented by the transport, with the ids offered for them
+const RTP_EXTENSIONS: [(u8, &str); 1] = [(TRANSPORT_CC_EXTENSION_ID, TRANSPORT_CC_URI)];
+
+/// Largest extension id the one-byte header form can carry (RFC 8285 Section 4.2)
+const MAX_ONE_BYTE_EXTENSION_ID: u8 = 14;
+
+/// `a=mid` of the data channel m-line, which always comes first
+const APPLICATION_MID: &str = "0";
+
+/// A video m-line of the local description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct VideoSection {
+    /// Media identification (`a=mid`)
+    pub mid: String,
+    /// Direction offered, or agreed once the answer is known
+    pub direction: Direction,
+    /// Removed by a renegotiation: the m-line stays, rejected with port 0
+    /// (RFC 3264 Section 8.2)
+    pub stopped: bool,
+}
+
+impl VideoSection {
+    fn new(mid: String, direction: Direction) -> Self {
+        Self {
+            mid,
+            direction,
+            stopped: false,
+        }
+    }
+}
+
 /// Handles all SDP-related operations
 pub(super) struct SdpHandler {
     logger: Logger,
+    /// RIDs advertised in `a=rid`/`a=simulcast`, empty when simulcast is off
+    simulcast_rids: Vec<String>,
+    /// Video codecs FFmpeg can encode and decode on this system
+    video_codecs: Vec<VideoCodec>,
+    /// Header extensions put in the next description: ours in an offer,
+    /// the supported part of the remote offer in an answer
+    rtp_extensions: Vec<Extmap>,
+    /// Video m-lines in order; the first one carries the camera
+    video_sections: Vec<VideoSection>,
+    /// Origin of the last description, reused with a higher version on renegotiation
+    origin: Option<Origin>,
 }
 
 impl SdpHandler {
     pub fn new(logger: Logger) -> Self {
-        Self { logger }
+        let video_codecs = VideoCodec::available();
+        if !video_codecs.contains(&VideoCodec::H264) {
+            logger.warn("H.264 is unavailable (FFmpeg without libx264), not offering it");
+        }
+        Self {
+            logger,
+            simulcast_rids: Vec::new(),
+            video_codecs,
+            rtp_extensions: RTP_EXTENSIONS
+                .iter()
+                .map(|&(id, uri)| Extmap::new(id, uri))
+                .collect(),
+            video_sections: vec![VideoSection::new("1".to_string(), Direction::SendRecv)],
+            origin: None,
+        }
+    }
+
+    /// Restricts the video codecs offered and accepted
+    pub fn set_video_codecs(&mut self, codecs: Vec<VideoCodec>) {
+        self.video_codecs = codecs;
+    }
+
+    /// Video codecs offered and accepted, in preference order
+    pub fn video_codecs(&self) -> &[VideoCodec] {
+        &self.video_codecs
+    }
+
+    /// Sets the simulcast layers advertised on the video m-line
+    pub fn set_simulcast_rids(&mut self, rids: Vec<String>) {
+        self.simulcast_rids = rids;
+    }
+
+    /// Video m-lines of the local description, in order
+    pub fn video_sections(&self) -> &[VideoSection] {
+        &self.video_sections
+    }
+
+    /// Appends a video m-line for a new track, advertised in the next offer
+    ///
+    /// # Returns
+    /// The `a=mid` of the new m-line
+    pub fn add_video_section(&mut self, direction: Direction) -> String {
+        let mid = (self.video_sections.len() + 1).to_string();
+        self.video_sections
+            .push(VideoSection::new(mid.clone(), direction));
+        mid
+    }
+
+    /// Changes the direction offered for a video m-line
+    pub fn set_video_direction(&mut self, mid: &str, direction: Direction) -> Result<(), String> {
+        let section = self.video_section_mut(mid)?;
+        section.direction = direction;
+        Ok(())
+    }
+
+    /// Rejects a video m-line in the next offer (port 0)
+    ///
+    /// M-lines are never deleted so the indexes of the others stay the same.
+    pub fn stop_video_section(&mut self, mid: &str) -> Result<(), String> {
+        let section = self.video_section_mut(mid)?;
+        section.stopped = true;
+        section.direction = Direction::Inactive;
+        Ok(())
     }
 
-    pub fn create_offer(&self, ice_agent: &IceAgent) -> Result<String, Box<dyn Error>> {
+    fn video_section_mut(&mut self, mid: &str) -> Result<&mut VideoSection, String> {
+        self.video_sections
+            .iter_mut()
+            .find(|section| section.mid == mid)
+            .ok_or_else(|| format!("No video m-line with mid {}", mid))
+    }
+
+    /// Updates the video m-lines from a remote description
+    ///
+    /// A remote offer decides the m-lines: the answer mirrors them, each
+    /// with the direction we are willing to use restricted by the offered
+    /// one. A remote answer narrows the directions we offered. Either way a
+    /// rejected (port 0) m-line stops.
+    ///
+    /// # Returns
+    /// The video m-lines after the update
+    pub fn apply_remote_video_sections(
+        &mut self,
+        sdp_type: SdpType,
+        sdp: &str,
+    ) -> Result<Vec<VideoSection>, Box<dyn Error>> {
+        let session = SessionDescription::parse(sdp_type.clone(), sdp)?;
+        let remote: Vec<&MediaDescription> = session
+            .media
+            .iter()
+            .filter(|media| media.media_type == "video")
+            .collect();
+
+        let sections = remote
+            .iter()
+            .enumerate()
+            .map(|(index, media)| {
+                let mid = media
+                    .mid()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| (index + 1).to_string());
+                let remote_direction = media_direction(media);
+                let local = self
+                    .video_sections
+                    .iter()
+                    .find(|section| section.mid == mid)
+                    .map(|section| section.direction)
+                    .unwrap_or(match sdp_type {
+                        SdpType::Offer => Direction::SendRecv,
+                        SdpType::Answer => Direction::Inactive,
+                    });
+                let stopped = media.port == 0;
+                VideoSection {
+                    mid,
+                    direction: if stopped {
+                        Direction::Inactive
+                    } else {
+                        local.answer_to(remote_direction)
+                    },
+                    stopped,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if sections.is_empty() {
+            return Ok(self.video_sections.clone());
+        }
+        self.video_sections = sections;
+        Ok(self.video_sections.clone())
+    }
+
+    pub fn create_offer(&mut self, ice_agent: &IceAgent) -> Result<String, Box<dyn Error>> {
         self.logger.info("Creating SDP offer with ICE candidates");
         let sdp = self.build_sdp(SdpType::Offer, ice_agent)?;
         Ok(sdp)
     }
 
-    pub fn create_answer(&self, ice_agent: &IceAgent) -> Result<String, Box<dyn Error>> {
+    pub fn create_answer(&mut self, ice_agent: &IceAgent) -> Result<String, Box<dyn Error>> {
         self.logger.info("Creating SDP answer with ICE candidates");
         let sdp = self.build_sdp(SdpType::Answer, ice_agent)?;
         Ok(sdp)
@@ -59,14 +260,224 @@ impl SdpHandler {
             })
     }
 
-    fn build_sdp(&self, sdp_type: SdpType, ice_agent: &IceAgent) -> Result<String, Box<dyn Error>> {
+    /// Picks the video codec from a remote description's rtpmap lines
+    ///
+    /// The first supported codec in the remote m-line order wins, so an
+    /// answer narrowed to one codec is honored as-is. Codecs unavailable on
+    /// this system are skipped.
+    ///
+    /// H.264 only counts if one of its payload types has a profile the
+    /// encoder can produce.
+    pub fn negotiate_video_codec(&self, sdp_type: SdpType, sdp: &str) -> Option<VideoCodec> {
+        let session = SessionDescription::parse(sdp_type, sdp).ok()?;
+        let video = session.media.iter().find(|m| m.media_type == "video")?;
+        let h264_usable = H264Fmtp::negotiate(video, &H264_PROFILES).is_ok();
+
+        video
+            .rtpmaps()
+            .into_iter()
+            .filter_map(|(_, encoding)| VideoCodec::from_encoding_name(encoding))
+            .filter(|codec| self.video_codecs.contains(codec))
+            .find(|codec| *codec != VideoCodec::H264 || h264_usable)
+    }
+
+    /// Agrees on the RTP header extension ids with a remote description
+    ///
+    /// A remote offer decides the ids: the answer echoes the offered
+    /// extensions the transport implements. A remote answer keeps the
+    /// offered extensions it accepted.
+    ///
+    /// # Returns
+    /// The agreed mappings; empty if the remote side uses no extensions
+    pub fn negotiate_rtp_extensions(&mut self, sdp_type: SdpType, sdp: &str) -> Vec<Extmap> {
+        let remote = SessionDescription::parse(sdp_type.clone(), sdp)
+            .ok()
+            .and_then(|session| {
+                session
+                    .media
+                    .into_iter()
+                    .find(|media| media.protocol.contains("RTP"))
+            })
+            .map(|media| media.extmaps())
+            .unwrap_or_default();
+
+        match sdp_type {
+            SdpType::Offer => {
+                let supported: Vec<&str> = RTP_EXTENSIONS.iter().map(|&(_, uri)| uri).collect();
+                self.rtp_extensions = Extmap::select(&remote, &supported)
+                    .into_iter()
+                    .filter(|extmap| extmap.id <= MAX_ONE_BYTE_EXTENSION_ID)
+                    .collect();
+                self.rtp_extensions.clone()
+            }
+            SdpType::Answer => Extmap::negotiate(&self.rtp_extensions, &remote),
+        }
+    }
+
+    /// Picks the H.264 profile and packetization mode from the remote fmtp lines
+    ///
+    /// # Returns
+    /// * `Ok((H264EncoderSettings, PacketizationMode))` - Encoder and packetizer configuration
+    /// * `Err` - If the SDP has no H.264 payload type the encoder can send
+    pub fn negotiate_h264(
+        sdp_type: SdpType,
+        sdp: &str,
+    ) -> Result<(H264EncoderSettings, PacketizationMode), Box<dyn Error>> {
+        let session = SessionDescription::parse(sdp_type, sdp)?;
+        let video = session
+            .media
+            .iter()
+            .find(|m| m.media_type == "video")
+            .ok_or("No video media description")?;
+        let (_, fmtp) = H264Fmtp::negotiate(video, &H264_PROFILES)?;
+
+        let profile = match fmtp.profile {
+            H264Profile::Main => H264EncoderProfile::Main,
+            H264Profile::High => H264EncoderProfile::High,
+            _ => H264EncoderProfile::ConstrainedBaseline,
+        };
+        let mode = PacketizationMode::from_fmtp(fmtp.packetization_mode)
+            .ok_or("Unsupported H.264 packetization mode")?;
+        let max_nal_size =
+            (mode == PacketizationMode::SingleNalUnit).then_some(SINGLE_NAL_MAX_SIZE);
+
+        Ok((
+            H264EncoderSettings {
+                profile,
+                max_nal_size,
+            },
+            mode,
+        ))
+    }
+
+    /// Payload types and codecs of `VIDEO_CODECS` available on this system
+    fn offered_video_codecs(&self) -> Vec<(u8, VideoCodec)> {
+        VIDEO_CODECS
+            .into_iter()
+            .filter(|(_, codec)| self.video_codecs.contains(codec))
+            .collect()
+    }
+
+    fn video_media(&self) -> MediaDescription {
+        let offered = self.offered_video_codecs();
+        // RTX is only offered for the codecs that are
+        let rtx_payload_types: Vec<(u8, u8)> = RTX_PAYLOAD_TYPES
+            .into_iter()
+            .filter(|(_, primary)| offered.iter().any(|(pt, _)| pt == primary))
+            .collect();
+
+        let mut attributes: Vec<Attribute> = offered
+            .iter()
+            .map(|(payload_type, codec)| Attribute {
+                name: "rtpmap".to_string(),
+                value: Some(format!("{} {}/90000", payload_type, codec.encoding_name())),
+            })
+            .collect();
+        if let Some((payload_type, _)) =
+            offered.iter().find(|(_, codec)| *codec == VideoCodec::H264)
+        {
+            attributes.push(Attribute {
+                name: "fmtp".to_string(),
+                value: Some(format!("{} {}", payload_type, LOCAL_H264_FMTP.to_params())),
+            });
+        }
+        // RFC 4588: one retransmission payload type per codec
+        for (rtx_payload_type, primary_payload_type) in &rtx_payload_types {
+            attributes.push(Attribute {
+                name: "rtpmap".to_string(),
+                value: Some(format!("{} rtx/90000", rtx_payload_type)),
+            });
+            attributes.push(Attribute {
+                name: "fmtp".to_string(),
+                value: Some(format!("{} apt={}", rtx_payload_type, primary_payload_type)),
+            });
+        }
+        attributes.push(Attribute {
+            name: "rtcp-mux".to_string(),
+            value: None,
+        });
+
+        // RFC 8853: one a=rid per layer plus the a=simulcast send list
+        if self.simulcast_rids.len() > 1 {
+            attributes.extend(self.simulcast_rids.iter().map(|rid| Attribute {
+                name: "rid".to_string(),
+                value: Some(format!("{} send", rid)),
+            }));
+            attributes.push(Attribute {
+                name: "simulcast".to_string(),
+                value: Some(format!("send {}", self.simulcast_rids.join(";"))),
+            });
+        }
+
+        MediaDescription {
+            media_type: "video".to_string(),
+            port: 9,
+            protocol: "UDP/TLS/RTP/SAVPF".to_string(),
+            formats: offered
+                .iter()
+                .map(|(payload_type, _)| *payload_type)
+                .chain(
+                    rtx_payload_types
+                        .iter()
+                        .map(|(rtx_payload_type, _)| *rtx_payload_type),
+                )
+                .map(|payload_type| payload_type.to_string())
+                .collect(),
+            connection: None,
+            bandwidths: Vec::new(),
+            attributes,
+        }
+    }
+
+    /// The m-line of each video section, in order
+    ///
+    /// Simulcast is only advertised on the first (camera) m-line.
+    fn video_sections_media(&self) -> Vec<MediaDescription> {
+        self.video_sections
+            .iter()
+            .enumerate()
+            .map(|(index, section)| {
+                let mut media = self.video_media();
+                if index > 0 {
+                    media
+                        .attributes
+                        .retain(|attr| attr.name != "rid" && attr.name != "simulcast");
+                }
+                if section.stopped {
+                    media.port = 0;
+                }
+                media.attributes.insert(
+                    0,
+                    Attribute {
+                        name: "mid".to_string(),
+                        value: Some(section.mid.clone()),
+                    },
+                );
+                media.attributes.push(Attribute {
+                    name: section.direction.as_str().to_string(),
+                    value: None,
+                });
+                media
+            })
+            .collect()
+    }
+
+    fn build_sdp(
+        &mut self,
+        sdp_type: SdpType,
+        ice_agent: &IceAgent,
+    ) -> Result<String, Box<dyn Error>> {
         let media = MediaDescription {
             media_type: "application".to_string(),
             port: 9,
             protocol: "DTLS/SCTP".to_string(),
             formats: vec!["webrtc-datachannel".to_string()],
             connection: None,
-            attributes: Vec::new(),
+            bandwidths: Vec::new(),
+            attributes: vec![Attribute {
+                name: "mid".to_string(),
+                value: Some(APPLICATION_MID.to_string()),
+            }],
         };
 
         let candidate_ip = ice_agent
@@ -75,10 +486,14 @@ impl SdpHandler {
             .map(|c| c.address.to_string());
 
         let mut builder = SessionDescription::builder(sdp_type)
-            .origin(self.make_origin(candidate_ip.as_deref()))
+            .origin(self.next_origin(candidate_ip.as_deref()))
             .session_name("Rust WebRTC")
             .timing(Timing::default())
-            .add_media(media)
+            .add_media(media);
+        for video in self.video_sections_media() {
+            builder = builder.add_media(video);
+        }
+        builder = builder
             .add_attribute(Attribute {
                 name: "ice-ufrag".to_string(),
                 value: Some(ice_agent.ufrag.clone()),
@@ -86,7 +501,11 @@ impl SdpHandler {
             .add_attribute(Attribute {
                 name: "ice-pwd".to_string(),
                 value: Some(ice_agent.pwd.clone()),
-            });
+            })
+            .ice_options(&["trickle"]);
+        for extmap in &self.rtp_extensions {
+            builder = builder.rtp_extension(extmap.id, &extmap.uri);
+        }
 
         for c in &ice_agent.local_candidates {
             builder = builder.add_attribute(Attribute {
@@ -94,6 +513,8 @@ impl SdpHandler {
                 value: Some(format!("{}", c)),
             });
         }
+        // Gathering finished before the description was built
+        builder = builder.end_of_candidates();
 
         self.logger.info(&format!(
             "SDP built with {} ICE candidates (host + srflx + relay)",
@@ -104,6 +525,23 @@ impl SdpHandler {
         Ok(sdp.to_string())
     }
 
+    /// Origin for the next description
+    ///
+    /// The first description gets a new session id at version 1; every later
+    /// one (a renegotiation) keeps the id and increments the version
+    /// (RFC 3264 Section 8).
+    fn next_origin(&mut self, local_candidate_ip: Option<&str>) -> Origin {
+        let origin = match self.origin.take() {
+            Some(mut origin) => {
+                origin.session_version += 1;
+                origin
+            }
+            None => self.make_origin(local_candidate_ip),
+        };
+        self.origin = Some(origin.clone());
+        origin
+    }
+
     fn make_origin(&self, local_candidate_ip: Option<&str>) -> Origin {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -126,3 +564,159 @@ impl SdpHandler {
         }
     }
 }
+
+/// Direction attribute of a media description (sendrecv when absent)
+fn media_direction(media: &MediaDescription) -> Direction {
+    media
+        .attributes
+        .iter()
+        .find_map(|attr| Direction::from_attribute(&attr.name))
+        .unwrap_or(Direction::SendRecv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::LogLevel;
+
+    fn create_test_logger() -> Logger {
+        let log_path = std::env::temp_dir().join("test_sdp_handler.log");
+        Logger::new(log_path, LogLevel::Debug).unwrap()
+    }
+
+    /// Remote offer listing H.264 before VP8, as browsers do
+    fn remote_offer() -> String {
+        "v=0\r\n\
+         o=- 1 1 IN IP4 127.0.0.1\r\n\
+         s=-\r\n\
+         t=0 0\r\n\
+         m=video 9 UDP/TLS/RTP/SAVPF 102 96\r\n\
+         a=rtpmap:102 H264/90000\r\n\
+         a=fmtp:102 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f\r\n\
+         a=rtpmap:96 VP8/90000\r\n"
+            .to_string()
+    }
+
+    #[test]
+    fn test_h264_unavailable_falls_back_to_vp8() {
+        let mut handler = SdpHandler::new(create_test_logger());
+        handler.set_video_codecs(vec![VideoCodec::VP8, VideoCodec::VP9]);
+
+        let offer = handler.create_offer(&IceAgent::new()).unwrap();
+        assert!(!offer.contains("H264"));
+        assert!(!offer.contains("apt=96"));
+        assert!(
+            offer
+                .lines()
+                .any(|line| line.starts_with("m=video") && line.ends_with(" 97 98 100 101"))
+        );
+
+        assert_eq!(
+            handler.negotiate_video_codec(SdpType::Offer, &remote_offer()),
+            Some(VideoCodec::VP8)
+        );
+    }
+
+    #[test]
+    fn test_h264_preferred_when_available() {
+        let mut handler = SdpHandler::new(create_test_logger());
+        handler.set_video_codecs(VideoCodec::ALL.to_vec());
+
+        let offer = handler.create_offer(&IceAgent::new()).unwrap();
+        assert!(offer.contains("a=rtpmap:96 H264/90000"));
+        assert!(offer.contains("a=fmtp:96 level-asymmetry-allowed=1;packetization-mode=1"));
+
+        assert_eq!(
+            handler.negotiate_video_codec(SdpType::Offer, &remote_offer()),
+            Some(VideoCodec::H264)
+        );
+    }
+
+    #[test]
+    fn test_rtp_extensions_follow_remote_offer() {
+        let mut offerer = SdpHandler::new(create_test_logger());
+        let offer = offerer.create_offer(&IceAgent::new()).unwrap();
+        assert!(offer.contains(&format!("a=extmap:5 {}", TRANSPORT_CC_URI)));
+
+        // The answerer keeps the offered id and drops what it does not implement
+        let offer_with_extmaps = remote_offer()
+            + "a=extmap:2 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\n"
+            + &format!("a=extmap:3 {}\r\n", TRANSPORT_CC_URI);
+        let mut answerer = SdpHandler::new(create_test_logger());
+        let agreed = answerer.negotiate_rtp_extensions(SdpType::Offer, &offer_with_extmaps);
+        assert_eq!(agreed, vec![Extmap::new(3, TRANSPORT_CC_URI)]);
+        let answer = answerer.create_answer(&IceAgent::new()).unwrap();
+        assert!(answer.contains(&format!("a=extmap:3 {}", TRANSPORT_CC_URI)));
+        assert!(!answer.contains("ssrc-audio-level"));
+
+        assert_eq!(
+            offerer.negotiate_rtp_extensions(SdpType::Answer, &answer),
+            vec![Extmap::new(3, TRANSPORT_CC_URI)]
+        );
+        // A peer without a=extmap lines agrees on nothing
+        assert!(
+            offerer
+                .negotiate_rtp_extensions(SdpType::Answer, &remote_offer())
+                .is_empty()
+        );
+    }
+
+    /// Session id and version of the `o=` line
+    fn origin_id_and_version(sdp: &str) -> (String, String) {
+        let origin = sdp.lines().find(|line| line.starts_with("o=")).unwrap();
+        let fields: Vec<&str> = origin.split_whitespace().collect();
+        (fields[1].to_string(), fields[2].to_string())
+    }
+
+    #[test]
+    fn test_renegotiation_adds_video_mline_and_bumps_version() {
+        let mut offerer = SdpHandler::new(create_test_logger());
+        let first = offerer.create_offer(&IceAgent::new()).unwrap();
+        assert_eq!(first.matches("m=video").count(), 1);
+
+        let mid = offerer.add_video_section(Direction::SendOnly);
+        let second = offerer.create_offer(&IceAgent::new()).unwrap();
+        let (first_id, first_version) = origin_id_and_version(&first);
+        let (second_id, second_version) = origin_id_and_version(&second);
+        assert_eq!(first_id, second_id);
+        assert_eq!(first_version, "1");
+        assert_eq!(second_version, "2");
+        assert_eq!(second.matches("m=video").count(), 2);
+        assert!(second.contains(&format!("a=mid:{}", mid)));
+
+        // The answer mirrors the new m-line, receiving what is only sent
+        let mut answerer = SdpHandler::new(create_test_logger());
+        let sections = answerer
+            .apply_remote_video_sections(SdpType::Offer, &second)
+            .unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[1].mid, mid);
+        assert_eq!(sections[1].direction, Direction::RecvOnly);
+        let answer = answerer.create_answer(&IceAgent::new()).unwrap();
+        assert_eq!(answer.matches("m=video").count(), 2);
+        assert!(answer.contains("a=recvonly"));
+
+        let agreed = offerer
+            .apply_remote_video_sections(SdpType::Answer, &answer)
+            .unwrap();
+        assert_eq!(agreed[0].direction, Direction::SendRecv);
+        assert_eq!(agreed[1].direction, Direction::SendOnly);
+    }
+
+    #[test]
+    fn test_stopped_video_section_is_rejected_with_port_zero() {
+        let mut offerer = SdpHandler::new(create_test_logger());
+        let mid = offerer.add_video_section(Direction::SendOnly);
+        offerer.stop_video_section(&mid).unwrap();
+        let offer = offerer.create_offer(&IceAgent::new()).unwrap();
+        assert!(offer.contains("m=video 0 "));
+
+        let mut answerer = SdpHandler::new(create_test_logger());
+        let sections = answerer
+            .apply_remote_video_sections(SdpType::Offer, &offer)
+            .unwrap();
+        assert!(!sections[0].stopped);
+        assert!(sections[1].stopped);
+        assert!(offerer.stop_video_section("9").is_err());
+    }
+}
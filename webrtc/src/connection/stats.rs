@@ -0,0 +1,302 @@
+//! Aggregated connection statistics
+//!
+//! Collects the counters spread over `PacketStats`, `RtcpStats` and
+//! `JitterBufferStats`, plus the selected ICE candidate pair, into a single
+//! `getStats`-style snapshot for the UI.
+
+use ice::CandidatePair;
+use network::{JitterBufferStats, PacketStats, RtcpStats};
+use std::time::Instant;
+
+/// Minimum window over which the send bitrate is measured
+const BITRATE_WINDOW_SECS: f64 = 1.0;
+
+/// Time constant of the bandwidth estimate smoothing
+const BANDWIDTH_SMOOTHING_SECS: f64 = 2.0;
+
+/// Snapshot of the statistics of a `WebRtcConnection`
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionStats {
+    /// RTP bytes sent
+    pub bytes_sent: u64,
+    /// RTP packets sent
+    pub packets_sent: u32,
+    /// RTP bytes received
+    pub bytes_received: u64,
+    /// RTP packets received (audio and video)
+    pub packets_received: u64,
+    /// Current send bitrate (bits per second)
+    pub send_bitrate_bps: f64,
+    /// Smoothed estimate of the bitrate the path can carry (bits per second)
+    pub estimated_bandwidth_bps: f64,
+    /// Round-trip time from the SR/RR exchange (milliseconds)
+    pub rtt_ms: Option<f64>,
+    /// Jitter estimated by the video jitter buffer (milliseconds)
+    pub jitter_ms: f64,
+    /// Current playout delay of the video jitter buffer (milliseconds)
+    pub playout_delay_ms: f64,
+    /// Received packets lost (gaps in sequence)
+    pub packets_lost: u64,
+    /// Loss rate of received packets (0.0 - 1.0)
+    pub loss_rate: f64,
+    /// Loss rate of our stream as reported by the peer (0.0 - 1.0)
+    pub remote_loss_rate: f64,
+    /// Candidate pair media flows on, once ICE selected one
+    pub selected_pair: Option<CandidatePair>,
+}
+
+impl ConnectionStats {
+    /// Builds a snapshot from the underlying statistics
+    ///
+    /// # Arguments
+    /// * `packets` - Loss and reordering counters of the receive path
+    /// * `rtcp` - RTCP counters, if the secure transport is up
+    /// * `jitter` - Video jitter buffer statistics
+    /// * `selected_pair` - Selected ICE candidate pair
+    /// * `send_bitrate_bps` - Send bitrate measured by a [`BitrateMeter`]
+    pub fn collect(
+        packets: &PacketStats,
+        rtcp: Option<&RtcpStats>,
+        jitter: &JitterBufferStats,
+        selected_pair: Option<CandidatePair>,
+        send_bitrate_bps: f64,
+    ) -> Self {
+        let mut stats = Self {
+            packets_received: packets.packets_received,
+            packets_lost: packets.packets_lost,
+            loss_rate: packets.loss_rate,
+            jitter_ms: jitter.jitter_ms,
+            playout_delay_ms: jitter.playout_delay_ms,
+            send_bitrate_bps,
+            selected_pair,
+            ..Self::default()
+        };
+
+        if let Some(rtcp) = rtcp {
+            stats.bytes_sent = rtcp.bytes_sent;
+            stats.packets_sent = rtcp.packets_sent;
+            stats.bytes_received = rtcp.bytes_received;
+            stats.rtt_ms = rtcp.calculate_rtt().map(|rtt_secs| rtt_secs * 1000.0);
+            stats.remote_loss_rate = rtcp.remote_fraction_lost as f64 / 256.0;
+        }
+
+        stats
+    }
+}
+
+/// Measures a bitrate from a growing byte counter
+#[derive(Debug, Clone)]
+pub struct BitrateMeter {
+    last_bytes: u64,
+    last_update: Instant,
+    bitrate_bps: f64,
+}
+
+impl BitrateMeter {
+    pub fn new() -> Self {
+        Self {
+            last_bytes: 0,
+            last_update: Instant::now(),
+            bitrate_bps: 0.0,
+        }
+    }
+
+    /// Updates the measurement with the current byte counter
+    ///
+    /// The rate is recomputed once at least a second has passed since the
+    /// previous measurement; in between, the last measured rate is returned.
+    pub fn update(&mut self, total_bytes: u64, now: Instant) -> f64 {
+        let elapsed = now
+            .saturating_duration_since(self.last_update)
+            .as_secs_f64();
+
+        if elapsed >= BITRATE_WINDOW_SECS {
+            let bytes_delta = total_bytes.saturating_sub(self.last_bytes);
+            self.bitrate_bps = bytes_delta as f64 * 8.0 / elapsed;
+            self.last_bytes = total_bytes;
+            self.last_update = now;
+        }
+
+        self.bitrate_bps
+    }
+}
+
+impl Default for BitrateMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Estimates the available send bandwidth from RTCP feedback
+///
+/// Each sample is the rate that reached the peer: the paced send bitrate
+/// minus the share the peer reports lost. When the path is saturated that
+/// is its capacity; otherwise it is a lower bound. Samples are smoothed with
+/// a time-based EWMA so the estimate does not jump with every report.
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthEstimator {
+    estimate_bps: f64,
+    last_update: Option<Instant>,
+}
+
+impl BandwidthEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current estimate in bits per second
+    pub fn estimate_bps(&self) -> f64 {
+        self.estimate_bps
+    }
+
+    /// Adds a sample and returns the updated estimate
+    ///
+    /// # Arguments
+    /// * `send_bitrate_bps` - Measured send bitrate
+    /// * `remote_loss_rate` - Loss of our stream reported by the peer (0.0 - 1.0)
+    /// * `now` - Time of the sample
+    pub fn update(&mut self, send_bitrate_bps: f64, remote_loss_rate: f64, now: Instant) -> f64 {
+        let sample = send_bitrate_bps * (1.0 - remote_loss_rate.clamp(0.0, 1.0));
+
+        self.estimate_bps = match self.last_update {
+            None => sample,
+            Some(last) => {
+                let elapsed = now.saturating_duration_since(last).as_secs_f64();
+                let weight = 1.0 - (-elapsed / BANDWIDTH_SMOOTHING_SECS).exp();
+                self.estimate_bps + weight * (sample - self.estimate_bps)
+            }
+        };
+        self.last_update = Some(now);
+
+        self.estimate_bps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use network::codec::rtp::RtpHeader;
+    use network::{JitterBuffer, PacketHandler, RtpPacket};
+    use std::time::{Duration, SystemTime};
+
+    fn video_packet(seq: u16) -> RtpPacket {
+        let header = RtpHeader {
+            version: 2,
+            padding: false,
+            extension: false,
+            marker: true,
+            payload_type: 96,
+            sequence_number: seq,
+            timestamp: seq as u32 * 3000,
+            ssrc: 1234,
+            csrc: Vec::new(),
+            extensions: Vec::new(),
+        };
+        RtpPacket::new(header, vec![0u8; 100])
+    }
+
+    #[test]
+    fn test_stats_reflect_underlying_counters() {
+        let mut packet_handler = PacketHandler::new();
+        let mut jitter_buffer = JitterBuffer::new();
+        let mut rtcp = RtcpStats::new(42);
+
+        // Sequence 3 is lost
+        for seq in [1u16, 2, 4, 5] {
+            let packet = video_packet(seq);
+            packet_handler.process_packet(seq);
+            rtcp.update_receiver(112, seq, packet.header.timestamp, SystemTime::now());
+            jitter_buffer.push(packet);
+        }
+        for seq in 0..10 {
+            rtcp.update_sender(1000, seq * 3000);
+        }
+        rtcp.remote_fraction_lost = 64;
+
+        let stats = ConnectionStats::collect(
+            packet_handler.stats(),
+            Some(&rtcp),
+            jitter_buffer.stats(),
+            None,
+            8000.0,
+        );
+
+        assert_eq!(
+            stats.packets_received,
+            packet_handler.stats().packets_received
+        );
+        assert_eq!(stats.packets_received, 4);
+        assert_eq!(stats.packets_lost, 1);
+        assert_eq!(stats.loss_rate, packet_handler.stats().loss_rate);
+        assert_eq!(stats.packets_sent, 10);
+        assert_eq!(stats.bytes_sent, 10_000);
+        assert_eq!(stats.bytes_received, 4 * 112);
+        assert_eq!(stats.jitter_ms, jitter_buffer.stats().jitter_ms);
+        assert_eq!(stats.remote_loss_rate, 0.25);
+        assert_eq!(stats.send_bitrate_bps, 8000.0);
+        assert!(stats.rtt_ms.is_none());
+        assert!(stats.selected_pair.is_none());
+    }
+
+    #[test]
+    fn test_stats_without_transport() {
+        let stats = ConnectionStats::collect(
+            &PacketStats::default(),
+            None,
+            &JitterBufferStats::default(),
+            None,
+            0.0,
+        );
+
+        assert_eq!(stats.packets_sent, 0);
+        assert_eq!(stats.bytes_received, 0);
+        assert!(stats.rtt_ms.is_none());
+    }
+
+    #[test]
+    fn test_bitrate_meter_keeps_last_rate_within_window() {
+        let start = Instant::now();
+        let mut meter = BitrateMeter {
+            last_bytes: 0,
+            last_update: start,
+            bitrate_bps: 0.0,
+        };
+
+        assert_eq!(meter.update(1000, start + Duration::from_millis(500)), 0.0);
+        assert_eq!(
+            meter.update(125_000, start + Duration::from_secs(1)),
+            1_000_000.0
+        );
+        // Still within the next window: previous rate is kept
+        assert_eq!(
+            meter.update(200_000, start + Duration::from_millis(1500)),
+            1_000_000.0
+        );
+    }
+
+    #[test]
+    fn test_bandwidth_estimate_converges_to_path_capacity() {
+        let start = Instant::now();
+        let mut estimator = BandwidthEstimator::new();
+
+        // Sending 1 Mbps on a clean path
+        for second in 0..5 {
+            estimator.update(1_000_000.0, 0.0, start + Duration::from_secs(second));
+        }
+        assert_eq!(estimator.estimate_bps(), 1_000_000.0);
+
+        // Sending 2 Mbps into a 1.2 Mbps bottleneck: the peer reports 40% loss
+        let capacity = 1_200_000.0;
+        let loss = 1.0 - capacity / 2_000_000.0;
+        let first = estimator.update(2_000_000.0, loss, start + Duration::from_secs(5));
+        assert!(first > 1_000_000.0 && first < 1_100_000.0, "{first}");
+
+        let mut previous = first;
+        for second in 6..30 {
+            let estimate = estimator.update(2_000_000.0, loss, start + Duration::from_secs(second));
+            assert!(estimate >= previous && estimate <= capacity);
+            previous = estimate;
+        }
+        assert!((capacity - previous) / capacity < 0.01, "{previous}");
+    }
+}
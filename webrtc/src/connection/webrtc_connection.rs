@@ -5,19 +5,34 @@
 use super::audio::AudioHandler;
 use super::camera::CameraHandler;
 use super::ice::IceHandler;
+use super::loopback::EchoPeer;
 use super::sdp::SdpHandler;
+use super::stats::{BandwidthEstimator, BitrateMeter, ConnectionStats};
 use crate::audio_info::AudioInfo;
 use crate::audio_manager::AudioSettings;
 use crate::camera_info::CameraInfo;
 use crate::camera_manager::CameraResolution;
-use crate::session::{ControlMessage, P2PConfig, SecureP2PSession};
+use crate::session::{
+    ChatMessage, ControlMessage, FileTransferConfig, P2PConfig, SecureP2PSession, SimulcastConfig,
+};
+use ice::ConnectionState;
 use logging::Logger;
+use media::VideoCodec;
+use network::DscpConfig;
+use std::collections::HashMap;
 use std::error::Error;
 use std::net::SocketAddr;
+use std::time::{Duration, Instant};
 
 /// Type alias for RGB frame data: (width, height, pixel_data)
 pub type RgbFrame = (usize, usize, Vec<u8>);
 
+/// Receives decoded remote video frames in headless mode
+pub type VideoFrameCallback = Box<dyn FnMut(RgbFrame) + Send>;
+
+/// Receives decoded remote audio frames in headless mode
+pub type AudioFrameCallback = Box<dyn FnMut(media::AudioFrame) + Send>;
+
 /// Main WebRTC connection with DTLS/SRTP encryption
 pub struct WebRtcConnection {
     ice_handler: IceHandler,
@@ -30,6 +45,24 @@ pub struct WebRtcConnection {
     connection_started: bool,
     is_offerer: bool,
     file_channel_ready_emitted: bool,
+    /// Video bitrate limit from the remote answer's b= lines
+    remote_max_bitrate: Option<u32>,
+    /// Video codec negotiated from the remote rtpmap lines
+    video_codec: VideoCodec,
+    /// Send bitrate measurement for `get_stats`
+    bitrate_meter: BitrateMeter,
+    /// Available bandwidth estimate for `get_stats`
+    bandwidth_estimator: BandwidthEstimator,
+    /// Level (0.0 to 1.0) of the last captured microphone frame
+    audio_level: f32,
+    /// Media is pushed and delivered programmatically, without devices
+    headless: bool,
+    video_callback: Option<VideoFrameCallback>,
+    audio_callback: Option<AudioFrameCallback>,
+    /// Local peer echoing media back while in loopback mode
+    loopback: Option<EchoPeer>,
+    /// SSRC of each extra video track, by the `a=mid` of its m-line
+    video_tracks: HashMap<String, u32>,
 }
 
 impl WebRtcConnection {
@@ -57,9 +90,17 @@ impl WebRtcConnection {
 
         logger.info(&format!("DTLS initialized, fingerprint: {}", fingerprint));
 
+        // Until negotiation picks one, assume the preferred codec this system has
+        let sdp_handler = SdpHandler::new(logger.clone());
+        let video_codec = sdp_handler
+            .video_codecs()
+            .first()
+            .copied()
+            .unwrap_or(VideoCodec::H264);
+
         Ok(Self {
             ice_handler: IceHandler::new(logger.clone()),
-            sdp_handler: SdpHandler::new(logger.clone()),
+            sdp_handler,
             camera_handler: CameraHandler::new(logger.clone()),
             audio_handler: AudioHandler::new(logger.clone()),
             media_session,
@@ -68,6 +109,16 @@ impl WebRtcConnection {
             connection_started: false,
             is_offerer: false,
             file_channel_ready_emitted: false,
+            remote_max_bitrate: None,
+            video_codec,
+            bitrate_meter: BitrateMeter::new(),
+            bandwidth_estimator: BandwidthEstimator::new(),
+            audio_level: 0.0,
+            headless: false,
+            video_callback: None,
+            audio_callback: None,
+            loopback: None,
+            video_tracks: HashMap::new(),
         })
     }
 
@@ -77,11 +128,18 @@ impl WebRtcConnection {
 
     pub fn create_offer_from_new(logger: Logger) -> Result<(Self, String), Box<dyn Error>> {
         logger.info("Creating new secure WebRTC connection for OFFERER");
-        let mut conn = Self::new(None, logger.clone())?;
+        let conn = Self::new(None, logger)?;
+        Self::create_offer_from(conn)
+    }
+
+    /// Makes `conn` the OFFERER and generates its offer
+    ///
+    /// Used with a connection created in advance, e.g. one warmed up with `pregather`.
+    pub fn create_offer_from(mut conn: Self) -> Result<(Self, String), Box<dyn Error>> {
         conn.is_offerer = true;
 
         let offer = conn.create_offer()?;
-        logger.info("OFFER generated with DTLS fingerprint");
+        conn.logger.info("OFFER generated with DTLS fingerprint");
         Ok((conn, offer))
     }
 
@@ -90,7 +148,18 @@ impl WebRtcConnection {
         logger: Logger,
     ) -> Result<(Self, String), Box<dyn Error>> {
         logger.info("Creating new secure WebRTC connection for ANSWERER");
-        let mut conn = Self::new(None, logger.clone())?;
+        let conn = Self::new(None, logger)?;
+        Self::create_answer_from(conn, offer_sdp)
+    }
+
+    /// Makes `conn` the ANSWERER of `offer_sdp` and generates its answer
+    ///
+    /// Used with a connection created in advance, e.g. one warmed up with `pregather`.
+    pub fn create_answer_from(
+        mut conn: Self,
+        offer_sdp: &str,
+    ) -> Result<(Self, String), Box<dyn Error>> {
+        let logger = conn.logger.clone();
         conn.is_offerer = false;
 
         logger.info("Processing remote OFFER with DTLS fingerprint...");
@@ -106,31 +175,7 @@ impl WebRtcConnection {
         self.logger
             .info("Creating SDP offer with DTLS fingerprint and ICE candidates");
 
-        let port = self.session_config.local_port();
-        self.logger
-            .info(&format!("Gathering ICE candidates on port {}...", port));
-        self.ice_handler.gather_candidates(port)?;
-
-        self.logger.info(&format!(
-            "Total ICE candidates gathered: {}",
-            self.ice_handler.ice_agent.local_candidates.len()
-        ));
-
-        for (i, candidate) in self
-            .ice_handler
-            .ice_agent
-            .local_candidates
-            .iter()
-            .enumerate()
-        {
-            self.logger.info(&format!(
-                "  Candidate {}: {}:{} (type: {:?})",
-                i + 1,
-                candidate.address,
-                candidate.port,
-                candidate.candidate_type
-            ));
-        }
+        self.gather_local_candidates()?;
 
         let fingerprint = self
             .media_session
@@ -148,6 +193,32 @@ impl WebRtcConnection {
         self.logger
             .info("Creating SDP answer with DTLS fingerprint and ICE candidates");
 
+        self.gather_local_candidates()?;
+
+        let fingerprint = self
+            .media_session
+            .get_local_fingerprint()
+            .ok_or("DTLS not initialized")?;
+
+        let sdp = self
+            .sdp_handler
+            .create_answer(&self.ice_handler.ice_agent)?;
+        let sdp = SdpHandler::add_fingerprint_to_sdp(sdp, fingerprint, false);
+
+        self.logger.info("SDP answer created with DTLS fingerprint");
+        Ok(sdp)
+    }
+
+    /// Gathers the local ICE candidates put in the offer/answer
+    ///
+    /// A renegotiation reuses the candidates of the established connection.
+    fn gather_local_candidates(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.connection_started {
+            self.logger
+                .info("Renegotiating: keeping the gathered ICE candidates");
+            return Ok(());
+        }
+
         let port = self.session_config.local_port();
         self.logger
             .info(&format!("Gathering ICE candidates on port {}...", port));
@@ -173,19 +244,7 @@ impl WebRtcConnection {
                 candidate.candidate_type
             ));
         }
-
-        let fingerprint = self
-            .media_session
-            .get_local_fingerprint()
-            .ok_or("DTLS not initialized")?;
-
-        let sdp = self
-            .sdp_handler
-            .create_answer(&self.ice_handler.ice_agent)?;
-        let sdp = SdpHandler::add_fingerprint_to_sdp(sdp, fingerprint, false);
-
-        self.logger.info("SDP answer created with DTLS fingerprint");
-        Ok(sdp)
+        Ok(())
     }
 
     pub fn set_remote_sdp(&mut self, sdp: &str) -> Result<(), Box<dyn Error>> {
@@ -222,10 +281,296 @@ impl WebRtcConnection {
             sdp_type
         ));
 
+        if self.connection_started {
+            return self.apply_renegotiation(sdp_type, sdp);
+        }
+
         self.process_remote_candidates(sdp)?;
+        if let Ok(session) = sdp::SessionDescription::parse(sdp_type.clone(), sdp) {
+            self.ice_handler.apply_remote_ice_options(&session);
+        }
         self.extract_and_set_fingerprint(sdp)?;
         self.extract_and_apply_remote_endpoint(sdp)?;
 
+        if sdp_type == sdp::SdpType::Answer {
+            self.apply_remote_bandwidth(sdp)?;
+        }
+
+        let extensions = self
+            .sdp_handler
+            .negotiate_rtp_extensions(sdp_type.clone(), sdp);
+        let transport_cc_id = sdp::Extmap::id_of(&extensions, sdp::extmap::TRANSPORT_CC_URI);
+        if transport_cc_id.is_none() {
+            self.logger
+                .warn("Remote peer did not agree to transport-cc, congestion feedback disabled");
+        }
+        self.media_session
+            .set_transport_cc_extension_id(transport_cc_id);
+        self.sdp_handler
+            .apply_remote_video_sections(sdp_type.clone(), sdp)?;
+
+        if let Some(codec) = self
+            .sdp_handler
+            .negotiate_video_codec(sdp_type.clone(), sdp)
+        {
+            self.logger.info(&format!(
+                "Negotiated video codec: {}",
+                codec.encoding_name()
+            ));
+            self.video_codec = codec;
+        }
+        self.media_session.set_video_codec(self.video_codec)?;
+
+        if self.video_codec == VideoCodec::H264 {
+            match SdpHandler::negotiate_h264(sdp_type, sdp) {
+                Ok((settings, mode)) => {
+                    self.logger.info(&format!(
+                        "Negotiated H.264 {:?}, packetization {:?}",
+                        settings.profile, mode
+                    ));
+                    self.media_session.configure_h264(settings, mode)?;
+                }
+                Err(e) => self
+                    .logger
+                    .warn(&format!("Keeping default H.264 parameters: {}", e)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies a new offer/answer to an established connection
+    ///
+    /// ICE, DTLS and the SRTP keys are kept; only the video m-lines change.
+    /// Tracks whose m-line the remote rejected are stopped.
+    fn apply_renegotiation(
+        &mut self,
+        sdp_type: sdp::SdpType,
+        sdp: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let fingerprint = SdpHandler::extract_fingerprint_from_sdp(sdp);
+        if fingerprint.as_deref() != self.media_session.get_remote_fingerprint() {
+            return Err("Renegotiation cannot change the DTLS fingerprint".into());
+        }
+
+        let sections = self
+            .sdp_handler
+            .apply_remote_video_sections(sdp_type.clone(), sdp)?;
+        for section in sections.iter().filter(|section| section.stopped) {
+            if let Some(ssrc) = self.video_tracks.remove(&section.mid) {
+                self.media_session.remove_video_track(ssrc)?;
+            }
+        }
+
+        self.logger.info(&format!(
+            "Renegotiated {:?}: {} video m-line(s), {} extra track(s)",
+            sdp_type,
+            sections.len(),
+            self.video_tracks.len()
+        ));
+        Ok(())
+    }
+
+    /// Adds an outgoing video track on a new m-line
+    ///
+    /// The track is advertised in the next offer, so `create_offer` must be
+    /// called afterwards to renegotiate. Frames are sent with `push_track_frame`.
+    ///
+    /// # Returns
+    /// The `a=mid` identifying the track
+    pub fn add_video_track(&mut self, width: u32, height: u32) -> Result<String, Box<dyn Error>> {
+        let bitrate = self
+            .session_config
+            .clone()
+            .with_resolution(width, height)
+            .calculate_optimal_bitrate();
+        let ssrc = self.media_session.add_video_track(width, height, bitrate)?;
+        let mid = self.sdp_handler.add_video_section(sdp::Direction::SendOnly);
+        self.logger.info(&format!(
+            "Added video track {} (SSRC {}), renegotiation needed",
+            mid, ssrc
+        ));
+        self.video_tracks.insert(mid.clone(), ssrc);
+        Ok(mid)
+    }
+
+    /// Stops the video track `mid` and rejects its m-line in the next offer
+    pub fn remove_video_track(&mut self, mid: &str) -> Result<(), Box<dyn Error>> {
+        self.sdp_handler.stop_video_section(mid)?;
+        if let Some(ssrc) = self.video_tracks.remove(mid) {
+            self.media_session.remove_video_track(ssrc)?;
+        }
+        Ok(())
+    }
+
+    /// Changes the direction of the video m-line `mid` for the next offer
+    pub fn set_video_direction(
+        &mut self,
+        mid: &str,
+        direction: sdp::Direction,
+    ) -> Result<(), Box<dyn Error>> {
+        self.sdp_handler.set_video_direction(mid, direction)?;
+        Ok(())
+    }
+
+    /// Sends a frame on the extra video track `mid`
+    ///
+    /// Frames are dropped while the negotiated direction does not allow sending.
+    pub fn push_track_frame(&mut self, mid: &str, frame: RgbFrame) -> Result<(), Box<dyn Error>> {
+        let ssrc = *self
+            .video_tracks
+            .get(mid)
+            .ok_or_else(|| format!("No video track with mid {}", mid))?;
+        let sending = self
+            .sdp_handler
+            .video_sections()
+            .iter()
+            .any(|section| section.mid == mid && section.direction.can_send());
+        if !sending {
+            return Ok(());
+        }
+
+        let (width, height, rgb_data) = frame;
+        let frame = media::rgb_to_frame(width, height, &rgb_data)?;
+        self.media_session.send_track_frame(ssrc, &frame)?;
+        Ok(())
+    }
+
+    /// Sends video as several simulcast layers, each on its own SSRC
+    ///
+    /// Must be called before creating the offer/answer so the layers are
+    /// advertised with `a=rid` and `a=simulcast`.
+    pub fn enable_simulcast(&mut self, config: SimulcastConfig) -> Result<(), Box<dyn Error>> {
+        self.media_session.enable_simulcast(&config)?;
+        let rids = if config.is_enabled() {
+            config.rids()
+        } else {
+            Vec::new()
+        };
+        self.sdp_handler.set_simulcast_rids(rids);
+        Ok(())
+    }
+
+    /// Switches the connection to headless mode (no camera, microphone or speakers)
+    ///
+    /// Media is supplied with `push_video_frame`/`push_audio_frame`, and
+    /// received media goes to the callbacks set with `on_video_frame` and
+    /// `on_audio_frame` when `deliver_received_media` is called. Must be
+    /// called before `establish_connection` so no playback device is opened.
+    pub fn enable_headless(&mut self) {
+        self.headless = true;
+    }
+
+    /// Returns whether the connection runs without media devices
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Sets the callback receiving decoded remote video frames in headless mode
+    pub fn on_video_frame(&mut self, callback: impl FnMut(RgbFrame) + Send + 'static) {
+        self.video_callback = Some(Box::new(callback));
+    }
+
+    /// Sets the callback receiving decoded remote audio frames in headless mode
+    pub fn on_audio_frame(&mut self, callback: impl FnMut(media::AudioFrame) + Send + 'static) {
+        self.audio_callback = Some(Box::new(callback));
+    }
+
+    /// Connects to a local echo peer instead of a remote participant
+    ///
+    /// A headless connection on localhost decodes everything this
+    /// connection sends and sends it back, so captured media goes through
+    /// encoding, RTP, SRTP and decoding twice and comes back as remote
+    /// media. Used to test the camera and microphone without a second
+    /// participant. Replaces the offer/answer exchange and
+    /// `establish_connection`.
+    ///
+    /// # Errors
+    /// If the connection is already connected, or the echo peer cannot be
+    /// created or connected
+    pub fn start_loopback(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.connection_started || self.loopback.is_some() {
+            return Err("Loopback needs a connection that is not connected yet".into());
+        }
+        self.logger
+            .info("Starting loopback through a local echo peer");
+
+        let mut echo = WebRtcConnection::new(Some(self.local_port() + 1), self.logger.clone())?;
+        echo.enable_headless();
+
+        self.is_offerer = true;
+        let offer = self.create_offer()?;
+        echo.set_remote_offer(&offer)?;
+        let answer = echo.create_answer()?;
+        self.set_remote_answer(&answer)?;
+
+        // Both sides of the DTLS handshake block until it completes
+        let mut peer = EchoPeer::start(echo, self.logger.clone());
+        if let Err(e) = self.establish_connection() {
+            peer.stop();
+            return Err(format!("Loopback connection failed: {}", e).into());
+        }
+
+        self.loopback = Some(peer);
+        self.logger.info("Loopback active");
+        Ok(())
+    }
+
+    /// Returns whether the connection is looped back to a local echo peer
+    pub fn is_loopback(&self) -> bool {
+        self.loopback.is_some()
+    }
+
+    /// Returns whether the remote peer accepts trickled ICE candidates
+    pub fn remote_supports_trickle(&self) -> bool {
+        self.ice_handler.remote_supports_trickle()
+    }
+
+    /// Returns the video codec negotiated with the remote peer
+    pub fn video_codec(&self) -> VideoCodec {
+        self.video_codec
+    }
+
+    /// Clamps the video encoder bitrate to the b= limits of a remote answer
+    ///
+    /// All media share one transport, so the lowest limit of any media
+    /// section applies (TIAS preferred over AS within a section).
+    fn apply_remote_bandwidth(&mut self, sdp: &str) -> Result<(), Box<dyn Error>> {
+        let session = match sdp::SessionDescription::parse(sdp::SdpType::Answer, sdp) {
+            Ok(session) => session,
+            Err(e) => {
+                self.logger
+                    .warn(&format!("Could not parse answer for b= lines: {}", e));
+                return Ok(());
+            }
+        };
+
+        let Some(limit) = session
+            .media
+            .iter()
+            .filter_map(|media| media.max_bitrate())
+            .min()
+        else {
+            return Ok(());
+        };
+
+        let limit = limit.min(u32::MAX as u64) as u32;
+        self.remote_max_bitrate = Some(limit);
+        self.logger.info(&format!(
+            "Remote answer limits bitrate to {:.2} Mbps",
+            limit as f64 / 1_000_000.0
+        ));
+
+        if self.session_config.codec_bitrate() > limit {
+            let (width, height) = (
+                self.session_config.frame_width(),
+                self.session_config.frame_height(),
+            );
+            self.session_config = self.session_config.clone().with_bitrate(limit);
+            self.media_session
+                .update_encoder_resolution(width, height, limit)
+                .map_err(|e| format!("Failed to apply remote bandwidth limit: {}", e))?;
+        }
         Ok(())
     }
 
@@ -308,6 +653,20 @@ impl WebRtcConnection {
         self.ice_handler.set_turn_servers(servers);
     }
 
+    /// Gathers ICE candidates before a call starts
+    ///
+    /// The next offer/answer reuses them while they are fresh, and gathers
+    /// again transparently once they went stale.
+    pub fn pregather(&mut self) -> Result<(), Box<dyn Error>> {
+        let port = self.session_config.local_port();
+        self.ice_handler.pregather(port)
+    }
+
+    /// Age of the pregathered ICE candidates, `None` if `pregather` was not called
+    pub fn pregather_age(&self) -> Option<Duration> {
+        self.ice_handler.pregather_age()
+    }
+
     pub fn add_ice_candidate(
         &mut self,
         candidate: &str,
@@ -332,7 +691,10 @@ impl WebRtcConnection {
             self.ice_handler.ice_agent.remote_candidates.len()
         ));
 
-        let remote_addr = self.determine_remote_address()?;
+        let remote_addr = match self.run_ice_checks()? {
+            Some(addr) => addr,
+            None => self.determine_remote_address()?,
+        };
         let is_server = !self.is_offerer;
 
         self.logger.info(&format!(
@@ -356,8 +718,18 @@ impl WebRtcConnection {
         self.start_media_threads()?;
         self.verify_security()?;
 
+        if let Err(e) = self.ice_handler.start_consent_checks(remote_addr) {
+            self.logger.warn(&format!(
+                "ICE consent freshness not started: {}. Connection state will not be tracked.",
+                e
+            ));
+        }
+
         // Auto-start audio playback so users can hear remote audio immediately
-        if let Err(e) = self.audio_handler.start_playback(48000, 2) {
+        if self.headless {
+            self.logger
+                .info("Headless connection: remote audio goes to the audio callback");
+        } else if let Err(e) = self.audio_handler.start_playback(48000, 2) {
             self.logger.warn(&format!(
                 "Failed to auto-start audio playback: {}. Audio reception may not work.",
                 e
@@ -373,6 +745,18 @@ impl WebRtcConnection {
         Ok(())
     }
 
+    /// Runs ICE connectivity checks from the media port, bounded by the
+    /// checking timeout and cancelled by [`close`](Self::close)
+    ///
+    /// # Returns
+    /// The remote address of the selected pair, or None to use the signalled one
+    fn run_ice_checks(&mut self) -> Result<Option<SocketAddr>, Box<dyn Error>> {
+        let socket = self.media_session.clone_media_socket()?;
+        self.ice_handler
+            .run_connectivity_checks(socket, self.is_offerer)
+            .map_err(|e| format!("ICE connectivity checks failed: {}", e).into())
+    }
+
     fn determine_remote_address(&self) -> Result<SocketAddr, Box<dyn Error>> {
         let (remote_ip, remote_port) = self.ice_handler.get_remote_address().unwrap_or_else(|| {
             self.logger
@@ -434,6 +818,11 @@ impl WebRtcConnection {
         self.audio_handler.is_audio_running()
     }
 
+    /// Level (0.0 to 1.0) of the last microphone frame sent
+    pub fn audio_level(&self) -> f32 {
+        self.audio_level
+    }
+
     pub fn start_camera(&mut self, camera_index: i32, fps: f64) -> Result<(), Box<dyn Error>> {
         let (resolution, should_send_message) =
             self.camera_handler.start_camera(camera_index, fps)?;
@@ -464,6 +853,33 @@ impl WebRtcConnection {
             .map_err(|e| format!("Failed to send CameraOn message: {}", e).into())
     }
 
+    /// Switches the running camera to another device without renegotiating
+    ///
+    /// The video keeps its SSRC and encoder (recreated only if the new
+    /// device captures at another resolution), and the next frame is sent as
+    /// a keyframe so the remote decoder resyncs right away.
+    pub fn switch_camera(&mut self, device_id: i32) -> Result<(), Box<dyn Error>> {
+        let resolution = self.camera_handler.switch_camera(device_id)?;
+
+        // The screen share stays the video source; the camera resumes when it stops
+        if self.camera_handler.is_screen_sharing() {
+            return Ok(());
+        }
+
+        if resolution.width != self.session_config.frame_width()
+            || resolution.height != self.session_config.frame_height()
+        {
+            self.apply_camera_resolution(resolution)?;
+        }
+        self.media_session.request_keyframe();
+        Ok(())
+    }
+
+    /// Returns the device ID of the running camera
+    pub fn active_camera_device(&self) -> Option<i32> {
+        self.camera_handler.active_camera_device()
+    }
+
     pub fn stop_camera(&mut self) {
         self.camera_handler.stop_camera();
 
@@ -484,6 +900,41 @@ impl WebRtcConnection {
         self.camera_handler.capture_frame()
     }
 
+    // ===== Screen share Methods =====
+
+    pub fn is_screen_sharing(&self) -> bool {
+        self.camera_handler.is_screen_sharing()
+    }
+
+    /// Starts sending a display instead of the camera
+    pub fn start_screen_share(&mut self, display_id: i32, fps: f64) -> Result<(), Box<dyn Error>> {
+        let resolution = self.camera_handler.start_screen_share(display_id, fps)?;
+        self.apply_camera_resolution(resolution)?;
+
+        if self.connection_started && !self.camera_handler.is_camera_running() {
+            self.send_camera_on_message()?;
+        }
+
+        Ok(())
+    }
+
+    /// Stops the screen share, falling back to the camera if it is running
+    pub fn stop_screen_share(&mut self) {
+        self.camera_handler.stop_screen_share();
+
+        if self.connection_started && !self.camera_handler.is_camera_running() {
+            self.logger
+                .info("Sending CameraOff control message to peer");
+            if let Err(e) = self
+                .media_session
+                .send_control_message(ControlMessage::CameraOff)
+            {
+                self.logger
+                    .error(&format!("Failed to send CameraOff message: {}", e));
+            }
+        }
+    }
+
     // ===== Audio Methods =====
 
     pub fn discover_audio_devices(&mut self) -> Result<Vec<AudioInfo>, Box<dyn Error>> {
@@ -643,6 +1094,7 @@ impl WebRtcConnection {
 
     pub fn capture_and_send(&mut self) -> Result<RgbFrame, Box<dyn Error>> {
         let frame = self.camera_handler.capture_frame()?;
+        self.follow_source_resolution(&frame)?;
         let (width, height, rgb_data) = media::frame_to_rgb(&frame)?;
         let rgb_frame = (width, height, rgb_data);
 
@@ -653,11 +1105,60 @@ impl WebRtcConnection {
     /// Captures an audio frame and sends it through the WebRTC session
     pub fn capture_audio_and_send(&mut self) -> Result<(), Box<dyn Error>> {
         let audio_frame = self.audio_handler.capture_frame()?;
+        self.audio_level = media::AudioDetection::level(&audio_frame);
         self.media_session
             .send_audio_frame(audio_frame)
             .map_err(|e| format!("Failed to send audio frame: {}", e).into())
     }
 
+    /// Sends a video frame supplied by the caller instead of the camera
+    ///
+    /// The encoder follows the frame resolution, as with a camera switch.
+    pub fn push_video_frame(&mut self, frame: RgbFrame) -> Result<(), Box<dyn Error>> {
+        let (width, height, rgb_data) = frame;
+        let frame = media::rgb_to_frame(width, height, &rgb_data)?;
+        self.follow_source_resolution(&frame)?;
+        self.send_frame(frame)
+    }
+
+    /// Sends an audio frame supplied by the caller instead of the microphone
+    pub fn push_audio_frame(&mut self, frame: media::AudioFrame) -> Result<(), Box<dyn Error>> {
+        self.audio_level = media::AudioDetection::level(&frame);
+        self.media_session
+            .send_audio_frame(frame)
+            .map_err(|e| format!("Failed to send audio frame: {}", e).into())
+    }
+
+    /// Hands all decoded remote frames to the headless callbacks
+    ///
+    /// Frames of a kind without a callback are dropped.
+    ///
+    /// # Returns
+    /// Number of frames delivered
+    pub fn deliver_received_media(&mut self) -> Result<usize, Box<dyn Error>> {
+        let mut delivered = 0;
+
+        while let Some(frame) = self.receive_frame()? {
+            if let Some(callback) = self.video_callback.as_mut() {
+                callback(frame);
+                delivered += 1;
+            }
+        }
+
+        loop {
+            match self.media_session.receive_audio_frame() {
+                Ok(Some(frame)) => {
+                    if let Some(callback) = self.audio_callback.as_mut() {
+                        callback(frame);
+                        delivered += 1;
+                    }
+                }
+                Ok(None) => return Ok(delivered),
+                Err(e) => return Err(format!("Failed to receive audio: {}", e).into()),
+            }
+        }
+    }
+
     pub fn receive_frame(&self) -> Result<Option<RgbFrame>, Box<dyn Error>> {
         match self.media_session.receive_frame() {
             Ok(Some(frame)) => {
@@ -669,6 +1170,23 @@ impl WebRtcConnection {
         }
     }
 
+    /// Returns the SSRCs of the remote video streams received besides the main one
+    pub fn remote_video_ssrcs(&self) -> Vec<u32> {
+        self.media_session.remote_video_ssrcs()
+    }
+
+    /// Receives a decoded frame of the secondary remote video stream `ssrc`
+    pub fn receive_stream_frame(&self, ssrc: u32) -> Result<Option<RgbFrame>, Box<dyn Error>> {
+        match self.media_session.receive_stream_frame(ssrc) {
+            Ok(Some(frame)) => {
+                let (width, height, rgb_data) = media::frame_to_rgb(&frame)?;
+                Ok(Some((width, height, rgb_data)))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Failed to receive frame of stream {}: {}", ssrc, e).into()),
+        }
+    }
+
     /// Receives all available decoded audio frames and plays them on output device
     pub fn receive_audio(&mut self) -> Result<(), Box<dyn Error>> {
         loop {
@@ -739,6 +1257,53 @@ impl WebRtcConnection {
         self.media_session.get_rtcp_stats()
     }
 
+    /// Returns a snapshot of all connection statistics
+    ///
+    /// Aggregates packet, RTCP and jitter buffer counters with the selected
+    /// ICE candidate pair. The send bitrate is measured between calls.
+    pub fn get_stats(&mut self) -> ConnectionStats {
+        let rtcp_stats = self.get_rtcp_stats();
+        let bytes_sent = rtcp_stats.as_ref().map_or(0, |stats| stats.bytes_sent);
+        let now = Instant::now();
+        let send_bitrate_bps = self.bitrate_meter.update(bytes_sent, now);
+
+        let mut stats = ConnectionStats::collect(
+            &self.get_packet_stats(),
+            rtcp_stats.as_ref(),
+            &self.get_jitter_stats(),
+            self.ice_handler.ice_agent.selected_pair().cloned(),
+            send_bitrate_bps,
+        );
+        stats.estimated_bandwidth_bps =
+            self.bandwidth_estimator
+                .update(send_bitrate_bps, stats.remote_loss_rate, now);
+        stats
+    }
+
+    /// Returns the next ICE connection state change, if any
+    ///
+    /// Also runs consent freshness on the media path, so it should be called
+    /// regularly once the connection is established.
+    pub fn poll_ice_state(&mut self) -> Option<ConnectionState> {
+        if self.connection_started {
+            let received = self.media_session.take_stun_packets();
+            for (packet, to) in self.ice_handler.process_consent(received, Instant::now()) {
+                if let Err(e) = self.media_session.send_stun(&packet, to) {
+                    self.logger
+                        .debug(&format!("Failed to send ICE consent packet: {}", e));
+                }
+            }
+            if let Some(remote_addr) = self.ice_handler.poll_selected_pair_change() {
+                self.logger.info(&format!(
+                    "ICE selected pair changed, sending media to {}",
+                    remote_addr
+                ));
+                self.media_session.set_remote_addr(remote_addr);
+            }
+        }
+        self.ice_handler.poll_state_change()
+    }
+
     pub fn clear_video_buffers(&self) {
         self.media_session.clear_video_buffers();
     }
@@ -830,6 +1395,52 @@ impl WebRtcConnection {
             .map_err(|e| e.into())
     }
 
+    /// Sends a text message on the chat data channel
+    ///
+    /// The text must be non-empty and at most `MAX_CHAT_MESSAGE_BYTES` long.
+    pub fn send_chat(&self, text: &str) -> Result<(), Box<dyn Error>> {
+        if !self.is_connected() {
+            return Err("Cannot send chat message: Connection not established".into());
+        }
+
+        let message = ChatMessage::text(text)?;
+        self.media_session.send_chat(&message).map_err(|e| e.into())
+    }
+
+    /// Polls for received chat messages
+    pub fn poll_chat_message(&self) -> Option<ChatMessage> {
+        self.media_session.poll_chat_message()
+    }
+
+    /// Starts recording the call to a Matroska (.mkv) file
+    ///
+    /// Records the remote H.264 video and Opus audio, plus the local camera
+    /// and microphone when `include_local` is set. Recording can start and
+    /// stop at any point of the call; video tracks begin at the next keyframe.
+    pub fn start_recording(
+        &self,
+        path: &std::path::Path,
+        include_local: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if !self.is_connected() {
+            return Err("Cannot start recording: Connection not established".into());
+        }
+
+        self.media_session
+            .start_recording(path, include_local)
+            .map_err(|e| e.into())
+    }
+
+    /// Stops the recording, flushing and finalizing its file
+    pub fn stop_recording(&self) -> Result<(), Box<dyn Error>> {
+        self.media_session.stop_recording().map_err(|e| e.into())
+    }
+
+    /// Returns whether the call is being recorded
+    pub fn is_recording(&self) -> bool {
+        self.media_session.is_recording()
+    }
+
     /// Polls for file transfer events
     /// Returns the next file transfer event if available
     pub fn poll_file_event(&self) -> Option<crate::session::file_transfer::FileTransferEvent> {
@@ -845,7 +1456,10 @@ impl WebRtcConnection {
             resolution.width, resolution.height, resolution.fps
         ));
 
-        let bitrate = self.calculate_bitrate(resolution.width, resolution.height);
+        let mut bitrate = self.calculate_bitrate(resolution.width, resolution.height);
+        if let Some(limit) = self.remote_max_bitrate {
+            bitrate = bitrate.min(limit);
+        }
         self.update_session_config(resolution, bitrate);
         self.update_encoder(resolution, bitrate)?;
 
@@ -856,6 +1470,35 @@ impl WebRtcConnection {
         Ok(())
     }
 
+    /// Reinitializes the encoder when the source resolution changes mid-stream
+    ///
+    /// Happens when a shared display is resized or when switching between
+    /// the screen and the camera.
+    fn follow_source_resolution(
+        &mut self,
+        frame: &media::VideoFrame,
+    ) -> Result<(), Box<dyn Error>> {
+        let (width, height) = (frame.width() as u32, frame.height() as u32);
+        if width == self.session_config.frame_width()
+            && height == self.session_config.frame_height()
+        {
+            return Ok(());
+        }
+
+        self.logger.info(&format!(
+            "Video source resolution changed: {}x{} -> {}x{}",
+            self.session_config.frame_width(),
+            self.session_config.frame_height(),
+            width,
+            height
+        ));
+        self.apply_camera_resolution(CameraResolution {
+            width,
+            height,
+            fps: self.session_config.fps(),
+        })
+    }
+
     fn calculate_bitrate(&self, width: u32, height: u32) -> u32 {
         let pixels = (width * height) as f64;
         let base_pixels = 1280.0 * 720.0;
@@ -966,6 +1609,20 @@ impl WebRtcConnection {
         }
     }
 
+    /// Set file transfer settings, e.g. a rate limit so transfers don't starve media
+    ///
+    /// Must be called before the connection is established.
+    pub fn set_file_transfer_config(&mut self, config: FileTransferConfig) {
+        self.media_session.set_file_transfer_config(config);
+    }
+
+    /// Mark outgoing audio and video with the DSCP values in `config`
+    ///
+    /// Use `DscpConfig::recommended()` for EF audio and AF41 video.
+    pub fn set_dscp_config(&mut self, config: DscpConfig) {
+        self.media_session.set_dscp_config(config);
+    }
+
     /// Check if file channel is ready and emit event if not yet emitted
     ///
     /// This method should be called periodically (e.g., from poll_sctp in receive_thread)
@@ -1000,15 +1657,335 @@ impl Drop for WebRtcConnection {
 impl WebRtcConnection {
     /// Properly close the WebRTC connection and release all resources
     pub fn close(&mut self) {
+        self.ice_handler.cancel_checks();
+
         self.logger.info("[CLEANUP] Stopping camera...");
         self.stop_camera();
         self.stop_audio();
 
         self.logger.info("[CLEANUP] Closing media session...");
         self.media_session.close();
+        if let Some(mut peer) = self.loopback.take() {
+            peer.stop();
+        }
 
         self.logger.info("[CLEANUP] WebRTC connection closed");
         self.connection_started = false;
         self.file_channel_ready_emitted = false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera_manager::CameraManager;
+    use logging::LogLevel;
+    use media::VideoSource;
+    use opencv::core::{CV_8UC3, Mat, Scalar};
+    use std::time::Duration;
+
+    fn create_test_logger() -> Logger {
+        let log_path = std::env::temp_dir().join("test_webrtc_connection.log");
+        Logger::new(log_path, LogLevel::Debug).unwrap()
+    }
+
+    /// Camera producing black frames
+    struct MockCamera {
+        width: u32,
+        height: u32,
+    }
+
+    impl VideoSource for MockCamera {
+        fn capture_frame(&mut self) -> media::error::Result<media::VideoFrame> {
+            let mat = Mat::new_rows_cols_with_default(
+                self.height as i32,
+                self.width as i32,
+                CV_8UC3,
+                Scalar::all(0.0),
+            )?;
+            Ok(media::VideoFrame::new(mat))
+        }
+
+        fn resolution(&self) -> (u32, u32) {
+            (self.width, self.height)
+        }
+
+        fn fps(&self) -> f64 {
+            30.0
+        }
+    }
+
+    fn mock_device_available(device_id: i32) -> bool {
+        (0..2).contains(&device_id)
+    }
+
+    /// Opens a 640x480 camera for even device IDs and a 1280x720 one for odd IDs
+    fn open_mock_camera(
+        device_id: i32,
+        _fps: f64,
+        _logger: Logger,
+    ) -> Result<Box<dyn VideoSource + Send>, Box<dyn Error>> {
+        let (width, height) = if device_id % 2 == 0 {
+            (640, 480)
+        } else {
+            (1280, 720)
+        };
+        Ok(Box::new(MockCamera { width, height }))
+    }
+
+    #[test]
+    fn test_switch_camera_keeps_ssrc_and_forces_keyframe() {
+        let logger = create_test_logger();
+        let mut conn = WebRtcConnection::new(None, logger.clone()).unwrap();
+        conn.camera_handler = CameraHandler::with_camera_manager(
+            CameraManager::with_devices(logger.clone(), mock_device_available, open_mock_camera),
+            logger,
+        );
+
+        conn.start_camera(0, 30.0).unwrap();
+        conn.capture_frame().unwrap();
+        let ssrc = conn.media_session.video_ssrc();
+        assert!(!conn.media_session.is_keyframe_requested());
+
+        conn.switch_camera(1).unwrap();
+
+        assert_eq!(conn.active_camera_device(), Some(1));
+        assert_eq!(conn.media_session.video_ssrc(), ssrc);
+        assert!(conn.media_session.is_keyframe_requested());
+        assert_eq!(conn.session_config.frame_width(), 1280);
+        assert_eq!(conn.capture_frame().unwrap().width(), 1280);
+
+        // Switching back keeps the same stream too
+        conn.switch_camera(0).unwrap();
+        assert_eq!(conn.active_camera_device(), Some(0));
+        assert_eq!(conn.media_session.video_ssrc(), ssrc);
+    }
+
+    #[test]
+    fn test_switch_camera_rejects_unknown_device() {
+        let logger = create_test_logger();
+        let mut conn = WebRtcConnection::new(None, logger.clone()).unwrap();
+        conn.camera_handler = CameraHandler::with_camera_manager(
+            CameraManager::with_devices(logger.clone(), mock_device_available, open_mock_camera),
+            logger,
+        );
+
+        // Nothing to switch before the camera is started
+        assert!(conn.switch_camera(1).is_err());
+
+        conn.start_camera(0, 30.0).unwrap();
+        assert!(conn.switch_camera(7).is_err());
+        assert_eq!(conn.active_camera_device(), Some(0));
+        assert!(!conn.media_session.is_keyframe_requested());
+    }
+
+    #[test]
+    fn test_offer_uses_fresh_pregathered_candidates() {
+        let mut conn = WebRtcConnection::new(Some(48200), create_test_logger()).unwrap();
+        conn.set_stun_servers(Vec::new());
+        conn.set_turn_servers(Vec::new());
+        assert!(conn.pregather_age().is_none());
+
+        conn.pregather().unwrap();
+        let cached = conn.ice_handler.ice_agent.get_local_candidates_sdp();
+        assert!(!cached.is_empty());
+        let age = conn.pregather_age().unwrap();
+
+        let (conn, offer) = WebRtcConnection::create_offer_from(conn).unwrap();
+        for candidate in &cached {
+            assert!(
+                offer.contains(candidate.as_str()),
+                "{} not offered",
+                candidate
+            );
+        }
+        assert_eq!(
+            conn.ice_handler.ice_agent.get_local_candidates_sdp(),
+            cached
+        );
+        // Not gathered again: the cache kept aging
+        assert!(conn.pregather_age().unwrap() >= age);
+    }
+
+    #[test]
+    fn test_headless_loopback_delivers_pushed_frame() {
+        let logger = create_test_logger();
+        let mut offerer = WebRtcConnection::new(Some(47000), logger.clone()).unwrap();
+        offerer.is_offerer = true;
+        offerer.enable_headless();
+        let offer = offerer.create_offer().unwrap();
+
+        let mut answerer = WebRtcConnection::new(Some(47200), logger).unwrap();
+        answerer.enable_headless();
+        answerer.set_remote_offer(&offer).unwrap();
+        let answer = answerer.create_answer().unwrap();
+        offerer.set_remote_answer(&answer).unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        answerer.on_video_frame(move |frame| {
+            let _ = tx.send(frame);
+        });
+
+        // Both sides of the DTLS handshake block until it completes
+        let handshake = std::thread::spawn(move || {
+            answerer
+                .establish_connection()
+                .map(|_| answerer)
+                .map_err(|e| e.to_string())
+        });
+        offerer.establish_connection().unwrap();
+        let mut answerer = handshake.join().unwrap().unwrap();
+
+        // The decoder may need a few frames before it outputs one
+        let pixel = [240u8, 120, 30];
+        let rgb = pixel.repeat(320 * 240);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let (width, height, data) = loop {
+            offerer.push_video_frame((320, 240, rgb.clone())).unwrap();
+            answerer.deliver_received_media().unwrap();
+            if let Ok(frame) = rx.try_recv() {
+                break frame;
+            }
+            assert!(Instant::now() < deadline, "No frame decoded over loopback");
+            std::thread::sleep(Duration::from_millis(33));
+        };
+
+        assert_eq!((width, height), (320, 240));
+        // Lossy codec: compare the average of each colour channel
+        for (channel, &expected) in pixel.iter().enumerate() {
+            let sum: u64 = data
+                .iter()
+                .skip(channel)
+                .step_by(3)
+                .map(|&v| v as u64)
+                .sum();
+            let mean = sum / (width * height) as u64;
+            assert!(
+                mean.abs_diff(expected as u64) < 16,
+                "Channel {} mean {} expected {}",
+                channel,
+                mean,
+                expected
+            );
+        }
+
+        offerer.close();
+        answerer.close();
+    }
+
+    #[test]
+    fn test_renegotiation_adds_track_without_interrupting_main_video() {
+        let logger = create_test_logger();
+        let mut offerer = WebRtcConnection::new(Some(47600), logger.clone()).unwrap();
+        offerer.is_offerer = true;
+        offerer.enable_headless();
+        let offer = offerer.create_offer().unwrap();
+
+        let mut answerer = WebRtcConnection::new(Some(47800), logger).unwrap();
+        answerer.enable_headless();
+        answerer.set_remote_offer(&offer).unwrap();
+        let answer = answerer.create_answer().unwrap();
+        offerer.set_remote_answer(&answer).unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        answerer.on_video_frame(move |frame| {
+            let _ = tx.send(frame);
+        });
+
+        let handshake = std::thread::spawn(move || {
+            answerer
+                .establish_connection()
+                .map(|_| answerer)
+                .map_err(|e| e.to_string())
+        });
+        offerer.establish_connection().unwrap();
+        let mut answerer = handshake.join().unwrap().unwrap();
+
+        // Renegotiate a second video m-line over the established transport
+        let local_port = offerer.local_port();
+        let mid = offerer.add_video_track(160, 120).unwrap();
+        let reoffer = offerer.create_offer().unwrap();
+        let origin = |sdp: &str| -> Vec<String> {
+            let line = sdp.lines().find(|line| line.starts_with("o=")).unwrap();
+            line.split_whitespace().map(str::to_string).collect()
+        };
+        assert_eq!(origin(&reoffer)[1], origin(&offer)[1]);
+        assert_eq!(origin(&reoffer)[2], "2");
+        assert_eq!(reoffer.matches("m=video").count(), 2);
+
+        answerer.set_remote_offer(&reoffer).unwrap();
+        let reanswer = answerer.create_answer().unwrap();
+        offerer.set_remote_answer(&reanswer).unwrap();
+        assert!(offerer.is_connected() && answerer.is_connected());
+        assert_eq!(offerer.local_port(), local_port);
+
+        let main = [240u8, 120, 30].repeat(320 * 240);
+        let track = [30u8, 200, 90].repeat(160 * 120);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut main_frames = 0;
+        let mut track_frame = None;
+        while main_frames < 3 || track_frame.is_none() {
+            offerer.push_video_frame((320, 240, main.clone())).unwrap();
+            offerer
+                .push_track_frame(&mid, (160, 120, track.clone()))
+                .unwrap();
+            answerer.deliver_received_media().unwrap();
+            main_frames += rx.try_iter().count();
+            for ssrc in answerer.remote_video_ssrcs() {
+                if let Some(frame) = answerer.receive_stream_frame(ssrc).unwrap() {
+                    track_frame = Some(frame);
+                }
+            }
+            assert!(
+                Instant::now() < deadline,
+                "main frames {}, track frame {}",
+                main_frames,
+                track_frame.is_some()
+            );
+            std::thread::sleep(Duration::from_millis(33));
+        }
+
+        let (width, height, _) = track_frame.unwrap();
+        assert_eq!((width, height), (160, 120));
+
+        offerer.close();
+        answerer.close();
+    }
+
+    #[test]
+    fn test_loopback_echoes_pushed_frame() {
+        let logger = create_test_logger();
+        let mut conn = WebRtcConnection::new(Some(47400), logger).unwrap();
+        conn.enable_headless();
+        let (tx, rx) = std::sync::mpsc::channel();
+        conn.on_video_frame(move |frame| {
+            let _ = tx.send(frame);
+        });
+
+        conn.start_loopback().unwrap();
+        assert!(conn.is_loopback());
+        assert!(conn.start_loopback().is_err());
+
+        let pixel = [30u8, 200, 90];
+        let rgb = pixel.repeat(320 * 240);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let (width, height, data) = loop {
+            conn.push_video_frame((320, 240, rgb.clone())).unwrap();
+            conn.deliver_received_media().unwrap();
+            if let Ok(frame) = rx.try_recv() {
+                break frame;
+            }
+            assert!(Instant::now() < deadline, "No frame echoed over loopback");
+            std::thread::sleep(Duration::from_millis(33));
+        };
+
+        assert_eq!((width, height), (320, 240));
+        // Encoded twice by a lossy codec: compare the mean of the green channel
+        let green: u64 = data.iter().skip(1).step_by(3).map(|&v| v as u64).sum();
+        let mean = green / (width * height) as u64;
+        assert!(mean.abs_diff(pixel[1] as u64) < 24, "Green mean {}", mean);
+
+        conn.close();
+        assert!(!conn.is_loopback());
+    }
+}
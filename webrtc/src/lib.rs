@@ -10,6 +10,7 @@
 //! - **`WebRtcConnection`** - Main interface (backward compatible, uses plain UDP)
 //! - **`SecureWebRtcConnection`** - SECURE interface with DTLS/SRTP encryption
 //! - **`RgbFrame`** - Type alias for RGB frame data: `(width, height, pixel_data)`
+//! - **`VideoFrameCallback`** / **`AudioFrameCallback`** - Receivers of remote media in headless mode
 //! - **`CameraInfo`** - Camera device information
 //! - **`CameraManager`** - Camera lifecycle management
 //! - **`CameraResolution`** - Camera resolution information
@@ -18,6 +19,7 @@
 //! - **`AudioSettings`** - Audio configuration information
 //! - **`AudioFrame`** - Audio sample data
 //! - **`ControlMessage`** - Control message types (CameraOn, CameraOff, ParticipantDisconnected)
+//! - **`SimulcastConfig`** - Simulcast layer resolutions and bitrates
 //!
 //! ### ICE/STUN/TURN API (for signaling servers)
 //! - **`IceAgent`** - ICE candidate gathering and management
@@ -78,22 +80,28 @@ pub use audio_info::AudioInfo;
 pub use audio_manager::{AudioManager, AudioSettings};
 pub use camera_info::CameraInfo;
 pub use camera_manager::{CameraManager, CameraResolution};
-pub use connection::{RgbFrame, WebRtcConnection};
-pub use session::{ControlMessage, FileTransferEvent};
+pub use connection::{
+    AudioFrameCallback, ConnectionStats, RgbFrame, VideoFrameCallback, WebRtcConnection,
+};
+pub use media::{CameraCapability, PixelFormat};
+pub use session::{
+    ChatMessage, ControlMessage, FileTransferConfig, FileTransferEvent, MAX_CHAT_MESSAGE_BYTES,
+    SimulcastConfig, SimulcastLayer,
+};
 
 // ===== PUBLIC API - Audio =====
 pub use media::AudioFrame;
 
 // ===== PUBLIC API - Secure Network (DTLS/SRTP) =====
 pub use network::{
-    DtlsContext, JitterBufferStats, PacketStats, RtcpStats, SecureUdpTransport, SrtpContext,
-    SrtpKeys,
+    Dscp, DscpConfig, DtlsContext, JitterBufferStats, PacketStats, RtcpStats, SecureUdpTransport,
+    SrtpContext, SrtpKeys,
 };
 
 // ===== PUBLIC API - ICE =====
 pub use ice::{
     Candidate, CandidateBuilder, CandidatePair, CandidateType, ConnectionState, IceAgent, IceError,
-    detect_local_ip,
+    PairState, detect_local_ip, detect_local_ips,
 };
 
 // ===== PUBLIC API - STUN =====
@@ -104,6 +112,7 @@ pub use turn::{TransportProtocol, TurnAttributeType, TurnClient, TurnError, Turn
 
 // ===== PUBLIC API - SDP =====
 pub use sdp::{
-    Attribute as SdpAttribute, Connection as SdpConnection, MediaDescription, Origin, SdpError,
-    SdpType, SessionDescription, SessionDescriptionBuilder,
+    Attribute as SdpAttribute, Codec as SdpCodec, Connection as SdpConnection, Direction,
+    LocalCapabilities, MediaDescription, Negotiator, Origin, SdpError, SdpType, SessionDescription,
+    SessionDescriptionBuilder, SetupRole,
 };
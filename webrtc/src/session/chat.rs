@@ -0,0 +1,113 @@
+//! In-call text chat over a data channel
+//!
+//! Chat messages travel as small JSON objects on a dedicated reliable,
+//! ordered data channel labeled [`CHAT_CHANNEL_LABEL`], e.g.
+//! `{"type":"Text","text":"hello","timestamp":1700000000000}`.
+
+use json_parser::impl_json_tagged;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Label of the chat data channel
+pub const CHAT_CHANNEL_LABEL: &str = "chat";
+/// Largest chat text accepted, in bytes of UTF-8
+pub const MAX_CHAT_MESSAGE_BYTES: usize = 4096;
+/// Largest serialized chat message accepted (text plus JSON framing)
+const MAX_CHAT_FRAME_BYTES: usize = MAX_CHAT_MESSAGE_BYTES * 2;
+
+/// Messages exchanged on the chat data channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatMessage {
+    /// A text message and when it was sent (milliseconds since the Unix epoch)
+    Text { text: String, timestamp: u64 },
+}
+
+impl_json_tagged! {
+    ChatMessage, tag = "type" {
+        Text { text: String, timestamp: u64 },
+    }
+}
+
+impl ChatMessage {
+    /// Creates a text message stamped with the current time
+    ///
+    /// # Errors
+    /// If `text` is empty or longer than [`MAX_CHAT_MESSAGE_BYTES`]
+    pub fn text(text: &str) -> Result<Self, String> {
+        validate_text(text)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+        Ok(ChatMessage::Text {
+            text: text.to_string(),
+            timestamp,
+        })
+    }
+
+    /// Serialize to the JSON sent on the chat channel
+    pub fn to_json(&self) -> String {
+        json_parser::to_string(self)
+    }
+
+    /// Deserialize a message received on the chat channel
+    ///
+    /// # Errors
+    /// If the data is not UTF-8, not a chat message, or its text is empty
+    /// or too long
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() > MAX_CHAT_FRAME_BYTES {
+            return Err(format!("Chat message too large ({} bytes)", data.len()));
+        }
+        let json = std::str::from_utf8(data).map_err(|_| "Chat message is not valid UTF-8")?;
+        let message: ChatMessage = json_parser::from_str(json).map_err(|e| e.to_string())?;
+        match &message {
+            ChatMessage::Text { text, .. } => validate_text(text)?,
+        }
+        Ok(message)
+    }
+}
+
+fn validate_text(text: &str) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("Chat message is empty".to_string());
+    }
+    if text.len() > MAX_CHAT_MESSAGE_BYTES {
+        return Err(format!(
+            "Chat message too long ({} bytes, max {})",
+            text.len(),
+            MAX_CHAT_MESSAGE_BYTES
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_message_roundtrip() {
+        let message = ChatMessage::Text {
+            text: "héllo 👋".to_string(),
+            timestamp: 1_700_000_000_000,
+        };
+
+        let decoded = ChatMessage::from_bytes(message.to_json().as_bytes()).unwrap();
+
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn test_chat_message_rejects_invalid_text() {
+        assert!(ChatMessage::text("   ").is_err());
+        assert!(ChatMessage::text(&"a".repeat(MAX_CHAT_MESSAGE_BYTES + 1)).is_err());
+        assert!(ChatMessage::text(&"a".repeat(MAX_CHAT_MESSAGE_BYTES)).is_ok());
+    }
+
+    #[test]
+    fn test_chat_message_rejects_malformed_frames() {
+        assert!(ChatMessage::from_bytes(&[0x7B, 0xFF, 0xFE, 0x7D]).is_err());
+        assert!(ChatMessage::from_bytes(br#"{"type":"Unknown"}"#).is_err());
+        assert!(ChatMessage::from_bytes(br#"{"type":"Text","text":"","timestamp":1}"#).is_err());
+    }
+}
@@ -3,6 +3,8 @@
 //! This module defines the configuration structure for P2P sessions,
 //! including video resolution, codec settings, and network parameters.
 
+use super::file_transfer::FileTransferConfig;
+
 /// Configuration for P2P session
 #[derive(Debug, Clone)]
 pub(crate) struct P2PConfig {
@@ -18,6 +20,8 @@ pub(crate) struct P2PConfig {
     local_port: u16,
     /// Remote port
     remote_port: u16,
+    /// File transfer settings
+    file_transfer: FileTransferConfig,
 }
 
 impl P2PConfig {
@@ -46,6 +50,10 @@ impl P2PConfig {
         self.remote_port
     }
 
+    pub(crate) fn file_transfer(&self) -> FileTransferConfig {
+        self.file_transfer
+    }
+
     /// Creates a builder for P2PConfig
     pub(crate) fn builder() -> P2PConfigBuilder {
         P2PConfigBuilder::default()
@@ -95,6 +103,12 @@ impl P2PConfig {
         self.remote_port = port;
         self
     }
+
+    /// Sets the file transfer settings.
+    pub(crate) fn with_file_transfer(mut self, config: FileTransferConfig) -> Self {
+        self.file_transfer = config;
+        self
+    }
 }
 
 impl Default for P2PConfig {
@@ -106,6 +120,7 @@ impl Default for P2PConfig {
             codec_bitrate: 5000000, // 5 Mbps
             local_port: 5004,
             remote_port: 5004,
+            file_transfer: FileTransferConfig::default(),
         }
     }
 }
@@ -50,14 +50,30 @@ pub(super) fn establish_secure_connection(
             NetworkError::TransportError("UDP transport already consumed".to_string())
         })?;
 
+    // The selected candidate pair decides the address family of the media socket
+    if udp_instance
+        .rebind_for(remote_addr)
+        .map_err(|e| NetworkError::TransportError(format!("Failed to rebind UDP: {}", e)))?
+    {
+        logger.info(&format!(
+            "Rebound media socket for {} peer {}",
+            if remote_addr.is_ipv6() {
+                "IPv6"
+            } else {
+                "IPv4"
+            },
+            remote_addr
+        ));
+    }
     udp_instance.set_remote(remote_addr);
 
     // Create temporary SRTP keys (will be replaced after handshake)
     let temp_keys = network::SrtpKeys {
-        local_master_key: [0u8; 16],
+        local_master_key: [0u8; 32],
         local_master_salt: [0u8; 14],
-        remote_master_key: [0u8; 16],
+        remote_master_key: [0u8; 32],
         remote_master_salt: [0u8; 14],
+        cipher_suite: network::SrtpCipherSuite::default(),
     };
 
     let mut secure_transport = SecureUdpTransport::new_from_dtls(udp_instance, temp_keys);
@@ -75,25 +91,25 @@ pub(super) fn establish_secure_connection(
         .set_nonblocking(true)
         .map_err(|e| NetworkError::TransportError(format!("Failed to set non-blocking: {}", e)))?;
 
-    let cert = dtls.get_dimpl_certificate().clone();
-    let mut dtls_engine = DtlsEngine::new(is_server, remote_addr, cert)
-        .map_err(|e| NetworkError::SecurityError(format!("Failed to create DTLS engine: {}", e)))?;
+    let mut dtls_engine = DtlsEngine::from_context(is_server, remote_addr, &dtls).map_err(|e| {
+        NetworkError::SecurityError(format!("Failed to create DTLS engine: {}", e))
+    })?;
 
     logger.info("Starting DTLS handshake using dimpl (Sans-IO)...");
 
-    // Event-driven handshake loop
+    // Event-driven handshake loop (the engine gives up once flight retransmissions run out)
     let start_time = Instant::now();
-    let timeout = Duration::from_secs(10);
+    let handshake_timeout = dtls.handshake_timeout();
     let mut last_timeout_check = Instant::now();
     let mut packets_sent = 0;
     let mut packets_received = 0;
 
     while !dtls_engine.is_connected() {
-        // Check for overall timeout
-        if start_time.elapsed() > timeout {
+        // Check for overall timeout (a server may never hear a ClientHello)
+        if start_time.elapsed() > handshake_timeout {
             logger.error(&format!(
-                "DTLS handshake timeout after 10s - sent: {}, received: {}",
-                packets_sent, packets_received
+                "DTLS handshake timeout after {:?} - sent: {}, received: {}",
+                handshake_timeout, packets_sent, packets_received
             ));
             return Err(NetworkError::SecurityError(
                 "DTLS handshake timeout".to_string(),
@@ -175,9 +191,15 @@ pub(super) fn establish_secure_connection(
             }
         }
 
-        // Handle timeouts (for retransmissions)
+        // Handle timeouts (resends the last flight when the retransmission timer fires)
         if last_timeout_check.elapsed() > Duration::from_millis(100) {
             dtls_engine.handle_timeout(Instant::now()).map_err(|e| {
+                logger.error(&format!(
+                    "DTLS handshake failed - sent: {}, received: {}, retransmits: {}",
+                    packets_sent,
+                    packets_received,
+                    dtls_engine.handshake_retransmits()
+                ));
                 NetworkError::SecurityError(format!("DTLS timeout handling failed: {}", e))
             })?;
             last_timeout_check = Instant::now();
@@ -216,6 +238,16 @@ pub(super) fn establish_secure_connection(
         packets_sent, packets_received
     ));
 
+    // Verify the peer certificate matches the fingerprint from its SDP (MITM protection)
+    let peer_cert = dtls_engine.peer_certificate().ok_or_else(|| {
+        NetworkError::SecurityError("Peer did not present a DTLS certificate".to_string())
+    })?;
+    if let Err(e) = DtlsContext::verify_remote_fingerprint(remote_fingerprint, peer_cert) {
+        logger.error(&format!("Aborting connection: {}", e));
+        return Err(NetworkError::SecurityError(e));
+    }
+    logger.info("Remote DTLS certificate matches SDP fingerprint");
+
     // Extract SRTP keys from completed handshake
     let srtp_keys = dtls_engine.get_srtp_keys().ok_or_else(|| {
         NetworkError::SecurityError("SRTP keys not available after handshake".to_string())
@@ -223,10 +255,9 @@ pub(super) fn establish_secure_connection(
 
     logger.info("SRTP keys extracted from DTLS session");
 
-    // Verify remote fingerprint
-    let actual_fingerprint = dtls_engine.get_fingerprint();
+    let local_fingerprint = dtls_engine.get_fingerprint();
     logger.info(&format!("Remote fingerprint: {}", remote_fingerprint));
-    logger.info(&format!("Local fingerprint: {}", actual_fingerprint));
+    logger.info(&format!("Local fingerprint: {}", local_fingerprint));
 
     // Update transport with real SRTP keys
     secure_transport.update_srtp_keys(srtp_keys.clone());
@@ -241,3 +272,43 @@ pub(super) fn establish_secure_connection(
 
     Ok((dtls, dtls_engine))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::LogLevel;
+    use std::net::UdpSocket;
+
+    fn create_test_logger() -> Logger {
+        let log_path = std::env::temp_dir().join("test_dtls_setup.log");
+        Logger::new(log_path, LogLevel::Debug).unwrap()
+    }
+
+    #[test]
+    fn test_server_without_client_hello_times_out() {
+        // The remote socket stays silent, so the server never sees a ClientHello
+        let silent_peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let remote_addr = silent_peer.local_addr().unwrap();
+
+        let mut dtls = DtlsContext::new().unwrap();
+        dtls.set_handshake_timeout(Duration::from_millis(300));
+        let udp = UdpTransport::new("127.0.0.1:0").unwrap();
+        let udp_transport = Arc::new(Mutex::new(Some(udp)));
+        let transport = Arc::new(Mutex::new(None));
+
+        let start = Instant::now();
+        let result = establish_secure_connection(
+            remote_addr,
+            true,
+            "sha-256 00",
+            dtls,
+            udp_transport,
+            transport.clone(),
+            &create_test_logger(),
+        );
+
+        assert!(matches!(result, Err(NetworkError::SecurityError(_))));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(transport.lock().unwrap().is_none());
+    }
+}
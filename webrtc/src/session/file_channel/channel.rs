@@ -2,13 +2,18 @@
 
 use super::mime::guess_mime_type;
 use crate::session::file_transfer::{
-    FileTransferEvent, FileTransferMessage, FileTransferState, IncomingTransfer, OutgoingTransfer,
+    FileTransferConfig, FileTransferEvent, FileTransferMessage, FileTransferState,
+    IncomingTransfer, OutgoingTransfer,
 };
 use std::collections::HashMap;
 use std::io;
 use std::path::Path;
 
 /// File channel for managing file transfers
+///
+/// Several transfers can be active at once. Data chunks carry their transfer
+/// ID and outgoing transfers take turns on the channel, so files are
+/// interleaved chunk by chunk rather than sent one after another.
 #[derive(Debug)]
 pub struct FileChannel {
     channel_id: u16,
@@ -21,11 +26,22 @@ pub struct FileChannel {
     buffered_amount: usize,
     /// Max buffered amount before pausing (1MB)
     max_buffered_amount: usize,
+    /// Data chunking paused until the data channel reports `BufferedAmountLow`
+    sending_paused: bool,
+    /// Outgoing transfer that sent the last data chunk (round-robin cursor)
+    last_served: Option<u64>,
+    /// Settings applied to new outgoing transfers
+    config: FileTransferConfig,
 }
 
 impl FileChannel {
     /// Create new file channel
     pub fn new(channel_id: u16) -> Self {
+        Self::with_config(channel_id, FileTransferConfig::default())
+    }
+
+    /// Create new file channel with transfer settings
+    pub fn with_config(channel_id: u16, config: FileTransferConfig) -> Self {
         Self {
             channel_id,
             next_transfer_id: rand::random::<u64>() & 0x0000_FFFF_FFFF_FFFF,
@@ -35,6 +51,9 @@ impl FileChannel {
             send_queue: Vec::new(),
             buffered_amount: 0,
             max_buffered_amount: 1024 * 1024, // 1MB - supports adaptive chunking
+            sending_paused: false,
+            last_served: None,
+            config,
         }
     }
 
@@ -43,22 +62,36 @@ impl FileChannel {
         self.channel_id
     }
 
+    /// Send over a different data channel, keeping all transfers
+    pub fn set_channel_id(&mut self, channel_id: u16) {
+        self.channel_id = channel_id;
+    }
+
     /// Notify that bytes were successfully sent (reduces buffered amount)
     pub fn on_bytes_sent(&mut self, bytes: usize) {
         self.buffered_amount = self.buffered_amount.saturating_sub(bytes);
 
         // Adaptive feedback: successful send, increase chunk size
-        for transfer in self.outgoing.values_mut() {
-            if transfer.state == FileTransferState::Transferring {
-                transfer.adapt_chunk_size(true);
-                break; // Only adapt active transfer
-            }
+        if let Some(transfer) = self.last_served.and_then(|id| self.outgoing.get_mut(&id))
+            && transfer.state == FileTransferState::Transferring
+        {
+            transfer.adapt_chunk_size(true);
         }
     }
 
     /// Check if we can send more data (flow control)
     pub fn can_send(&self) -> bool {
-        self.buffered_amount < self.max_buffered_amount
+        !self.sending_paused && self.buffered_amount < self.max_buffered_amount
+    }
+
+    /// Stop reading file chunks (data channel buffer is above its low-water mark)
+    pub fn pause_sending(&mut self) {
+        self.sending_paused = true;
+    }
+
+    /// Resume reading file chunks once the data channel buffer has drained
+    pub fn on_buffered_amount_low(&mut self) {
+        self.sending_paused = false;
     }
 
     /// Start sending a file
@@ -79,7 +112,10 @@ impl FileChannel {
         let id = self.next_transfer_id;
         self.next_transfer_id += 1;
 
-        let transfer = OutgoingTransfer::new(id, path.to_path_buf(), size);
+        let mut transfer = OutgoingTransfer::new(id, path.to_path_buf(), size);
+        if let Some(rate) = self.config.max_bytes_per_second {
+            transfer = transfer.with_max_bytes_per_second(rate);
+        }
         self.outgoing.insert(id, transfer);
 
         self.send_queue.push(FileTransferMessage::Offer {
@@ -140,6 +176,51 @@ impl FileChannel {
         Ok(())
     }
 
+    /// Pause active transfers after the data channel dropped
+    ///
+    /// Nothing buffered on the old channel will arrive, so flow control is
+    /// reset. Incoming transfers continue with [`resume_transfer`](Self::resume_transfer)
+    /// once a channel is open again.
+    pub fn interrupt_transfers(&mut self) {
+        for transfer in self.outgoing.values_mut() {
+            transfer.interrupt();
+        }
+        for transfer in self.incoming.values_mut() {
+            transfer.interrupt();
+        }
+        self.buffered_amount = 0;
+        self.sending_paused = false;
+    }
+
+    /// Ask the sender to continue an interrupted incoming transfer
+    pub fn resume_transfer(&mut self, id: u64) -> Result<(), &'static str> {
+        let transfer = self.incoming.get_mut(&id).ok_or("Transfer not found")?;
+        if transfer.state != FileTransferState::Interrupted {
+            return Err("Transfer not interrupted");
+        }
+        let (offset, hash) = transfer
+            .resume_request()
+            .map_err(|_| "Cannot read partial file")?;
+        self.send_queue
+            .push(FileTransferMessage::ResumeRequest { id, offset, hash });
+        Ok(())
+    }
+
+    /// Resume an incoming transfer from the partial file left at `save_path`
+    ///
+    /// Used when the transfer state was lost with the previous connection;
+    /// `id` and `size` come from the original offer.
+    pub fn resume_partial_transfer(
+        &mut self,
+        id: u64,
+        size: u64,
+        save_path: &Path,
+    ) -> io::Result<()> {
+        let transfer = IncomingTransfer::resume(size, save_path.to_path_buf())?;
+        self.incoming.insert(id, transfer);
+        self.resume_transfer(id).map_err(io::Error::other)
+    }
+
     /// Process incoming data from data channel
     pub fn on_data(&mut self, data: &[u8]) {
         let msg = match FileTransferMessage::from_bytes(data) {
@@ -216,9 +297,72 @@ impl FileChannel {
                 self.incoming.remove(&id);
                 self.events.push(FileTransferEvent::Failed { id, reason });
             }
+            FileTransferMessage::ResumeRequest { id, offset, hash } => {
+                self.on_resume_request(id, offset, hash);
+            }
+            FileTransferMessage::ResumeAck { id, offset, hash } => {
+                self.on_resume_ack(id, offset, hash);
+            }
+        }
+    }
+
+    /// Sender side: continue an outgoing transfer from the receiver's offset
+    fn on_resume_request(&mut self, id: u64, offset: u64, hash: u64) {
+        let Some(transfer) = self.outgoing.get_mut(&id) else {
+            self.send_queue.push(FileTransferMessage::Cancel {
+                id,
+                reason: "Unknown transfer".to_string(),
+            });
+            return;
+        };
+
+        match transfer.resume(offset, hash) {
+            Ok((offset, hash)) => {
+                let total = transfer.total_size;
+                // The queue is popped from the back: the ack goes out first
+                if transfer.state == FileTransferState::Completed {
+                    self.send_queue
+                        .push(FileTransferMessage::Complete { id, checksum: 0 });
+                }
+                self.send_queue
+                    .push(FileTransferMessage::ResumeAck { id, offset, hash });
+                self.events
+                    .push(FileTransferEvent::Resumed { id, offset, total });
+            }
+            Err(e) => self.fail_transfer(id, &e.to_string()),
+        }
+    }
+
+    /// Receiver side: the sender agreed to continue from `offset`
+    fn on_resume_ack(&mut self, id: u64, offset: u64, hash: u64) {
+        let Some(transfer) = self.incoming.get_mut(&id) else {
+            return;
+        };
+
+        match transfer.on_resume_ack(offset, hash) {
+            Ok(()) => {
+                let total = transfer.total_size;
+                self.events
+                    .push(FileTransferEvent::Resumed { id, offset, total });
+            }
+            Err(e) => self.fail_transfer(id, &e.to_string()),
         }
     }
 
+    /// Drop a transfer that cannot continue and tell the peer
+    fn fail_transfer(&mut self, id: u64, reason: &str) {
+        self.outgoing.remove(&id);
+        self.incoming.remove(&id);
+        self.send_queue.push(FileTransferMessage::Cancel {
+            id,
+            reason: reason.to_string(),
+        });
+        self.events.push(FileTransferEvent::Failed {
+            id,
+            reason: reason.to_string(),
+        });
+    }
+
     /// Poll for next message to send
     pub fn poll_send(&mut self) -> Option<Vec<u8>> {
         // First, check send queue for control messages (Complete, etc.)
@@ -251,56 +395,57 @@ impl FileChannel {
         let mut to_remove = Vec::new();
         let mut result = None;
 
-        for transfer in self.outgoing.values_mut() {
-            if transfer.state == FileTransferState::Transferring {
-                match transfer.read_next_chunk() {
-                    Ok(Some(data)) => {
-                        let offset = transfer.bytes_sent - data.len() as u64;
-                        let transfer_id = transfer.id;
-
-                        // Generate progress event
-                        self.events.push(FileTransferEvent::Progress {
-                            id: transfer_id,
-                            bytes: transfer.bytes_sent,
-                            total: transfer.total_size,
-                        });
-
-                        // Check if transfer just completed
-                        if transfer.state == FileTransferState::Completed {
-                            // Queue Complete message (will be sent next iteration)
-                            self.send_queue.push(FileTransferMessage::Complete {
-                                id: transfer_id,
-                                checksum: 0,
-                            });
-                        }
-
-                        // Create message bytes
-                        let msg_bytes = FileTransferMessage::Data {
-                            id: transfer_id,
-                            offset,
-                            data,
-                        }
-                        .to_bytes();
+        // Take turns so concurrent transfers share the channel
+        for transfer_id in self.outgoing_in_turn() {
+            let Some(transfer) = self.outgoing.get_mut(&transfer_id) else {
+                continue;
+            };
+            match transfer.read_next_chunk() {
+                Ok(Some(data)) => {
+                    let offset = transfer.bytes_sent - data.len() as u64;
 
-                        // Track buffered amount for flow control
-                        self.buffered_amount += msg_bytes.len();
+                    // Generate progress event
+                    self.events.push(FileTransferEvent::Progress {
+                        id: transfer_id,
+                        bytes: transfer.bytes_sent,
+                        total: transfer.total_size,
+                    });
 
-                        result = Some(msg_bytes);
-                        break;
-                    }
-                    Ok(None) => {
-                        // Transfer finished but no data to send - shouldn't happen normally
-                        // as we queue Complete message when last chunk is sent
-                    }
-                    Err(e) => {
-                        // File read error
-                        let transfer_id = transfer.id;
-                        self.events.push(FileTransferEvent::Failed {
+                    // Check if transfer just completed
+                    if transfer.state == FileTransferState::Completed {
+                        // Queue Complete message (will be sent next iteration)
+                        self.send_queue.push(FileTransferMessage::Complete {
                             id: transfer_id,
-                            reason: format!("File read error: {}", e),
+                            checksum: 0,
                         });
-                        to_remove.push(transfer_id);
                     }
+
+                    // Create message bytes
+                    let msg_bytes = FileTransferMessage::Data {
+                        id: transfer_id,
+                        offset,
+                        data,
+                    }
+                    .to_bytes();
+
+                    // Track buffered amount for flow control
+                    self.buffered_amount += msg_bytes.len();
+                    self.last_served = Some(transfer_id);
+
+                    result = Some(msg_bytes);
+                    break;
+                }
+                Ok(None) => {
+                    // Transfer finished but no data to send - shouldn't happen normally
+                    // as we queue Complete message when last chunk is sent
+                }
+                Err(e) => {
+                    // File read error
+                    self.events.push(FileTransferEvent::Failed {
+                        id: transfer_id,
+                        reason: format!("File read error: {}", e),
+                    });
+                    to_remove.push(transfer_id);
                 }
             }
         }
@@ -313,6 +458,23 @@ impl FileChannel {
         result
     }
 
+    /// Active outgoing transfer IDs, starting after the one served last
+    fn outgoing_in_turn(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self
+            .outgoing
+            .iter()
+            .filter(|(_, transfer)| transfer.state == FileTransferState::Transferring)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+
+        if let Some(last) = self.last_served {
+            let next = ids.partition_point(|id| *id <= last);
+            ids.rotate_left(next);
+        }
+        ids
+    }
+
     /// Poll for next event
     pub fn poll_event(&mut self) -> Option<FileTransferEvent> {
         if self.events.is_empty() {
@@ -335,3 +497,200 @@ impl FileChannel {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "roomrtc-file-channel-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_source(dir: &Path, size: usize) -> (PathBuf, Vec<u8>) {
+        write_named_source(dir, "source.bin", size, 31)
+    }
+
+    fn write_named_source(dir: &Path, name: &str, size: usize, seed: usize) -> (PathBuf, Vec<u8>) {
+        let content: Vec<u8> = (0..size).map(|i| (i * seed % 251) as u8).collect();
+        let path = dir.join(name);
+        fs::write(&path, &content).unwrap();
+        (path, content)
+    }
+
+    /// Delivers up to `limit` messages from `from` to `to`
+    fn pump(from: &mut FileChannel, to: &mut FileChannel, limit: usize) -> usize {
+        let mut delivered = 0;
+        while delivered < limit {
+            let Some(bytes) = from.poll_send() else {
+                break;
+            };
+            from.on_bytes_sent(bytes.len());
+            to.on_data(&bytes);
+            delivered += 1;
+        }
+        delivered
+    }
+
+    /// Pumps both directions until neither side has anything to send
+    fn pump_all(a: &mut FileChannel, b: &mut FileChannel) {
+        while pump(a, b, usize::MAX) + pump(b, a, usize::MAX) > 0 {}
+    }
+
+    /// Sends the offer, accepts it and returns the transfer id
+    fn start_transfer(
+        sender: &mut FileChannel,
+        receiver: &mut FileChannel,
+        source: &Path,
+        dest: &Path,
+    ) -> u64 {
+        let id = sender.send_file(source).unwrap();
+        pump(sender, receiver, 1);
+        receiver.accept_transfer(id, dest).unwrap();
+        pump(receiver, sender, 1);
+        id
+    }
+
+    fn completed_path(channel: &mut FileChannel) -> Option<PathBuf> {
+        std::iter::from_fn(|| channel.poll_event()).find_map(|event| match event {
+            FileTransferEvent::Completed { path, .. } => Some(path),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_resume_after_drop_produces_identical_file() {
+        let dir = test_dir("resume");
+        let (source, content) = write_source(&dir, 50_000);
+        let dest = dir.join("received.bin");
+
+        let mut sender = FileChannel::new(1);
+        let mut receiver = FileChannel::new(1);
+        let id = start_transfer(&mut sender, &mut receiver, &source, &dest);
+
+        // Deliver 10 chunks, then lose 3 in flight and drop the connection
+        assert_eq!(pump(&mut sender, &mut receiver, 10), 10);
+        let acknowledged = receiver.get_progress(id).unwrap().0;
+        assert!(acknowledged > 0);
+        for _ in 0..3 {
+            assert!(sender.poll_send().is_some());
+        }
+        sender.interrupt_transfers();
+
+        // The receiver's state is lost; resume from the partial file alone
+        let mut receiver = FileChannel::new(1);
+        receiver
+            .resume_partial_transfer(id, content.len() as u64, &dest)
+            .unwrap();
+        pump_all(&mut receiver, &mut sender);
+
+        let resumed_at = std::iter::from_fn(|| sender.poll_event()).find_map(|e| match e {
+            FileTransferEvent::Resumed { offset, .. } => Some(offset),
+            _ => None,
+        });
+        assert_eq!(resumed_at, Some(acknowledged));
+        assert_eq!(completed_path(&mut receiver), Some(dest.clone()));
+        assert_eq!(fs::read(&dest).unwrap(), content);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resume_with_mismatched_partial_restarts() {
+        let dir = test_dir("mismatch");
+        let (source, content) = write_source(&dir, 20_000);
+        let dest = dir.join("received.bin");
+
+        let mut sender = FileChannel::new(1);
+        let mut receiver = FileChannel::new(1);
+        let id = start_transfer(&mut sender, &mut receiver, &source, &dest);
+
+        pump(&mut sender, &mut receiver, 5);
+        sender.interrupt_transfers();
+        receiver.interrupt_transfers();
+
+        // Corrupt the partial file so its hash no longer matches the source
+        let part_path = dir.join("received.bin.part");
+        let mut partial = fs::read(&part_path).unwrap();
+        partial[0] ^= 0xFF;
+        fs::write(&part_path, &partial).unwrap();
+
+        receiver.resume_transfer(id).unwrap();
+        pump_all(&mut receiver, &mut sender);
+
+        assert_eq!(completed_path(&mut receiver), Some(dest.clone()));
+        assert_eq!(fs::read(&dest).unwrap(), content);
+        assert!(!part_path.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_concurrent_transfers_interleave() {
+        let dir = test_dir("concurrent");
+        let (source_a, content_a) = write_named_source(&dir, "a.bin", 30_000, 31);
+        let (source_b, content_b) = write_named_source(&dir, "b.bin", 20_000, 97);
+        let dest_a = dir.join("a-received.bin");
+        let dest_b = dir.join("b-received.bin");
+
+        let mut sender = FileChannel::new(1);
+        let mut receiver = FileChannel::new(1);
+        let id_a = sender.send_file(&source_a).unwrap();
+        let id_b = sender.send_file(&source_b).unwrap();
+        pump(&mut sender, &mut receiver, 2);
+        receiver.accept_transfer(id_a, &dest_a).unwrap();
+        receiver.accept_transfer(id_b, &dest_b).unwrap();
+        pump_all(&mut receiver, &mut sender);
+
+        let events: Vec<_> = std::iter::from_fn(|| receiver.poll_event()).collect();
+        let progress = |wanted: u64| -> Vec<(usize, u64, u64)> {
+            events
+                .iter()
+                .enumerate()
+                .filter_map(|(index, event)| match event {
+                    FileTransferEvent::Progress { id, bytes, total } if *id == wanted => {
+                        Some((index, *bytes, *total))
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+        let progress_a = progress(id_a);
+        let progress_b = progress(id_b);
+
+        // Each transfer reports its own, monotonically increasing progress
+        for (updates, size) in [(&progress_a, 30_000), (&progress_b, 20_000)] {
+            assert!(updates.windows(2).all(|w| w[0].1 < w[1].1));
+            assert!(updates.iter().all(|(_, _, total)| *total == size));
+            assert_eq!(updates.last().map(|(_, bytes, _)| *bytes), Some(size));
+        }
+
+        // Both were in flight at the same time
+        let first = |updates: &[(usize, u64, u64)]| updates.first().unwrap().0;
+        let last = |updates: &[(usize, u64, u64)]| updates.last().unwrap().0;
+        assert!(first(&progress_b) < last(&progress_a));
+        assert!(first(&progress_a) < last(&progress_b));
+
+        let completed: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                FileTransferEvent::Completed { id, .. } => Some(*id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(completed.len(), 2);
+        assert!(completed.contains(&id_a) && completed.contains(&id_b));
+        assert_eq!(fs::read(&dest_a).unwrap(), content_a);
+        assert_eq!(fs::read(&dest_b).unwrap(), content_b);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
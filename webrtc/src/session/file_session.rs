@@ -1,12 +1,15 @@
 //! File session - separate session for file transfers sharing DTLS transport
 //!
 //! FileSession provides file transfer capabilities over an existing DTLS connection,
-//! running parallel to video streaming without interference.
+//! running parallel to video streaming without interference. It also carries the
+//! in-call text chat on its own data channel.
 
+use super::chat::{CHAT_CHANNEL_LABEL, ChatMessage};
 use super::file_channel::FileChannel;
-use super::file_transfer::FileTransferEvent;
-use network::datachannel::{DataChannelEvent, DataChannelManager};
+use super::file_transfer::{FileTransferConfig, FileTransferEvent};
+use network::datachannel::{DataChannelConfig, DataChannelEvent, DataChannelManager};
 use network::sctp::{AssociationConfig, SctpAssociation, SctpPacket};
+use std::collections::VecDeque;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -23,6 +26,12 @@ pub struct FileSession {
     file_channel_ids: Arc<Mutex<Vec<u16>>>,
     /// Whether the session is established
     established: Arc<AtomicBool>,
+    /// Settings for file channels created by this session
+    transfer_config: FileTransferConfig,
+    /// Whether the local chat channel was created
+    chat_channel_created: bool,
+    /// Chat messages received and not yet polled
+    chat_messages: Arc<Mutex<VecDeque<ChatMessage>>>,
 }
 
 impl FileSession {
@@ -40,9 +49,18 @@ impl FileSession {
             file_channel: Arc::new(Mutex::new(None)),
             file_channel_ids: Arc::new(Mutex::new(Vec::new())),
             established: Arc::new(AtomicBool::new(false)),
+            transfer_config: FileTransferConfig::default(),
+            chat_channel_created: false,
+            chat_messages: Arc::new(Mutex::new(VecDeque::new())),
         }
     }
 
+    /// Apply transfer settings (e.g. a rate limit) to files sent over this session
+    pub fn with_transfer_config(mut self, config: FileTransferConfig) -> Self {
+        self.transfer_config = config;
+        self
+    }
+
     /// Check if the file session is ready
     ///
     /// This directly checks the DataChannelManager for any open file-transfer channel,
@@ -109,11 +127,49 @@ impl FileSession {
         }
 
         let mut fc = self.file_channel.lock().map_err(|_| "Lock error")?;
-        *fc = Some(FileChannel::new(channel_id));
+        *fc = Some(FileChannel::with_config(channel_id, self.transfer_config));
+
+        Ok(())
+    }
+
+    /// Create the chat channel after SCTP association is established
+    ///
+    /// The channel is reliable and ordered, so messages arrive in the order
+    /// they were sent.
+    fn create_chat_channel(&mut self) -> Result<(), &'static str> {
+        if self.chat_channel_created {
+            return Ok(());
+        }
+
+        let mut manager = self.channel_manager.lock().map_err(|_| "Lock error")?;
+        if !manager.is_established() {
+            return Err("SCTP association not established");
+        }
+        manager.create_channel(DataChannelConfig::reliable(CHAT_CHANNEL_LABEL))?;
+        self.chat_channel_created = true;
 
         Ok(())
     }
 
+    /// Send a chat message
+    ///
+    /// Uses the open chat channel with the lowest ID, so every message from
+    /// this peer travels on the same ordered channel.
+    pub fn send_chat(&self, message: &ChatMessage) -> Result<(), String> {
+        let mut manager = self.channel_manager.lock().map_err(|e| e.to_string())?;
+        let channel_id = manager
+            .find_open_channel(CHAT_CHANNEL_LABEL)
+            .ok_or("Chat channel not open yet")?;
+        manager
+            .send_text(channel_id, &message.to_json())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Poll for a received chat message
+    pub fn poll_chat_message(&self) -> Option<ChatMessage> {
+        self.chat_messages.lock().ok()?.pop_front()
+    }
+
     /// Send a file
     ///
     /// If no channel is ready yet, this will retry for up to 5 seconds.
@@ -150,13 +206,12 @@ impl FileSession {
 
         if fc.is_none() {
             // Create FileChannel if it doesn't exist yet
-            *fc = Some(super::file_channel::FileChannel::new(open_channel_id));
+            *fc = Some(FileChannel::with_config(open_channel_id, self.transfer_config));
         } else {
-            // Update FileChannel to use the open channel ID if different
+            // Retarget the FileChannel instead of recreating it so other
+            // transfers in progress keep their state
             let file_channel = fc.as_mut().unwrap();
-            if file_channel.channel_id() != open_channel_id {
-                *fc = Some(super::file_channel::FileChannel::new(open_channel_id));
-            }
+            file_channel.set_channel_id(open_channel_id);
         }
 
         let file_channel = fc.as_mut().unwrap();
@@ -190,6 +245,23 @@ impl FileSession {
             .map_err(|e| e.to_string())
     }
 
+    /// Pause active transfers after the data channel dropped
+    pub fn interrupt_transfers(&self) {
+        if let Ok(mut fc) = self.file_channel.lock()
+            && let Some(channel) = fc.as_mut() {
+                channel.interrupt_transfers();
+            }
+    }
+
+    /// Ask the sender to continue an interrupted incoming transfer
+    pub fn resume_transfer(&self, id: u64) -> Result<(), String> {
+        let mut fc = self.file_channel.lock().map_err(|e| e.to_string())?;
+        let file_channel = fc.as_mut().ok_or("File channel not established")?;
+        file_channel
+            .resume_transfer(id)
+            .map_err(|e| e.to_string())
+    }
+
     /// Notify that bytes were sent (for flow control)
     pub fn on_bytes_sent(&mut self, bytes: usize) {
         if let Ok(mut fc) = self.file_channel.lock()
@@ -213,12 +285,13 @@ impl FileSession {
             fc.is_some()
         };
 
-        let should_create = is_established && !has_channel;
+        let should_create = is_established && (!has_channel || !self.chat_channel_created);
 
         if should_create {
             drop(manager); // Release lock before calling create_file_channel
-            // Association is now established, create the file channel
+            // Association is now established, create the file and chat channels
             let _ = self.create_file_channel().is_ok();
+            let _ = self.create_chat_channel().is_ok();
             manager = self.channel_manager.lock().map_err(|e| e.to_string())?;
         }
 
@@ -228,6 +301,20 @@ impl FileSession {
         // Get mutable access to file channel once for all events
         if let Ok(mut fc_lock) = self.file_channel.lock() {
             for event in events {
+                if let DataChannelEvent::Message { id, payload } = &event
+                    && manager
+                        .get_channel(*id)
+                        .is_some_and(|channel| channel.label() == CHAT_CHANNEL_LABEL)
+                {
+                    // Malformed chat messages are dropped
+                    if let Ok(message) = ChatMessage::from_bytes(payload.as_bytes())
+                        && let Ok(mut chat_messages) = self.chat_messages.lock()
+                    {
+                        chat_messages.push_back(message);
+                    }
+                    continue;
+                }
+
                 if let DataChannelEvent::ChannelOpened { id, label } = &event {
                     // If this is a file transfer channel from the remote peer, track it
                     if label == "file-transfer" {
@@ -238,7 +325,7 @@ impl FileSession {
 
                         // If we don't have a file channel yet, create one with remote's ID
                         if fc_lock.is_none() {
-                            *fc_lock = Some(FileChannel::new(*id));
+                            *fc_lock = Some(FileChannel::with_config(*id, self.transfer_config));
                         }
 
                         // NOW set established flag - channel is open!
@@ -250,7 +337,7 @@ impl FileSession {
 
                 // Process data events for file channel
                 if let Some(fc) = fc_lock.as_mut()
-                    && let DataChannelEvent::DataReceived { id, data } = event {
+                    && let DataChannelEvent::Message { id, payload } = event {
                         // Check if this data is from a file transfer channel
                         let is_file_channel = if let Ok(ids) = self.file_channel_ids.lock() {
                             ids.contains(&id)
@@ -259,7 +346,7 @@ impl FileSession {
                         };
 
                         if is_file_channel {
-                            fc.on_data(&data);
+                            fc.on_data(payload.as_bytes());
                         }
                     }
             }
@@ -272,6 +359,9 @@ impl FileSession {
     }
 
     /// Poll for outgoing SCTP data to send
+    ///
+    /// File chunking pauses while the data channel's buffered amount is above its
+    /// low-water mark and resumes once `BufferedAmountLow` fires.
     pub fn poll_send(&self) -> Option<Vec<u8>> {
         // First check file channel for messages
         if let Ok(mut fc) = self.file_channel.lock()
@@ -280,13 +370,26 @@ impl FileSession {
                     // Send through data channel manager
                     if let Ok(mut manager) = self.channel_manager.lock() {
                         let channel_id = file_channel.channel_id();
-                        let _ = manager.send(channel_id, &data);
+                        let _ = manager.send_binary(channel_id, &data);
+
+                        // Backpressure: stop chunking until the SCTP queue drains
+                        if manager
+                            .get_channel(channel_id)
+                            .is_some_and(|channel| channel.is_buffered_amount_high())
+                        {
+                            file_channel.pause_sending();
+                        }
                     }
                 }
 
         // Then check SCTP association for packets
         if let Ok(mut manager) = self.channel_manager.lock()
             && let Some(packet) = manager.poll_send() {
+                if let Ok(mut fc) = self.file_channel.lock()
+                    && let Some(file_channel) = fc.as_mut()
+                        && manager.take_buffered_amount_low(file_channel.channel_id()) {
+                            file_channel.on_buffered_amount_low();
+                        }
                 return Some(packet.to_bytes());
             }
 
@@ -321,4 +424,58 @@ mod tests {
         let session = FileSession::new(true);
         assert!(!session.is_established());
     }
+
+    /// Runs the SCTP handshake and opens the data channels of both peers
+    fn establish_pair() -> (FileSession, FileSession) {
+        let mut client = FileSession::new(true);
+        let mut server = FileSession::new(false);
+
+        let init = client.establish().unwrap();
+        let init_ack = server.on_sctp_data(&init).unwrap();
+        let cookie_echo = client.on_sctp_data(&init_ack[0]).unwrap();
+        let cookie_ack = server.on_sctp_data(&cookie_echo[0]).unwrap();
+        client.on_sctp_data(&cookie_ack[0]).unwrap();
+
+        // DATA_CHANNEL_OPEN and DATA_CHANNEL_ACK in both directions
+        for _ in 0..2 {
+            deliver(&mut client, &mut server);
+            deliver(&mut server, &mut client);
+        }
+        (client, server)
+    }
+
+    /// Delivers every packet queued by `from` to `to` and feeds back the responses
+    fn deliver(from: &mut FileSession, to: &mut FileSession) {
+        while let Some(packet) = from.poll_send() {
+            for response in to.on_sctp_data(&packet).unwrap() {
+                from.on_sctp_data(&response).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_chat_messages_preserve_order() {
+        let (mut client, mut server) = establish_pair();
+        let first = ChatMessage::text("first").unwrap();
+        let second = ChatMessage::text("second").unwrap();
+        let third = ChatMessage::text("third").unwrap();
+
+        client.send_chat(&first).unwrap();
+        client.send_chat(&second).unwrap();
+        server
+            .send_chat(&ChatMessage::text("reply").unwrap())
+            .unwrap();
+        client.send_chat(&third).unwrap();
+        deliver(&mut client, &mut server);
+        deliver(&mut server, &mut client);
+
+        assert_eq!(server.poll_chat_message(), Some(first));
+        assert_eq!(server.poll_chat_message(), Some(second));
+        assert_eq!(server.poll_chat_message(), Some(third));
+        assert_eq!(server.poll_chat_message(), None);
+        assert!(matches!(
+            client.poll_chat_message(),
+            Some(ChatMessage::Text { text, .. }) if text == "reply"
+        ));
+    }
 }
@@ -0,0 +1,38 @@
+//! Content hash used to check that a partial file matches its source
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a hash of the first `len` bytes of the file at `path`
+///
+/// Fails with `UnexpectedEof` if the file is shorter than `len`.
+pub(crate) fn hash_prefix(path: &Path, len: u64) -> io::Result<u64> {
+    let mut reader = File::open(path)?.take(len);
+    let mut buffer = [0u8; 8192];
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut total = 0u64;
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        for byte in &buffer[..read] {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        total += read as u64;
+    }
+
+    if total < len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "File shorter than resume offset",
+        ));
+    }
+    Ok(hash)
+}
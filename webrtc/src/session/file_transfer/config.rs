@@ -0,0 +1,32 @@
+//! File transfer settings
+
+/// Settings applied to outgoing file transfers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FileTransferConfig {
+    /// Maximum data rate of each outgoing transfer (`None` = unlimited)
+    pub max_bytes_per_second: Option<u64>,
+}
+
+impl FileTransferConfig {
+    /// Limits each outgoing transfer to `bytes_per_second`
+    ///
+    /// A rate of 0 means unlimited, not a transfer that never progresses.
+    pub fn with_max_bytes_per_second(mut self, bytes_per_second: u64) -> Self {
+        self.max_bytes_per_second = (bytes_per_second > 0).then_some(bytes_per_second);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_rate_is_unlimited() {
+        let config = FileTransferConfig::default().with_max_bytes_per_second(0);
+        assert_eq!(config.max_bytes_per_second, None);
+
+        let config = FileTransferConfig::default().with_max_bytes_per_second(1000);
+        assert_eq!(config.max_bytes_per_second, Some(1000));
+    }
+}
@@ -15,6 +15,8 @@ pub enum FileTransferEvent {
     Accepted { id: u64 },
     /// File transfer was rejected by peer
     Rejected { id: u64, reason: String },
+    /// Interrupted transfer continues from `offset`
+    Resumed { id: u64, offset: u64, total: u64 },
     /// Transfer progress update
     Progress { id: u64, bytes: u64, total: u64 },
     /// Transfer completed successfully
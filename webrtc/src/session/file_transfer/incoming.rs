@@ -1,11 +1,17 @@
 //! Incoming file transfer (receiver side)
 
+use super::checksum::hash_prefix;
 use super::state::FileTransferState;
 use std::collections::HashMap;
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 
 /// Incoming file transfer (receiver side)
+///
+/// Contiguous data is written to `<save_path>.part` as it arrives, so the
+/// partial file length is always the last acknowledged offset. An interrupted
+/// transfer can resume from it, even after the in-memory state is lost.
 #[derive(Debug)]
 pub struct IncomingTransfer {
     /// Total file size in bytes
@@ -16,8 +22,12 @@ pub struct IncomingTransfer {
     pub save_path: Option<PathBuf>,
     /// Current state
     pub state: FileTransferState,
-    /// Received chunks (for reassembly)
+    /// Out-of-order chunks waiting for the gap before them to be filled
     chunks: HashMap<u64, Vec<u8>>,
+    /// Partial file (opened on first write)
+    part_file: Option<File>,
+    /// Bytes written to the partial file
+    written: u64,
 }
 
 impl IncomingTransfer {
@@ -29,9 +39,40 @@ impl IncomingTransfer {
             save_path: None,
             state: FileTransferState::Pending,
             chunks: HashMap::new(),
+            part_file: None,
+            written: 0,
         }
     }
 
+    /// Rebuild an interrupted transfer from the partial file left on disk
+    ///
+    /// The transfer starts `Interrupted`; call [`resume_request`](Self::resume_request)
+    /// to ask the sender to continue.
+    pub fn resume(size: u64, save_path: PathBuf) -> io::Result<Self> {
+        let part_path = Self::part_path_for(&save_path);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&part_path)?;
+
+        let mut written = file.metadata()?.len();
+        if written > size {
+            // Not from this transfer, start over
+            file.set_len(0)?;
+            written = 0;
+        }
+
+        Ok(Self {
+            total_size: size,
+            bytes_received: written,
+            save_path: Some(save_path),
+            state: FileTransferState::Interrupted,
+            chunks: HashMap::new(),
+            part_file: Some(file),
+            written,
+        })
+    }
+
     /// Accept the transfer and set save path
     pub fn accept(&mut self, save_path: PathBuf) {
         if self.state == FileTransferState::Pending {
@@ -45,6 +86,24 @@ impl IncomingTransfer {
         self.state = FileTransferState::Cancelled;
     }
 
+    /// Path of the partial file, once a save path is set
+    pub fn part_path(&self) -> Option<PathBuf> {
+        self.save_path
+            .as_ref()
+            .map(|path| Self::part_path_for(path))
+    }
+
+    fn part_path_for(save_path: &Path) -> PathBuf {
+        let mut name = save_path.as_os_str().to_os_string();
+        name.push(".part");
+        PathBuf::from(name)
+    }
+
+    /// Offset up to which the data is safely on disk
+    pub fn acknowledged_offset(&self) -> u64 {
+        self.written
+    }
+
     /// Receive a data chunk
     pub fn receive_chunk(&mut self, offset: u64, data: Vec<u8>) -> io::Result<()> {
         if self.state != FileTransferState::Transferring {
@@ -56,12 +115,12 @@ impl IncomingTransfer {
         let data_len = data.len() as u64;
 
         // Check for duplicate or out-of-bounds chunks
-        if self.chunks.contains_key(&offset) {
+        if offset < self.written || self.chunks.contains_key(&offset) {
             // Duplicate chunk, skip it
             return Ok(());
         }
 
-        if self.bytes_received + data_len > self.total_size {
+        if offset + data_len > self.total_size {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 "Received more data than expected",
@@ -70,31 +129,125 @@ impl IncomingTransfer {
 
         self.chunks.insert(offset, data);
         self.bytes_received += data_len;
+        self.write_contiguous()
+    }
+
+    /// Append buffered chunks that continue the partial file
+    fn write_contiguous(&mut self) -> io::Result<()> {
+        while let Some(data) = self.chunks.remove(&self.written) {
+            self.part_file()?.write_all(&data)?;
+            self.written += data.len() as u64;
+        }
         Ok(())
     }
 
-    /// Finalize the transfer - write all chunks to file
+    fn part_file(&mut self) -> io::Result<&mut File> {
+        if self.part_file.is_none() {
+            let part_path = self
+                .part_path()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No save path set"))?;
+            // A fresh transfer never reuses a stale partial file
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(part_path)?;
+            self.part_file = Some(file);
+        }
+        Ok(self.part_file.as_mut().expect("part file just opened"))
+    }
+
+    /// Pause after the connection dropped
+    ///
+    /// Out-of-order chunks are discarded; the sender resends everything after
+    /// the acknowledged offset.
+    pub fn interrupt(&mut self) {
+        if self.state == FileTransferState::Transferring {
+            self.state = FileTransferState::Interrupted;
+            self.chunks.clear();
+            self.bytes_received = self.written;
+        }
+    }
+
+    /// Offset and content hash to send in a resume request
+    pub fn resume_request(&mut self) -> io::Result<(u64, u64)> {
+        if self.state != FileTransferState::Interrupted {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Transfer not interrupted",
+            ));
+        }
+        let part_path = self
+            .part_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No save path set"))?;
+        self.part_file()?.flush()?;
+        let hash = hash_prefix(&part_path, self.written)?;
+        Ok((self.written, hash))
+    }
+
+    /// Continue from the offset the sender agreed on
+    ///
+    /// The sender restarts from 0 when its file does not match the partial
+    /// one, so anything past `offset` is dropped. `hash` must match the
+    /// partial file up to `offset`.
+    pub fn on_resume_ack(&mut self, offset: u64, hash: u64) -> io::Result<()> {
+        if self.state != FileTransferState::Interrupted {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Transfer not interrupted",
+            ));
+        }
+        if offset > self.written {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Resume offset beyond received data",
+            ));
+        }
+
+        let part_path = self
+            .part_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No save path set"))?;
+        if hash_prefix(&part_path, offset)? != hash {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Partial file does not match the source",
+            ));
+        }
+
+        let file = self.part_file()?;
+        file.set_len(offset)?;
+        file.seek(SeekFrom::Start(offset))?;
+        self.written = offset;
+        self.bytes_received = offset;
+        self.state = FileTransferState::Transferring;
+        Ok(())
+    }
+
+    /// Finalize the transfer - move the completed partial file into place
     pub fn finalize(&mut self) -> io::Result<PathBuf> {
         let save_path = self
             .save_path
-            .as_ref()
+            .clone()
             .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No save path set"))?;
 
-        let mut file = std::fs::File::create(save_path)?;
-
-        // Sort chunks by offset and write
-        let mut offsets: Vec<_> = self.chunks.keys().copied().collect();
-        offsets.sort();
-
-        for offset in offsets {
-            if let Some(data) = self.chunks.get(&offset) {
-                file.write_all(data)?;
-            }
+        self.write_contiguous()?;
+        if self.written != self.total_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Transfer incomplete: {} of {} bytes",
+                    self.written, self.total_size
+                ),
+            ));
         }
 
-        file.flush()?;
+        self.part_file()?.flush()?;
+        self.part_file = None;
+        if let Some(part_path) = self.part_path() {
+            std::fs::rename(part_path, &save_path)?;
+        }
         self.state = FileTransferState::Completed;
 
-        Ok(save_path.clone())
+        Ok(save_path)
     }
 }
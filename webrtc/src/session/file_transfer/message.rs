@@ -22,6 +22,10 @@ pub enum FileTransferMessage {
     Complete { id: u64, checksum: u64 },
     /// Cancel ongoing transfer
     Cancel { id: u64, reason: String },
+    /// Receiver asks to continue an interrupted transfer from `offset`
+    ResumeRequest { id: u64, offset: u64, hash: u64 },
+    /// Sender continues from `offset` (0 if the partial file did not match)
+    ResumeAck { id: u64, offset: u64, hash: u64 },
 }
 
 impl FileTransferMessage {
@@ -31,6 +35,8 @@ impl FileTransferMessage {
     const TYPE_DATA: u8 = 0x04;
     const TYPE_COMPLETE: u8 = 0x05;
     const TYPE_CANCEL: u8 = 0x06;
+    const TYPE_RESUME_REQUEST: u8 = 0x07;
+    const TYPE_RESUME_ACK: u8 = 0x08;
 
     /// Serialize to bytes
     pub fn to_bytes(&self) -> Vec<u8> {
@@ -46,6 +52,12 @@ impl FileTransferMessage {
             Self::Data { id, offset, data } => Self::serialize_data(*id, *offset, data),
             Self::Complete { id, checksum } => Self::serialize_complete(*id, *checksum),
             Self::Cancel { id, reason } => Self::serialize_cancel(*id, reason),
+            Self::ResumeRequest { id, offset, hash } => {
+                Self::serialize_resume(Self::TYPE_RESUME_REQUEST, *id, *offset, *hash)
+            }
+            Self::ResumeAck { id, offset, hash } => {
+                Self::serialize_resume(Self::TYPE_RESUME_ACK, *id, *offset, *hash)
+            }
         }
     }
 
@@ -107,6 +119,15 @@ impl FileTransferMessage {
         buf
     }
 
+    fn serialize_resume(msg_type: u8, id: u64, offset: u64, hash: u64) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(25);
+        buf.push(msg_type);
+        buf.extend_from_slice(&id.to_be_bytes());
+        buf.extend_from_slice(&offset.to_be_bytes());
+        buf.extend_from_slice(&hash.to_be_bytes());
+        buf
+    }
+
     /// Parse from bytes
     pub fn from_bytes(data: &[u8]) -> io::Result<Self> {
         if data.is_empty() {
@@ -119,6 +140,7 @@ impl FileTransferMessage {
             Self::TYPE_DATA => Self::parse_data(data),
             Self::TYPE_COMPLETE => Self::parse_complete(data),
             Self::TYPE_CANCEL => Self::parse_cancel(data),
+            Self::TYPE_RESUME_REQUEST | Self::TYPE_RESUME_ACK => Self::parse_resume(data),
             _ => Err(io::Error::new(io::ErrorKind::InvalidData, "Unknown type")),
         }
     }
@@ -207,6 +229,20 @@ impl FileTransferMessage {
             .unwrap_or_default();
         Ok(Self::Cancel { id, reason })
     }
+
+    fn parse_resume(data: &[u8]) -> io::Result<Self> {
+        if data.len() < 25 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Too short"));
+        }
+        let id = u64::from_be_bytes(data[1..9].try_into().unwrap());
+        let offset = u64::from_be_bytes(data[9..17].try_into().unwrap());
+        let hash = u64::from_be_bytes(data[17..25].try_into().unwrap());
+        if data[0] == Self::TYPE_RESUME_REQUEST {
+            Ok(Self::ResumeRequest { id, offset, hash })
+        } else {
+            Ok(Self::ResumeAck { id, offset, hash })
+        }
+    }
 }
 
 #[cfg(test)]
@@ -234,4 +270,33 @@ mod tests {
             _ => panic!("Wrong type"),
         }
     }
+
+    #[test]
+    fn test_resume_roundtrip() {
+        let request = FileTransferMessage::ResumeRequest {
+            id: 7,
+            offset: 4096,
+            hash: 0xDEAD_BEEF_0000_0001,
+        };
+        match FileTransferMessage::from_bytes(&request.to_bytes()).unwrap() {
+            FileTransferMessage::ResumeRequest { id, offset, hash } => {
+                assert_eq!((id, offset, hash), (7, 4096, 0xDEAD_BEEF_0000_0001));
+            }
+            _ => panic!("Wrong type"),
+        }
+
+        let ack = FileTransferMessage::ResumeAck {
+            id: 7,
+            offset: 0,
+            hash: 42,
+        };
+        assert!(matches!(
+            FileTransferMessage::from_bytes(&ack.to_bytes()).unwrap(),
+            FileTransferMessage::ResumeAck {
+                id: 7,
+                offset: 0,
+                hash: 42
+            }
+        ));
+    }
 }
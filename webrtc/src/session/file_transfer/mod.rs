@@ -1,14 +1,18 @@
 //! File transfer protocol over WebRTC data channels
 //!
-//! Implements reliable file transfer with chunking, progress tracking,
-//! and accept/reject flow.
+//! Implements reliable file transfer with chunking, rate limiting, progress tracking,
+//! accept/reject flow and resumption of interrupted transfers.
 
+mod checksum;
+mod config;
 mod event;
 mod incoming;
 mod message;
 mod outgoing;
 mod state;
+mod throttle;
 
+pub use config::FileTransferConfig;
 pub use event::FileTransferEvent;
 pub use incoming::IncomingTransfer;
 pub use message::FileTransferMessage;
@@ -1,6 +1,8 @@
 //! Outgoing file transfer (sender side)
 
+use super::checksum::hash_prefix;
 use super::state::FileTransferState;
+use super::throttle::Throttle;
 use std::io::{self, Read, Seek, SeekFrom};
 use std::path::PathBuf;
 
@@ -23,6 +25,8 @@ pub struct OutgoingTransfer {
     successful_chunks: u64,
     /// Number of failed/slow chunks
     failed_chunks: u64,
+    /// Rate limit on chunk emission (`None` = unlimited)
+    throttle: Option<Throttle>,
 }
 
 impl OutgoingTransfer {
@@ -43,9 +47,25 @@ impl OutgoingTransfer {
             chunk_size: Self::INITIAL_CHUNK_SIZE,
             successful_chunks: 0,
             failed_chunks: 0,
+            throttle: None,
         }
     }
 
+    /// Limit this transfer to `bytes_per_second`
+    ///
+    /// Chunks are paced with a token bucket holding about 100 ms of data
+    /// (at least one full chunk), so the link is never flooded in bursts.
+    /// A rate of 0 leaves the transfer unthrottled.
+    pub fn with_max_bytes_per_second(mut self, bytes_per_second: u64) -> Self {
+        if bytes_per_second == 0 {
+            self.throttle = None;
+            return self;
+        }
+        let burst = (bytes_per_second / 10).max(Self::MAX_CHUNK_SIZE as u64);
+        self.throttle = Some(Throttle::new(bytes_per_second, burst));
+        self
+    }
+
     /// Mark as accepted and start transferring
     pub fn accept(&mut self) {
         if self.state == FileTransferState::Pending {
@@ -71,12 +91,58 @@ impl OutgoingTransfer {
         }
     }
 
+    /// Pause after the connection dropped
+    pub fn interrupt(&mut self) {
+        if self.state == FileTransferState::Transferring {
+            self.state = FileTransferState::Interrupted;
+        }
+    }
+
+    /// Continue from the receiver's acknowledged offset
+    ///
+    /// `hash` is the receiver's hash of its partial file. If it does not
+    /// match this file up to `offset`, the transfer restarts from 0.
+    ///
+    /// Returns the offset sending continues from and the hash of the data
+    /// before it.
+    pub fn resume(&mut self, offset: u64, hash: u64) -> io::Result<(u64, u64)> {
+        if !matches!(
+            self.state,
+            FileTransferState::Transferring | FileTransferState::Interrupted
+        ) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Transfer cannot be resumed",
+            ));
+        }
+
+        let matches = offset <= self.total_size
+            && hash_prefix(&self.path, offset).is_ok_and(|local| local == hash);
+        let (offset, hash) = if matches {
+            (offset, hash)
+        } else {
+            (0, hash_prefix(&self.path, 0)?)
+        };
+
+        self.bytes_sent = offset;
+        self.chunk_size = Self::INITIAL_CHUNK_SIZE;
+        self.state = if offset >= self.total_size {
+            FileTransferState::Completed
+        } else {
+            FileTransferState::Transferring
+        };
+        Ok((offset, hash))
+    }
+
     // /// Get current chunk size
     // pub fn get_chunk_size(&self) -> usize {
     //     self.chunk_size
     // }
 
     /// Read next chunk from file
+    ///
+    /// Returns `None` when nothing can be sent now, including when the rate
+    /// limit has no room for the next chunk yet.
     pub fn read_next_chunk(&mut self) -> io::Result<Option<Vec<u8>>> {
         if self.state != FileTransferState::Transferring {
             return Ok(None);
@@ -85,11 +151,16 @@ impl OutgoingTransfer {
             return Ok(None);
         }
 
-        let mut file = std::fs::File::open(&self.path)?;
-        file.seek(SeekFrom::Start(self.bytes_sent))?;
-
         let remaining = (self.total_size - self.bytes_sent) as usize;
         let chunk_size = remaining.min(self.chunk_size);
+        if let Some(throttle) = &mut self.throttle
+            && !throttle.try_consume(chunk_size as u64)
+        {
+            return Ok(None);
+        }
+
+        let mut file = std::fs::File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.bytes_sent))?;
         let mut buffer = vec![0u8; chunk_size];
 
         let bytes_read = file.read(&mut buffer)?;
@@ -107,3 +178,73 @@ impl OutgoingTransfer {
         Ok(Some(buffer))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_throttled_transfer_takes_configured_time() {
+        let path =
+            std::env::temp_dir().join(format!("roomrtc-throttle-{}.bin", std::process::id()));
+        std::fs::write(&path, vec![0xA5u8; 10_000]).unwrap();
+
+        // 10 KB at 10 KB/s: the first 1200-byte burst is free, the rest paced
+        let mut transfer =
+            OutgoingTransfer::new(1, path.clone(), 10_000).with_max_bytes_per_second(10_000);
+        transfer.accept();
+
+        let start = Instant::now();
+        let mut sent = 0;
+        while transfer.state == FileTransferState::Transferring {
+            match transfer.read_next_chunk().unwrap() {
+                Some(chunk) => sent += chunk.len(),
+                None => std::thread::sleep(Duration::from_millis(1)),
+            }
+        }
+        let elapsed = start.elapsed();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(sent, 10_000);
+        assert!(
+            elapsed >= Duration::from_millis(800),
+            "too fast: {:?}",
+            elapsed
+        );
+        assert!(
+            elapsed < Duration::from_millis(1500),
+            "too slow: {:?}",
+            elapsed
+        );
+    }
+
+    #[test]
+    fn test_unthrottled_transfer_is_not_paced() {
+        let path =
+            std::env::temp_dir().join(format!("roomrtc-unthrottled-{}.bin", std::process::id()));
+        std::fs::write(&path, vec![0x5Au8; 10_000]).unwrap();
+
+        let mut transfer = OutgoingTransfer::new(2, path.clone(), 10_000);
+        transfer.accept();
+        while transfer.read_next_chunk().unwrap().is_some() {}
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(transfer.state, FileTransferState::Completed);
+    }
+
+    #[test]
+    fn test_zero_rate_does_not_stall_transfer() {
+        let path =
+            std::env::temp_dir().join(format!("roomrtc-zero-rate-{}.bin", std::process::id()));
+        std::fs::write(&path, vec![0x3Cu8; 10_000]).unwrap();
+
+        let mut transfer =
+            OutgoingTransfer::new(3, path.clone(), 10_000).with_max_bytes_per_second(0);
+        transfer.accept();
+        while transfer.read_next_chunk().unwrap().is_some() {}
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(transfer.state, FileTransferState::Completed);
+    }
+}
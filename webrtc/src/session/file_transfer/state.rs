@@ -7,6 +7,8 @@ pub enum FileTransferState {
     Pending,
     /// Transfer in progress
     Transferring,
+    /// Connection dropped mid-transfer, waiting to be resumed
+    Interrupted,
     /// Transfer completed
     Completed,
     /// Transfer cancelled or rejected
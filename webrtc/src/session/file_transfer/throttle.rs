@@ -0,0 +1,83 @@
+//! Token bucket pacing for outgoing file data
+
+use std::time::Instant;
+
+/// Token bucket limiting bytes per second with a burst allowance
+///
+/// Each byte costs one token; tokens refill continuously at `bytes_per_sec`
+/// up to `burst`.
+#[derive(Debug, Clone)]
+pub(crate) struct Throttle {
+    bytes_per_sec: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl Throttle {
+    /// Creates a throttle with a full bucket
+    ///
+    /// `burst` must be at least the largest chunk, or that chunk never fits.
+    pub fn new(bytes_per_sec: u64, burst: u64) -> Self {
+        let burst = burst.max(1) as f64;
+        Self {
+            bytes_per_sec: bytes_per_sec as f64,
+            burst,
+            tokens: burst,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes `bytes` tokens now if available
+    pub fn try_consume(&mut self, bytes: u64) -> bool {
+        self.try_consume_at(bytes, Instant::now())
+    }
+
+    /// Takes `bytes` tokens at `now` if available
+    pub fn try_consume_at(&mut self, bytes: u64, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.bytes_per_sec).min(self.burst);
+        self.last_refill = now;
+
+        let bytes = bytes as f64;
+        if self.tokens >= bytes {
+            self.tokens -= bytes;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_burst_then_refill() {
+        let mut throttle = Throttle::new(1000, 500);
+        let start = Instant::now();
+
+        assert!(throttle.try_consume_at(500, start));
+        assert!(!throttle.try_consume_at(100, start));
+
+        // 100 ms at 1000 B/s refills 100 bytes
+        let later = start + Duration::from_millis(100);
+        assert!(throttle.try_consume_at(100, later));
+        assert!(!throttle.try_consume_at(1, later));
+    }
+
+    #[test]
+    fn test_refill_capped_at_burst() {
+        let mut throttle = Throttle::new(1000, 500);
+        let start = Instant::now();
+        assert!(throttle.try_consume_at(500, start));
+
+        let much_later = start + Duration::from_secs(10);
+        assert!(!throttle.try_consume_at(501, much_later));
+        assert!(throttle.try_consume_at(500, much_later));
+    }
+}
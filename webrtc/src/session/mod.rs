@@ -4,21 +4,29 @@
 //! video streaming, including encoding, RTP packetization, and transport.
 
 // P2P session module - handles WebRTC media pipeline
+mod chat;
 mod config;
 mod control_message;
 mod dtls_setup;
 pub mod file_channel;
 pub mod file_session;
 pub mod file_transfer;
+mod recording;
 mod recv_thread;
 mod secure_session;
 mod send_thread;
+mod simulcast;
+mod stream_router;
+mod video_codec;
 mod video_decode_thread;
 
 // Re-export public types
+pub use chat::{CHAT_CHANNEL_LABEL, ChatMessage, MAX_CHAT_MESSAGE_BYTES};
 pub use control_message::ControlMessage;
-pub use file_transfer::FileTransferEvent;
+pub use file_transfer::{FileTransferConfig, FileTransferEvent};
+pub use simulcast::{SimulcastConfig, SimulcastLayer};
 
 // Re-export internal types
 pub(crate) use config::P2PConfig;
 pub(crate) use secure_session::SecureP2PSession;
+pub(crate) use video_codec::VIDEO_CODECS;
@@ -0,0 +1,115 @@
+//! H.264 helpers for storing Annex B access units in Matroska
+//!
+//! The encoder and depacketizer produce Annex B (start code delimited)
+//! access units, while Matroska's `V_MPEG4/ISO/AVC` expects each NAL unit
+//! prefixed by its length and the parameter sets in an
+//! AVCDecoderConfigurationRecord ("avcC").
+
+/// NAL unit type of an IDR slice
+const NAL_IDR: u8 = 5;
+/// NAL unit type of a sequence parameter set
+const NAL_SPS: u8 = 7;
+/// NAL unit type of a picture parameter set
+const NAL_PPS: u8 = 8;
+
+/// Splits an Annex B access unit into its NAL units, without start codes
+pub(super) fn split_annex_b(data: &[u8]) -> Vec<&[u8]> {
+    let mut starts = Vec::new();
+    let mut i = 0;
+    while i + 3 <= data.len() {
+        if data[i..i + 3] == [0x00, 0x00, 0x01] {
+            starts.push(i + 3);
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(index, &start)| {
+            let end = starts.get(index + 1).map_or(data.len(), |&next| next - 3);
+            // A four byte start code leaves a zero at the end of the previous unit
+            let mut nal = &data[start..end];
+            while let [rest @ .., 0x00] = nal {
+                nal = rest;
+            }
+            nal
+        })
+        .filter(|nal| !nal.is_empty())
+        .collect()
+}
+
+/// Returns the type of a NAL unit
+pub(super) fn nal_type(nal: &[u8]) -> u8 {
+    nal.first().map_or(0, |header| header & 0x1F)
+}
+
+/// Returns whether the NAL units contain an IDR slice
+pub(super) fn is_keyframe(nals: &[&[u8]]) -> bool {
+    nals.iter().any(|nal| nal_type(nal) == NAL_IDR)
+}
+
+/// Returns the first SPS and PPS among the NAL units
+pub(super) fn parameter_sets<'a>(nals: &[&'a [u8]]) -> (Option<&'a [u8]>, Option<&'a [u8]>) {
+    let find = |wanted| nals.iter().copied().find(|nal| nal_type(nal) == wanted);
+    (find(NAL_SPS), find(NAL_PPS))
+}
+
+/// Joins NAL units, each prefixed by its length as a 32 bit big endian number
+pub(super) fn length_prefixed(nals: &[&[u8]]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(nals.iter().map(|nal| nal.len() + 4).sum());
+    for nal in nals {
+        out.extend_from_slice(&(nal.len() as u32).to_be_bytes());
+        out.extend_from_slice(nal);
+    }
+    out
+}
+
+/// Builds the AVCDecoderConfigurationRecord for one SPS and one PPS
+///
+/// # Returns
+/// None if the SPS is too short to carry the profile and level
+pub(super) fn decoder_config(sps: &[u8], pps: &[u8]) -> Option<Vec<u8>> {
+    let profile = sps.get(1..4)?;
+    let mut config = vec![1, profile[0], profile[1], profile[2]];
+    // 4 byte NAL lengths, one SPS
+    config.extend_from_slice(&[0xFF, 0xE1]);
+    config.extend_from_slice(&(sps.len() as u16).to_be_bytes());
+    config.extend_from_slice(sps);
+    config.push(1);
+    config.extend_from_slice(&(pps.len() as u16).to_be_bytes());
+    config.extend_from_slice(pps);
+    Some(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_unit_conversion() {
+        let sps = [0x67, 0x42, 0xC0, 0x1F, 0xAB];
+        let pps = [0x68, 0xCE, 0x3C, 0x80];
+        let idr = [0x65, 0x88, 0x84, 0x21];
+        let mut access_unit = vec![0, 0, 0, 1];
+        access_unit.extend_from_slice(&sps);
+        access_unit.extend_from_slice(&[0, 0, 1]);
+        access_unit.extend_from_slice(&pps);
+        access_unit.extend_from_slice(&[0, 0, 0, 1]);
+        access_unit.extend_from_slice(&idr);
+
+        let nals = split_annex_b(&access_unit);
+        assert_eq!(nals, vec![&sps[..], &pps[..], &idr[..]]);
+        assert!(is_keyframe(&nals));
+        assert_eq!(parameter_sets(&nals), (Some(&sps[..]), Some(&pps[..])));
+
+        let converted = length_prefixed(&nals[2..]);
+        assert_eq!(converted, vec![0, 0, 0, 4, 0x65, 0x88, 0x84, 0x21]);
+
+        let config = decoder_config(&sps, &pps).unwrap();
+        assert_eq!(&config[..6], &[1, 0x42, 0xC0, 0x1F, 0xFF, 0xE1]);
+        assert_eq!(config.len(), 6 + 2 + sps.len() + 1 + 2 + pps.len());
+    }
+}
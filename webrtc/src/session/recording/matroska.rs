@@ -0,0 +1,387 @@
+//! Minimal Matroska (MKV) muxer
+//!
+//! Writes one Segment holding an Info element, a Tracks element and
+//! Clusters of SimpleBlocks, with millisecond timestamps. Track details
+//! only known once media arrives (codec private data, video size) are
+//! filled in on [`MatroskaWriter::finish`], which rewrites the Tracks
+//! element in space reserved for it behind the Info element.
+
+use std::io::{self, Seek, SeekFrom, Write};
+
+// EBML header
+const EBML: u32 = 0x1A45_DFA3;
+const EBML_VERSION: u32 = 0x4286;
+const EBML_READ_VERSION: u32 = 0x42F7;
+const EBML_MAX_ID_LENGTH: u32 = 0x42F2;
+const EBML_MAX_SIZE_LENGTH: u32 = 0x42F3;
+const DOC_TYPE: u32 = 0x4282;
+const DOC_TYPE_VERSION: u32 = 0x4287;
+const DOC_TYPE_READ_VERSION: u32 = 0x4285;
+
+// Segment
+pub(super) const SEGMENT: u32 = 0x1853_8067;
+pub(super) const INFO: u32 = 0x1549_A966;
+const TIMESTAMP_SCALE: u32 = 0x2A_D7B1;
+const MUXING_APP: u32 = 0x4D80;
+const WRITING_APP: u32 = 0x5741;
+const DURATION: u32 = 0x4489;
+pub(super) const TRACKS: u32 = 0x1654_AE6B;
+pub(super) const CLUSTER: u32 = 0x1F43_B675;
+pub(super) const VOID: u32 = 0xEC;
+
+// Tracks
+pub(super) const TRACK_ENTRY: u32 = 0xAE;
+pub(super) const TRACK_NUMBER: u32 = 0xD7;
+const TRACK_UID: u32 = 0x73C5;
+pub(super) const TRACK_TYPE: u32 = 0x83;
+const FLAG_LACING: u32 = 0x9C;
+pub(super) const CODEC_ID: u32 = 0x86;
+pub(super) const CODEC_PRIVATE: u32 = 0x63A2;
+const NAME: u32 = 0x536E;
+pub(super) const VIDEO: u32 = 0xE0;
+pub(super) const PIXEL_WIDTH: u32 = 0xB0;
+pub(super) const PIXEL_HEIGHT: u32 = 0xBA;
+pub(super) const AUDIO: u32 = 0xE1;
+const SAMPLING_FREQUENCY: u32 = 0xB5;
+pub(super) const CHANNELS: u32 = 0x9F;
+
+// Clusters
+const CLUSTER_TIMESTAMP: u32 = 0xE7;
+pub(super) const SIMPLE_BLOCK: u32 = 0xA3;
+
+/// Bytes reserved for the Tracks element, including its initial version
+const TRACKS_RESERVED_BYTES: usize = 2048;
+/// Longest time span covered by one cluster, in milliseconds
+const MAX_CLUSTER_DURATION_MS: i64 = 5_000;
+/// Size field value meaning "unknown size", patched on finish
+const UNKNOWN_SIZE: [u8; 8] = [0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+
+/// Kind of a track and its media-specific settings
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum TrackKind {
+    Video {
+        width: u32,
+        height: u32,
+    },
+    Audio {
+        sampling_frequency: f64,
+        channels: u8,
+    },
+}
+
+/// A track of the file
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Track {
+    pub number: u64,
+    pub name: String,
+    pub codec_id: &'static str,
+    pub codec_private: Option<Vec<u8>>,
+    pub kind: TrackKind,
+    /// Number of blocks written; tracks left empty are dropped on finish
+    pub blocks: u64,
+}
+
+/// Cluster being filled, written out once it is complete
+struct PendingCluster {
+    timestamp_ms: i64,
+    body: Vec<u8>,
+}
+
+/// Streams tracks into a Matroska file
+pub(super) struct MatroskaWriter<W: Write + Seek> {
+    out: W,
+    tracks: Vec<Track>,
+    /// Offset of the first byte of the Segment's body
+    segment_start: u64,
+    /// Offset of the Duration float in the Info element
+    duration_offset: u64,
+    /// Offset of the space reserved for the Tracks element
+    tracks_offset: u64,
+    cluster: Option<PendingCluster>,
+    last_timestamp_ms: i64,
+}
+
+impl PendingCluster {
+    fn new(timestamp_ms: i64) -> Self {
+        let mut body = Vec::new();
+        write_uint(&mut body, CLUSTER_TIMESTAMP, timestamp_ms as u64);
+        Self { timestamp_ms, body }
+    }
+}
+
+impl<W: Write + Seek> MatroskaWriter<W> {
+    /// Writes the file header and the initial Tracks element
+    ///
+    /// # Errors
+    /// If writing fails or the tracks do not fit in the reserved space
+    pub fn new(mut out: W, tracks: Vec<Track>) -> io::Result<Self> {
+        let mut header = Vec::new();
+        write_element(&mut header, EBML, &ebml_header());
+        write_id(&mut header, SEGMENT);
+        header.extend_from_slice(&UNKNOWN_SIZE);
+        let segment_start = out.stream_position()? + header.len() as u64;
+
+        let (info, duration_position) = info_element();
+        let duration_offset = segment_start + duration_position as u64;
+        header.extend_from_slice(&info);
+        let tracks_offset = segment_start + info.len() as u64;
+        header.extend_from_slice(&reserved_tracks(&tracks)?);
+        out.write_all(&header)?;
+
+        Ok(Self {
+            out,
+            tracks,
+            segment_start,
+            duration_offset,
+            tracks_offset,
+            cluster: None,
+            last_timestamp_ms: 0,
+        })
+    }
+
+    /// Returns the track with the given number
+    pub fn track_mut(&mut self, number: u64) -> Option<&mut Track> {
+        self.tracks.iter_mut().find(|track| track.number == number)
+    }
+
+    /// Writes a frame of `track` as a SimpleBlock
+    ///
+    /// # Arguments
+    /// * `track` - Track number
+    /// * `timestamp_ms` - Presentation time since the start of the file
+    /// * `keyframe` - Whether the frame can be decoded on its own
+    /// * `data` - Frame in the track's codec format
+    pub fn write_block(
+        &mut self,
+        track: u64,
+        timestamp_ms: i64,
+        keyframe: bool,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let entry = self
+            .track_mut(track)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Unknown track"))?;
+        entry.blocks += 1;
+
+        let starts_cluster = self.cluster.as_ref().is_none_or(|cluster| {
+            !(0..=MAX_CLUSTER_DURATION_MS).contains(&(timestamp_ms - cluster.timestamp_ms))
+        });
+        if starts_cluster {
+            self.flush_cluster()?;
+        }
+        let cluster = self
+            .cluster
+            .get_or_insert_with(|| PendingCluster::new(timestamp_ms.max(0)));
+
+        let mut block = Vec::with_capacity(data.len() + 4);
+        write_vint(&mut block, track);
+        block.extend_from_slice(&((timestamp_ms - cluster.timestamp_ms) as i16).to_be_bytes());
+        block.push(if keyframe { 0x80 } else { 0x00 });
+        block.extend_from_slice(data);
+        write_element(&mut cluster.body, SIMPLE_BLOCK, &block);
+
+        self.last_timestamp_ms = self.last_timestamp_ms.max(timestamp_ms);
+        Ok(())
+    }
+
+    /// Flushes the last cluster and completes the headers
+    ///
+    /// Tracks that received no blocks are left out of the final Tracks
+    /// element.
+    ///
+    /// # Errors
+    /// If writing fails or the tracks do not fit in the reserved space
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush_cluster()?;
+        let end = self.out.stream_position()?;
+
+        let tracks: Vec<Track> = self
+            .tracks
+            .iter()
+            .filter(|track| track.blocks > 0)
+            .cloned()
+            .collect();
+        self.out.seek(SeekFrom::Start(self.tracks_offset))?;
+        self.out.write_all(&reserved_tracks(&tracks)?)?;
+
+        self.out.seek(SeekFrom::Start(self.duration_offset))?;
+        self.out
+            .write_all(&(self.last_timestamp_ms as f64).to_be_bytes())?;
+
+        let mut segment_size = vec![0x01];
+        segment_size.extend_from_slice(&(end - self.segment_start).to_be_bytes()[1..]);
+        self.out.seek(SeekFrom::Start(self.segment_start - 8))?;
+        self.out.write_all(&segment_size)?;
+
+        self.out.seek(SeekFrom::Start(end))?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn flush_cluster(&mut self) -> io::Result<()> {
+        if let Some(cluster) = self.cluster.take() {
+            let mut element = Vec::with_capacity(cluster.body.len() + 12);
+            write_element(&mut element, CLUSTER, &cluster.body);
+            self.out.write_all(&element)?;
+        }
+        Ok(())
+    }
+}
+
+fn ebml_header() -> Vec<u8> {
+    let mut body = Vec::new();
+    write_uint(&mut body, EBML_VERSION, 1);
+    write_uint(&mut body, EBML_READ_VERSION, 1);
+    write_uint(&mut body, EBML_MAX_ID_LENGTH, 4);
+    write_uint(&mut body, EBML_MAX_SIZE_LENGTH, 8);
+    write_element(&mut body, DOC_TYPE, b"matroska");
+    write_uint(&mut body, DOC_TYPE_VERSION, 4);
+    write_uint(&mut body, DOC_TYPE_READ_VERSION, 2);
+    body
+}
+
+/// Builds the Info element
+///
+/// # Returns
+/// The element and the position of its Duration value
+fn info_element() -> (Vec<u8>, usize) {
+    let mut body = Vec::new();
+    write_uint(&mut body, TIMESTAMP_SCALE, 1_000_000);
+    write_element(&mut body, MUXING_APP, b"RoomRTC");
+    write_element(&mut body, WRITING_APP, b"RoomRTC");
+    write_id(&mut body, DURATION);
+    write_vint(&mut body, 8);
+    let duration_position = body.len();
+    body.extend_from_slice(&0f64.to_be_bytes());
+
+    let mut info = Vec::new();
+    write_id(&mut info, INFO);
+    write_vint(&mut info, body.len() as u64);
+    let duration_position = info.len() + duration_position;
+    info.extend_from_slice(&body);
+    (info, duration_position)
+}
+
+/// Builds the Tracks element padded with a Void to the reserved size
+fn reserved_tracks(tracks: &[Track]) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    for track in tracks {
+        write_element(&mut body, TRACK_ENTRY, &track_entry(track));
+    }
+    let mut element = Vec::with_capacity(TRACKS_RESERVED_BYTES);
+    write_element(&mut element, TRACKS, &body);
+
+    // A Void needs at least an ID and a one byte size
+    if element.len() + 2 > TRACKS_RESERVED_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Track headers exceed the reserved space",
+        ));
+    }
+    let padding = TRACKS_RESERVED_BYTES - element.len();
+    write_void(&mut element, padding);
+    Ok(element)
+}
+
+fn track_entry(track: &Track) -> Vec<u8> {
+    let mut body = Vec::new();
+    write_uint(&mut body, TRACK_NUMBER, track.number);
+    write_uint(&mut body, TRACK_UID, track.number);
+    write_element(&mut body, NAME, track.name.as_bytes());
+    write_uint(&mut body, FLAG_LACING, 0);
+    write_element(&mut body, CODEC_ID, track.codec_id.as_bytes());
+    if let Some(codec_private) = &track.codec_private {
+        write_element(&mut body, CODEC_PRIVATE, codec_private);
+    }
+
+    match track.kind {
+        TrackKind::Video { width, height } => {
+            write_uint(&mut body, TRACK_TYPE, 1);
+            let mut video = Vec::new();
+            write_uint(&mut video, PIXEL_WIDTH, u64::from(width));
+            write_uint(&mut video, PIXEL_HEIGHT, u64::from(height));
+            write_element(&mut body, VIDEO, &video);
+        }
+        TrackKind::Audio {
+            sampling_frequency,
+            channels,
+        } => {
+            write_uint(&mut body, TRACK_TYPE, 2);
+            let mut audio = Vec::new();
+            write_element(
+                &mut audio,
+                SAMPLING_FREQUENCY,
+                &sampling_frequency.to_be_bytes(),
+            );
+            write_uint(&mut audio, CHANNELS, u64::from(channels));
+            write_element(&mut body, AUDIO, &audio);
+        }
+    }
+    body
+}
+
+/// Writes a Void element occupying exactly `len` bytes (`len` >= 2)
+fn write_void(out: &mut Vec<u8>, len: usize) {
+    write_id(out, VOID);
+    if len < 9 {
+        out.push(0x80 | (len - 2) as u8);
+        out.resize(out.len() + len - 2, 0);
+    } else {
+        out.push(0x01);
+        out.extend_from_slice(&((len - 9) as u64).to_be_bytes()[1..]);
+        out.resize(out.len() + len - 9, 0);
+    }
+}
+
+fn write_element(out: &mut Vec<u8>, id: u32, body: &[u8]) {
+    write_id(out, id);
+    write_vint(out, body.len() as u64);
+    out.extend_from_slice(body);
+}
+
+fn write_uint(out: &mut Vec<u8>, id: u32, value: u64) {
+    let bytes = value.to_be_bytes();
+    let skip = bytes.iter().take(7).take_while(|&&byte| byte == 0).count();
+    write_element(out, id, &bytes[skip..]);
+}
+
+/// Writes an element ID, whose bytes already carry the length marker
+fn write_id(out: &mut Vec<u8>, id: u32) {
+    let bytes = id.to_be_bytes();
+    let skip = bytes.iter().take(3).take_while(|&&byte| byte == 0).count();
+    out.extend_from_slice(&bytes[skip..]);
+}
+
+/// Writes a variable length integer in the fewest bytes that hold it
+fn write_vint(out: &mut Vec<u8>, value: u64) {
+    // All ones is reserved for "unknown", so each length holds one less value
+    let len = (1..=8)
+        .find(|&len| value < (1u64 << (7 * len)) - 1)
+        .unwrap_or(8);
+    let marked = value | (1u64 << (7 * len));
+    out.extend_from_slice(&marked.to_be_bytes()[8 - len..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vint_encoding() {
+        let mut out = Vec::new();
+        write_vint(&mut out, 1);
+        write_vint(&mut out, 127);
+        write_vint(&mut out, 300);
+        assert_eq!(out, vec![0x81, 0x40, 0x7F, 0x41, 0x2C]);
+    }
+
+    #[test]
+    fn test_void_fills_exact_length() {
+        for len in [2, 8, 9, 10, 2048] {
+            let mut out = Vec::new();
+            write_void(&mut out, len);
+            assert_eq!(out.len(), len);
+            assert_eq!(out[0], 0xEC);
+        }
+    }
+}
@@ -0,0 +1,474 @@
+//! Call recording
+//!
+//! Muxes the media of a call into a Matroska file: the remote H.264 video
+//! and Opus audio and, optionally, the local ones. The media threads tap
+//! every frame they decode (remote) or encode (local) and hand its
+//! compressed data to the shared [`Recorder`], so recording needs no extra
+//! encoding work.
+
+mod avc;
+mod matroska;
+
+use matroska::{MatroskaWriter, Track, TrackKind};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+/// Sample rate Opus is always stored at in Matroska
+const OPUS_SAMPLE_RATE: u32 = 48_000;
+
+/// Media tracks a recording can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RecordedTrack {
+    RemoteVideo,
+    RemoteAudio,
+    LocalVideo,
+    LocalAudio,
+}
+
+impl RecordedTrack {
+    /// Matroska track number
+    fn number(self) -> u64 {
+        match self {
+            RecordedTrack::RemoteVideo => 1,
+            RecordedTrack::RemoteAudio => 2,
+            RecordedTrack::LocalVideo => 3,
+            RecordedTrack::LocalAudio => 4,
+        }
+    }
+
+    fn is_local(self) -> bool {
+        matches!(self, RecordedTrack::LocalVideo | RecordedTrack::LocalAudio)
+    }
+
+    fn track(self) -> Track {
+        let (name, codec_id, kind) = match self {
+            RecordedTrack::RemoteVideo => ("Remote video", "V_MPEG4/ISO/AVC", video_kind()),
+            RecordedTrack::RemoteAudio => ("Remote audio", "A_OPUS", audio_kind()),
+            RecordedTrack::LocalVideo => ("Local video", "V_MPEG4/ISO/AVC", video_kind()),
+            RecordedTrack::LocalAudio => ("Local audio", "A_OPUS", audio_kind()),
+        };
+        Track {
+            number: self.number(),
+            name: name.to_string(),
+            codec_id,
+            codec_private: None,
+            kind,
+            blocks: 0,
+        }
+    }
+}
+
+fn video_kind() -> TrackKind {
+    TrackKind::Video {
+        width: 0,
+        height: 0,
+    }
+}
+
+fn audio_kind() -> TrackKind {
+    TrackKind::Audio {
+        sampling_frequency: f64::from(OPUS_SAMPLE_RATE),
+        channels: 1,
+    }
+}
+
+/// A recording in progress
+struct Recording {
+    writer: MatroskaWriter<BufWriter<File>>,
+    started: Instant,
+}
+
+impl Recording {
+    fn timestamp_ms(&self) -> i64 {
+        self.started.elapsed().as_millis() as i64
+    }
+
+    /// Whether a video track still waits for a keyframe to start with
+    fn needs_keyframe(&mut self, track: RecordedTrack) -> bool {
+        self.writer
+            .track_mut(track.number())
+            .is_some_and(|track| track.codec_private.is_none())
+    }
+
+    fn record_video(
+        &mut self,
+        track: RecordedTrack,
+        access_unit: &[u8],
+        width: u32,
+        height: u32,
+    ) -> std::io::Result<()> {
+        let timestamp_ms = self.timestamp_ms();
+        let nals = avc::split_annex_b(access_unit);
+        let keyframe = avc::is_keyframe(&nals);
+        let Some(entry) = self.writer.track_mut(track.number()) else {
+            return Ok(());
+        };
+
+        // Frames before the first keyframe cannot be decoded from the file
+        if entry.codec_private.is_none() {
+            let (Some(sps), Some(pps)) = avc::parameter_sets(&nals) else {
+                return Ok(());
+            };
+            if !keyframe {
+                return Ok(());
+            }
+            entry.codec_private = avc::decoder_config(sps, pps);
+            if entry.codec_private.is_none() {
+                return Ok(());
+            }
+        }
+        entry.kind = TrackKind::Video { width, height };
+
+        self.writer.write_block(
+            track.number(),
+            timestamp_ms,
+            keyframe,
+            &avc::length_prefixed(&nals),
+        )
+    }
+
+    fn record_audio(&mut self, track: RecordedTrack, packet: &[u8]) -> std::io::Result<()> {
+        let timestamp_ms = self.timestamp_ms();
+        let Some(entry) = self.writer.track_mut(track.number()) else {
+            return Ok(());
+        };
+        let Some(&toc) = packet.first() else {
+            return Ok(());
+        };
+
+        if entry.codec_private.is_none() {
+            let channels = if toc & 0x04 != 0 { 2 } else { 1 };
+            entry.codec_private = Some(opus_head(channels));
+            entry.kind = TrackKind::Audio {
+                sampling_frequency: f64::from(OPUS_SAMPLE_RATE),
+                channels,
+            };
+        }
+
+        self.writer
+            .write_block(track.number(), timestamp_ms, true, packet)
+    }
+}
+
+/// Builds the Opus identification header used as the track's codec private data
+fn opus_head(channels: u8) -> Vec<u8> {
+    let mut head = b"OpusHead".to_vec();
+    head.push(1); // version
+    head.push(channels);
+    head.extend_from_slice(&0u16.to_le_bytes()); // pre-skip
+    head.extend_from_slice(&OPUS_SAMPLE_RATE.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family
+    head
+}
+
+/// Shared handle to the call recording
+///
+/// Cloned into every media thread. While no recording is running the
+/// `record_*` calls do nothing, so recording can start and stop at any
+/// point of the call.
+#[derive(Clone, Default)]
+pub(super) struct Recorder {
+    recording: Arc<Mutex<Option<Recording>>>,
+}
+
+impl Recorder {
+    fn lock(&self) -> MutexGuard<'_, Option<Recording>> {
+        self.recording
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Starts recording to a new Matroska file at `path`
+    ///
+    /// Video tracks start at their next keyframe.
+    ///
+    /// # Arguments
+    /// * `path` - File to create, replacing any existing one
+    /// * `include_local` - Also record the local camera and microphone
+    ///
+    /// # Errors
+    /// If a recording is already running or the file cannot be written
+    pub fn start(&self, path: &Path, include_local: bool) -> Result<(), String> {
+        let mut recording = self.lock();
+        if recording.is_some() {
+            return Err("A recording is already in progress".to_string());
+        }
+
+        let tracks = [
+            RecordedTrack::RemoteVideo,
+            RecordedTrack::RemoteAudio,
+            RecordedTrack::LocalVideo,
+            RecordedTrack::LocalAudio,
+        ]
+        .into_iter()
+        .filter(|track| include_local || !track.is_local())
+        .map(RecordedTrack::track)
+        .collect();
+
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let writer = MatroskaWriter::new(BufWriter::new(file), tracks)
+            .map_err(|e| format!("Failed to write recording header: {}", e))?;
+
+        *recording = Some(Recording {
+            writer,
+            started: Instant::now(),
+        });
+        Ok(())
+    }
+
+    /// Stops the recording, flushing and finalizing the file
+    ///
+    /// # Errors
+    /// If no recording is running or the file cannot be finalized
+    pub fn stop(&self) -> Result<(), String> {
+        let recording = self
+            .lock()
+            .take()
+            .ok_or_else(|| "No recording in progress".to_string())?;
+
+        recording
+            .writer
+            .finish()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to finalize recording: {}", e))
+    }
+
+    /// Returns whether a recording is running
+    pub fn is_recording(&self) -> bool {
+        self.lock().is_some()
+    }
+
+    /// Returns whether `track` is recorded but still waits for a keyframe
+    ///
+    /// The media threads use this to request a keyframe right away instead
+    /// of waiting for the next periodic one.
+    pub fn needs_keyframe(&self, track: RecordedTrack) -> bool {
+        self.lock()
+            .as_mut()
+            .is_some_and(|recording| recording.needs_keyframe(track))
+    }
+
+    /// Records an H.264 access unit
+    ///
+    /// # Arguments
+    /// * `track` - Video track the frame belongs to
+    /// * `access_unit` - Annex B access unit
+    /// * `width` - Frame width in pixels
+    /// * `height` - Frame height in pixels
+    ///
+    /// # Errors
+    /// If writing fails; the recording is then abandoned
+    pub fn record_video(
+        &self,
+        track: RecordedTrack,
+        access_unit: &[u8],
+        width: i32,
+        height: i32,
+    ) -> Result<(), String> {
+        self.record(|recording| {
+            recording.record_video(
+                track,
+                access_unit,
+                width.max(0) as u32,
+                height.max(0) as u32,
+            )
+        })
+    }
+
+    /// Records an Opus packet
+    ///
+    /// # Errors
+    /// If writing fails; the recording is then abandoned
+    pub fn record_audio(&self, track: RecordedTrack, packet: &[u8]) -> Result<(), String> {
+        self.record(|recording| recording.record_audio(track, packet))
+    }
+
+    fn record(
+        &self,
+        write: impl FnOnce(&mut Recording) -> std::io::Result<()>,
+    ) -> Result<(), String> {
+        let mut recording = self.lock();
+        let Some(active) = recording.as_mut() else {
+            return Ok(());
+        };
+
+        if let Err(e) = write(active) {
+            *recording = None;
+            return Err(format!("Recording stopped: {}", e));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matroska::*;
+    use super::*;
+
+    /// Reads an EBML variable length integer, removing its length marker
+    fn read_vint(data: &[u8]) -> (u64, usize) {
+        let len = data[0].leading_zeros() as usize + 1;
+        let value = data[..len]
+            .iter()
+            .fold(0u64, |value, &byte| (value << 8) | u64::from(byte));
+        (value & ((1u64 << (7 * len)) - 1), len)
+    }
+
+    /// Splits EBML data into its elements
+    fn elements(mut data: &[u8]) -> Vec<(u32, &[u8])> {
+        let mut found = Vec::new();
+        while !data.is_empty() {
+            let id_len = data[0].leading_zeros() as usize + 1;
+            let id = data[..id_len]
+                .iter()
+                .fold(0u32, |id, &byte| (id << 8) | u32::from(byte));
+            let (size, size_len) = read_vint(&data[id_len..]);
+            let start = id_len + size_len;
+            let end = start + size as usize;
+            found.push((id, &data[start..end]));
+            data = &data[end..];
+        }
+        found
+    }
+
+    fn children(data: &[u8], id: u32) -> Vec<&[u8]> {
+        elements(data)
+            .into_iter()
+            .filter(|(child, _)| *child == id)
+            .map(|(_, body)| body)
+            .collect()
+    }
+
+    fn child(data: &[u8], id: u32) -> &[u8] {
+        children(data, id)[0]
+    }
+
+    fn uint(data: &[u8]) -> u64 {
+        data.iter()
+            .fold(0u64, |value, &byte| (value << 8) | u64::from(byte))
+    }
+
+    fn access_unit(nals: &[&[u8]]) -> Vec<u8> {
+        nals.iter()
+            .flat_map(|nal| [&[0, 0, 0, 1][..], nal].concat())
+            .collect()
+    }
+
+    #[test]
+    fn test_recording_track_structure() {
+        let path =
+            std::env::temp_dir().join(format!("roomrtc-recording-{}.mkv", std::process::id()));
+        let recorder = Recorder::default();
+        recorder.start(&path, false).unwrap();
+        assert!(recorder.is_recording());
+        assert!(recorder.needs_keyframe(RecordedTrack::RemoteVideo));
+        assert!(!recorder.needs_keyframe(RecordedTrack::LocalVideo));
+
+        let sps: &[u8] = &[0x67, 0x42, 0xC0, 0x1F, 0xAB];
+        let pps: &[u8] = &[0x68, 0xCE, 0x3C, 0x80];
+        let idr: &[u8] = &[0x65, 0x88, 0x84, 0x21];
+        let slice: &[u8] = &[0x41, 0x9A, 0x02, 0x11];
+        let video = RecordedTrack::RemoteVideo;
+        // A frame before the first keyframe is dropped
+        recorder
+            .record_video(video, &access_unit(&[slice]), 640, 480)
+            .unwrap();
+        recorder
+            .record_video(video, &access_unit(&[sps, pps, idr]), 640, 480)
+            .unwrap();
+        recorder
+            .record_video(video, &access_unit(&[slice]), 640, 480)
+            .unwrap();
+        recorder
+            .record_video(video, &access_unit(&[slice]), 640, 480)
+            .unwrap();
+        assert!(!recorder.needs_keyframe(video));
+
+        for _ in 0..3 {
+            recorder
+                .record_audio(RecordedTrack::RemoteAudio, &[0xFC, 0x01, 0x02])
+                .unwrap();
+        }
+        // Local media is not recorded unless requested
+        recorder
+            .record_audio(RecordedTrack::LocalAudio, &[0xFC, 0x03])
+            .unwrap();
+
+        recorder.stop().unwrap();
+        assert!(!recorder.is_recording());
+        let data = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let top = elements(&data);
+        assert_eq!(top.len(), 2);
+        assert_eq!(child(top[0].1, 0x4282), b"matroska");
+        assert_eq!(top[1].0, SEGMENT);
+        let segment = top[1].1;
+        assert_eq!(children(segment, INFO).len(), 1);
+
+        let entries = children(child(segment, TRACKS), TRACK_ENTRY);
+        assert_eq!(entries.len(), 2);
+
+        let video_entry = entries[0];
+        assert_eq!(uint(child(video_entry, TRACK_NUMBER)), 1);
+        assert_eq!(uint(child(video_entry, TRACK_TYPE)), 1);
+        assert_eq!(child(video_entry, CODEC_ID), b"V_MPEG4/ISO/AVC");
+        assert_eq!(
+            &child(video_entry, CODEC_PRIVATE)[..4],
+            &[1, 0x42, 0xC0, 0x1F]
+        );
+        let video_settings = child(video_entry, VIDEO);
+        assert_eq!(uint(child(video_settings, PIXEL_WIDTH)), 640);
+        assert_eq!(uint(child(video_settings, PIXEL_HEIGHT)), 480);
+
+        let audio_entry = entries[1];
+        assert_eq!(uint(child(audio_entry, TRACK_NUMBER)), 2);
+        assert_eq!(uint(child(audio_entry, TRACK_TYPE)), 2);
+        assert_eq!(child(audio_entry, CODEC_ID), b"A_OPUS");
+        assert_eq!(&child(audio_entry, CODEC_PRIVATE)[..8], b"OpusHead");
+        assert_eq!(uint(child(child(audio_entry, AUDIO), CHANNELS)), 2);
+
+        let blocks: Vec<&[u8]> = children(segment, CLUSTER)
+            .into_iter()
+            .flat_map(|cluster| children(cluster, SIMPLE_BLOCK))
+            .collect();
+        let video_blocks: Vec<&[u8]> = blocks.iter().copied().filter(|b| b[0] == 0x81).collect();
+        let audio_blocks: Vec<&[u8]> = blocks.iter().copied().filter(|b| b[0] == 0x82).collect();
+        assert_eq!(
+            (video_blocks.len(), audio_blocks.len(), blocks.len()),
+            (3, 3, 6)
+        );
+        // The first video block is the keyframe, stored with length prefixed NAL units
+        assert_eq!(video_blocks[0][3], 0x80);
+        assert_eq!(video_blocks[1][3], 0x00);
+        assert_eq!(&video_blocks[1][4..], &[0, 0, 0, 4, 0x41, 0x9A, 0x02, 0x11]);
+    }
+
+    #[test]
+    fn test_recording_start_stop_errors() {
+        let path = std::env::temp_dir().join(format!(
+            "roomrtc-recording-twice-{}.mkv",
+            std::process::id()
+        ));
+        let recorder = Recorder::default();
+
+        assert!(recorder.stop().is_err());
+        // Frames arriving while not recording are ignored
+        recorder
+            .record_audio(RecordedTrack::RemoteAudio, &[0xFC])
+            .unwrap();
+
+        recorder.start(&path, true).unwrap();
+        assert!(recorder.start(&path, true).is_err());
+        assert!(recorder.needs_keyframe(RecordedTrack::LocalVideo));
+        recorder.stop().unwrap();
+        assert!(recorder.stop().is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,19 +1,31 @@
 //! Receive thread functionality for secure P2P session
 
 use super::control_message::ControlMessage;
+use super::recording::{RecordedTrack, Recorder};
+use super::secure_session::video_jitter_buffer;
+use super::stream_router::{Route, StreamRouter};
+use super::video_decode_thread::{self, VideoDecodeThreadParams};
 use logging::Logger;
-use media::{AudioFrame, OpusDecoder};
+use media::{AudioFrame, H264Decoder, OpusDecoder, VideoFrame};
+use network::codec::lip_sync::{self, NtpRtpMapping, SyncSample};
+use network::codec::packetizers::rtx::RTX_PAYLOAD_TYPES;
 use network::codec::rtp::control_payload;
 use network::{
-    JitterBuffer, OpusRtpDepacketizer, PacketHandler,
-    SecureUdpTransport,
+    JitterBuffer, JitterBufferConfig, OpusRtpDepacketizer, PacketHandler, PopResult,
+    RtxRtpDepacketizer, SecureUdpTransport,
 };
+use std::collections::HashMap;
 use std::sync::{
     Arc, Mutex,
-    mpsc::{Sender, SyncSender},
+    mpsc::{Receiver, Sender, SyncSender, sync_channel},
 };
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two lip-sync offset updates
+const LIP_SYNC_INTERVAL: Duration = Duration::from_millis(500);
+/// Video payload types offered in SDP (H264, VP8, VP9)
+const VIDEO_PAYLOAD_TYPES: [u8; 3] = [96, 97, 98];
 
 /// Parameters for receive thread
 pub struct RecvThreadParams {
@@ -27,6 +39,9 @@ pub struct RecvThreadParams {
     pub logger: Logger,
     pub dtls_engine: Arc<Mutex<Option<network::security::dtls::DtlsEngine>>>,
     pub file_session: Arc<Mutex<Option<super::file_session::FileSession>>>,
+    /// Decoded frames of the remote video streams besides the primary one, by SSRC
+    pub remote_video_streams: Arc<Mutex<HashMap<u32, Receiver<VideoFrame>>>>,
+    pub recorder: Recorder,
 }
 
 struct RecvThreadState {
@@ -35,6 +50,12 @@ struct RecvThreadState {
     frames_decoded: u64,
     audio_packets_received: u64,
     audio_frames_decoded: u64,
+    /// Audio packets reported lost by the jitter buffer and not yet concealed
+    audio_packets_lost: u32,
+    /// SSRCs of the remote audio and video streams (for lip-sync)
+    audio_ssrc: Option<u32>,
+    video_ssrc: Option<u32>,
+    last_lip_sync: Option<Instant>,
 }
 
 pub(super) fn run_recv_thread(params: RecvThreadParams) {
@@ -43,12 +64,27 @@ pub(super) fn run_recv_thread(params: RecvThreadParams) {
         .info("Secure RECV thread started (video + audio)");
 
     let mut audio_depacketizer = OpusRtpDepacketizer::new();
+    let rtx_depacketizer = RtxRtpDepacketizer::new(&RTX_PAYLOAD_TYPES);
+    let mut router = StreamRouter::new(
+        &VIDEO_PAYLOAD_TYPES,
+        Arc::clone(&params.jitter_buffer),
+        video_jitter_buffer,
+    );
+    // PLC reports lost audio packets so they can be concealed (with FEC)
+    let mut audio_jitter_buffer = JitterBuffer::with_config(JitterBufferConfig {
+        enable_plc: true,
+        ..JitterBufferConfig::audio_defaults()
+    });
     let mut state = RecvThreadState {
         packets_received: 0,
         packets_released_from_buffer: 0,
         frames_decoded: 0,
         audio_packets_received: 0,
         audio_frames_decoded: 0,
+        audio_packets_lost: 0,
+        audio_ssrc: None,
+        video_ssrc: None,
+        last_lip_sync: None,
     };
 
     loop {
@@ -63,10 +99,22 @@ pub(super) fn run_recv_thread(params: RecvThreadParams) {
                         continue;
                     }
 
+                    if rtx_depacketizer.is_rtx(packet.header.payload_type) {
+                        // Retransmitted video packet: restore it into the video stream
+                        if let Some(original) = state
+                            .video_ssrc
+                            .and_then(|ssrc| rtx_depacketizer.depacketize(&packet, ssrc))
+                        {
+                            add_to_jitter_buffer(&params.jitter_buffer, original, &params.logger);
+                        }
+                        continue;
+                    }
+
                     // Distinguish between video (96) and audio (111) based on payload type
                     if packet.header.payload_type == 111 {
                         // Audio packet
                         state.audio_packets_received += 1;
+                        state.audio_ssrc = Some(packet.header.ssrc);
 
                         track_packet_stats(
                             &params.audio_packet_handler,
@@ -75,20 +123,55 @@ pub(super) fn run_recv_thread(params: RecvThreadParams) {
                             "Audio",
                         );
 
-                        process_audio_packet(&packet, &params, &mut audio_depacketizer, &mut state);
+                        audio_jitter_buffer.push(packet);
                     } else {
-                        // Video packet
+                        // Video packet: route it to the pipeline of its stream
+                        let ssrc = packet.header.ssrc;
+                        let (jitter_buffer, primary) =
+                            match router.route(ssrc, packet.header.payload_type) {
+                                Route::Existing(jitter_buffer) => {
+                                    let primary = state.video_ssrc == Some(ssrc);
+                                    (jitter_buffer, primary)
+                                }
+                                Route::New {
+                                    jitter_buffer,
+                                    primary: true,
+                                } => {
+                                    state.video_ssrc = Some(ssrc);
+                                    (jitter_buffer, true)
+                                }
+                                Route::New {
+                                    jitter_buffer,
+                                    primary: false,
+                                } => {
+                                    if !start_stream_pipeline(ssrc, &jitter_buffer, &params) {
+                                        router.remove(ssrc);
+                                        continue;
+                                    }
+                                    (jitter_buffer, false)
+                                }
+                                Route::UnknownPayloadType => {
+                                    params.logger.debug(&format!(
+                                        "Dropping RTP packet with unknown payload type {}",
+                                        packet.header.payload_type
+                                    ));
+                                    continue;
+                                }
+                            };
+
                         state.packets_received += 1;
                         packets_this_batch += 1;
                         log_packet_received(&params.logger, &state);
 
-                        track_packet_stats(
-                            &params.packet_handler,
-                            packet.header.sequence_number,
-                            &params.logger,
-                            "Video",
-                        );
-                        add_to_jitter_buffer(&params.jitter_buffer, packet, &params.logger);
+                        if primary {
+                            track_packet_stats(
+                                &params.packet_handler,
+                                packet.header.sequence_number,
+                                &params.logger,
+                                "Video",
+                            );
+                        }
+                        add_to_jitter_buffer(&jitter_buffer, packet, &params.logger);
                     }
                 }
                 Ok(None) => {
@@ -103,6 +186,10 @@ pub(super) fn run_recv_thread(params: RecvThreadParams) {
             }
         }
 
+        process_remote_bye(&mut audio_jitter_buffer, &mut router, &params, &mut state);
+        play_out_audio(&mut audio_jitter_buffer, &params, &mut audio_depacketizer, &mut state);
+        synchronize_streams(&mut audio_jitter_buffer, &params, &mut state);
+
         if packets_this_batch == 0 {
             // Only sleep if no packets were received
             thread::sleep(Duration::from_micros(100));
@@ -169,11 +256,8 @@ fn process_incoming_sctp(params: &RecvThreadParams) -> Result<(), network::Netwo
                                         let packets = dtls_eng.take_pending_packets();
                                         let mut transport_guard2 = params.transport.lock().unwrap();
                                         if let Some(transport) = transport_guard2.as_mut() {
-                                            let remote_addr = transport.remote_addr();
                                             for packet in packets {
-                                                let _ = transport
-                                                    .socket()
-                                                    .send_to(&packet, remote_addr);
+                                                let _ = transport.send_dtls(&packet);
                                             }
                                         }
                                     }
@@ -341,7 +425,293 @@ fn add_to_jitter_buffer(
         .push(packet);
 }
 
-/// Process audio packet: depacketize and decode immediately (no jitter buffer for audio)
+/// Starts the decode pipeline of a secondary remote video stream
+///
+/// Frames decoded from the stream are published in
+/// `params.remote_video_streams` under its SSRC.
+///
+/// # Returns
+/// False if the pipeline could not be started
+fn start_stream_pipeline(
+    ssrc: u32,
+    jitter_buffer: &Arc<Mutex<JitterBuffer>>,
+    params: &RecvThreadParams,
+) -> bool {
+    let decoder = match H264Decoder::new(params.logger.clone()) {
+        Ok(decoder) => decoder,
+        Err(e) => {
+            params.logger.error(&format!(
+                "Failed to create decoder for video stream {}: {}",
+                ssrc, e
+            ));
+            return false;
+        }
+    };
+
+    let (tx_decode, rx_decode) = sync_channel::<VideoFrame>(12);
+    let decode_params = VideoDecodeThreadParams {
+        jitter_buffer: Arc::clone(jitter_buffer),
+        decoder: Arc::new(Mutex::new(decoder)),
+        tx_decode,
+        transport: Arc::clone(&params.transport),
+        recorder: None,
+        logger: params.logger.clone(),
+    };
+    if let Err(e) = thread::Builder::new()
+        .name(format!("video-decode-{}", ssrc))
+        .spawn(move || video_decode_thread::run_video_decode_thread(decode_params))
+    {
+        params.logger.error(&format!(
+            "Failed to spawn decode thread for video stream {}: {}",
+            ssrc, e
+        ));
+        return false;
+    }
+
+    params
+        .remote_video_streams
+        .lock()
+        .unwrap_or_else(|poisoned| {
+            params
+                .logger
+                .error("Remote video streams mutex poisoned, recovering");
+            poisoned.into_inner()
+        })
+        .insert(ssrc, rx_decode);
+    params.logger.info(&format!(
+        "New remote video stream {}, decoder started",
+        ssrc
+    ));
+    true
+}
+
+/// Stop playing out the remote streams the peer ended with RTCP BYE
+fn process_remote_bye(
+    audio_jitter_buffer: &mut JitterBuffer,
+    router: &mut StreamRouter,
+    params: &RecvThreadParams,
+    state: &mut RecvThreadState,
+) {
+    let bye_ssrcs = params
+        .transport
+        .lock()
+        .unwrap_or_else(|poisoned| {
+            params
+                .logger
+                .error("Transport mutex poisoned in receive thread, recovering");
+            poisoned.into_inner()
+        })
+        .as_mut()
+        .map(|transport| transport.take_bye_ssrcs())
+        .unwrap_or_default();
+
+    for ssrc in bye_ssrcs {
+        if router.remove(ssrc).is_some_and(|stream| !stream.primary) {
+            // Dropping the stream's buffer and frame receiver stops its decoder
+            params
+                .remote_video_streams
+                .lock()
+                .unwrap_or_else(|poisoned| {
+                    params
+                        .logger
+                        .error("Remote video streams mutex poisoned, recovering");
+                    poisoned.into_inner()
+                })
+                .remove(&ssrc);
+            params
+                .logger
+                .info(&format!("Remote video stream {} ended with BYE", ssrc));
+            continue;
+        }
+
+        let mut video_jitter_buffer = params.jitter_buffer.lock().unwrap_or_else(|poisoned| {
+            params
+                .logger
+                .error("Jitter buffer mutex poisoned on BYE, recovering");
+            poisoned.into_inner()
+        });
+        let Some(control_msg) =
+            handle_remote_bye(ssrc, state, &mut video_jitter_buffer, audio_jitter_buffer)
+        else {
+            continue;
+        };
+        drop(video_jitter_buffer);
+
+        params.logger.info(&format!(
+            "Remote stream {} ended with BYE: {:?}",
+            ssrc, control_msg
+        ));
+
+        let packet_handler = match control_msg {
+            ControlMessage::CameraOff => &params.packet_handler,
+            _ => &params.audio_packet_handler,
+        };
+        packet_handler
+            .lock()
+            .unwrap_or_else(|poisoned| {
+                params
+                    .logger
+                    .error("Packet handler mutex poisoned, recovering");
+                poisoned.into_inner()
+            })
+            .clear();
+
+        if let Err(e) = params.tx_control.send(control_msg) {
+            params
+                .logger
+                .error(&format!("Failed to forward control message: {}", e));
+        }
+    }
+}
+
+/// Clears the buffer of the remote stream `ssrc` belongs to
+///
+/// # Returns
+/// The control message telling the UI the stream stopped, or None if
+/// `ssrc` is not a stream being received
+fn handle_remote_bye(
+    ssrc: u32,
+    state: &mut RecvThreadState,
+    video_jitter_buffer: &mut JitterBuffer,
+    audio_jitter_buffer: &mut JitterBuffer,
+) -> Option<ControlMessage> {
+    if state.video_ssrc == Some(ssrc) {
+        video_jitter_buffer.clear();
+        state.video_ssrc = None;
+        Some(ControlMessage::CameraOff)
+    } else if state.audio_ssrc == Some(ssrc) {
+        audio_jitter_buffer.clear();
+        state.audio_ssrc = None;
+        state.audio_packets_lost = 0;
+        Some(ControlMessage::AudioOff)
+    } else {
+        None
+    }
+}
+
+/// Align audio and video playout using the peer's Sender Report clock mappings
+///
+/// The stream that would play out earlier is delayed so that audio and video
+/// captured at the same time are presented together.
+fn synchronize_streams(
+    audio_jitter_buffer: &mut JitterBuffer,
+    params: &RecvThreadParams,
+    state: &mut RecvThreadState,
+) {
+    if state
+        .last_lip_sync
+        .is_some_and(|synced| synced.elapsed() < LIP_SYNC_INTERVAL)
+    {
+        return;
+    }
+    let (Some(audio_ssrc), Some(video_ssrc)) = (state.audio_ssrc, state.video_ssrc) else {
+        return;
+    };
+    state.last_lip_sync = Some(Instant::now());
+
+    let mappings = {
+        let transport_guard = params.transport.lock().unwrap_or_else(|poisoned| {
+            params
+                .logger
+                .error("Transport mutex poisoned in lip-sync, recovering");
+            poisoned.into_inner()
+        });
+        transport_guard.as_ref().and_then(|transport| {
+            Some((
+                transport.remote_ntp_mapping(audio_ssrc)?,
+                transport.remote_ntp_mapping(video_ssrc)?,
+            ))
+        })
+    };
+    let Some((audio_mapping, video_mapping)) = mappings else {
+        return; // No Sender Reports yet
+    };
+
+    let mut video_jitter_buffer = params.jitter_buffer.lock().unwrap_or_else(|poisoned| {
+        params
+            .logger
+            .error("Jitter buffer mutex poisoned in lip-sync, recovering");
+        poisoned.into_inner()
+    });
+    let (Some(audio), Some(video)) = (
+        sync_sample(audio_jitter_buffer, audio_mapping),
+        sync_sample(&video_jitter_buffer, video_mapping),
+    ) else {
+        return;
+    };
+
+    let offsets = lip_sync::playout_offsets(&audio, &video);
+    audio_jitter_buffer.set_sync_offset(offsets.audio);
+    video_jitter_buffer.set_sync_offset(offsets.video);
+}
+
+fn sync_sample(jitter_buffer: &JitterBuffer, mapping: NtpRtpMapping) -> Option<SyncSample> {
+    let (rtp_timestamp, playout_time) = jitter_buffer.latest_playout()?;
+    Some(SyncSample {
+        mapping,
+        clock_rate: jitter_buffer.clock_rate(),
+        rtp_timestamp,
+        playout_time,
+    })
+}
+
+/// Release every audio packet whose playout time has come
+///
+/// Lost packets are concealed once the packet that follows them is
+/// released, so the decoder can recover the last one from its FEC data.
+fn play_out_audio(
+    jitter_buffer: &mut JitterBuffer,
+    params: &RecvThreadParams,
+    depacketizer: &mut OpusRtpDepacketizer,
+    state: &mut RecvThreadState,
+) {
+    loop {
+        match jitter_buffer.pop() {
+            PopResult::Packet(packet) => process_audio_packet(&packet, params, depacketizer, state),
+            PopResult::Concealed => state.audio_packets_lost += 1,
+            PopResult::Empty => break,
+        }
+    }
+}
+
+/// Produces concealment audio for the packets lost before `next_packet`
+///
+/// Only the frame right before `next_packet` can be recovered from its FEC
+/// data; earlier losses fall back to packet loss concealment.
+fn conceal_lost_audio(
+    decoder: &mut OpusDecoder,
+    next_packet: &[u8],
+    params: &RecvThreadParams,
+    state: &mut RecvThreadState,
+) {
+    let lost = std::mem::take(&mut state.audio_packets_lost);
+
+    for remaining in (0..lost).rev() {
+        let recovery = (remaining == 0).then_some(next_packet);
+        match decoder.conceal(recovery) {
+            Ok(audio_frame) => {
+                if let Err(e) = params.tx_audio_decode.try_send(audio_frame) {
+                    params
+                        .logger
+                        .error(&format!("Failed to send concealed audio to app: {}", e));
+                }
+            }
+            Err(e) => {
+                params
+                    .logger
+                    .warn(&format!("Audio concealment failed: {}", e));
+            }
+        }
+    }
+
+    params.logger.debug(&format!(
+        "🔊 RECV: Concealed {} lost audio packet(s) (fec: {})",
+        lost,
+        decoder.fec_enabled()
+    ));
+}
+
+/// Process audio packet: depacketize and decode
 fn process_audio_packet(
     packet: &network::codec::rtp::RtpPacket,
     params: &RecvThreadParams,
@@ -358,6 +728,10 @@ fn process_audio_packet(
             poisoned.into_inner()
         });
 
+        if state.audio_packets_lost > 0 {
+            conceal_lost_audio(&mut decoder, &opus_data, params, state);
+        }
+
         match decoder.decode(&opus_data) {
             Ok(Some(audio_frame)) => {
                 state.audio_frames_decoded += 1;
@@ -370,6 +744,13 @@ fn process_audio_packet(
                     ));
                 }
 
+                if let Err(e) = params
+                    .recorder
+                    .record_audio(RecordedTrack::RemoteAudio, &opus_data)
+                {
+                    params.logger.error(&e);
+                }
+
                 // Send to application
                 if let Err(e) = params.tx_audio_decode.try_send(audio_frame) {
                     params
@@ -390,3 +771,69 @@ fn process_audio_packet(
         params.logger.warn("Failed to depacketize audio packet");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use network::RtpPacket;
+    use network::codec::rtp::RtpHeader;
+
+    fn empty_state() -> RecvThreadState {
+        RecvThreadState {
+            packets_received: 0,
+            packets_released_from_buffer: 0,
+            frames_decoded: 0,
+            audio_packets_received: 0,
+            audio_frames_decoded: 0,
+            audio_packets_lost: 0,
+            audio_ssrc: None,
+            video_ssrc: None,
+            last_lip_sync: None,
+        }
+    }
+
+    #[test]
+    fn test_bye_clears_video_and_blanks_camera() {
+        let mut state = empty_state();
+        state.video_ssrc = Some(1111);
+        state.audio_ssrc = Some(2222);
+        let mut video_jitter_buffer = JitterBuffer::new();
+        let mut audio_jitter_buffer =
+            JitterBuffer::with_config(JitterBufferConfig::audio_defaults());
+        video_jitter_buffer.push(RtpPacket::new(RtpHeader::new(96, 1111), vec![1, 2, 3]));
+        audio_jitter_buffer.push(RtpPacket::new(RtpHeader::new(111, 2222), vec![4, 5]));
+
+        let event = handle_remote_bye(
+            1111,
+            &mut state,
+            &mut video_jitter_buffer,
+            &mut audio_jitter_buffer,
+        );
+
+        assert!(matches!(event, Some(ControlMessage::CameraOff)));
+        assert!(video_jitter_buffer.peek().is_none());
+        assert_eq!(state.video_ssrc, None);
+        // The audio stream keeps playing
+        assert!(audio_jitter_buffer.peek().is_some());
+        assert_eq!(state.audio_ssrc, Some(2222));
+    }
+
+    #[test]
+    fn test_bye_for_unknown_ssrc_is_ignored() {
+        let mut state = empty_state();
+        state.video_ssrc = Some(1111);
+        let mut video_jitter_buffer = JitterBuffer::new();
+        let mut audio_jitter_buffer = JitterBuffer::new();
+        video_jitter_buffer.push(RtpPacket::new(RtpHeader::new(96, 1111), vec![1]));
+
+        let event = handle_remote_bye(
+            9999,
+            &mut state,
+            &mut video_jitter_buffer,
+            &mut audio_jitter_buffer,
+        );
+
+        assert!(event.is_none());
+        assert!(video_jitter_buffer.peek().is_some());
+    }
+}
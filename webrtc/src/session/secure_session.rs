@@ -1,24 +1,35 @@
 //! Secure P2P session implementation with DTLS/SRTP
+use crate::session::chat::ChatMessage;
 use crate::session::dtls_setup;
 use crate::session::file_session::FileSession;
+use crate::session::file_transfer::FileTransferConfig;
+use crate::session::recording::Recorder;
 use crate::session::recv_thread;
 use crate::session::send_thread;
 use crate::session::video_decode_thread;
 
 use super::config::P2PConfig;
 use super::control_message::ControlMessage;
+use super::simulcast::{LayerSender, SimulcastConfig, SimulcastLayer, SimulcastSender};
+use super::video_codec::CodecSender;
 use crate::DtlsContext;
 use logging::Logger;
-use media::{AudioFrame, H264Decoder, H264Encoder, OpusDecoder, OpusEncoder, VideoFrame};
+use media::{
+    AudioConfig, AudioFrame, H264Decoder, H264Encoder, H264EncoderSettings, OpusDecoder,
+    OpusEncoder, VideoCodec, VideoFrame,
+};
+use network::codec::rtcp::transport_cc::TRANSPORT_CC_EXTENSION_ID;
 use network::codec::rtp::{RtpHeader, RtpPacket};
 use network::security::dtls::DtlsEngine;
 use network::transport::secure::UdpTransport;
 use network::{
-    H264RtpPacketizer, JitterBuffer, NetworkError, OpusRtpPacketizer, PacketHandler, Result,
-    RtpPacketizer, SecureUdpTransport,
+    DscpConfig, H264RtpPacketizer, JitterBuffer, NetworkError, OpusRtpPacketizer, PacketHandler,
+    PacketizationMode, Result, RtpPacketizer, SecureUdpTransport,
 };
-use std::net::SocketAddr;
+use std::collections::HashMap;
+use std::net::{SocketAddr, UdpSocket};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Receiver, Sender, SyncSender, channel, sync_channel};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
@@ -47,6 +58,20 @@ pub struct SecureP2PSession {
     encoder: Arc<Mutex<H264Encoder>>,
     decoder: Arc<Mutex<H264Decoder>>,
     packetizer: Arc<Mutex<H264RtpPacketizer>>,
+    /// Per-layer encoders when simulcast is enabled (replaces `encoder`)
+    simulcast: Arc<Mutex<Option<SimulcastSender>>>,
+    /// VP8/VP9 encoder when negotiation picked one of them (replaces `encoder`)
+    codec_sender: Arc<Mutex<Option<CodecSender>>>,
+    /// Extra outgoing video tracks added by renegotiation, by SSRC
+    video_tracks: Mutex<HashMap<u32, LayerSender>>,
+    /// Set to make the send thread start the next frame with a keyframe
+    keyframe_requested: Arc<AtomicBool>,
+    /// H.264 profile and NAL size limit negotiated with the remote peer
+    h264_settings: H264EncoderSettings,
+    /// Header extension ID agreed for transport-cc, applied when the transport is created
+    transport_cc_extension_id: Option<u8>,
+    /// DSCP marking per media kind, applied when the transport is created
+    dscp_config: Option<DscpConfig>,
 
     // Audio components
     audio_encoder: Arc<Mutex<OpusEncoder>>,
@@ -67,6 +92,8 @@ pub struct SecureP2PSession {
     rx_decode: Receiver<VideoFrame>,
     rx_audio_decode: Receiver<AudioFrame>,
     rx_control: Receiver<ControlMessage>,
+    /// Decoded frames of the remote video streams besides the primary one, by SSRC
+    remote_video_streams: Arc<Mutex<HashMap<u32, Receiver<VideoFrame>>>>,
 
     send_thread: Option<JoinHandle<()>>,
     recv_thread: Option<JoinHandle<()>>,
@@ -83,6 +110,9 @@ pub struct SecureP2PSession {
 
     /// File transfer session (SCTP data channels)
     file_session: Arc<Mutex<Option<FileSession>>>,
+
+    /// Recorder shared with the media threads
+    recorder: Recorder,
 }
 
 impl SecureP2PSession {
@@ -104,6 +134,13 @@ impl SecureP2PSession {
             encoder: Arc::new(Mutex::new(encoder)),
             decoder: Arc::new(Mutex::new(decoder)),
             packetizer: Arc::new(Mutex::new(packetizer)),
+            simulcast: Arc::new(Mutex::new(None)),
+            codec_sender: Arc::new(Mutex::new(None)),
+            video_tracks: Mutex::new(HashMap::new()),
+            keyframe_requested: Arc::new(AtomicBool::new(false)),
+            h264_settings: H264EncoderSettings::default(),
+            transport_cc_extension_id: Some(TRANSPORT_CC_EXTENSION_ID),
+            dscp_config: None,
             audio_encoder: Arc::new(Mutex::new(audio_encoder)),
             audio_decoder: Arc::new(Mutex::new(audio_decoder)),
             audio_packetizer: Arc::new(Mutex::new(audio_packetizer)),
@@ -118,6 +155,7 @@ impl SecureP2PSession {
             rx_decode,
             rx_audio_decode,
             rx_control,
+            remote_video_streams: Arc::new(Mutex::new(HashMap::new())),
             send_thread: None,
             recv_thread: None,
             video_decode_thread: None,
@@ -130,6 +168,7 @@ impl SecureP2PSession {
             secure_connection_established: false,
             control_sequence: Arc::new(Mutex::new(0)),
             file_session: Arc::new(Mutex::new(None)),
+            recorder: Recorder::default(),
         })
     }
 
@@ -161,10 +200,28 @@ impl SecureP2PSession {
         self.local_fingerprint.as_deref()
     }
 
+    pub fn get_remote_fingerprint(&self) -> Option<&str> {
+        self.remote_fingerprint.as_deref()
+    }
+
     pub fn has_remote_fingerprint(&self) -> bool {
         self.remote_fingerprint.is_some()
     }
 
+    /// Clones the media socket so ICE connectivity checks can run on it
+    /// before the DTLS handshake takes it over
+    pub fn clone_media_socket(&self) -> Result<UdpSocket> {
+        let udp_guard = self.udp_transport.lock().map_err(|_| {
+            NetworkError::TransportError("UDP transport mutex poisoned".to_string())
+        })?;
+        let udp = udp_guard.as_ref().ok_or_else(|| {
+            NetworkError::TransportError("UDP transport already consumed".to_string())
+        })?;
+        udp.socket().try_clone().map_err(|e| {
+            NetworkError::TransportError(format!("Failed to clone media socket: {}", e))
+        })
+    }
+
     pub fn establish_secure_connection(
         &mut self,
         remote_addr: SocketAddr,
@@ -193,10 +250,15 @@ impl SecureP2PSession {
         self.dtls_context = Some(dtls);
         *self.dtls_engine.lock().unwrap() = Some(dtls_engine);
         self.secure_connection_established = true;
+        self.set_transport_cc_extension_id(self.transport_cc_extension_id);
+        if let Some(config) = self.dscp_config {
+            self.set_dscp_config(config);
+        }
 
         self.logger
             .info("Initializing file transfer session (SCTP/Data Channels)");
-        let mut file_session = FileSession::new(!is_server);
+        let mut file_session =
+            FileSession::new(!is_server).with_transfer_config(self.config.file_transfer());
         match file_session.establish() {
             Ok(init_packet) => {
                 if !init_packet.is_empty()
@@ -213,9 +275,8 @@ impl SecureP2PSession {
                         if let Ok(mut transport_guard) = self.transport.lock()
                             && let Some(transport) = transport_guard.as_mut()
                         {
-                            let remote_addr = transport.remote_addr();
                             for packet in packets {
-                                if let Err(e) = transport.socket().send_to(&packet, remote_addr) {
+                                if let Err(e) = transport.send_dtls(&packet) {
                                     self.logger
                                         .error(&format!("Failed to send DTLS packet: {}", e));
                                 }
@@ -239,6 +300,13 @@ impl SecureP2PSession {
         self.secure_connection_established
     }
 
+    /// Set file transfer settings (e.g. a rate limit)
+    ///
+    /// Applies to the file session created by the next DTLS handshake.
+    pub fn set_file_transfer_config(&mut self, config: FileTransferConfig) {
+        self.config = self.config.clone().with_file_transfer(config);
+    }
+
     /// Send a file to the remote peer
     ///
     /// # Arguments
@@ -356,16 +424,13 @@ impl SecureP2PSession {
                             let packets = dtls_engine.take_pending_packets();
                             if let Ok(mut transport_guard) = self.transport.lock()
                                 && let Some(transport) = transport_guard.as_mut() {
-                                    let remote_addr = transport.remote_addr();
                                     for packet in packets {
-                                        transport.socket().send_to(&packet, remote_addr).map_err(
-                                            |e| {
-                                                NetworkError::TransportError(format!(
-                                                    "UDP send failed: {}",
-                                                    e
-                                                ))
-                                            },
-                                        )?;
+                                        transport.send_dtls(&packet).map_err(|e| {
+                                            NetworkError::TransportError(format!(
+                                                "UDP send failed: {}",
+                                                e
+                                            ))
+                                        })?;
                                     }
                                 }
 
@@ -423,12 +488,13 @@ impl SecureP2PSession {
             bitrate as f64 / 1_000_000.0
         ));
 
-        let new_encoder = H264Encoder::new(
+        let new_encoder = H264Encoder::with_settings(
             width,
             height,
             bitrate,
             30,
             self.config.fps(),
+            self.h264_settings,
             self.logger.clone(),
         )
         .map_err(|e| NetworkError::Config(format!("Failed to recreate encoder: {}", e)))?;
@@ -438,6 +504,12 @@ impl SecureP2PSession {
             poisoned.into_inner()
         }) = new_encoder;
 
+        if let Some(sender) = self.lock_codec_sender().as_mut() {
+            sender
+                .set_resolution(width, height, bitrate)
+                .map_err(NetworkError::Config)?;
+        }
+
         self.config = self
             .config
             .clone()
@@ -448,6 +520,270 @@ impl SecureP2PSession {
         Ok(())
     }
 
+    /// Applies the H.264 profile and packetization mode negotiated in the SDP
+    ///
+    /// Recreates the encoder when the settings change. Single NAL unit mode
+    /// also makes the encoder cap its slices, since the packetizer may not
+    /// fragment them.
+    pub fn configure_h264(
+        &mut self,
+        settings: H264EncoderSettings,
+        mode: PacketizationMode,
+    ) -> Result<()> {
+        self.packetizer
+            .lock()
+            .unwrap_or_else(|poisoned| {
+                self.logger.error("Packetizer mutex poisoned, recovering");
+                poisoned.into_inner()
+            })
+            .set_packetization_mode(mode);
+
+        if settings == self.h264_settings {
+            return Ok(());
+        }
+        self.h264_settings = settings;
+
+        let new_encoder = H264Encoder::with_settings(
+            self.config.frame_width(),
+            self.config.frame_height(),
+            self.config.codec_bitrate(),
+            30,
+            self.config.fps(),
+            settings,
+            self.logger.clone(),
+        )
+        .map_err(|e| NetworkError::Config(format!("Failed to recreate encoder: {}", e)))?;
+
+        *self.encoder.lock().unwrap_or_else(|poisoned| {
+            self.logger.error("Encoder mutex poisoned, recovering");
+            poisoned.into_inner()
+        }) = new_encoder;
+        Ok(())
+    }
+
+    /// Sends the main video stream with the codec negotiated in the SDP
+    ///
+    /// H.264 uses the encoder and packetizer the session was created with;
+    /// VP8 and VP9 get their own, keeping the stream's SSRC. Simulcast layers
+    /// and extra tracks are always H.264.
+    pub fn set_video_codec(&mut self, codec: VideoCodec) -> Result<()> {
+        let current = self
+            .lock_codec_sender()
+            .as_ref()
+            .map_or(VideoCodec::H264, CodecSender::codec);
+        if current == codec {
+            return Ok(());
+        }
+
+        let sender = match codec {
+            VideoCodec::H264 => None,
+            _ => Some(
+                CodecSender::new(
+                    codec,
+                    (
+                        self.config.frame_width(),
+                        self.config.frame_height(),
+                        self.config.codec_bitrate(),
+                    ),
+                    self.config.fps(),
+                    self.video_ssrc(),
+                    self.logger.clone(),
+                )
+                .map_err(NetworkError::Config)?,
+            ),
+        };
+
+        self.logger
+            .info(&format!("Sending video as {}", codec.encoding_name()));
+        *self.lock_codec_sender() = sender;
+        Ok(())
+    }
+
+    /// Sets the header extension ID agreed for transport-wide sequence numbers
+    ///
+    /// `None` if the peer did not agree to the extension. Applies to the
+    /// transport now if it exists, otherwise once the DTLS handshake creates it.
+    pub fn set_transport_cc_extension_id(&mut self, id: Option<u8>) {
+        self.transport_cc_extension_id = id;
+        if let Some(transport) = self
+            .transport
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_mut()
+        {
+            transport.set_transport_cc_extension_id(id);
+        }
+    }
+
+    /// Marks outgoing audio and video packets with the DSCP values in `config`
+    ///
+    /// Applies to the transport now if it exists, otherwise once the DTLS
+    /// handshake creates it. Audio packets are recognized by the payload type
+    /// and SSRC of the audio packetizer. Where the platform cannot mark
+    /// packets they are sent unmarked.
+    pub fn set_dscp_config(&mut self, config: DscpConfig) {
+        self.dscp_config = Some(config);
+        let (audio_payload_type, audio_ssrc) = {
+            let packetizer = self
+                .audio_packetizer
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            (packetizer.get_payload_type(), packetizer.get_ssrc())
+        };
+        if let Some(transport) = self
+            .transport
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_mut()
+        {
+            transport.set_audio_stream(audio_payload_type, audio_ssrc);
+            if let Err(e) = transport.set_dscp_config(config) {
+                self.logger.warn(&format!(
+                    "DSCP marking unavailable, sending unmarked: {}",
+                    e
+                ));
+            }
+        }
+    }
+
+    /// Makes the next sent video frame a keyframe with its parameter sets
+    ///
+    /// Used when the video source changes so the remote decoder resyncs
+    /// without waiting for a PLI.
+    pub fn request_keyframe(&self) {
+        self.keyframe_requested.store(true, Ordering::Release);
+    }
+
+    #[cfg(test)]
+    pub fn is_keyframe_requested(&self) -> bool {
+        self.keyframe_requested.load(Ordering::Acquire)
+    }
+
+    /// Returns the SSRC the video stream is sent with
+    pub fn video_ssrc(&self) -> u32 {
+        self.packetizer
+            .lock()
+            .unwrap_or_else(|poisoned| {
+                self.logger
+                    .error("Packetizer mutex poisoned in video_ssrc, recovering");
+                poisoned.into_inner()
+            })
+            .get_ssrc()
+    }
+
+    /// Sends video as several simulcast layers instead of a single stream
+    ///
+    /// An empty or single-layer config turns simulcast off again.
+    pub fn enable_simulcast(&mut self, config: &SimulcastConfig) -> Result<()> {
+        let sender = if config.is_enabled() {
+            Some(
+                SimulcastSender::new(config, 96, self.config.fps(), self.logger.clone())
+                    .map_err(NetworkError::Config)?,
+            )
+        } else {
+            None
+        };
+
+        *self.simulcast.lock().unwrap_or_else(|poisoned| {
+            self.logger.error("Simulcast mutex poisoned, recovering");
+            poisoned.into_inner()
+        }) = sender;
+        Ok(())
+    }
+
+    /// Adds an outgoing video track with its own encoder and SSRC
+    ///
+    /// The main video stream keeps running; frames for the new track are
+    /// sent with `send_track_frame`. The track is H.264, like simulcast layers.
+    ///
+    /// # Returns
+    /// The SSRC of the new track
+    pub fn add_video_track(&self, width: u32, height: u32, bitrate: u32) -> Result<u32> {
+        let layer = SimulcastLayer {
+            rid: format!("track{}", self.lock_video_tracks().len() + 1),
+            width: width & !1,
+            height: height & !1,
+            bitrate,
+        };
+        let sender = LayerSender::new(&layer, 96, self.config.fps(), self.logger.clone())
+            .map_err(NetworkError::Config)?;
+        let ssrc = sender.ssrc();
+
+        self.lock_video_tracks().insert(ssrc, sender);
+        self.logger.info(&format!(
+            "Added video track {} ({}x{})",
+            ssrc, layer.width, layer.height
+        ));
+        Ok(ssrc)
+    }
+
+    /// Encodes a frame on the extra video track `ssrc` and sends it
+    ///
+    /// Runs on the caller's thread, not the send thread.
+    pub fn send_track_frame(&self, ssrc: u32, frame: &VideoFrame) -> Result<()> {
+        if !self.secure_connection_established {
+            return Err(NetworkError::SecurityError(
+                "Cannot send frame before secure connection is established".to_string(),
+            ));
+        }
+
+        let packets = self
+            .lock_video_tracks()
+            .get_mut(&ssrc)
+            .ok_or_else(|| NetworkError::Config(format!("No video track {}", ssrc)))?
+            .encode(frame)
+            .map_err(NetworkError::Config)?;
+
+        let mut transport = self.transport.lock().unwrap_or_else(|poisoned| {
+            self.logger
+                .error("Transport mutex poisoned in send_track_frame, recovering");
+            poisoned.into_inner()
+        });
+        let transport = transport
+            .as_mut()
+            .ok_or_else(|| NetworkError::TransportError("Transport not initialized".to_string()))?;
+        for packet in &packets {
+            transport.send_rtp(packet).map_err(NetworkError::Media)?;
+        }
+        Ok(())
+    }
+
+    /// Stops the extra video track `ssrc` and ends its stream with RTCP BYE
+    ///
+    /// The peer stops decoding the stream on the BYE.
+    pub fn remove_video_track(&self, ssrc: u32) -> Result<()> {
+        if self.lock_video_tracks().remove(&ssrc).is_none() {
+            return Err(NetworkError::Config(format!("No video track {}", ssrc)));
+        }
+        self.logger.info(&format!("Removed video track {}", ssrc));
+
+        if let Some(transport) = self
+            .transport
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_mut()
+            && let Err(e) = transport.send_stream_bye(ssrc, Some("track removed".to_string()))
+        {
+            self.logger
+                .warn(&format!("Failed to send BYE for track {}: {}", ssrc, e));
+        }
+        Ok(())
+    }
+
+    fn lock_video_tracks(&self) -> std::sync::MutexGuard<'_, HashMap<u32, LayerSender>> {
+        self.video_tracks.lock().unwrap_or_else(|poisoned| {
+            self.logger.error("Video tracks mutex poisoned, recovering");
+            poisoned.into_inner()
+        })
+    }
+
+    fn lock_codec_sender(&self) -> std::sync::MutexGuard<'_, Option<CodecSender>> {
+        self.codec_sender.lock().unwrap_or_else(|poisoned| {
+            self.logger.error("Codec sender mutex poisoned, recovering");
+            poisoned.into_inner()
+        })
+    }
+
     pub fn start(&mut self) -> Result<()> {
         if !self.secure_connection_established {
             return Err(NetworkError::SecurityError(
@@ -502,6 +838,42 @@ impl SecureP2PSession {
         }
     }
 
+    /// Returns the SSRCs of the remote video streams decoded besides the primary one
+    ///
+    /// Such streams appear when the peer adds a video track (e.g. a screen
+    /// share); their frames are read with `receive_stream_frame`.
+    pub fn remote_video_ssrcs(&self) -> Vec<u32> {
+        self.remote_video_streams
+            .lock()
+            .unwrap_or_else(|poisoned| {
+                self.logger
+                    .error("Remote video streams mutex poisoned, recovering");
+                poisoned.into_inner()
+            })
+            .keys()
+            .copied()
+            .collect()
+    }
+
+    /// Receives a decoded frame of the secondary remote video stream `ssrc`
+    pub fn receive_stream_frame(&self, ssrc: u32) -> Result<Option<VideoFrame>> {
+        let streams = self.remote_video_streams.lock().unwrap_or_else(|poisoned| {
+            self.logger
+                .error("Remote video streams mutex poisoned, recovering");
+            poisoned.into_inner()
+        });
+        let rx_decode = streams.get(&ssrc).ok_or_else(|| {
+            NetworkError::ChannelError(format!("No remote video stream {}", ssrc))
+        })?;
+        match rx_decode.try_recv() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(std::sync::mpsc::TryRecvError::Empty) => Ok(None),
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Err(NetworkError::ChannelError(
+                format!("Decode channel of stream {} disconnected", ssrc),
+            )),
+        }
+    }
+
     pub fn receive_audio_frame(&self) -> Result<Option<AudioFrame>> {
         match self.rx_audio_decode.try_recv() {
             Ok(frame) => Ok(Some(frame)),
@@ -525,16 +897,8 @@ impl SecureP2PSession {
         *seq_guard = seq_guard.wrapping_add(1);
         drop(seq_guard);
 
-        // Get video SSRC from packetizer to use video_ssrc + 1 for control
-        let video_ssrc = self
-            .packetizer
-            .lock()
-            .unwrap_or_else(|poisoned| {
-                self.logger
-                    .error("Packetizer mutex poisoned in send_control_message, recovering");
-                poisoned.into_inner()
-            })
-            .get_ssrc();
+        // Use the video SSRC + 1 for control
+        let video_ssrc = self.video_ssrc();
 
         let control_packet = create_control_packet(seq, payload, video_ssrc);
 
@@ -601,6 +965,53 @@ impl SecureP2PSession {
         Ok(())
     }
 
+    /// Send a chat message on the chat data channel
+    pub fn send_chat(&self, message: &ChatMessage) -> Result<()> {
+        let file_session_guard = self
+            .file_session
+            .lock()
+            .map_err(|e| NetworkError::TransportError(format!("Lock error: {}", e)))?;
+
+        let file_session = file_session_guard.as_ref().ok_or_else(|| {
+            NetworkError::TransportError("File session not established".to_string())
+        })?;
+
+        file_session
+            .send_chat(message)
+            .map_err(NetworkError::TransportError)
+    }
+
+    /// Poll for received chat messages
+    pub fn poll_chat_message(&self) -> Option<ChatMessage> {
+        let file_session_guard = self.file_session.try_lock().ok()?;
+        file_session_guard.as_ref()?.poll_chat_message()
+    }
+
+    /// Start recording the call to a Matroska file
+    ///
+    /// The remote video and audio are always recorded; `include_local`
+    /// adds the local camera (unless simulcast is enabled) and microphone.
+    pub fn start_recording(&self, path: &Path, include_local: bool) -> Result<()> {
+        self.recorder
+            .start(path, include_local)
+            .map_err(NetworkError::TransportError)?;
+        self.logger
+            .info(&format!("[RECORDING] Recording to {}", path.display()));
+        Ok(())
+    }
+
+    /// Stop the recording and finalize its file
+    pub fn stop_recording(&self) -> Result<()> {
+        self.recorder.stop().map_err(NetworkError::TransportError)?;
+        self.logger.info("[RECORDING] Recording stopped");
+        Ok(())
+    }
+
+    /// Returns whether the call is being recorded
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_recording()
+    }
+
     /// Poll for file transfer events
     pub fn poll_event(&self) -> Option<super::file_transfer::FileTransferEvent> {
         let file_session_guard = match self.file_session.try_lock() {
@@ -671,6 +1082,41 @@ impl SecureP2PSession {
             .map(|t| (*t.get_stats()).clone())
     }
 
+    /// Takes the STUN packets received on the media socket (ICE consent)
+    pub fn take_stun_packets(&self) -> Vec<(Vec<u8>, SocketAddr)> {
+        self.transport
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_mut()
+            .map(|t| t.take_stun_packets())
+            .unwrap_or_default()
+    }
+
+    /// Redirects media to a new remote address (ICE selected pair change)
+    pub fn set_remote_addr(&self, remote_addr: SocketAddr) {
+        if let Some(transport) = self
+            .transport
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_mut()
+        {
+            transport.set_remote(remote_addr);
+        }
+    }
+
+    /// Sends a STUN packet on the media socket (ICE consent)
+    pub fn send_stun(&self, data: &[u8], to: SocketAddr) -> Result<()> {
+        let mut transport_guard = self
+            .transport
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(transport) = transport_guard.as_mut() {
+            transport.send_stun(data, to)?;
+        }
+        Ok(())
+    }
+
     /// Clears jitter buffer and packet handler to flush delayed video packets
     /// Call this when camera turns off to prevent stale frames from being displayed
     pub fn clear_video_buffers(&self) {
@@ -721,6 +1167,22 @@ impl SecureP2PSession {
             self.logger.info("[SESSION_CLEANUP] File session closed");
         }
 
+        // Finalize the recording file
+        if self.recorder.is_recording() {
+            match self.recorder.stop() {
+                Ok(()) => self.logger.info("[SESSION_CLEANUP] Recording stopped"),
+                Err(e) => self.logger.error(&format!("[SESSION_CLEANUP] {}", e)),
+            }
+        }
+
+        // Drop secondary stream receivers and extra track encoders
+        if let Ok(mut streams) = self.remote_video_streams.lock() {
+            streams.clear();
+        }
+        if let Ok(mut tracks) = self.video_tracks.lock() {
+            tracks.clear();
+        }
+
         // Clear thread handles (they will be joined when dropped)
         self.send_thread = None;
         self.recv_thread = None;
@@ -766,13 +1228,21 @@ fn create_audio_codec_components(logger: &Logger) -> Result<(OpusEncoder, OpusDe
     const SAMPLE_RATE: u32 = 48000;
     const CHANNELS: u32 = 2;
     const BITRATE: u32 = 64000;
+    // In-band FEC lets the decoder rebuild a single lost packet from the next one;
+    // DTX stops sending packets while the microphone is silent
+    const EXPECTED_PACKET_LOSS: u8 = 15;
 
     logger.info("Creating Opus audio encoder and decoder");
 
-    let encoder = OpusEncoder::new(SAMPLE_RATE, CHANNELS, BITRATE, logger.clone())
+    let config = AudioConfig::new(None, SAMPLE_RATE, CHANNELS)
+        .and_then(|config| config.with_fec(EXPECTED_PACKET_LOSS))
+        .map(|config| config.with_dtx())
+        .map_err(|e| NetworkError::Config(format!("Invalid audio configuration: {}", e)))?;
+
+    let encoder = OpusEncoder::with_config(&config, BITRATE, logger.clone())
         .map_err(|e| NetworkError::Config(format!("Failed to create audio encoder: {}", e)))?;
 
-    let decoder = OpusDecoder::new(SAMPLE_RATE, CHANNELS, logger.clone())
+    let decoder = OpusDecoder::with_config(&config, logger.clone())
         .map_err(|e| NetworkError::Config(format!("Failed to create audio decoder: {}", e)))?;
 
     logger.info(&format!(
@@ -785,18 +1255,22 @@ fn create_audio_codec_components(logger: &Logger) -> Result<(OpusEncoder, OpusDe
     Ok((encoder, decoder))
 }
 
-fn create_buffer_components() -> (JitterBuffer, PacketHandler, PacketHandler) {
-    let jitter_config = network::JitterBufferConfig {
-        clock_rate: 90000,
+/// Creates the jitter buffer of a remote video stream
+pub(super) fn video_jitter_buffer() -> JitterBuffer {
+    JitterBuffer::with_config(network::JitterBufferConfig {
         min_delay_frames: 1,
         max_delay_frames: 8,
         target_jitter_ms: 10.0,
         max_capacity: 250,
         adaptation_speed: 0.15,
         ultra_low_latency: true,
-    };
+        enable_plc: true,
+        ..network::JitterBufferConfig::video_defaults()
+    })
+}
 
-    let jitter_buffer = JitterBuffer::with_config(jitter_config);
+fn create_buffer_components() -> (JitterBuffer, PacketHandler, PacketHandler) {
+    let jitter_buffer = video_jitter_buffer();
     let packet_handler = PacketHandler::new();
     let audio_packet_handler = PacketHandler::new();
 
@@ -860,11 +1334,15 @@ fn spawn_threads(
     let send_params = send_thread::SendThreadParams {
         encoder: Arc::clone(&session.encoder),
         packetizer: Arc::clone(&session.packetizer),
+        simulcast: Arc::clone(&session.simulcast),
+        codec_sender: Arc::clone(&session.codec_sender),
+        keyframe_requested: Arc::clone(&session.keyframe_requested),
         audio_encoder: Arc::clone(&session.audio_encoder),
         audio_packetizer: Arc::clone(&session.audio_packetizer),
         transport: Arc::clone(&session.transport),
         rx_encode,
         rx_audio_encode,
+        recorder: session.recorder.clone(),
         logger: session.logger.clone(),
     };
 
@@ -878,6 +1356,8 @@ fn spawn_threads(
         jitter_buffer: Arc::clone(&session.jitter_buffer),
         decoder: Arc::clone(&session.decoder),
         tx_decode,
+        transport: Arc::clone(&session.transport),
+        recorder: Some(session.recorder.clone()),
         logger: session.logger.clone(),
     };
 
@@ -899,6 +1379,8 @@ fn spawn_threads(
         logger: session.logger.clone(),
         dtls_engine: Arc::clone(&session.dtls_engine),
         file_session: Arc::clone(&session.file_session),
+        remote_video_streams: Arc::clone(&session.remote_video_streams),
+        recorder: session.recorder.clone(),
     };
 
     let recv_handle = thread::Builder::new()
@@ -919,5 +1401,5 @@ fn create_control_packet(seq: u16, payload: Vec<u8>, video_ssrc: u32) -> RtpPack
     header.timestamp = 0;
     header.marker = true;
 
-    RtpPacket { header, payload }
+    RtpPacket::new(header, payload)
 }
@@ -1,13 +1,23 @@
 //! Send thread functionality for secure P2P session
 
+use super::recording::{RecordedTrack, Recorder};
+use super::simulcast::SimulcastSender;
+use super::video_codec::CodecSender;
 use logging::Logger;
-use media::{AudioFrame, H264Encoder, OpusEncoder, VideoFrame};
-use network::{H264RtpPacketizer, OpusRtpPacketizer, RtpPacketizer, SecureUdpTransport};
+use media::{AudioFrame, H264Encoder, OpusEncoder, VideoEncoder, VideoFrame};
+use network::{
+    BitrateController, DelayBasedController, H264RtpPacketizer, OpusRtpPacketizer, RtpPacketizer,
+    SecureUdpTransport,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Receiver, TryRecvError};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+/// Lowest video bitrate congestion control may reduce the encoder to (bps)
+const MIN_VIDEO_BITRATE: u32 = 150_000;
+
 /// Type alias for encoded frame with optional SPS/PPS to reduce type complexity
 type EncodedFrame = (Vec<Vec<u8>>, Option<Vec<u8>>, Option<Vec<u8>>);
 
@@ -18,11 +28,17 @@ type NalPackets = (Vec<Vec<u8>>, Vec<Vec<u8>>, Vec<Vec<u8>>);
 pub(super) struct SendThreadParams {
     pub encoder: Arc<Mutex<H264Encoder>>,
     pub packetizer: Arc<Mutex<H264RtpPacketizer>>,
+    pub simulcast: Arc<Mutex<Option<SimulcastSender>>>,
+    /// VP8/VP9 encoder and packetizer, used instead of `encoder` when set
+    pub codec_sender: Arc<Mutex<Option<CodecSender>>>,
+    /// Set by the session when the video source changed
+    pub keyframe_requested: Arc<AtomicBool>,
     pub audio_encoder: Arc<Mutex<OpusEncoder>>,
     pub audio_packetizer: Arc<Mutex<OpusRtpPacketizer>>,
     pub transport: Arc<Mutex<Option<SecureUdpTransport>>>,
     pub rx_encode: Receiver<VideoFrame>,
     pub rx_audio_encode: Receiver<AudioFrame>,
+    pub recorder: Recorder,
     pub logger: Logger,
 }
 
@@ -32,18 +48,37 @@ struct SendThreadState {
     sps_pps_sent: bool,
     packet_count: u64,
     audio_packet_count: u64,
+    bitrate_controller: BitrateController,
+    delay_controller: DelayBasedController,
 }
 
 pub(super) fn run_send_thread(params: SendThreadParams) {
     params
         .logger
         .info("Secure SEND thread started (video + audio)");
+
+    // The configured bitrate is the ceiling; loss and delay feedback can only lower it
+    let configured_bitrate = params
+        .encoder
+        .lock()
+        .map(|encoder| encoder.get_bitrate())
+        .unwrap_or(MIN_VIDEO_BITRATE);
     let mut state = SendThreadState {
         frame_count: 0,
         audio_frame_count: 0,
         sps_pps_sent: false,
         packet_count: 0,
         audio_packet_count: 0,
+        bitrate_controller: BitrateController::new(
+            configured_bitrate,
+            MIN_VIDEO_BITRATE.min(configured_bitrate),
+            configured_bitrate,
+        ),
+        delay_controller: DelayBasedController::new(
+            configured_bitrate,
+            MIN_VIDEO_BITRATE.min(configured_bitrate),
+            configured_bitrate,
+        ),
     };
 
     loop {
@@ -130,6 +165,16 @@ fn process_frame(
     state: &mut SendThreadState,
     frame: VideoFrame,
 ) -> Result<(), String> {
+    handle_keyframe_request(params, state);
+    handle_bitrate_adaptation(params, state);
+
+    if send_simulcast_frame(params, state, &frame)? || send_codec_frame(params, state, &frame)? {
+        return Ok(());
+    }
+    if params.recorder.needs_keyframe(RecordedTrack::LocalVideo) {
+        force_keyframe(params, state);
+    }
+
     let (encoded_packets, cached_sps, cached_pps) =
         encode_frame(&params.encoder, &frame, &params.logger, state.sps_pps_sent)?;
 
@@ -153,6 +198,7 @@ fn process_frame(
         &cached_packets,
         &sps_pps_packets,
         &frame_packets,
+        &frame,
         params,
         &mut state.packet_count,
     )?;
@@ -165,6 +211,204 @@ fn process_frame(
     Ok(())
 }
 
+/// Force an IDR frame if the remote decoder sent a PLI/FIR or the video source changed
+fn handle_keyframe_request(params: &SendThreadParams, state: &mut SendThreadState) {
+    let requested_by_peer = params
+        .transport
+        .lock()
+        .unwrap_or_else(|poisoned| {
+            params
+                .logger
+                .error("Transport mutex poisoned in send thread, recovering");
+            poisoned.into_inner()
+        })
+        .as_mut()
+        .is_some_and(|transport| transport.take_keyframe_request());
+    let requested_locally = params.keyframe_requested.swap(false, Ordering::AcqRel);
+
+    if requested_by_peer {
+        params
+            .logger
+            .info("Keyframe requested by remote peer (PLI/FIR)");
+    } else if requested_locally {
+        params
+            .logger
+            .info("Keyframe requested after a video source change");
+    } else {
+        return;
+    }
+
+    force_keyframe(params, state);
+}
+
+/// Make the next encoded frame an IDR preceded by the parameter sets
+fn force_keyframe(params: &SendThreadParams, state: &mut SendThreadState) {
+    params
+        .encoder
+        .lock()
+        .unwrap_or_else(|poisoned| {
+            params
+                .logger
+                .error("Encoder mutex poisoned in send thread, recovering");
+            poisoned.into_inner()
+        })
+        .request_keyframe();
+
+    if let Some(simulcast) = lock_simulcast(params).as_mut() {
+        simulcast.request_keyframe();
+    }
+    if let Some(sender) = lock_codec_sender(params).as_mut() {
+        sender.request_keyframe();
+    }
+
+    // Resend parameter sets with the IDR so the decoder can resync
+    state.sps_pps_sent = false;
+}
+
+/// Apply the target bitrate derived from the peer's RTCP feedback
+///
+/// The loss-based (Receiver Reports) and delay-based (transport-cc) targets
+/// are combined by taking the lower of the two.
+fn handle_bitrate_adaptation(params: &SendThreadParams, state: &mut SendThreadState) {
+    let changed = params
+        .transport
+        .lock()
+        .unwrap_or_else(|poisoned| {
+            params
+                .logger
+                .error("Transport mutex poisoned in send thread, recovering");
+            poisoned.into_inner()
+        })
+        .as_mut()
+        .is_some_and(|transport| {
+            let loss_update = state.bitrate_controller.update(transport.get_stats());
+            let delay_update = state
+                .delay_controller
+                .on_packet_results(&transport.take_packet_results());
+            loss_update.is_some() || delay_update.is_some()
+        });
+
+    if !changed {
+        return;
+    }
+
+    let target = state
+        .bitrate_controller
+        .target_bitrate()
+        .min(state.delay_controller.target_bitrate());
+
+    params.logger.info(&format!(
+        "Adapting video bitrate to {} bps (remote loss/delay feedback)",
+        target
+    ));
+
+    params
+        .encoder
+        .lock()
+        .unwrap_or_else(|poisoned| {
+            params
+                .logger
+                .error("Encoder mutex poisoned in send thread, recovering");
+            poisoned.into_inner()
+        })
+        .set_bitrate(target);
+    if let Some(sender) = lock_codec_sender(params).as_mut() {
+        sender.set_bitrate(target);
+    }
+}
+
+fn lock_simulcast(params: &SendThreadParams) -> std::sync::MutexGuard<'_, Option<SimulcastSender>> {
+    params.simulcast.lock().unwrap_or_else(|poisoned| {
+        params
+            .logger
+            .error("Simulcast mutex poisoned in send thread, recovering");
+        poisoned.into_inner()
+    })
+}
+
+fn lock_codec_sender(
+    params: &SendThreadParams,
+) -> std::sync::MutexGuard<'_, Option<CodecSender>> {
+    params.codec_sender.lock().unwrap_or_else(|poisoned| {
+        params
+            .logger
+            .error("Codec sender mutex poisoned in send thread, recovering");
+        poisoned.into_inner()
+    })
+}
+
+/// Encode and send a frame with the negotiated VP8/VP9 codec
+///
+/// The recorder muxes H.264 only, so these frames are not recorded.
+///
+/// # Returns
+/// `true` if a VP8/VP9 codec is in use and handled the frame
+fn send_codec_frame(
+    params: &SendThreadParams,
+    state: &mut SendThreadState,
+    frame: &VideoFrame,
+) -> Result<bool, String> {
+    let packets = match lock_codec_sender(params).as_mut() {
+        Some(sender) => sender.encode(frame)?,
+        None => return Ok(false),
+    };
+
+    for packet in &packets {
+        state.packet_count += 1;
+        send_rtp_packet(
+            packet,
+            state.packet_count,
+            &params.transport,
+            &params.logger,
+        )?;
+    }
+
+    params.logger.info(&format!(
+        "Frame {} complete: {} packets, {} total packets sent",
+        state.frame_count,
+        packets.len(),
+        state.packet_count
+    ));
+
+    Ok(true)
+}
+
+/// Encode and send every simulcast layer of a frame
+///
+/// # Returns
+/// `true` if simulcast is enabled and handled the frame
+fn send_simulcast_frame(
+    params: &SendThreadParams,
+    state: &mut SendThreadState,
+    frame: &VideoFrame,
+) -> Result<bool, String> {
+    let streams = match lock_simulcast(params).as_mut() {
+        Some(simulcast) => simulcast.encode(frame)?,
+        None => return Ok(false),
+    };
+
+    for (_, packets) in &streams {
+        for packet in packets {
+            state.packet_count += 1;
+            send_rtp_packet(
+                packet,
+                state.packet_count,
+                &params.transport,
+                &params.logger,
+            )?;
+        }
+    }
+
+    params.logger.info(&format!(
+        "Frame {} complete: {} simulcast layers, {} total packets sent",
+        state.frame_count,
+        streams.len(),
+        state.packet_count
+    ));
+
+    Ok(true)
+}
+
 fn encode_frame(
     encoder: &Arc<Mutex<H264Encoder>>,
     frame: &VideoFrame,
@@ -222,28 +466,44 @@ fn prepare_nal_packets(
     Ok((cached_packets, sps_pps_packets, frame_packets))
 }
 
+/// Sends every NAL unit of a frame as one access unit
+///
+/// The NAL units are packetized together so they share one RTP timestamp and
+/// only the last packet carries the marker bit.
 fn send_all_nals(
     cached_packets: &[Vec<u8>],
     sps_pps_packets: &[Vec<u8>],
     frame_packets: &[Vec<u8>],
+    frame: &VideoFrame,
     params: &SendThreadParams,
     packet_count: &mut u64,
 ) -> Result<usize, String> {
-    let mut nal_count = 0;
-
-    for h264_data in cached_packets
+    let nals: Vec<&Vec<u8>> = cached_packets
         .iter()
         .chain(sps_pps_packets)
         .chain(frame_packets)
-    {
-        nal_count += 1;
-        send_nal(h264_data, nal_count, params, packet_count)?;
+        .collect();
+
+    if nals.is_empty() {
+        return Ok(0);
+    }
+
+    let access_unit: Vec<u8> = nals.iter().flat_map(|nal| nal.iter().copied()).collect();
+    send_access_unit(&access_unit, nals.len(), params, packet_count)?;
+
+    if let Err(e) = params.recorder.record_video(
+        RecordedTrack::LocalVideo,
+        &access_unit,
+        frame.width(),
+        frame.height(),
+    ) {
+        params.logger.error(&e);
     }
 
-    Ok(nal_count)
+    Ok(nals.len())
 }
 
-fn send_nal(
+fn send_access_unit(
     h264_data: &[u8],
     nal_count: usize,
     params: &SendThreadParams,
@@ -262,7 +522,7 @@ fn send_nal(
         .packetize(h264_data);
 
     params.logger.debug(&format!(
-        "NAL #{}: type={}, size={} bytes → {} RTP packets",
+        "Access unit: {} NALs, first type={}, size={} bytes → {} RTP packets",
         nal_count,
         nal_type,
         h264_data.len(),
@@ -326,31 +586,45 @@ fn process_audio_frame(
     audio_frame: AudioFrame,
 ) -> Result<(), String> {
     // Encode audio frame
-    let encoded_audio = {
+    let (encoded_audio, dtx_silent, talkspurt_start) = {
         let mut encoder = params
             .audio_encoder
             .lock()
             .map_err(|e| format!("Encoder lock error: {}", e))?;
 
-        encoder
+        let encoded = encoder
             .encode(&audio_frame)
-            .map_err(|e| format!("Audio encoding failed: {}", e))?
+            .map_err(|e| format!("Audio encoding failed: {}", e))?;
+        (encoded, encoder.is_silent(), encoder.is_talkspurt_start())
     };
 
+    let mut packetizer = params
+        .audio_packetizer
+        .lock()
+        .map_err(|e| format!("Packetizer lock error: {}", e))?;
+
     if encoded_audio.is_empty() {
-        // Encoder needs more data, skip this frame
+        if dtx_silent {
+            // DTX dropped the frame: keep the RTP clock running
+            packetizer.skip_frame();
+        }
+        // Otherwise the encoder needs more data
         return Ok(());
     }
 
-    // Packetize encoded audio
-    let rtp_packets = {
-        let mut packetizer = params
-            .audio_packetizer
-            .lock()
-            .map_err(|e| format!("Packetizer lock error: {}", e))?;
+    if let Err(e) = params
+        .recorder
+        .record_audio(RecordedTrack::LocalAudio, &encoded_audio)
+    {
+        params.logger.error(&e);
+    }
 
-        packetizer.packetize(&encoded_audio)
-    };
+    // Packetize encoded audio
+    if talkspurt_start {
+        packetizer.mark_talkspurt();
+    }
+    let rtp_packets = packetizer.packetize(&encoded_audio);
+    drop(packetizer);
 
     // Send packets
     let mut transport = params
@@ -0,0 +1,335 @@
+//! Simulcast send path
+//!
+//! Encodes every captured frame at several resolutions, each layer with its
+//! own H.264 encoder, RTP packetizer (and therefore SSRC) and RID, so an SFU
+//! can forward the layer that fits each receiver.
+
+use logging::Logger;
+use media::{H264Encoder, VideoFrame};
+use network::codec::rtp::RtpPacket;
+use network::{H264RtpPacketizer, RtpPacketizer};
+
+/// Keyframe interval used by every layer encoder
+const LAYER_KEYFRAME_INTERVAL: u32 = 30;
+/// Lowest bitrate a layer is given, in bits per second
+const MIN_LAYER_BITRATE: u32 = 150_000;
+
+/// A single simulcast layer (one spatial resolution)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulcastLayer {
+    /// RTP stream identifier advertised in `a=rid`
+    pub rid: String,
+    /// Encoded width in pixels
+    pub width: u32,
+    /// Encoded height in pixels
+    pub height: u32,
+    /// Target bitrate in bits per second
+    pub bitrate: u32,
+}
+
+/// Describes the layers sent when simulcast is enabled
+///
+/// Layers are ordered from highest to lowest resolution, which is also the
+/// order they are listed in `a=simulcast`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulcastConfig {
+    layers: Vec<SimulcastLayer>,
+}
+
+impl SimulcastConfig {
+    /// Creates an empty configuration (simulcast disabled)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds `count` layers from a base resolution
+    ///
+    /// Each layer halves the resolution of the previous one and gets a
+    /// quarter of its bitrate. RIDs are "h", "m" and "l".
+    ///
+    /// # Arguments
+    /// * `width` - Width of the full resolution layer
+    /// * `height` - Height of the full resolution layer
+    /// * `bitrate` - Bitrate of the full resolution layer
+    /// * `count` - Number of layers (clamped to 1..=3)
+    pub fn from_base(width: u32, height: u32, bitrate: u32, count: usize) -> Self {
+        const RIDS: [&str; 3] = ["h", "m", "l"];
+        let count = count.clamp(1, RIDS.len());
+        let rids = match count {
+            1 => &RIDS[..1],
+            2 => &["h", "l"][..],
+            _ => &RIDS[..],
+        };
+
+        let layers = rids
+            .iter()
+            .enumerate()
+            .map(|(i, rid)| SimulcastLayer {
+                rid: rid.to_string(),
+                // H.264 needs even dimensions for 4:2:0 chroma
+                width: (width >> i) & !1,
+                height: (height >> i) & !1,
+                bitrate: (bitrate >> (2 * i)).max(MIN_LAYER_BITRATE),
+            })
+            .collect();
+
+        Self { layers }
+    }
+
+    /// Appends a layer
+    pub fn add_layer(mut self, rid: &str, width: u32, height: u32, bitrate: u32) -> Self {
+        self.layers.push(SimulcastLayer {
+            rid: rid.to_string(),
+            width,
+            height,
+            bitrate,
+        });
+        self
+    }
+
+    /// Returns the configured layers
+    pub fn layers(&self) -> &[SimulcastLayer] {
+        &self.layers
+    }
+
+    /// Returns the RIDs in send order
+    pub fn rids(&self) -> Vec<String> {
+        self.layers.iter().map(|layer| layer.rid.clone()).collect()
+    }
+
+    /// Simulcast needs at least two layers to be meaningful
+    pub fn is_enabled(&self) -> bool {
+        self.layers.len() > 1
+    }
+}
+
+/// Encoder and packetizer pair for one layer
+///
+/// Also sends the extra video tracks added by renegotiation: a track is a
+/// single layer with its own SSRC.
+pub(super) struct LayerSender {
+    layer: SimulcastLayer,
+    encoder: H264Encoder,
+    packetizer: H264RtpPacketizer,
+    sps_pps_sent: bool,
+}
+
+impl LayerSender {
+    /// Creates the encoder and packetizer of a layer
+    ///
+    /// # Arguments
+    /// * `layer` - Resolution and bitrate to encode at
+    /// * `payload_type` - RTP payload type of the packets
+    /// * `fps` - Capture frame rate
+    /// * `logger` - Logger instance
+    pub(super) fn new(
+        layer: &SimulcastLayer,
+        payload_type: u8,
+        fps: f64,
+        logger: Logger,
+    ) -> Result<Self, String> {
+        let encoder = H264Encoder::new(
+            layer.width,
+            layer.height,
+            layer.bitrate,
+            LAYER_KEYFRAME_INTERVAL,
+            fps,
+            logger,
+        )
+        .map_err(|e| format!("Failed to create encoder for rid {}: {}", layer.rid, e))?;
+
+        Ok(LayerSender {
+            layer: layer.clone(),
+            encoder,
+            packetizer: H264RtpPacketizer::new(payload_type, 1460, fps),
+            sps_pps_sent: false,
+        })
+    }
+
+    /// SSRC of the RTP stream this layer sends
+    pub(super) fn ssrc(&self) -> u32 {
+        self.packetizer.get_ssrc()
+    }
+
+    /// Scales a captured frame to the layer resolution and encodes it
+    ///
+    /// # Returns
+    /// The RTP packets of the encoded access unit
+    pub(super) fn encode(&mut self, frame: &VideoFrame) -> Result<Vec<RtpPacket>, String> {
+        let (width, height) = (self.layer.width as i32, self.layer.height as i32);
+        let scaled;
+        let layer_frame = if frame.width() == width && frame.height() == height {
+            frame
+        } else {
+            scaled = frame
+                .scaled(width, height)
+                .map_err(|e| format!("Scaling for rid {} failed: {}", self.layer.rid, e))?;
+            &scaled
+        };
+
+        let nals = self
+            .encoder
+            .encode(layer_frame)
+            .map_err(|e| format!("Encoding rid {} failed: {}", self.layer.rid, e))?;
+        Ok(self.packetize_nals(nals))
+    }
+
+    /// Packetizes encoded NAL units as one access unit, prepending SPS/PPS
+    /// until they were sent
+    fn packetize_nals(&mut self, nals: Vec<Vec<u8>>) -> Vec<RtpPacket> {
+        let mut to_send = Vec::with_capacity(nals.len() + 2);
+
+        if !self.sps_pps_sent {
+            let has_parameter_sets = nals
+                .iter()
+                .any(|nal| matches!(super::send_thread::get_nal_type(nal), 7 | 8));
+            if !has_parameter_sets {
+                to_send.extend(self.encoder.get_sps().cloned());
+                to_send.extend(self.encoder.get_pps().cloned());
+            }
+            self.sps_pps_sent = !to_send.is_empty() || has_parameter_sets;
+        }
+        to_send.extend(nals.into_iter().filter(|nal| !nal.is_empty()));
+
+        if to_send.is_empty() {
+            return Vec::new();
+        }
+
+        // One access unit: a single timestamp, marker on the last packet
+        self.packetizer.packetize(&to_send.concat())
+    }
+}
+
+/// Encodes and packetizes every simulcast layer of a frame
+pub(crate) struct SimulcastSender {
+    layers: Vec<LayerSender>,
+    logger: Logger,
+}
+
+impl SimulcastSender {
+    /// Creates one encoder and packetizer per layer
+    ///
+    /// # Arguments
+    /// * `config` - Layers to send
+    /// * `payload_type` - RTP payload type shared by all layers
+    /// * `fps` - Capture frame rate
+    /// * `logger` - Logger instance
+    pub(crate) fn new(
+        config: &SimulcastConfig,
+        payload_type: u8,
+        fps: f64,
+        logger: Logger,
+    ) -> Result<Self, String> {
+        let layers = config
+            .layers()
+            .iter()
+            .map(|layer| LayerSender::new(layer, payload_type, fps, logger.clone()))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        logger.info(&format!(
+            "Simulcast enabled with {} layers: {}",
+            layers.len(),
+            config.rids().join(";")
+        ));
+
+        Ok(Self { layers, logger })
+    }
+
+    /// Returns the SSRC used by each layer, keyed by RID
+    pub(crate) fn ssrcs(&self) -> Vec<(String, u32)> {
+        self.layers
+            .iter()
+            .map(|sender| (sender.layer.rid.clone(), sender.ssrc()))
+            .collect()
+    }
+
+    /// Forces an IDR on every layer and resends parameter sets
+    pub(crate) fn request_keyframe(&mut self) {
+        for sender in &mut self.layers {
+            sender.encoder.request_keyframe();
+            sender.sps_pps_sent = false;
+        }
+    }
+
+    /// Encodes a captured frame on every layer
+    ///
+    /// # Returns
+    /// RTP packets per layer, keyed by RID, in layer order
+    pub(crate) fn encode(
+        &mut self,
+        frame: &VideoFrame,
+    ) -> Result<Vec<(String, Vec<RtpPacket>)>, String> {
+        let mut streams = Vec::with_capacity(self.layers.len());
+
+        for sender in &mut self.layers {
+            let packets = sender.encode(frame)?;
+            self.logger.debug(&format!(
+                "Simulcast rid {}: {}x{} → {} RTP packets",
+                sender.layer.rid,
+                sender.layer.width,
+                sender.layer.height,
+                packets.len()
+            ));
+            streams.push((sender.layer.rid.clone(), packets));
+        }
+
+        Ok(streams)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::LogLevel;
+    use opencv::core::{CV_8UC3, Mat, Scalar};
+    use opencv::prelude::*;
+
+    fn create_test_logger() -> Logger {
+        let log_path = std::env::temp_dir().join("test_simulcast.log");
+        Logger::new(log_path, LogLevel::Debug).unwrap()
+    }
+
+    #[test]
+    fn test_config_from_base() {
+        let config = SimulcastConfig::from_base(1280, 720, 2_000_000, 2);
+
+        assert!(config.is_enabled());
+        assert_eq!(config.rids(), vec!["h", "l"]);
+        assert_eq!(config.layers()[1].width, 640);
+        assert_eq!(config.layers()[1].height, 360);
+        assert_eq!(config.layers()[1].bitrate, 500_000);
+        assert!(!SimulcastConfig::from_base(1280, 720, 2_000_000, 1).is_enabled());
+    }
+
+    #[test]
+    fn test_two_layers_produce_distinct_rtp_streams() {
+        let config = SimulcastConfig::new()
+            .add_layer("h", 320, 240, 400_000)
+            .add_layer("l", 160, 120, 150_000);
+        let Ok(mut sender) = SimulcastSender::new(&config, 96, 30.0, create_test_logger()) else {
+            // No H.264 encoder in this environment
+            return;
+        };
+
+        let mat =
+            Mat::new_rows_cols_with_default(240, 320, CV_8UC3, Scalar::new(40.0, 90.0, 160.0, 0.0))
+                .unwrap();
+        let streams = sender.encode(&VideoFrame::new(mat)).unwrap();
+
+        assert_eq!(streams.len(), 2);
+        assert_eq!(streams[0].0, "h");
+        assert_eq!(streams[1].0, "l");
+
+        let ssrc_of = |packets: &[RtpPacket]| {
+            assert!(!packets.is_empty());
+            let ssrc = packets[0].header.ssrc;
+            assert!(packets.iter().all(|p| p.header.ssrc == ssrc));
+            ssrc
+        };
+        assert_ne!(ssrc_of(&streams[0].1), ssrc_of(&streams[1].1));
+
+        let ssrcs = sender.ssrcs();
+        assert_eq!(ssrcs[0].1, ssrc_of(&streams[0].1));
+        assert_eq!(ssrcs[1].1, ssrc_of(&streams[1].1));
+    }
+}
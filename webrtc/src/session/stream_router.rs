@@ -0,0 +1,182 @@
+//! Incoming video stream demultiplexing
+//!
+//! Routes received video RTP packets to the jitter buffer of the stream
+//! their SSRC belongs to. A stream is created the first time an SSRC with
+//! a known payload type appears, so tracks added by a renegotiation (a
+//! screen share, another participant) get their own decode pipeline
+//! without restarting the session.
+
+use network::JitterBuffer;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A remote video stream being received
+pub(super) struct RemoteStream {
+    /// Buffer the stream's decode pipeline pops packets from
+    pub jitter_buffer: Arc<Mutex<JitterBuffer>>,
+    /// Whether the stream is decoded by the session's main video pipeline
+    pub primary: bool,
+}
+
+/// Where a packet was routed to
+pub(super) enum Route {
+    /// The packet belongs to a stream already being received
+    Existing(Arc<Mutex<JitterBuffer>>),
+    /// The packet starts a new stream
+    ///
+    /// A secondary stream has no decoder yet: the caller must start a
+    /// decode pipeline on `jitter_buffer` before packets pile up.
+    New {
+        jitter_buffer: Arc<Mutex<JitterBuffer>>,
+        primary: bool,
+    },
+    /// The payload type was not negotiated; the packet should be dropped
+    UnknownPayloadType,
+}
+
+/// Keys remote video streams by SSRC
+///
+/// The first stream takes the session's main jitter buffer so it keeps
+/// being decoded by the main video pipeline. Later streams get a buffer of
+/// their own, created by `new_buffer`.
+pub(super) struct StreamRouter {
+    payload_types: Vec<u8>,
+    primary_buffer: Arc<Mutex<JitterBuffer>>,
+    new_buffer: fn() -> JitterBuffer,
+    streams: HashMap<u32, RemoteStream>,
+}
+
+impl StreamRouter {
+    /// Creates a router with no streams
+    ///
+    /// # Arguments
+    /// * `payload_types` - Negotiated video payload types
+    /// * `primary_buffer` - Jitter buffer of the main video pipeline
+    /// * `new_buffer` - Creates the jitter buffer of a secondary stream
+    pub fn new(
+        payload_types: &[u8],
+        primary_buffer: Arc<Mutex<JitterBuffer>>,
+        new_buffer: fn() -> JitterBuffer,
+    ) -> Self {
+        Self {
+            payload_types: payload_types.to_vec(),
+            primary_buffer,
+            new_buffer,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Finds, or creates, the stream a packet belongs to
+    ///
+    /// # Arguments
+    /// * `ssrc` - SSRC of the packet
+    /// * `payload_type` - Payload type of the packet
+    pub fn route(&mut self, ssrc: u32, payload_type: u8) -> Route {
+        if let Some(stream) = self.streams.get(&ssrc) {
+            return Route::Existing(Arc::clone(&stream.jitter_buffer));
+        }
+        if !self.payload_types.contains(&payload_type) {
+            return Route::UnknownPayloadType;
+        }
+
+        let primary = !self.streams.values().any(|stream| stream.primary);
+        let jitter_buffer = if primary {
+            Arc::clone(&self.primary_buffer)
+        } else {
+            Arc::new(Mutex::new((self.new_buffer)()))
+        };
+        self.streams.insert(
+            ssrc,
+            RemoteStream {
+                jitter_buffer: Arc::clone(&jitter_buffer),
+                primary,
+            },
+        );
+        Route::New {
+            jitter_buffer,
+            primary,
+        }
+    }
+
+    /// Stops routing packets of `ssrc`
+    ///
+    /// Once the primary stream is removed, the next new stream becomes
+    /// the primary one.
+    ///
+    /// # Returns
+    /// The removed stream, or None if `ssrc` was not being received
+    pub fn remove(&mut self, ssrc: u32) -> Option<RemoteStream> {
+        self.streams.remove(&ssrc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use network::RtpPacket;
+    use network::codec::rtp::RtpHeader;
+
+    fn router() -> StreamRouter {
+        StreamRouter::new(
+            &[96],
+            Arc::new(Mutex::new(JitterBuffer::new())),
+            JitterBuffer::new,
+        )
+    }
+
+    fn push(route: Route, packet: RtpPacket) -> Arc<Mutex<JitterBuffer>> {
+        let jitter_buffer = match route {
+            Route::Existing(jitter_buffer) | Route::New { jitter_buffer, .. } => jitter_buffer,
+            Route::UnknownPayloadType => panic!("packet was not routed"),
+        };
+        jitter_buffer.lock().unwrap().push(packet);
+        jitter_buffer
+    }
+
+    #[test]
+    fn test_two_video_ssrcs_get_independent_pipelines() {
+        let mut router = router();
+
+        let first = router.route(1111, 96);
+        assert!(matches!(first, Route::New { primary: true, .. }));
+        let first = push(first, RtpPacket::new(RtpHeader::new(96, 1111), vec![1]));
+
+        let second = router.route(2222, 96);
+        assert!(matches!(second, Route::New { primary: false, .. }));
+        let second = push(second, RtpPacket::new(RtpHeader::new(96, 2222), vec![2]));
+
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert!(Arc::ptr_eq(&first, &router.primary_buffer));
+        assert_eq!(first.lock().unwrap().peek().unwrap().header.ssrc, 1111);
+        assert_eq!(second.lock().unwrap().peek().unwrap().header.ssrc, 2222);
+        assert_eq!(router.streams.len(), 2);
+
+        // Later packets follow their stream
+        let again = router.route(2222, 96);
+        assert!(matches!(&again, Route::Existing(buffer) if Arc::ptr_eq(buffer, &second)));
+    }
+
+    #[test]
+    fn test_unknown_payload_type_is_not_routed() {
+        let mut router = router();
+
+        assert!(matches!(router.route(1111, 120), Route::UnknownPayloadType));
+        assert!(router.streams.is_empty());
+    }
+
+    #[test]
+    fn test_next_stream_becomes_primary_after_removal() {
+        let mut router = router();
+        router.route(1111, 96);
+        router.route(2222, 96);
+
+        assert!(router.remove(1111).is_some_and(|stream| stream.primary));
+        assert!(router.remove(1111).is_none());
+
+        assert!(matches!(
+            router.route(3333, 96),
+            Route::New { primary: true, .. }
+        ));
+        assert!(!router.streams[&2222].primary);
+    }
+}
@@ -0,0 +1,268 @@
+//! VP8/VP9 media path
+//!
+//! H.264 keeps its dedicated pipeline (parameter sets, packetization modes,
+//! simulcast). When negotiation picks VP8 or VP9, the main video stream is
+//! sent through a [`CodecSender`] instead, and received packets with a VP8 or
+//! VP9 payload type are decoded by a [`CodecReceiver`].
+
+use logging::Logger;
+use media::{MediaError, VP8Decoder, VP9Decoder, VideoCodec, VideoEncoder, VideoFrame};
+use network::codec::rtp::RtpPacket;
+use network::{
+    RtpDepacketizer, RtpPacketizer, Vp8RtpDepacketizer, Vp8RtpPacketizer, Vp9RtpDepacketizer,
+    Vp9RtpPacketizer,
+};
+
+/// Video codecs with the payload types they are offered with, in preference order
+pub(crate) const VIDEO_CODECS: [(u8, VideoCodec); 3] = [
+    (96, VideoCodec::H264),
+    (97, VideoCodec::VP8),
+    (98, VideoCodec::VP9),
+];
+
+/// Keyframe interval of the VP8/VP9 encoders, like the H.264 one
+const KEYFRAME_INTERVAL: u32 = 30;
+/// Largest RTP payload, payload descriptor included (bytes)
+const MAX_PAYLOAD_SIZE: usize = 1460;
+
+/// Returns the payload type a codec is offered with
+pub(super) fn payload_type_of(codec: VideoCodec) -> u8 {
+    VIDEO_CODECS
+        .iter()
+        .find(|(_, offered)| *offered == codec)
+        .map(|(payload_type, _)| *payload_type)
+        .unwrap_or(VIDEO_CODECS[0].0)
+}
+
+/// Returns the codec sent with a video payload type
+pub(super) fn codec_of(payload_type: u8) -> Option<VideoCodec> {
+    VIDEO_CODECS
+        .iter()
+        .find(|(offered, _)| *offered == payload_type)
+        .map(|(_, codec)| *codec)
+}
+
+/// Encoder and packetizer of the main video stream for VP8 or VP9
+pub(super) struct CodecSender {
+    codec: VideoCodec,
+    encoder: Box<dyn VideoEncoder + Send>,
+    packetizer: Box<dyn RtpPacketizer + Send>,
+    fps: f64,
+    logger: Logger,
+}
+
+impl CodecSender {
+    /// Creates the encoder and packetizer of a VP8 or VP9 stream
+    ///
+    /// # Arguments
+    /// * `codec` - VP8 or VP9
+    /// * `width`, `height`, `bitrate` - Encoder configuration
+    /// * `fps` - Capture frame rate
+    /// * `ssrc` - SSRC of the stream, kept from the H.264 packetizer
+    /// * `logger` - Logger instance
+    pub(super) fn new(
+        codec: VideoCodec,
+        (width, height, bitrate): (u32, u32, u32),
+        fps: f64,
+        ssrc: u32,
+        logger: Logger,
+    ) -> Result<Self, String> {
+        let payload_type = payload_type_of(codec);
+        let packetizer: Box<dyn RtpPacketizer + Send> = match codec {
+            VideoCodec::VP8 => {
+                Box::new(Vp8RtpPacketizer::new(payload_type, MAX_PAYLOAD_SIZE, fps).with_ssrc(ssrc))
+            }
+            VideoCodec::VP9 => {
+                Box::new(Vp9RtpPacketizer::new(payload_type, MAX_PAYLOAD_SIZE, fps).with_ssrc(ssrc))
+            }
+            VideoCodec::H264 => return Err("H.264 uses the dedicated pipeline".to_string()),
+        };
+        let encoder = create_encoder(codec, width, height, bitrate, fps, &logger)?;
+
+        Ok(CodecSender {
+            codec,
+            encoder,
+            packetizer,
+            fps,
+            logger,
+        })
+    }
+
+    /// Codec the stream is sent with
+    pub(super) fn codec(&self) -> VideoCodec {
+        self.codec
+    }
+
+    /// Recreates the encoder at a new resolution, keeping the RTP stream
+    pub(super) fn set_resolution(
+        &mut self,
+        width: u32,
+        height: u32,
+        bitrate: u32,
+    ) -> Result<(), String> {
+        self.encoder = create_encoder(self.codec, width, height, bitrate, self.fps, &self.logger)?;
+        Ok(())
+    }
+
+    /// Encodes a frame
+    ///
+    /// # Returns
+    /// The RTP packets of the encoded frame, empty if the encoder buffered it
+    pub(super) fn encode(&mut self, frame: &VideoFrame) -> Result<Vec<RtpPacket>, String> {
+        let data = self
+            .encoder
+            .encode(frame)
+            .map_err(|e| format!("{} encoding failed: {}", self.codec.encoding_name(), e))?;
+        Ok(self.packetizer.packetize(&data))
+    }
+
+    /// Makes the next encoded frame a keyframe
+    pub(super) fn request_keyframe(&mut self) {
+        self.encoder.request_keyframe();
+    }
+
+    /// Changes the target bitrate of the encoder
+    pub(super) fn set_bitrate(&mut self, bitrate: u32) {
+        self.encoder.set_bitrate(bitrate);
+    }
+}
+
+fn create_encoder(
+    codec: VideoCodec,
+    width: u32,
+    height: u32,
+    bitrate: u32,
+    fps: f64,
+    logger: &Logger,
+) -> Result<Box<dyn VideoEncoder + Send>, String> {
+    codec
+        .create_encoder(
+            width,
+            height,
+            bitrate,
+            fps,
+            KEYFRAME_INTERVAL,
+            logger.clone(),
+        )
+        .map_err(|e| format!("Failed to create {} encoder: {}", codec.encoding_name(), e))
+}
+
+/// Depacketizer and decoder of a received VP8 or VP9 stream
+pub(super) enum CodecReceiver {
+    Vp8(Vp8RtpDepacketizer, VP8Decoder),
+    Vp9(Vp9RtpDepacketizer, VP9Decoder),
+}
+
+impl CodecReceiver {
+    /// Creates the receive path of a codec
+    ///
+    /// # Returns
+    /// `Ok(None)` for H.264, which the decode thread handles itself
+    pub(super) fn new(codec: VideoCodec, logger: &Logger) -> Result<Option<Self>, MediaError> {
+        Ok(match codec {
+            VideoCodec::H264 => None,
+            VideoCodec::VP8 => Some(CodecReceiver::Vp8(
+                Vp8RtpDepacketizer::new(),
+                VP8Decoder::new(logger.clone())?,
+            )),
+            VideoCodec::VP9 => Some(CodecReceiver::Vp9(
+                Vp9RtpDepacketizer::new(),
+                VP9Decoder::new(logger.clone())?,
+            )),
+        })
+    }
+
+    /// Codec of the stream
+    pub(super) fn codec(&self) -> VideoCodec {
+        match self {
+            CodecReceiver::Vp8(..) => VideoCodec::VP8,
+            CodecReceiver::Vp9(..) => VideoCodec::VP9,
+        }
+    }
+
+    /// Adds a packet, returning the frame it completes
+    pub(super) fn process_packet(&mut self, packet: &RtpPacket) -> Option<Vec<u8>> {
+        match self {
+            CodecReceiver::Vp8(depacketizer, _) => depacketizer.process_packet(packet),
+            CodecReceiver::Vp9(depacketizer, _) => depacketizer.process_packet(packet),
+        }
+    }
+
+    /// Returns true once if a keyframe should be requested from the sender (PLI)
+    pub(super) fn take_keyframe_request(&mut self) -> bool {
+        match self {
+            CodecReceiver::Vp8(depacketizer, _) => depacketizer.take_keyframe_request(),
+            CodecReceiver::Vp9(depacketizer, _) => depacketizer.take_keyframe_request(),
+        }
+    }
+
+    /// Decodes a complete frame
+    ///
+    /// # Returns
+    /// `Ok(None)` if the decoder needs more data
+    pub(super) fn decode(&mut self, frame: &[u8]) -> Result<Option<VideoFrame>, MediaError> {
+        match self {
+            CodecReceiver::Vp8(_, decoder) => decoder.decode(frame),
+            CodecReceiver::Vp9(_, decoder) => decoder.decode(frame),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::LogLevel;
+    use opencv::core::{CV_8UC3, Mat, Scalar};
+
+    fn create_test_logger() -> Logger {
+        let log_path = std::env::temp_dir().join("test_video_codec.log");
+        Logger::new(log_path, LogLevel::Debug).unwrap()
+    }
+
+    fn test_frame() -> VideoFrame {
+        let mat = Mat::new_rows_cols_with_default(240, 320, CV_8UC3, Scalar::all(128.0)).unwrap();
+        VideoFrame::new(mat)
+    }
+
+    #[test]
+    fn test_payload_types_map_both_ways() {
+        for (payload_type, codec) in VIDEO_CODECS {
+            assert_eq!(payload_type_of(codec), payload_type);
+            assert_eq!(codec_of(payload_type), Some(codec));
+        }
+        assert_eq!(codec_of(111), None);
+    }
+
+    #[test]
+    fn test_vp9_stream_round_trips() {
+        let logger = create_test_logger();
+        let Ok(mut sender) = CodecSender::new(
+            VideoCodec::VP9,
+            (320, 240, 500_000),
+            30.0,
+            1234,
+            logger.clone(),
+        ) else {
+            // No VP9 encoder in this environment
+            return;
+        };
+        let mut receiver = CodecReceiver::new(VideoCodec::VP9, &logger)
+            .unwrap()
+            .unwrap();
+
+        let mut decoded = None;
+        for _ in 0..5 {
+            let packets = sender.encode(&test_frame()).unwrap();
+            assert!(packets.iter().all(|p| p.header.ssrc == 1234));
+            assert!(packets.iter().all(|p| p.header.payload_type == 98));
+            for packet in &packets {
+                if let Some(frame) = receiver.process_packet(packet) {
+                    decoded = receiver.decode(&frame).unwrap().or(decoded);
+                }
+            }
+        }
+
+        let frame = decoded.expect("no VP9 frame decoded");
+        assert_eq!((frame.width(), frame.height()), (320, 240));
+    }
+}
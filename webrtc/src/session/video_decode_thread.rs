@@ -1,24 +1,33 @@
 //! Video decoding thread
 //!
 //! This module handles the dedicated thread for popping frames from the jitter buffer,
-//! depacketizing, decoding H264, and sending frames to the application.
+//! depacketizing, decoding (H264, or VP8/VP9 by payload type), and sending frames
+//! to the application.
 //! This decouples the heavy decoding workload from the network reception thread.
 
 use logging::Logger;
-use media::{H264Decoder, VideoFrame};
-use network::{H264RtpDepacketizer, JitterBuffer, RtpDepacketizer};
+use media::{H264Decoder, VideoCodec, VideoFrame};
+use network::{H264RtpDepacketizer, JitterBuffer, PopResult, RtpDepacketizer, SecureUdpTransport};
 use std::sync::mpsc::SyncSender;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use super::recording::{RecordedTrack, Recorder};
 use super::send_thread::get_nal_type;
+use super::video_codec::{self, CodecReceiver};
+
+/// Minimum time between two Picture Loss Indications for the same stream
+const PLI_MIN_INTERVAL: Duration = Duration::from_millis(500);
 
 /// Parameters for the video decode thread
 pub struct VideoDecodeThreadParams {
     pub jitter_buffer: Arc<Mutex<JitterBuffer>>,
     pub decoder: Arc<Mutex<H264Decoder>>,
     pub tx_decode: SyncSender<VideoFrame>,
+    pub transport: Arc<Mutex<Option<SecureUdpTransport>>>,
+    /// Recorder of the call, for the stream shown as the remote video
+    pub recorder: Option<Recorder>,
     pub logger: Logger,
 }
 
@@ -26,11 +35,14 @@ pub fn run_video_decode_thread(params: VideoDecodeThreadParams) {
     params.logger.info("Video Decode thread started");
 
     let mut depacketizer = H264RtpDepacketizer::new();
+    let mut codec_receiver: Option<CodecReceiver> = None;
     let mut frames_decoded: u64 = 0;
+    let mut last_pli_sent: Option<Instant> = None;
+    let mut last_frame: Option<VideoFrame> = None;
 
     loop {
         // Try to pop a packet from the jitter buffer
-        let packet = {
+        let pop_result = {
             let mut jitter = params.jitter_buffer.lock().unwrap_or_else(|poisoned| {
                 params
                     .logger
@@ -40,55 +52,173 @@ pub fn run_video_decode_thread(params: VideoDecodeThreadParams) {
             jitter.pop()
         };
 
-        if let Some(packet) = packet {
-            // Process packet
-            if let Some(nal_data) = depacketizer.process_packet(&packet) {
-                let nal_type = get_nal_type(&nal_data);
+        let packet = match pop_result {
+            PopResult::Packet(packet) => packet,
+            PopResult::Concealed => {
+                // Packet lost: repeat the last frame until the depacketizer
+                // notices the gap and the sender delivers a keyframe
+                if let Some(frame) = &last_frame {
+                    let _ = params.tx_decode.try_send(frame.clone());
+                }
+                continue;
+            }
+            PopResult::Empty => {
+                if Arc::strong_count(&params.jitter_buffer) == 1 {
+                    // The stream was removed and nothing can push to the buffer anymore
+                    params
+                        .logger
+                        .info("Video stream ended, decode thread stopping");
+                    return;
+                }
+                // No packets in jitter buffer, sleep briefly to avoid busy loop
+                // The jitter buffer 'pop' is non-blocking effectively if we hold the lock,
+                // but the jitter buffer logic usually returns None if not ready.
+                // We sleep a bit to give recv_thread time to push.
+                thread::sleep(Duration::from_millis(1));
+                continue;
+            }
+        };
+
+        select_codec_receiver(&params, &mut codec_receiver, packet.header.payload_type);
+
+        // Process packet; a complete access unit is returned on the marker bit
+        let (access_unit, keyframe_needed) = match codec_receiver.as_mut() {
+            Some(receiver) => (
+                receiver.process_packet(&packet),
+                receiver.take_keyframe_request(),
+            ),
+            None => (
+                depacketizer.process_packet(&packet),
+                depacketizer.take_keyframe_request(),
+            ),
+        };
 
-                // Decode
-                let decoded_result = {
+        let recording_needs_keyframe = params
+            .recorder
+            .as_ref()
+            .is_some_and(|recorder| recorder.needs_keyframe(RecordedTrack::RemoteVideo));
+        if keyframe_needed || recording_needs_keyframe {
+            request_keyframe(&params, packet.header.ssrc, &mut last_pli_sent);
+        }
+
+        if let Some(nal_data) = access_unit {
+            let nal_type = get_nal_type(&nal_data);
+
+            // Decode
+            let decoded_result = match codec_receiver.as_mut() {
+                Some(receiver) => receiver.decode(&nal_data),
+                None => {
                     let mut decoder = params.decoder.lock().unwrap_or_else(|poisoned| {
                         params.logger.error("Decoder mutex poisoned, recovering");
                         poisoned.into_inner()
                     });
-                    // Log decode attempt here if needed for deep debugging, but keep clean for now
                     decoder.decode(&nal_data)
-                };
-
-                match decoded_result {
-                    Ok(Some(frame)) => {
-                        frames_decoded += 1;
-                        if frames_decoded.is_multiple_of(30) {
-                            params.logger.info(&format!(
-                                "DECODED frame #{}: {}x{}",
-                                frames_decoded,
-                                frame.width(),
-                                frame.height()
-                            ));
-                        }
-
-                        if params.tx_decode.try_send(frame).is_err() {
-                            params
-                                .logger
-                                .warn("Failed to send decoded frame (channel full)");
-                        }
+                }
+            };
+
+            match decoded_result {
+                Ok(Some(frame)) => {
+                    frames_decoded += 1;
+                    if frames_decoded.is_multiple_of(30) {
+                        params.logger.info(&format!(
+                            "DECODED frame #{}: {}x{}",
+                            frames_decoded,
+                            frame.width(),
+                            frame.height()
+                        ));
                     }
-                    Ok(None) => {
-                        // Decoder needs more data
+
+                    // The recorder muxes H.264 only
+                    if let Some(recorder) = &params.recorder
+                        && codec_receiver.is_none()
+                        && let Err(e) = recorder.record_video(
+                            RecordedTrack::RemoteVideo,
+                            &nal_data,
+                            frame.width(),
+                            frame.height(),
+                        )
+                    {
+                        params.logger.error(&e);
                     }
-                    Err(e) => {
+
+                    last_frame = Some(frame.clone());
+                    if params.tx_decode.try_send(frame).is_err() {
                         params
                             .logger
-                            .error(&format!("Decode error (NAL type {}): {}", nal_type, e));
+                            .warn("Failed to send decoded frame (channel full)");
                     }
                 }
+                Ok(None) => {
+                    // Decoder needs more data
+                }
+                Err(e) => {
+                    params
+                        .logger
+                        .error(&format!("Decode error (NAL type {}): {}", nal_type, e));
+                }
+            }
+        }
+    }
+}
+
+/// Switches the receive path to the codec of a packet's payload type
+///
+/// H.264 packets (and unknown payload types) use the thread's H.264
+/// depacketizer and decoder; VP8 and VP9 get their own, created on their
+/// first packet.
+fn select_codec_receiver(
+    params: &VideoDecodeThreadParams,
+    codec_receiver: &mut Option<CodecReceiver>,
+    payload_type: u8,
+) {
+    let Some(codec) = video_codec::codec_of(payload_type) else {
+        return;
+    };
+    if codec_receiver.as_ref().map_or(VideoCodec::H264, CodecReceiver::codec) == codec {
+        return;
+    }
+
+    match CodecReceiver::new(codec, &params.logger) {
+        Ok(receiver) => {
+            params
+                .logger
+                .info(&format!("Receiving video as {}", codec.encoding_name()));
+            *codec_receiver = receiver;
+        }
+        Err(e) => params.logger.error(&format!(
+            "Failed to create {} decoder: {}",
+            codec.encoding_name(),
+            e
+        )),
+    }
+}
+
+/// Send a PLI to the remote sender, rate limited to one per `PLI_MIN_INTERVAL`
+fn request_keyframe(
+    params: &VideoDecodeThreadParams,
+    media_ssrc: u32,
+    last_pli_sent: &mut Option<Instant>,
+) {
+    if last_pli_sent.is_some_and(|sent| sent.elapsed() < PLI_MIN_INTERVAL) {
+        return;
+    }
+
+    let mut transport_guard = params.transport.lock().unwrap_or_else(|poisoned| {
+        params
+            .logger
+            .error("Transport mutex poisoned in video decode thread, recovering");
+        poisoned.into_inner()
+    });
+
+    if let Some(transport) = transport_guard.as_mut() {
+        match transport.send_pli(media_ssrc) {
+            Ok(()) => {
+                params
+                    .logger
+                    .info("Missing keyframe, sent PLI to remote sender");
+                *last_pli_sent = Some(Instant::now());
             }
-        } else {
-            // No packets in jitter buffer, sleep briefly to avoid busy loop
-            // The jitter buffer 'pop' is non-blocking effectively if we hold the lock,
-            // but the jitter buffer logic usually returns None if not ready.
-            // We sleep a bit to give recv_thread time to push.
-            thread::sleep(Duration::from_millis(1));
+            Err(e) => params.logger.error(&format!("Failed to send PLI: {}", e)),
         }
     }
 }
@@ -8,8 +8,18 @@
 /// Attributes provide additional information in STUN messages.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AttributeType {
+    /// ERROR-CODE (0x0009) - Error class, number and reason phrase
+    ErrorCode,
     /// XOR-MAPPED-ADDRESS (0x0020) - XOR'd reflexive transport address (recommended)
     XorMappedAddress,
+    /// PRIORITY (0x0024) - Priority of the peer reflexive candidate (RFC 8445)
+    Priority,
+    /// USE-CANDIDATE (0x0025) - Nominates the pair the check is sent on (RFC 8445)
+    UseCandidate,
+    /// ICE-CONTROLLED (0x8029) - Sender is the controlled agent (RFC 8445)
+    IceControlled,
+    /// ICE-CONTROLLING (0x802A) - Sender is the controlling agent (RFC 8445)
+    IceControlling,
 }
 
 impl AttributeType {
@@ -19,7 +29,12 @@ impl AttributeType {
     /// The u16 representation of the attribute type
     pub fn to_u16(self) -> u16 {
         match self {
+            AttributeType::ErrorCode => 0x0009,
             AttributeType::XorMappedAddress => 0x0020,
+            AttributeType::Priority => 0x0024,
+            AttributeType::UseCandidate => 0x0025,
+            AttributeType::IceControlled => 0x8029,
+            AttributeType::IceControlling => 0x802A,
         }
     }
 }
@@ -30,6 +45,11 @@ mod tests {
 
     #[test]
     fn test_attribute_type_to_u16() {
+        assert_eq!(AttributeType::ErrorCode.to_u16(), 0x0009);
         assert_eq!(AttributeType::XorMappedAddress.to_u16(), 0x0020);
+        assert_eq!(AttributeType::Priority.to_u16(), 0x0024);
+        assert_eq!(AttributeType::UseCandidate.to_u16(), 0x0025);
+        assert_eq!(AttributeType::IceControlled.to_u16(), 0x8029);
+        assert_eq!(AttributeType::IceControlling.to_u16(), 0x802A);
     }
 }
@@ -2,27 +2,42 @@
 //!
 //! This module provides a STUN client for discovering reflexive (public) addresses.
 //! The client sends Binding Requests to a STUN server and receives the reflexive
-//! address in the response.
+//! address in the response, over UDP, TCP or TLS.
 
-use std::io;
-use std::net::{SocketAddr, UdpSocket};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+use std::sync::Mutex;
 use std::time::Duration;
 
 use crate::attribute_type::AttributeType;
+use crate::errors::StunError;
 use crate::message::Message;
 use crate::message_builder::MessageBuilder;
+use crate::message_header::MessageHeader;
 use crate::message_type::MessageType;
+use crate::server_url::{StunServerUrl, StunTransport};
 use crate::xor_mapped_address;
 
 /// Maximum STUN message size (typical)
 const MAX_STUN_MESSAGE_SIZE: usize = 548;
 
+/// Timeout for connecting, sending and receiving
+const IO_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Connection to the STUN server.
+enum Transport {
+    Udp(UdpSocket),
+    Tcp(Mutex<TcpStream>),
+    #[cfg(feature = "tls")]
+    Tls(Mutex<native_tls::TlsStream<TcpStream>>),
+}
+
 /// STUN client for discovering reflexive addresses.
 ///
 /// The client sends Binding Requests to a STUN server and receives
 /// the reflexive (public) address in the response.
 pub struct StunClient {
-    socket: UdpSocket,
+    transport: Transport,
     server_addr: SocketAddr,
 }
 
@@ -38,20 +53,134 @@ impl StunClient {
     /// * `Err(io::Error)` - If binding fails
     pub fn new(bind_addr: SocketAddr, server_addr: SocketAddr) -> io::Result<Self> {
         let socket = UdpSocket::bind(bind_addr)?;
-        socket.set_read_timeout(Some(Duration::from_secs(3)))?;
-        socket.set_write_timeout(Some(Duration::from_secs(3)))?;
+        socket.set_read_timeout(Some(IO_TIMEOUT))?;
+        socket.set_write_timeout(Some(IO_TIMEOUT))?;
+
+        Ok(Self {
+            transport: Transport::Udp(socket),
+            server_addr,
+        })
+    }
+
+    /// Creates a STUN client that talks to the server over TCP.
+    ///
+    /// # Arguments
+    /// * `server_addr` - Address of the STUN server
+    ///
+    /// # Returns
+    /// * `Ok(StunClient)` - If the connection was established
+    /// * `Err(io::Error)` - If connecting fails
+    pub fn connect_tcp(server_addr: SocketAddr) -> io::Result<Self> {
+        let stream = Self::connect_stream(server_addr, IO_TIMEOUT)?;
+        Ok(Self {
+            transport: Transport::Tcp(Mutex::new(stream)),
+            server_addr,
+        })
+    }
+
+    /// Creates a STUN client that talks to the server over TLS (`stuns:`).
+    ///
+    /// # Arguments
+    /// * `server_addr` - Address of the STUN server (usually port 5349)
+    /// * `server_name` - Host name the server certificate must match
+    ///
+    /// # Returns
+    /// * `Ok(StunClient)` - If the TLS handshake succeeded
+    /// * `Err(io::Error)` - If connecting or the handshake fails
+    #[cfg(feature = "tls")]
+    pub fn connect_tls(server_addr: SocketAddr, server_name: &str) -> io::Result<Self> {
+        Self::connect_tls_with_timeout(server_addr, server_name, IO_TIMEOUT)
+    }
+
+    #[cfg(feature = "tls")]
+    fn connect_tls_with_timeout(
+        server_addr: SocketAddr,
+        server_name: &str,
+        timeout: Duration,
+    ) -> io::Result<Self> {
+        let stream = Self::connect_stream(server_addr, timeout)?;
+        let connector = native_tls::TlsConnector::new().map_err(io::Error::other)?;
+        let stream = connector
+            .connect(server_name, stream)
+            .map_err(|e| io::Error::other(e.to_string()))?;
 
         Ok(Self {
-            socket,
+            transport: Transport::Tls(Mutex::new(stream)),
             server_addr,
         })
     }
 
+    fn connect_stream(server_addr: SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        let stream = TcpStream::connect_timeout(&server_addr, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        stream.set_nodelay(true)?;
+        Ok(stream)
+    }
+
+    /// Sets how long sends and receives wait before failing.
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum wait per operation (must be non-zero)
+    pub fn set_timeout(&self, timeout: Duration) -> io::Result<()> {
+        match &self.transport {
+            Transport::Udp(socket) => {
+                socket.set_read_timeout(Some(timeout))?;
+                socket.set_write_timeout(Some(timeout))
+            }
+            Transport::Tcp(stream) => {
+                let stream = stream
+                    .lock()
+                    .map_err(|_| io::Error::other("STUN stream poisoned"))?;
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))
+            }
+            #[cfg(feature = "tls")]
+            Transport::Tls(stream) => {
+                let stream = stream
+                    .lock()
+                    .map_err(|_| io::Error::other("STUN stream poisoned"))?;
+                stream.get_ref().set_read_timeout(Some(timeout))?;
+                stream.get_ref().set_write_timeout(Some(timeout))
+            }
+        }
+    }
+
+    /// Sends a request and returns the raw bytes of the next message received.
+    fn exchange(&self, request: &[u8]) -> io::Result<Vec<u8>> {
+        match &self.transport {
+            Transport::Udp(socket) => {
+                socket.send_to(request, self.server_addr)?;
+                // Use smaller buffer for efficiency
+                let mut buf = [0u8; MAX_STUN_MESSAGE_SIZE];
+                let (size, _) = socket.recv_from(&mut buf)?;
+                Ok(buf[..size].to_vec())
+            }
+            Transport::Tcp(stream) => {
+                let mut stream = stream
+                    .lock()
+                    .map_err(|_| io::Error::other("STUN stream poisoned"))?;
+                stream.write_all(request)?;
+                read_stream_message(&mut *stream)
+            }
+            #[cfg(feature = "tls")]
+            Transport::Tls(stream) => {
+                let mut stream = stream
+                    .lock()
+                    .map_err(|_| io::Error::other("STUN stream poisoned"))?;
+                stream.write_all(request)?;
+                read_stream_message(&mut *stream)
+            }
+        }
+    }
+
     /// Performs a STUN Binding Request to discover the reflexive address.
     ///
     /// # Returns
     /// * `Ok(SocketAddr)` - The reflexive address returned by the server
-    /// * `Err(io::Error)` - If the request fails
+    /// * `Err(io::Error)` - If the request fails. An error response from the
+    ///   server wraps [`StunError::ServerError`] with its code and reason
+    ///   (see [`io::Error::get_ref`])
     pub fn get_reflexive_address(&self) -> io::Result<SocketAddr> {
         // Create Binding Request using MessageBuilder
         let request = MessageBuilder::new(MessageType::Request)
@@ -59,18 +188,26 @@ impl StunClient {
             .build()
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
 
-        // Send request
-        self.socket.send_to(&request.encode(), self.server_addr)?;
-
-        // Receive response (use smaller buffer for efficiency)
-        let mut buf = [0u8; MAX_STUN_MESSAGE_SIZE];
-        let (size, _) = self.socket.recv_from(&mut buf)?;
+        // Send request and receive the response
+        let response_bytes = self.exchange(&request.encode())?;
 
         // Parse response
-        let response = Message::decode(&buf[..size]).map_err(|_| {
+        let response = Message::decode(&response_bytes).map_err(|_| {
             io::Error::new(io::ErrorKind::InvalidData, "Failed to decode STUN response")
         })?;
 
+        // Surface error responses with their ERROR-CODE
+        if response.message_type() == MessageType::ErrorResponse
+            && response.transaction_id() == request.transaction_id()
+        {
+            let error = match response.error_code() {
+                Some(Ok((code, reason))) => StunError::ServerError { code, reason },
+                Some(Err(e)) => e,
+                None => StunError::UnexpectedMessageType,
+            };
+            return Err(io::Error::other(error));
+        }
+
         // Verify it's a Binding Response
         if response.message_type() != MessageType::Response {
             return Err(io::Error::new(
@@ -140,9 +277,12 @@ impl StunClient {
     /// This helper resolves each server name (DNS lookup), tries each resolved address,
     /// and returns the reflexive address from the first successful server.
     ///
+    /// Servers are STUN URLs (see [`StunServerUrl`]): `stun:` and bare
+    /// `host:port` use UDP, `?transport=tcp` uses TCP and `stuns:` uses TLS.
+    ///
     /// # Arguments
-    /// * `bind_addr` - Local address to bind the socket to
-    /// * `servers` - Array of server names like "stun.l.google.com:19302"
+    /// * `bind_addr` - Local address to bind the socket to (UDP only)
+    /// * `servers` - Array of server URLs like "stun.l.google.com:19302" or "stuns:example.com"
     ///
     /// # Returns
     /// * `Ok(SocketAddr)` - The reflexive address from the first successful server
@@ -152,8 +292,6 @@ impl StunClient {
         bind_addr: SocketAddr,
         servers: &[String],
     ) -> io::Result<SocketAddr> {
-        use std::net::ToSocketAddrs;
-
         let mut last_error = None;
 
         for (i, server_str) in servers.iter().enumerate() {
@@ -164,59 +302,119 @@ impl StunClient {
                 server_str
             );
 
-            let addrs: Vec<SocketAddr> = match server_str.to_socket_addrs() {
-                Ok(iter) => {
-                    let addrs: Vec<_> = iter.collect();
-                    println!(
-                        "[STUN] Resolved {} to {} address(es)",
-                        server_str,
-                        addrs.len()
-                    );
-                    addrs
+            match Self::discover_reflexive_from_server(bind_addr, server_str, IO_TIMEOUT) {
+                Ok(reflexive) => return Ok(reflexive),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| io::Error::other("All STUN servers failed")))
+    }
+
+    /// Discovers the reflexive address through a single STUN server.
+    ///
+    /// Resolves the server name and tries each resolved address in turn;
+    /// every connect, send and receive waits at most `timeout`, so an
+    /// unreachable server fails quickly instead of stalling gathering.
+    ///
+    /// # Arguments
+    /// * `bind_addr` - Local address to bind the socket to (UDP only)
+    /// * `server` - Server URL (see [`StunServerUrl`])
+    /// * `timeout` - Maximum wait per network operation
+    ///
+    /// # Returns
+    /// * `Ok(SocketAddr)` - The reflexive address returned by the server
+    /// * `Err(io::Error)` - If the URL is invalid or every address fails
+    pub fn discover_reflexive_from_server(
+        bind_addr: SocketAddr,
+        server: &str,
+        timeout: Duration,
+    ) -> io::Result<SocketAddr> {
+        use std::net::ToSocketAddrs;
+
+        let url = StunServerUrl::parse(server).inspect_err(|e| println!("[STUN] {}", e))?;
+
+        let addrs: Vec<SocketAddr> = match (url.host.as_str(), url.port).to_socket_addrs() {
+            Ok(iter) => {
+                let addrs: Vec<_> = iter.collect();
+                println!("[STUN] Resolved {} to {} address(es)", server, addrs.len());
+                addrs
+            }
+            Err(e) => {
+                println!("[STUN] DNS resolution failed for {}: {}", server, e);
+                return Err(e);
+            }
+        };
+
+        let mut last_error = None;
+
+        for (j, server_addr) in addrs.iter().enumerate() {
+            println!(
+                "[STUN] Trying address {}/{}: {}",
+                j + 1,
+                addrs.len(),
+                server_addr
+            );
+
+            let client = match url.transport {
+                StunTransport::Udp => StunClient::new(bind_addr, *server_addr),
+                StunTransport::Tcp => {
+                    Self::connect_stream(*server_addr, timeout).map(|stream| Self {
+                        transport: Transport::Tcp(Mutex::new(stream)),
+                        server_addr: *server_addr,
+                    })
                 }
-                Err(e) => {
-                    println!("[STUN] DNS resolution failed for {}: {}", server_str, e);
-                    last_error = Some(e);
-                    continue;
+                #[cfg(feature = "tls")]
+                StunTransport::Tls => {
+                    StunClient::connect_tls_with_timeout(*server_addr, &url.host, timeout)
                 }
+                #[cfg(not(feature = "tls"))]
+                StunTransport::Tls => Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "stuns: requires the tls feature",
+                )),
             };
 
-            if addrs.is_empty() {
-                println!("[STUN] No addresses resolved for {}", server_str);
-                continue;
-            }
-
-            for (j, server_addr) in addrs.iter().enumerate() {
-                println!(
-                    "[STUN] Trying address {}/{}: {}",
-                    j + 1,
-                    addrs.len(),
-                    server_addr
-                );
-
-                match StunClient::new(bind_addr, *server_addr) {
-                    Ok(client) => match client.get_reflexive_address() {
-                        Ok(reflexive) => {
-                            println!("[STUN] SUCCESS! Got reflexive address: {}", reflexive);
-                            return Ok(reflexive);
-                        }
-                        Err(e) => {
-                            println!("[STUN] Query failed to {}: {}", server_addr, e);
-                            last_error = Some(e);
-                        }
-                    },
-                    Err(e) => {
-                        println!("[STUN] Failed to create client for {}: {}", server_addr, e);
-                        last_error = Some(e);
-                    }
+            let result = client.and_then(|client| {
+                client.set_timeout(timeout)?;
+                client.get_reflexive_address()
+            });
+            match result {
+                Ok(reflexive) => {
+                    println!("[STUN] SUCCESS! Got reflexive address: {}", reflexive);
+                    return Ok(reflexive);
+                }
+                Err(e) => {
+                    println!("[STUN] Query failed to {}: {}", server_addr, e);
+                    last_error = Some(e);
                 }
             }
         }
 
-        Err(last_error.unwrap_or_else(|| io::Error::other("All STUN servers failed")))
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No addresses resolved for {}", server),
+            )
+        }))
     }
 }
 
+/// Reads one STUN message from a stream.
+///
+/// Over TCP and TLS, STUN messages are sent back to back without extra
+/// framing (RFC 5389 Section 7.2.2); the header's length field tells where
+/// each message ends.
+fn read_stream_message<R: Read>(stream: &mut R) -> io::Result<Vec<u8>> {
+    let mut message = vec![0u8; MessageHeader::SIZE];
+    stream.read_exact(&mut message)?;
+
+    let length = u16::from_be_bytes([message[2], message[3]]) as usize;
+    message.resize(MessageHeader::SIZE + length, 0);
+    stream.read_exact(&mut message[MessageHeader::SIZE..])?;
+    Ok(message)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,4 +446,73 @@ mod tests {
         // Transaction IDs should be different
         assert_ne!(msg1.transaction_id(), msg2.transaction_id());
     }
+
+    #[test]
+    fn test_read_stream_message_splits_back_to_back_messages() {
+        let mut first = Message::new(MessageType::Request, [1; 12]);
+        first.add_attribute(AttributeType::Priority, &[0, 0, 0, 1]);
+        let second = Message::new(MessageType::Response, [2; 12]);
+
+        let mut bytes = first.encode();
+        bytes.extend(second.encode());
+        let mut reader = bytes.as_slice();
+
+        assert_eq!(read_stream_message(&mut reader).unwrap(), first.encode());
+        assert_eq!(read_stream_message(&mut reader).unwrap(), second.encode());
+        assert!(read_stream_message(&mut reader).is_err());
+    }
+
+    /// Spawns a mock TCP STUN server answering a single Binding request.
+    fn spawn_tcp_responder<F>(respond: F) -> (SocketAddr, std::thread::JoinHandle<()>)
+    where
+        F: FnOnce([u8; 12]) -> Message + Send + 'static,
+    {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = Message::decode(&read_stream_message(&mut stream).unwrap()).unwrap();
+            assert_eq!(request.message_type(), MessageType::Request);
+            let response = respond(request.transaction_id());
+            stream.write_all(&response.encode()).unwrap();
+        });
+        (server_addr, handle)
+    }
+
+    #[test]
+    fn test_binding_request_over_tcp() {
+        let reflexive: SocketAddr = "203.0.113.5:54321".parse().unwrap();
+        let (server_addr, server) = spawn_tcp_responder(move |transaction_id| {
+            let mut response = Message::new(MessageType::Response, transaction_id);
+            response.add_xor_mapped_address(reflexive);
+            response
+        });
+
+        let client = StunClient::connect_tcp(server_addr).unwrap();
+        assert_eq!(client.get_reflexive_address().unwrap(), reflexive);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_error_response_surfaces_server_error() {
+        let (server_addr, server) = spawn_tcp_responder(|transaction_id| {
+            let mut response = Message::new(MessageType::ErrorResponse, transaction_id);
+            response.add_error_code(401, "Unauthorized");
+            response
+        });
+
+        let client = StunClient::connect_tcp(server_addr).unwrap();
+        let err = client.get_reflexive_address().unwrap_err();
+        let stun_error = err.get_ref().and_then(|e| e.downcast_ref::<StunError>());
+        assert_eq!(
+            stun_error,
+            Some(&StunError::ServerError {
+                code: 401,
+                reason: "Unauthorized".to_string()
+            })
+        );
+        server.join().unwrap();
+    }
 }
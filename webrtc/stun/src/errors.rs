@@ -41,6 +41,8 @@ pub enum StunError {
     TransactionIdMismatch,
     /// Unexpected message type
     UnexpectedMessageType,
+    /// Error response from the server (ERROR-CODE attribute)
+    ServerError { code: u16, reason: String },
 }
 
 impl std::fmt::Display for StunError {
@@ -67,6 +69,9 @@ impl std::fmt::Display for StunError {
             StunError::Timeout => write!(f, "Timeout waiting for response"),
             StunError::TransactionIdMismatch => write!(f, "Transaction ID mismatch"),
             StunError::UnexpectedMessageType => write!(f, "Unexpected message type"),
+            StunError::ServerError { code, reason } => {
+                write!(f, "STUN server error {}: {}", code, reason)
+            }
         }
     }
 }
@@ -10,7 +10,12 @@ mod message;
 mod message_builder;
 mod message_header;
 mod message_type;
+mod server_url;
 mod xor_mapped_address;
 
+pub use attribute_type::AttributeType;
 pub use client::StunClient;
 pub use errors::StunError;
+pub use message::Message;
+pub use message_type::MessageType;
+pub use server_url::{StunServerUrl, StunTransport};
@@ -70,6 +70,48 @@ impl Message {
         self.header.set_message_length(self.attributes.len() as u16);
     }
 
+    /// Adds an ERROR-CODE attribute (RFC 5389 Section 15.6).
+    ///
+    /// # Arguments
+    /// * `code` - Error code from 300 to 699 (e.g. 401)
+    /// * `reason` - Human readable reason phrase
+    pub fn add_error_code(&mut self, code: u16, reason: &str) {
+        let mut value = vec![0, 0, (code / 100) as u8, (code % 100) as u8];
+        value.extend_from_slice(reason.as_bytes());
+        self.add_attribute(AttributeType::ErrorCode, &value);
+    }
+
+    /// Decodes the ERROR-CODE attribute of an error response.
+    ///
+    /// # Returns
+    /// * `Some(Ok((code, reason)))` - Code as class * 100 + number, and the reason phrase
+    /// * `Some(Err(StunError))` - If the attribute is malformed
+    /// * `None` - If the message has no ERROR-CODE attribute
+    pub fn error_code(&self) -> Option<Result<(u16, String), StunError>> {
+        let value = self.attribute(AttributeType::ErrorCode)?;
+        if value.len() < 4 {
+            return Some(Err(StunError::AttributeTooShort));
+        }
+
+        let class = u16::from(value[2] & 0x07);
+        let number = u16::from(value[3]);
+        if !(3..=6).contains(&class) || number > 99 {
+            return Some(Err(StunError::InvalidAttributeFormat));
+        }
+
+        let reason = String::from_utf8_lossy(&value[4..]).into_owned();
+        Some(Ok((class * 100 + number, reason)))
+    }
+
+    /// Adds an XOR-MAPPED-ADDRESS attribute carrying `addr`.
+    ///
+    /// # Arguments
+    /// * `addr` - The reflexive address seen by the sender
+    pub fn add_xor_mapped_address(&mut self, addr: std::net::SocketAddr) {
+        let value = crate::xor_mapped_address::encode(addr, &self.transaction_id());
+        self.add_attribute(AttributeType::XorMappedAddress, &value);
+    }
+
     /// Encodes the complete message to bytes.
     ///
     /// # Returns
@@ -117,6 +159,35 @@ impl Message {
     pub fn attributes_bytes(&self) -> &[u8] {
         &self.attributes
     }
+
+    /// Gets the value of the first attribute of the given type.
+    ///
+    /// # Arguments
+    /// * `attr_type` - The attribute type to look for
+    ///
+    /// # Returns
+    /// The attribute value (without padding), or `None` if it is absent
+    pub fn attribute(&self, attr_type: AttributeType) -> Option<&[u8]> {
+        let attrs = &self.attributes;
+        let mut offset = 0;
+
+        while offset + 4 <= attrs.len() {
+            let found_type = u16::from_be_bytes([attrs[offset], attrs[offset + 1]]);
+            let length = u16::from_be_bytes([attrs[offset + 2], attrs[offset + 3]]) as usize;
+            offset += 4;
+
+            if offset + length > attrs.len() {
+                return None;
+            }
+            if found_type == attr_type.to_u16() {
+                return Some(&attrs[offset..offset + length]);
+            }
+
+            offset += length + (4 - (length % 4)) % 4;
+        }
+
+        None
+    }
 }
 
 #[cfg(test)]
@@ -148,6 +219,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_message_attribute_lookup() {
+        let mut message = Message::new(MessageType::Request, [7; 12]);
+        message.add_attribute(AttributeType::Priority, &1_845_501_695u32.to_be_bytes());
+        message.add_attribute(AttributeType::UseCandidate, &[]);
+        message.add_attribute(AttributeType::IceControlling, &[0xAB; 8]);
+
+        let decoded = Message::decode(&message.encode()).unwrap();
+        assert_eq!(
+            decoded.attribute(AttributeType::Priority),
+            Some(&1_845_501_695u32.to_be_bytes()[..])
+        );
+        assert_eq!(
+            decoded.attribute(AttributeType::UseCandidate),
+            Some(&[][..])
+        );
+        assert_eq!(
+            decoded.attribute(AttributeType::IceControlling),
+            Some(&[0xAB; 8][..])
+        );
+        assert!(decoded.attribute(AttributeType::IceControlled).is_none());
+    }
+
+    #[test]
+    fn test_parse_401_error_response() {
+        // Binding error response with ERROR-CODE 401 "Unauthorized"
+        let mut bytes = vec![
+            0x01, 0x11, 0x00, 0x14, // type 0x0111, length 20
+            0x21, 0x12, 0xA4, 0x42, // magic cookie
+        ];
+        bytes.extend_from_slice(&[3; 12]); // transaction ID
+        bytes.extend_from_slice(&[0x00, 0x09, 0x00, 0x10]); // ERROR-CODE, length 16
+        bytes.extend_from_slice(&[0x00, 0x00, 0x04, 0x01]); // class 4, number 1
+        bytes.extend_from_slice(b"Unauthorized");
+
+        let message = Message::decode(&bytes).unwrap();
+        assert_eq!(message.message_type(), MessageType::ErrorResponse);
+        assert_eq!(
+            message.error_code(),
+            Some(Ok((401, "Unauthorized".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_error_code_roundtrip_and_validation() {
+        let mut message = Message::new(MessageType::ErrorResponse, [4; 12]);
+        message.add_error_code(438, "Stale Nonce");
+        let decoded = Message::decode(&message.encode()).unwrap();
+        assert_eq!(
+            decoded.error_code(),
+            Some(Ok((438, "Stale Nonce".to_string())))
+        );
+
+        let mut bad = Message::new(MessageType::ErrorResponse, [4; 12]);
+        bad.add_attribute(AttributeType::ErrorCode, &[0, 0, 1, 0]);
+        assert_eq!(
+            bad.error_code(),
+            Some(Err(StunError::InvalidAttributeFormat))
+        );
+
+        assert!(
+            Message::new(MessageType::Response, [4; 12])
+                .error_code()
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_message_encode_decode() {
         let transaction_id = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
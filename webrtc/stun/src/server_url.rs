@@ -0,0 +1,160 @@
+//! STUN server URLs
+//!
+//! This module parses STUN server URLs according to RFC 7064:
+//! `stun:host[:port][?transport=udp|tcp]` and `stuns:host[:port]`.
+//! A bare `host:port` is accepted as STUN over UDP.
+
+use std::io;
+
+/// Default port for STUN over UDP and TCP.
+pub const DEFAULT_PORT: u16 = 3478;
+
+/// Default port for STUN over TLS.
+pub const DEFAULT_TLS_PORT: u16 = 5349;
+
+/// Transport used to reach a STUN server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StunTransport {
+    /// Plain UDP datagrams
+    Udp,
+    /// STUN over a TCP stream
+    Tcp,
+    /// STUN over TLS (`stuns:`)
+    Tls,
+}
+
+/// A parsed STUN server URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StunServerUrl {
+    /// Transport to use
+    pub transport: StunTransport,
+    /// Host name or IP address (without IPv6 brackets)
+    pub host: String,
+    /// Server port
+    pub port: u16,
+}
+
+impl StunServerUrl {
+    /// Parses a STUN server URL.
+    ///
+    /// # Arguments
+    /// * `url` - e.g. `stun.l.google.com:19302`, `stun:example.com?transport=tcp`
+    ///   or `stuns:example.com:5349`
+    ///
+    /// # Returns
+    /// * `Ok(StunServerUrl)` - The parsed URL
+    /// * `Err(io::Error)` - If the URL is malformed
+    pub fn parse(url: &str) -> io::Result<Self> {
+        let invalid = |reason: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid STUN URL '{}': {}", url, reason),
+            )
+        };
+
+        let (mut transport, rest) = if let Some(rest) = url.strip_prefix("stuns:") {
+            (StunTransport::Tls, rest)
+        } else if let Some(rest) = url.strip_prefix("stun:") {
+            (StunTransport::Udp, rest)
+        } else {
+            (StunTransport::Udp, url)
+        };
+
+        let (authority, query) = match rest.split_once('?') {
+            Some((authority, query)) => (authority, Some(query)),
+            None => (rest, None),
+        };
+
+        if let Some(query) = query {
+            match query.strip_prefix("transport=") {
+                Some("udp") if transport != StunTransport::Tls => {}
+                Some("tcp") if transport != StunTransport::Tls => transport = StunTransport::Tcp,
+                Some("tcp") => {}
+                _ => return Err(invalid("unsupported transport")),
+            }
+        }
+
+        let (host, port) = split_host_port(authority).ok_or_else(|| invalid("bad host"))?;
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid("bad port"))?,
+            None if transport == StunTransport::Tls => DEFAULT_TLS_PORT,
+            None => DEFAULT_PORT,
+        };
+
+        Ok(Self {
+            transport,
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+/// Splits `host[:port]`, handling bracketed IPv6 literals (`[::1]:3478`).
+fn split_host_port(authority: &str) -> Option<(&str, Option<&str>)> {
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        return match after {
+            "" => Some((host, None)),
+            _ => Some((host, Some(after.strip_prefix(':')?))),
+        };
+    }
+
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+    if host.is_empty() || host.contains(':') {
+        return None;
+    }
+    Some((host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(transport: StunTransport, host: &str, port: u16) -> StunServerUrl {
+        StunServerUrl {
+            transport,
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn test_parse_bare_host_port() {
+        assert_eq!(
+            StunServerUrl::parse("stun.l.google.com:19302").unwrap(),
+            url(StunTransport::Udp, "stun.l.google.com", 19302)
+        );
+    }
+
+    #[test]
+    fn test_parse_schemes_and_default_ports() {
+        assert_eq!(
+            StunServerUrl::parse("stun:example.com").unwrap(),
+            url(StunTransport::Udp, "example.com", DEFAULT_PORT)
+        );
+        assert_eq!(
+            StunServerUrl::parse("stun:example.com:3479?transport=tcp").unwrap(),
+            url(StunTransport::Tcp, "example.com", 3479)
+        );
+        assert_eq!(
+            StunServerUrl::parse("stuns:example.com").unwrap(),
+            url(StunTransport::Tls, "example.com", DEFAULT_TLS_PORT)
+        );
+        assert_eq!(
+            StunServerUrl::parse("stun:[2001:db8::1]:3478").unwrap(),
+            url(StunTransport::Udp, "2001:db8::1", 3478)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_urls() {
+        assert!(StunServerUrl::parse("stun:").is_err());
+        assert!(StunServerUrl::parse("stun:example.com:port").is_err());
+        assert!(StunServerUrl::parse("stun:example.com?transport=sctp").is_err());
+        assert!(StunServerUrl::parse("stuns:example.com?transport=udp").is_err());
+        assert!(StunServerUrl::parse("stun:[::1").is_err());
+    }
+}
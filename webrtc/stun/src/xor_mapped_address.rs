@@ -1,4 +1,4 @@
-//! XOR-MAPPED-ADDRESS attribute decoding
+//! XOR-MAPPED-ADDRESS attribute encoding and decoding
 //!
 //! This module implements the XOR-MAPPED-ADDRESS attribute according to RFC 5389.
 //! XOR-MAPPED-ADDRESS is the preferred method for conveying reflexive addresses
@@ -13,6 +13,31 @@ use crate::message_header::MAGIC_COOKIE;
 const FAMILY_IPV4: u8 = 0x01;
 const FAMILY_IPV6: u8 = 0x02;
 
+/// Encodes an XOR-MAPPED-ADDRESS attribute value.
+///
+/// # Arguments
+/// * `addr` - The reflexive address to encode
+/// * `transaction_id` - The transaction ID of the message carrying it
+///
+/// # Returns
+/// The attribute value bytes (without the type/length header)
+pub fn encode(addr: SocketAddr, transaction_id: &[u8; 12]) -> Vec<u8> {
+    let mut xor_mask = Vec::with_capacity(16);
+    xor_mask.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+    xor_mask.extend_from_slice(transaction_id);
+
+    let xor_port = addr.port() ^ (MAGIC_COOKIE >> 16) as u16;
+    let (family, octets) = match addr.ip() {
+        IpAddr::V4(ip) => (FAMILY_IPV4, ip.octets().to_vec()),
+        IpAddr::V6(ip) => (FAMILY_IPV6, ip.octets().to_vec()),
+    };
+
+    let mut bytes = vec![0, family];
+    bytes.extend_from_slice(&xor_port.to_be_bytes());
+    bytes.extend(octets.iter().zip(&xor_mask).map(|(byte, mask)| byte ^ mask));
+    bytes
+}
+
 /// Decodes an XOR-MAPPED-ADDRESS attribute value.
 ///
 /// # Arguments
@@ -127,4 +152,14 @@ mod tests {
         let bytes = vec![0x00, 0x01];
         assert_eq!(None, decode(&bytes, &transaction_id));
     }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let transaction_id = [9u8; 12];
+        for addr in ["203.0.113.5:54321", "[2001:db8::7]:3478"] {
+            let addr: SocketAddr = addr.parse().unwrap();
+            let encoded = encode(addr, &transaction_id);
+            assert_eq!(decode(&encoded, &transaction_id), Some(addr));
+        }
+    }
 }
@@ -1,6 +1,8 @@
 //! TURN client implementation.
 //!
 //! Provides a TURN client for allocating relay addresses and managing permissions.
+//! Allocations are UDP relays over a UDP control connection, or TCP relays
+//! (RFC 6062) over a TCP control connection.
 
 use crate::errors::{Result, TurnError};
 use crate::message::{
@@ -8,7 +10,8 @@ use crate::message::{
 };
 use crate::turn_attribute_type::{TransportProtocol, TurnAttributeType};
 use crate::turn_message_type::TurnMessageType;
-use std::net::{SocketAddr, UdpSocket};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
 use std::time::{Duration, Instant};
 
 use logging::Logger;
@@ -23,13 +26,24 @@ const MAGIC_COOKIE_HIGH: u16 = 0x2112;
 const ATTR_USERNAME: u16 = 0x0006;
 /// Maximum UDP packet size
 const MAX_UDP_PACKET_SIZE: usize = 1500;
+/// STUN/TURN message header size (type, length, cookie, transaction ID)
+const STUN_HEADER_SIZE: usize = 20;
+/// Default wait for a server response
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connection the client exchanges TURN messages with the server on.
+enum ControlConnection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
 
 /// TURN client for allocating relay addresses and managing permissions.
 ///
 /// The client supports Allocate, Refresh, CreatePermission, ChannelBind,
-/// and Send operations.
+/// and Send operations, plus Connect and ConnectionBind for TCP
+/// allocations.
 pub struct TurnClient {
-    socket: UdpSocket,
+    connection: ControlConnection,
     server_addr: SocketAddr,
     username: String,
     relay_addr: Option<SocketAddr>,
@@ -53,18 +67,71 @@ impl TurnClient {
         let socket = UdpSocket::bind("0.0.0.0:0").map_err(TurnError::Io)?;
 
         socket
-            .set_read_timeout(Some(Duration::from_secs(5)))
+            .set_read_timeout(Some(DEFAULT_TIMEOUT))
+            .map_err(TurnError::Io)?;
+
+        Ok(Self::with_connection(
+            ControlConnection::Udp(socket),
+            server_addr,
+            username,
+        ))
+    }
+
+    /// Creates a TURN client that requests TCP allocations (RFC 6062).
+    ///
+    /// Connects to the server over TCP; the relay then carries TCP
+    /// connections to peers, opened with [`connect`](Self::connect) and
+    /// [`connection_bind`](Self::connection_bind).
+    ///
+    /// # Arguments
+    /// * `server_addr` - TURN server address
+    /// * `username` - Authentication username
+    ///
+    /// # Returns
+    /// * `Ok(TurnClient)` - Client connected to the server
+    /// * `Err(TurnError)` - Failed to connect to the server
+    pub fn new_tcp(server_addr: SocketAddr, username: String) -> Result<Self> {
+        let stream =
+            TcpStream::connect_timeout(&server_addr, DEFAULT_TIMEOUT).map_err(TurnError::Io)?;
+
+        stream
+            .set_read_timeout(Some(DEFAULT_TIMEOUT))
             .map_err(TurnError::Io)?;
 
-        Ok(TurnClient {
-            socket,
+        Ok(Self::with_connection(
+            ControlConnection::Tcp(stream),
+            server_addr,
+            username,
+        ))
+    }
+
+    /// Creates a client without an allocation on the given control connection
+    fn with_connection(
+        connection: ControlConnection,
+        server_addr: SocketAddr,
+        username: String,
+    ) -> Self {
+        TurnClient {
+            connection,
             server_addr,
             username,
             relay_addr: None,
             lifetime: DEFAULT_LIFETIME,
             last_refresh: None,
             logger: None,
-        })
+        }
+    }
+
+    /// Sets how long requests wait for the server's response.
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum wait per request (must be non-zero)
+    pub fn set_timeout(&self, timeout: Duration) -> Result<()> {
+        match &self.connection {
+            ControlConnection::Udp(socket) => socket.set_read_timeout(Some(timeout)),
+            ControlConnection::Tcp(stream) => stream.set_read_timeout(Some(timeout)),
+        }
+        .map_err(TurnError::Io)
     }
 
     /// Attaches a logger to the TURN client.
@@ -76,6 +143,8 @@ impl TurnClient {
     /// Allocates a relay address on the TURN server.
     ///
     /// Sends an Allocate request with REQUESTED-TRANSPORT and LIFETIME attributes.
+    /// The requested transport is TCP for clients created with
+    /// [`new_tcp`](Self::new_tcp), UDP otherwise.
     ///
     /// # Returns
     /// * `Ok(SocketAddr)` - The allocated relay address
@@ -204,9 +273,7 @@ impl TurnClient {
         self.ensure_allocation()?;
 
         let msg = self.build_send_indication(data, peer_addr);
-        self.socket
-            .send_to(&msg, self.server_addr)
-            .map_err(TurnError::Io)?;
+        self.send_message(&msg)?;
 
         self.log_info(&format!(
             "Sent {} bytes to {} via relay",
@@ -216,6 +283,91 @@ impl TurnClient {
         Ok(())
     }
 
+    /// Asks the server to open a TCP connection to a peer (RFC 6062 Section 4.3).
+    ///
+    /// A permission for the peer is installed by the server as part of the
+    /// request. The returned id is passed to
+    /// [`connection_bind`](Self::connection_bind) to use the connection.
+    ///
+    /// # Arguments
+    /// * `peer_addr` - The peer's address
+    ///
+    /// # Returns
+    /// * `Ok(u32)` - CONNECTION-ID of the relayed connection
+    /// * `Err(TurnError::UnsupportedTransport)` - If the allocation is not TCP
+    /// * `Err(TurnError)` - If the server could not reach the peer
+    pub fn connect(&mut self, peer_addr: SocketAddr) -> Result<u32> {
+        self.ensure_tcp_allocation()?;
+        self.log_info(&format!("Connecting to TCP peer {} via relay", peer_addr));
+
+        let mut msg = self.build_request(TurnMessageType::ConnectRequest);
+        self.add_xor_peer_addr(&mut msg, peer_addr);
+        let response = self.send_and_receive(&msg)?;
+
+        self.verify_success_response(
+            &response,
+            TurnMessageType::ConnectError,
+            "TURN Connect failed",
+        )?;
+
+        let connection_id = self.extract_connection_id(&response)?;
+        self.log_info(&format!(
+            "TCP peer {} connected (connection id {})",
+            peer_addr, connection_id
+        ));
+        Ok(connection_id)
+    }
+
+    /// Opens a data connection to the server and binds it to a relayed TCP
+    /// connection (RFC 6062 Section 4.4).
+    ///
+    /// Once bound, the stream carries the peer's bytes unframed in both
+    /// directions.
+    ///
+    /// # Arguments
+    /// * `connection_id` - Id returned by [`connect`](Self::connect), or
+    ///   received in a ConnectionAttempt indication
+    ///
+    /// # Returns
+    /// * `Ok(TcpStream)` - Data connection relaying to the peer
+    /// * `Err(TurnError::UnsupportedTransport)` - If the allocation is not TCP
+    /// * `Err(TurnError)` - If the server rejected the binding
+    pub fn connection_bind(&mut self, connection_id: u32) -> Result<TcpStream> {
+        self.ensure_tcp_allocation()?;
+        self.log_info(&format!(
+            "Binding data connection to connection id {}",
+            connection_id
+        ));
+
+        let timeout = self.read_timeout().unwrap_or(DEFAULT_TIMEOUT);
+        let mut stream =
+            TcpStream::connect_timeout(&self.server_addr, timeout).map_err(TurnError::Io)?;
+        stream
+            .set_read_timeout(Some(timeout))
+            .map_err(TurnError::Io)?;
+
+        let mut msg = self.build_request(TurnMessageType::ConnectionBindRequest);
+        add_turn_attribute(
+            &mut msg,
+            TurnAttributeType::ConnectionId.to_u16(),
+            &connection_id.to_be_bytes(),
+        );
+        stream.write_all(&msg).map_err(TurnError::Io)?;
+        let response = read_stream_message(&mut stream)?;
+
+        self.verify_success_response(
+            &response,
+            TurnMessageType::ConnectionBindError,
+            "TURN ConnectionBind failed",
+        )?;
+        if parse_turn_message_type(&response) != Some(TurnMessageType::ConnectionBindResponse) {
+            return Err(TurnError::InvalidResponse);
+        }
+
+        self.log_info("TCP data connection bound");
+        Ok(stream)
+    }
+
     /// Returns the allocated relay address, if any.
     pub fn relay_address(&self) -> Option<SocketAddr> {
         self.relay_addr
@@ -260,8 +412,8 @@ impl TurnClient {
     fn build_allocate_request(&self) -> Vec<u8> {
         let mut msg = self.build_request(TurnMessageType::AllocateRequest);
 
-        // REQUESTED-TRANSPORT (UDP)
-        let transport = TransportProtocol::Udp.to_u8();
+        // REQUESTED-TRANSPORT (UDP, or TCP over a TCP control connection)
+        let transport = self.requested_transport().to_u8();
         add_turn_attribute(
             &mut msg,
             TurnAttributeType::RequestedTransport.to_u16(),
@@ -322,6 +474,33 @@ impl TurnClient {
         Ok(())
     }
 
+    /// Ensures a TCP allocation exists, returns error if not
+    fn ensure_tcp_allocation(&self) -> Result<()> {
+        self.ensure_allocation()?;
+        if self.requested_transport() != TransportProtocol::Tcp {
+            return Err(TurnError::UnsupportedTransport);
+        }
+        Ok(())
+    }
+
+    /// Transport of the relay requested in Allocate
+    fn requested_transport(&self) -> TransportProtocol {
+        match self.connection {
+            ControlConnection::Udp(_) => TransportProtocol::Udp,
+            ControlConnection::Tcp(_) => TransportProtocol::Tcp,
+        }
+    }
+
+    /// Read timeout of the control connection
+    fn read_timeout(&self) -> Option<Duration> {
+        match &self.connection {
+            ControlConnection::Udp(socket) => socket.read_timeout(),
+            ControlConnection::Tcp(stream) => stream.read_timeout(),
+        }
+        .ok()
+        .flatten()
+    }
+
     /// Validates channel number is in valid range
     fn validate_channel_number(&self, channel: u16) -> Result<()> {
         if !(0x4000..=0x7FFF).contains(&channel) {
@@ -337,16 +516,35 @@ impl TurnClient {
         add_turn_attribute(msg, ATTR_USERNAME, self.username.as_bytes());
     }
 
+    /// Sends a message to the server on the control connection
+    fn send_message(&self, msg: &[u8]) -> Result<()> {
+        match &self.connection {
+            ControlConnection::Udp(socket) => socket.send_to(msg, self.server_addr).map(|_| ()),
+            ControlConnection::Tcp(stream) => (&*stream).write_all(msg),
+        }
+        .map_err(TurnError::Io)
+    }
+
     /// Sends a message and receives the response
     fn send_and_receive(&self, msg: &[u8]) -> Result<Vec<u8>> {
-        self.socket
-            .send_to(msg, self.server_addr)
-            .map_err(TurnError::Io)?;
-
-        let mut buffer = [0u8; MAX_UDP_PACKET_SIZE];
-        let (len, _) = self.socket.recv_from(&mut buffer).map_err(TurnError::Io)?;
+        self.send_message(msg)?;
 
-        Ok(buffer[..len].to_vec())
+        match &self.connection {
+            ControlConnection::Udp(socket) => {
+                let mut buffer = [0u8; MAX_UDP_PACKET_SIZE];
+                let (len, _) = socket.recv_from(&mut buffer).map_err(TurnError::Io)?;
+                Ok(buffer[..len].to_vec())
+            }
+            ControlConnection::Tcp(stream) => {
+                // ConnectionAttempt indications can arrive before the response
+                loop {
+                    let message = read_stream_message(&mut &*stream)?;
+                    if !parse_turn_message_type(&message).is_some_and(|t| t.is_indication()) {
+                        return Ok(message);
+                    }
+                }
+            }
+        }
     }
 
     /// Verifies the response is a success, not an error
@@ -373,6 +571,10 @@ impl TurnClient {
                 TurnMessageType::ChannelBindError => {
                     TurnError::ChannelBindFailed(error_msg.to_string())
                 }
+                TurnMessageType::ConnectError => TurnError::ConnectFailed(error_msg.to_string()),
+                TurnMessageType::ConnectionBindError => {
+                    TurnError::ConnectionBindFailed(error_msg.to_string())
+                }
                 _ => TurnError::InvalidResponse,
             });
         }
@@ -386,6 +588,15 @@ impl TurnClient {
             .and_then(|value| self.decode_xor_address(value))
     }
 
+    /// Extracts CONNECTION-ID from a Connect response
+    fn extract_connection_id(&self, bytes: &[u8]) -> Result<u32> {
+        let value = self.find_attribute(bytes, TurnAttributeType::ConnectionId.to_u16())?;
+        let id: [u8; 4] = value
+            .try_into()
+            .map_err(|_| TurnError::AttributeError("CONNECTION-ID must be 4 bytes".to_string()))?;
+        Ok(u32::from_be_bytes(id))
+    }
+
     /// Finds an attribute in a TURN message
     fn find_attribute<'a>(&self, bytes: &'a [u8], attr_type: u16) -> Result<&'a [u8]> {
         const HEADER_SIZE: usize = 20;
@@ -492,9 +703,34 @@ impl TurnClient {
     }
 }
 
+/// Reads one TURN message from a stream, using the header length to find its end
+fn read_stream_message(stream: &mut impl Read) -> Result<Vec<u8>> {
+    let mut message = vec![0u8; STUN_HEADER_SIZE];
+    stream.read_exact(&mut message).map_err(TurnError::Io)?;
+
+    let length = u16::from_be_bytes([message[2], message[3]]) as usize;
+    message.resize(STUN_HEADER_SIZE + length, 0);
+    stream
+        .read_exact(&mut message[STUN_HEADER_SIZE..])
+        .map_err(TurnError::Io)?;
+    Ok(message)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::extract_transaction_id;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Builds a response to `request` carrying the given attributes
+    fn response(request: &[u8], message_type: TurnMessageType, attrs: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut msg = build_turn_message(message_type, extract_transaction_id(request).unwrap());
+        for (attr_type, value) in attrs {
+            add_turn_attribute(&mut msg, *attr_type, value);
+        }
+        msg
+    }
 
     #[test]
     fn test_xor_address_encoding() {
@@ -525,4 +761,106 @@ mod tests {
         // Fresh allocation shouldn't need refresh
         assert!(!client.needs_refresh());
     }
+
+    #[test]
+    fn test_connect_requires_tcp_allocation() {
+        let mut client =
+            TurnClient::new("127.0.0.1:3478".parse().unwrap(), "user".to_string()).unwrap();
+        let peer: SocketAddr = "192.0.2.1:1234".parse().unwrap();
+
+        assert!(matches!(client.connect(peer), Err(TurnError::NoAllocation)));
+
+        client.relay_addr = Some("198.51.100.1:5000".parse().unwrap());
+        assert!(matches!(
+            client.connect(peer),
+            Err(TurnError::UnsupportedTransport)
+        ));
+    }
+
+    #[test]
+    fn test_tcp_allocation_relays_to_peer() {
+        const CONNECTION_ID: u32 = 0x1234;
+        let relay_addr: SocketAddr = "198.51.100.1:5000".parse().unwrap();
+
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_addr = server.local_addr().unwrap();
+        let peer = TcpListener::bind("127.0.0.1:0").unwrap();
+        let peer_addr = peer.local_addr().unwrap();
+
+        // Mock TURN server: one TCP allocation relaying one connection
+        let mock = thread::spawn(move || {
+            let codec = TurnClient::new(server_addr, String::new()).unwrap();
+            let (mut control, _) = server.accept().unwrap();
+
+            let allocate = read_stream_message(&mut control).unwrap();
+            assert_eq!(
+                parse_turn_message_type(&allocate),
+                Some(TurnMessageType::AllocateRequest)
+            );
+            let transport = codec
+                .find_attribute(&allocate, TurnAttributeType::RequestedTransport.to_u16())
+                .unwrap();
+            assert_eq!(transport[0], TransportProtocol::Tcp.to_u8());
+            let relayed = codec.encode_xor_address(relay_addr);
+            let reply = response(
+                &allocate,
+                TurnMessageType::AllocateResponse,
+                &[(TurnAttributeType::XorRelayedAddress.to_u16(), &relayed)],
+            );
+            control.write_all(&reply).unwrap();
+
+            let connect = read_stream_message(&mut control).unwrap();
+            let target = codec
+                .find_attribute(&connect, TurnAttributeType::XorPeerAddress.to_u16())
+                .and_then(|value| codec.decode_xor_address(value))
+                .unwrap();
+            let mut upstream = TcpStream::connect(target).unwrap();
+            let reply = response(
+                &connect,
+                TurnMessageType::ConnectResponse,
+                &[(
+                    TurnAttributeType::ConnectionId.to_u16(),
+                    &CONNECTION_ID.to_be_bytes(),
+                )],
+            );
+            control.write_all(&reply).unwrap();
+
+            let (mut data, _) = server.accept().unwrap();
+            let bind = read_stream_message(&mut data).unwrap();
+            assert_eq!(
+                parse_turn_message_type(&bind),
+                Some(TurnMessageType::ConnectionBindRequest)
+            );
+            assert_eq!(codec.extract_connection_id(&bind).unwrap(), CONNECTION_ID);
+            data.write_all(&response(
+                &bind,
+                TurnMessageType::ConnectionBindResponse,
+                &[],
+            ))
+            .unwrap();
+
+            // Relay one byte each way
+            let mut byte = [0u8; 1];
+            data.read_exact(&mut byte).unwrap();
+            upstream.write_all(&byte).unwrap();
+            upstream.read_exact(&mut byte).unwrap();
+            data.write_all(&byte).unwrap();
+        });
+
+        let mut client = TurnClient::new_tcp(server_addr, "user".to_string()).unwrap();
+        assert_eq!(client.allocate().unwrap(), relay_addr);
+        assert_eq!(client.connect(peer_addr).unwrap(), CONNECTION_ID);
+        let (mut peer_stream, _) = peer.accept().unwrap();
+        let mut data = client.connection_bind(CONNECTION_ID).unwrap();
+
+        let mut byte = [0u8; 1];
+        data.write_all(&[0x42]).unwrap();
+        peer_stream.read_exact(&mut byte).unwrap();
+        assert_eq!(byte, [0x42]);
+        peer_stream.write_all(&[0x24]).unwrap();
+        data.read_exact(&mut byte).unwrap();
+        assert_eq!(byte, [0x24]);
+
+        mock.join().unwrap();
+    }
 }
@@ -21,6 +21,10 @@ pub enum TurnError {
     ChannelBindFailed(String),
     /// Refresh failed
     RefreshFailed(String),
+    /// Connect request for a TCP peer failed (RFC 6062)
+    ConnectFailed(String),
+    /// Binding a data connection to a TCP peer failed (RFC 6062)
+    ConnectionBindFailed(String),
     /// Invalid response from server
     InvalidResponse,
     /// Authentication failed
@@ -43,6 +47,8 @@ pub enum TurnError {
     InvalidMessage(String),
     /// Attribute parsing error
     AttributeError(String),
+    /// Malformed TURN server URL
+    InvalidUrl(String),
 }
 
 impl fmt::Display for TurnError {
@@ -54,6 +60,8 @@ impl fmt::Display for TurnError {
             TurnError::PermissionFailed(msg) => write!(f, "Permission creation failed: {}", msg),
             TurnError::ChannelBindFailed(msg) => write!(f, "Channel bind failed: {}", msg),
             TurnError::RefreshFailed(msg) => write!(f, "Refresh failed: {}", msg),
+            TurnError::ConnectFailed(msg) => write!(f, "Connect failed: {}", msg),
+            TurnError::ConnectionBindFailed(msg) => write!(f, "Connection bind failed: {}", msg),
             TurnError::InvalidResponse => write!(f, "Invalid response from TURN server"),
             TurnError::AuthenticationFailed => write!(f, "Authentication failed"),
             TurnError::NoAllocation => write!(f, "No allocation exists"),
@@ -73,6 +81,7 @@ impl fmt::Display for TurnError {
             TurnError::Timeout => write!(f, "Timeout waiting for response"),
             TurnError::InvalidMessage(msg) => write!(f, "Invalid message: {}", msg),
             TurnError::AttributeError(msg) => write!(f, "Attribute error: {}", msg),
+            TurnError::InvalidUrl(msg) => write!(f, "Invalid TURN URL: {}", msg),
         }
     }
 }
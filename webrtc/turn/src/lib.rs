@@ -1,15 +1,18 @@
 //! TURN Module - Traversal Using Relays around NAT
 //!
-//! Implementation of TURN (Traversal Using Relays around NAT) according to RFC 5766.
+//! Implementation of TURN (Traversal Using Relays around NAT) according to RFC 5766,
+//! with TCP allocations according to RFC 6062.
 
 pub mod client;
 pub mod errors;
 pub mod message;
 pub mod turn_attribute_type;
 pub mod turn_message_type;
+pub mod url;
 
 pub use client::TurnClient;
 pub use errors::TurnError;
 pub use message::TurnMessage;
 pub use turn_attribute_type::{TransportProtocol, TurnAttributeType};
 pub use turn_message_type::TurnMessageType;
+pub use url::TurnUrl;
@@ -2,7 +2,7 @@
 //!
 //! TURN extends STUN with additional attributes for relay functionality.
 
-/// TURN attribute types according to RFC 5766.
+/// TURN attribute types according to RFC 5766 and RFC 6062.
 ///
 /// These attributes are used in TURN messages to convey
 /// relay-specific information.
@@ -39,6 +39,10 @@ pub enum TurnAttributeType {
     /// RESERVATION-TOKEN: 0x0022
     /// Token for reserving an allocation
     ReservationToken = 0x0022,
+
+    /// CONNECTION-ID: 0x002A (RFC 6062)
+    /// Identifies a relayed TCP connection between Connect and ConnectionBind
+    ConnectionId = 0x002A,
 }
 
 impl TurnAttributeType {
@@ -68,6 +72,7 @@ impl TurnAttributeType {
             0x0019 => Some(TurnAttributeType::RequestedTransport),
             0x001A => Some(TurnAttributeType::DontFragment),
             0x0022 => Some(TurnAttributeType::ReservationToken),
+            0x002A => Some(TurnAttributeType::ConnectionId),
             _ => None,
         }
     }
@@ -83,6 +88,7 @@ impl TurnAttributeType {
             TurnAttributeType::RequestedTransport => "REQUESTED-TRANSPORT",
             TurnAttributeType::DontFragment => "DONT-FRAGMENT",
             TurnAttributeType::ReservationToken => "RESERVATION-TOKEN",
+            TurnAttributeType::ConnectionId => "CONNECTION-ID",
         }
     }
 }
@@ -159,6 +165,10 @@ mod tests {
             TurnAttributeType::from_u16(0x0013),
             Some(TurnAttributeType::Data)
         );
+        assert_eq!(
+            TurnAttributeType::from_u16(0x002A),
+            Some(TurnAttributeType::ConnectionId)
+        );
         assert_eq!(TurnAttributeType::from_u16(0xFFFF), None);
     }
 
@@ -2,10 +2,11 @@
 //!
 //! TURN extends STUN with additional message types for relay functionality.
 
-/// TURN message types according to RFC 5766.
+/// TURN message types according to RFC 5766 and RFC 6062.
 ///
 /// TURN uses STUN's message format but adds new message types
-/// for allocation, permission, and channel management.
+/// for allocation, permission, and channel management, plus the
+/// TCP relay methods of RFC 6062.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TurnMessageType {
     // Allocate (0x0003)
@@ -28,9 +29,22 @@ pub enum TurnMessageType {
     ChannelBindResponse,
     ChannelBindError,
 
+    // Connect (0x000A, RFC 6062)
+    ConnectRequest,
+    ConnectResponse,
+    ConnectError,
+
+    // ConnectionBind (0x000B, RFC 6062)
+    ConnectionBindRequest,
+    ConnectionBindResponse,
+    ConnectionBindError,
+
     // Send/Data Indications
     SendIndication,
     DataIndication,
+
+    // ConnectionAttempt Indication (RFC 6062)
+    ConnectionAttemptIndication,
 }
 
 impl TurnMessageType {
@@ -60,9 +74,20 @@ impl TurnMessageType {
             TurnMessageType::ChannelBindResponse => 0x0109,
             TurnMessageType::ChannelBindError => 0x0119,
 
+            // Connect: 0x000A
+            TurnMessageType::ConnectRequest => 0x000A,
+            TurnMessageType::ConnectResponse => 0x010A,
+            TurnMessageType::ConnectError => 0x011A,
+
+            // ConnectionBind: 0x000B
+            TurnMessageType::ConnectionBindRequest => 0x000B,
+            TurnMessageType::ConnectionBindResponse => 0x010B,
+            TurnMessageType::ConnectionBindError => 0x011B,
+
             // Indications
             TurnMessageType::SendIndication => 0x0016,
             TurnMessageType::DataIndication => 0x0017,
+            TurnMessageType::ConnectionAttemptIndication => 0x001C,
         }
     }
 
@@ -92,8 +117,17 @@ impl TurnMessageType {
             0x0109 => Some(TurnMessageType::ChannelBindResponse),
             0x0119 => Some(TurnMessageType::ChannelBindError),
 
+            0x000A => Some(TurnMessageType::ConnectRequest),
+            0x010A => Some(TurnMessageType::ConnectResponse),
+            0x011A => Some(TurnMessageType::ConnectError),
+
+            0x000B => Some(TurnMessageType::ConnectionBindRequest),
+            0x010B => Some(TurnMessageType::ConnectionBindResponse),
+            0x011B => Some(TurnMessageType::ConnectionBindError),
+
             0x0016 => Some(TurnMessageType::SendIndication),
             0x0017 => Some(TurnMessageType::DataIndication),
+            0x001C => Some(TurnMessageType::ConnectionAttemptIndication),
 
             _ => None,
         }
@@ -114,8 +148,15 @@ impl TurnMessageType {
             TurnMessageType::ChannelBindRequest => "ChannelBind Request",
             TurnMessageType::ChannelBindResponse => "ChannelBind Success Response",
             TurnMessageType::ChannelBindError => "ChannelBind Error Response",
+            TurnMessageType::ConnectRequest => "Connect Request",
+            TurnMessageType::ConnectResponse => "Connect Success Response",
+            TurnMessageType::ConnectError => "Connect Error Response",
+            TurnMessageType::ConnectionBindRequest => "ConnectionBind Request",
+            TurnMessageType::ConnectionBindResponse => "ConnectionBind Success Response",
+            TurnMessageType::ConnectionBindError => "ConnectionBind Error Response",
             TurnMessageType::SendIndication => "Send Indication",
             TurnMessageType::DataIndication => "Data Indication",
+            TurnMessageType::ConnectionAttemptIndication => "ConnectionAttempt Indication",
         }
     }
 
@@ -127,6 +168,8 @@ impl TurnMessageType {
                 | TurnMessageType::RefreshRequest
                 | TurnMessageType::CreatePermissionRequest
                 | TurnMessageType::ChannelBindRequest
+                | TurnMessageType::ConnectRequest
+                | TurnMessageType::ConnectionBindRequest
         )
     }
 
@@ -138,6 +181,8 @@ impl TurnMessageType {
                 | TurnMessageType::RefreshResponse
                 | TurnMessageType::CreatePermissionResponse
                 | TurnMessageType::ChannelBindResponse
+                | TurnMessageType::ConnectResponse
+                | TurnMessageType::ConnectionBindResponse
         )
     }
 
@@ -149,6 +194,8 @@ impl TurnMessageType {
                 | TurnMessageType::RefreshError
                 | TurnMessageType::CreatePermissionError
                 | TurnMessageType::ChannelBindError
+                | TurnMessageType::ConnectError
+                | TurnMessageType::ConnectionBindError
         )
     }
 
@@ -156,7 +203,9 @@ impl TurnMessageType {
     pub fn is_indication(&self) -> bool {
         matches!(
             self,
-            TurnMessageType::SendIndication | TurnMessageType::DataIndication
+            TurnMessageType::SendIndication
+                | TurnMessageType::DataIndication
+                | TurnMessageType::ConnectionAttemptIndication
         )
     }
 }
@@ -192,6 +241,28 @@ mod tests {
         assert_eq!(TurnMessageType::from_u16(0xFFFF), None);
     }
 
+    #[test]
+    fn test_tcp_relay_message_types() {
+        for message_type in [
+            TurnMessageType::ConnectRequest,
+            TurnMessageType::ConnectResponse,
+            TurnMessageType::ConnectError,
+            TurnMessageType::ConnectionBindRequest,
+            TurnMessageType::ConnectionBindResponse,
+            TurnMessageType::ConnectionBindError,
+            TurnMessageType::ConnectionAttemptIndication,
+        ] {
+            assert_eq!(
+                TurnMessageType::from_u16(message_type.to_u16()),
+                Some(message_type)
+            );
+        }
+        assert_eq!(TurnMessageType::ConnectRequest.to_u16(), 0x000A);
+        assert_eq!(TurnMessageType::ConnectionBindResponse.to_u16(), 0x010B);
+        assert!(TurnMessageType::ConnectionBindError.is_error_response());
+        assert!(TurnMessageType::ConnectionAttemptIndication.is_indication());
+    }
+
     #[test]
     fn test_message_type_checks() {
         assert!(TurnMessageType::AllocateRequest.is_request());
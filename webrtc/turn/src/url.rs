@@ -0,0 +1,258 @@
+//! TURN server URLs.
+//!
+//! Parses TURN URIs (RFC 7065) in the form used by WebRTC configurations:
+//! `turn:host:port?transport=udp&username=user&password=pass`.
+
+use crate::errors::{Result, TurnError};
+use crate::turn_attribute_type::TransportProtocol;
+use std::net::{SocketAddr, ToSocketAddrs};
+
+/// Default port of `turn:` servers
+pub const DEFAULT_TURN_PORT: u16 = 3478;
+/// Default port of `turns:` (TLS) servers
+pub const DEFAULT_TURNS_PORT: u16 = 5349;
+
+/// A parsed TURN server URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurnUrl {
+    /// `turns:` scheme: the connection to the server uses TLS
+    pub secure: bool,
+    /// Server host name or IP address (IPv6 without brackets)
+    pub host: String,
+    /// Server port (3478 or 5349 when the URL has none)
+    pub port: u16,
+    /// Transport to the server (UDP for `turn:`, TCP for `turns:` by default)
+    pub transport: TransportProtocol,
+    /// Percent-decoded username, if present
+    pub username: Option<String>,
+    /// Percent-decoded password (`password` or `credential`), if present
+    pub password: Option<String>,
+}
+
+impl TurnUrl {
+    /// Parses a TURN URL.
+    ///
+    /// # Arguments
+    /// * `url` - URL such as `turn:host:3478?transport=udp&username=u&password=p`
+    ///
+    /// # Returns
+    /// * `Ok(TurnUrl)` - Parsed URL with defaults applied
+    /// * `Err(TurnError::InvalidUrl)` - If the URL is malformed
+    pub fn parse(url: &str) -> Result<Self> {
+        let invalid = |reason: &str| TurnError::InvalidUrl(format!("{}: {}", reason, url));
+
+        let (secure, rest) = if let Some(rest) = url.strip_prefix("turns:") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("turn:") {
+            (false, rest)
+        } else {
+            return Err(invalid("scheme must be turn: or turns:"));
+        };
+
+        let (authority, query) = match rest.split_once('?') {
+            Some((authority, query)) => (authority, Some(query)),
+            None => (rest, None),
+        };
+        let default_port = if secure {
+            DEFAULT_TURNS_PORT
+        } else {
+            DEFAULT_TURN_PORT
+        };
+        let (host, port) = Self::parse_authority(authority, default_port).map_err(invalid)?;
+
+        let mut parsed = Self {
+            secure,
+            host,
+            port,
+            transport: if secure {
+                TransportProtocol::Tcp
+            } else {
+                TransportProtocol::Udp
+            },
+            username: None,
+            password: None,
+        };
+
+        for param in query.into_iter().flat_map(|q| q.split('&')) {
+            if param.is_empty() {
+                continue;
+            }
+            let (key, value) = param
+                .split_once('=')
+                .ok_or_else(|| invalid("query parameter without value"))?;
+            let value = percent_decode(value).ok_or_else(|| invalid("bad percent encoding"))?;
+
+            match key {
+                "transport" => {
+                    parsed.transport = match value.to_ascii_lowercase().as_str() {
+                        "udp" => TransportProtocol::Udp,
+                        "tcp" => TransportProtocol::Tcp,
+                        _ => return Err(invalid("transport must be udp or tcp")),
+                    }
+                }
+                "username" => parsed.username = Some(value),
+                "password" | "credential" => parsed.password = Some(value),
+                // Unknown parameters are ignored for forward compatibility
+                _ => {}
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Splits `host[:port]` (or `[ipv6][:port]`) applying the default port
+    fn parse_authority(
+        authority: &str,
+        default_port: u16,
+    ) -> std::result::Result<(String, u16), &'static str> {
+        let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+            let (host, after) = rest.split_once(']').ok_or("unclosed IPv6 bracket")?;
+            match after {
+                "" => (host, None),
+                _ => (
+                    host,
+                    Some(after.strip_prefix(':').ok_or("junk after IPv6 host")?),
+                ),
+            }
+        } else {
+            match authority.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            }
+        };
+
+        if host.is_empty() {
+            return Err("missing host");
+        }
+        let port = match port {
+            Some(port) => port
+                .parse::<u16>()
+                .ok()
+                .filter(|&port| port != 0)
+                .ok_or("invalid port")?,
+            None => default_port,
+        };
+
+        Ok((host.to_string(), port))
+    }
+
+    /// Resolves the server host and port to a socket address.
+    ///
+    /// # Returns
+    /// * `Ok(SocketAddr)` - First address the host resolves to
+    /// * `Err(TurnError::Io)` - If the host cannot be resolved
+    pub fn resolve(&self) -> Result<SocketAddr> {
+        (self.host.as_str(), self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| TurnError::InvalidUrl(format!("{} resolves to no address", self.host)))
+    }
+}
+
+/// Decodes `%XX` escapes (and `+` as space) in a query value
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = value.get(i + 1..i + 3)?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_turn_url() {
+        let url =
+            TurnUrl::parse("turn:turn.example.com:3479?transport=udp&username=user&password=pass")
+                .unwrap();
+
+        assert!(!url.secure);
+        assert_eq!(url.host, "turn.example.com");
+        assert_eq!(url.port, 3479);
+        assert_eq!(url.transport, TransportProtocol::Udp);
+        assert_eq!(url.username.as_deref(), Some("user"));
+        assert_eq!(url.password.as_deref(), Some("pass"));
+    }
+
+    #[test]
+    fn test_parse_turns_url() {
+        let url =
+            TurnUrl::parse("turns:turn.example.com:443?username=user&credential=pass").unwrap();
+
+        assert!(url.secure);
+        assert_eq!(url.port, 443);
+        assert_eq!(url.transport, TransportProtocol::Tcp);
+        assert_eq!(url.password.as_deref(), Some("pass"));
+    }
+
+    #[test]
+    fn test_missing_port_uses_scheme_default() {
+        assert_eq!(TurnUrl::parse("turn:example.com").unwrap().port, 3478);
+        assert_eq!(TurnUrl::parse("turns:example.com").unwrap().port, 5349);
+
+        let url = TurnUrl::parse("turn:[2001:db8::1]?transport=udp").unwrap();
+        assert_eq!(url.host, "2001:db8::1");
+        assert_eq!(url.port, 3478);
+    }
+
+    #[test]
+    fn test_tcp_transport() {
+        let url = TurnUrl::parse("turn:10.0.0.1:3478?transport=TCP").unwrap();
+        assert_eq!(url.transport, TransportProtocol::Tcp);
+        assert!(!url.secure);
+    }
+
+    #[test]
+    fn test_credentials_are_percent_decoded() {
+        let url = TurnUrl::parse("turn:example.com?username=alice%40corp&password=p%26ss%3Dword")
+            .unwrap();
+
+        assert_eq!(url.username.as_deref(), Some("alice@corp"));
+        assert_eq!(url.password.as_deref(), Some("p&ss=word"));
+    }
+
+    #[test]
+    fn test_malformed_urls() {
+        for url in [
+            "stun:example.com:3478",
+            "turn:",
+            "turn::3478",
+            "turn:example.com:port",
+            "turn:example.com:70000",
+            "turn:[::1",
+            "turn:example.com?transport=sctp",
+            "turn:example.com?username",
+            "turn:example.com?password=bad%zz",
+        ] {
+            assert!(
+                matches!(TurnUrl::parse(url), Err(TurnError::InvalidUrl(_))),
+                "{} should be rejected",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_ip_host() {
+        let url = TurnUrl::parse("turn:127.0.0.1:3478").unwrap();
+        assert_eq!(url.resolve().unwrap(), "127.0.0.1:3478".parse().unwrap());
+    }
+}